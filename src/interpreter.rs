@@ -1,79 +1,2338 @@
 //! Interpreter for TypeScript code
 
-use std::collections::HashMap;
-use crate::parser::{Statement, Expression};
-use crate::lexer::Token;
+mod bigint;
+mod builtins;
+mod class;
+mod collection;
+mod date;
+mod encoding;
+mod environment;
+mod exception;
+mod gc;
+mod generator;
+mod inspect;
+mod internal;
+mod iteration;
+mod object;
+mod promise;
+mod stack;
+mod state;
+mod string;
+mod timer;
+mod typed_array;
+mod value;
 
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::ptr::NonNull;
+use std::rc::Rc;
+use num_bigint::BigInt;
+use crate::parser::{
+    ForBinding, FunctionKind, ObjectProperty, Pattern, PatternElement, PatternProperty, PropertyName, Statement, Expression, FunctionDefinition,
+    SwitchCase, TemplateLiteral,
+    VariableKind,
+};
+use crate::lexer::{Location, Token};
+use environment::{Binding, EnvRef, Environment, FunctionContext, ScopeKind};
+use exception::StackFrame;
+use iteration::Iteration;
+use object::{Attributes, Closure, Heap, NativeFunction, Object, ObjectKind, PrivateName, Property, PropertyKey, PropertyValue};
+use promise::EventLoop;
+use stack::StackPool;
+use state::State;
+
+pub use object::ObjectId;
+pub use string::JsString;
+pub use value::{loose_equals, number_to_string, same_value, same_value_zero, strict_equals, string_to_number, to_int32, to_uint32, Symbol, Value};
+use value::{less_than, pow};
+
+/// Nested calls allowed before a `RangeError` is raised.
+const MAX_CALL_DEPTH: usize = 5000;
+
+/// Each call, statement and expression runs with at least this much native
+/// stack left, growing the stack onto the heap when it runs low, so deep
+/// recursion is bounded by `MAX_CALL_DEPTH` rather than the thread's stack
+/// size, and deeply nested code by memory. The parser keeps the same red
+/// zone.
+pub(crate) const STACK_RED_ZONE: usize = 256 * 1024;
+pub(crate) const STACK_GROWTH: usize = 4 * 1024 * 1024;
+
+/// Runs scripts. The interpreter's state lives on the heap: the body of a
+/// suspended generator, async call or script waiting on `await` keeps
+/// frames on a stack of its own, which refer to an interpreter of their
+/// own that shares the state. The interpreter can therefore be moved
+/// freely, and nothing suspended ever holds a reference to it.
 pub struct Interpreter {
-    variables: HashMap<String, Value>,
-    last_value: Option<Value>,
+    state: NonNull<State>,
+    /// Whether this interpreter owns `state`, rather than running a
+    /// generator body on another's
+    owner: bool,
+}
+
+impl Interpreter {
+    /// An interpreter sharing the state of this one, for a generator body
+    /// to run on. It must not outlive this interpreter.
+    fn share(&self) -> Self {
+        Self { state: self.state, owner: false }
+    }
 }
 
+impl Drop for Interpreter {
+    fn drop(&mut self) {
+        if self.owner {
+            // SAFETY: the owner allocated `state` with `Box::new`, and the
+            // interpreters sharing it belong to generators it owns
+            drop(unsafe { Box::from_raw(self.state.as_ptr()) });
+        }
+    }
+}
+
+impl std::ops::Deref for Interpreter {
+    type Target = State;
+
+    fn deref(&self) -> &State {
+        // SAFETY: `state` outlives every interpreter sharing it, and each
+        // borrow of it is a borrow of the interpreter it is reached
+        // through. Only one interpreter runs at a time, as a generator
+        // body only runs inside the call that resumes it, and no borrow
+        // is held across resuming or suspending one.
+        unsafe { self.state.as_ref() }
+    }
+}
+
+impl std::ops::DerefMut for Interpreter {
+    fn deref_mut(&mut self) -> &mut State {
+        // SAFETY: as for `deref`
+        unsafe { self.state.as_mut() }
+    }
+}
+
+/// The built-in prototypes that newly created objects link to, and the
+/// built-in functions the interpreter itself refers to.
+struct Intrinsics {
+    object_prototype: ObjectId,
+    function_prototype: ObjectId,
+    array_prototype: ObjectId,
+    /// `Array.prototype.values`, the iterator of `arguments` objects
+    array_values: ObjectId,
+    boolean_prototype: ObjectId,
+    number_prototype: ObjectId,
+    string_prototype: ObjectId,
+    symbol_prototype: ObjectId,
+    bigint_prototype: ObjectId,
+    date_prototype: ObjectId,
+    array_buffer_prototype: ObjectId,
+    shared_array_buffer_prototype: ObjectId,
+    /// `%TypedArray%.prototype`, which the prototypes of the typed arrays
+    /// inherit from
+    typed_array_prototype: ObjectId,
+    /// The prototypes of the typed arrays, in the order of `ElementType::ALL`
+    typed_array_prototypes: [ObjectId; 11],
+    data_view_prototype: ObjectId,
+    text_encoder_prototype: ObjectId,
+    text_decoder_prototype: ObjectId,
+    map_prototype: ObjectId,
+    set_prototype: ObjectId,
+    weak_map_prototype: ObjectId,
+    weak_set_prototype: ObjectId,
+    map_iterator_prototype: ObjectId,
+    set_iterator_prototype: ObjectId,
+    /// `%IteratorPrototype%`, which every built-in iterator inherits from
+    iterator_prototype: ObjectId,
+    /// `Iterator`, which `Iterator.prototype.constructor` returns
+    iterator_constructor: ObjectId,
+    /// `Array`, which the methods copying arrays make plain arrays for
+    array_constructor: ObjectId,
+    array_iterator_prototype: ObjectId,
+    string_iterator_prototype: ObjectId,
+    /// The prototype of generator functions
+    generator_function_prototype: ObjectId,
+    /// The prototype of generator objects, `GeneratorFunction.prototype.prototype`
+    generator_prototype: ObjectId,
+    /// `%AsyncIteratorPrototype%`, whose `Symbol.asyncIterator` method
+    /// returns the iterator itself
+    async_iterator_prototype: ObjectId,
+    /// The prototype of async generator functions
+    async_generator_function_prototype: ObjectId,
+    /// The prototype of async generator objects
+    async_generator_prototype: ObjectId,
+    /// The prototype of the iterators `Iterator.prototype.map` and the
+    /// other helpers return
+    iterator_helper_prototype: ObjectId,
+    /// The prototype of the wrappers `Iterator.from` returns
+    wrapped_iterator_prototype: ObjectId,
+    /// `Promise`, which `await` and async functions make promises with
+    promise_constructor: ObjectId,
+    promise_prototype: ObjectId,
+    /// The prototype of async functions
+    async_function_prototype: ObjectId,
+    /// `Proxy`, a constructor without a `prototype`
+    proxy_constructor: ObjectId,
+    error_prototype: ObjectId,
+    type_error_prototype: ObjectId,
+    range_error_prototype: ObjectId,
+    reference_error_prototype: ObjectId,
+    syntax_error_prototype: ObjectId,
+    aggregate_error_prototype: ObjectId,
+}
+
+/// The target of an assignment or update, resolved once so that compound
+/// operators evaluate the object and key a single time.
+enum Reference {
+    Binding(String),
+    Property(Value, PropertyKey),
+    /// `object.#name`
+    Private(Value, PrivateName),
+    /// `super.key`, which reads from the home object's prototype but
+    /// writes to `this`
+    Super(PropertyKey),
+}
+
+/// The symbols the language itself looks properties up by.
+struct WellKnownSymbols {
+    iterator: Symbol,
+    async_iterator: Symbol,
+    has_instance: Symbol,
+    to_primitive: Symbol,
+    to_string_tag: Symbol,
+    species: Symbol,
+}
+
+impl WellKnownSymbols {
+    fn new() -> Self {
+        let symbol = |name: &str| Symbol::new(Some(format!("Symbol.{}", name)));
+        Self {
+            iterator: symbol("iterator"),
+            async_iterator: symbol("asyncIterator"),
+            has_instance: symbol("hasInstance"),
+            to_primitive: symbol("toPrimitive"),
+            to_string_tag: symbol("toStringTag"),
+            species: symbol("species"),
+        }
+    }
+}
+
+/// Which conversion `coerce_to_primitive` prefers when an object has both.
+#[derive(Clone, Copy, PartialEq)]
+enum PreferredType {
+    Default,
+    Number,
+    String,
+}
+
+/// How a statement finished executing. Anything other than `Normal`
+/// unwinds enclosing statements until something handles it.
 #[derive(Clone, Debug, PartialEq)]
-pub enum Value {
-    Number(f64),
-    String(String),
-    Boolean(bool),
-    Null,
+enum Completion {
+    Normal(Value),
+    Break(Option<String>),
+    Continue(Option<String>),
+    Return(Value),
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        Self {
-            variables: HashMap::new(),
+        let builtin_scope = Environment::new_global();
+        let global = Environment::new_child(&builtin_scope, ScopeKind::Global);
+        let mut heap = Heap::default();
+        let object_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, None));
+        let function_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let array_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let boolean_prototype = heap.allocate(Object::new(ObjectKind::Primitive(Value::Boolean(false)), Some(object_prototype)));
+        let number_prototype = heap.allocate(Object::new(ObjectKind::Primitive(Value::Number(0.0)), Some(object_prototype)));
+        let string_prototype = heap.allocate(Object::new(ObjectKind::Primitive(Value::String(JsString::new())), Some(object_prototype)));
+        let symbol_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let bigint_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let date_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let mut ordinary = || heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let (map_prototype, set_prototype, weak_map_prototype, weak_set_prototype) = (ordinary(), ordinary(), ordinary(), ordinary());
+        let (array_buffer_prototype, shared_array_buffer_prototype, data_view_prototype) = (ordinary(), ordinary(), ordinary());
+        let (text_encoder_prototype, text_decoder_prototype) = (ordinary(), ordinary());
+        let typed_array_prototype = ordinary();
+        let iterator_prototype = ordinary();
+        let mut iterator = || heap.allocate(Object::new(ObjectKind::Ordinary, Some(iterator_prototype)));
+        let (map_iterator_prototype, set_iterator_prototype) = (iterator(), iterator());
+        let (array_iterator_prototype, string_iterator_prototype) = (iterator(), iterator());
+        let (generator_prototype, iterator_helper_prototype, wrapped_iterator_prototype) = (iterator(), iterator(), iterator());
+        let generator_function_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(function_prototype)));
+        let async_function_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(function_prototype)));
+        let async_generator_function_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(function_prototype)));
+        let async_iterator_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let async_generator_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(async_iterator_prototype)));
+        let typed_array_prototypes = [(); 11].map(|_| heap.allocate(Object::new(ObjectKind::Ordinary, Some(typed_array_prototype))));
+        let promise_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let promise_constructor = heap.allocate(Object::new(ObjectKind::NativeFunction(builtins::promise_constructor), Some(function_prototype)));
+        let array_values = heap.allocate(Object::new(ObjectKind::NativeFunction(builtins::array_values), Some(function_prototype)));
+        let iterator_constructor = heap.allocate(Object::new(ObjectKind::NativeFunction(builtins::iterator_constructor), Some(function_prototype)));
+        let array_constructor = heap.allocate(Object::new(ObjectKind::NativeFunction(builtins::array_constructor), Some(function_prototype)));
+        let proxy_constructor = heap.allocate(Object::new(ObjectKind::NativeFunction(builtins::proxy_constructor), Some(function_prototype)));
+        let error_prototype = heap.allocate(Object::new(ObjectKind::Ordinary, Some(object_prototype)));
+        let mut native_error_prototype = || heap.allocate(Object::new(ObjectKind::Ordinary, Some(error_prototype)));
+        let type_error_prototype = native_error_prototype();
+        let range_error_prototype = native_error_prototype();
+        let reference_error_prototype = native_error_prototype();
+        let syntax_error_prototype = native_error_prototype();
+        let aggregate_error_prototype = native_error_prototype();
+
+        let state = Box::new(State {
+            env: global.clone(),
+            builtin_scope,
+            global,
+            heap,
+            intrinsics: Intrinsics {
+                object_prototype,
+                function_prototype,
+                array_prototype,
+                array_values,
+                boolean_prototype,
+                number_prototype,
+                string_prototype,
+                symbol_prototype,
+                bigint_prototype,
+                date_prototype,
+                array_buffer_prototype,
+                shared_array_buffer_prototype,
+                typed_array_prototype,
+                typed_array_prototypes,
+                data_view_prototype,
+                text_encoder_prototype,
+                text_decoder_prototype,
+                map_prototype,
+                set_prototype,
+                weak_map_prototype,
+                weak_set_prototype,
+                map_iterator_prototype,
+                set_iterator_prototype,
+                iterator_prototype,
+                iterator_constructor,
+                array_constructor,
+                array_iterator_prototype,
+                string_iterator_prototype,
+                generator_function_prototype,
+                generator_prototype,
+                async_iterator_prototype,
+                async_generator_function_prototype,
+                async_generator_prototype,
+                iterator_helper_prototype,
+                wrapped_iterator_prototype,
+                promise_constructor,
+                promise_prototype,
+                async_function_prototype,
+                proxy_constructor,
+                error_prototype,
+                type_error_prototype,
+                range_error_prototype,
+                reference_error_prototype,
+                syntax_error_prototype,
+                aggregate_error_prototype,
+            },
+            call_depth: 0,
+            joining: Vec::new(),
+            new_target: None,
+            exception: None,
+            frames: vec![StackFrame::default()],
+            console: builtins::Console::default(),
+            random: builtins::Random::default(),
+            time_zone: tz::TimeZone::utc(),
+            builtin_objects: 0,
             last_value: None,
-        }
+            symbols: WellKnownSymbols::new(),
+            symbol_registry: HashMap::new(),
+            template_objects: HashMap::new(),
+            scopes: Vec::new(),
+            resumers: Vec::new(),
+            yielder: std::ptr::null(),
+            stack_limit: None,
+            generator_stacks: StackPool::default(),
+            event_loop: EventLoop::default(),
+        });
+        let mut interpreter = Self { state: NonNull::from(Box::leak(state)), owner: true };
+        builtins::install(&mut interpreter);
+        interpreter.builtin_objects = interpreter.live_objects();
+        interpreter.heap.temporaries.clear();
+        interpreter
     }
 
+    /// Evaluates a single top-level statement. Any `var` declarations it
+    /// contains are hoisted to the global scope first. Like `run`, this
+    /// also runs the promise jobs the statement queued.
     pub fn eval(&mut self, stmt: Statement) -> std::result::Result<Value, String> {
+        self.exception = None;
+        self.hoist_var_declarations(std::slice::from_ref(&stmt))?;
+        self.instantiate_function_declarations(std::slice::from_ref(&stmt))?;
+        self.run_script(vec![stmt])
+    }
+
+    /// Evaluates a whole program, hoisting its declarations before running
+    /// any statement, then runs the promise jobs it queued. Returns the
+    /// value of the last statement, or, if the program is still waiting on
+    /// a top-level `await`, a promise for it; see `run_until_idle`.
+    pub fn run(&mut self, program: Vec<Statement>) -> std::result::Result<Value, String> {
+        self.exception = None;
+        self.hoist_var_declarations(&program)?;
+        self.hoist_lexical_declarations(&program)?;
+        self.instantiate_function_declarations(&program)?;
+        self.run_script(program)
+    }
+
+    /// Executes the statements of a script, returning the value of the
+    /// last one.
+    fn exec_script(&mut self, statements: &[Statement]) -> std::result::Result<Value, String> {
+        let mut val = Value::Undefined;
+        for stmt in statements {
+            match self.exec_statement(stmt)? {
+                Completion::Normal(v) => val = v,
+                _ => {
+                    val = Value::Undefined;
+                    break;
+                }
+            }
+            // Between top-level statements only the completion value is
+            // held outside the interpreter
+            self.maybe_collect_garbage(&[&val]);
+        }
+        Ok(val)
+    }
+
+    fn exec_statement(&mut self, stmt: &Statement) -> std::result::Result<Completion, String> {
+        // What the statement held on to is released when it finishes
+        let mark = self.heap.temporaries.len();
+        // Statements nest inside one function as deeply as the source does,
+        // so each checks the stack as calls do
+        let completion = self.with_stack(|interpreter| interpreter.execute_statement(stmt));
+        self.heap.temporaries.truncate(mark);
+        completion
+    }
+
+    fn execute_statement(&mut self, stmt: &Statement) -> std::result::Result<Completion, String> {
         match stmt {
-            Statement::Let { name, value } => {
-                let val = self.eval_expression(value)?;
-                self.variables.insert(name, val.clone());
-                self.last_value = Some(val.clone());
-                Ok(val)
+            Statement::VariableDeclaration { kind, declarations } => {
+                let mut val = Value::Undefined;
+                for declarator in declarations {
+                    val = self.declare(*kind, &declarator.target, declarator.init.as_ref())?;
+                }
+                Ok(Completion::Normal(val))
+            },
+            Statement::Expression(expr) => Ok(Completion::Normal(self.eval_expression(expr)?)),
+            Statement::Block(body) => {
+                let scope = Environment::new_child(&self.env, ScopeKind::Block);
+                self.in_scope(scope, |interpreter| interpreter.exec_block(body))
+            },
+            Statement::If { condition, consequent, alternate } => {
+                if self.eval_expression(condition)?.is_truthy() {
+                    self.exec_statement(consequent)
+                } else if let Some(alternate) = alternate {
+                    self.exec_statement(alternate)
+                } else {
+                    Ok(Completion::Normal(Value::Undefined))
+                }
+            },
+            Statement::While { .. }
+            | Statement::DoWhile { .. }
+            | Statement::For { .. }
+            | Statement::ForIn { .. }
+            | Statement::ForOf { .. } => {
+                self.exec_loop(stmt, &[])
+            },
+            Statement::Switch { discriminant, cases } => self.exec_switch(discriminant, cases, &[]),
+            // Instantiated when the enclosing scope was entered
+            Statement::FunctionDeclaration(_) => Ok(Completion::Normal(Value::Undefined)),
+            Statement::ClassDeclaration(definition) => {
+                let name = Pattern::Identifier(definition.name.clone().unwrap_or_default());
+                let class = Expression::Class(Rc::clone(definition));
+                self.declare(VariableKind::Let, &name, Some(&class))?;
+                Ok(Completion::Normal(Value::Undefined))
+            },
+            Statement::Return(value) => {
+                let val = match value {
+                    Some(expr) => self.eval_expression(expr)?,
+                    None => Value::Undefined,
+                };
+                Ok(Completion::Return(val))
+            },
+            Statement::Throw(value) => {
+                let value = self.eval_expression(value)?;
+                Err(self.throw_value(value))
+            }
+            Statement::Try { block, handler, finalizer } => self.exec_try(block, handler.as_ref(), finalizer.as_deref()),
+            Statement::Break(label) => Ok(Completion::Break(label.clone())),
+            Statement::Continue(label) => Ok(Completion::Continue(label.clone())),
+            Statement::Labeled { .. } => self.exec_labeled(stmt, Vec::new()),
+            Statement::Empty => Ok(Completion::Normal(Value::Undefined)),
+        }
+    }
+
+    /// Runs statements in the current scope after hoisting their lexical
+    /// declarations into it, stopping at the first abrupt completion.
+    fn exec_block(&mut self, body: &[Statement]) -> std::result::Result<Completion, String> {
+        self.hoist_lexical_declarations(body)?;
+        self.instantiate_function_declarations(body)?;
+        let mut val = Value::Undefined;
+        for stmt in body {
+            match self.exec_statement(stmt)? {
+                Completion::Normal(v) => val = v,
+                abrupt => return Ok(abrupt),
+            }
+        }
+        Ok(Completion::Normal(val))
+    }
+
+    /// Collects the labels of directly nested labeled statements so that the
+    /// loop they name can handle `continue label`.
+    fn exec_labeled(&mut self, stmt: &Statement, mut labels: Vec<String>) -> std::result::Result<Completion, String> {
+        let Statement::Labeled { label, body } = stmt else {
+            return self.exec_statement(stmt);
+        };
+        labels.push(label.clone());
+
+        let completion = match body.as_ref() {
+            Statement::Labeled { .. } => self.exec_labeled(body, labels)?,
+            Statement::While { .. }
+            | Statement::DoWhile { .. }
+            | Statement::For { .. }
+            | Statement::ForIn { .. }
+            | Statement::ForOf { .. } => {
+                self.exec_loop(body, &labels)?
+            }
+            Statement::Switch { discriminant, cases } => self.exec_switch(discriminant, cases, &labels)?,
+            other => self.exec_statement(other)?,
+        };
+
+        match completion {
+            Completion::Break(Some(target)) if &target == label => Ok(Completion::Normal(Value::Undefined)),
+            other => Ok(other),
+        }
+    }
+
+    /// Decides how a loop proceeds after its body completes: `None` to keep
+    /// iterating, or the completion the loop itself finishes with.
+    fn loop_continues(completion: Completion, labels: &[String]) -> Option<Completion> {
+        match completion {
+            Completion::Normal(_) | Completion::Continue(None) => None,
+            Completion::Continue(Some(label)) if labels.contains(&label) => None,
+            Completion::Break(None) => Some(Completion::Normal(Value::Undefined)),
+            Completion::Break(Some(label)) if labels.contains(&label) => Some(Completion::Normal(Value::Undefined)),
+            abrupt => Some(abrupt),
+        }
+    }
+
+    fn exec_loop(&mut self, stmt: &Statement, labels: &[String]) -> std::result::Result<Completion, String> {
+        match stmt {
+            Statement::While { condition, body } => {
+                let mark = self.heap.temporaries.len();
+                while self.eval_expression(condition)?.is_truthy() {
+                    self.end_iteration(mark);
+                    if let Some(done) = Self::loop_continues(self.exec_statement(body)?, labels) {
+                        return Ok(done);
+                    }
+                }
+            }
+            Statement::DoWhile { body, condition } => {
+                let mark = self.heap.temporaries.len();
+                loop {
+                    if let Some(done) = Self::loop_continues(self.exec_statement(body)?, labels) {
+                        return Ok(done);
+                    }
+                    if !self.eval_expression(condition)?.is_truthy() {
+                        break;
+                    }
+                    self.end_iteration(mark);
+                }
+            }
+            Statement::For { init, test, update, body } => {
+                let scope = Environment::new_child(&self.env, ScopeKind::Block);
+                return self.in_scope(scope, |interpreter| {
+                    interpreter.exec_for(init.as_deref(), test.as_ref(), update.as_ref(), body, labels)
+                });
+            }
+            Statement::ForIn { left, object, body } => {
+                let object = self.eval_expression(object)?;
+                let mark = self.heap.temporaries.len();
+                for key in self.for_in_keys(&object)? {
+                    self.end_iteration(mark);
+                    // Keys deleted by an earlier iteration are skipped
+                    let present = match &object {
+                        Value::Object(id) | Value::Function(id) => self.has_property(*id, &key)?,
+                        _ => true,
+                    };
+                    if !present {
+                        continue;
+                    }
+                    let completion = self.exec_for_in_of_body(left, Value::String(JsString::from(key.to_string())), body)?;
+                    if let Some(done) = Self::loop_continues(completion, labels) {
+                        return Ok(done);
+                    }
+                }
+            }
+            Statement::ForOf { left, iterable, body, is_await: true } => {
+                let iterable = self.eval_expression(iterable)?;
+                let (mut iteration, from_sync) = self.async_iterate(&iterable)?;
+                let close = if from_sync { Self::close_iteration } else { Self::close_async_iteration };
+                let mark = self.heap.temporaries.len();
+                while let Some(value) = self.async_iteration_step(&mut iteration, from_sync)? {
+                    let completion = match self.exec_for_in_of_body(left, value, body) {
+                        Ok(completion) => completion,
+                        Err(message) => return close(self, &mut iteration, Err(message)),
+                    };
+                    if let Some(done) = Self::loop_continues(completion, labels) {
+                        return close(self, &mut iteration, Ok(done));
+                    }
+                    self.end_iteration(mark);
+                }
+            }
+            Statement::ForOf { left, iterable, body, is_await: false } => {
+                let iterable = self.eval_expression(iterable)?;
+                let mut iteration = self.iterate(&iterable)?;
+                let mark = self.heap.temporaries.len();
+                while let Some(value) = self.iteration_step(&mut iteration)? {
+                    // Leaving the loop early closes the iterator
+                    let completion = match self.exec_for_in_of_body(left, value, body) {
+                        Ok(completion) => completion,
+                        Err(message) => return self.close_iteration(&mut iteration, Err(message)),
+                    };
+                    if let Some(done) = Self::loop_continues(completion, labels) {
+                        return self.close_iteration(&mut iteration, Ok(done));
+                    }
+                    self.end_iteration(mark);
+                }
+            }
+            _ => unreachable!("exec_loop called on a non-loop statement"),
+        }
+        Ok(Completion::Normal(Value::Undefined))
+    }
+
+    /// The keys a `for...in` loop visits: enumerable properties of the
+    /// object and then of each prototype, skipping shadowed names.
+    fn for_in_keys(&mut self, value: &Value) -> std::result::Result<Vec<PropertyKey>, String> {
+        match value {
+            Value::Object(id) | Value::Function(id) => {
+                let mut seen = HashSet::new();
+                let mut keys = Vec::new();
+                let mut current = Some(*id);
+                while let Some(id) = current {
+                    for key in self.own_property_keys(id)? {
+                        if matches!(key, PropertyKey::Symbol(_)) || !seen.insert(key.clone()) {
+                            continue;
+                        }
+                        if self.get_own_property(id, &key)?.is_some_and(|property| property.attributes.enumerable) {
+                            keys.push(key);
+                        }
+                    }
+                    current = self.get_prototype_of(id)?;
+                }
+                Ok(keys)
+            }
+            Value::String(s) => Ok((0..s.len() as u32).map(PropertyKey::Index).collect()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Binds one key of a `for...in` loop or value of a `for...of` loop and
+    /// runs the body.
+    fn exec_for_in_of_body(&mut self, left: &ForBinding, value: Value, body: &Statement) -> std::result::Result<Completion, String> {
+        match left {
+            ForBinding::Declaration { kind: VariableKind::Var, target } => {
+                self.bind_pattern(target, value, Some(VariableKind::Var))?;
+                self.exec_statement(body)
+            }
+            // `let` and `const` get fresh bindings per iteration
+            ForBinding::Declaration { kind, target } => {
+                let scope = Environment::new_child(&self.env, ScopeKind::Block);
+                for name in target.bound_names() {
+                    scope.borrow_mut().insert(name.clone(), Binding { value: None, kind: *kind });
+                }
+                self.in_scope(scope, |interpreter| {
+                    interpreter.bind_pattern(target, value, Some(*kind))?;
+                    interpreter.exec_statement(body)
+                })
+            }
+            ForBinding::Assignment(target) => {
+                self.bind_pattern(target, value, None)?;
+                self.exec_statement(body)
+            }
+        }
+    }
+
+    fn exec_for(
+        &mut self,
+        init: Option<&Statement>,
+        test: Option<&Expression>,
+        update: Option<&Expression>,
+        body: &Statement,
+        labels: &[String],
+    ) -> std::result::Result<Completion, String> {
+        let per_iteration = match init {
+            Some(init @ Statement::VariableDeclaration { kind: VariableKind::Let | VariableKind::Const, .. }) => {
+                self.hoist_lexical_declarations(std::slice::from_ref(init))?;
+                self.exec_statement(init)?;
+                true
+            }
+            Some(init) => {
+                self.exec_statement(init)?;
+                false
+            }
+            None => false,
+        };
+
+        // Each iteration gets a fresh copy of the loop's `let` bindings so
+        // closures created in the body capture that iteration's values
+        if per_iteration {
+            self.env = Environment::copy_scope(&self.env);
+        }
+        let mark = self.heap.temporaries.len();
+        loop {
+            self.end_iteration(mark);
+            if let Some(test) = test {
+                if !self.eval_expression(test)?.is_truthy() {
+                    break;
+                }
+            }
+            if let Some(done) = Self::loop_continues(self.exec_statement(body)?, labels) {
+                return Ok(done);
+            }
+            if per_iteration {
+                self.env = Environment::copy_scope(&self.env);
+            }
+            if let Some(update) = update {
+                self.eval_expression(update)?;
+            }
+        }
+        Ok(Completion::Normal(Value::Undefined))
+    }
+
+    fn exec_switch(
+        &mut self,
+        discriminant: &Expression,
+        cases: &[SwitchCase],
+        labels: &[String],
+    ) -> std::result::Result<Completion, String> {
+        let value = self.eval_expression(discriminant)?;
+        let scope = Environment::new_child(&self.env, ScopeKind::Block);
+        let completion = self.in_scope(scope, |interpreter| {
+            for case in cases {
+                interpreter.hoist_lexical_declarations(&case.consequent)?;
+            }
+
+            let mut start = None;
+            for (index, case) in cases.iter().enumerate() {
+                if let Some(test) = &case.test {
+                    if strict_equals(&value, &interpreter.eval_expression(test)?) {
+                        start = Some(index);
+                        break;
+                    }
+                }
+            }
+            let start = start.or_else(|| cases.iter().position(|case| case.test.is_none()));
+
+            // Matching starts execution at one clause and falls through the rest
+            let mut val = Value::Undefined;
+            for case in cases.iter().skip(start.unwrap_or(cases.len())) {
+                for stmt in &case.consequent {
+                    match interpreter.exec_statement(stmt)? {
+                        Completion::Normal(v) => val = v,
+                        abrupt => return Ok(abrupt),
+                    }
+                }
+            }
+            Ok(Completion::Normal(val))
+        })?;
+
+        match completion {
+            Completion::Break(None) => Ok(Completion::Normal(Value::Undefined)),
+            Completion::Break(Some(label)) if labels.contains(&label) => Ok(Completion::Normal(Value::Undefined)),
+            other => Ok(other),
+        }
+    }
+
+    /// Runs `f` with `scope` as the current environment, restoring the
+    /// previous one afterwards even if `f` fails.
+    fn in_scope<T>(
+        &mut self,
+        scope: EnvRef,
+        f: impl FnOnce(&mut Self) -> std::result::Result<T, String>,
+    ) -> std::result::Result<T, String> {
+        let outer = std::mem::replace(&mut self.env, scope);
+        self.scopes.push(outer);
+        let result = f(self);
+        self.env = self.scopes.pop().expect("scopes are restored in order");
+        result
+    }
+
+    /// Declares every `var` in `body` (including nested blocks) in the
+    /// enclosing function or global scope, initialized to `undefined`.
+    fn hoist_var_declarations(&mut self, body: &[Statement]) -> std::result::Result<(), String> {
+        let mut names = Vec::new();
+        collect_var_names(body, &mut names);
+        let scope = Environment::var_scope(&self.env);
+        let mut scope = scope.borrow_mut();
+        for name in names {
+            match scope.get_own(&name) {
+                Some(binding) if binding.kind != VariableKind::Var => {
+                    return Err(syntax_error(format!("Identifier '{}' has already been declared", name)));
+                }
+                Some(_) => {}
+                None => scope.insert(name, Binding { value: Some(Value::Undefined), kind: VariableKind::Var }),
+            }
+        }
+        Ok(())
+    }
+
+    /// Declares the `let`/`const` and class bindings made directly in `body`
+    /// in the current scope, uninitialized until their declaration is
+    /// evaluated.
+    fn hoist_lexical_declarations(&mut self, body: &[Statement]) -> std::result::Result<(), String> {
+        let mut scope = self.env.borrow_mut();
+        for stmt in body {
+            let names: Vec<(&String, VariableKind)> = match stmt {
+                Statement::VariableDeclaration { kind: kind @ (VariableKind::Let | VariableKind::Const), declarations } => {
+                    declarations.iter().flat_map(|declarator| declarator.target.bound_names()).map(|name| (name, *kind)).collect()
+                }
+                Statement::ClassDeclaration(definition) => definition.name.iter().map(|name| (name, VariableKind::Let)).collect(),
+                _ => continue,
+            };
+            for (name, kind) in names {
+                if scope.get_own(name).is_some() {
+                    return Err(syntax_error(format!("Identifier '{}' has already been declared", name)));
+                }
+                scope.insert(name.clone(), Binding { value: None, kind });
+            }
+        }
+        Ok(())
+    }
+
+    /// Binds the function declarations made directly in `body` in the current
+    /// scope, so they can be called before the declaration is reached.
+    fn instantiate_function_declarations(&mut self, body: &[Statement]) -> std::result::Result<(), String> {
+        for stmt in body {
+            if let Statement::FunctionDeclaration(definition) = stmt {
+                let name = definition.name.clone().unwrap_or_default();
+                if let Some(binding) = self.env.borrow().get_own(&name) {
+                    if binding.kind != VariableKind::Var {
+                        return Err(syntax_error(format!("Identifier '{}' has already been declared", name)));
+                    }
+                }
+                let function = self.create_function(definition, self.env.clone());
+                self.env.borrow_mut().insert(name, Binding { value: Some(function), kind: VariableKind::Var });
+            }
+        }
+        Ok(())
+    }
+
+    fn declare(&mut self, kind: VariableKind, target: &Pattern, init: Option<&Expression>) -> std::result::Result<Value, String> {
+        // Hoisting already created `var` bindings in the var scope
+        if kind == VariableKind::Var && init.is_none() {
+            return Ok(Value::Undefined);
+        }
+
+        // The bindings exist but stay uninitialized while the initializer
+        // runs, so `let x = x + 1` reports a TDZ error
+        if kind != VariableKind::Var {
+            let mut scope = self.env.borrow_mut();
+            for name in target.bound_names() {
+                if let Some(Binding { value: Some(_), .. }) = scope.get_own(name) {
+                    return Err(syntax_error(format!("Identifier '{}' has already been declared", name)));
+                }
+                scope.insert(name.clone(), Binding { value: None, kind });
+            }
+        }
+
+        let val = match (init, target) {
+            (Some(expr), Pattern::Identifier(name)) => self.eval_named(expr, name)?,
+            (Some(expr), _) => self.eval_expression(expr)?,
+            (None, _) => Value::Undefined,
+        };
+        self.bind_pattern(target, val.clone(), Some(kind))?;
+        Ok(val)
+    }
+
+    /// Initializes a declared binding, in the var scope for `var`.
+    fn initialize_binding(&mut self, kind: VariableKind, name: &str, value: Value) {
+        let scope = match kind {
+            VariableKind::Var => Environment::var_scope(&self.env),
+            VariableKind::Let | VariableKind::Const => self.env.clone(),
+        };
+        scope.borrow_mut().insert(name.to_string(), Binding { value: Some(value), kind });
+    }
+
+    /// Destructures `value` into `pattern`. The names it binds are declared
+    /// as `kind`, or assigned to as existing targets when `kind` is `None`.
+    fn bind_pattern(&mut self, pattern: &Pattern, value: Value, kind: Option<VariableKind>) -> std::result::Result<(), String> {
+        match pattern {
+            Pattern::Identifier(name) => match kind {
+                Some(kind) => {
+                    self.initialize_binding(kind, name, value);
+                    Ok(())
+                }
+                None => self.assign(name, value),
+            },
+            Pattern::Expression(target) => {
+                let reference = self.eval_reference(target)?;
+                self.put_reference(&reference, value)
+            }
+            Pattern::Array { elements, rest } => {
+                // Only as many values are taken as the pattern needs, and
+                // the iterator is closed if it is not done by then
+                let mut iteration = self.iterate(&value)?;
+                let result = self.bind_array_pattern(&mut iteration, elements, rest.as_deref(), kind);
+                self.close_iteration(&mut iteration, result)
+            }
+            Pattern::Object { properties, rest } => {
+                self.hold(&value);
+                if matches!(value, Value::Null | Value::Undefined) {
+                    let shown = value.to_js_string();
+                    return Err(type_error(match properties.first() {
+                        Some(PatternProperty { key: PropertyName::Literal(key), .. }) => {
+                            format!("Cannot destructure property '{}' of '{}' as it is {}.", key, shown, shown)
+                        }
+                        _ => format!("Cannot destructure '{}' as it is {}.", shown, shown),
+                    }));
+                }
+                let mut used = Vec::with_capacity(properties.len());
+                for property in properties {
+                    let key = self.eval_property_name(&property.key)?;
+                    let property_value = self.get_member(&value, &key)?;
+                    used.push(key);
+                    self.bind_element(&property.target, property.default.as_ref(), property_value, kind)?;
+                }
+                if let Some(rest) = rest {
+                    let rest_object = self.create_object();
+                    self.copy_data_properties(rest_object, &value, &used)?;
+                    self.bind_pattern(rest, Value::Object(rest_object), kind)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn bind_array_pattern(
+        &mut self,
+        iteration: &mut Iteration,
+        elements: &[Option<PatternElement>],
+        rest: Option<&Pattern>,
+        kind: Option<VariableKind>,
+    ) -> std::result::Result<(), String> {
+        for element in elements {
+            let value = self.iteration_step(iteration)?.unwrap_or(Value::Undefined);
+            if let Some(element) = element {
+                self.bind_element(&element.target, element.default.as_ref(), value, kind)?;
+            }
+        }
+        if let Some(rest) = rest {
+            let values = self.collect_iteration(iteration)?;
+            let rest_value = self.create_array(values);
+            self.bind_pattern(rest, rest_value, kind)?;
+        }
+        Ok(())
+    }
+
+    /// Binds one element of a pattern, or a parameter, falling back to
+    /// `default` when the value is `undefined`.
+    fn bind_element(
+        &mut self,
+        target: &Pattern,
+        default: Option<&Expression>,
+        value: Value,
+        kind: Option<VariableKind>,
+    ) -> std::result::Result<(), String> {
+        let value = match (value, default) {
+            (Value::Undefined, Some(default)) => match target {
+                Pattern::Identifier(name) => self.eval_named(default, name)?,
+                _ => self.eval_expression(default)?,
             },
-            Statement::Expression(expr) => {
-                let val = self.eval_expression(expr)?;
-                self.last_value = Some(val.clone());
+            (value, _) => value,
+        };
+        self.bind_pattern(target, value, kind)
+    }
+
+    fn lookup(&self, name: &str) -> std::result::Result<Value, String> {
+        let scope = Environment::resolve(&self.env, name)
+            .ok_or_else(|| reference_error(format!("{} is not defined", name)))?;
+        let scope = scope.borrow();
+        match scope.get_own(name) {
+            Some(Binding { value: Some(value), .. }) => Ok(value.clone()),
+            _ => Err(reference_error(format!("Cannot access '{}' before initialization", name))),
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: Value) -> std::result::Result<(), String> {
+        let scope = Environment::resolve(&self.env, name)
+            .ok_or_else(|| reference_error(format!("{} is not defined", name)))?;
+        let mut scope = scope.borrow_mut();
+        match scope.get_own_mut(name) {
+            Some(Binding { value: None, .. }) => {
+                Err(reference_error(format!("Cannot access '{}' before initialization", name)))
+            }
+            Some(Binding { kind: VariableKind::Const, .. }) => {
+                Err(type_error("Assignment to constant variable."))
+            }
+            Some(binding) => {
+                binding.value = Some(value);
+                Ok(())
+            }
+            None => Err(reference_error(format!("{} is not defined", name))),
+        }
+    }
+
+    fn eval_expression(&mut self, expr: &Expression) -> std::result::Result<Value, String> {
+        let value = self.with_stack(|interpreter| interpreter.evaluate_expression(expr))?;
+        self.hold(&value);
+        Ok(value)
+    }
+
+    /// `eval_expression` without holding on to the result.
+    fn evaluate_expression(&mut self, expr: &Expression) -> std::result::Result<Value, String> {
+        match expr {
+            Expression::Number(n) => Ok(Value::Number(*n)),
+            Expression::BigInt(n) => Ok(Value::BigInt(n.clone())),
+            Expression::String(s) => Ok(Value::String(s.clone())),
+            Expression::Boolean(b) => Ok(Value::Boolean(*b)),
+            Expression::Null => Ok(Value::Null),
+            Expression::Identifier { name, location } => {
+                self.set_location(*location);
+                self.lookup(name)
+            }
+            Expression::This => self.this_value(),
+            Expression::Function(definition) => {
+                // A named function expression can refer to itself by name
+                let Some(name) = &definition.name else {
+                    return Ok(self.create_function(definition, self.env.clone()));
+                };
+                let scope = Environment::new_child(&self.env, ScopeKind::Block);
+                let function = self.create_function(definition, scope.clone());
+                scope.borrow_mut().insert(name.clone(), Binding { value: Some(function.clone()), kind: VariableKind::Const });
+                Ok(function)
+            }
+            Expression::Class(definition) => self.eval_class(definition, None),
+            Expression::Super | Expression::PrivateName(_) => Err(syntax_error("Unexpected token")),
+            Expression::Call { callee, arguments, location } => {
+                if matches!(**callee, Expression::Super) {
+                    self.set_location(*location);
+                    return self.eval_super_call(arguments);
+                }
+                let (function, this) = self.eval_callee(callee)?;
+                self.eval_call(callee, function, this, arguments, *location)
+            }
+            Expression::TaggedTemplate { tag, template, location } => {
+                let (function, this) = self.eval_callee(tag)?;
+                self.hold(&function);
+                let mut args = vec![Value::Object(self.template_object(template)?)];
+                for expression in &template.expressions {
+                    args.push(self.eval_expression(expression)?);
+                }
+                self.set_location(*location);
+                if !matches!(function, Value::Function(_)) {
+                    return Err(type_error(format!("{} is not a function", self.describe_callee(tag, &function))));
+                }
+                self.call_function(&function, this, args)
+            }
+            Expression::New { callee, arguments, location } => {
+                let constructor = self.eval_expression(callee)?;
+                let args = self.eval_arguments(arguments)?;
+                self.set_location(*location);
+                if !self.is_constructor(&constructor) {
+                    return Err(type_error(format!("{} is not a constructor", self.describe_callee(callee, &constructor))));
+                }
+                self.construct(&constructor, args, &constructor)
+            }
+            Expression::Member { object, .. } | Expression::Index { object, .. } if matches!(**object, Expression::Super) => {
+                self.this_value()?;
+                let key = self.member_key(expr)?;
+                self.get_super(&key)
+            }
+            Expression::NewTarget => {
+                Ok(Environment::function_context(&self.env).map_or(Value::Undefined, |context| context.new_target))
+            }
+            Expression::Member { object, .. } | Expression::Index { object, .. } => {
+                let target = self.eval_expression(object)?;
+                let key = self.member_key(expr)?;
+                self.get_member(&target, &key)
+            }
+            Expression::PrivateMember { object, name } => {
+                let target = self.eval_expression(object)?;
+                let name = self.private_name(name)?;
+                self.get_private(&target, &name)
+            }
+            Expression::OptionalChain(chain) => Ok(self.eval_chain(chain)?.unwrap_or(Value::Undefined)),
+            Expression::Optional(_) => unreachable!("`?.` bases are evaluated by eval_chain"),
+            Expression::Logical { left, operator, right } => {
+                let lhs = self.eval_expression(left)?;
+                let short_circuits = match operator {
+                    Token::AmpersandAmpersand => !lhs.is_truthy(),
+                    Token::QuestionQuestion => !matches!(lhs, Value::Null | Value::Undefined),
+                    _ => lhs.is_truthy(),
+                };
+                if short_circuits {
+                    Ok(lhs)
+                } else {
+                    self.eval_expression(right)
+                }
+            }
+            Expression::Conditional { test, consequent, alternate } => {
+                if self.eval_expression(test)?.is_truthy() {
+                    self.eval_expression(consequent)
+                } else {
+                    self.eval_expression(alternate)
+                }
+            }
+            Expression::Sequence(expressions) => {
+                let mut val = Value::Undefined;
+                for expr in expressions {
+                    val = self.eval_expression(expr)?;
+                }
                 Ok(val)
+            }
+            Expression::Template(template) => {
+                let text = |index: usize| template.cooked[index].as_ref().expect("untagged template with an invalid escape");
+                let mut result = text(0).clone();
+                for (index, expression) in template.expressions.iter().enumerate() {
+                    let value = self.eval_expression(expression)?;
+                    result.push_js_str(&self.coerce_to_string(&value)?);
+                    result.push_js_str(text(index + 1));
+                }
+                Ok(Value::String(result))
+            }
+            Expression::Binary { left, operator, right, location } => {
+                // `#name in object`, the only place a bare private name appears
+                if let Expression::PrivateName(name) = left.as_ref() {
+                    let target = self.eval_expression(right)?;
+                    let name = self.private_name(name)?;
+                    return self.has_private(&target, &name).map(Value::Boolean);
+                }
+                let lhs = self.eval_expression(left)?;
+                let rhs = self.eval_expression(right)?;
+                self.set_location(*location);
+                self.eval_binary_op(lhs, operator, rhs)
+            }
+            Expression::Unary { operator: Token::Delete, operand, .. } => match operand.as_ref() {
+                Expression::Member { object, .. } | Expression::Index { object, .. } if matches!(**object, Expression::Super) => {
+                    Err(reference_error("Unsupported reference to 'super'"))
+                }
+                Expression::Member { object, .. } | Expression::Index { object, .. } => {
+                    let target = self.eval_expression(object)?;
+                    let key = self.member_key(operand)?;
+                    self.delete_member(&target, &key).map(Value::Boolean)
+                }
+                // `delete a?.b` does nothing when `a` is nullish
+                Expression::OptionalChain(chain) => match chain.as_ref() {
+                    Expression::Member { object, .. } | Expression::Index { object, .. } => {
+                        let Some(target) = self.eval_chain(object)? else {
+                            return Ok(Value::Boolean(true));
+                        };
+                        let key = self.member_key(chain)?;
+                        self.delete_member(&target, &key).map(Value::Boolean)
+                    }
+                    chain => {
+                        self.eval_chain(chain)?;
+                        Ok(Value::Boolean(true))
+                    }
+                },
+                operand => {
+                    self.eval_expression(operand)?;
+                    Ok(Value::Boolean(true))
+                }
             },
+            Expression::Unary { operator: Token::Typeof, operand, .. } => {
+                // An undeclared variable is `undefined` here rather than an error
+                if let Expression::Identifier { name, .. } = operand.as_ref() {
+                    if Environment::resolve(&self.env, name).is_none() {
+                        return Ok(Value::String("undefined".into()));
+                    }
+                }
+                let value = self.eval_expression(operand)?;
+                Ok(Value::String(value.type_of().into()))
+            }
+            Expression::Unary { operator, operand, location } => {
+                let val = self.eval_expression(operand)?;
+                self.set_location(*location);
+                match operator {
+                    Token::Minus => match self.coerce_to_numeric(&val)? {
+                        Value::BigInt(n) => Ok(Value::BigInt(-n)),
+                        n => Ok(Value::Number(-n.to_number())),
+                    },
+                    Token::Plus => Ok(Value::Number(self.coerce_to_number(&val)?)),
+                    Token::Bang => Ok(Value::Boolean(!val.is_truthy())),
+                    Token::Void => Ok(Value::Undefined),
+                    Token::Tilde => match self.coerce_to_numeric(&val)? {
+                        Value::BigInt(n) => Ok(Value::BigInt(-n - 1)),
+                        n => Ok(Value::Number(!to_int32(n.to_number()) as f64)),
+                    },
+                    _ => Err(format!("Unsupported unary operator {:?}", operator)),
+                }
+            }
+            Expression::Update { operator, prefix, target } => {
+                let reference = self.eval_reference(target)?;
+                let current = self.get_reference(&reference)?;
+                let old = self.coerce_to_numeric(&current)?;
+                let delta = if *operator == Token::PlusPlus { 1 } else { -1 };
+                let new = match &old {
+                    Value::BigInt(n) => Value::BigInt(n + delta),
+                    n => Value::Number(n.to_number() + delta as f64),
+                };
+                self.put_reference(&reference, new.clone())?;
+                Ok(if *prefix { new } else { old })
+            }
+            Expression::Assignment {
+                operator: operator @ (Token::AmpersandAmpersandEqual | Token::PipePipeEqual | Token::QuestionQuestionEqual),
+                target,
+                value,
+            } => {
+                let reference = self.eval_reference(target)?;
+                let current = self.get_reference(&reference)?;
+                let short_circuits = match operator {
+                    Token::AmpersandAmpersandEqual => !current.is_truthy(),
+                    Token::PipePipeEqual => current.is_truthy(),
+                    _ => !matches!(current, Value::Null | Value::Undefined),
+                };
+                if short_circuits {
+                    return Ok(current);
+                }
+                let val = match &reference {
+                    Reference::Binding(name) => self.eval_named(value, name)?,
+                    _ => self.eval_expression(value)?,
+                };
+                self.put_reference(&reference, val.clone())?;
+                Ok(val)
+            }
+            Expression::Assignment { operator, target, value } => {
+                let reference = self.eval_reference(target)?;
+                let val = match compound_operator(operator) {
+                    Some(op) => {
+                        let current = self.get_reference(&reference)?;
+                        self.hold(&current);
+                        let rhs = self.eval_expression(value)?;
+                        self.eval_binary_op(current, &op, rhs)?
+                    }
+                    None => match &reference {
+                        Reference::Binding(name) => self.eval_named(value, name)?,
+                        _ => self.eval_expression(value)?,
+                    },
+                };
+                self.put_reference(&reference, val.clone())?;
+                Ok(val)
+            }
+            Expression::DestructuringAssignment { target, value } => {
+                let value = self.eval_expression(value)?;
+                self.bind_pattern(target, value.clone(), None)?;
+                Ok(value)
+            }
+            Expression::Array(elements) => {
+                let mut values = Vec::with_capacity(elements.len());
+                for element in elements {
+                    match element {
+                        None => values.push(None),
+                        Some(Expression::Spread(inner)) => {
+                            let iterable = self.eval_expression(inner)?;
+                            values.extend(self.collect_iterable(&iterable)?.into_iter().map(Some));
+                        }
+                        Some(element) => values.push(Some(self.eval_expression(element)?)),
+                    }
+                }
+                let prototype = self.intrinsics.array_prototype;
+                let id = self.heap.allocate_array(values, Some(prototype));
+                Ok(Value::Object(id))
+            }
+            Expression::Spread(_) => Err(syntax_error("Unexpected spread element")),
+            Expression::Object(properties) => self.eval_object_literal(properties),
+            Expression::Yield { argument, delegate } => self.eval_yield(argument.as_deref(), *delegate),
+            Expression::Await(argument) => self.eval_await(argument),
+        }
+    }
+
+    /// Evaluates `expr`, naming it `name` if it is an anonymous function,
+    /// as in `const f = () => {}`.
+    fn eval_named(&mut self, expr: &Expression, name: &str) -> std::result::Result<Value, String> {
+        match expr {
+            Expression::Function(definition) if definition.name.is_none() => {
+                let function = self.create_function(definition, self.env.clone());
+                self.set_function_name(&function, name);
+                Ok(function)
+            }
+            Expression::Class(definition) if definition.name.is_none() => self.eval_class(definition, Some(name)),
+            _ => self.eval_expression(expr),
+        }
+    }
+
+    fn set_function_name(&mut self, function: &Value, name: &str) {
+        if let Value::Function(id) = function {
+            let name = Property::data(Value::String(name.into()), Attributes::READ_ONLY);
+            self.heap.define_property(*id, PropertyKey::from("name"), name);
+        }
+    }
+
+    fn eval_object_literal(&mut self, properties: &[ObjectProperty]) -> std::result::Result<Value, String> {
+        let id = self.create_object();
+        self.hold(&Value::Object(id));
+        for property in properties {
+            match property {
+                // `__proto__: value` sets the prototype instead of a property
+                ObjectProperty::Property { key: PropertyName::Literal(name), value } if name == "__proto__" => {
+                    match self.eval_expression(value)? {
+                        Value::Object(prototype) | Value::Function(prototype) => self.heap.get_mut(id).prototype = Some(prototype),
+                        Value::Null => self.heap.get_mut(id).prototype = None,
+                        _ => {}
+                    }
+                }
+                ObjectProperty::Property { key, value } => {
+                    let key = self.eval_property_name(key)?;
+                    let is_method = matches!(value, Expression::Function(definition) if definition.kind == FunctionKind::Method);
+                    let value = self.eval_named(value, &key.function_name())?;
+                    if is_method {
+                        self.set_home_object(&value, id);
+                    }
+                    self.heap.define_property(id, key, Property::data(value, Attributes::DEFAULT));
+                }
+                ObjectProperty::Getter { key, function } | ObjectProperty::Setter { key, function } => {
+                    let is_getter = matches!(property, ObjectProperty::Getter { .. });
+                    let key = self.eval_property_name(key)?;
+                    let function = self.create_function(function, self.env.clone());
+                    self.set_home_object(&function, id);
+                    let prefix = if is_getter { "get" } else { "set" };
+                    self.set_function_name(&function, &format!("{} {}", prefix, key.function_name()));
+
+                    // A getter and setter for the same key share one property
+                    let existing = self.heap.get(id).properties.get(&key);
+                    let accessor = Property::accessor(existing, is_getter, function, Attributes::DEFAULT);
+                    self.heap.define_property(id, key, accessor);
+                }
+                ObjectProperty::Spread(source) => {
+                    let source = self.eval_expression(source)?;
+                    self.copy_data_properties(id, &source, &[])?;
+                }
+            }
+        }
+        Ok(Value::Object(id))
+    }
+
+    fn eval_property_name(&mut self, name: &PropertyName) -> std::result::Result<PropertyKey, String> {
+        match name {
+            PropertyName::Literal(name) => Ok(PropertyKey::from(name.as_str())),
+            PropertyName::Computed(expr) => {
+                let key = self.eval_expression(expr)?;
+                self.coerce_to_property_key(&key)
+            }
+        }
+    }
+
+    /// Copies the own enumerable properties of `source` onto `target`, as
+    /// object spread and rest patterns do, skipping the keys in `excluded`.
+    /// Getters on `source` are invoked.
+    fn copy_data_properties(&mut self, target: ObjectId, source: &Value, excluded: &[PropertyKey]) -> std::result::Result<(), String> {
+        match source {
+            Value::Object(id) | Value::Function(id) => {
+                for key in self.own_property_keys(*id)? {
+                    if excluded.contains(&key) {
+                        continue;
+                    }
+                    let enumerable = self.get_own_property(*id, &key)?.is_some_and(|p| p.attributes.enumerable);
+                    if enumerable {
+                        let value = self.get_member(source, &key)?;
+                        self.heap.define_property(target, key, Property::data(value, Attributes::DEFAULT));
+                    }
+                }
+            }
+            Value::String(s) => {
+                for (index, unit) in s.units().iter().enumerate() {
+                    if excluded.contains(&PropertyKey::Index(index as u32)) {
+                        continue;
+                    }
+                    let value = Property::data(Value::String(JsString::from(vec![*unit])), Attributes::DEFAULT);
+                    self.heap.define_property(target, PropertyKey::Index(index as u32), value);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The function a call's callee evaluates to, with the receiver it is
+    /// called with: the object for a method call, `undefined` otherwise.
+    fn eval_callee(&mut self, callee: &Expression) -> std::result::Result<(Value, Value), String> {
+        Ok(match callee {
+            Expression::Member { object, .. } | Expression::Index { object, .. } if matches!(**object, Expression::Super) => {
+                let this = self.this_value()?;
+                let key = self.member_key(callee)?;
+                (self.get_super(&key)?, this)
+            }
+            Expression::PrivateMember { object, name } => {
+                let this = self.eval_expression(object)?;
+                let name = self.private_name(name)?;
+                (self.get_private(&this, &name)?, this)
+            }
+            Expression::Member { object, .. } | Expression::Index { object, .. } => {
+                let this = self.eval_expression(object)?;
+                let key = self.member_key(callee)?;
+                (self.get_member(&this, &key)?, this)
+            }
+            _ => (self.eval_expression(callee)?, Value::Undefined),
+        })
+    }
+
+    /// Evaluates the arguments of a call whose callee evaluated to
+    /// `function`, then calls it.
+    fn eval_call(
+        &mut self,
+        callee: &Expression,
+        function: Value,
+        this: Value,
+        arguments: &[Expression],
+        location: Location,
+    ) -> std::result::Result<Value, String> {
+        self.hold(&function);
+        let args = self.eval_arguments(arguments)?;
+        self.set_location(location);
+        if !matches!(function, Value::Function(_)) {
+            return Err(type_error(format!("{} is not a function", self.describe_callee(callee, &function))));
+        }
+        self.call_function(&function, this, args)
+    }
+
+    /// The frozen array of a template's cooked strings, with the raw
+    /// strings as its `raw` property, that a tag function receives.
+    fn template_object(&mut self, template: &Rc<TemplateLiteral>) -> std::result::Result<ObjectId, String> {
+        if let Some((_, id)) = self.template_objects.get(&Rc::as_ptr(template)) {
+            return Ok(*id);
         }
+        let prototype = Some(self.intrinsics.array_prototype);
+        let raw = template.raw.iter().map(|text| Some(Value::String(text.clone().into()))).collect();
+        let raw = self.heap.allocate_array(raw, prototype);
+        builtins::set_integrity_level(self, raw, true)?;
+        let cooked = template.cooked.iter().map(|text| Some(text.clone().map_or(Value::Undefined, Value::String))).collect();
+        let id = self.heap.allocate_array(cooked, prototype);
+        self.heap.define_property(id, PropertyKey::from("raw"), Property::data(Value::Object(raw), Attributes::FIXED));
+        builtins::set_integrity_level(self, id, true)?;
+        self.template_objects.insert(Rc::as_ptr(template), (Rc::clone(template), id));
+        Ok(id)
     }
 
-    fn eval_expression(&self, expr: Expression) -> std::result::Result<Value, String> {
+    /// Evaluates a link of an optional chain, or `None` once a `?.` has
+    /// found `null` or `undefined` before it and the rest is skipped.
+    fn eval_chain(&mut self, expr: &Expression) -> std::result::Result<Option<Value>, String> {
         match expr {
-            Expression::Number(n) => Ok(Value::Number(n)),
-            Expression::String(s) => Ok(Value::String(s)),
-            Expression::Identifier(name) => {
-                self.variables.get(&name)
-                    .cloned()
-                    .ok_or_else(|| format!("Undefined variable: {}", name))
+            Expression::Optional(base) => Ok(self.eval_chain(base)?.filter(|value| !matches!(value, Value::Null | Value::Undefined))),
+            Expression::Member { object, .. } | Expression::Index { object, .. } if !matches!(**object, Expression::Super) => {
+                let Some(target) = self.eval_chain(object)? else { return Ok(None) };
+                let key = self.member_key(expr)?;
+                self.get_member(&target, &key).map(Some)
+            }
+            Expression::PrivateMember { object, name } => {
+                let Some(target) = self.eval_chain(object)? else { return Ok(None) };
+                let name = self.private_name(name)?;
+                self.get_private(&target, &name).map(Some)
+            }
+            Expression::Call { callee, arguments, location } if !matches!(**callee, Expression::Super) => {
+                let Some((function, this)) = self.eval_chain_callee(callee)? else { return Ok(None) };
+                // `f?.()` names `f` when it is not a function
+                let described = match callee.as_ref() {
+                    Expression::Optional(base) => base,
+                    callee => callee,
+                };
+                self.eval_call(described, function, this, arguments, *location).map(Some)
+            }
+            _ => self.eval_expression(expr).map(Some),
+        }
+    }
+
+    /// `eval_callee` for the callee of a call inside an optional chain.
+    fn eval_chain_callee(&mut self, callee: &Expression) -> std::result::Result<Option<(Value, Value)>, String> {
+        match callee {
+            Expression::Optional(base) => {
+                let callee = self.eval_chain_callee(base)?;
+                Ok(callee.filter(|(function, _)| !matches!(function, Value::Null | Value::Undefined)))
+            }
+            Expression::Member { object, .. } | Expression::Index { object, .. } if matches!(**object, Expression::Super) => {
+                self.eval_callee(callee).map(Some)
+            }
+            Expression::Member { object, .. } | Expression::Index { object, .. } => {
+                let Some(this) = self.eval_chain(object)? else { return Ok(None) };
+                let key = self.member_key(callee)?;
+                Ok(Some((self.get_member(&this, &key)?, this)))
+            }
+            Expression::PrivateMember { object, name } => {
+                let Some(this) = self.eval_chain(object)? else { return Ok(None) };
+                let name = self.private_name(name)?;
+                Ok(Some((self.get_private(&this, &name)?, this)))
+            }
+            _ => Ok(self.eval_chain(callee)?.map(|function| (function, Value::Undefined))),
+        }
+    }
+
+    fn eval_arguments(&mut self, arguments: &[Expression]) -> std::result::Result<Vec<Value>, String> {
+        let mut args = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            match argument {
+                Expression::Spread(inner) => {
+                    let iterable = self.eval_expression(inner)?;
+                    args.extend(self.collect_iterable(&iterable)?);
+                }
+                argument => args.push(self.eval_expression(argument)?),
+            }
+        }
+        Ok(args)
+    }
+
+    fn eval_reference(&mut self, target: &Expression) -> std::result::Result<Reference, String> {
+        match target {
+            Expression::Identifier { name, location } => {
+                self.set_location(*location);
+                Ok(Reference::Binding(name.clone()))
+            }
+            Expression::Member { object, .. } | Expression::Index { object, .. } if matches!(**object, Expression::Super) => {
+                self.this_value()?;
+                Ok(Reference::Super(self.member_key(target)?))
+            }
+            Expression::PrivateMember { object, name } => {
+                let object = self.eval_expression(object)?;
+                Ok(Reference::Private(object, self.private_name(name)?))
+            }
+            Expression::Member { object, .. } | Expression::Index { object, .. } => {
+                let object = self.eval_expression(object)?;
+                let key = self.member_key(target)?;
+                Ok(Reference::Property(object, key))
+            }
+            _ => Err(syntax_error("Invalid assignment target")),
+        }
+    }
+
+    fn get_reference(&mut self, reference: &Reference) -> std::result::Result<Value, String> {
+        match reference {
+            Reference::Binding(name) => self.lookup(name),
+            Reference::Property(object, key) => self.get_member(object, key),
+            Reference::Private(object, name) => self.get_private(object, name),
+            Reference::Super(key) => self.get_super(key),
+        }
+    }
+
+    fn put_reference(&mut self, reference: &Reference, value: Value) -> std::result::Result<(), String> {
+        match reference {
+            Reference::Binding(name) => self.assign(name, value),
+            Reference::Property(object, key) => self.set_member(object, key.clone(), value),
+            Reference::Private(object, name) => self.set_private(object, name, value),
+            Reference::Super(key) => self.set_super(key.clone(), value),
+        }
+    }
+
+    /// Evaluates the property key of a member expression, whose object has
+    /// been evaluated, recording where the access happens.
+    fn member_key(&mut self, expr: &Expression) -> std::result::Result<PropertyKey, String> {
+        match expr {
+            Expression::Member { property, location, .. } => {
+                self.set_location(*location);
+                Ok(PropertyKey::from(property.as_str()))
+            }
+            Expression::Index { index, location, .. } => {
+                let key = self.eval_expression(index)?;
+                self.set_location(*location);
+                self.coerce_to_property_key(&key)
+            }
+            _ => unreachable!("member_key called on a non-member expression"),
+        }
+    }
+
+    fn coerce_to_property_key(&mut self, value: &Value) -> std::result::Result<PropertyKey, String> {
+        match value {
+            Value::Number(n) if *n >= 0.0 && n.fract() == 0.0 && *n < u32::MAX as f64 => Ok(PropertyKey::Index(*n as u32)),
+            Value::Symbol(symbol) => Ok(PropertyKey::Symbol(symbol.clone())),
+            other => match self.coerce_to_primitive(other.clone(), PreferredType::String)? {
+                Value::Symbol(symbol) => Ok(PropertyKey::Symbol(symbol)),
+                primitive => Ok(PropertyKey::from(self.coerce_to_string(&primitive)?)),
             },
-            Expression::Binary { left, operator, right } => {
-                let lhs = self.eval_expression(*left)?;
-                let rhs = self.eval_expression(*right)?;
-                self.eval_binary_op(lhs, operator, rhs)
+        }
+    }
+
+    /// Reads a property, looking through the prototype chain of objects.
+    /// The prototype a primitive's properties are looked up on.
+    pub(super) fn primitive_prototype(&self, value: &Value) -> Option<ObjectId> {
+        match value {
+            Value::String(_) => Some(self.intrinsics.string_prototype),
+            Value::Boolean(_) => Some(self.intrinsics.boolean_prototype),
+            Value::Number(_) => Some(self.intrinsics.number_prototype),
+            Value::Symbol(_) => Some(self.intrinsics.symbol_prototype),
+            Value::BigInt(_) => Some(self.intrinsics.bigint_prototype),
+            _ => None,
+        }
+    }
+
+    fn get_member(&mut self, target: &Value, key: &PropertyKey) -> std::result::Result<Value, String> {
+        match target {
+            Value::Object(id) | Value::Function(id) => self.get_property(*id, key, target),
+            Value::String(s) => Ok(match key {
+                PropertyKey::String(name) if name == "length" => Value::Number(s.len() as f64),
+                PropertyKey::Index(index) => match s.units().get(*index as usize) {
+                    Some(unit) => Value::String(JsString::from(vec![*unit])),
+                    None => Value::Undefined,
+                },
+                _ => self.get_property(self.intrinsics.string_prototype, key, target)?,
+            }),
+            Value::Boolean(_) => self.get_property(self.intrinsics.boolean_prototype, key, target),
+            Value::Number(_) => self.get_property(self.intrinsics.number_prototype, key, target),
+            Value::Symbol(_) => self.get_property(self.intrinsics.symbol_prototype, key, target),
+            Value::BigInt(_) => self.get_property(self.intrinsics.bigint_prototype, key, target),
+            Value::Null | Value::Undefined => Err(type_error(format!(
+                "Cannot read properties of {} (reading '{}')",
+                target.to_js_string(),
+                key
+            ))),
+        }
+    }
+
+    /// Writes a property. Assigning an array's `length` truncates or
+    /// extends it.
+    fn set_member(&mut self, target: &Value, key: PropertyKey, value: Value) -> std::result::Result<(), String> {
+        match target {
+            Value::Object(id) | Value::Function(id) => {
+                if !self.set_property(*id, &key, value, target)? {
+                    return Err(self.set_failure(*id, &key));
+                }
+                Ok(())
+            }
+            Value::Null | Value::Undefined => Err(type_error(format!(
+                "Cannot set properties of {} (setting '{}')",
+                target.to_js_string(),
+                key
+            ))),
+            // Primitives have no own properties to write to
+            _ => Ok(()),
+        }
+    }
+
+    /// The error for an assignment to `key` of `id` that was refused.
+    fn set_failure(&self, id: ObjectId, key: &PropertyKey) -> String {
+        if matches!(self.heap.get(id).kind, ObjectKind::Proxy(_)) {
+            return type_error(format!("'set' on proxy: trap returned falsish for property '{}'", key));
+        }
+        match self.heap.find_property(id, key) {
+            Some(Property { value: PropertyValue::Accessor { .. }, .. }) => {
+                type_error(format!("Cannot set property {} of {} which has only a getter", key, self.describe_object(id)))
+            }
+            // Inherited read-only properties also block assignment
+            Some(property) if !property.attributes.writable => type_error(format!("Cannot assign to read only property '{}' of {}", key, self.describe_object(id))),
+            _ => type_error(format!("Cannot add property {}, object is not extensible", key)),
+        }
+    }
+
+    /// The `delete` operator on a property.
+    fn delete_member(&mut self, target: &Value, key: &PropertyKey) -> std::result::Result<bool, String> {
+        match target {
+            Value::Object(id) | Value::Function(id) => {
+                if !self.delete_property(*id, key)? {
+                    if matches!(self.heap.get(*id).kind, ObjectKind::Proxy(_)) {
+                        return Err(type_error(format!("'deleteProperty' on proxy: trap returned falsish for property '{}'", key)));
+                    }
+                    return Err(type_error(format!("Cannot delete property '{}' of {}", key, self.describe_object(*id))));
+                }
+                Ok(true)
+            }
+            Value::Null | Value::Undefined => Err(type_error("Cannot convert undefined or null to object")),
+            _ => Ok(true),
+        }
+    }
+
+    /// How an object is named in error messages, following Node.
+    fn describe_object(&self, id: ObjectId) -> String {
+        let object = self.heap.get(id);
+        if object.is_array() {
+            "object '[object Array]'".to_string()
+        } else if object.is_callable() {
+            "function".to_string()
+        } else {
+            "object '#<Object>'".to_string()
+        }
+    }
+
+    /// Creates an array object holding `elements`.
+    fn create_array(&mut self, elements: Vec<Value>) -> Value {
+        let elements = elements.into_iter().map(Some).collect();
+        let prototype = self.intrinsics.array_prototype;
+        Value::Object(self.heap.allocate_array(elements, Some(prototype)))
+    }
+
+    /// The `ToPrimitive` abstract operation: objects are converted by calling
+    /// their `valueOf` / `toString` methods.
+    fn coerce_to_primitive(&mut self, value: Value, preferred: PreferredType) -> std::result::Result<Value, String> {
+        if !matches!(value, Value::Object(_) | Value::Function(_)) {
+            return Ok(value);
+        }
+        if let Some(exotic) = self.get_method_by_key(&value, &PropertyKey::Symbol(self.symbols.to_primitive.clone()))? {
+            let hint = match preferred {
+                PreferredType::Default => "default",
+                PreferredType::Number => "number",
+                PreferredType::String => "string",
+            };
+            let result = self.call_function(&exotic, value, vec![Value::String(hint.into())])?;
+            if matches!(result, Value::Object(_) | Value::Function(_)) {
+                return Err(type_error("Cannot convert object to primitive value"));
+            }
+            return Ok(result);
+        }
+        self.ordinary_to_primitive(value, preferred)
+    }
+
+    /// The `OrdinaryToPrimitive` abstract operation: calls `valueOf` and
+    /// `toString` in the order `preferred` asks for.
+    fn ordinary_to_primitive(&mut self, value: Value, preferred: PreferredType) -> std::result::Result<Value, String> {
+        let methods = match preferred {
+            PreferredType::String => ["toString", "valueOf"],
+            PreferredType::Number | PreferredType::Default => ["valueOf", "toString"],
+        };
+        for name in methods {
+            let method = self.get_member(&value, &PropertyKey::from(name))?;
+            if matches!(method, Value::Function(_)) {
+                let result = self.call_function(&method, value.clone(), Vec::new())?;
+                if !matches!(result, Value::Object(_) | Value::Function(_)) {
+                    return Ok(result);
+                }
+            }
+        }
+        Err(type_error("Cannot convert object to primitive value"))
+    }
+
+    /// The `ToNumber` abstract operation.
+    fn coerce_to_number(&mut self, value: &Value) -> std::result::Result<f64, String> {
+        match self.coerce_to_primitive(value.clone(), PreferredType::Number)? {
+            Value::Symbol(_) => Err(type_error("Cannot convert a Symbol value to a number")),
+            Value::BigInt(_) => Err(type_error("Cannot convert a BigInt value to a number")),
+            primitive => Ok(primitive.to_number()),
+        }
+    }
+
+    /// The `ToNumeric` abstract operation: `ToNumber`, except that BigInts
+    /// stay BigInts.
+    fn coerce_to_numeric(&mut self, value: &Value) -> std::result::Result<Value, String> {
+        match self.coerce_to_primitive(value.clone(), PreferredType::Number)? {
+            Value::BigInt(n) => Ok(Value::BigInt(n)),
+            primitive => self.coerce_to_number(&primitive).map(Value::Number),
+        }
+    }
+
+    /// The `ToBigInt` abstract operation.
+    fn coerce_to_bigint(&mut self, value: &Value) -> std::result::Result<BigInt, String> {
+        match self.coerce_to_primitive(value.clone(), PreferredType::Number)? {
+            Value::BigInt(n) => Ok(n),
+            Value::Boolean(b) => Ok(BigInt::from(b as u8)),
+            Value::String(s) => bigint::string_to_bigint(&s.to_string()).ok_or_else(|| syntax_error(format!("Cannot convert {} to a BigInt", s))),
+            primitive => Err(type_error(format!("Cannot convert {} to a BigInt", primitive.to_js_string()))),
+        }
+    }
+
+    /// The `ToString` abstract operation.
+    fn coerce_to_string(&mut self, value: &Value) -> std::result::Result<JsString, String> {
+        match self.coerce_to_primitive(value.clone(), PreferredType::String)? {
+            Value::Symbol(_) => Err(type_error("Cannot convert a Symbol value to a string")),
+            primitive => Ok(primitive.to_utf16()),
+        }
+    }
+
+    /// A best-effort description of a value for error messages, which must
+    /// not run user code.
+    fn to_display_string(&self, value: &Value) -> String {
+        match value {
+            Value::String(s) => format!("\"{}\"", s),
+            Value::Object(id) if self.heap.get(*id).is_array() => "array".to_string(),
+            Value::Object(_) => "object".to_string(),
+            other => other.to_js_string(),
+        }
+    }
+
+    /// Names the callee of a failed call or `new`, falling back to the value
+    /// it evaluated to, as in `1 is not a function`.
+    fn describe_callee(&self, callee: &Expression, value: &Value) -> String {
+        callee_name(callee).unwrap_or_else(|| self.to_display_string(value))
+    }
+
+    /// The `IsLooselyEqual` abstract operation, converting objects compared
+    /// against primitives.
+    fn loose_equals(&mut self, left: Value, right: Value) -> std::result::Result<bool, String> {
+        let is_object = |v: &Value| matches!(v, Value::Object(_) | Value::Function(_));
+        let is_nullish = |v: &Value| matches!(v, Value::Null | Value::Undefined);
+        match (is_object(&left), is_object(&right)) {
+            (true, false) if !is_nullish(&right) => {
+                let left = self.coerce_to_primitive(left, PreferredType::Default)?;
+                Ok(loose_equals(&left, &right))
+            }
+            (false, true) if !is_nullish(&left) => {
+                let right = self.coerce_to_primitive(right, PreferredType::Default)?;
+                Ok(loose_equals(&left, &right))
+            }
+            _ => Ok(loose_equals(&left, &right)),
+        }
+    }
+
+    /// Creates a function object for `definition` closing over `env`.
+    fn create_function(&mut self, definition: &Rc<FunctionDefinition>, env: EnvRef) -> Value {
+        let length = definition.params.iter().take_while(|p| p.default.is_none() && !p.rest).count();
+        let closure = Closure { definition: Rc::clone(definition), env, home_object: None, class: None };
+        let prototype = if definition.is_generator && definition.is_async {
+            Some(self.intrinsics.async_generator_function_prototype)
+        } else if definition.is_generator {
+            Some(self.intrinsics.generator_function_prototype)
+        } else if definition.is_async {
+            Some(self.intrinsics.async_function_prototype)
+        } else {
+            Some(self.intrinsics.function_prototype)
+        };
+        let id = self.heap.allocate(Object::new(ObjectKind::Function(closure), prototype));
+        let name = definition.name.clone().unwrap_or_default();
+        self.heap.define_property(id, PropertyKey::from("length"), Property::data(Value::Number(length as f64), Attributes::READ_ONLY));
+        self.heap.define_property(id, PropertyKey::from("name"), Property::data(Value::String(name.into()), Attributes::READ_ONLY));
+
+        // Every ordinary function may be used as a constructor, so it gets a
+        // `prototype` object for its instances. Classes set up their own.
+        // Generator functions are not constructors, but their `prototype`
+        // is what the generators they return inherit from. Async functions
+        // have none.
+        if definition.is_generator {
+            let generator_prototype = if definition.is_async {
+                Some(self.intrinsics.async_generator_prototype)
+            } else {
+                Some(self.intrinsics.generator_prototype)
+            };
+            let instance_prototype = self.heap.allocate(Object::new(ObjectKind::Ordinary, generator_prototype));
+            let attributes = Attributes { writable: true, enumerable: false, configurable: false };
+            self.heap.define_property(id, PropertyKey::from("prototype"), Property::data(Value::Object(instance_prototype), attributes));
+        } else if definition.kind == FunctionKind::Normal && !definition.is_async {
+            let instance_prototype = self.create_object();
+            let constructor = Property::data(Value::Function(id), Attributes::HIDDEN);
+            self.heap.define_property(instance_prototype, PropertyKey::from("constructor"), constructor);
+            let attributes = Attributes { writable: true, enumerable: false, configurable: false };
+            self.heap.define_property(id, PropertyKey::from("prototype"), Property::data(Value::Object(instance_prototype), attributes));
+        }
+        Value::Function(id)
+    }
+
+    /// The value referring to the object `id`, which is a function value
+    /// for callable objects.
+    fn object_value(&self, id: ObjectId) -> Value {
+        if self.heap.get(id).is_callable() {
+            Value::Function(id)
+        } else {
+            Value::Object(id)
+        }
+    }
+
+    /// Changes the prototype of an object, as `Object.setPrototypeOf` does.
+    fn set_prototype(&mut self, id: ObjectId, prototype: Option<ObjectId>) -> std::result::Result<(), String> {
+        if self.set_prototype_of(id, prototype)? {
+            return Ok(());
+        }
+        let object = self.heap.get(id);
+        Err(type_error(if matches!(object.kind, ObjectKind::Proxy(_)) {
+            "'setPrototypeOf' on proxy: trap returned falsish"
+        } else if !object.extensible {
+            "#<Object> is not extensible"
+        } else {
+            "Cyclic __proto__ value"
+        }))
+    }
+
+    /// Creates an empty object inheriting from `Object.prototype`.
+    fn create_object(&mut self) -> ObjectId {
+        let prototype = Some(self.intrinsics.object_prototype);
+        self.heap.allocate(Object::new(ObjectKind::Ordinary, prototype))
+    }
+
+    /// Creates a wrapper object for a primitive, as `new Number(1)` and
+    /// `Object("ab")` do. String wrappers expose their characters as
+    /// read-only index properties.
+    fn create_primitive_wrapper(&mut self, value: Value, prototype: ObjectId) -> ObjectId {
+        let units = match &value {
+            Value::String(s) => s.units().to_vec(),
+            _ => Vec::new(),
+        };
+        let is_string = matches!(value, Value::String(_));
+        let id = self.heap.allocate(Object::new(ObjectKind::Primitive(value), Some(prototype)));
+        if is_string {
+            let attributes = Attributes { writable: false, enumerable: true, configurable: false };
+            for (index, unit) in units.iter().enumerate() {
+                let character = Value::String(JsString::from(vec![*unit]));
+                self.heap.define_property(id, PropertyKey::Index(index as u32), Property::data(character, attributes));
             }
+            let length = Property::data(Value::Number(units.len() as f64), Attributes::FIXED);
+            self.heap.define_property(id, PropertyKey::from("length"), length);
+        }
+        id
+    }
+
+    /// The `ToObject` operation: objects as they are, primitives in a
+    /// wrapper object, and a `TypeError` for `null` and `undefined`.
+    pub(super) fn coerce_to_object(&mut self, value: &Value) -> std::result::Result<ObjectId, String> {
+        match value {
+            Value::Object(id) | Value::Function(id) => Ok(*id),
+            Value::Null | Value::Undefined => Err(type_error("Cannot convert undefined or null to object")),
+            primitive => {
+                let prototype = self.primitive_prototype(primitive).expect("primitives have a prototype");
+                Ok(self.create_primitive_wrapper(primitive.clone(), prototype))
+            }
+        }
+    }
+
+    /// Creates a function object implemented by `function`.
+    fn create_native_function(&mut self, name: &str, length: u32, function: NativeFunction) -> Value {
+        let prototype = Some(self.intrinsics.function_prototype);
+        let id = self.heap.allocate(Object::new(ObjectKind::NativeFunction(function), prototype));
+        self.heap.define_property(id, PropertyKey::from("length"), Property::data(Value::Number(length as f64), Attributes::READ_ONLY));
+        self.heap.define_property(id, PropertyKey::from("name"), Property::data(Value::String(name.into()), Attributes::READ_ONLY));
+        Value::Function(id)
+    }
+
+    /// Calls a function value with the given receiver and arguments.
+    fn call_function(&mut self, function: &Value, this: Value, args: Vec<Value>) -> std::result::Result<Value, String> {
+        let Value::Function(id) = function else {
+            return Err(type_error(format!("{} is not a function", self.to_display_string(function))));
+        };
+        match &self.heap.get(*id).kind {
+            ObjectKind::Function(closure) if closure.definition.is_class_constructor() => {
+                let name = closure.definition.name.clone().unwrap_or_default();
+                Err(type_error(format!("Class constructor {} cannot be invoked without 'new'", name)))
+            }
+            ObjectKind::Function(closure) => {
+                let context = FunctionContext { this: Some(this), callee: Some(*id), home_object: closure.home_object, new_target: Value::Undefined };
+                Ok(self.call_closure(*id, context, args)?.0)
+            }
+            ObjectKind::NativeFunction(native) => {
+                let native = *native;
+                self.call_native(native, this, args, None)
+            }
+            ObjectKind::BoundFunction(bound) => {
+                let target = bound.target.clone();
+                let this = bound.this.clone();
+                let args = bound.args.iter().cloned().chain(args).collect();
+                self.call_function(&target, this, args)
+            }
+            ObjectKind::PromiseFunction(function) => {
+                let function = function.clone();
+                self.call_promise_function(function, &args)
+            }
+            ObjectKind::Proxy(_) => self.call_proxy(*id, this, args),
+            ObjectKind::RevokeProxy(_) => {
+                self.revoke_proxy(*id);
+                Ok(Value::Undefined)
+            }
+            _ => Err(type_error("object is not a function")),
+        }
+    }
+
+    /// Runs a native function, with `new_target` set while it runs.
+    fn call_native(
+        &mut self,
+        native: NativeFunction,
+        this: Value,
+        args: Vec<Value>,
+        new_target: Option<Value>,
+    ) -> std::result::Result<Value, String> {
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(range_error("Maximum call stack size exceeded"));
+        }
+        self.call_depth += 1;
+        // What the call holds, its receiver and arguments included, is
+        // released when it returns, except for its result
+        let mark = self.heap.temporaries.len();
+        self.hold(&this);
+        for arg in &args {
+            self.hold(arg);
+        }
+        let saved = std::mem::replace(&mut self.new_target, new_target);
+        let result = self.with_stack(|interpreter| native(interpreter, &this, &args));
+        self.new_target = saved;
+        self.heap.temporaries.truncate(mark);
+        if let Ok(value) = &result {
+            self.hold(value);
+        }
+        self.call_depth -= 1;
+        result
+    }
+
+    /// Runs the body of the closure `id` in a new scope, returning its
+    /// result and the scope, whose `this` constructors need afterwards.
+    fn call_closure(
+        &mut self,
+        id: ObjectId,
+        context: FunctionContext,
+        args: Vec<Value>,
+    ) -> std::result::Result<(Value, EnvRef), String> {
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(range_error("Maximum call stack size exceeded"));
+        }
+        let ObjectKind::Function(closure) = &self.heap.get(id).kind else {
+            unreachable!("call_closure called on a non-closure")
+        };
+        let definition = Rc::clone(&closure.definition);
+        let function = self.function_name(id);
+        let constructing = context.new_target != Value::Undefined;
+
+        // Arrow functions have no `this` of their own and see their
+        // defining scope's instead
+        let scope = if definition.is_arrow() {
+            Environment::new_child(&closure.env, ScopeKind::Function)
+        } else {
+            Environment::new_function(&closure.env, context)
+        };
+        self.call_depth += 1;
+        self.frames.push(StackFrame { function, constructing, location: None });
+        // What the call holds, its arguments included, is released when it
+        // returns, except for its result
+        let mark = self.heap.temporaries.len();
+        for arg in &args {
+            self.hold(arg);
+        }
+        let result = self.with_stack(|interpreter| {
+            interpreter.in_scope(Rc::clone(&scope), |interpreter| {
+                interpreter.maybe_collect_garbage(&[]);
+                interpreter.exec_function_body(&definition, args)
+            })
+        });
+        self.heap.temporaries.truncate(mark);
+        match &result {
+            Ok(value) => self.hold(value),
+            Err(message) => self.materialize_exception(message),
+        }
+        self.frames.pop();
+        self.call_depth -= 1;
+        Ok((result?, scope))
+    }
+
+    /// The `name` of the function `id`, or an empty string if it has none.
+    fn function_name(&self, id: ObjectId) -> String {
+        match self.heap.get(id).properties.get(&PropertyKey::from("name")) {
+            Some(Property { value: PropertyValue::Data(Value::String(name)), .. }) => name.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Binds parameters and runs a function body in the current (function)
+    /// scope. Generator functions return a generator that runs the body,
+    /// and async functions a promise for its result.
+    fn exec_function_body(&mut self, definition: &Rc<FunctionDefinition>, args: Vec<Value>) -> std::result::Result<Value, String> {
+        if definition.is_async && !definition.is_generator {
+            return Ok(self.call_async(definition, args));
+        }
+        self.bind_parameters(definition, args)?;
+        if definition.is_generator {
+            return self.create_generator(definition);
+        }
+        match self.exec_block(&definition.body)? {
+            Completion::Return(val) => Ok(val),
+            _ => Ok(Value::Undefined),
+        }
+    }
+
+    /// Sets up the scope of a call: `arguments`, the parameters and the
+    /// `var` declarations of the body.
+    fn bind_parameters(&mut self, definition: &FunctionDefinition, args: Vec<Value>) -> std::result::Result<(), String> {
+        if definition.uses_arguments {
+            let prototype = Some(self.intrinsics.object_prototype);
+            let arguments = self.heap.allocate(Object::new(ObjectKind::Arguments, prototype));
+            let length = Property::data(Value::Number(args.len() as f64), Attributes::HIDDEN);
+            self.heap.define_property(arguments, PropertyKey::from("length"), length);
+            for (index, arg) in args.iter().enumerate() {
+                self.heap.set_property(arguments, PropertyKey::Index(index as u32), arg.clone());
+            }
+            let values = Property::data(Value::Function(self.intrinsics.array_values), Attributes::HIDDEN);
+            let key = PropertyKey::Symbol(self.symbols.iterator.clone());
+            self.heap.define_property(arguments, key, values);
+            self.env.borrow_mut().insert("arguments".to_string(), Binding {
+                value: Some(Value::Object(arguments)),
+                kind: VariableKind::Var,
+            });
+        }
+
+        let mut args = args.into_iter();
+        for param in &definition.params {
+            let value = if param.rest {
+                self.create_array(args.by_ref().collect())
+            } else {
+                args.next().unwrap_or(Value::Undefined)
+            };
+            self.bind_element(&param.target, param.default.as_ref(), value, Some(VariableKind::Var))?;
+        }
+
+        // Fields are set up before a base class constructor body runs;
+        // derived classes do it when `super()` returns
+        if definition.kind == FunctionKind::BaseConstructor {
+            let context = Environment::function_context(&self.env).expect("constructor without a function scope");
+            let this = context.this.expect("base constructor without this");
+            self.initialize_instance(&this, context.callee.expect("constructor without callee"))?;
+        }
+
+        // With parameter expressions, the body's `var`s get a scope of their
+        // own so closures in the parameters keep seeing the parameters;
+        // each starts with the value of the parameter it shadows
+        let has_expressions = definition.params.iter().any(|param| param.default.is_some() || !matches!(param.target, Pattern::Identifier(_)));
+        if !has_expressions {
+            return self.hoist_var_declarations(&definition.body);
+        }
+        let parameters = Rc::clone(&self.env);
+        self.env = Environment::new_child(&parameters, ScopeKind::Function);
+        let mut names = Vec::new();
+        collect_var_names(&definition.body, &mut names);
+        for name in names {
+            let value = parameters.borrow().get_own(&name).and_then(|binding| binding.value.clone()).unwrap_or(Value::Undefined);
+            self.env.borrow_mut().insert(name, Binding { value: Some(value), kind: VariableKind::Var });
         }
+        Ok(())
     }
 
-    fn eval_binary_op(&self, left: Value, op: Token, right: Value) -> std::result::Result<Value, String> {
+    fn eval_binary_op(&mut self, left: Value, op: &Token, right: Value) -> std::result::Result<Value, String> {
+        match op {
+            Token::EqualEqualEqual => return Ok(Value::Boolean(strict_equals(&left, &right))),
+            Token::BangEqualEqual => return Ok(Value::Boolean(!strict_equals(&left, &right))),
+            Token::EqualEqual => return Ok(Value::Boolean(self.loose_equals(left, right)?)),
+            Token::BangEqual => return Ok(Value::Boolean(!self.loose_equals(left, right)?)),
+            Token::In => {
+                let (Value::Object(id) | Value::Function(id)) = right else {
+                    return Err(type_error(format!(
+                        "Cannot use 'in' operator to search for '{}' in {}",
+                        self.coerce_to_string(&left)?,
+                        right.to_js_string()
+                    )));
+                };
+                let key = self.coerce_to_property_key(&left)?;
+                return Ok(Value::Boolean(self.has_property(id, &key)?));
+            }
+            Token::Instanceof => return Ok(Value::Boolean(self.instance_of(&left, &right)?)),
+            _ => {}
+        }
+
+        let preferred = if *op == Token::Plus { PreferredType::Default } else { PreferredType::Number };
+        let left = self.coerce_to_primitive(left, preferred)?;
+        let right = self.coerce_to_primitive(right, preferred)?;
+        if matches!(left, Value::Symbol(_)) || matches!(right, Value::Symbol(_)) {
+            let concatenating = *op == Token::Plus && (matches!(left, Value::String(_)) || matches!(right, Value::String(_)));
+            let target = if concatenating { "string" } else { "number" };
+            return Err(type_error(format!("Cannot convert a Symbol value to a {}", target)));
+        }
+
         match (left, op, right) {
-            (Value::Number(l), Token::Plus, Value::Number(r)) => Ok(Value::Number(l + r)),
-            (Value::Number(l), Token::Minus, Value::Number(r)) => Ok(Value::Number(l - r)),
-            (Value::Number(l), Token::Star, Value::Number(r)) => Ok(Value::Number(l * r)),
-            (Value::Number(l), Token::Slash, Value::Number(r)) => Ok(Value::Number(l / r)),
-            (Value::String(l), Token::Plus, Value::String(r)) => Ok(Value::String(l + &r)),
-            _ => Err(String::from("Invalid operation")),
+            (left, Token::LAngle, right) => Ok(Value::Boolean(less_than(&left, &right) == Some(true))),
+            (left, Token::RAngle, right) => Ok(Value::Boolean(less_than(&right, &left) == Some(true))),
+            (left, Token::LessEqual, right) => Ok(Value::Boolean(less_than(&right, &left) == Some(false))),
+            (left, Token::GreaterEqual, right) => Ok(Value::Boolean(less_than(&left, &right) == Some(false))),
+            (l, Token::Plus, r) if matches!(l, Value::String(_)) || matches!(r, Value::String(_)) => {
+                Ok(Value::String(JsString::concat(&l.to_utf16(), &r.to_utf16())))
+            }
+            (Value::BigInt(l), op, Value::BigInt(r)) => bigint::binary_op(&l, op, &r).map(Value::BigInt),
+            (Value::BigInt(_), _, _) | (_, _, Value::BigInt(_)) => Err(type_error("Cannot mix BigInt and other types, use explicit conversions")),
+            (l, op, r) => {
+                let (l, r) = (l.to_number(), r.to_number());
+                let result = match op {
+                    Token::Plus => l + r,
+                    Token::Minus => l - r,
+                    Token::Star => l * r,
+                    Token::Slash => l / r,
+                    Token::Percent => l % r,
+                    Token::StarStar => pow(l, r),
+                    Token::Ampersand => (to_int32(l) & to_int32(r)) as f64,
+                    Token::Pipe => (to_int32(l) | to_int32(r)) as f64,
+                    Token::Caret => (to_int32(l) ^ to_int32(r)) as f64,
+                    Token::ShiftLeft => to_int32(l).wrapping_shl(to_uint32(r) & 31) as f64,
+                    Token::ShiftRight => to_int32(l).wrapping_shr(to_uint32(r) & 31) as f64,
+                    Token::UnsignedShiftRight => to_uint32(l).wrapping_shr(to_uint32(r) & 31) as f64,
+                    _ => return Err(String::from("Invalid operation")),
+                };
+                Ok(Value::Number(result))
+            }
         }
     }
 
+    /// The `instanceof` operator, which asks `constructor` through its
+    /// `Symbol.hasInstance` method.
+    fn instance_of(&mut self, value: &Value, constructor: &Value) -> std::result::Result<bool, String> {
+        if !matches!(constructor, Value::Object(_) | Value::Function(_)) {
+            return Err(type_error("Right-hand side of 'instanceof' is not an object"));
+        }
+        if let Some(handler) = self.get_method_by_key(constructor, &PropertyKey::Symbol(self.symbols.has_instance.clone()))? {
+            return Ok(self.call_function(&handler, constructor.clone(), vec![value.clone()])?.is_truthy());
+        }
+        if !matches!(constructor, Value::Function(_)) {
+            return Err(type_error("Right-hand side of 'instanceof' is not callable"));
+        }
+        self.ordinary_has_instance(value, constructor)
+    }
+
+    /// The `OrdinaryHasInstance` operation: whether `constructor.prototype`
+    /// is on the prototype chain of `value`.
+    fn ordinary_has_instance(&mut self, value: &Value, constructor: &Value) -> std::result::Result<bool, String> {
+        let Value::Function(id) = constructor else {
+            return Ok(false);
+        };
+        if let ObjectKind::BoundFunction(bound) = &self.heap.get(*id).kind {
+            let target = bound.target.clone();
+            return self.instance_of(value, &target);
+        }
+        let (Value::Object(object) | Value::Function(object)) = value else {
+            return Ok(false);
+        };
+        let prototype = match self.get_member(constructor, &PropertyKey::from("prototype"))? {
+            Value::Object(prototype) | Value::Function(prototype) => prototype,
+            other => {
+                return Err(type_error(format!(
+                    "Function has non-object prototype '{}' in instanceof check",
+                    other.to_js_string()
+                )))
+            }
+        };
+        let mut current = self.get_prototype_of(*object)?;
+        while let Some(id) = current {
+            if id == prototype {
+                return Ok(true);
+            }
+            current = self.get_prototype_of(id)?;
+        }
+        Ok(false)
+    }
+
+    /// Sends the output of `console.log`, `info`, `debug`, `dir` and the
+    /// other informational methods to `sink` instead of standard output.
+    pub fn set_stdout(&mut self, sink: Box<dyn Write>) {
+        self.console.stdout = sink;
+    }
+
+    /// Sends the output of `console.error`, `warn` and `assert` to `sink`
+    /// instead of standard error.
+    pub fn set_stderr(&mut self, sink: Box<dyn Write>) {
+        self.console.stderr = sink;
+    }
+
+    /// Reseeds `Math.random`, making the numbers it returns repeatable.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.random = builtins::Random::new(seed);
+    }
+
     pub fn get_last_value(&self) -> Option<&Value> {
         self.last_value.as_ref()
     }
 
-    pub fn get_variables(&self) -> &HashMap<String, Value> {
-        &self.variables
+    /// Declares a global variable holding `value`, as a top-level `var`
+    /// would, replacing any variable of that name. This is how the host
+    /// hands values such as `create_array_buffer` results to scripts.
+    /// Fails if `value` is an object that has been garbage collected.
+    pub fn set_variable(&mut self, name: &str, value: Value) -> std::result::Result<(), String> {
+        if let Value::Object(id) | Value::Function(id) = value {
+            if self.heap.try_get(id).is_none() {
+                return Err(type_error("Cannot use an object that has been garbage collected"));
+            }
+        }
+        self.global.borrow_mut().insert(name.to_string(), Binding { value: Some(value), kind: VariableKind::Var });
+        Ok(())
     }
-}
\ No newline at end of file
+
+    /// Returns the initialized variables of the global scope and their
+    /// current values: those the scripts and the host defined, not the
+    /// built-in globals.
+    pub fn get_variables(&self) -> HashMap<String, Value> {
+        self.global
+            .borrow()
+            .bindings()
+            .iter()
+            .filter_map(|(name, binding)| Some((name.clone(), binding.value.clone()?)))
+            .collect()
+    }
+}
+
+/// Collects the names declared with `var` anywhere in `body`, descending
+/// into nested statements but not into nested functions.
+fn collect_var_names(body: &[Statement], names: &mut Vec<String>) {
+    for stmt in body {
+        match stmt {
+            Statement::VariableDeclaration { kind: VariableKind::Var, declarations } => {
+                names.extend(declarations.iter().flat_map(|d| d.target.bound_names()).cloned());
+            }
+            Statement::Block(inner) => collect_var_names(inner, names),
+            Statement::If { consequent, alternate, .. } => {
+                collect_var_names(std::slice::from_ref(consequent), names);
+                if let Some(alternate) = alternate {
+                    collect_var_names(std::slice::from_ref(alternate), names);
+                }
+            }
+            Statement::While { body, .. } | Statement::DoWhile { body, .. } | Statement::Labeled { body, .. } => {
+                collect_var_names(std::slice::from_ref(body), names);
+            }
+            Statement::For { init, body, .. } => {
+                if let Some(init) = init {
+                    collect_var_names(std::slice::from_ref(init), names);
+                }
+                collect_var_names(std::slice::from_ref(body), names);
+            }
+            Statement::ForIn { left, body, .. } | Statement::ForOf { left, body, .. } => {
+                if let ForBinding::Declaration { kind: VariableKind::Var, target } = left {
+                    names.extend(target.bound_names().into_iter().cloned());
+                }
+                collect_var_names(std::slice::from_ref(body), names);
+            }
+            Statement::Switch { cases, .. } => {
+                for case in cases {
+                    collect_var_names(&case.consequent, names);
+                }
+            }
+            Statement::Try { block, handler, finalizer } => {
+                collect_var_names(block, names);
+                if let Some(handler) = handler {
+                    collect_var_names(&handler.body, names);
+                }
+                if let Some(finalizer) = finalizer {
+                    collect_var_names(finalizer, names);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Maps a compound assignment operator to the binary operator it applies.
+fn compound_operator(op: &Token) -> Option<Token> {
+    Some(match op {
+        Token::PlusEqual => Token::Plus,
+        Token::MinusEqual => Token::Minus,
+        Token::StarEqual => Token::Star,
+        Token::SlashEqual => Token::Slash,
+        Token::PercentEqual => Token::Percent,
+        Token::StarStarEqual => Token::StarStar,
+        Token::AmpersandEqual => Token::Ampersand,
+        Token::PipeEqual => Token::Pipe,
+        Token::CaretEqual => Token::Caret,
+        Token::ShiftLeftEqual => Token::ShiftLeft,
+        Token::ShiftRightEqual => Token::ShiftRight,
+        Token::UnsignedShiftRightEqual => Token::UnsignedShiftRight,
+        _ => return None,
+    })
+}
+
+/// Names a call target in "is not a function" errors, e.g. `obj.method`.
+/// Names a callee expression for error messages, if it has a name.
+fn callee_name(callee: &Expression) -> Option<String> {
+    let object_name = |object: &Expression| callee_name(object).unwrap_or_else(|| "(intermediate value)".to_string());
+    Some(match callee {
+        Expression::Identifier { name, .. } => name.clone(),
+        Expression::This => "this".to_string(),
+        Expression::Member { object, property, .. } => format!("{}.{}", object_name(object), property),
+        Expression::Index { object, .. } => format!("{}[...]", object_name(object)),
+        Expression::PrivateMember { object, name } => format!("{}.#{}", object_name(object), name),
+        Expression::Super => "super".to_string(),
+        Expression::Number(n) => number_to_string(*n),
+        Expression::String(s) => format!("\"{}\"", s),
+        Expression::Optional(base) => format!("{}?", object_name(base)),
+        Expression::Call { callee, .. } => format!("{}(...)", object_name(callee)),
+        _ => return None,
+    })
+}
+
+fn range_error(message: impl Into<String>) -> String {
+    format!("RangeError: {}", message.into())
+}
+
+fn reference_error(message: impl Into<String>) -> String {
+    format!("ReferenceError: {}", message.into())
+}
+
+fn type_error(message: impl Into<String>) -> String {
+    format!("TypeError: {}", message.into())
+}
+
+fn syntax_error(message: impl Into<String>) -> String {
+    format!("SyntaxError: {}", message.into())
+}
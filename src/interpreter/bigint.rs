@@ -0,0 +1,138 @@
+//! BigInt arithmetic and the conversions between BigInts and other
+//! primitives.
+
+use std::cmp::Ordering;
+
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, One, Pow, Signed, ToPrimitive, Zero};
+
+use super::{range_error, type_error};
+use crate::lexer::Token;
+
+/// The most bits a BigInt may have, as in V8.
+const MAX_BITS: u64 = 1 << 30;
+
+fn too_big() -> String {
+    range_error("Maximum BigInt size exceeded")
+}
+
+/// Applies a binary operator to two BigInts.
+pub(super) fn binary_op(left: &BigInt, op: &Token, right: &BigInt) -> Result<BigInt, String> {
+    Ok(match op {
+        Token::Plus => left + right,
+        Token::Minus => left - right,
+        Token::Star => left * right,
+        // Both truncate towards zero, like Rust's operators
+        Token::Slash | Token::Percent if right.is_zero() => return Err(range_error("Division by zero")),
+        Token::Slash => left / right,
+        Token::Percent => left % right,
+        Token::StarStar => return exponentiate(left, right),
+        Token::Ampersand => left & right,
+        Token::Pipe => left | right,
+        Token::Caret => left ^ right,
+        Token::ShiftLeft => return shift_left(left, right),
+        Token::ShiftRight => return shift_left(left, &-right),
+        Token::UnsignedShiftRight => return Err(type_error("BigInts have no unsigned right shift, use >> instead")),
+        _ => return Err(String::from("Invalid operation")),
+    })
+}
+
+fn exponentiate(base: &BigInt, exponent: &BigInt) -> Result<BigInt, String> {
+    if exponent.is_negative() {
+        return Err(range_error("Exponent must be non-negative"));
+    }
+    if base.is_zero() || base.is_one() || exponent.is_zero() {
+        return Ok(if exponent.is_zero() { BigInt::one() } else { base.clone() });
+    }
+    if base == &BigInt::from(-1) {
+        return Ok(if exponent.bit(0) { base.clone() } else { BigInt::one() });
+    }
+    match exponent.to_u32() {
+        Some(exponent) if (base.bits() - 1) * exponent as u64 <= MAX_BITS => Ok(Pow::pow(base, exponent)),
+        _ => Err(too_big()),
+    }
+}
+
+/// `left << shift`, shifting right for a negative `shift`. Right shifts
+/// round towards negative infinity.
+fn shift_left(left: &BigInt, shift: &BigInt) -> Result<BigInt, String> {
+    if left.is_zero() {
+        return Ok(BigInt::zero());
+    }
+    if shift.is_negative() {
+        return Ok(match (-shift).to_u64() {
+            Some(bits) if bits <= left.bits() => left >> bits,
+            _ if left.is_negative() => BigInt::from(-1),
+            _ => BigInt::zero(),
+        });
+    }
+    match shift.to_u64() {
+        Some(bits) if left.bits() + bits <= MAX_BITS => Ok(left << bits),
+        _ => Err(too_big()),
+    }
+}
+
+/// `BigInt.asUintN(bits, n)`: `n` modulo `2 ** bits`.
+pub(crate) fn as_uint_n(bits: u64, n: &BigInt) -> Result<BigInt, String> {
+    if bits > MAX_BITS {
+        return Err(too_big());
+    }
+    let modulus = BigInt::one() << bits;
+    Ok(((n % &modulus) + &modulus) % modulus)
+}
+
+/// `BigInt.asIntN(bits, n)`: `n` wrapped into a two's complement integer of
+/// `bits` bits.
+pub(crate) fn as_int_n(bits: u64, n: &BigInt) -> Result<BigInt, String> {
+    if bits == 0 {
+        return Ok(BigInt::zero());
+    }
+    let unsigned = as_uint_n(bits, n)?;
+    if unsigned.bit(bits - 1) {
+        Ok(unsigned - (BigInt::one() << bits))
+    } else {
+        Ok(unsigned)
+    }
+}
+
+/// The `NumberToBigInt` abstract operation.
+pub(crate) fn number_to_bigint(n: f64) -> Result<BigInt, String> {
+    if n.fract() != 0.0 || !n.is_finite() {
+        return Err(range_error(format!("The number {} cannot be converted to a BigInt because it is not an integer", super::number_to_string(n))));
+    }
+    Ok(BigInt::from_f64(n).expect("finite integer"))
+}
+
+/// `StringToBigInt`: like `StringToNumber`, but only integers without an
+/// exponent are accepted, and only decimal ones may have a sign. `None`
+/// for anything else.
+pub(crate) fn string_to_bigint(s: &str) -> Option<BigInt> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Some(BigInt::zero());
+    }
+    let (radix, digits) = match s.get(..2) {
+        Some("0x") | Some("0X") => (16, &s[2..]),
+        Some("0o") | Some("0O") => (8, &s[2..]),
+        Some("0b") | Some("0B") => (2, &s[2..]),
+        _ => (10, s),
+    };
+    let unsigned = if radix == 10 { digits.strip_prefix(['+', '-']).unwrap_or(digits) } else { digits };
+    if unsigned.is_empty() || !unsigned.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    BigInt::parse_bytes(digits.as_bytes(), radix)
+}
+
+/// Compares a BigInt with a number exactly, `None` if the number is NaN.
+pub(crate) fn compare_with_number(b: &BigInt, n: f64) -> Option<Ordering> {
+    if n.is_nan() {
+        return None;
+    }
+    if n.is_infinite() {
+        return Some(if n > 0.0 { Ordering::Less } else { Ordering::Greater });
+    }
+    let integer = n.trunc();
+    let order = b.cmp(&BigInt::from_f64(integer).expect("finite integer"));
+    Some(order.then(0.0.partial_cmp(&(n - integer)).expect("finite fraction")))
+}
@@ -0,0 +1,902 @@
+//! `Array`, its static methods and `Array.prototype`.
+//!
+//! The prototype methods are generic: they work on any object with a
+//! `length` and index properties, reading and writing through the normal
+//! property accessors so that holes and user-defined objects behave as in
+//! JavaScript.
+
+use std::cmp::Ordering;
+
+use super::string::MAX_STRING_LENGTH;
+use super::{arg, define_alias, define_global, define_method, relative_index, to_integer};
+use crate::interpreter::collection::IterationKind;
+use crate::interpreter::object::{Attributes, NativeFunction, ObjectId, Property, PropertyDescriptor, PropertyKey};
+use crate::interpreter::value::{same_value_zero, strict_equals};
+use crate::interpreter::{range_error, type_error, Interpreter, JsString, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.array_prototype;
+    let constructor_id = interpreter.intrinsics.array_constructor;
+    let constructor = Value::Function(constructor_id);
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("length"), Property::data(Value::Number(1.0), Attributes::READ_ONLY));
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("name"), Property::data(Value::String("Array".into()), Attributes::READ_ONLY));
+    super::link_constructor(interpreter, &constructor, prototype);
+    define_method(interpreter, constructor_id, "isArray", 1, is_array);
+    define_method(interpreter, constructor_id, "from", 1, from);
+    define_method(interpreter, constructor_id, "of", 0, of);
+    // `Array[Symbol.species]` returns the constructor it is looked up on,
+    // so the copying methods of subclasses make instances of the subclass
+    let species = interpreter.create_native_function("get [Symbol.species]", 0, |_, this, _| Ok(this.clone()));
+    let key = PropertyKey::Symbol(interpreter.symbols.species.clone());
+    interpreter.heap.define_property(constructor_id, key, Property::accessor(None, true, species, Attributes::HIDDEN));
+
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("at", 1, at),
+        ("concat", 1, concat),
+        ("copyWithin", 2, copy_within),
+        ("entries", 0, |i, this, _| iterator(i, this, "entries", IterationKind::Entries)),
+        ("every", 1, every),
+        ("fill", 1, fill),
+        ("filter", 1, filter),
+        ("find", 1, find),
+        ("findIndex", 1, find_index),
+        ("findLast", 1, find_last),
+        ("findLastIndex", 1, find_last_index),
+        ("flat", 0, flat),
+        ("flatMap", 1, flat_map),
+        ("forEach", 1, for_each),
+        ("includes", 1, includes),
+        ("indexOf", 1, index_of),
+        ("join", 1, join),
+        ("keys", 0, |i, this, _| iterator(i, this, "keys", IterationKind::Keys)),
+        ("lastIndexOf", 1, last_index_of),
+        ("map", 1, map),
+        ("pop", 0, pop),
+        ("push", 1, push),
+        ("reduce", 1, reduce),
+        ("reduceRight", 1, reduce_right),
+        ("reverse", 0, reverse),
+        ("shift", 0, shift),
+        ("slice", 2, slice),
+        ("some", 1, some),
+        ("sort", 1, sort),
+        ("splice", 2, splice),
+        ("toReversed", 0, to_reversed),
+        ("toSorted", 1, to_sorted),
+        ("toSpliced", 2, to_spliced),
+        ("toString", 0, to_string),
+        ("unshift", 1, unshift),
+        ("with", 2, with),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    // `values` is allocated with the intrinsics, as `arguments` objects use
+    // it whatever happens to `Array.prototype`
+    let values = interpreter.intrinsics.array_values;
+    interpreter.heap.define_property(values, PropertyKey::from("length"), Property::data(Value::Number(0.0), Attributes::READ_ONLY));
+    interpreter.heap.define_property(values, PropertyKey::from("name"), Property::data(Value::String("values".into()), Attributes::READ_ONLY));
+    interpreter.heap.define_property(prototype, PropertyKey::from("values"), Property::data(Value::Function(values), Attributes::HIDDEN));
+    let iterator = PropertyKey::Symbol(interpreter.symbols.iterator.clone());
+    define_alias(interpreter, prototype, iterator, "values");
+
+    define_global(interpreter, "Array", constructor);
+}
+
+pub(in crate::interpreter) fn array_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.array_prototype)?;
+    match args {
+        [Value::Number(n)] => {
+            if *n < 0.0 || n.fract() != 0.0 || *n > u32::MAX as f64 {
+                return Err(range_error("Invalid array length"));
+            }
+            // `Array(n)` creates `n` holes, so only the length is stored
+            let id = interpreter.heap.allocate_array(Vec::new(), Some(prototype));
+            interpreter.heap.set_array_length(id, *n as u32);
+            Ok(Value::Object(id))
+        }
+        _ => {
+            let elements = args.iter().cloned().map(Some).collect();
+            Ok(Value::Object(interpreter.heap.allocate_array(elements, Some(prototype))))
+        }
+    }
+}
+
+fn is_array(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(interpreter.is_array(&arg(args, 0))?))
+}
+
+fn from(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let source = arg(args, 0);
+    let map_fn = match arg(args, 1) {
+        Value::Undefined => None,
+        map_fn => Some(callback(interpreter, &map_fn)?),
+    };
+    let this_arg = arg(args, 2);
+    let map = |interpreter: &mut Interpreter, value: Value, index: usize| match &map_fn {
+        Some(map_fn) => interpreter.call_function(map_fn, this_arg.clone(), vec![value, Value::Number(index as f64)]),
+        None => Ok(value),
+    };
+
+    let method = match &source {
+        Value::Null | Value::Undefined => None,
+        _ => interpreter.iterator_method(&source)?,
+    };
+    let mut values = Vec::new();
+    let target = match (&source, method) {
+        (_, Some(method)) => {
+            let target = construct_this(interpreter, this, Vec::new())?;
+            let mut iteration = interpreter.iterate_with(&source, &method)?;
+            while let Some(value) = interpreter.iteration_step(&mut iteration)? {
+                // A throwing `mapFn` closes the iterator
+                let mapped = map(interpreter, value, values.len());
+                let mapped = match mapped {
+                    Ok(mapped) => mapped,
+                    Err(message) => return interpreter.close_iteration(&mut iteration, Err(message)),
+                };
+                interpreter.hold(&mapped);
+                values.push(mapped);
+            }
+            target
+        }
+        (Value::Object(id) | Value::Function(id), None) => {
+            // Array-likes: anything with a `length`
+            let length = length_of(interpreter, *id)?;
+            let target = construct_this(interpreter, this, vec![Value::Number(length as f64)])?;
+            for index in 0..length {
+                let value = get(interpreter, *id, index)?;
+                values.push(map(interpreter, value, index as usize)?);
+            }
+            target
+        }
+        (Value::Null | Value::Undefined, None) => {
+            values = interpreter.collect_iterable(&source)?;
+            None
+        }
+        (_, None) => construct_this(interpreter, this, vec![Value::Number(0.0)])?,
+    };
+    store_elements(interpreter, target, values.into_iter().map(Some).collect(), true)
+}
+
+/// `values`, which is also `Array.prototype[Symbol.iterator]` and the
+/// iterator of `arguments` objects.
+pub(in crate::interpreter) fn values(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    iterator(interpreter, this, "values", IterationKind::Values)
+}
+
+pub(super) fn iterator(interpreter: &mut Interpreter, this: &Value, method: &str, kind: IterationKind) -> Result<Value, String> {
+    if matches!(this, Value::Null | Value::Undefined) {
+        return Err(type_error(format!("Array.prototype.{} called on null or undefined", method)));
+    }
+    Ok(interpreter.create_array_iterator(this.clone(), kind))
+}
+
+fn of(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = construct_this(interpreter, this, vec![Value::Number(args.len() as f64)])?;
+    store_elements(interpreter, target, args.iter().cloned().map(Some).collect(), true)
+}
+
+/// The object `Array.from` and `Array.of` store their elements in: one
+/// made by `this` with `args` when that is a constructor other than
+/// `Array`, else `None` for a plain array.
+fn construct_this(interpreter: &mut Interpreter, this: &Value, args: Vec<Value>) -> Result<Option<ObjectId>, String> {
+    match this {
+        Value::Function(id) if *id == interpreter.intrinsics.array_constructor => Ok(None),
+        _ if interpreter.is_constructor(this) => construct_object(interpreter, this, args).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// The `ArraySpeciesCreate` operation: the object the methods copying an
+/// array store their result in, made by `constructor[Symbol.species]` of
+/// an array receiver, or `None` for a plain array.
+fn species_create(interpreter: &mut Interpreter, original: &Value, length: u64) -> Result<Option<ObjectId>, String> {
+    if !interpreter.is_array(original)? {
+        return Ok(None);
+    }
+    let mut constructor = interpreter.get_member(original, &PropertyKey::from("constructor"))?;
+    if let Value::Object(_) | Value::Function(_) = constructor {
+        let species = PropertyKey::Symbol(interpreter.symbols.species.clone());
+        constructor = match interpreter.get_member(&constructor, &species)? {
+            Value::Null => Value::Undefined,
+            species => species,
+        };
+    }
+    match constructor {
+        Value::Undefined => Ok(None),
+        Value::Function(id) if id == interpreter.intrinsics.array_constructor => Ok(None),
+        _ if interpreter.is_constructor(&constructor) => {
+            construct_object(interpreter, &constructor, vec![Value::Number(length as f64)]).map(Some)
+        }
+        _ => Err(type_error("object.constructor[Symbol.species] is not a constructor")),
+    }
+}
+
+/// Constructs an object with `constructor`, held until the elements are
+/// stored in it.
+fn construct_object(interpreter: &mut Interpreter, constructor: &Value, args: Vec<Value>) -> Result<ObjectId, String> {
+    let result = interpreter.construct(constructor, args, constructor)?;
+    interpreter.hold(&result);
+    match result {
+        Value::Object(id) | Value::Function(id) => Ok(id),
+        _ => Err(type_error("object is not a constructor")),
+    }
+}
+
+/// Stores `elements`, apart from holes, in `target`, setting its `length`
+/// too when `with_length` is set; or makes a plain array of them when there
+/// is no `target`.
+fn store_elements(interpreter: &mut Interpreter, target: Option<ObjectId>, elements: Vec<Option<Value>>, with_length: bool) -> Result<Value, String> {
+    let Some(id) = target else {
+        let prototype = interpreter.intrinsics.array_prototype;
+        return Ok(Value::Object(interpreter.heap.allocate_array(elements, Some(prototype))));
+    };
+    let length = elements.len() as u64;
+    for (index, element) in elements.into_iter().enumerate() {
+        if let Some(value) = element {
+            let descriptor = PropertyDescriptor::of(&Property::data(value, Attributes::DEFAULT));
+            interpreter.define_property_or_throw(id, index_key(index as u64), descriptor)?;
+        }
+    }
+    if with_length {
+        set_length(interpreter, id, length)?;
+    }
+    Ok(interpreter.object_value(id))
+}
+
+/// The largest length of an array-like, 2^53 - 1.
+const MAX_LENGTH: u64 = (1 << 53) - 1;
+
+/// Resolves the receiver of an `Array.prototype` method and its length.
+fn this_object(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<(ObjectId, u64), String> {
+    match this {
+        Value::Object(id) | Value::Function(id) => Ok((*id, length_of(interpreter, *id)?)),
+        Value::Null | Value::Undefined => {
+            Err(type_error(format!("Array.prototype.{} called on null or undefined", method)))
+        }
+        other => Err(type_error(format!(
+            "Array.prototype.{} called on non-object {}",
+            method,
+            interpreter.to_display_string(other)
+        ))),
+    }
+}
+
+/// The `LengthOfArrayLike` operation, rejecting lengths no array could
+/// have.
+pub(super) fn length_of(interpreter: &mut Interpreter, id: ObjectId) -> Result<u64, String> {
+    let length = interpreter.get_member(&Value::Object(id), &PropertyKey::from("length"))?;
+    let length = to_integer(interpreter.coerce_to_number(&length)?);
+    if length > u32::MAX as f64 {
+        return Err(range_error("Invalid array length"));
+    }
+    Ok(length.max(0.0) as u64)
+}
+
+fn set_length(interpreter: &mut Interpreter, id: ObjectId, length: u64) -> Result<(), String> {
+    interpreter.set_member(&Value::Object(id), PropertyKey::from("length"), Value::Number(length as f64))
+}
+
+/// The key of element `index` of an array-like, which is an ordinary
+/// string key past the largest array index.
+pub(super) fn index_key(index: u64) -> PropertyKey {
+    match u32::try_from(index) {
+        Ok(index) if index != u32::MAX => PropertyKey::Index(index),
+        _ => PropertyKey::String(index.to_string()),
+    }
+}
+
+fn get(interpreter: &mut Interpreter, id: ObjectId, index: u64) -> Result<Value, String> {
+    interpreter.get_member(&Value::Object(id), &index_key(index))
+}
+
+fn set(interpreter: &mut Interpreter, id: ObjectId, index: u64, value: Value) -> Result<(), String> {
+    interpreter.set_member(&Value::Object(id), index_key(index), value)
+}
+
+fn delete(interpreter: &mut Interpreter, id: ObjectId, index: u64) -> Result<(), String> {
+    interpreter.delete_member(&Value::Object(id), &index_key(index)).map(|_| ())
+}
+
+fn has(interpreter: &mut Interpreter, id: ObjectId, index: u64) -> Result<bool, String> {
+    interpreter.has_property(id, &index_key(index))
+}
+
+/// Reads the first `length` elements, with `None` for holes.
+fn read_elements(interpreter: &mut Interpreter, id: ObjectId, length: u64) -> Result<Vec<Option<Value>>, String> {
+    let mut elements = Vec::new();
+    for index in 0..length {
+        if has(interpreter, id, index)? {
+            elements.push(Some(get(interpreter, id, index)?));
+        } else {
+            elements.push(None);
+        }
+    }
+    Ok(elements)
+}
+
+/// The length of an array-like of `length` elements after adding `count`
+/// more, which may not pass 2^53 - 1. Arrays have a smaller limit still,
+/// which setting their `length` enforces.
+fn grown_length(length: u64, count: usize, action: &str) -> Result<u64, String> {
+    let new_length = length + count as u64;
+    if new_length > MAX_LENGTH {
+        return Err(type_error(format!(
+            "{} {} elements on an array-like of length {} is disallowed, as the total surpasses 2**53-1",
+            action, count, length
+        )));
+    }
+    Ok(new_length)
+}
+
+/// Fails as setting the `length` of an array to `length` would, for the
+/// methods that would otherwise move every element before finding out.
+fn check_array_length(interpreter: &mut Interpreter, this: &Value, length: u64) -> Result<(), String> {
+    if length > u32::MAX as u64 && interpreter.is_array(this)? {
+        return Err(range_error("Invalid array length"));
+    }
+    Ok(())
+}
+
+/// Replaces the elements of an object that previously had `old_length`
+/// elements, deleting holes and anything past the new end.
+fn write_elements(
+    interpreter: &mut Interpreter,
+    id: ObjectId,
+    elements: Vec<Option<Value>>,
+    old_length: u64,
+) -> Result<(), String> {
+    let new_length = elements.len() as u64;
+    for (index, element) in elements.into_iter().enumerate() {
+        match element {
+            Some(value) => set(interpreter, id, index as u64, value)?,
+            None => delete(interpreter, id, index as u64)?,
+        }
+    }
+    for index in new_length..old_length {
+        delete(interpreter, id, index)?;
+    }
+    set_length(interpreter, id, new_length)
+}
+
+pub(super) fn callback(interpreter: &Interpreter, value: &Value) -> Result<Value, String> {
+    match value {
+        Value::Function(_) => Ok(value.clone()),
+        other => Err(type_error(format!("{} is not a function", interpreter.to_display_string(other)))),
+    }
+}
+
+/// Calls `callback(element, index, array)` for every present element until
+/// `stop` returns true for a result, returning the index it stopped at.
+fn iterate(
+    interpreter: &mut Interpreter,
+    this: &Value,
+    args: &[Value],
+    method: &str,
+    mut stop: impl FnMut(&mut Interpreter, u64, Value, Value) -> Result<bool, String>,
+) -> Result<Option<u64>, String> {
+    let (id, length) = this_object(interpreter, this, method)?;
+    let function = callback(interpreter, &arg(args, 0))?;
+    let this_arg = arg(args, 1);
+    for index in 0..length {
+        if !has(interpreter, id, index)? {
+            continue;
+        }
+        let element = get(interpreter, id, index)?;
+        let call_args = vec![element.clone(), Value::Number(index as f64), Value::Object(id)];
+        let result = interpreter.call_function(&function, this_arg.clone(), call_args)?;
+        if stop(interpreter, index, element, result)? {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+pub(super) fn at(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "at")?;
+    let n = to_integer(interpreter.coerce_to_number(&arg(args, 0))?);
+    let index = if n < 0.0 { length as f64 + n } else { n };
+    if index < 0.0 || index >= length as f64 {
+        return Ok(Value::Undefined);
+    }
+    get(interpreter, id, index as u64)
+}
+
+fn concat(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = species_create(interpreter, this, 0)?;
+    // The result is an array, so checking before reading saves walking
+    // elements that could never be stored. Reading the length of a plain
+    // array has no side effects, so those are summed up front.
+    let mut total = 0;
+    for item in std::iter::once(this).chain(args) {
+        total += match item {
+            Value::Object(id) if interpreter.heap.get(*id).is_array() => length_of(interpreter, *id)?,
+            _ => 1,
+        };
+    }
+    if total > u32::MAX as u64 {
+        return Err(range_error("Invalid array length"));
+    }
+    let mut elements = Vec::new();
+    for item in std::iter::once(this).chain(args) {
+        match item {
+            Value::Object(id) if interpreter.is_array(item)? => {
+                let length = length_of(interpreter, *id)?;
+                if elements.len() as u64 + length > u32::MAX as u64 {
+                    return Err(range_error("Invalid array length"));
+                }
+                elements.extend(read_elements(interpreter, *id, length)?);
+            }
+            other => elements.push(Some(other.clone())),
+        }
+    }
+    store_elements(interpreter, target, elements, true)
+}
+
+fn copy_within(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "copyWithin")?;
+    let target = relative_index(interpreter, &arg(args, 0), length, 0)? as usize;
+    let start = relative_index(interpreter, &arg(args, 1), length, 0)? as usize;
+    let end = relative_index(interpreter, &arg(args, 2), length, length)? as usize;
+    let mut elements = read_elements(interpreter, id, length)?;
+    let count = end.saturating_sub(start).min(length as usize - target);
+    let copied: Vec<_> = elements[start..start + count].to_vec();
+    elements.splice(target..target + count, copied);
+    write_elements(interpreter, id, elements, length)?;
+    Ok(this.clone())
+}
+
+pub(super) fn every(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let failed = iterate(interpreter, this, args, "every", |_, _, _, result| Ok(!result.is_truthy()))?;
+    Ok(Value::Boolean(failed.is_none()))
+}
+
+pub(super) fn some(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let found = iterate(interpreter, this, args, "some", |_, _, _, result| Ok(result.is_truthy()))?;
+    Ok(Value::Boolean(found.is_some()))
+}
+
+pub(super) fn for_each(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    iterate(interpreter, this, args, "forEach", |_, _, _, _| Ok(false))?;
+    Ok(Value::Undefined)
+}
+
+fn map(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (_, length) = this_object(interpreter, this, "map")?;
+    let target = species_create(interpreter, this, length)?;
+    let elements = map_elements(interpreter, this, args, "map", length)?;
+    store_elements(interpreter, target, elements, false)
+}
+
+/// The results of calling the callback on each element, for `map` and
+/// `flatMap`. Holes in the source stay holes.
+fn map_elements(interpreter: &mut Interpreter, this: &Value, args: &[Value], method: &str, length: u64) -> Result<Vec<Option<Value>>, String> {
+    let mut elements = vec![None; length as usize];
+    iterate(interpreter, this, args, method, |_, index, _, result| {
+        elements[index as usize] = Some(result);
+        Ok(false)
+    })?;
+    Ok(elements)
+}
+
+fn filter(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = species_create(interpreter, this, 0)?;
+    let mut kept = Vec::new();
+    iterate(interpreter, this, args, "filter", |_, _, element, result| {
+        if result.is_truthy() {
+            kept.push(element);
+        }
+        Ok(false)
+    })?;
+    store_elements(interpreter, target, kept.into_iter().map(Some).collect(), false)
+}
+
+/// Shared by the `find` family, which unlike the other iteration methods
+/// visit holes as `undefined`.
+fn find_element(
+    interpreter: &mut Interpreter,
+    this: &Value,
+    args: &[Value],
+    method: &str,
+    from_end: bool,
+) -> Result<Option<(u64, Value)>, String> {
+    let (id, length) = this_object(interpreter, this, method)?;
+    let function = callback(interpreter, &arg(args, 0))?;
+    let this_arg = arg(args, 1);
+    let indices: Box<dyn Iterator<Item = u64>> = if from_end { Box::new((0..length).rev()) } else { Box::new(0..length) };
+    for index in indices {
+        let element = get(interpreter, id, index)?;
+        let call_args = vec![element.clone(), Value::Number(index as f64), Value::Object(id)];
+        if interpreter.call_function(&function, this_arg.clone(), call_args)?.is_truthy() {
+            return Ok(Some((index, element)));
+        }
+    }
+    Ok(None)
+}
+
+pub(super) fn find(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let found = find_element(interpreter, this, args, "find", false)?;
+    Ok(found.map_or(Value::Undefined, |(_, element)| element))
+}
+
+pub(super) fn find_index(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let found = find_element(interpreter, this, args, "findIndex", false)?;
+    Ok(Value::Number(found.map_or(-1.0, |(index, _)| index as f64)))
+}
+
+pub(super) fn find_last(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let found = find_element(interpreter, this, args, "findLast", true)?;
+    Ok(found.map_or(Value::Undefined, |(_, element)| element))
+}
+
+pub(super) fn find_last_index(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let found = find_element(interpreter, this, args, "findLastIndex", true)?;
+    Ok(Value::Number(found.map_or(-1.0, |(index, _)| index as f64)))
+}
+
+fn fill(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "fill")?;
+    let value = arg(args, 0);
+    let start = relative_index(interpreter, &arg(args, 1), length, 0)?;
+    let end = relative_index(interpreter, &arg(args, 2), length, length)?;
+    for index in start..end {
+        set(interpreter, id, index, value.clone())?;
+    }
+    Ok(this.clone())
+}
+
+/// Appends the elements of `source` to `target`, descending into nested
+/// arrays up to `depth` levels. Holes are skipped.
+fn flatten_into(interpreter: &mut Interpreter, target: &mut Vec<Value>, source: ObjectId, depth: f64) -> Result<(), String> {
+    let length = length_of(interpreter, source)?;
+    for element in read_elements(interpreter, source, length)?.into_iter().flatten() {
+        match element {
+            Value::Object(id) if depth >= 1.0 && interpreter.is_array(&element)? => {
+                flatten_into(interpreter, target, id, depth - 1.0)?;
+            }
+            element => target.push(element),
+        }
+    }
+    Ok(())
+}
+
+fn flat(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, _) = this_object(interpreter, this, "flat")?;
+    let depth = match arg(args, 0) {
+        Value::Undefined => 1.0,
+        depth => to_integer(interpreter.coerce_to_number(&depth)?),
+    };
+    let target = species_create(interpreter, this, 0)?;
+    let mut elements = Vec::new();
+    flatten_into(interpreter, &mut elements, id, depth)?;
+    store_elements(interpreter, target, elements.into_iter().map(Some).collect(), false)
+}
+
+fn flat_map(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (_, length) = this_object(interpreter, this, "flatMap")?;
+    let target = species_create(interpreter, this, 0)?;
+    let mut elements = Vec::new();
+    for element in map_elements(interpreter, this, args, "flatMap", length)?.into_iter().flatten() {
+        match element {
+            Value::Object(id) if interpreter.is_array(&element)? => flatten_into(interpreter, &mut elements, id, 0.0)?,
+            element => elements.push(element),
+        }
+    }
+    store_elements(interpreter, target, elements.into_iter().map(Some).collect(), false)
+}
+
+pub(super) fn includes(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "includes")?;
+    let search = arg(args, 0);
+    let start = relative_index(interpreter, &arg(args, 1), length, 0)?;
+    for index in start..length {
+        if same_value_zero(&get(interpreter, id, index)?, &search) {
+            return Ok(Value::Boolean(true));
+        }
+    }
+    Ok(Value::Boolean(false))
+}
+
+pub(super) fn index_of(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "indexOf")?;
+    let search = arg(args, 0);
+    let start = relative_index(interpreter, &arg(args, 1), length, 0)?;
+    for index in start..length {
+        if has(interpreter, id, index)? && strict_equals(&get(interpreter, id, index)?, &search) {
+            return Ok(Value::Number(index as f64));
+        }
+    }
+    Ok(Value::Number(-1.0))
+}
+
+pub(super) fn last_index_of(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "lastIndexOf")?;
+    if length == 0 {
+        return Ok(Value::Number(-1.0));
+    }
+    let search = arg(args, 0);
+    let start = match args.get(1) {
+        None => length as f64 - 1.0,
+        Some(from) => {
+            let n = to_integer(interpreter.coerce_to_number(from)?);
+            if n < 0.0 { length as f64 + n } else { n.min(length as f64 - 1.0) }
+        }
+    };
+    if start < 0.0 {
+        return Ok(Value::Number(-1.0));
+    }
+    for index in (0..=start as u64).rev() {
+        if has(interpreter, id, index)? && strict_equals(&get(interpreter, id, index)?, &search) {
+            return Ok(Value::Number(index as f64));
+        }
+    }
+    Ok(Value::Number(-1.0))
+}
+
+pub(super) fn join(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "join")?;
+    let separator = match arg(args, 0) {
+        Value::Undefined => JsString::from(","),
+        separator => interpreter.coerce_to_string(&separator)?,
+    };
+    // A cyclic reference prints as an empty string rather than recursing
+    if interpreter.joining.contains(&id) {
+        return Ok(Value::String(JsString::new()));
+    }
+    interpreter.joining.push(id);
+    let result = join_elements(interpreter, id, length, &separator);
+    interpreter.joining.pop();
+    Ok(Value::String(result?))
+}
+
+fn join_elements(interpreter: &mut Interpreter, id: ObjectId, length: u64, separator: &JsString) -> Result<JsString, String> {
+    // The separators alone can be too long, whatever the elements are
+    if length.saturating_sub(1) as f64 * separator.len() as f64 > MAX_STRING_LENGTH as f64 {
+        return Err(range_error("Invalid string length"));
+    }
+    let mut result = JsString::new();
+    for index in 0..length {
+        if index > 0 {
+            result.push_js_str(separator);
+        }
+        match get(interpreter, id, index)? {
+            Value::Null | Value::Undefined => {}
+            element => result.push_js_str(&interpreter.coerce_to_string(&element)?),
+        }
+        if result.len() > MAX_STRING_LENGTH {
+            return Err(range_error("Invalid string length"));
+        }
+    }
+    Ok(result)
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    join(interpreter, this, &[])
+}
+
+fn pop(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "pop")?;
+    if length == 0 {
+        set_length(interpreter, id, 0)?;
+        return Ok(Value::Undefined);
+    }
+    let element = get(interpreter, id, length - 1)?;
+    delete(interpreter, id, length - 1)?;
+    set_length(interpreter, id, length - 1)?;
+    Ok(element)
+}
+
+fn push(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "push")?;
+    let new_length = grown_length(length, args.len(), "Pushing")?;
+    for (offset, value) in args.iter().enumerate() {
+        set(interpreter, id, length + offset as u64, value.clone())?;
+    }
+    set_length(interpreter, id, new_length)?;
+    Ok(Value::Number(new_length as f64))
+}
+
+fn shift(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "shift")?;
+    if length == 0 {
+        set_length(interpreter, id, 0)?;
+        return Ok(Value::Undefined);
+    }
+    let mut elements = read_elements(interpreter, id, length)?;
+    let first = elements.remove(0);
+    write_elements(interpreter, id, elements, length)?;
+    Ok(first.unwrap_or(Value::Undefined))
+}
+
+fn unshift(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "unshift")?;
+    let new_length = grown_length(length, args.len(), "Unshifting")?;
+    check_array_length(interpreter, this, new_length)?;
+    let mut elements: Vec<_> = args.iter().cloned().map(Some).collect();
+    elements.extend(read_elements(interpreter, id, length)?);
+    let new_length = elements.len();
+    write_elements(interpreter, id, elements, length)?;
+    Ok(Value::Number(new_length as f64))
+}
+
+fn reduce_elements(interpreter: &mut Interpreter, this: &Value, args: &[Value], method: &str, from_end: bool) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, method)?;
+    let function = callback(interpreter, &arg(args, 0))?;
+    let mut indices: Box<dyn Iterator<Item = u64>> = if from_end { Box::new((0..length).rev()) } else { Box::new(0..length) };
+
+    let mut accumulator = match args.get(1) {
+        Some(initial) => initial.clone(),
+        None => loop {
+            match indices.next() {
+                Some(index) if has(interpreter, id, index)? => break get(interpreter, id, index)?,
+                Some(_) => continue,
+                None => return Err(type_error("Reduce of empty array with no initial value")),
+            }
+        },
+    };
+    for index in indices {
+        if !has(interpreter, id, index)? {
+            continue;
+        }
+        let element = get(interpreter, id, index)?;
+        let call_args = vec![accumulator, element, Value::Number(index as f64), Value::Object(id)];
+        accumulator = interpreter.call_function(&function, Value::Undefined, call_args)?;
+    }
+    Ok(accumulator)
+}
+
+pub(super) fn reduce(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    reduce_elements(interpreter, this, args, "reduce", false)
+}
+
+pub(super) fn reduce_right(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    reduce_elements(interpreter, this, args, "reduceRight", true)
+}
+
+fn reverse(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "reverse")?;
+    let mut elements = read_elements(interpreter, id, length)?;
+    elements.reverse();
+    write_elements(interpreter, id, elements, length)?;
+    Ok(this.clone())
+}
+
+fn to_reversed(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "toReversed")?;
+    let mut elements = dense_elements(interpreter, id, length)?;
+    elements.reverse();
+    Ok(interpreter.create_array(elements))
+}
+
+/// Reads the first `length` elements, treating holes as `undefined` as the
+/// copying methods (`toReversed`, `toSorted`, ...) do.
+fn dense_elements(interpreter: &mut Interpreter, id: ObjectId, length: u64) -> Result<Vec<Value>, String> {
+    (0..length).map(|index| get(interpreter, id, index)).collect()
+}
+
+fn slice(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "slice")?;
+    let start = relative_index(interpreter, &arg(args, 0), length, 0)?;
+    let end = relative_index(interpreter, &arg(args, 1), length, length)?;
+    let target = species_create(interpreter, this, end.saturating_sub(start))?;
+    let mut elements = Vec::new();
+    for index in start..end.max(start) {
+        elements.push(if has(interpreter, id, index)? { Some(get(interpreter, id, index)?) } else { None });
+    }
+    store_elements(interpreter, target, elements, true)
+}
+
+/// Sorts `values` with the user's comparator, or by string order when it is
+/// `undefined`. `undefined` values always sort last.
+fn sort_values(interpreter: &mut Interpreter, values: Vec<Value>, comparator: &Value) -> Result<Vec<Value>, String> {
+    if !matches!(comparator, Value::Undefined) {
+        callback(interpreter, comparator)?;
+    }
+    let (undefined, defined): (Vec<_>, Vec<_>) = values.into_iter().partition(|v| matches!(v, Value::Undefined));
+    let mut sorted = merge_sort(interpreter, defined, comparator)?;
+    sorted.extend(undefined);
+    Ok(sorted)
+}
+
+/// A stable merge sort whose comparisons may fail, since they call user code.
+pub(super) fn merge_sort(interpreter: &mut Interpreter, mut values: Vec<Value>, comparator: &Value) -> Result<Vec<Value>, String> {
+    if values.len() <= 1 {
+        return Ok(values);
+    }
+    let right = values.split_off(values.len() / 2);
+    let left = merge_sort(interpreter, values, comparator)?;
+    let right = merge_sort(interpreter, right, comparator)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // Take from the right only when strictly smaller, keeping the sort stable
+        if compare(interpreter, l, r, comparator)? == Ordering::Greater {
+            merged.push(right.next().unwrap());
+        } else {
+            merged.push(left.next().unwrap());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+fn compare(interpreter: &mut Interpreter, left: &Value, right: &Value, comparator: &Value) -> Result<Ordering, String> {
+    if matches!(comparator, Value::Undefined) {
+        let left = interpreter.coerce_to_string(left)?;
+        let right = interpreter.coerce_to_string(right)?;
+        // JavaScript compares strings by UTF-16 code units
+        return Ok(left.cmp(&right));
+    }
+    let result = interpreter.call_function(comparator, Value::Undefined, vec![left.clone(), right.clone()])?;
+    let order = interpreter.coerce_to_number(&result)?;
+    Ok(order.partial_cmp(&0.0).unwrap_or(Ordering::Equal))
+}
+
+fn sort(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "sort")?;
+    let elements = read_elements(interpreter, id, length)?;
+    let holes = elements.iter().filter(|e| e.is_none()).count();
+    let values = elements.into_iter().flatten().collect();
+    let mut sorted: Vec<_> = sort_values(interpreter, values, &arg(args, 0))?.into_iter().map(Some).collect();
+    // Holes move to the end
+    sorted.extend(std::iter::repeat_n(None, holes));
+    write_elements(interpreter, id, sorted, length)?;
+    Ok(this.clone())
+}
+
+fn to_sorted(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "toSorted")?;
+    let values = dense_elements(interpreter, id, length)?;
+    let sorted = sort_values(interpreter, values, &arg(args, 0))?;
+    Ok(interpreter.create_array(sorted))
+}
+
+/// Resolves the `start` and `deleteCount` arguments of `splice` and `toSpliced`.
+fn splice_range(interpreter: &mut Interpreter, args: &[Value], length: u64) -> Result<(usize, usize), String> {
+    let start = relative_index(interpreter, &arg(args, 0), length, 0)?;
+    let delete_count = match args.len() {
+        0 => 0,
+        1 => length - start,
+        _ => {
+            let n = to_integer(interpreter.coerce_to_number(&args[1])?);
+            n.clamp(0.0, (length - start) as f64) as u64
+        }
+    };
+    Ok((start as usize, delete_count as usize))
+}
+
+fn splice(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "splice")?;
+    let (start, delete_count) = splice_range(interpreter, args, length)?;
+    let new_length = grown_length(length - delete_count as u64, args.len().saturating_sub(2), "Splicing")?;
+    check_array_length(interpreter, this, new_length)?;
+    let target = species_create(interpreter, this, delete_count as u64)?;
+    let mut elements = read_elements(interpreter, id, length)?;
+    let items = args.iter().skip(2).cloned().map(Some);
+    let removed: Vec<_> = elements.splice(start..start + delete_count, items).collect();
+    write_elements(interpreter, id, elements, length)?;
+    store_elements(interpreter, target, removed, true)
+}
+
+fn to_spliced(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "toSpliced")?;
+    let (start, delete_count) = splice_range(interpreter, args, length)?;
+    let mut elements = dense_elements(interpreter, id, length)?;
+    elements.splice(start..start + delete_count, args.iter().skip(2).cloned());
+    Ok(interpreter.create_array(elements))
+}
+
+fn with(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, length) = this_object(interpreter, this, "with")?;
+    let n = to_integer(interpreter.coerce_to_number(&arg(args, 0))?);
+    let index = if n < 0.0 { length as f64 + n } else { n };
+    if index < 0.0 || index >= length as f64 {
+        return Err(range_error("Invalid index"));
+    }
+    let mut elements = dense_elements(interpreter, id, length)?;
+    elements[index as usize] = arg(args, 1);
+    Ok(interpreter.create_array(elements))
+}
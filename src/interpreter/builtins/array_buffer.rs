@@ -0,0 +1,84 @@
+//! `ArrayBuffer` and `SharedArrayBuffer`. Scripts never run in parallel,
+//! so a `SharedArrayBuffer` is an `ArrayBuffer` of another type.
+
+use super::{arg, define_getter, define_global, define_method, define_to_string_tag, incompatible_receiver, relative_index, to_index};
+use crate::interpreter::object::{NativeFunction, ObjectId, ObjectKind};
+use crate::interpreter::{range_error, type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let constructor = install_buffer(interpreter, false, |i, _, args| construct(i, args, false));
+    define_method(interpreter, constructor, "isView", 1, is_view);
+    install_buffer(interpreter, true, |i, _, args| construct(i, args, true));
+}
+
+fn buffer_name(shared: bool) -> &'static str {
+    if shared { "SharedArrayBuffer" } else { "ArrayBuffer" }
+}
+
+/// Creates `ArrayBuffer`, or `SharedArrayBuffer` when `shared` is set,
+/// returning the constructor.
+fn install_buffer(interpreter: &mut Interpreter, shared: bool, constructor: NativeFunction) -> ObjectId {
+    let name = buffer_name(shared);
+    let prototype = if shared { interpreter.intrinsics.shared_array_buffer_prototype } else { interpreter.intrinsics.array_buffer_prototype };
+    let constructor = interpreter.create_native_function(name, 1, constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let (byte_length, slice): (NativeFunction, NativeFunction) = if shared {
+        (|i, this, _| byte_length(i, this, true), |i, this, args| slice(i, this, args, true))
+    } else {
+        (|i, this, _| byte_length(i, this, false), |i, this, args| slice(i, this, args, false))
+    };
+    define_getter(interpreter, prototype, "byteLength", byte_length);
+    define_method(interpreter, prototype, "slice", 2, slice);
+    define_to_string_tag(interpreter, prototype, name);
+    define_global(interpreter, name, constructor.clone());
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+    constructor_id
+}
+
+fn construct(interpreter: &mut Interpreter, args: &[Value], shared: bool) -> Result<Value, String> {
+    if interpreter.new_target.is_none() {
+        return Err(type_error(format!("Constructor {} requires 'new'", buffer_name(shared))));
+    }
+    let length = to_index(interpreter, &arg(args, 0), |_| range_error("Invalid array buffer length"))?;
+    let fallback = if shared { interpreter.intrinsics.shared_array_buffer_prototype } else { interpreter.intrinsics.array_buffer_prototype };
+    let prototype = interpreter.new_target_prototype(fallback)?;
+    Ok(Value::Object(interpreter.allocate_array_buffer(length, shared, prototype)?))
+}
+
+/// `ArrayBuffer.isView(value)`: whether `value` is a typed array or a
+/// `DataView`.
+fn is_view(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let view = match arg(args, 0) {
+        Value::Object(id) => matches!(interpreter.heap.get(id).kind, ObjectKind::TypedArray(_) | ObjectKind::DataView(_)),
+        _ => false,
+    };
+    Ok(Value::Boolean(view))
+}
+
+/// The buffer a method was called on, which must be of the type the
+/// method belongs to.
+fn this_buffer(interpreter: &Interpreter, this: &Value, shared: bool, method: &str) -> Result<ObjectId, String> {
+    match this {
+        Value::Object(id) if matches!(&interpreter.heap.get(*id).kind, ObjectKind::ArrayBuffer(buffer) if buffer.shared == shared) => Ok(*id),
+        _ => Err(incompatible_receiver(interpreter, &format!("{}.prototype.{}", buffer_name(shared), method), this)),
+    }
+}
+
+fn byte_length(interpreter: &mut Interpreter, this: &Value, shared: bool) -> Result<Value, String> {
+    let id = this_buffer(interpreter, this, shared, "byteLength")?;
+    Ok(Value::Number(interpreter.buffer_bytes(id).len() as f64))
+}
+
+/// `slice(start, end)`: a new buffer holding a copy of the bytes between
+/// two relative indices.
+fn slice(interpreter: &mut Interpreter, this: &Value, args: &[Value], shared: bool) -> Result<Value, String> {
+    let id = this_buffer(interpreter, this, shared, "slice")?;
+    let length = interpreter.buffer_bytes(id).len() as u64;
+    let start = relative_index(interpreter, &arg(args, 0), length, 0)? as usize;
+    let end = relative_index(interpreter, &arg(args, 1), length, length)? as usize;
+    let prototype = if shared { interpreter.intrinsics.shared_array_buffer_prototype } else { interpreter.intrinsics.array_buffer_prototype };
+    let copy = interpreter.allocate_array_buffer(end.saturating_sub(start), shared, prototype)?;
+    let bytes = interpreter.buffer_bytes(id)[start..end.max(start)].to_vec();
+    interpreter.buffer_bytes_mut(copy).copy_from_slice(&bytes);
+    Ok(Value::Object(copy))
+}
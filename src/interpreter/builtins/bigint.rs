@@ -0,0 +1,78 @@
+//! `BigInt` and `BigInt.prototype`.
+
+use num_bigint::BigInt;
+use num_traits::Signed;
+
+use super::{arg, define_global, define_method, define_to_string_tag, to_integer};
+use crate::interpreter::bigint::{as_int_n, as_uint_n, number_to_bigint};
+use crate::interpreter::object::NativeFunction;
+use crate::interpreter::{range_error, type_error, Interpreter, PreferredType, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.bigint_prototype;
+    let constructor = interpreter.create_native_function("BigInt", 1, bigint_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+
+    let methods: &[(&str, u32, NativeFunction)] = &[("toLocaleString", 0, to_locale_string), ("toString", 0, to_string), ("valueOf", 0, value_of)];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    define_to_string_tag(interpreter, prototype, "BigInt");
+    define_method(interpreter, constructor_id, "asIntN", 2, |i, _, args| wrap(i, args, as_int_n));
+    define_method(interpreter, constructor_id, "asUintN", 2, |i, _, args| wrap(i, args, as_uint_n));
+    define_global(interpreter, "BigInt", constructor);
+}
+
+/// `BigInt(value)` converts like `ToBigInt`, except that integral numbers
+/// are accepted too. BigInts are primitives, so `new BigInt()` is an error.
+fn bigint_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if interpreter.new_target.is_some() {
+        return Err(type_error("BigInt is not a constructor"));
+    }
+    match interpreter.coerce_to_primitive(arg(args, 0), PreferredType::Number)? {
+        Value::Number(n) => number_to_bigint(n).map(Value::BigInt),
+        primitive => interpreter.coerce_to_bigint(&primitive).map(Value::BigInt),
+    }
+}
+
+/// `BigInt.asIntN(bits, bigint)` and `BigInt.asUintN`.
+fn wrap(interpreter: &mut Interpreter, args: &[Value], operation: fn(u64, &BigInt) -> Result<BigInt, String>) -> Result<Value, String> {
+    let bits = to_integer(interpreter.coerce_to_number(&arg(args, 0))?);
+    if !(0.0..=9007199254740991.0).contains(&bits) {
+        return Err(range_error("Invalid value: not (convertible to) a safe integer"));
+    }
+    let n = interpreter.coerce_to_bigint(&arg(args, 1))?;
+    operation(bits as u64, &n).map(Value::BigInt)
+}
+
+/// The BigInt a `BigInt.prototype` method was called on.
+fn this_bigint(this: &Value, method: &str) -> Result<BigInt, String> {
+    match this {
+        Value::BigInt(n) => Ok(n.clone()),
+        _ => Err(type_error(format!("BigInt.prototype.{} requires that 'this' be a BigInt", method))),
+    }
+}
+
+fn value_of(_interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    this_bigint(this, "valueOf").map(Value::BigInt)
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let n = this_bigint(this, "toString")?;
+    let radix = match arg(args, 0) {
+        Value::Undefined => 10.0,
+        radix => to_integer(interpreter.coerce_to_number(&radix)?),
+    };
+    if !(2.0..=36.0).contains(&radix) {
+        return Err(range_error("toString() radix must be between 2 and 36"));
+    }
+    Ok(Value::String(n.to_str_radix(radix as u32).into()))
+}
+
+/// `BigInt.prototype.toLocaleString` for the `en-US` locale.
+fn to_locale_string(_interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let n = this_bigint(this, "toLocaleString")?;
+    let sign = if n.is_negative() { "-" } else { "" };
+    Ok(Value::String(format!("{}{}", sign, super::number::group_thousands(&n.magnitude().to_string())).into()))
+}
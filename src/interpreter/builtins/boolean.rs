@@ -0,0 +1,50 @@
+//! `Boolean` and `Boolean.prototype`.
+
+use super::{arg, define_global, define_method};
+use crate::interpreter::object::{NativeFunction, ObjectKind};
+use crate::interpreter::{type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.boolean_prototype;
+    let constructor = interpreter.create_native_function("Boolean", 1, boolean_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+
+    let methods: &[(&str, u32, NativeFunction)] = &[("toString", 0, to_string), ("valueOf", 0, value_of)];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    define_global(interpreter, "Boolean", constructor);
+}
+
+/// `Boolean(value)` converts; `new Boolean(value)` creates a wrapper
+/// object.
+fn boolean_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let b = arg(args, 0).is_truthy();
+    if interpreter.new_target.is_none() {
+        return Ok(Value::Boolean(b));
+    }
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.boolean_prototype)?;
+    Ok(Value::Object(interpreter.create_primitive_wrapper(Value::Boolean(b), prototype)))
+}
+
+/// The boolean a `Boolean.prototype` method was called on, unwrapping
+/// `Boolean` objects.
+fn this_boolean(interpreter: &Interpreter, this: &Value, method: &str) -> Result<bool, String> {
+    match this {
+        Value::Boolean(b) => Ok(*b),
+        Value::Object(id) => match interpreter.heap.get(*id).kind {
+            ObjectKind::Primitive(Value::Boolean(b)) => Ok(b),
+            _ => Err(type_error(format!("Boolean.prototype.{} requires that 'this' be a Boolean", method))),
+        },
+        _ => Err(type_error(format!("Boolean.prototype.{} requires that 'this' be a Boolean", method))),
+    }
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let b = this_boolean(interpreter, this, "toString")?;
+    Ok(Value::String(b.to_string().into()))
+}
+
+fn value_of(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(this_boolean(interpreter, this, "valueOf")?))
+}
@@ -0,0 +1,371 @@
+//! The keyed collections `Map`, `Set`, `WeakMap` and `WeakSet`, and the
+//! iterators of `Map` and `Set`.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{arg, define_alias, define_getter, define_global, define_method, define_to_string_tag, incompatible_receiver, receiver_description};
+use crate::interpreter::collection::{IterationKind, OrderedTable};
+use crate::interpreter::object::{NativeFunction, Object, ObjectId, ObjectKind, PropertyKey};
+use crate::interpreter::{type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    install_map(interpreter);
+    install_set(interpreter);
+    install_weak_map(interpreter);
+    install_weak_set(interpreter);
+
+    let map_iterator = interpreter.intrinsics.map_iterator_prototype;
+    define_method(interpreter, map_iterator, "next", 0, map_iterator_next);
+    define_to_string_tag(interpreter, map_iterator, "Map Iterator");
+    let set_iterator = interpreter.intrinsics.set_iterator_prototype;
+    define_method(interpreter, set_iterator, "next", 0, set_iterator_next);
+    define_to_string_tag(interpreter, set_iterator, "Set Iterator");
+}
+
+/// Creates a constructor whose instances start out as `kind`, linked to
+/// `prototype`, and defines `methods` on the prototype.
+fn install_collection(
+    interpreter: &mut Interpreter,
+    name: &str,
+    constructor: NativeFunction,
+    prototype: ObjectId,
+    methods: &[(&str, u32, NativeFunction)],
+) {
+    let constructor = interpreter.create_native_function(name, 0, constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    define_to_string_tag(interpreter, prototype, name);
+    define_global(interpreter, name, constructor);
+}
+
+/// Allocates the object a collection constructor returns, which inherits
+/// from the prototype of `new.target` so that subclasses work.
+fn construct(interpreter: &mut Interpreter, name: &str, kind: ObjectKind, fallback: ObjectId) -> Result<Value, String> {
+    if interpreter.new_target.is_none() {
+        return Err(type_error(format!("Constructor {} requires 'new'", name)));
+    }
+    let prototype = interpreter.new_target_prototype(fallback)?;
+    let id = interpreter.heap.allocate(Object::new(kind, Some(prototype)));
+    Ok(Value::Object(id))
+}
+
+/// Adds the values of `iterable` to a new collection by calling its
+/// `adder` method, `set` or `add`, so that overrides in subclasses are
+/// used. Maps take `[key, value]` entries.
+fn add_entries(interpreter: &mut Interpreter, collection: &Value, iterable: &Value, adder: &str, entries: bool) -> Result<(), String> {
+    if matches!(iterable, Value::Undefined | Value::Null) {
+        return Ok(());
+    }
+    let function = interpreter.get_member(collection, &PropertyKey::from(adder))?;
+    if !matches!(function, Value::Function(_)) {
+        return Err(type_error(format!(
+            "'{}' returned for property '{}' of object '{}' is not a function",
+            interpreter.to_display_string(&function),
+            adder,
+            receiver_description(interpreter, collection)
+        )));
+    }
+    let mut iteration = interpreter.iterate(iterable)?;
+    while let Some(item) = interpreter.iteration_step(&mut iteration)? {
+        // The iterator is closed if adding an entry throws
+        let added = add_entry(interpreter, collection, &function, item, entries);
+        if added.is_err() {
+            return interpreter.close_iteration(&mut iteration, added);
+        }
+    }
+    Ok(())
+}
+
+fn add_entry(interpreter: &mut Interpreter, collection: &Value, adder: &Value, item: Value, entries: bool) -> Result<(), String> {
+    let args = if entries {
+        if !matches!(item, Value::Object(_) | Value::Function(_)) {
+            return Err(type_error(format!("Iterator value {} is not an entry object", interpreter.to_display_string(&item))));
+        }
+        let key = interpreter.get_member(&item, &PropertyKey::Index(0))?;
+        let value = interpreter.get_member(&item, &PropertyKey::Index(1))?;
+        vec![key, value]
+    } else {
+        vec![item]
+    };
+    interpreter.call_function(adder, collection.clone(), args)?;
+    Ok(())
+}
+
+/// The table of the `Map` (or `Set`, when `set` is true) a method was
+/// called on.
+fn this_table<'a>(interpreter: &'a mut Interpreter, this: &Value, set: bool, method: &str) -> Result<&'a mut OrderedTable, String> {
+    if let Value::Object(id) = this {
+        match &interpreter.heap.get(*id).kind {
+            ObjectKind::Map(_) if !set => {}
+            ObjectKind::Set(_) if set => {}
+            _ => return Err(incompatible_receiver(interpreter, method, this)),
+        }
+        let (ObjectKind::Map(table) | ObjectKind::Set(table)) = &mut interpreter.heap.get_mut(*id).kind else {
+            unreachable!()
+        };
+        return Ok(table);
+    }
+    Err(incompatible_receiver(interpreter, method, this))
+}
+
+/// Calls `callback(value, key, collection)` for each entry, including
+/// entries added during the loop.
+fn for_each(interpreter: &mut Interpreter, this: &Value, args: &[Value], set: bool) -> Result<Value, String> {
+    let method = if set { "Set.prototype.forEach" } else { "Map.prototype.forEach" };
+    let cursor = this_table(interpreter, this, set, method)?.cursor();
+    let callback = arg(args, 0);
+    if !matches!(callback, Value::Function(_)) {
+        return Err(type_error(format!("{} is not a function", interpreter.to_display_string(&callback))));
+    }
+    let this_arg = arg(args, 1);
+    loop {
+        let Some((key, value)) = this_table(interpreter, this, set, method)?.next_entry(&cursor) else {
+            return Ok(Value::Undefined);
+        };
+        interpreter.call_function(&callback, this_arg.clone(), vec![value, key, this.clone()])?;
+    }
+}
+
+fn iterator(interpreter: &mut Interpreter, this: &Value, set: bool, method: &str, kind: IterationKind) -> Result<Value, String> {
+    this_table(interpreter, this, set, method)?;
+    let Value::Object(id) = this else { unreachable!() };
+    Ok(Value::Object(interpreter.create_collection_iterator(*id, kind)))
+}
+
+pub(super) fn map_iterator_next(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    iterator_next(interpreter, this, "Map Iterator")
+}
+
+pub(super) fn set_iterator_next(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    iterator_next(interpreter, this, "Set Iterator")
+}
+
+fn iterator_next(interpreter: &mut Interpreter, this: &Value, name: &str) -> Result<Value, String> {
+    let of_map = name == "Map Iterator";
+    let id = match this {
+        Value::Object(id) => match &interpreter.heap.get(*id).kind {
+            ObjectKind::CollectionIterator(iterator) if iterator.of_map == of_map => *id,
+            _ => return Err(type_error(format!("Method {}.prototype.next called on incompatible receiver #<Object>", name))),
+        },
+        _ => return Err(incompatible_receiver(interpreter, &format!("{}.prototype.next", name), this)),
+    };
+    Ok(match interpreter.collection_iterator_next(id) {
+        Some(value) => interpreter.create_iterator_result(value, false),
+        None => interpreter.create_iterator_result(Value::Undefined, true),
+    })
+}
+
+fn install_map(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.map_prototype;
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("clear", 0, map_clear),
+        ("delete", 1, map_delete),
+        ("entries", 0, map_entries),
+        ("forEach", 1, |i, this, args| for_each(i, this, args, false)),
+        ("get", 1, map_get),
+        ("has", 1, map_has),
+        ("keys", 0, |i, this, _| iterator(i, this, false, "Map.prototype.keys", IterationKind::Keys)),
+        ("set", 2, map_set),
+        ("values", 0, |i, this, _| iterator(i, this, false, "Map.prototype.values", IterationKind::Values)),
+    ];
+    install_collection(interpreter, "Map", map_constructor, prototype, methods);
+    define_getter(interpreter, prototype, "size", |i, this, _| {
+        Ok(Value::Number(this_table(i, this, false, "get Map.prototype.size")?.len() as f64))
+    });
+    let iterator = PropertyKey::Symbol(interpreter.symbols.iterator.clone());
+    define_alias(interpreter, prototype, iterator, "entries");
+}
+
+fn map_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let fallback = interpreter.intrinsics.map_prototype;
+    let map = construct(interpreter, "Map", ObjectKind::Map(OrderedTable::default()), fallback)?;
+    add_entries(interpreter, &map, &arg(args, 0), "set", true)?;
+    Ok(map)
+}
+
+fn map_get(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let table = this_table(interpreter, this, false, "Map.prototype.get")?;
+    Ok(table.get(&arg(args, 0)).cloned().unwrap_or(Value::Undefined))
+}
+
+fn map_has(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(this_table(interpreter, this, false, "Map.prototype.has")?.has(&arg(args, 0))))
+}
+
+fn map_set(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    this_table(interpreter, this, false, "Map.prototype.set")?.set(arg(args, 0), arg(args, 1));
+    Ok(this.clone())
+}
+
+fn map_delete(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(this_table(interpreter, this, false, "Map.prototype.delete")?.delete(&arg(args, 0))))
+}
+
+fn map_clear(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    this_table(interpreter, this, false, "Map.prototype.clear")?.clear();
+    Ok(Value::Undefined)
+}
+
+fn map_entries(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    iterator(interpreter, this, false, "Map.prototype.entries", IterationKind::Entries)
+}
+
+fn install_set(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.set_prototype;
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("add", 1, set_add),
+        ("clear", 0, set_clear),
+        ("delete", 1, set_delete),
+        ("entries", 0, |i, this, _| iterator(i, this, true, "Set.prototype.entries", IterationKind::Entries)),
+        ("forEach", 1, |i, this, args| for_each(i, this, args, true)),
+        ("has", 1, set_has),
+        ("values", 0, set_values),
+    ];
+    install_collection(interpreter, "Set", set_constructor, prototype, methods);
+    define_getter(interpreter, prototype, "size", |i, this, _| {
+        Ok(Value::Number(this_table(i, this, true, "get Set.prototype.size")?.len() as f64))
+    });
+    // `keys` is the same function as `values`
+    define_alias(interpreter, prototype, PropertyKey::from("keys"), "values");
+    let iterator = PropertyKey::Symbol(interpreter.symbols.iterator.clone());
+    define_alias(interpreter, prototype, iterator, "values");
+}
+
+fn set_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let fallback = interpreter.intrinsics.set_prototype;
+    let set = construct(interpreter, "Set", ObjectKind::Set(OrderedTable::default()), fallback)?;
+    add_entries(interpreter, &set, &arg(args, 0), "add", false)?;
+    Ok(set)
+}
+
+fn set_add(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    // Like map keys, set values are stored with `-0` as `+0`
+    let value = match arg(args, 0) {
+        Value::Number(n) => Value::Number(n + 0.0),
+        value => value,
+    };
+    this_table(interpreter, this, true, "Set.prototype.add")?.set(value.clone(), value);
+    Ok(this.clone())
+}
+
+fn set_has(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(this_table(interpreter, this, true, "Set.prototype.has")?.has(&arg(args, 0))))
+}
+
+fn set_delete(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(this_table(interpreter, this, true, "Set.prototype.delete")?.delete(&arg(args, 0))))
+}
+
+fn set_clear(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    this_table(interpreter, this, true, "Set.prototype.clear")?.clear();
+    Ok(Value::Undefined)
+}
+
+fn set_values(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    iterator(interpreter, this, true, "Set.prototype.values", IterationKind::Values)
+}
+
+/// The object a weak collection is keyed by, `None` for primitives,
+/// which can never be collected.
+fn weak_key(value: &Value) -> Option<ObjectId> {
+    match value {
+        Value::Object(id) | Value::Function(id) => Some(*id),
+        _ => None,
+    }
+}
+
+fn this_weak_map<'a>(interpreter: &'a mut Interpreter, this: &Value, method: &str) -> Result<&'a mut HashMap<ObjectId, Value>, String> {
+    if let Value::Object(id) = this {
+        if matches!(interpreter.heap.get(*id).kind, ObjectKind::WeakMap(_)) {
+            let ObjectKind::WeakMap(entries) = &mut interpreter.heap.get_mut(*id).kind else { unreachable!() };
+            return Ok(entries);
+        }
+    }
+    Err(incompatible_receiver(interpreter, method, this))
+}
+
+fn this_weak_set<'a>(interpreter: &'a mut Interpreter, this: &Value, method: &str) -> Result<&'a mut HashSet<ObjectId>, String> {
+    if let Value::Object(id) = this {
+        if matches!(interpreter.heap.get(*id).kind, ObjectKind::WeakSet(_)) {
+            let ObjectKind::WeakSet(keys) = &mut interpreter.heap.get_mut(*id).kind else { unreachable!() };
+            return Ok(keys);
+        }
+    }
+    Err(incompatible_receiver(interpreter, method, this))
+}
+
+fn install_weak_map(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.weak_map_prototype;
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("delete", 1, weak_map_delete),
+        ("get", 1, weak_map_get),
+        ("has", 1, weak_map_has),
+        ("set", 2, weak_map_set),
+    ];
+    install_collection(interpreter, "WeakMap", weak_map_constructor, prototype, methods);
+}
+
+fn weak_map_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let fallback = interpreter.intrinsics.weak_map_prototype;
+    let map = construct(interpreter, "WeakMap", ObjectKind::WeakMap(HashMap::new()), fallback)?;
+    add_entries(interpreter, &map, &arg(args, 0), "set", true)?;
+    Ok(map)
+}
+
+fn weak_map_get(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let entries = this_weak_map(interpreter, this, "WeakMap.prototype.get")?;
+    Ok(weak_key(&arg(args, 0)).and_then(|key| entries.get(&key).cloned()).unwrap_or(Value::Undefined))
+}
+
+fn weak_map_has(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let entries = this_weak_map(interpreter, this, "WeakMap.prototype.has")?;
+    Ok(Value::Boolean(weak_key(&arg(args, 0)).is_some_and(|key| entries.contains_key(&key))))
+}
+
+fn weak_map_set(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let entries = this_weak_map(interpreter, this, "WeakMap.prototype.set")?;
+    let key = weak_key(&arg(args, 0)).ok_or_else(|| type_error("Invalid value used as weak map key"))?;
+    entries.insert(key, arg(args, 1));
+    Ok(this.clone())
+}
+
+fn weak_map_delete(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let entries = this_weak_map(interpreter, this, "WeakMap.prototype.delete")?;
+    Ok(Value::Boolean(weak_key(&arg(args, 0)).is_some_and(|key| entries.remove(&key).is_some())))
+}
+
+fn install_weak_set(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.weak_set_prototype;
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("add", 1, weak_set_add),
+        ("delete", 1, weak_set_delete),
+        ("has", 1, weak_set_has),
+    ];
+    install_collection(interpreter, "WeakSet", weak_set_constructor, prototype, methods);
+}
+
+fn weak_set_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let fallback = interpreter.intrinsics.weak_set_prototype;
+    let set = construct(interpreter, "WeakSet", ObjectKind::WeakSet(HashSet::new()), fallback)?;
+    add_entries(interpreter, &set, &arg(args, 0), "add", false)?;
+    Ok(set)
+}
+
+fn weak_set_add(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let keys = this_weak_set(interpreter, this, "WeakSet.prototype.add")?;
+    let key = weak_key(&arg(args, 0)).ok_or_else(|| type_error("Invalid value used in weak set"))?;
+    keys.insert(key);
+    Ok(this.clone())
+}
+
+fn weak_set_has(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let keys = this_weak_set(interpreter, this, "WeakSet.prototype.has")?;
+    Ok(Value::Boolean(weak_key(&arg(args, 0)).is_some_and(|key| keys.contains(&key))))
+}
+
+fn weak_set_delete(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let keys = this_weak_set(interpreter, this, "WeakSet.prototype.delete")?;
+    Ok(Value::Boolean(weak_key(&arg(args, 0)).is_some_and(|key| keys.remove(&key))))
+}
@@ -0,0 +1,417 @@
+//! `console`, writing to sinks that embedders can replace.
+//!
+//! Messages are formatted as in Node: a leading string may contain `%s`,
+//! `%d`, `%i`, `%f`, `%j`, `%o`, `%O` and `%c` specifiers, strings are
+//! printed as they are and other values are inspected.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Instant;
+
+use super::json::to_json_string;
+use super::{arg, define_global, define_method};
+use crate::interpreter::inspect::{format_number, InspectOptions};
+use crate::interpreter::object::{NativeFunction, ObjectId, ObjectKind, PropertyKey, PropertyValue};
+use crate::interpreter::{Interpreter, Value};
+
+/// The output streams and bookkeeping behind `console`.
+pub(crate) struct Console {
+    pub stdout: Box<dyn Write>,
+    pub stderr: Box<dyn Write>,
+    /// Levels of `console.group` currently open
+    group_depth: usize,
+    counts: HashMap<String, u32>,
+    timers: HashMap<String, Instant>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            stdout: Box::new(std::io::stdout()),
+            stderr: Box::new(std::io::stderr()),
+            group_depth: 0,
+            counts: HashMap::new(),
+            timers: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let console = interpreter.create_object();
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("assert", 0, assert),
+        ("count", 0, count),
+        ("countReset", 0, count_reset),
+        ("debug", 0, log),
+        ("dir", 0, dir),
+        ("error", 0, error),
+        ("group", 0, group),
+        ("groupCollapsed", 0, group),
+        ("groupEnd", 0, group_end),
+        ("info", 0, log),
+        ("log", 0, log),
+        ("table", 1, table),
+        ("time", 0, time),
+        ("timeEnd", 0, time_end),
+        ("timeLog", 0, time_log),
+        ("warn", 0, error),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, console, name, length, function);
+    }
+    let console = interpreter.object_value(console);
+    define_global(interpreter, "console", console);
+}
+
+/// Writes `text` as one message, indented by the open groups.
+fn print(interpreter: &mut Interpreter, stream: Stream, text: &str) {
+    let indentation = "  ".repeat(interpreter.console.group_depth);
+    let mut message = String::new();
+    for line in text.split('\n') {
+        message.push_str(&indentation);
+        message.push_str(line);
+        message.push('\n');
+    }
+    let sink = match stream {
+        Stream::Stdout => &mut interpreter.console.stdout,
+        Stream::Stderr => &mut interpreter.console.stderr,
+    };
+    // Like Node, a console that cannot be written to is not an error
+    let _ = sink.write_all(message.as_bytes()).and_then(|()| sink.flush());
+}
+
+/// Joins the arguments of `console.log` into a message, applying format
+/// specifiers in a leading string.
+fn format_args(interpreter: &mut Interpreter, args: &[Value]) -> Result<String, String> {
+    let mut rest = args.iter();
+    let mut parts = Vec::new();
+    if let Some(Value::String(format)) = args.first() {
+        rest.next();
+        let mut message = String::new();
+        let format = format.to_string();
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            let specifier = match (c, chars.peek()) {
+                ('%', Some(&specifier)) if "sdifjoOc%".contains(specifier) => specifier,
+                _ => {
+                    message.push(c);
+                    continue;
+                }
+            };
+            if specifier == '%' {
+                chars.next();
+                message.push('%');
+                continue;
+            }
+            let Some(value) = rest.next() else {
+                message.push(c);
+                continue;
+            };
+            chars.next();
+            message.push_str(&format_specifier(interpreter, specifier, value)?);
+        }
+        parts.push(message);
+    }
+    for value in rest {
+        parts.push(match value {
+            Value::String(s) => s.to_string(),
+            other => interpreter.inspect(other, InspectOptions::default()),
+        });
+    }
+    Ok(parts.join(" "))
+}
+
+fn format_specifier(interpreter: &mut Interpreter, specifier: char, value: &Value) -> Result<String, String> {
+    Ok(match specifier {
+        's' => match value {
+            Value::String(s) => s.to_string(),
+            Value::Number(n) => format_number(*n),
+            Value::BigInt(n) => format!("{}n", n),
+            Value::Object(id) if has_custom_to_string(interpreter, *id) => interpreter.coerce_to_string(value)?.to_string(),
+            Value::Function(_) => interpreter.coerce_to_string(value)?.to_string(),
+            Value::Object(_) => interpreter.inspect(value, InspectOptions { depth: Some(0), ..InspectOptions::default() }),
+            other => other.to_js_string(),
+        },
+        'd' | 'i' if matches!(value, Value::BigInt(_)) => format!("{}n", value.to_js_string()),
+        'd' | 'i' | 'f' => {
+            let n = interpreter.coerce_to_number(value)?;
+            format_number(if specifier == 'i' { n.trunc() } else { n })
+        }
+        // Like Node, a cycle is reported in place of the text
+        'j' => match to_json_string(interpreter, value) {
+            Ok(json) => json.unwrap_or_else(|| "undefined".to_string()),
+            Err(error) if error.contains("circular structure") => "[Circular]".to_string(),
+            Err(error) => return Err(error),
+        },
+        'o' => interpreter.inspect(value, InspectOptions { depth: Some(4), ..InspectOptions::default() }),
+        'O' => interpreter.inspect(value, InspectOptions::default()),
+        // CSS styling does not apply to a terminal
+        _ => String::new(),
+    })
+}
+
+/// Whether an object's `toString` comes from user code rather than a
+/// built-in prototype, in which case `%s` calls it.
+fn has_custom_to_string(interpreter: &Interpreter, id: ObjectId) -> bool {
+    match interpreter.heap.find_property(id, &PropertyKey::from("toString")).map(|property| &property.value) {
+        Some(PropertyValue::Data(Value::Function(function))) => {
+            !matches!(interpreter.heap.get(*function).kind, ObjectKind::NativeFunction(_))
+        }
+        _ => false,
+    }
+}
+
+/// The label argument of `count` and `time`, `"default"` when missing.
+fn label(interpreter: &mut Interpreter, args: &[Value]) -> Result<String, String> {
+    match arg(args, 0) {
+        Value::Undefined => Ok("default".to_string()),
+        label => Ok(interpreter.coerce_to_string(&label)?.to_string()),
+    }
+}
+
+fn log(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let message = format_args(interpreter, args)?;
+    print(interpreter, Stream::Stdout, &message);
+    Ok(Value::Undefined)
+}
+
+fn error(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let message = format_args(interpreter, args)?;
+    print(interpreter, Stream::Stderr, &message);
+    Ok(Value::Undefined)
+}
+
+/// `console.dir(value, { depth })` inspects even strings.
+fn dir(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let mut options = InspectOptions::default();
+    if let options_value @ Value::Object(_) = arg(args, 1) {
+        match interpreter.get_member(&options_value, &PropertyKey::from("depth"))? {
+            Value::Null => options.depth = None,
+            Value::Number(depth) if depth == f64::INFINITY => options.depth = None,
+            Value::Number(depth) if depth >= 0.0 => options.depth = Some(depth as usize),
+            _ => {}
+        }
+    }
+    let message = interpreter.inspect(&arg(args, 0), options);
+    print(interpreter, Stream::Stdout, &message);
+    Ok(Value::Undefined)
+}
+
+fn assert(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if arg(args, 0).is_truthy() {
+        return Ok(Value::Undefined);
+    }
+    let mut data = args.iter().skip(1).cloned().collect::<Vec<_>>();
+    match data.first_mut() {
+        Some(first) => *first = Value::String(format!("Assertion failed: {}", interpreter.coerce_to_string(first)?).into()),
+        None => data.push(Value::String("Assertion failed".into())),
+    }
+    let message = format_args(interpreter, &data)?;
+    print(interpreter, Stream::Stderr, &message);
+    Ok(Value::Undefined)
+}
+
+fn count(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let label = label(interpreter, args)?;
+    let count = interpreter.console.counts.entry(label.clone()).or_insert(0);
+    *count += 1;
+    let message = format!("{}: {}", label, count);
+    print(interpreter, Stream::Stdout, &message);
+    Ok(Value::Undefined)
+}
+
+fn count_reset(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let label = label(interpreter, args)?;
+    match interpreter.console.counts.get_mut(&label) {
+        Some(count) => *count = 0,
+        None => print(interpreter, Stream::Stderr, &format!("Count for '{}' does not exist", label)),
+    }
+    Ok(Value::Undefined)
+}
+
+fn group(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if !args.is_empty() {
+        let message = format_args(interpreter, args)?;
+        print(interpreter, Stream::Stdout, &message);
+    }
+    interpreter.console.group_depth += 1;
+    Ok(Value::Undefined)
+}
+
+fn group_end(interpreter: &mut Interpreter, _this: &Value, _args: &[Value]) -> Result<Value, String> {
+    interpreter.console.group_depth = interpreter.console.group_depth.saturating_sub(1);
+    Ok(Value::Undefined)
+}
+
+fn time(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let label = label(interpreter, args)?;
+    match interpreter.console.timers.entry(label) {
+        Entry::Occupied(entry) => {
+            let message = format!("Warning: Label '{}' already exists for console.time()", entry.key());
+            print(interpreter, Stream::Stderr, &message);
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(Instant::now());
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+fn time_end(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let label = label(interpreter, args)?;
+    match interpreter.console.timers.remove(&label) {
+        Some(start) => print(interpreter, Stream::Stdout, &format!("{}: {}", label, format_duration(start))),
+        None => print(interpreter, Stream::Stderr, &format!("Warning: No such label '{}' for console.timeEnd()", label)),
+    }
+    Ok(Value::Undefined)
+}
+
+/// `console.timeLog(label, ...data)` prints the time so far and keeps the
+/// timer running.
+fn time_log(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let label = label(interpreter, args)?;
+    let Some(start) = interpreter.console.timers.get(&label).copied() else {
+        print(interpreter, Stream::Stderr, &format!("Warning: No such label '{}' for console.timeLog()", label));
+        return Ok(Value::Undefined);
+    };
+    let mut message = format!("{}: {}", label, format_duration(start));
+    if args.len() > 1 {
+        message.push(' ');
+        message.push_str(&format_args(interpreter, &args[1..])?);
+    }
+    print(interpreter, Stream::Stdout, &message);
+    Ok(Value::Undefined)
+}
+
+/// The time since `start` as Node prints it: `1.5ms`, `2.250s` or
+/// `1:05.000 (m:ss.mmm)`.
+fn format_duration(start: Instant) -> String {
+    let ms = start.elapsed().as_secs_f64() * 1000.0;
+    if ms < 1000.0 {
+        return format!("{}ms", format_number((ms * 1000.0).round() / 1000.0));
+    }
+    let minutes = (ms / 60_000.0).floor();
+    if minutes == 0.0 {
+        return format!("{:.3}s", ms / 1000.0);
+    }
+    let hours = (minutes / 60.0).floor();
+    let seconds = format!("{:06.3}", (ms % 60_000.0) / 1000.0);
+    if hours == 0.0 {
+        format!("{}:{} (m:ss.mmm)", minutes, seconds)
+    } else {
+        format!("{}:{:02}:{} (h:mm:ss.mmm)", hours, minutes % 60.0, seconds)
+    }
+}
+
+/// `console.table(data, columns)` prints the rows of `data` in a grid,
+/// with a column for each property of the rows and a `Values` column for
+/// rows that are primitives.
+fn table(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let data = arg(args, 0);
+    let Value::Object(data_id) = data else {
+        return log(interpreter, this, args);
+    };
+    let filter = match arg(args, 1) {
+        columns @ Value::Object(_) => {
+            let mut names = Vec::new();
+            for column in interpreter.collect_iterable(&columns)? {
+                names.push(interpreter.coerce_to_string(&column)?);
+            }
+            Some(names)
+        }
+        _ => None,
+    };
+
+    let enumerable_keys = |interpreter: &Interpreter, id: ObjectId| {
+        let object = interpreter.heap.get(id);
+        object.own_keys().into_iter().filter(|key| object.is_enumerable(key)).collect::<Vec<_>>()
+    };
+    let row_keys = enumerable_keys(interpreter, data_id);
+    let mut columns: Vec<(String, HashMap<usize, String>)> = Vec::new();
+    let mut values = HashMap::new();
+    for (row, key) in row_keys.iter().enumerate() {
+        let item = interpreter.get_member(&data, key)?;
+        let Value::Object(item_id) = item else {
+            values.insert(row, table_cell(interpreter, &item));
+            continue;
+        };
+        let keys = match &filter {
+            Some(names) => names.iter().map(PropertyKey::from).collect(),
+            None => enumerable_keys(interpreter, item_id),
+        };
+        for key in keys {
+            let name = key.to_string();
+            let column = match columns.iter().position(|(column, _)| *column == name) {
+                Some(position) => position,
+                None => {
+                    columns.push((name, HashMap::new()));
+                    columns.len() - 1
+                }
+            };
+            if interpreter.heap.get(item_id).properties.contains_key(&key) {
+                let value = interpreter.get_member(&item, &key)?;
+                columns[column].1.insert(row, table_cell(interpreter, &value));
+            }
+        }
+    }
+
+    let mut head = vec!["(index)".to_string()];
+    let mut cells: Vec<Vec<String>> = row_keys.iter().map(|key| vec![key.to_string()]).collect();
+    for (name, column) in &columns {
+        head.push(name.clone());
+        for (row, line) in cells.iter_mut().enumerate() {
+            line.push(column.get(&row).cloned().unwrap_or_default());
+        }
+    }
+    if !values.is_empty() {
+        head.push("Values".to_string());
+        for (row, line) in cells.iter_mut().enumerate() {
+            line.push(values.get(&row).cloned().unwrap_or_default());
+        }
+    }
+    let message = render_table(&head, &cells);
+    print(interpreter, Stream::Stdout, &message);
+    Ok(Value::Undefined)
+}
+
+/// A value as shown in a table cell: on one line, with nested objects only
+/// named and objects of more than two properties not expanded at all.
+fn table_cell(interpreter: &Interpreter, value: &Value) -> String {
+    if let Value::Object(id) = value {
+        let object = interpreter.heap.get(*id);
+        if !object.is_array() && object.own_keys().iter().filter(|key| object.is_enumerable(key)).count() > 2 {
+            return "[Object]".to_string();
+        }
+    }
+    interpreter.inspect(value, InspectOptions { depth: Some(0), max_array_length: 3, break_length: usize::MAX })
+}
+
+fn render_table(head: &[String], rows: &[Vec<String>]) -> String {
+    let width = |cell: &String| cell.chars().count();
+    let widths: Vec<usize> = (0..head.len())
+        .map(|column| rows.iter().map(|row| width(&row[column])).chain([width(&head[column])]).max().unwrap_or(0))
+        .collect();
+    let render_row = |row: &[String]| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| {
+                format!("{}{}", cell, " ".repeat(width - cell.chars().count()))
+            })
+            .collect();
+        format!("│ {} │", cells.join(" │ "))
+    };
+    let divider: Vec<String> = widths.iter().map(|width| "─".repeat(width + 2)).collect();
+
+    let mut lines = vec![format!("┌{}┐", divider.join("┬")), render_row(head), format!("├{}┤", divider.join("┼"))];
+    lines.extend(rows.iter().map(|row| render_row(row)));
+    lines.push(format!("└{}┘", divider.join("┴")));
+    lines.join("\n")
+}
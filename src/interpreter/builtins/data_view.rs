@@ -0,0 +1,120 @@
+//! `DataView`, which reads and writes numbers of any type at any offset
+//! into a buffer, big-endian unless asked otherwise.
+
+use super::{arg, define_getter, define_global, define_method, define_to_string_tag, incompatible_receiver, to_index};
+use crate::interpreter::object::{NativeFunction, Object, ObjectKind};
+use crate::interpreter::typed_array::{DataViewData, ElementType};
+use crate::interpreter::{number_to_string, range_error, type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.data_view_prototype;
+    let constructor = interpreter.create_native_function("DataView", 1, construct);
+    super::link_constructor(interpreter, &constructor, prototype);
+
+    let getters: &[(&str, NativeFunction)] = &[("buffer", buffer), ("byteLength", byte_length), ("byteOffset", byte_offset)];
+    for &(name, getter) in getters {
+        define_getter(interpreter, prototype, name, getter);
+    }
+    let accessors: [(ElementType, NativeFunction, NativeFunction); 10] = [
+        (ElementType::Int8, |i, this, args| get(i, this, args, ElementType::Int8), |i, this, args| set(i, this, args, ElementType::Int8)),
+        (ElementType::Uint8, |i, this, args| get(i, this, args, ElementType::Uint8), |i, this, args| set(i, this, args, ElementType::Uint8)),
+        (ElementType::Int16, |i, this, args| get(i, this, args, ElementType::Int16), |i, this, args| set(i, this, args, ElementType::Int16)),
+        (ElementType::Uint16, |i, this, args| get(i, this, args, ElementType::Uint16), |i, this, args| set(i, this, args, ElementType::Uint16)),
+        (ElementType::Int32, |i, this, args| get(i, this, args, ElementType::Int32), |i, this, args| set(i, this, args, ElementType::Int32)),
+        (ElementType::Uint32, |i, this, args| get(i, this, args, ElementType::Uint32), |i, this, args| set(i, this, args, ElementType::Uint32)),
+        (ElementType::Float32, |i, this, args| get(i, this, args, ElementType::Float32), |i, this, args| set(i, this, args, ElementType::Float32)),
+        (ElementType::Float64, |i, this, args| get(i, this, args, ElementType::Float64), |i, this, args| set(i, this, args, ElementType::Float64)),
+        (ElementType::BigInt64, |i, this, args| get(i, this, args, ElementType::BigInt64), |i, this, args| set(i, this, args, ElementType::BigInt64)),
+        (ElementType::BigUint64, |i, this, args| get(i, this, args, ElementType::BigUint64), |i, this, args| set(i, this, args, ElementType::BigUint64)),
+    ];
+    for (element, getter, setter) in accessors {
+        define_method(interpreter, prototype, &format!("get{}", element.name()), 1, getter);
+        define_method(interpreter, prototype, &format!("set{}", element.name()), 2, setter);
+    }
+    define_to_string_tag(interpreter, prototype, "DataView");
+    define_global(interpreter, "DataView", constructor);
+}
+
+/// `new DataView(buffer, byteOffset, byteLength)`.
+fn construct(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if interpreter.new_target.is_none() {
+        return Err(type_error("Constructor DataView requires 'new'"));
+    }
+    let buffer = match arg(args, 0) {
+        Value::Object(id) if matches!(interpreter.heap.get(id).kind, ObjectKind::ArrayBuffer(_)) => id,
+        _ => return Err(type_error("First argument to DataView constructor must be an ArrayBuffer")),
+    };
+    let outside = |offset: f64| range_error(format!("Start offset {} is outside the bounds of the buffer", number_to_string(offset)));
+    let byte_offset = to_index(interpreter, &arg(args, 1), outside)?;
+    let buffer_length = interpreter.buffer_bytes(buffer).len();
+    if byte_offset > buffer_length {
+        return Err(outside(byte_offset as f64));
+    }
+    let invalid_length = |length: f64| range_error(format!("Invalid DataView length {}", number_to_string(length)));
+    let byte_length = match arg(args, 2) {
+        Value::Undefined => buffer_length - byte_offset,
+        length => {
+            let length = to_index(interpreter, &length, invalid_length)?;
+            if length > buffer_length - byte_offset {
+                return Err(invalid_length(length as f64));
+            }
+            length
+        }
+    };
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.data_view_prototype)?;
+    let view = DataViewData { buffer, byte_offset, byte_length };
+    Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::DataView(view), Some(prototype)))))
+}
+
+/// The `DataView` a method was called on.
+fn this_view(interpreter: &Interpreter, this: &Value, method: &str) -> Result<DataViewData, String> {
+    if let Value::Object(id) = this {
+        if let ObjectKind::DataView(view) = interpreter.heap.get(*id).kind {
+            return Ok(view);
+        }
+    }
+    Err(incompatible_receiver(interpreter, &format!("DataView.prototype.{}", method), this))
+}
+
+fn buffer(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Object(this_view(interpreter, this, "buffer")?.buffer))
+}
+
+fn byte_length(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(this_view(interpreter, this, "byteLength")?.byte_length as f64))
+}
+
+fn byte_offset(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(this_view(interpreter, this, "byteOffset")?.byte_offset as f64))
+}
+
+/// The range of buffer bytes an access of `element` at `offset` covers.
+fn byte_range(view: DataViewData, offset: usize, element: ElementType) -> Result<std::ops::Range<usize>, String> {
+    if offset.saturating_add(element.size()) > view.byte_length {
+        return Err(range_error("Offset is outside the bounds of the DataView"));
+    }
+    let start = view.byte_offset + offset;
+    Ok(start..start + element.size())
+}
+
+/// `getInt8(byteOffset)`, `getFloat64(byteOffset, littleEndian)` and the
+/// other getters.
+fn get(interpreter: &mut Interpreter, this: &Value, args: &[Value], element: ElementType) -> Result<Value, String> {
+    let view = this_view(interpreter, this, &format!("get{}", element.name()))?;
+    let offset = to_index(interpreter, &arg(args, 0), |_| range_error("Offset is outside the bounds of the DataView"))?;
+    let little_endian = arg(args, 1).is_truthy();
+    let range = byte_range(view, offset, element)?;
+    Ok(element.decode(&interpreter.buffer_bytes(view.buffer)[range], little_endian))
+}
+
+/// `setInt8(byteOffset, value)`, `setFloat64(byteOffset, value,
+/// littleEndian)` and the other setters.
+fn set(interpreter: &mut Interpreter, this: &Value, args: &[Value], element: ElementType) -> Result<Value, String> {
+    let view = this_view(interpreter, this, &format!("set{}", element.name()))?;
+    let offset = to_index(interpreter, &arg(args, 0), |_| range_error("Offset is outside the bounds of the DataView"))?;
+    let value = interpreter.coerce_to_element(element, &arg(args, 1))?;
+    let little_endian = arg(args, 2).is_truthy();
+    let range = byte_range(view, offset, element)?;
+    element.encode(&value, &mut interpreter.buffer_bytes_mut(view.buffer)[range], little_endian);
+    Ok(Value::Undefined)
+}
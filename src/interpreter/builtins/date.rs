@@ -0,0 +1,276 @@
+//! `Date` and `Date.prototype`.
+
+use super::{arg, define_alias, define_global, define_method};
+use crate::interpreter::date::{format_iso, format_utc, from_fields, time_clip, Fields};
+use crate::interpreter::object::{Attributes, NativeFunction, Object, ObjectKind, Property, PropertyKey};
+use crate::interpreter::{range_error, type_error, Interpreter, PreferredType, Value};
+
+/// The calendar fields, in the order the constructor and setters take
+/// them.
+#[derive(Clone, Copy)]
+enum Field {
+    Year,
+    Month,
+    Date,
+    Hours,
+    Minutes,
+    Seconds,
+    Milliseconds,
+    /// Only read, by `getDay`
+    Weekday,
+}
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.date_prototype;
+    let constructor = interpreter.create_native_function("Date", 7, date_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+    let statics: &[(&str, u32, NativeFunction)] = &[("UTC", 7, utc), ("now", 0, now), ("parse", 1, parse)];
+    for &(name, length, function) in statics {
+        define_method(interpreter, constructor_id, name, length, function);
+    }
+
+    let getters: &[(&str, NativeFunction)] = &[
+        ("getDate", |i, this, _| get(i, this, Field::Date, true)),
+        ("getDay", |i, this, _| get(i, this, Field::Weekday, true)),
+        ("getFullYear", |i, this, _| get(i, this, Field::Year, true)),
+        ("getHours", |i, this, _| get(i, this, Field::Hours, true)),
+        ("getMilliseconds", |i, this, _| get(i, this, Field::Milliseconds, true)),
+        ("getMinutes", |i, this, _| get(i, this, Field::Minutes, true)),
+        ("getMonth", |i, this, _| get(i, this, Field::Month, true)),
+        ("getSeconds", |i, this, _| get(i, this, Field::Seconds, true)),
+        ("getTime", value_of),
+        ("getTimezoneOffset", get_timezone_offset),
+        ("getUTCDate", |i, this, _| get(i, this, Field::Date, false)),
+        ("getUTCDay", |i, this, _| get(i, this, Field::Weekday, false)),
+        ("getUTCFullYear", |i, this, _| get(i, this, Field::Year, false)),
+        ("getUTCHours", |i, this, _| get(i, this, Field::Hours, false)),
+        ("getUTCMilliseconds", |i, this, _| get(i, this, Field::Milliseconds, false)),
+        ("getUTCMinutes", |i, this, _| get(i, this, Field::Minutes, false)),
+        ("getUTCMonth", |i, this, _| get(i, this, Field::Month, false)),
+        ("getUTCSeconds", |i, this, _| get(i, this, Field::Seconds, false)),
+        ("valueOf", value_of),
+    ];
+    for &(name, function) in getters {
+        define_method(interpreter, prototype, name, 0, function);
+    }
+
+    let setters: &[(&str, u32, NativeFunction)] = &[
+        ("setDate", 1, |i, this, args| set(i, this, args, Field::Date, true)),
+        ("setFullYear", 3, |i, this, args| set(i, this, args, Field::Year, true)),
+        ("setHours", 4, |i, this, args| set(i, this, args, Field::Hours, true)),
+        ("setMilliseconds", 1, |i, this, args| set(i, this, args, Field::Milliseconds, true)),
+        ("setMinutes", 3, |i, this, args| set(i, this, args, Field::Minutes, true)),
+        ("setMonth", 2, |i, this, args| set(i, this, args, Field::Month, true)),
+        ("setSeconds", 2, |i, this, args| set(i, this, args, Field::Seconds, true)),
+        ("setTime", 1, set_time),
+        ("setUTCDate", 1, |i, this, args| set(i, this, args, Field::Date, false)),
+        ("setUTCFullYear", 3, |i, this, args| set(i, this, args, Field::Year, false)),
+        ("setUTCHours", 4, |i, this, args| set(i, this, args, Field::Hours, false)),
+        ("setUTCMilliseconds", 1, |i, this, args| set(i, this, args, Field::Milliseconds, false)),
+        ("setUTCMinutes", 3, |i, this, args| set(i, this, args, Field::Minutes, false)),
+        ("setUTCMonth", 2, |i, this, args| set(i, this, args, Field::Month, false)),
+        ("setUTCSeconds", 2, |i, this, args| set(i, this, args, Field::Seconds, false)),
+    ];
+    for &(name, length, function) in setters {
+        define_method(interpreter, prototype, name, length, function);
+    }
+
+    let formats: &[(&str, u32, NativeFunction)] = &[
+        ("toDateString", 0, |i, this, _| format(i, this, |i, t| i.format_date(t, true, false))),
+        ("toISOString", 0, to_iso_string),
+        ("toJSON", 1, to_json),
+        ("toLocaleDateString", 0, |i, this, _| format(i, this, |i, t| i.format_locale_date(t, true, false))),
+        ("toLocaleString", 0, |i, this, _| format(i, this, |i, t| i.format_locale_date(t, true, true))),
+        ("toLocaleTimeString", 0, |i, this, _| format(i, this, |i, t| i.format_locale_date(t, false, true))),
+        ("toString", 0, |i, this, _| format(i, this, |i, t| i.format_date(t, true, true))),
+        ("toTimeString", 0, |i, this, _| format(i, this, |i, t| i.format_date(t, false, true))),
+        ("toUTCString", 0, |i, this, _| format(i, this, |_, t| if t.is_nan() { "Invalid Date".to_string() } else { format_utc(t) })),
+    ];
+    for &(name, length, function) in formats {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    define_alias(interpreter, prototype, PropertyKey::from("toGMTString"), "toUTCString");
+    let to_primitive = interpreter.create_native_function("[Symbol.toPrimitive]", 1, to_primitive);
+    let key = PropertyKey::Symbol(interpreter.symbols.to_primitive.clone());
+    interpreter.heap.define_property(prototype, key, Property::data(to_primitive, Attributes::READ_ONLY));
+
+    define_global(interpreter, "Date", constructor);
+}
+
+/// `new Date()` is the current time, `new Date(value)` a time value, a
+/// parsed string or a copy of another date, and `new Date(year, month,
+/// ...)` a local time. Called without `new`, `Date()` is the current time
+/// as a string.
+fn date_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if interpreter.new_target.is_none() {
+        let now = interpreter.current_time();
+        return Ok(Value::String(interpreter.format_date(now, true, true).into()));
+    }
+    let t = match args {
+        [] => interpreter.current_time(),
+        [value] => match value {
+            Value::Object(id) if matches!(interpreter.heap.get(*id).kind, ObjectKind::Date(_)) => this_time(interpreter, value)?,
+            value => match interpreter.coerce_to_primitive(value.clone(), PreferredType::Default)? {
+                Value::String(s) => interpreter.parse_date(&s.to_string()),
+                primitive => time_clip(interpreter.coerce_to_number(&primitive)?),
+            },
+        },
+        args => {
+            let local = from_fields(fields_from_arguments(interpreter, args)?);
+            time_clip(interpreter.utc_time(local))
+        }
+    };
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.date_prototype)?;
+    let id = interpreter.heap.allocate(Object::new(ObjectKind::Date(t), Some(prototype)));
+    Ok(Value::Object(id))
+}
+
+/// The fields given to the constructor or `Date.UTC`, with years 0 to 99
+/// taken to mean 1900 to 1999.
+fn fields_from_arguments(interpreter: &mut Interpreter, args: &[Value]) -> Result<[f64; 7], String> {
+    let mut fields = [f64::NAN, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+    for (field, value) in fields.iter_mut().zip(args) {
+        *field = interpreter.coerce_to_number(value)?;
+    }
+    let year = fields[0].trunc();
+    if (0.0..=99.0).contains(&year) {
+        fields[0] = 1900.0 + year;
+    }
+    Ok(fields)
+}
+
+fn utc(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let fields = fields_from_arguments(interpreter, args)?;
+    Ok(Value::Number(time_clip(from_fields(fields))))
+}
+
+fn now(interpreter: &mut Interpreter, _this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(interpreter.current_time()))
+}
+
+fn parse(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = interpreter.coerce_to_string(&arg(args, 0))?;
+    Ok(Value::Number(interpreter.parse_date(&s.to_string())))
+}
+
+/// The time value of the date a `Date.prototype` method was called on.
+fn this_time(interpreter: &Interpreter, this: &Value) -> Result<f64, String> {
+    match this {
+        Value::Object(id) => match interpreter.heap.get(*id).kind {
+            ObjectKind::Date(t) => Ok(t),
+            _ => Err(type_error("this is not a Date object.")),
+        },
+        _ => Err(type_error("this is not a Date object.")),
+    }
+}
+
+fn value_of(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    this_time(interpreter, this).map(Value::Number)
+}
+
+/// The getters, in local time or UTC.
+fn get(interpreter: &mut Interpreter, this: &Value, field: Field, local: bool) -> Result<Value, String> {
+    let t = this_time(interpreter, this)?;
+    if t.is_nan() {
+        return Ok(Value::Number(f64::NAN));
+    }
+    let fields = Fields::of(if local { interpreter.local_time(t) } else { t });
+    Ok(Value::Number(match field {
+        Field::Weekday => fields.weekday,
+        field => fields.to_array()[field as usize],
+    }))
+}
+
+/// `getTimezoneOffset()`: how many minutes local time is behind UTC.
+fn get_timezone_offset(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let t = this_time(interpreter, this)?;
+    if t.is_nan() {
+        return Ok(Value::Number(f64::NAN));
+    }
+    Ok(Value::Number(-interpreter.time_zone_offset(t) / 60000.0 + 0.0))
+}
+
+/// Stores a new time value in a date, returning it.
+fn store(interpreter: &mut Interpreter, this: &Value, t: f64) -> Result<Value, String> {
+    if let Value::Object(id) = this {
+        interpreter.heap.get_mut(*id).kind = ObjectKind::Date(t);
+    }
+    Ok(Value::Number(t))
+}
+
+fn set_time(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    this_time(interpreter, this)?;
+    let t = time_clip(interpreter.coerce_to_number(&arg(args, 0))?);
+    store(interpreter, this, t)
+}
+
+/// The setters, which replace `first` and the fields after it that are
+/// passed, up to the number each setter takes, in local time or UTC.
+/// `setFullYear` on an invalid date starts from the epoch; the other
+/// setters leave it invalid.
+fn set(interpreter: &mut Interpreter, this: &Value, args: &[Value], first: Field, local: bool) -> Result<Value, String> {
+    let t = this_time(interpreter, this)?;
+    let base = match (t.is_nan(), first) {
+        (true, Field::Year) => 0.0,
+        (_, _) if local => interpreter.local_time(t),
+        _ => t,
+    };
+    let mut fields = if base.is_nan() { [f64::NAN; 7] } else { Fields::of(base).to_array() };
+    let count = match first {
+        Field::Hours => 4,
+        Field::Year | Field::Minutes => 3,
+        Field::Month | Field::Seconds => 2,
+        _ => 1,
+    };
+    for index in 0..args.len().clamp(1, count) {
+        fields[first as usize + index] = interpreter.coerce_to_number(&arg(args, index))?;
+    }
+    if base.is_nan() {
+        return Ok(Value::Number(f64::NAN));
+    }
+    let new = from_fields(fields);
+    let new = time_clip(if local { interpreter.utc_time(new) } else { new });
+    store(interpreter, this, new)
+}
+
+/// The string methods, which all print invalid dates the same way.
+fn format(interpreter: &mut Interpreter, this: &Value, formatter: fn(&Interpreter, f64) -> String) -> Result<Value, String> {
+    let t = this_time(interpreter, this)?;
+    Ok(Value::String(formatter(interpreter, t).into()))
+}
+
+fn to_iso_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let t = this_time(interpreter, this)?;
+    if t.is_nan() {
+        return Err(range_error("Invalid time value"));
+    }
+    Ok(Value::String(format_iso(t).into()))
+}
+
+/// `toJSON` is generic: it calls `toISOString` on anything whose number
+/// value is finite.
+fn to_json(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    if matches!(this, Value::Null | Value::Undefined) {
+        return Err(type_error("Date.prototype.toJSON called on null or undefined"));
+    }
+    let primitive = interpreter.coerce_to_primitive(this.clone(), PreferredType::Number)?;
+    if matches!(primitive, Value::Number(n) if !n.is_finite()) {
+        return Ok(Value::Null);
+    }
+    interpreter.invoke(this, "toISOString", Vec::new())
+}
+
+/// `Date.prototype[Symbol.toPrimitive](hint)`: dates convert to strings
+/// unless a number is asked for.
+fn to_primitive(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    if !matches!(this, Value::Object(_) | Value::Function(_)) {
+        return Err(type_error("Date.prototype [ @@toPrimitive ] called on non-object"));
+    }
+    let preferred = match arg(args, 0) {
+        Value::String(hint) if hint == "string" || hint == "default" => PreferredType::String,
+        Value::String(hint) if hint == "number" => PreferredType::Number,
+        hint => return Err(type_error(format!("Invalid hint: {}", hint.to_js_string()))),
+    };
+    interpreter.ordinary_to_primitive(this.clone(), preferred)
+}
@@ -0,0 +1,171 @@
+//! `TextEncoder` and `TextDecoder`, which convert between strings and the
+//! bytes of a buffer. Encoding is always to UTF-8; decoding also
+//! understands UTF-16 in either byte order.
+
+use super::{arg, define_getter, define_global, define_method, define_to_string_tag, incompatible_receiver};
+use crate::interpreter::encoding::{Encoding, TextDecoder};
+use crate::interpreter::object::{Attributes, NativeFunction, Object, ObjectId, ObjectKind, Property, PropertyKey};
+use crate::interpreter::typed_array::ElementType;
+use crate::interpreter::{range_error, type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.text_encoder_prototype;
+    let constructor = interpreter.create_native_function("TextEncoder", 0, encoder_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    define_getter(interpreter, prototype, "encoding", encoder_encoding);
+    define_method(interpreter, prototype, "encode", 0, encode);
+    define_method(interpreter, prototype, "encodeInto", 2, encode_into);
+    define_to_string_tag(interpreter, prototype, "TextEncoder");
+    define_global(interpreter, "TextEncoder", constructor);
+
+    let prototype = interpreter.intrinsics.text_decoder_prototype;
+    let constructor = interpreter.create_native_function("TextDecoder", 0, decoder_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let getters: &[(&str, NativeFunction)] = &[("encoding", decoder_encoding), ("fatal", fatal), ("ignoreBOM", ignore_bom)];
+    for &(name, getter) in getters {
+        define_getter(interpreter, prototype, name, getter);
+    }
+    define_method(interpreter, prototype, "decode", 0, decode);
+    define_to_string_tag(interpreter, prototype, "TextDecoder");
+    define_global(interpreter, "TextDecoder", constructor);
+}
+
+fn require_new(interpreter: &Interpreter, name: &str) -> Result<(), String> {
+    match interpreter.new_target {
+        Some(_) => Ok(()),
+        None => Err(type_error(format!("Class constructor {} cannot be invoked without 'new'", name))),
+    }
+}
+
+/// Reads the member `name` of an options argument, which may be left out.
+fn option(interpreter: &mut Interpreter, options: &Value, name: &str) -> Result<Value, String> {
+    match options {
+        Value::Undefined | Value::Null => Ok(Value::Undefined),
+        Value::Object(_) | Value::Function(_) => interpreter.get_member(options, &PropertyKey::from(name)),
+        _ => Err(type_error("The \"options\" argument must be of type object")),
+    }
+}
+
+fn encoder_constructor(interpreter: &mut Interpreter, _this: &Value, _args: &[Value]) -> Result<Value, String> {
+    require_new(interpreter, "TextEncoder")?;
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.text_encoder_prototype)?;
+    Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::TextEncoder, Some(prototype)))))
+}
+
+fn this_encoder(interpreter: &Interpreter, this: &Value, method: &str) -> Result<(), String> {
+    match this {
+        Value::Object(id) if matches!(interpreter.heap.get(*id).kind, ObjectKind::TextEncoder) => Ok(()),
+        _ => Err(incompatible_receiver(interpreter, &format!("TextEncoder.prototype.{}", method), this)),
+    }
+}
+
+fn encoder_encoding(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    this_encoder(interpreter, this, "encoding")?;
+    Ok(Value::String("utf-8".into()))
+}
+
+/// `encode(input)`: the UTF-8 bytes of a string, in a new `Uint8Array`.
+fn encode(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    this_encoder(interpreter, this, "encode")?;
+    let input = match arg(args, 0) {
+        Value::Undefined => String::new(),
+        input => interpreter.coerce_to_string(&input)?.to_string(),
+    };
+    let prototype = interpreter.intrinsics.typed_array_prototypes[ElementType::Uint8 as usize];
+    let array = interpreter.create_typed_array(ElementType::Uint8, input.len(), prototype)?;
+    interpreter.array_buffer_bytes_mut(&Value::Object(array)).expect("typed arrays have bytes").copy_from_slice(input.as_bytes());
+    Ok(Value::Object(array))
+}
+
+/// `encodeInto(source, destination)`: writes as many whole characters of
+/// `source` as fit into a `Uint8Array`, returning how many UTF-16 code
+/// units were read and how many bytes were written.
+fn encode_into(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    this_encoder(interpreter, this, "encodeInto")?;
+    // Lone surrogates are written as U+FFFD, which is one code unit too
+    let source = interpreter.coerce_to_string(&arg(args, 0))?.to_string();
+    let destination = arg(args, 1);
+    let is_uint8_array = match destination {
+        Value::Object(id) => matches!(interpreter.heap.get(id).kind, ObjectKind::TypedArray(view) if view.element == ElementType::Uint8),
+        _ => false,
+    };
+    if !is_uint8_array {
+        return Err(type_error("The \"dest\" argument must be an instance of Uint8Array"));
+    }
+    let bytes = interpreter.array_buffer_bytes_mut(&destination).expect("typed arrays have bytes");
+    let (mut read, mut written) = (0, 0);
+    for character in source.chars() {
+        let length = character.len_utf8();
+        if written + length > bytes.len() {
+            break;
+        }
+        character.encode_utf8(&mut bytes[written..written + length]);
+        written += length;
+        read += character.len_utf16();
+    }
+    let result = interpreter.create_object();
+    for (name, count) in [("read", read), ("written", written)] {
+        interpreter.heap.define_property(result, PropertyKey::from(name), Property::data(Value::Number(count as f64), Attributes::DEFAULT));
+    }
+    Ok(Value::Object(result))
+}
+
+/// `new TextDecoder(label, { fatal, ignoreBOM })`.
+fn decoder_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    require_new(interpreter, "TextDecoder")?;
+    let encoding = match arg(args, 0) {
+        Value::Undefined => Encoding::Utf8,
+        label => {
+            let label = interpreter.coerce_to_string(&label)?.to_string();
+            Encoding::from_label(&label).ok_or_else(|| range_error(format!("The \"{}\" encoding is not supported", label)))?
+        }
+    };
+    let options = arg(args, 1);
+    let fatal = option(interpreter, &options, "fatal")?.is_truthy();
+    let ignore_bom = option(interpreter, &options, "ignoreBOM")?.is_truthy();
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.text_decoder_prototype)?;
+    let decoder = Box::new(TextDecoder::new(encoding, fatal, ignore_bom));
+    Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::TextDecoder(decoder), Some(prototype)))))
+}
+
+fn this_decoder(interpreter: &Interpreter, this: &Value, method: &str) -> Result<ObjectId, String> {
+    match this {
+        Value::Object(id) if matches!(interpreter.heap.get(*id).kind, ObjectKind::TextDecoder(_)) => Ok(*id),
+        _ => Err(incompatible_receiver(interpreter, &format!("TextDecoder.prototype.{}", method), this)),
+    }
+}
+
+fn decoder_mut(interpreter: &mut Interpreter, id: ObjectId) -> &mut TextDecoder {
+    let ObjectKind::TextDecoder(decoder) = &mut interpreter.heap.get_mut(id).kind else { unreachable!("checked by this_decoder") };
+    decoder
+}
+
+fn decoder_encoding(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let id = this_decoder(interpreter, this, "encoding")?;
+    Ok(Value::String(decoder_mut(interpreter, id).encoding.name().into()))
+}
+
+fn fatal(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let id = this_decoder(interpreter, this, "fatal")?;
+    Ok(Value::Boolean(decoder_mut(interpreter, id).fatal))
+}
+
+fn ignore_bom(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let id = this_decoder(interpreter, this, "ignoreBOM")?;
+    Ok(Value::Boolean(decoder_mut(interpreter, id).ignore_bom))
+}
+
+/// `decode(input, { stream })`: the text held by a buffer or view.
+fn decode(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = this_decoder(interpreter, this, "decode")?;
+    let stream = option(interpreter, &arg(args, 1), "stream")?.is_truthy();
+    let input = arg(args, 0);
+    let bytes = match input {
+        Value::Undefined => Vec::new(),
+        _ => match interpreter.array_buffer_bytes(&input) {
+            Some(bytes) => bytes.to_vec(),
+            None => return Err(type_error("The \"input\" argument must be an instance of ArrayBuffer or ArrayBufferView")),
+        },
+    };
+    Ok(Value::String(decoder_mut(interpreter, id).decode(&bytes, stream)?.into()))
+}
@@ -0,0 +1,117 @@
+//! `Error`, the native error types derived from it, and their prototypes.
+
+use super::{arg, define_global, define_method};
+use crate::interpreter::object::{Attributes, NativeFunction, ObjectId, Property, PropertyKey};
+use crate::interpreter::{type_error, Interpreter, JsString, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.error_prototype;
+    let error = install_error_type(interpreter, "Error", prototype, error_constructor);
+    define_method(interpreter, prototype, "toString", 0, to_string);
+
+    let Value::Function(error_id) = error else { unreachable!() };
+    let native_errors = [
+        ("TypeError", interpreter.intrinsics.type_error_prototype, type_error_constructor as NativeFunction),
+        ("RangeError", interpreter.intrinsics.range_error_prototype, range_error_constructor),
+        ("ReferenceError", interpreter.intrinsics.reference_error_prototype, reference_error_constructor),
+        ("SyntaxError", interpreter.intrinsics.syntax_error_prototype, syntax_error_constructor),
+    ];
+    for (name, prototype, function) in native_errors {
+        let constructor = install_error_type(interpreter, name, prototype, function);
+        // `TypeError` and friends inherit the statics of `Error`
+        let Value::Function(id) = constructor else { unreachable!() };
+        interpreter.heap.get_mut(id).prototype = Some(error_id);
+    }
+    let prototype = interpreter.intrinsics.aggregate_error_prototype;
+    let constructor = install_error_type(interpreter, "AggregateError", prototype, aggregate_error_constructor);
+    let Value::Function(id) = constructor else { unreachable!() };
+    interpreter.heap.define_property(id, PropertyKey::from("length"), Property::data(Value::Number(2.0), Attributes::READ_ONLY));
+    interpreter.heap.get_mut(id).prototype = Some(error_id);
+}
+
+/// Creates the constructor for an error type and fills in its prototype's
+/// `name` and `message`.
+fn install_error_type(interpreter: &mut Interpreter, name: &str, prototype: ObjectId, function: NativeFunction) -> Value {
+    let constructor = interpreter.create_native_function(name, 1, function);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let name_value = Value::String(name.into());
+    interpreter.heap.define_property(prototype, PropertyKey::from("name"), Property::data(name_value, Attributes::HIDDEN));
+    let message = Value::String(JsString::new());
+    interpreter.heap.define_property(prototype, PropertyKey::from("message"), Property::data(message, Attributes::HIDDEN));
+    define_global(interpreter, name, constructor.clone());
+    constructor
+}
+
+fn error_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    construct_error(interpreter, interpreter.intrinsics.error_prototype, args)
+}
+
+fn type_error_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    construct_error(interpreter, interpreter.intrinsics.type_error_prototype, args)
+}
+
+fn range_error_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    construct_error(interpreter, interpreter.intrinsics.range_error_prototype, args)
+}
+
+fn reference_error_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    construct_error(interpreter, interpreter.intrinsics.reference_error_prototype, args)
+}
+
+fn syntax_error_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    construct_error(interpreter, interpreter.intrinsics.syntax_error_prototype, args)
+}
+
+/// `new AggregateError(errors, message, { cause })` keeps the values of
+/// the iterable `errors` in an array.
+fn aggregate_error_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let error = construct_error(interpreter, interpreter.intrinsics.aggregate_error_prototype, args.get(1..).unwrap_or_default())?;
+    let errors = interpreter.collect_iterable(&arg(args, 0))?;
+    let errors = interpreter.create_array(errors);
+    let Value::Object(id) = error else { unreachable!("errors are objects") };
+    interpreter.heap.define_property(id, PropertyKey::from("errors"), Property::data(errors, Attributes::HIDDEN));
+    Ok(error)
+}
+
+/// `new Error(message, { cause })`, which also works without `new`.
+fn construct_error(interpreter: &mut Interpreter, fallback: ObjectId, args: &[Value]) -> Result<Value, String> {
+    let prototype = interpreter.new_target_prototype(fallback)?;
+    let message = match arg(args, 0) {
+        Value::Undefined => None,
+        message => Some(interpreter.coerce_to_string(&message)?.to_string()),
+    };
+    let cause = match arg(args, 1) {
+        options @ Value::Object(id) if interpreter.heap.has_property(id, &PropertyKey::from("cause")) => {
+            Some(interpreter.get_member(&options, &PropertyKey::from("cause"))?)
+        }
+        _ => None,
+    };
+
+    let id = interpreter.create_error(prototype, message);
+    if let Some(cause) = cause {
+        interpreter.heap.define_property(id, PropertyKey::from("cause"), Property::data(cause, Attributes::HIDDEN));
+    }
+    Ok(Value::Object(id))
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    if !matches!(this, Value::Object(_) | Value::Function(_)) {
+        return Err(type_error(format!(
+            "Method Error.prototype.toString called on incompatible receiver {}",
+            interpreter.to_display_string(this)
+        )));
+    }
+    let name = match interpreter.get_member(this, &PropertyKey::from("name"))? {
+        Value::Undefined => "Error".to_string(),
+        name => interpreter.coerce_to_string(&name)?.to_string(),
+    };
+    let message = match interpreter.get_member(this, &PropertyKey::from("message"))? {
+        Value::Undefined => String::new(),
+        message => interpreter.coerce_to_string(&message)?.to_string(),
+    };
+    Ok(Value::String(match (name.is_empty(), message.is_empty()) {
+        (true, _) => message.into(),
+        (false, true) => name.into(),
+        (false, false) => format!("{}: {}", name, message).into(),
+    }))
+}
@@ -0,0 +1,120 @@
+//! `Function` and `Function.prototype`.
+
+use super::{arg, define_global, define_method};
+use crate::interpreter::object::{Attributes, BoundFunction, NativeFunction, Object, ObjectKind, Property, PropertyKey};
+use crate::interpreter::{syntax_error, type_error, Interpreter, Value};
+use crate::parser::{Expression, Parser};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.function_prototype;
+    let constructor = interpreter.create_native_function("Function", 1, function_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("apply", 2, apply),
+        ("bind", 1, bind),
+        ("call", 1, call),
+        ("toString", 0, to_string),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    let has_instance = interpreter.create_native_function("[Symbol.hasInstance]", 1, |i, this, args| {
+        i.ordinary_has_instance(&arg(args, 0), this).map(Value::Boolean)
+    });
+    let key = PropertyKey::Symbol(interpreter.symbols.has_instance.clone());
+    interpreter.heap.define_property(prototype, key, Property::data(has_instance, Attributes::FIXED));
+
+    define_global(interpreter, "Function", constructor);
+}
+
+/// `Function(...params, body)` compiles a function in the global scope.
+fn function_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    compile_function(interpreter, args, "function")
+}
+
+/// Compiles the parameters and body in `args` as a function declared with
+/// `keyword`, `function` or `function*`.
+pub(super) fn compile_function(interpreter: &mut Interpreter, args: &[Value], keyword: &str) -> Result<Value, String> {
+    let mut texts = Vec::with_capacity(args.len());
+    for value in args {
+        texts.push(interpreter.coerce_to_string(value)?.to_string());
+    }
+    let body = texts.pop().unwrap_or_default();
+    let source = format!("({} anonymous({}\n) {{\n{}\n}})", keyword, texts.join(","), body);
+
+    let mut parser = Parser::new(&source);
+    // The parser grows the stack through `stacker`
+    let parsed = interpreter.with_stacker(|_| parser.parse_expression());
+    let definition = match parsed {
+        Ok(Expression::Function(definition)) if parser.is_eof() => definition,
+        Ok(_) => return Err(syntax_error("Unexpected token in function body")),
+        Err(message) => return Err(syntax_error(message)),
+    };
+    Ok(interpreter.create_function(&definition, interpreter.global.clone()))
+}
+
+fn require_callable(interpreter: &Interpreter, this: &Value, method: &str) -> Result<(), String> {
+    match this {
+        Value::Function(_) => Ok(()),
+        other => Err(type_error(format!(
+            "Function.prototype.{} called on {}, which is not a function",
+            method,
+            interpreter.to_display_string(other)
+        ))),
+    }
+}
+
+fn call(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    require_callable(interpreter, this, "call")?;
+    let rest = args.iter().skip(1).cloned().collect();
+    interpreter.call_function(this, arg(args, 0), rest)
+}
+
+fn apply(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    require_callable(interpreter, this, "apply")?;
+    let arguments = match arg(args, 1) {
+        Value::Null | Value::Undefined => Vec::new(),
+        // Any array-like object is accepted
+        list => interpreter.create_list_from_array_like(&list)?,
+    };
+    interpreter.call_function(this, arg(args, 0), arguments)
+}
+
+fn bind(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    require_callable(interpreter, this, "bind")?;
+    let bound_args: Vec<Value> = args.iter().skip(1).cloned().collect();
+
+    let target_name = match interpreter.get_member(this, &PropertyKey::from("name"))? {
+        Value::String(name) => name,
+        _ => String::new().into(),
+    };
+    let target_length = match interpreter.get_member(this, &PropertyKey::from("length"))? {
+        Value::Number(length) => length,
+        _ => 0.0,
+    };
+    let length = (target_length - bound_args.len() as f64).max(0.0);
+
+    let bound = BoundFunction { target: this.clone(), this: arg(args, 0), args: bound_args };
+    let prototype = Some(interpreter.intrinsics.function_prototype);
+    let id = interpreter.heap.allocate(Object::new(ObjectKind::BoundFunction(bound), prototype));
+    interpreter.heap.define_property(id, PropertyKey::from("length"), Property::data(Value::Number(length), Attributes::READ_ONLY));
+    let name = Value::String(format!("bound {}", target_name).into());
+    interpreter.heap.define_property(id, PropertyKey::from("name"), Property::data(name, Attributes::READ_ONLY));
+    Ok(Value::Function(id))
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    require_callable(interpreter, this, "toString")?;
+    if let Value::Function(id) = this {
+        if let ObjectKind::Function(closure) = &interpreter.heap.get(*id).kind {
+            return Ok(Value::String(closure.definition.source_text.clone().into()));
+        }
+    }
+    // Built-in and bound functions have no source to show
+    let name = match interpreter.get_member(this, &PropertyKey::from("name"))? {
+        Value::String(name) => name,
+        _ => String::new().into(),
+    };
+    Ok(Value::String(format!("function {}() {{ [native code] }}", name).into()))
+}
@@ -0,0 +1,97 @@
+//! `GeneratorFunction.prototype` and the generator prototype with `next`,
+//! `return` and `throw`, their async counterparts, and the prototype of
+//! async iterators.
+
+use super::{arg, define_method, define_to_string_tag, incompatible_receiver};
+use crate::interpreter::generator::{GeneratorStep, ResumeAction};
+use crate::interpreter::object::{Attributes, ObjectId, Property, PropertyKey};
+use crate::interpreter::{Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let function_prototype = interpreter.intrinsics.generator_function_prototype;
+    let prototype = interpreter.intrinsics.generator_prototype;
+    // `GeneratorFunction` is not a global; it is only reachable through
+    // the prototype of generator functions
+    let constructor = interpreter.create_native_function("GeneratorFunction", 1, generator_function_constructor);
+    link_generator_function(interpreter, constructor, function_prototype, prototype);
+    define_to_string_tag(interpreter, function_prototype, "GeneratorFunction");
+    define_to_string_tag(interpreter, prototype, "Generator");
+
+    define_method(interpreter, prototype, "next", 1, generator_next);
+    define_method(interpreter, prototype, "return", 1, |i, this, args| resume(i, this, ResumeAction::Return(arg(args, 0)), "return"));
+    define_method(interpreter, prototype, "throw", 1, |i, this, args| resume(i, this, ResumeAction::Throw(arg(args, 0)), "throw"));
+
+    install_async_generator(interpreter);
+}
+
+/// `AsyncGeneratorFunction` is reached through the prototype of async
+/// generator functions in the same way.
+fn install_async_generator(interpreter: &mut Interpreter) {
+    let async_iterator = interpreter.intrinsics.async_iterator_prototype;
+    let method = interpreter.create_native_function("[Symbol.asyncIterator]", 0, |_, this, _| Ok(this.clone()));
+    let key = PropertyKey::Symbol(interpreter.symbols.async_iterator.clone());
+    interpreter.heap.define_property(async_iterator, key, Property::data(method, Attributes::HIDDEN));
+
+    let function_prototype = interpreter.intrinsics.async_generator_function_prototype;
+    let prototype = interpreter.intrinsics.async_generator_prototype;
+    let constructor = interpreter.create_native_function("AsyncGeneratorFunction", 1, |i, _, args| {
+        super::function::compile_function(i, args, "async function*")
+    });
+    link_generator_function(interpreter, constructor, function_prototype, prototype);
+    define_to_string_tag(interpreter, function_prototype, "AsyncGeneratorFunction");
+    define_to_string_tag(interpreter, prototype, "AsyncGenerator");
+
+    define_method(interpreter, prototype, "next", 1, |i, this, args| resume_async(i, this, ResumeAction::Next(arg(args, 0)), "next"));
+    define_method(interpreter, prototype, "return", 1, |i, this, args| resume_async(i, this, ResumeAction::Return(arg(args, 0)), "return"));
+    define_method(interpreter, prototype, "throw", 1, |i, this, args| resume_async(i, this, ResumeAction::Throw(arg(args, 0)), "throw"));
+}
+
+/// Links the constructor of a kind of generator function, the prototype
+/// of such functions, and the prototype of the generators they return.
+fn link_generator_function(interpreter: &mut Interpreter, constructor: Value, function_prototype: ObjectId, prototype: ObjectId) {
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+    let function_prototype_value = Value::Object(function_prototype);
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("prototype"), Property::data(function_prototype_value, Attributes::FIXED));
+    interpreter.heap.define_property(function_prototype, PropertyKey::from("prototype"), Property::data(Value::Object(prototype), Attributes::READ_ONLY));
+    interpreter.heap.define_property(function_prototype, PropertyKey::from("constructor"), Property::data(constructor, Attributes::READ_ONLY));
+    interpreter.heap.define_property(prototype, PropertyKey::from("constructor"), Property::data(Value::Object(function_prototype), Attributes::READ_ONLY));
+}
+
+/// `GeneratorFunction(...params, body)` compiles a generator function in
+/// the global scope.
+fn generator_function_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    super::function::compile_function(interpreter, args, "function*")
+}
+
+pub(super) fn generator_next(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    resume(interpreter, this, ResumeAction::Next(arg(args, 0)), "next")
+}
+
+fn resume(interpreter: &mut Interpreter, this: &Value, action: ResumeAction, method: &str) -> Result<Value, String> {
+    let id = match this {
+        Value::Object(id) if interpreter.is_generator(*id, false) => *id,
+        _ => return Err(incompatible_receiver(interpreter, &format!("[Generator].prototype.{}", method), this)),
+    };
+    Ok(match interpreter.resume_generator(id, action)? {
+        GeneratorStep::Yield(value) => interpreter.create_iterator_result(value, false),
+        GeneratorStep::Delegated(result) => result,
+        GeneratorStep::Return(value) => interpreter.create_iterator_result(value, true),
+        GeneratorStep::Await(_) => unreachable!("await in a generator"),
+    })
+}
+
+/// Queues a request on an async generator, returning the promise for its
+/// result. Any other receiver gets a rejected promise rather than an
+/// exception.
+fn resume_async(interpreter: &mut Interpreter, this: &Value, action: ResumeAction, method: &str) -> Result<Value, String> {
+    if let Value::Object(id) = this {
+        if interpreter.is_generator(*id, true) {
+            return Ok(Value::Object(interpreter.enqueue_async_generator(*id, action)));
+        }
+    }
+    let message = incompatible_receiver(interpreter, &format!("[AsyncGenerator].prototype.{}", method), this);
+    let reason = interpreter.catch_exception(&message);
+    let promise = interpreter.create_promise(interpreter.intrinsics.promise_prototype);
+    interpreter.reject_promise(promise, reason);
+    Ok(Value::Object(promise))
+}
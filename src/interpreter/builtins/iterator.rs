@@ -0,0 +1,436 @@
+//! `Iterator`, the helpers on `Iterator.prototype`, and the `next` methods
+//! of the array and string iterators.
+
+use super::{arg, define_global, define_method, define_to_string_tag, incompatible_receiver, to_integer};
+use crate::interpreter::generator::Body;
+use crate::interpreter::iteration::{HelperOperation, Iteration, IteratorHelper};
+use crate::interpreter::object::{Attributes, NativeFunction, Object, ObjectId, ObjectKind, Property, PropertyKey};
+use crate::interpreter::{range_error, type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.iterator_prototype;
+    let constructor_id = interpreter.intrinsics.iterator_constructor;
+    let constructor = Value::Function(constructor_id);
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("length"), Property::data(Value::Number(0.0), Attributes::READ_ONLY));
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("name"), Property::data(Value::String("Iterator".into()), Attributes::READ_ONLY));
+    super::link_constructor(interpreter, &constructor, prototype);
+    define_method(interpreter, constructor_id, "from", 1, from);
+
+    // `constructor` is an accessor, so that subclasses can shadow it
+    // without `Iterator.prototype` itself being changed
+    let getter = interpreter.create_native_function("get constructor", 0, |i, _, _| Ok(Value::Function(i.intrinsics.iterator_constructor)));
+    let setter = interpreter.create_native_function("set constructor", 1, |i, this, args| {
+        set_ignoring_prototype(i, this, PropertyKey::from("constructor"), arg(args, 0))
+    });
+    let property = Property::accessor(None, true, getter, Attributes::HIDDEN);
+    let property = Property::accessor(Some(&property), false, setter, Attributes::HIDDEN);
+    interpreter.heap.define_property(prototype, PropertyKey::from("constructor"), property);
+    // So is `Symbol.toStringTag`
+    let getter = interpreter.create_native_function("get [Symbol.toStringTag]", 0, |_, _, _| Ok(Value::String("Iterator".into())));
+    let setter = interpreter.create_native_function("set [Symbol.toStringTag]", 1, |i, this, args| {
+        set_ignoring_prototype(i, this, PropertyKey::Symbol(i.symbols.to_string_tag.clone()), arg(args, 0))
+    });
+    let property = Property::accessor(None, true, getter, Attributes::HIDDEN);
+    let property = Property::accessor(Some(&property), false, setter, Attributes::HIDDEN);
+    let key = PropertyKey::Symbol(interpreter.symbols.to_string_tag.clone());
+    interpreter.heap.define_property(prototype, key, property);
+
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("drop", 1, |i, this, args| lazy_helper(i, this, args, "drop")),
+        ("every", 1, every),
+        ("filter", 1, |i, this, args| lazy_helper(i, this, args, "filter")),
+        ("find", 1, find),
+        ("flatMap", 1, |i, this, args| lazy_helper(i, this, args, "flatMap")),
+        ("forEach", 1, for_each),
+        ("map", 1, |i, this, args| lazy_helper(i, this, args, "map")),
+        ("reduce", 1, reduce),
+        ("some", 1, some),
+        ("take", 1, |i, this, args| lazy_helper(i, this, args, "take")),
+        ("toArray", 0, to_array),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    let iterator = interpreter.create_native_function("[Symbol.iterator]", 0, |_, this, _| Ok(this.clone()));
+    let key = PropertyKey::Symbol(interpreter.symbols.iterator.clone());
+    interpreter.heap.define_property(prototype, key, Property::data(iterator, Attributes::HIDDEN));
+
+    let helper = interpreter.intrinsics.iterator_helper_prototype;
+    define_method(interpreter, helper, "next", 0, helper_next);
+    define_method(interpreter, helper, "return", 0, helper_return);
+    define_to_string_tag(interpreter, helper, "Iterator Helper");
+    let wrapped = interpreter.intrinsics.wrapped_iterator_prototype;
+    define_method(interpreter, wrapped, "next", 0, wrapped_next);
+    define_method(interpreter, wrapped, "return", 0, wrapped_return);
+    let array_iterator = interpreter.intrinsics.array_iterator_prototype;
+    define_method(interpreter, array_iterator, "next", 0, array_iterator_next);
+    define_to_string_tag(interpreter, array_iterator, "Array Iterator");
+    let string_iterator = interpreter.intrinsics.string_iterator_prototype;
+    define_method(interpreter, string_iterator, "next", 0, string_iterator_next);
+    define_to_string_tag(interpreter, string_iterator, "String Iterator");
+
+    define_global(interpreter, "Iterator", constructor);
+}
+
+/// Whether `native` is the built-in `next` of iterators like the one
+/// `kind` describes, which iteration can then step without calling it.
+pub(in crate::interpreter) fn is_native_next(native: NativeFunction, kind: &ObjectKind) -> bool {
+    let expected: NativeFunction = match kind {
+        ObjectKind::ArrayIterator(_) => array_iterator_next,
+        ObjectKind::StringIterator(_) => string_iterator_next,
+        ObjectKind::CollectionIterator(iterator) if iterator.of_map => super::collection::map_iterator_next,
+        ObjectKind::CollectionIterator(_) => super::collection::set_iterator_next,
+        // An async generator is driven by its own `next`, never natively
+        ObjectKind::Generator(generator) if matches!(generator.body, Body::Generator(_)) => super::generator::generator_next,
+        _ => return false,
+    };
+    std::ptr::fn_addr_eq(native, expected)
+}
+
+/// `Iterator` is abstract: it can only be constructed as the parent of a
+/// subclass.
+pub(in crate::interpreter) fn iterator_constructor(interpreter: &mut Interpreter, _this: &Value, _args: &[Value]) -> Result<Value, String> {
+    match interpreter.new_target.clone() {
+        None => Err(type_error("Constructor Iterator requires 'new'")),
+        Some(Value::Function(id)) if id == interpreter.intrinsics.iterator_constructor => {
+            Err(type_error("Abstract class Iterator not directly constructable"))
+        }
+        Some(_) => {
+            let prototype = interpreter.new_target_prototype(interpreter.intrinsics.iterator_prototype)?;
+            Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::Ordinary, Some(prototype)))))
+        }
+    }
+}
+
+/// Assigning `constructor` or `Symbol.toStringTag` defines it on the
+/// object assigned to, unless that is `Iterator.prototype`.
+fn set_ignoring_prototype(interpreter: &mut Interpreter, this: &Value, key: PropertyKey, value: Value) -> Result<Value, String> {
+    let id = match this {
+        Value::Object(id) | Value::Function(id) => *id,
+        _ => return Err(type_error(format!("Iterator.prototype.{} setter called on non-object", key))),
+    };
+    if id == interpreter.intrinsics.iterator_prototype {
+        return Err(type_error(format!("Cannot assign to read only property '{}' of object '[object Iterator]'", key)));
+    }
+    if interpreter.heap.get(id).properties.contains_key(&key) {
+        interpreter.set_member(this, key, value)?;
+    } else {
+        interpreter.heap.define_property(id, key, Property::data(value, Attributes::DEFAULT));
+    }
+    Ok(Value::Undefined)
+}
+
+/// `Iterator.from(value)` returns an iterator inheriting from
+/// `Iterator.prototype`, wrapping the iterator of `value` if it does not.
+fn from(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let iteration = iterator_flattenable(interpreter, &arg(args, 0), true)?;
+    let (Value::Object(id) | Value::Function(id)) = iteration.iterator else { unreachable!("iterators are objects") };
+    let prototype = interpreter.intrinsics.iterator_prototype;
+    let mut current = interpreter.heap.get(id).prototype;
+    while let Some(ancestor) = current {
+        if ancestor == prototype {
+            return Ok(iteration.iterator);
+        }
+        current = interpreter.heap.get(ancestor).prototype;
+    }
+    let wrapped = Some(interpreter.intrinsics.wrapped_iterator_prototype);
+    Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::WrappedIterator(iteration), wrapped))))
+}
+
+/// The `GetIteratorFlattenable` operation: the iterator `value` provides
+/// through `Symbol.iterator`, or `value` itself if it has no such method.
+/// Strings are only iterated when `strings` is set.
+fn iterator_flattenable(interpreter: &mut Interpreter, value: &Value, strings: bool) -> Result<Iteration, String> {
+    match value {
+        Value::Object(_) | Value::Function(_) => {}
+        Value::String(_) if strings => {}
+        _ => return Err(type_error(format!("{} is not an object", interpreter.to_display_string(value)))),
+    }
+    match interpreter.iterator_method(value)? {
+        Some(method) => interpreter.iterate_with(value, &method),
+        None => interpreter.iteration_of(value.clone()),
+    }
+}
+
+fn wrapped_iteration(interpreter: &Interpreter, this: &Value, method: &str) -> Result<Iteration, String> {
+    if let Value::Object(id) = this {
+        if let ObjectKind::WrappedIterator(iteration) = &interpreter.heap.get(*id).kind {
+            return Ok(iteration.clone());
+        }
+    }
+    Err(incompatible_receiver(interpreter, method, this))
+}
+
+fn wrapped_next(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let iteration = wrapped_iteration(interpreter, this, "%WrapForValidIteratorPrototype%.next")?;
+    interpreter.call_function(&iteration.next, iteration.iterator, Vec::new())
+}
+
+fn wrapped_return(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let iteration = wrapped_iteration(interpreter, this, "%WrapForValidIteratorPrototype%.return")?;
+    match interpreter.get_method(&iteration.iterator, "return")? {
+        Some(method) => interpreter.call_function(&method, iteration.iterator, Vec::new()),
+        None => Ok(interpreter.create_iterator_result(Value::Undefined, true)),
+    }
+}
+
+fn array_iterator_next(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let id = match this {
+        Value::Object(id) if matches!(interpreter.heap.get(*id).kind, ObjectKind::ArrayIterator(_)) => *id,
+        _ => return Err(incompatible_receiver(interpreter, "Array Iterator.prototype.next", this)),
+    };
+    Ok(match interpreter.array_iterator_next(id)? {
+        Some(value) => interpreter.create_iterator_result(value, false),
+        None => interpreter.create_iterator_result(Value::Undefined, true),
+    })
+}
+
+fn string_iterator_next(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let id = match this {
+        Value::Object(id) if matches!(interpreter.heap.get(*id).kind, ObjectKind::StringIterator(_)) => *id,
+        _ => return Err(incompatible_receiver(interpreter, "String Iterator.prototype.next", this)),
+    };
+    Ok(match interpreter.string_iterator_next(id) {
+        Some(value) => interpreter.create_iterator_result(value, false),
+        None => interpreter.create_iterator_result(Value::Undefined, true),
+    })
+}
+
+/// The iterator a helper was called on, before its `next` method is read.
+fn this_iterator(this: &Value, method: &str) -> Result<Value, String> {
+    match this {
+        Value::Object(_) | Value::Function(_) => Ok(this.clone()),
+        _ => Err(type_error(format!("Iterator.prototype.{} called on non-object", method))),
+    }
+}
+
+/// A callback argument, closing `iterator` when it is not callable.
+fn callback(interpreter: &mut Interpreter, iterator: &Value, value: Value) -> Result<Value, String> {
+    if matches!(value, Value::Function(_)) {
+        return Ok(value);
+    }
+    let error = Err(type_error(format!("{} is not a function", interpreter.to_display_string(&value))));
+    interpreter.close_iterator(iterator, error)
+}
+
+/// `map`, `filter`, `take`, `drop` and `flatMap`, which return a helper
+/// iterator that does the work as it is stepped.
+fn lazy_helper(interpreter: &mut Interpreter, this: &Value, args: &[Value], method: &str) -> Result<Value, String> {
+    let iterator = this_iterator(this, method)?;
+    let operation = match method {
+        "take" | "drop" => {
+            let limit = arg(args, 0);
+            let n = match interpreter.coerce_to_number(&limit) {
+                Ok(n) => n,
+                Err(message) => return interpreter.close_iterator(&iterator, Err(message)),
+            };
+            if n.is_nan() || to_integer(n) < 0.0 {
+                let error = Err(range_error(format!("{} must be positive", interpreter.to_display_string(&limit))));
+                return interpreter.close_iterator(&iterator, error);
+            }
+            if method == "take" { HelperOperation::Take(to_integer(n)) } else { HelperOperation::Drop(to_integer(n)) }
+        }
+        _ => {
+            let function = callback(interpreter, &iterator, arg(args, 0))?;
+            match method {
+                "map" => HelperOperation::Map(function),
+                "filter" => HelperOperation::Filter(function),
+                _ => HelperOperation::FlatMap(function),
+            }
+        }
+    };
+    let underlying = interpreter.iteration_of(iterator)?;
+    let helper = IteratorHelper { operation, underlying, counter: 0.0, running: false, inner: None };
+    let prototype = Some(interpreter.intrinsics.iterator_helper_prototype);
+    Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::IteratorHelper(Box::new(helper)), prototype))))
+}
+
+fn this_helper(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<ObjectId, String> {
+    let id = match this {
+        Value::Object(id) if matches!(interpreter.heap.get(*id).kind, ObjectKind::IteratorHelper(_)) => *id,
+        _ => return Err(incompatible_receiver(interpreter, &format!("Iterator Helper.prototype.{}", method), this)),
+    };
+    let helper = helper_mut(interpreter, id);
+    if helper.running {
+        return Err(type_error("Generator is already running"));
+    }
+    Ok(id)
+}
+
+fn helper_mut(interpreter: &mut Interpreter, id: ObjectId) -> &mut IteratorHelper {
+    let ObjectKind::IteratorHelper(helper) = &mut interpreter.heap.get_mut(id).kind else {
+        unreachable!("not an iterator helper")
+    };
+    helper
+}
+
+fn helper_next(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let id = this_helper(interpreter, this, "next")?;
+    helper_mut(interpreter, id).running = true;
+    let step = step_helper(interpreter, id);
+    helper_mut(interpreter, id).running = false;
+    Ok(match step? {
+        Some(value) => interpreter.create_iterator_result(value, false),
+        None => interpreter.create_iterator_result(Value::Undefined, true),
+    })
+}
+
+/// Closes the iterator being flattened by `flatMap`, if any, and then the
+/// underlying iterator.
+fn helper_return(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let id = this_helper(interpreter, this, "return")?;
+    let helper = helper_mut(interpreter, id);
+    let (inner, mut underlying) = (helper.inner.take(), helper.underlying.clone());
+    helper.underlying.done = true;
+    let closed = match inner {
+        Some(mut inner) => interpreter.close_iteration(&mut inner, Ok(())),
+        None => Ok(()),
+    };
+    interpreter.close_iteration(&mut underlying, closed)?;
+    Ok(interpreter.create_iterator_result(Value::Undefined, true))
+}
+
+/// Produces the next value of the helper `id`, or `None` once it is done.
+fn step_helper(interpreter: &mut Interpreter, id: ObjectId) -> Result<Option<Value>, String> {
+    loop {
+        let helper = helper_mut(interpreter, id);
+        if let Some(mut inner) = helper.inner.clone() {
+            let step = interpreter.iteration_step(&mut inner);
+            let helper = helper_mut(interpreter, id);
+            helper.inner = (!inner.done).then_some(inner);
+            match step {
+                Ok(Some(value)) => return Ok(Some(value)),
+                Ok(None) => continue,
+                Err(message) => return close_underlying(interpreter, id, Err(message)),
+            }
+        }
+
+        let mut underlying = helper.underlying.clone();
+        match &mut helper.operation {
+            HelperOperation::Take(remaining) if *remaining == 0.0 => return close_underlying(interpreter, id, Ok(None)),
+            HelperOperation::Take(remaining) => *remaining -= 1.0,
+            HelperOperation::Drop(remaining) => {
+                let skipped = std::mem::take(remaining);
+                let mut count = 0.0;
+                while count < skipped {
+                    count += 1.0;
+                    let step = interpreter.iteration_step(&mut underlying);
+                    helper_mut(interpreter, id).underlying = underlying.clone();
+                    if step?.is_none() {
+                        return Ok(None);
+                    }
+                }
+            }
+            _ => {}
+        }
+        let step = interpreter.iteration_step(&mut underlying);
+        let helper = helper_mut(interpreter, id);
+        helper.underlying = underlying;
+        let Some(value) = step? else {
+            return Ok(None);
+        };
+        let counter = Value::Number(helper.counter);
+        helper.counter += 1.0;
+        let function = match &helper.operation {
+            HelperOperation::Map(function) | HelperOperation::Filter(function) | HelperOperation::FlatMap(function) => function.clone(),
+            HelperOperation::Take(_) | HelperOperation::Drop(_) => return Ok(Some(value)),
+        };
+        let result = interpreter.call_function(&function, Value::Undefined, vec![value.clone(), counter]);
+        let result = match result {
+            Ok(result) => result,
+            Err(message) => return close_underlying(interpreter, id, Err(message)),
+        };
+        match &helper_mut(interpreter, id).operation {
+            HelperOperation::Map(_) => return Ok(Some(result)),
+            HelperOperation::Filter(_) if result.is_truthy() => return Ok(Some(value)),
+            HelperOperation::Filter(_) => {}
+            _ => match iterator_flattenable(interpreter, &result, false) {
+                Ok(inner) => helper_mut(interpreter, id).inner = Some(inner),
+                Err(message) => return close_underlying(interpreter, id, Err(message)),
+            },
+        }
+    }
+}
+
+fn close_underlying<T>(interpreter: &mut Interpreter, id: ObjectId, completion: Result<T, String>) -> Result<T, String> {
+    let mut underlying = helper_mut(interpreter, id).underlying.clone();
+    let result = interpreter.close_iteration(&mut underlying, completion);
+    helper_mut(interpreter, id).underlying = underlying;
+    result
+}
+
+/// Calls `function(value, counter)` for each value of the iterator `this`
+/// until `visit` returns a result, closing the iterator when it stops
+/// early.
+fn consume(
+    interpreter: &mut Interpreter,
+    this: &Value,
+    args: &[Value],
+    method: &str,
+    mut visit: impl FnMut(&mut Interpreter, Value, Value) -> Result<Option<Value>, String>,
+) -> Result<Option<Value>, String> {
+    let iterator = this_iterator(this, method)?;
+    let function = callback(interpreter, &iterator, arg(args, 0))?;
+    let mut iteration = interpreter.iteration_of(iterator)?;
+    let mut counter = 0.0;
+    while let Some(value) = interpreter.iteration_step(&mut iteration)? {
+        let result = interpreter.call_function(&function, Value::Undefined, vec![value.clone(), Value::Number(counter)]);
+        counter += 1.0;
+        match result.and_then(|result| visit(interpreter, value, result)) {
+            Ok(None) => {}
+            Ok(Some(found)) => return interpreter.close_iteration(&mut iteration, Ok(Some(found))),
+            Err(message) => return interpreter.close_iteration(&mut iteration, Err(message)),
+        }
+    }
+    Ok(None)
+}
+
+fn for_each(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    consume(interpreter, this, args, "forEach", |_, _, _| Ok(None))?;
+    Ok(Value::Undefined)
+}
+
+fn some(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let found = consume(interpreter, this, args, "some", |_, _, result| Ok(result.is_truthy().then_some(Value::Boolean(true))))?;
+    Ok(Value::Boolean(found.is_some()))
+}
+
+fn every(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let failed = consume(interpreter, this, args, "every", |_, _, result| Ok((!result.is_truthy()).then_some(Value::Boolean(false))))?;
+    Ok(Value::Boolean(failed.is_none()))
+}
+
+fn find(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let found = consume(interpreter, this, args, "find", |_, value, result| Ok(result.is_truthy().then_some(value)))?;
+    Ok(found.unwrap_or(Value::Undefined))
+}
+
+fn reduce(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let iterator = this_iterator(this, "reduce")?;
+    let reducer = callback(interpreter, &iterator, arg(args, 0))?;
+    let mut iteration = interpreter.iteration_of(iterator)?;
+    let (mut accumulator, mut counter) = match args.get(1) {
+        Some(initial) => (initial.clone(), 0.0),
+        None => match interpreter.iteration_step(&mut iteration)? {
+            Some(first) => (first, 1.0),
+            None => return Err(type_error("Reduce of a done iterator with no initial value")),
+        },
+    };
+    while let Some(value) = interpreter.iteration_step(&mut iteration)? {
+        interpreter.hold(&accumulator);
+        let result = interpreter.call_function(&reducer, Value::Undefined, vec![accumulator, value, Value::Number(counter)]);
+        accumulator = match result {
+            Ok(result) => result,
+            Err(message) => return interpreter.close_iteration(&mut iteration, Err(message)),
+        };
+        counter += 1.0;
+    }
+    Ok(accumulator)
+}
+
+fn to_array(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let iterator = this_iterator(this, "toArray")?;
+    let mut iteration = interpreter.iteration_of(iterator)?;
+    let values = interpreter.collect_iteration(&mut iteration)?;
+    Ok(interpreter.create_array(values))
+}
@@ -0,0 +1,620 @@
+//! The `JSON` object, which parses text through `serde_json` and writes
+//! JSON text itself.
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use super::array::{index_key, length_of};
+use super::{arg, define_global, define_method, define_to_string_tag, to_integer};
+use crate::interpreter::object::{Attributes, ObjectId, ObjectKind, Property, PropertyDescriptor, PropertyKey, PropertyValue};
+use crate::interpreter::{number_to_string, syntax_error, type_error, Interpreter, JsString, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let json = interpreter.create_object();
+    define_method(interpreter, json, "parse", 2, parse);
+    define_method(interpreter, json, "stringify", 3, stringify);
+    define_to_string_tag(interpreter, json, "JSON");
+    let json = interpreter.object_value(json);
+    define_global(interpreter, "JSON", json);
+}
+
+/// `JSON.parse(text, reviver)`.
+fn parse(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let source = interpreter.coerce_to_string(&arg(args, 0))?;
+    let text = EscapedText::new(source.units());
+    let json = interpreter.with_stacker(|_| {
+        // Nesting is only limited by memory, as in V8: `serde_stacker`
+        // grows the native stack for the recursive descent
+        let mut deserializer = serde_json::Deserializer::from_str(&text.text);
+        deserializer.disable_recursion_limit();
+        let json = serde_json::Value::deserialize(serde_stacker::Deserializer::new(&mut deserializer))?;
+        deserializer.end().map(|()| json)
+    });
+    let json = json.map_err(|error| syntax_error(parse_error_message(&text, &source.to_string(), &error)))?;
+    let value = from_json(interpreter, json);
+    let reviver = arg(args, 1);
+    if !matches!(reviver, Value::Function(_)) {
+        return Ok(value);
+    }
+    let root = interpreter.create_object();
+    interpreter.heap.define_property(root, PropertyKey::from(""), Property::data(value, Attributes::DEFAULT));
+    let root = interpreter.object_value(root);
+    internalize(interpreter, &root, PropertyKey::from(""), &reviver)
+}
+
+/// Stands in for lone surrogates in the text given to `serde_json`, which
+/// only reads well-formed strings. Each one is written as `SURROGATE`
+/// followed by `SURROGATE_BASE` plus its offset from U+D800, and
+/// `SURROGATE` itself is written twice.
+const SURROGATE: char = '\u{FDD0}';
+const SURROGATE_BASE: u32 = 0xF0000;
+
+/// JSON text with the lone surrogates in its strings, raw or escaped,
+/// replaced as described for `SURROGATE`, which leaves any error in it
+/// where it was.
+struct EscapedText {
+    text: String,
+    /// Where each replacement ends, in bytes of `text`, with the number
+    /// of code units of the original text up to there
+    replacements: Vec<(usize, usize)>,
+}
+
+impl EscapedText {
+    fn new(units: &[u16]) -> Self {
+        let mut escaped = EscapedText { text: String::with_capacity(units.len()), replacements: Vec::new() };
+        let mut in_string = false;
+        let mut start = 0;
+        let mut i = 0;
+        while i < units.len() {
+            let (length, replacement) = match units[i] {
+                0x22 => {
+                    in_string = !in_string;
+                    (1, None)
+                }
+                _ if !in_string => (1, None),
+                0x5C => match units.get(i + 1) {
+                    Some(0x75) => match hex_escape(units, i) {
+                        Some(0xD800..=0xDBFF) if matches!(hex_escape(units, i + 6), Some(0xDC00..=0xDFFF)) => (12, None),
+                        Some(unit @ 0xD800..=0xDFFF) => (6, Some(unit)),
+                        Some(unit) if u32::from(unit) == SURROGATE as u32 => (6, Some(unit)),
+                        _ => (2, None),
+                    },
+                    _ => (2, None),
+                },
+                0xD800..=0xDBFF if matches!(units.get(i + 1), Some(0xDC00..=0xDFFF)) => (2, None),
+                unit @ 0xD800..=0xDFFF => (1, Some(unit)),
+                unit if u32::from(unit) == SURROGATE as u32 => (1, Some(unit)),
+                _ => (1, None),
+            };
+            if let Some(unit) = replacement {
+                escaped.text.push_str(&String::from_utf16_lossy(&units[start..i]));
+                escaped.text.push(SURROGATE);
+                escaped.text.push(char::from_u32(SURROGATE_BASE + u32::from(unit) - 0xD800).unwrap_or(SURROGATE));
+                escaped.replacements.push((escaped.text.len(), i + length));
+                start = i + length;
+            }
+            i += length;
+        }
+        escaped.text.push_str(&String::from_utf16_lossy(&units[start..]));
+        escaped
+    }
+
+    /// The position in the original text, in code units, of the byte at
+    /// `offset`.
+    fn position(&self, offset: usize) -> usize {
+        let (end, position) = match self.replacements.partition_point(|&(end, _)| end <= offset) {
+            0 => (0, 0),
+            n => self.replacements[n - 1],
+        };
+        self.text.get(end..offset).map_or(offset, |between| position + between.encode_utf16().count())
+    }
+}
+
+/// The code unit written by the `\uXXXX` escape at `i`, if there is one.
+fn hex_escape(units: &[u16], i: usize) -> Option<u16> {
+    match units.get(i..i + 6)? {
+        [0x5C, 0x75, digits @ ..] => {
+            let digits = String::from_utf16(digits).ok()?;
+            u16::from_str_radix(&digits, 16).ok().filter(|_| digits.bytes().all(|b| b.is_ascii_hexdigit()))
+        }
+        _ => None,
+    }
+}
+
+/// A string read from `EscapedText`, with its lone surrogates put back.
+fn unescape(s: String) -> JsString {
+    if !s.contains(SURROGATE) {
+        return s.into();
+    }
+    let mut units = Vec::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            SURROGATE => match chars.next() {
+                Some(SURROGATE) | None => units.push(SURROGATE as u16),
+                Some(offset) => units.push((offset as u32 - SURROGATE_BASE + 0xD800) as u16),
+            },
+            _ => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+        }
+    }
+    units.into()
+}
+
+/// Rephrases a `serde_json` error the way V8 reports invalid JSON.
+fn parse_error_message(escaped: &EscapedText, source: &str, error: &serde_json::Error) -> String {
+    if error.is_eof() {
+        return end_of_input_message(source, &error.to_string());
+    }
+    let text = escaped.text.as_str();
+    let description = error.to_string();
+    let line_start: usize = text.split_inclusive('\n').take(error.line().saturating_sub(1)).map(str::len).sum();
+    let offset = (line_start + error.column().saturating_sub(1)).min(text.len());
+    let offset = (0..=offset).rev().find(|&offset| text.is_char_boundary(offset)).unwrap_or(0);
+    // Positions count UTF-16 code units, like string indices
+    let position = escaped.position(offset);
+    let previous = text[..offset].trim_end().chars().last();
+    if description.starts_with("trailing characters") {
+        format!("Unexpected non-whitespace character after JSON at position {}", position)
+    } else if description.starts_with("invalid escape") {
+        escape_error_message(text, offset, position)
+    } else if description.starts_with("control character") {
+        format!("Bad control character in string literal in JSON at position {}", position)
+    } else if description.starts_with("invalid number") {
+        number_error_message(previous, position)
+    } else if description.starts_with("key must be a string") || (description.starts_with("trailing comma") && text[offset..].starts_with('}')) {
+        missing_key_message(previous, position)
+    } else if description.starts_with("expected `:`") {
+        format!("Expected ':' after property name in JSON at position {}", position)
+    } else if description.starts_with("expected `,` or `]`") {
+        format!("Expected ',' or ']' after array element in JSON at position {}", position)
+    } else if description.starts_with("expected `,` or `}`") {
+        format!("Expected ',' or '}}' after property value in JSON at position {}", position)
+    } else {
+        match text.get(offset..).and_then(|rest| rest.chars().next()) {
+            Some('0'..='9' | '-') => format!("Unexpected number in JSON at position {}", position),
+            Some('"') => format!("Unexpected string in JSON at position {}", position),
+            Some(token) => format!("Unexpected token '{}', \"{}\" is not valid JSON", token, source),
+            None => "Unexpected end of JSON input".to_string(),
+        }
+    }
+}
+
+/// A bad escape is blamed on its first invalid character.
+fn escape_error_message(text: &str, offset: usize, position: usize) -> String {
+    let escape = text[..offset].rfind('\\').unwrap_or(offset);
+    let rest = text.get(escape + 1..).unwrap_or_default();
+    if !rest.starts_with('u') {
+        return format!("Bad escaped character in JSON at position {}", position);
+    }
+    let digits = rest[1..].bytes().take(4).take_while(u8::is_ascii_hexdigit).count();
+    let position = position - (offset - escape) + 2 + digits;
+    format!("Bad Unicode escape in JSON at position {}", position)
+}
+
+/// A number missing its digits is described by the part it stops in.
+fn number_error_message(previous: Option<char>, position: usize) -> String {
+    match previous {
+        Some('-') => format!("No number after minus sign in JSON at position {}", position),
+        Some('.') => format!("Unterminated fractional number in JSON at position {}", position),
+        Some('e' | 'E' | '+') => format!("Exponent part is missing a number in JSON at position {}", position),
+        _ => format!("Unexpected number in JSON at position {}", position),
+    }
+}
+
+/// V8's message for text that ends too early, which depends on what the
+/// innermost unclosed array or object was expecting next.
+fn end_of_input_message(text: &str, description: &str) -> String {
+    let length = text.encode_utf16().count();
+    if description.starts_with("EOF while parsing a string") {
+        return format!("Unterminated string in JSON at position {}", length);
+    }
+    // The innermost unclosed bracket, with the last bracket, comma or
+    // colon seen inside it
+    let mut open: Vec<(char, char)> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '{' | '[' => open.push((c, c)),
+            '}' | ']' => {
+                open.pop();
+            }
+            ',' | ':' => {
+                if let Some(last) = open.last_mut() {
+                    last.1 = c;
+                }
+            }
+            _ => {}
+        }
+    }
+    let previous = text.trim_end().chars().last();
+    // A number cut short ends in the part missing its digits
+    if text.trim_end().len() == text.len() && matches!(previous, Some('-' | '.' | 'e' | 'E' | '+')) {
+        let number = text.trim_end_matches(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'));
+        if !number.ends_with(|c: char| c.is_ascii_alphabetic()) {
+            return number_error_message(previous, length);
+        }
+    }
+    // serde_json only blames the container once the value inside is complete
+    let value_complete = description.starts_with("EOF while parsing an object") || description.starts_with("EOF while parsing a list");
+    match open.last() {
+        Some(('{', '{' | ',')) if matches!(previous, Some('{' | ',')) => missing_key_message(previous, length),
+        Some(('{', '{' | ',')) if value_complete => format!("Expected ':' after property name in JSON at position {}", length),
+        Some(('{', ':')) if value_complete => format!("Expected ',' or '}}' after property value in JSON at position {}", length),
+        Some(('[', _)) if value_complete && !matches!(previous, Some('[' | ',')) => {
+            format!("Expected ',' or ']' after array element in JSON at position {}", length)
+        }
+        _ => "Unexpected end of JSON input".to_string(),
+    }
+}
+
+/// A missing property name is described by what came before it.
+fn missing_key_message(previous: Option<char>, position: usize) -> String {
+    match previous {
+        Some(',') => format!("Expected double-quoted property name in JSON at position {}", position),
+        _ => format!("Expected property name or '}}' in JSON at position {}", position),
+    }
+}
+
+fn from_json(interpreter: &mut Interpreter, json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Boolean(b),
+        // Numbers keep their source text, which parses to the nearest
+        // double, overflowing to infinity as in JavaScript
+        serde_json::Value::Number(n) => Value::Number(n.as_str().parse().unwrap_or(f64::NAN)),
+        serde_json::Value::String(s) => Value::String(unescape(s)),
+        // Each element is moved out as it is converted, so dropping what
+        // is left never recurses
+        serde_json::Value::Array(elements) => {
+            let elements = elements.into_iter().map(|element| interpreter.with_stack(|interpreter| from_json(interpreter, element))).collect();
+            interpreter.create_array(elements)
+        }
+        serde_json::Value::Object(members) => {
+            let id = interpreter.create_object();
+            for (key, member) in members {
+                let member = interpreter.with_stack(|interpreter| from_json(interpreter, member));
+                interpreter.heap.define_property(id, PropertyKey::from(unescape(key)), Property::data(member, Attributes::DEFAULT));
+            }
+            interpreter.object_value(id)
+        }
+    }
+}
+
+/// Passes every parsed value to the reviver, innermost first, replacing
+/// it with the result or deleting it when that is `undefined`.
+fn internalize(interpreter: &mut Interpreter, holder: &Value, key: PropertyKey, reviver: &Value) -> Result<Value, String> {
+    let value = interpreter.get_member(holder, &key)?;
+    if let Value::Object(id) = value {
+        let keys = if interpreter.is_array(&value)? {
+            (0..length_of(interpreter, id)?).map(index_key).collect()
+        } else {
+            enumerable_keys(interpreter, id)?
+        };
+        for key in keys {
+            let element = interpreter.with_stack(|interpreter| internalize(interpreter, &value, key.clone(), reviver))?;
+            if matches!(element, Value::Undefined) {
+                interpreter.delete_property(id, &key)?;
+            } else {
+                interpreter.define_own_property(id, key, PropertyDescriptor::of(&Property::data(element, Attributes::DEFAULT)))?;
+            }
+        }
+    }
+    let key = Value::String(JsString::from(key.to_string()));
+    interpreter.call_function(reviver, holder.clone(), vec![key, value])
+}
+
+fn enumerable_keys(interpreter: &mut Interpreter, id: ObjectId) -> Result<Vec<PropertyKey>, String> {
+    let mut keys = Vec::new();
+    for key in interpreter.own_property_keys(id)? {
+        if matches!(key, PropertyKey::Symbol(_)) {
+            continue;
+        }
+        if interpreter.get_own_property(id, &key)?.is_some_and(|property| property.attributes.enumerable) {
+            keys.push(key);
+        }
+    }
+    Ok(keys)
+}
+
+/// `JSON.stringify(value, replacer, space)`.
+fn stringify(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let mut serializer = JsonSerializer::new(interpreter, &arg(args, 1))?;
+    serializer.gap = gap(interpreter, &arg(args, 2))?;
+    if !serializer.serialize_root(interpreter, arg(args, 0))? {
+        return Ok(Value::Undefined);
+    }
+    Ok(Value::String(serializer.output))
+}
+
+/// The JSON text for `value`, or `None` when it has no JSON form, as
+/// `JSON.stringify(value)` would produce it.
+pub(super) fn to_json_string(interpreter: &mut Interpreter, value: &Value) -> Result<Option<String>, String> {
+    let mut serializer = JsonSerializer::new(interpreter, &Value::Undefined)?;
+    let written = serializer.serialize_root(interpreter, value.clone())?;
+    Ok(written.then(|| serializer.output.to_string()))
+}
+
+/// The indentation the `space` argument asks for: up to ten spaces, or
+/// the first ten code units of a string.
+fn gap(interpreter: &mut Interpreter, space: &Value) -> Result<JsString, String> {
+    let space = match space {
+        Value::Object(id) => match &interpreter.heap.get(*id).kind {
+            ObjectKind::Primitive(Value::Number(_)) => Value::Number(interpreter.coerce_to_number(space)?),
+            ObjectKind::Primitive(Value::String(_)) => Value::String(interpreter.coerce_to_string(space)?),
+            _ => return Ok(JsString::new()),
+        },
+        space => space.clone(),
+    };
+    Ok(match space {
+        Value::Number(n) => JsString::from(" ".repeat(to_integer(n).clamp(0.0, 10.0) as usize)),
+        Value::String(s) => JsString::from(&s.units()[..s.len().min(10)]),
+        _ => JsString::new(),
+    })
+}
+
+/// Writes `s` as a JSON string literal, escaping lone surrogates so that
+/// the text stays well-formed.
+fn quote(s: &JsString, output: &mut JsString) {
+    output.push_str("\"");
+    for c in char::decode_utf16(s.units().iter().copied()) {
+        match c {
+            Ok('"') => output.push_str("\\\""),
+            Ok('\\') => output.push_str("\\\\"),
+            Ok('\u{8}') => output.push_str("\\b"),
+            Ok('\u{c}') => output.push_str("\\f"),
+            Ok('\n') => output.push_str("\\n"),
+            Ok('\r') => output.push_str("\\r"),
+            Ok('\t') => output.push_str("\\t"),
+            Ok(c) if c < ' ' => output.push_str(&format!("\\u{:04x}", c as u32)),
+            Ok(c) => output.push_str(c.encode_utf8(&mut [0; 4])),
+            Err(error) => output.push_str(&format!("\\u{:04x}", error.unpaired_surrogate())),
+        }
+    }
+    output.push_str("\"");
+}
+
+/// The state of one `JSON.stringify` call.
+struct JsonSerializer {
+    replacer: Option<Value>,
+    /// The keys to include from objects, when the replacer is an array
+    property_list: Option<Vec<PropertyKey>>,
+    /// The indentation added per level, empty for compact output
+    gap: JsString,
+    /// The indentation of the current level
+    indent: JsString,
+    /// The objects being serialized, each with the key its child is
+    /// being read from, to detect and describe cycles
+    stack: Vec<(ObjectId, PropertyKey)>,
+    /// The objects in `stack`, to look them up without scanning it
+    ancestors: HashSet<ObjectId>,
+    output: JsString,
+}
+
+impl JsonSerializer {
+    fn new(interpreter: &mut Interpreter, replacer: &Value) -> Result<Self, String> {
+        let mut serializer = JsonSerializer {
+            replacer: None,
+            property_list: None,
+            gap: JsString::new(),
+            indent: JsString::new(),
+            stack: Vec::new(),
+            ancestors: HashSet::new(),
+            output: JsString::new(),
+        };
+        match replacer {
+            Value::Function(_) => serializer.replacer = Some(replacer.clone()),
+            Value::Object(id) if interpreter.heap.get(*id).is_array() => {
+                let length = interpreter.heap.get(*id).array_length();
+                let mut keys = Vec::new();
+                for index in 0..length {
+                    let item = interpreter.get_member(replacer, &PropertyKey::Index(index))?;
+                    let key = match &item {
+                        Value::String(s) => s.clone(),
+                        Value::Number(n) => number_to_string(*n).into(),
+                        Value::Object(item_id) if matches!(interpreter.heap.get(*item_id).kind, ObjectKind::Primitive(Value::Number(_) | Value::String(_))) => {
+                            interpreter.coerce_to_string(&item)?
+                        }
+                        _ => continue,
+                    };
+                    let key = PropertyKey::from(key);
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+                serializer.property_list = Some(keys);
+            }
+            _ => {}
+        }
+        Ok(serializer)
+    }
+
+    /// Serializes `value` as the `""` property of a fresh wrapper object,
+    /// which is what `toJSON` and the replacer first see.
+    fn serialize_root(&mut self, interpreter: &mut Interpreter, value: Value) -> Result<bool, String> {
+        let wrapper = interpreter.create_object();
+        interpreter.heap.define_property(wrapper, PropertyKey::from(""), Property::data(value.clone(), Attributes::DEFAULT));
+        let wrapper = interpreter.object_value(wrapper);
+        self.serialize_property(interpreter, &wrapper, PropertyKey::from(""), value)
+    }
+
+    /// Writes the `key` property of `holder`, whose value is `value`.
+    /// Returns false, having written nothing, when the property is left
+    /// out.
+    fn serialize_property(&mut self, interpreter: &mut Interpreter, holder: &Value, key: PropertyKey, mut value: Value) -> Result<bool, String> {
+        if matches!(value, Value::Object(_) | Value::Function(_) | Value::BigInt(_)) {
+            let to_json = interpreter.get_member(&value, &PropertyKey::from("toJSON"))?;
+            if matches!(to_json, Value::Function(_)) {
+                value = interpreter.call_function(&to_json, value, vec![Value::String(JsString::from(key.to_string()))])?;
+            }
+        }
+        if let Some(replacer) = &self.replacer {
+            value = interpreter.call_function(replacer, holder.clone(), vec![Value::String(JsString::from(key.to_string())), value])?;
+        }
+        if let Value::Object(id) = value {
+            if let ObjectKind::Primitive(primitive) = &interpreter.heap.get(id).kind {
+                value = match primitive {
+                    Value::Number(_) => Value::Number(interpreter.coerce_to_number(&value)?),
+                    Value::String(_) => Value::String(interpreter.coerce_to_string(&value)?),
+                    primitive => primitive.clone(),
+                };
+            }
+        }
+        match value {
+            Value::Null => self.output.push_str("null"),
+            Value::Boolean(b) => self.output.push_str(if b { "true" } else { "false" }),
+            Value::String(s) => quote(&s, &mut self.output),
+            // Numbers are written the way `String(n)` would write them
+            Value::Number(n) if n.is_finite() => self.output.push_str(&number_to_string(n)),
+            Value::Number(_) => self.output.push_str("null"),
+            Value::BigInt(_) => return Err(type_error("Do not know how to serialize a BigInt")),
+            Value::Object(id) => {
+                if self.ancestors.contains(&id) {
+                    let start = self.stack.iter().position(|&(ancestor, _)| ancestor == id).expect("ancestors are on the stack");
+                    return Err(type_error(self.circular_message(interpreter, start, &key)));
+                }
+                self.stack.push((id, key));
+                self.ancestors.insert(id);
+                // Nesting is only limited by memory, so each level may need
+                // more native stack
+                let result = interpreter.with_stack(|interpreter| {
+                    if interpreter.is_array(&Value::Object(id))? {
+                        self.serialize_array(interpreter, id)
+                    } else {
+                        self.serialize_object(interpreter, id)
+                    }
+                });
+                self.ancestors.remove(&id);
+                self.stack.pop();
+                result?;
+            }
+            Value::Function(_) | Value::Symbol(_) | Value::Undefined => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn serialize_array(&mut self, interpreter: &mut Interpreter, id: ObjectId) -> Result<(), String> {
+        let array = interpreter.object_value(id);
+        let length = length_of(interpreter, id)?;
+        if length == 0 {
+            self.output.push_str("[]");
+            return Ok(());
+        }
+        let outer = self.indent.clone();
+        self.indent.push_js_str(&self.gap);
+        self.output.push_str("[");
+        for index in 0..length {
+            if index > 0 {
+                self.output.push_str(",");
+            }
+            self.new_line();
+            let key = index_key(index);
+            self.enter(key.clone());
+            let element = interpreter.get_member(&array, &key)?;
+            if !self.serialize_property(interpreter, &array, key, element)? {
+                self.output.push_str("null");
+            }
+        }
+        self.indent = outer;
+        self.new_line();
+        self.output.push_str("]");
+        Ok(())
+    }
+
+    fn serialize_object(&mut self, interpreter: &mut Interpreter, id: ObjectId) -> Result<(), String> {
+        let object = interpreter.object_value(id);
+        let keys = match &self.property_list {
+            Some(keys) => keys.clone(),
+            None => enumerable_keys(interpreter, id)?,
+        };
+        let outer = self.indent.clone();
+        self.indent.push_js_str(&self.gap);
+        self.output.push_str("{");
+        let mut empty = true;
+        for key in keys {
+            let start = self.output.len();
+            if !empty {
+                self.output.push_str(",");
+            }
+            self.new_line();
+            quote(&JsString::from(key.to_string()), &mut self.output);
+            self.output.push_str(if self.gap.is_empty() { ":" } else { ": " });
+            self.enter(key.clone());
+            let member = interpreter.get_member(&object, &key)?;
+            if self.serialize_property(interpreter, &object, key, member)? {
+                empty = false;
+            } else {
+                self.output.truncate(start);
+            }
+        }
+        self.indent = outer;
+        if !empty {
+            self.new_line();
+        }
+        self.output.push_str("}");
+        Ok(())
+    }
+
+    /// Starts a line at the current indentation, when indenting at all.
+    fn new_line(&mut self) {
+        if !self.gap.is_empty() {
+            self.output.push_str("\n");
+            self.output.push_js_str(&self.indent);
+        }
+    }
+
+    /// Records that the innermost object's `key` is being serialized.
+    fn enter(&mut self, key: PropertyKey) {
+        if let Some(last) = self.stack.last_mut() {
+            last.1 = key;
+        }
+    }
+
+    /// V8's description of a cycle: the path from the object that is
+    /// reached again around to the key that leads back to it.
+    fn circular_message(&self, interpreter: &Interpreter, start: usize, closing_key: &PropertyKey) -> String {
+        let mut message = format!(
+            "Converting circular structure to JSON\n    --> starting at object with constructor '{}'",
+            constructor_name(interpreter, self.stack[start].0)
+        );
+        for window in self.stack[start..].windows(2) {
+            let (_, key) = &window[0];
+            let (child, _) = window[1];
+            message.push_str(&format!(
+                "\n    |     {} -> object with constructor '{}'",
+                describe_key(key),
+                constructor_name(interpreter, child)
+            ));
+        }
+        let closing_key = &self.stack.last().map_or(closing_key, |(_, key)| key);
+        message.push_str(&format!("\n    --- {} closes the circle", describe_key(closing_key)));
+        message
+    }
+}
+
+fn describe_key(key: &PropertyKey) -> String {
+    match key {
+        PropertyKey::Index(index) => format!("index {}", index),
+        PropertyKey::String(name) => format!("property '{}'", name),
+        PropertyKey::Symbol(symbol) => format!("property {}", symbol),
+    }
+}
+
+/// The name of the `constructor` an object inherits, as cycle errors
+/// report it.
+fn constructor_name(interpreter: &Interpreter, id: ObjectId) -> String {
+    let heap = &interpreter.heap;
+    let constructor = heap.find_property(id, &PropertyKey::from("constructor")).map(|property| &property.value);
+    let Some(PropertyValue::Data(Value::Function(constructor))) = constructor else {
+        return "Object".to_string();
+    };
+    match heap.get(*constructor).properties.get(&PropertyKey::from("name")).map(|property| &property.value) {
+        Some(PropertyValue::Data(Value::String(name))) => name.to_string(),
+        _ => "Object".to_string(),
+    }
+}
@@ -0,0 +1,203 @@
+//! The `Math` object.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{arg, define_global, define_method, define_to_string_tag};
+use crate::interpreter::object::{Attributes, NativeFunction, Property, PropertyKey};
+use crate::interpreter::value::pow;
+use crate::interpreter::{to_int32, to_uint32, Interpreter, Value};
+
+/// The generator behind `Math.random`: xorshift128+, as in V8.
+pub(crate) struct Random {
+    state: [u64; 2],
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // Spread the seed over both words of state with SplitMix64, which
+        // also keeps a zero seed from producing a stuck generator
+        let mut seed = seed;
+        let mut split_mix = || {
+            seed = seed.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        };
+        Self { state: [split_mix(), split_mix()] }
+    }
+
+    /// A number in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        let [mut s1, s0] = self.state;
+        s1 ^= s1 << 23;
+        s1 ^= s1 >> 17;
+        s1 ^= s0;
+        s1 ^= s0 >> 26;
+        self.state = [s0, s1];
+        (s0.wrapping_add(s1) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Self::new(seed)
+    }
+}
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let math = interpreter.create_object();
+    let constants = [
+        ("E", std::f64::consts::E),
+        ("LN10", std::f64::consts::LN_10),
+        ("LN2", std::f64::consts::LN_2),
+        ("LOG10E", std::f64::consts::LOG10_E),
+        ("LOG2E", std::f64::consts::LOG2_E),
+        ("PI", std::f64::consts::PI),
+        ("SQRT1_2", std::f64::consts::FRAC_1_SQRT_2),
+        ("SQRT2", std::f64::consts::SQRT_2),
+    ];
+    for (name, value) in constants {
+        interpreter.heap.define_property(math, PropertyKey::from(name), Property::data(Value::Number(value), Attributes::FIXED));
+    }
+
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("abs", 1, |i, _, args| unary(i, args, f64::abs)),
+        ("acos", 1, |i, _, args| unary(i, args, f64::acos)),
+        ("acosh", 1, |i, _, args| unary(i, args, f64::acosh)),
+        ("asin", 1, |i, _, args| unary(i, args, f64::asin)),
+        ("asinh", 1, |i, _, args| unary(i, args, f64::asinh)),
+        ("atan", 1, |i, _, args| unary(i, args, f64::atan)),
+        ("atan2", 2, atan2),
+        ("atanh", 1, |i, _, args| unary(i, args, f64::atanh)),
+        ("cbrt", 1, |i, _, args| unary(i, args, f64::cbrt)),
+        ("ceil", 1, |i, _, args| unary(i, args, f64::ceil)),
+        ("clz32", 1, |i, _, args| unary(i, args, |n| to_uint32(n).leading_zeros() as f64)),
+        ("cos", 1, |i, _, args| unary(i, args, f64::cos)),
+        ("cosh", 1, |i, _, args| unary(i, args, f64::cosh)),
+        ("exp", 1, |i, _, args| unary(i, args, f64::exp)),
+        ("expm1", 1, |i, _, args| unary(i, args, f64::exp_m1)),
+        ("floor", 1, |i, _, args| unary(i, args, f64::floor)),
+        ("fround", 1, |i, _, args| unary(i, args, |n| n as f32 as f64)),
+        ("hypot", 2, hypot),
+        ("imul", 2, imul),
+        ("log", 1, |i, _, args| unary(i, args, f64::ln)),
+        ("log10", 1, |i, _, args| unary(i, args, f64::log10)),
+        ("log1p", 1, |i, _, args| unary(i, args, f64::ln_1p)),
+        ("log2", 1, |i, _, args| unary(i, args, f64::log2)),
+        ("max", 2, max),
+        ("min", 2, min),
+        ("pow", 2, math_pow),
+        ("random", 0, random),
+        ("round", 1, |i, _, args| unary(i, args, round)),
+        ("sign", 1, |i, _, args| unary(i, args, sign)),
+        ("sin", 1, |i, _, args| unary(i, args, f64::sin)),
+        ("sinh", 1, |i, _, args| unary(i, args, f64::sinh)),
+        ("sqrt", 1, |i, _, args| unary(i, args, f64::sqrt)),
+        ("tan", 1, |i, _, args| unary(i, args, f64::tan)),
+        ("tanh", 1, |i, _, args| unary(i, args, f64::tanh)),
+        ("trunc", 1, |i, _, args| unary(i, args, f64::trunc)),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, math, name, length, function);
+    }
+    define_to_string_tag(interpreter, math, "Math");
+
+    let math = interpreter.object_value(math);
+    define_global(interpreter, "Math", math);
+}
+
+/// Applies `operation` to the first argument converted to a number.
+fn unary(interpreter: &mut Interpreter, args: &[Value], operation: fn(f64) -> f64) -> Result<Value, String> {
+    let n = interpreter.coerce_to_number(&arg(args, 0))?;
+    Ok(Value::Number(operation(n)))
+}
+
+/// Converts every argument, as the variadic functions must even after
+/// the result is known.
+fn numbers(interpreter: &mut Interpreter, args: &[Value]) -> Result<Vec<f64>, String> {
+    args.iter().map(|value| interpreter.coerce_to_number(value)).collect()
+}
+
+/// Rounds half up, towards positive infinity, keeping the sign of zero.
+fn round(n: f64) -> f64 {
+    if !n.is_finite() || n.trunc() == n {
+        return n;
+    }
+    if (-0.5..0.0).contains(&n) {
+        return -0.0;
+    }
+    let floor = n.floor();
+    if n - floor >= 0.5 { floor + 1.0 } else { floor }
+}
+
+fn sign(n: f64) -> f64 {
+    if n.is_nan() || n == 0.0 { n } else { n.signum() }
+}
+
+fn atan2(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let y = interpreter.coerce_to_number(&arg(args, 0))?;
+    let x = interpreter.coerce_to_number(&arg(args, 1))?;
+    Ok(Value::Number(y.atan2(x)))
+}
+
+fn math_pow(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let base = interpreter.coerce_to_number(&arg(args, 0))?;
+    let exponent = interpreter.coerce_to_number(&arg(args, 1))?;
+    Ok(Value::Number(pow(base, exponent)))
+}
+
+fn imul(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let a = to_int32(interpreter.coerce_to_number(&arg(args, 0))?);
+    let b = to_int32(interpreter.coerce_to_number(&arg(args, 1))?);
+    Ok(Value::Number(a.wrapping_mul(b) as f64))
+}
+
+fn hypot(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let numbers = numbers(interpreter, args)?;
+    // Infinity wins even over NaN
+    if numbers.iter().any(|n| n.is_infinite()) {
+        return Ok(Value::Number(f64::INFINITY));
+    }
+    if numbers.iter().any(|n| n.is_nan()) {
+        return Ok(Value::Number(f64::NAN));
+    }
+    // Scale by the largest value so the squares cannot overflow
+    let largest = numbers.iter().fold(0.0, |largest: f64, n| largest.max(n.abs()));
+    if largest == 0.0 {
+        return Ok(Value::Number(0.0));
+    }
+    let sum: f64 = numbers.iter().map(|n| (n / largest) * (n / largest)).sum();
+    Ok(Value::Number(sum.sqrt() * largest))
+}
+
+fn max(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let result = numbers(interpreter, args)?.into_iter().fold(f64::NEG_INFINITY, |result, n| {
+        if result.is_nan() || n.is_nan() {
+            f64::NAN
+        } else if n > result || (n == 0.0 && result == 0.0 && result.is_sign_negative()) {
+            n
+        } else {
+            result
+        }
+    });
+    Ok(Value::Number(result))
+}
+
+fn min(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let result = numbers(interpreter, args)?.into_iter().fold(f64::INFINITY, |result, n| {
+        if result.is_nan() || n.is_nan() {
+            f64::NAN
+        } else if n < result || (n == 0.0 && result == 0.0 && n.is_sign_negative()) {
+            n
+        } else {
+            result
+        }
+    });
+    Ok(Value::Number(result))
+}
+
+fn random(interpreter: &mut Interpreter, _this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(interpreter.random.next_f64()))
+}
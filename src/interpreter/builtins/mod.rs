@@ -0,0 +1,175 @@
+//! The built-in global objects, installed into every new interpreter.
+
+mod array;
+mod array_buffer;
+mod bigint;
+mod boolean;
+mod collection;
+mod console;
+mod data_view;
+mod date;
+mod encoding;
+mod error;
+mod function;
+mod generator;
+mod iterator;
+mod json;
+mod math;
+mod normalization;
+mod number;
+mod object;
+mod promise;
+mod proxy;
+mod reflect;
+mod string;
+mod symbol;
+mod timer;
+mod typed_array;
+
+use super::environment::Binding;
+use super::object::{Attributes, NativeFunction, ObjectId, ObjectKind, Property, PropertyKey};
+use super::{type_error, Interpreter, Value};
+use crate::parser::VariableKind;
+
+pub(super) use array::{array_constructor, values as array_values};
+pub(super) use console::Console;
+pub(super) use iterator::{is_native_next, iterator_constructor};
+pub(super) use math::Random;
+pub(super) use object::set_integrity_level;
+pub(super) use promise::promise_constructor;
+pub(super) use proxy::proxy_constructor;
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    define_global(interpreter, "undefined", Value::Undefined);
+    object::install(interpreter);
+    function::install(interpreter);
+    symbol::install(interpreter);
+    iterator::install(interpreter);
+    generator::install(interpreter);
+    array::install(interpreter);
+    boolean::install(interpreter);
+    number::install(interpreter);
+    bigint::install(interpreter);
+    string::install(interpreter);
+    date::install(interpreter);
+    math::install(interpreter);
+    error::install(interpreter);
+    json::install(interpreter);
+    collection::install(interpreter);
+    array_buffer::install(interpreter);
+    typed_array::install(interpreter);
+    data_view::install(interpreter);
+    encoding::install(interpreter);
+    promise::install(interpreter);
+    proxy::install(interpreter);
+    reflect::install(interpreter);
+    timer::install(interpreter);
+    console::install(interpreter);
+}
+
+/// The argument at `index`, or `undefined` when fewer were passed.
+fn arg(args: &[Value], index: usize) -> Value {
+    args.get(index).cloned().unwrap_or(Value::Undefined)
+}
+
+/// Adds a native method called `name` to `target`.
+fn define_method(interpreter: &mut Interpreter, target: ObjectId, name: &str, length: u32, function: NativeFunction) {
+    let method = interpreter.create_native_function(name, length, function);
+    interpreter.heap.define_property(target, PropertyKey::from(name), Property::data(method, Attributes::HIDDEN));
+}
+
+/// Adds a native getter for `name` to `target`, like a `get` accessor in
+/// a class body.
+fn define_getter(interpreter: &mut Interpreter, target: ObjectId, name: &str, function: NativeFunction) {
+    let getter = interpreter.create_native_function(&format!("get {}", name), 0, function);
+    let property = Property::accessor(None, true, getter, Attributes::HIDDEN);
+    interpreter.heap.define_property(target, PropertyKey::from(name), property);
+}
+
+/// Makes `key` of `target` the same function as its method `existing`,
+/// as `Set.prototype.keys` is `Set.prototype.values`.
+fn define_alias(interpreter: &mut Interpreter, target: ObjectId, key: PropertyKey, existing: &str) {
+    let property = interpreter.heap.get(target).properties[&PropertyKey::from(existing)].clone();
+    interpreter.heap.define_property(target, key, property);
+}
+
+/// Defines `Symbol.toStringTag` on `target`, which names its instances in
+/// `Object.prototype.toString`.
+fn define_to_string_tag(interpreter: &mut Interpreter, target: ObjectId, tag: &str) {
+    let key = PropertyKey::Symbol(interpreter.symbols.to_string_tag.clone());
+    interpreter.heap.define_property(target, key, Property::data(Value::String(tag.into()), Attributes::READ_ONLY));
+}
+
+/// Connects a constructor with its `prototype` object in both directions.
+fn link_constructor(interpreter: &mut Interpreter, constructor: &Value, prototype: ObjectId) {
+    let Value::Function(constructor_id) = constructor else {
+        unreachable!("constructors are functions")
+    };
+    let attributes = Attributes { writable: false, enumerable: false, configurable: false };
+    let prototype_value = interpreter.object_value(prototype);
+    interpreter.heap.define_property(*constructor_id, PropertyKey::from("prototype"), Property::data(prototype_value, attributes));
+    let constructor = Property::data(constructor.clone(), Attributes::HIDDEN);
+    interpreter.heap.define_property(prototype, PropertyKey::from("constructor"), constructor);
+}
+
+/// Declares a global binding, as if by a top-level `var`.
+fn define_global(interpreter: &mut Interpreter, name: &str, value: Value) {
+    interpreter.builtin_scope.borrow_mut().insert(name.to_string(), Binding { value: Some(value), kind: VariableKind::Var });
+}
+
+/// The `ToIntegerOrInfinity` abstract operation on an already converted number.
+fn to_integer(n: f64) -> f64 {
+    if n.is_nan() { 0.0 } else { n.trunc() }
+}
+
+/// The `ToIndex` abstract operation, failing with the error `error`
+/// makes from the integer when it is negative or too large.
+fn to_index(interpreter: &mut Interpreter, value: &Value, error: impl FnOnce(f64) -> String) -> Result<usize, String> {
+    let n = to_integer(interpreter.coerce_to_number(value)?);
+    if !(0.0..=9007199254740991.0).contains(&n) {
+        return Err(error(n));
+    }
+    Ok(n as usize)
+}
+
+/// Resolves a relative index argument, where negative values count back
+/// from `length`, clamped to `0..=length`.
+fn relative_index(interpreter: &mut Interpreter, value: &Value, length: u64, default: u64) -> Result<u64, String> {
+    if matches!(value, Value::Undefined) {
+        return Ok(default);
+    }
+    let n = to_integer(interpreter.coerce_to_number(value)?);
+    let index = if n < 0.0 { (length as f64 + n).max(0.0) } else { n.min(length as f64) };
+    Ok(index as u64)
+}
+
+/// How V8 names a receiver in "incompatible receiver" errors.
+fn receiver_description(interpreter: &Interpreter, value: &Value) -> String {
+    match value {
+        Value::Object(id) | Value::Function(id) => {
+            let object = interpreter.heap.get(*id);
+            if object.is_array() {
+                return "[object Array]".to_string();
+            }
+            let name = match &object.kind {
+                ObjectKind::Map(_) => "Map",
+                ObjectKind::Set(_) => "Set",
+                ObjectKind::WeakMap(_) => "WeakMap",
+                ObjectKind::WeakSet(_) => "WeakSet",
+                ObjectKind::Promise(_) => "Promise",
+                ObjectKind::ArrayBuffer(buffer) if buffer.shared => "SharedArrayBuffer",
+                ObjectKind::ArrayBuffer(_) => "ArrayBuffer",
+                ObjectKind::TypedArray(view) => return format!("#<{}Array>", view.element.name()),
+                ObjectKind::DataView(_) => "DataView",
+                _ if object.is_callable() => "Function",
+                _ => "Object",
+            };
+            format!("#<{}>", name)
+        }
+        other => other.to_js_string(),
+    }
+}
+
+fn incompatible_receiver(interpreter: &Interpreter, method: &str, this: &Value) -> String {
+    type_error(format!("Method {} called on incompatible receiver {}", method, receiver_description(interpreter, this)))
+}
@@ -0,0 +1,126 @@
+//! The Unicode normalization forms behind `String.prototype.normalize`.
+
+mod tables;
+
+use tables::{COMBINING_CLASSES, COMPOSITIONS, DECOMPOSITIONS};
+
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum Form {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+// Hangul syllables are composed from leading consonants, vowels and
+// optional trailing consonants by arithmetic
+const S_BASE: u32 = 0xAC00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11A7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const S_COUNT: u32 = L_COUNT * V_COUNT * T_COUNT;
+
+pub(super) fn normalize(s: &str, form: Form) -> String {
+    let compatibility = matches!(form, Form::Nfkc | Form::Nfkd);
+    let mut code_points = Vec::with_capacity(s.len());
+    for c in s.chars() {
+        decompose(c as u32, compatibility, &mut code_points);
+    }
+    reorder(&mut code_points);
+    if matches!(form, Form::Nfc | Form::Nfkc) {
+        code_points = compose(&code_points);
+    }
+    code_points.into_iter().filter_map(char::from_u32).collect()
+}
+
+fn combining_class(code_point: u32) -> u8 {
+    let index = COMBINING_CLASSES.partition_point(|&(_, last, _)| last < code_point);
+    match COMBINING_CLASSES.get(index) {
+        Some(&(first, _, class)) if first <= code_point => class,
+        _ => 0,
+    }
+}
+
+/// Appends the full decomposition of `code_point` to `output`.
+fn decompose(code_point: u32, compatibility: bool, output: &mut Vec<u32>) {
+    if (S_BASE..S_BASE + S_COUNT).contains(&code_point) {
+        let index = code_point - S_BASE;
+        output.push(L_BASE + index / (V_COUNT * T_COUNT));
+        output.push(V_BASE + index % (V_COUNT * T_COUNT) / T_COUNT);
+        if !index.is_multiple_of(T_COUNT) {
+            output.push(T_BASE + index % T_COUNT);
+        }
+        return;
+    }
+    match DECOMPOSITIONS.binary_search_by_key(&code_point, |&(code_point, _, _)| code_point) {
+        Ok(index) if compatibility || !DECOMPOSITIONS[index].1 => {
+            for &part in DECOMPOSITIONS[index].2 {
+                decompose(part, compatibility, output);
+            }
+        }
+        _ => output.push(code_point),
+    }
+}
+
+/// Puts each run of combining marks in canonical order: sorted by
+/// combining class, keeping the order of marks in the same class.
+fn reorder(code_points: &mut [u32]) {
+    let mut start = 0;
+    while start < code_points.len() {
+        if combining_class(code_points[start]) == 0 {
+            start += 1;
+            continue;
+        }
+        let end = start + code_points[start..].iter().take_while(|&&c| combining_class(c) != 0).count();
+        code_points[start..end].sort_by_key(|&c| combining_class(c));
+        start = end;
+    }
+}
+
+/// The primary composite of `first` followed by `second`, if any.
+fn composite(first: u32, second: u32) -> Option<u32> {
+    if (L_BASE..L_BASE + L_COUNT).contains(&first) && (V_BASE..V_BASE + V_COUNT).contains(&second) {
+        return Some(S_BASE + ((first - L_BASE) * V_COUNT + (second - V_BASE)) * T_COUNT);
+    }
+    if (S_BASE..S_BASE + S_COUNT).contains(&first)
+        && (first - S_BASE).is_multiple_of(T_COUNT)
+        && (T_BASE + 1..T_BASE + T_COUNT).contains(&second)
+    {
+        return Some(first + second - T_BASE);
+    }
+    COMPOSITIONS
+        .binary_search_by_key(&(first, second), |&(first, second, _)| (first, second))
+        .ok()
+        .map(|index| COMPOSITIONS[index].2)
+}
+
+/// Canonical composition of fully decomposed, reordered code points.
+fn compose(code_points: &[u32]) -> Vec<u32> {
+    let mut output: Vec<u32> = Vec::with_capacity(code_points.len());
+    let mut starter: Option<usize> = None;
+    // The combining class of the last code point kept after the starter
+    let mut last_class = 0;
+    for &code_point in code_points {
+        let class = combining_class(code_point);
+        if let Some(starter) = starter {
+            // A mark is blocked from the starter by any mark in between of
+            // the same or a higher class
+            let adjacent = output.len() - 1 == starter;
+            if adjacent || (last_class != 0 && last_class < class) {
+                if let Some(composed) = composite(output[starter], code_point) {
+                    output[starter] = composed;
+                    continue;
+                }
+            }
+        }
+        if class == 0 {
+            starter = Some(output.len());
+        }
+        last_class = class;
+        output.push(code_point);
+    }
+    output
+}
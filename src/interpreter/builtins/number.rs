@@ -0,0 +1,450 @@
+//! `Number`, `Number.prototype` and the global numeric functions
+//! `parseInt`, `parseFloat`, `isNaN` and `isFinite`.
+
+use super::{arg, define_global, define_method, to_integer};
+use crate::interpreter::object::{Attributes, NativeFunction, ObjectKind, Property, PropertyKey};
+use crate::interpreter::{number_to_string, range_error, to_int32, type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.number_prototype;
+    let constructor = interpreter.create_native_function("Number", 1, number_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("toExponential", 1, to_exponential),
+        ("toFixed", 1, to_fixed),
+        ("toLocaleString", 0, to_locale_string),
+        ("toPrecision", 1, to_precision),
+        ("toString", 1, to_string),
+        ("valueOf", 0, value_of),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+
+    let constants = [
+        ("EPSILON", f64::EPSILON),
+        ("MAX_SAFE_INTEGER", 9007199254740991.0),
+        ("MAX_VALUE", f64::MAX),
+        ("MIN_SAFE_INTEGER", -9007199254740991.0),
+        ("MIN_VALUE", 5e-324),
+        ("NaN", f64::NAN),
+        ("NEGATIVE_INFINITY", f64::NEG_INFINITY),
+        ("POSITIVE_INFINITY", f64::INFINITY),
+    ];
+    for (name, value) in constants {
+        interpreter.heap.define_property(constructor_id, PropertyKey::from(name), Property::data(Value::Number(value), Attributes::FIXED));
+    }
+
+    let statics: &[(&str, u32, NativeFunction)] = &[
+        ("isFinite", 1, number_is_finite),
+        ("isInteger", 1, is_integer),
+        ("isNaN", 1, number_is_nan),
+        ("isSafeInteger", 1, is_safe_integer),
+    ];
+    for &(name, length, function) in statics {
+        define_method(interpreter, constructor_id, name, length, function);
+    }
+
+    // `Number.parseInt` is the global `parseInt` itself
+    let parsers: &[(&str, u32, NativeFunction)] = &[("parseFloat", 1, parse_float), ("parseInt", 2, parse_int)];
+    for &(name, length, function) in parsers {
+        let parser = interpreter.create_native_function(name, length, function);
+        interpreter.heap.define_property(constructor_id, PropertyKey::from(name), Property::data(parser.clone(), Attributes::HIDDEN));
+        define_global(interpreter, name, parser);
+    }
+
+    let globals: &[(&str, u32, NativeFunction)] = &[("isFinite", 1, is_finite), ("isNaN", 1, is_nan)];
+    for &(name, length, function) in globals {
+        let function = interpreter.create_native_function(name, length, function);
+        define_global(interpreter, name, function);
+    }
+    define_global(interpreter, "Infinity", Value::Number(f64::INFINITY));
+    define_global(interpreter, "NaN", Value::Number(f64::NAN));
+    define_global(interpreter, "Number", constructor);
+}
+
+/// `Number(value)` converts, BigInts included; `new Number(value)` creates
+/// a wrapper object.
+fn number_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let n = match args.first() {
+        Some(value) => interpreter.coerce_to_numeric(value)?.to_number(),
+        None => 0.0,
+    };
+    if interpreter.new_target.is_none() {
+        return Ok(Value::Number(n));
+    }
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.number_prototype)?;
+    Ok(Value::Object(interpreter.create_primitive_wrapper(Value::Number(n), prototype)))
+}
+
+/// The number a `Number.prototype` method was called on, unwrapping
+/// `Number` objects.
+fn this_number(interpreter: &Interpreter, this: &Value, method: &str) -> Result<f64, String> {
+    match this {
+        Value::Number(n) => Ok(*n),
+        Value::Object(id) => match interpreter.heap.get(*id).kind {
+            ObjectKind::Primitive(Value::Number(n)) => Ok(n),
+            _ => Err(type_error(format!("Number.prototype.{} requires that 'this' be a Number", method))),
+        },
+        _ => Err(type_error(format!("Number.prototype.{} requires that 'this' be a Number", method))),
+    }
+}
+
+/// A digits argument converted with `ToIntegerOrInfinity`, `None` when it
+/// is `undefined`.
+fn digits_argument(interpreter: &mut Interpreter, value: &Value) -> Result<Option<f64>, String> {
+    match value {
+        Value::Undefined => Ok(None),
+        value => Ok(Some(to_integer(interpreter.coerce_to_number(value)?))),
+    }
+}
+
+fn value_of(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(this_number(interpreter, this, "valueOf")?))
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let n = this_number(interpreter, this, "toString")?;
+    let radix = digits_argument(interpreter, &arg(args, 0))?.unwrap_or(10.0);
+    if !(2.0..=36.0).contains(&radix) {
+        return Err(range_error("toString() radix must be between 2 and 36"));
+    }
+    if radix == 10.0 || !n.is_finite() {
+        return Ok(Value::String(number_to_string(n).into()));
+    }
+    Ok(Value::String(to_radix_string(n, radix as u32).into()))
+}
+
+/// `Number.prototype.toLocaleString` for the `en-US` locale: grouped
+/// thousands and at most three fraction digits.
+fn to_locale_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let n = this_number(interpreter, this, "toLocaleString")?;
+    if !n.is_finite() {
+        let text = if n.is_nan() { "NaN" } else if n > 0.0 { "∞" } else { "-∞" };
+        return Ok(Value::String(text.into()));
+    }
+    let fixed = fixed_digits(n.abs(), 3);
+    let (integer, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut grouped = group_thousands(integer);
+    let fraction = fraction.trim_end_matches('0');
+    if !fraction.is_empty() {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    let sign = if n.is_sign_negative() { "-" } else { "" };
+    Ok(Value::String(format!("{}{}", sign, grouped).into()))
+}
+
+/// Separates the thousands of a run of integer digits with commas.
+pub(super) fn group_thousands(integer: &str) -> String {
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+fn to_fixed(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let n = this_number(interpreter, this, "toFixed")?;
+    let digits = digits_argument(interpreter, &arg(args, 0))?.unwrap_or(0.0);
+    if !(0.0..=100.0).contains(&digits) {
+        return Err(range_error("toFixed() digits argument must be between 0 and 100"));
+    }
+    if !n.is_finite() || n.abs() >= 1e21 {
+        return Ok(Value::String(number_to_string(n).into()));
+    }
+    let sign = if n < 0.0 { "-" } else { "" };
+    Ok(Value::String(format!("{}{}", sign, fixed_digits(n.abs(), digits as usize)).into()))
+}
+
+fn to_exponential(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let n = this_number(interpreter, this, "toExponential")?;
+    let digits = digits_argument(interpreter, &arg(args, 0))?;
+    if !n.is_finite() {
+        return Ok(Value::String(number_to_string(n).into()));
+    }
+    if digits.is_some_and(|digits| !(0.0..=100.0).contains(&digits)) {
+        return Err(range_error("toExponential() argument must be between 0 and 100"));
+    }
+    let (digits, exponent) = match digits {
+        Some(digits) => significant_digits(n.abs(), digits as usize + 1),
+        None => shortest_digits(n.abs()),
+    };
+    let sign = if n < 0.0 { "-" } else { "" };
+    Ok(Value::String(format!("{}{}", sign, exponential_notation(&digits, exponent)).into()))
+}
+
+fn to_precision(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let n = this_number(interpreter, this, "toPrecision")?;
+    let Some(precision) = digits_argument(interpreter, &arg(args, 0))? else {
+        return Ok(Value::String(number_to_string(n).into()));
+    };
+    if !n.is_finite() {
+        return Ok(Value::String(number_to_string(n).into()));
+    }
+    if !(1.0..=100.0).contains(&precision) {
+        return Err(range_error("toPrecision() argument must be between 1 and 100"));
+    }
+    let precision = precision as usize;
+    let (digits, exponent) = significant_digits(n.abs(), precision);
+    let sign = if n < 0.0 { "-" } else { "" };
+    let text = if exponent < -6 || exponent >= precision as i32 {
+        exponential_notation(&digits, exponent)
+    } else if exponent >= 0 {
+        let (integer, fraction) = digits.split_at(exponent as usize + 1);
+        if fraction.is_empty() { integer.to_string() } else { format!("{}.{}", integer, fraction) }
+    } else {
+        format!("0.{}{}", "0".repeat((-exponent - 1) as usize), digits)
+    };
+    Ok(Value::String(format!("{}{}", sign, text).into()))
+}
+
+/// The exact decimal expansion of a finite, non-negative `n` as its
+/// integer digits and enough fraction digits to hold any double.
+fn exact_decimal(n: f64) -> (String, String) {
+    let text = format!("{:.1100}", n);
+    let (integer, fraction) = text.split_once('.').expect("formatted with a fraction");
+    (integer.to_string(), fraction.to_string())
+}
+
+/// Keeps the first `count` of `digits`, rounding half up as the number
+/// formatting methods do. The result is one digit longer when rounding
+/// carries out of the first digit.
+fn round_digits(digits: &str, count: usize) -> String {
+    let mut kept: Vec<u8> = digits.as_bytes()[..count].to_vec();
+    if digits.as_bytes().get(count).is_some_and(|&digit| digit >= b'5') {
+        let mut index = count;
+        loop {
+            if index == 0 {
+                kept.insert(0, b'1');
+                break;
+            }
+            index -= 1;
+            if kept[index] == b'9' {
+                kept[index] = b'0';
+            } else {
+                kept[index] += 1;
+                break;
+            }
+        }
+    }
+    String::from_utf8(kept).expect("decimal digits")
+}
+
+/// `n` with exactly `fraction_digits` digits after the point.
+fn fixed_digits(n: f64, fraction_digits: usize) -> String {
+    let (integer, fraction) = exact_decimal(n);
+    let rounded = round_digits(&format!("{}{}", integer, fraction), integer.len() + fraction_digits);
+    let (integer, fraction) = rounded.split_at(rounded.len() - fraction_digits);
+    if fraction.is_empty() { integer.to_string() } else { format!("{}.{}", integer, fraction) }
+}
+
+/// The first `count` significant digits of a finite, non-negative `n` and
+/// the decimal exponent of the first one.
+fn significant_digits(n: f64, count: usize) -> (String, i32) {
+    if n == 0.0 {
+        return ("0".repeat(count), 0);
+    }
+    let (integer, fraction) = exact_decimal(n);
+    let all = format!("{}{}", integer, fraction);
+    let first = all.find(|c| c != '0').expect("n is not zero");
+    let mut exponent = integer.len() as i32 - 1 - first as i32;
+    let mut digits = round_digits(&all[first..], count);
+    if digits.len() > count {
+        digits.truncate(count);
+        exponent += 1;
+    }
+    (digits, exponent)
+}
+
+/// The shortest digits that round-trip to a finite, non-negative `n`, and
+/// the decimal exponent of the first one.
+fn shortest_digits(n: f64) -> (String, i32) {
+    let formatted = format!("{:e}", n);
+    let (mantissa, exponent) = formatted.split_once('e').expect("exponential format");
+    (mantissa.replace('.', ""), exponent.parse().expect("exponent"))
+}
+
+/// `d.ddde+x` notation for significant `digits` and their `exponent`.
+fn exponential_notation(digits: &str, exponent: i32) -> String {
+    let (first, rest) = digits.split_at(1);
+    let sign = if exponent < 0 { '-' } else { '+' };
+    if rest.is_empty() {
+        format!("{}e{}{}", first, sign, exponent.abs())
+    } else {
+        format!("{}.{}e{}{}", first, rest, sign, exponent.abs())
+    }
+}
+
+/// Formats a finite number in a radix other than 10, following V8: the
+/// fraction gets as many digits as the double's precision supports.
+fn to_radix_string(n: f64, radix: u32) -> String {
+    let digit_char = |digit: u32| std::char::from_digit(digit, radix).expect("digit below radix");
+    let radix_f = radix as f64;
+    let negative = n < 0.0;
+    let value = n.abs();
+    let mut integer = value.floor();
+    let mut fraction = value - integer;
+
+    // Half the distance to the next double: digits below it are noise
+    let next = f64::from_bits(value.to_bits() + 1);
+    let mut delta = (0.5 * (next - value)).max(f64::from_bits(1));
+    let mut fraction_digits = String::new();
+    if fraction >= delta {
+        loop {
+            fraction *= radix_f;
+            delta *= radix_f;
+            let digit = fraction as u32;
+            fraction_digits.push(digit_char(digit));
+            fraction -= digit as f64;
+            if (fraction > 0.5 || (fraction == 0.5 && digit & 1 == 1)) && fraction + delta > 1.0 {
+                // Round up, carrying into earlier digits
+                loop {
+                    match fraction_digits.pop() {
+                        None => {
+                            integer += 1.0;
+                            break;
+                        }
+                        Some(c) => {
+                            let digit = c.to_digit(radix).expect("digit below radix");
+                            if digit + 1 < radix {
+                                fraction_digits.push(digit_char(digit + 1));
+                                break;
+                            }
+                        }
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+
+    // Digits of the integer part beyond the double's precision are zeros
+    let mut integer_digits = Vec::new();
+    while integer / radix_f >= 9007199254740992.0 {
+        integer /= radix_f;
+        integer_digits.push('0');
+    }
+    loop {
+        let remainder = integer % radix_f;
+        integer_digits.push(digit_char(remainder as u32));
+        integer = (integer - remainder) / radix_f;
+        if integer <= 0.0 {
+            break;
+        }
+    }
+
+    let mut text = String::new();
+    if negative {
+        text.push('-');
+    }
+    text.extend(integer_digits.iter().rev());
+    if !fraction_digits.is_empty() {
+        text.push('.');
+        text.push_str(&fraction_digits);
+    }
+    text
+}
+
+fn number_is_finite(_interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(matches!(arg(args, 0), Value::Number(n) if n.is_finite())))
+}
+
+fn number_is_nan(_interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(matches!(arg(args, 0), Value::Number(n) if n.is_nan())))
+}
+
+fn is_integer(_interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(matches!(arg(args, 0), Value::Number(n) if n.is_finite() && n.trunc() == n)))
+}
+
+fn is_safe_integer(_interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let safe = matches!(arg(args, 0), Value::Number(n) if n.trunc() == n && n.abs() <= 9007199254740991.0);
+    Ok(Value::Boolean(safe))
+}
+
+fn is_finite(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(interpreter.coerce_to_number(&arg(args, 0))?.is_finite()))
+}
+
+fn is_nan(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(interpreter.coerce_to_number(&arg(args, 0))?.is_nan()))
+}
+
+/// `parseInt(string, radix)`: the longest prefix of digits in `radix`,
+/// which defaults to 16 after a `0x` prefix and 10 otherwise.
+fn parse_int(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let input = interpreter.coerce_to_string(&arg(args, 0))?.to_string();
+    let mut radix = to_int32(interpreter.coerce_to_number(&arg(args, 1))?) as u32;
+    let text = input.trim_start();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let strip_prefix = match radix {
+        0 => {
+            radix = 10;
+            true
+        }
+        2..=36 => radix == 16,
+        _ => return Ok(Value::Number(f64::NAN)),
+    };
+    let text = match text.get(..2) {
+        Some("0x" | "0X") if strip_prefix => {
+            radix = 16;
+            &text[2..]
+        }
+        _ => text,
+    };
+    let end = text.find(|c: char| !c.is_digit(radix)).unwrap_or(text.len());
+    let digits = &text[..end];
+    if digits.is_empty() {
+        return Ok(Value::Number(f64::NAN));
+    }
+    let value = if radix == 10 {
+        digits.parse::<f64>().expect("decimal digits")
+    } else {
+        digits.chars().fold(0.0, |acc, c| acc * radix as f64 + c.to_digit(radix).expect("digit below radix") as f64)
+    };
+    Ok(Value::Number(if negative { -value } else { value }))
+}
+
+/// `parseFloat(string)`: the longest prefix that is a decimal literal.
+fn parse_float(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let input = interpreter.coerce_to_string(&arg(args, 0))?.to_string();
+    let text = input.trim_start();
+    let bytes = text.as_bytes();
+    let mut end = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    if text[end..].starts_with("Infinity") {
+        return Ok(Value::Number(if bytes[0] == b'-' { f64::NEG_INFINITY } else { f64::INFINITY }));
+    }
+    let count_digits = |from: usize| bytes[from..].iter().take_while(|b| b.is_ascii_digit()).count();
+    let integer_digits = count_digits(end);
+    end += integer_digits;
+    let mut fraction_digits = 0;
+    if bytes.get(end) == Some(&b'.') {
+        fraction_digits = count_digits(end + 1);
+        if integer_digits + fraction_digits > 0 {
+            end += 1 + fraction_digits;
+        }
+    }
+    if integer_digits + fraction_digits == 0 {
+        return Ok(Value::Number(f64::NAN));
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exponent_digits = count_digits(end + 1 + sign);
+        if exponent_digits > 0 {
+            end += 1 + sign + exponent_digits;
+        }
+    }
+    Ok(Value::Number(text[..end].parse::<f64>().expect("decimal literal")))
+}
@@ -0,0 +1,476 @@
+//! `Object`, its static methods and `Object.prototype`.
+
+use super::{arg, define_global, define_method};
+use crate::interpreter::object::{Attributes, NativeFunction, Object, ObjectId, ObjectKind, Property, PropertyDescriptor, PropertyKey, PropertyValue};
+use crate::interpreter::value::same_value;
+use crate::interpreter::{type_error, Interpreter, JsString, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.object_prototype;
+    let constructor = interpreter.create_native_function("Object", 1, object_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+
+    let statics: &[(&str, u32, NativeFunction)] = &[
+        ("assign", 2, assign),
+        ("create", 2, create),
+        ("defineProperties", 2, define_properties),
+        ("defineProperty", 3, define_property),
+        ("entries", 1, entries),
+        ("freeze", 1, freeze),
+        ("fromEntries", 1, from_entries),
+        ("getOwnPropertyDescriptor", 2, get_own_property_descriptor),
+        ("getOwnPropertyDescriptors", 1, get_own_property_descriptors),
+        ("getOwnPropertyNames", 1, get_own_property_names),
+        ("getOwnPropertySymbols", 1, get_own_property_symbols),
+        ("getPrototypeOf", 1, get_prototype_of),
+        ("hasOwn", 2, has_own),
+        ("is", 2, is),
+        ("isExtensible", 1, is_extensible),
+        ("isFrozen", 1, is_frozen),
+        ("isSealed", 1, is_sealed),
+        ("keys", 1, keys),
+        ("preventExtensions", 1, prevent_extensions),
+        ("seal", 1, seal),
+        ("setPrototypeOf", 2, set_prototype_of),
+        ("values", 1, values),
+    ];
+    for &(name, length, function) in statics {
+        define_method(interpreter, constructor_id, name, length, function);
+    }
+
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("hasOwnProperty", 1, has_own_property),
+        ("isPrototypeOf", 1, is_prototype_of),
+        ("propertyIsEnumerable", 1, property_is_enumerable),
+        ("toLocaleString", 0, to_locale_string),
+        ("toString", 0, to_string),
+        ("valueOf", 0, value_of),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+
+    define_global(interpreter, "Object", constructor);
+}
+
+fn object_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    // `super()` from a subclass creates an instance of the subclass,
+    // ignoring the argument
+    let object_prototype = interpreter.intrinsics.object_prototype;
+    let prototype = interpreter.new_target_prototype(object_prototype)?;
+    if prototype != object_prototype {
+        return Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::Ordinary, Some(prototype)))));
+    }
+    match arg(args, 0) {
+        Value::Null | Value::Undefined => Ok(Value::Object(interpreter.create_object())),
+        value => {
+            let id = interpreter.coerce_to_object(&value)?;
+            Ok(interpreter.object_value(id))
+        }
+    }
+}
+
+/// The object `value` refers to, or `None` for primitives. `null` and
+/// `undefined` are rejected as `ToObject` would.
+fn object_id(value: &Value) -> Result<Option<ObjectId>, String> {
+    match value {
+        Value::Object(id) | Value::Function(id) => Ok(Some(*id)),
+        Value::Null | Value::Undefined => Err(type_error("Cannot convert undefined or null to object")),
+        _ => Ok(None),
+    }
+}
+
+/// Like `object_id`, but for methods that only accept objects.
+fn require_object(interpreter: &Interpreter, value: &Value, method: &str) -> Result<ObjectId, String> {
+    match value {
+        Value::Object(id) | Value::Function(id) => Ok(*id),
+        other => Err(type_error(format!("{} called on non-object {}", method, interpreter.to_display_string(other)))),
+    }
+}
+
+/// The own string keys of `value` in enumeration order, optionally
+/// including the non-enumerable ones.
+fn own_keys(interpreter: &mut Interpreter, value: &Value, include_hidden: bool) -> Result<Vec<PropertyKey>, String> {
+    let mut keys = own_property_keys(interpreter, value, include_hidden)?;
+    keys.retain(|key| !matches!(key, PropertyKey::Symbol(_)));
+    Ok(keys)
+}
+
+/// `own_keys` followed by the own symbol keys.
+fn own_property_keys(interpreter: &mut Interpreter, value: &Value, include_hidden: bool) -> Result<Vec<PropertyKey>, String> {
+    match object_id(value)? {
+        Some(id) => {
+            let mut keys = interpreter.own_property_keys(id)?;
+            if !include_hidden {
+                let mut enumerable = Vec::with_capacity(keys.len());
+                for key in keys {
+                    if interpreter.get_own_property(id, &key)?.is_some_and(|property| property.attributes.enumerable) {
+                        enumerable.push(key);
+                    }
+                }
+                keys = enumerable;
+            }
+            Ok(keys)
+        }
+        None => match value {
+            Value::String(s) => {
+                let mut keys: Vec<_> = (0..s.len() as u32).map(PropertyKey::Index).collect();
+                if include_hidden {
+                    keys.push(PropertyKey::from("length"));
+                }
+                Ok(keys)
+            }
+            _ => Ok(Vec::new()),
+        },
+    }
+}
+
+fn keys(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let keys = own_keys(interpreter, &arg(args, 0), false)?;
+    let keys = keys.into_iter().map(|key| Value::String(JsString::from(key.to_string()))).collect();
+    Ok(interpreter.create_array(keys))
+}
+
+fn values(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    let mut values = Vec::new();
+    for key in own_keys(interpreter, &target, false)? {
+        values.push(interpreter.get_member(&target, &key)?);
+    }
+    Ok(interpreter.create_array(values))
+}
+
+fn entries(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    let mut entries = Vec::new();
+    for key in own_keys(interpreter, &target, false)? {
+        let value = interpreter.get_member(&target, &key)?;
+        entries.push(interpreter.create_array(vec![Value::String(JsString::from(key.to_string())), value]));
+    }
+    Ok(interpreter.create_array(entries))
+}
+
+fn from_entries(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let entries = interpreter.collect_iterable(&arg(args, 0))?;
+    let id = interpreter.create_object();
+    for entry in entries {
+        if !matches!(entry, Value::Object(_) | Value::Function(_)) {
+            return Err(type_error(format!(
+                "Iterator value {} is not an entry object",
+                interpreter.to_display_string(&entry)
+            )));
+        }
+        let key = interpreter.get_member(&entry, &PropertyKey::Index(0))?;
+        let key = interpreter.coerce_to_property_key(&key)?;
+        let value = interpreter.get_member(&entry, &PropertyKey::Index(1))?;
+        interpreter.heap.define_property(id, key, Property::data(value, Attributes::DEFAULT));
+    }
+    Ok(Value::Object(id))
+}
+
+fn get_own_property_names(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let keys = own_keys(interpreter, &arg(args, 0), true)?;
+    let keys = keys.into_iter().map(|key| Value::String(JsString::from(key.to_string()))).collect();
+    Ok(interpreter.create_array(keys))
+}
+
+fn get_own_property_symbols(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let keys = own_property_keys(interpreter, &arg(args, 0), true)?;
+    let symbols = keys
+        .into_iter()
+        .filter_map(|key| match key {
+            PropertyKey::Symbol(symbol) => Some(Value::Symbol(symbol)),
+            _ => None,
+        })
+        .collect();
+    Ok(interpreter.create_array(symbols))
+}
+
+fn assign(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    if object_id(&target)?.is_none() {
+        return Err(type_error("Object.assign on primitives is not supported"));
+    }
+    for source in args.iter().skip(1) {
+        if matches!(source, Value::Null | Value::Undefined) {
+            continue;
+        }
+        for key in own_property_keys(interpreter, source, false)? {
+            let value = interpreter.get_member(source, &key)?;
+            interpreter.set_member(&target, key, value)?;
+        }
+    }
+    Ok(target)
+}
+
+/// Validates a prototype argument, which must be an object or `null`.
+fn to_prototype(interpreter: &Interpreter, value: &Value) -> Result<Option<ObjectId>, String> {
+    match value {
+        Value::Object(id) | Value::Function(id) => Ok(Some(*id)),
+        Value::Null => Ok(None),
+        other => Err(type_error(format!(
+            "Object prototype may only be an Object or null: {}",
+            interpreter.to_display_string(other)
+        ))),
+    }
+}
+
+fn create(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let prototype = to_prototype(interpreter, &arg(args, 0))?;
+    let id = interpreter.create_object();
+    interpreter.heap.get_mut(id).prototype = prototype;
+    let properties = arg(args, 1);
+    if !matches!(properties, Value::Undefined) {
+        define_properties_from(interpreter, id, &properties)?;
+    }
+    Ok(Value::Object(id))
+}
+
+fn get_prototype_of(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let value = arg(args, 0);
+    let prototype = match object_id(&value)? {
+        Some(id) => interpreter.get_prototype_of(id)?,
+        None => interpreter.primitive_prototype(&value),
+    };
+    Ok(prototype.map_or(Value::Null, |id| interpreter.object_value(id)))
+}
+
+fn set_prototype_of(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    if matches!(target, Value::Null | Value::Undefined) {
+        return Err(type_error("Object.setPrototypeOf called on null or undefined"));
+    }
+    let prototype = to_prototype(interpreter, &arg(args, 1))?;
+    if let Value::Object(id) | Value::Function(id) = target {
+        interpreter.set_prototype(id, prototype)?;
+    }
+    Ok(target)
+}
+
+fn define_property(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    let id = require_object(interpreter, &target, "Object.defineProperty")?;
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    let descriptor = interpreter.descriptor_from_object(&arg(args, 2))?;
+    interpreter.define_property_or_throw(id, key, descriptor)?;
+    Ok(target)
+}
+
+/// Defines every own enumerable property of `properties` as a descriptor.
+fn define_properties_from(interpreter: &mut Interpreter, id: ObjectId, properties: &Value) -> Result<(), String> {
+    let mut descriptors = Vec::new();
+    for key in own_property_keys(interpreter, properties, false)? {
+        let descriptor = interpreter.get_member(properties, &key)?;
+        descriptors.push((key, interpreter.descriptor_from_object(&descriptor)?));
+    }
+    for (key, descriptor) in descriptors {
+        interpreter.define_property_or_throw(id, key, descriptor)?;
+    }
+    Ok(())
+}
+
+fn define_properties(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    let id = require_object(interpreter, &target, "Object.defineProperties")?;
+    define_properties_from(interpreter, id, &arg(args, 1))?;
+    Ok(target)
+}
+
+/// The own property `key` of `value`, including the virtual properties
+/// of strings.
+fn own_property(interpreter: &mut Interpreter, value: &Value, key: &PropertyKey) -> Result<Option<Property>, String> {
+    match object_id(value)? {
+        Some(id) => interpreter.get_own_property(id, key),
+        None => {
+            if !matches!(value, Value::String(_)) || !own_keys(interpreter, value, true)?.contains(key) {
+                return Ok(None);
+            }
+            let element = interpreter.get_member(value, key)?;
+            let attributes = Attributes { writable: false, enumerable: key.as_index().is_some(), configurable: false };
+            Ok(Some(Property::data(element, attributes)))
+        }
+    }
+}
+
+fn get_own_property_descriptor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    match own_property(interpreter, &target, &key)? {
+        Some(property) => Ok(interpreter.descriptor_object(&PropertyDescriptor::of(&property))),
+        None => Ok(Value::Undefined),
+    }
+}
+
+fn get_own_property_descriptors(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    let result = interpreter.create_object();
+    for key in own_property_keys(interpreter, &target, true)? {
+        if let Some(property) = own_property(interpreter, &target, &key)? {
+            let descriptor = interpreter.descriptor_object(&PropertyDescriptor::of(&property));
+            interpreter.heap.define_property(result, key, Property::data(descriptor, Attributes::DEFAULT));
+        }
+    }
+    Ok(Value::Object(result))
+}
+
+/// Prevents new properties, throwing if a proxy refuses.
+fn prevent_extensions_or_throw(interpreter: &mut Interpreter, id: ObjectId) -> Result<(), String> {
+    if !interpreter.prevent_extensions(id)? {
+        return Err(type_error("'preventExtensions' on proxy: trap returned falsish"));
+    }
+    Ok(())
+}
+
+/// Prevents new properties, then makes every own property
+/// non-configurable, and for `freeze` also read-only.
+pub(in crate::interpreter) fn set_integrity_level(interpreter: &mut Interpreter, id: ObjectId, frozen: bool) -> Result<(), String> {
+    prevent_extensions_or_throw(interpreter, id)?;
+    for key in interpreter.own_property_keys(id)? {
+        let mut descriptor = PropertyDescriptor { configurable: Some(false), ..PropertyDescriptor::default() };
+        if frozen {
+            let property = interpreter.get_own_property(id, &key)?;
+            if property.is_some_and(|property| matches!(property.value, PropertyValue::Data(_))) {
+                descriptor.writable = Some(false);
+            }
+        }
+        interpreter.define_property_or_throw(id, key, descriptor)?;
+    }
+    Ok(())
+}
+
+fn test_integrity_level(interpreter: &mut Interpreter, value: &Value, frozen: bool) -> Result<bool, String> {
+    let (Value::Object(id) | Value::Function(id)) = *value else {
+        // Primitives are immutable
+        return Ok(true);
+    };
+    if interpreter.is_extensible(id)? {
+        return Ok(false);
+    }
+    for key in interpreter.own_property_keys(id)? {
+        let Some(property) = interpreter.get_own_property(id, &key)? else {
+            continue;
+        };
+        let writable_data = matches!(property.value, PropertyValue::Data(_)) && property.attributes.writable;
+        if property.attributes.configurable || (frozen && writable_data) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn freeze(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    if let Value::Object(id) | Value::Function(id) = target {
+        set_integrity_level(interpreter, id, true)?;
+    }
+    Ok(target)
+}
+
+fn seal(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    if let Value::Object(id) | Value::Function(id) = target {
+        set_integrity_level(interpreter, id, false)?;
+    }
+    Ok(target)
+}
+
+fn prevent_extensions(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    if let Value::Object(id) | Value::Function(id) = target {
+        prevent_extensions_or_throw(interpreter, id)?;
+    }
+    Ok(target)
+}
+
+fn is_frozen(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(test_integrity_level(interpreter, &arg(args, 0), true)?))
+}
+
+fn is_sealed(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(test_integrity_level(interpreter, &arg(args, 0), false)?))
+}
+
+fn is_extensible(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let extensible = match arg(args, 0) {
+        Value::Object(id) | Value::Function(id) => interpreter.is_extensible(id)?,
+        _ => false,
+    };
+    Ok(Value::Boolean(extensible))
+}
+
+fn is(_interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(same_value(&arg(args, 0), &arg(args, 1))))
+}
+
+fn has_own(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let target = arg(args, 0);
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    Ok(Value::Boolean(own_property(interpreter, &target, &key)?.is_some()))
+}
+
+fn has_own_property(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let key = interpreter.coerce_to_property_key(&arg(args, 0))?;
+    Ok(Value::Boolean(own_property(interpreter, this, &key)?.is_some()))
+}
+
+fn is_prototype_of(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (Value::Object(object) | Value::Function(object)) = arg(args, 0) else {
+        return Ok(Value::Boolean(false));
+    };
+    let Some(prototype) = object_id(this)? else {
+        return Ok(Value::Boolean(false));
+    };
+    let mut current = interpreter.get_prototype_of(object)?;
+    while let Some(id) = current {
+        if id == prototype {
+            return Ok(Value::Boolean(true));
+        }
+        current = interpreter.get_prototype_of(id)?;
+    }
+    Ok(Value::Boolean(false))
+}
+
+fn property_is_enumerable(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let key = interpreter.coerce_to_property_key(&arg(args, 0))?;
+    let property = own_property(interpreter, this, &key)?;
+    Ok(Value::Boolean(property.is_some_and(|property| property.attributes.enumerable)))
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let builtin_tag = match this {
+        Value::Undefined => return Ok(Value::String("[object Undefined]".into())),
+        Value::Null => return Ok(Value::String("[object Null]".into())),
+        Value::Number(_) => "Number",
+        Value::String(_) => "String",
+        Value::Boolean(_) => "Boolean",
+        Value::Symbol(_) | Value::BigInt(_) => "Object",
+        Value::Object(_) | Value::Function(_) if interpreter.is_array(this)? => "Array",
+        Value::Object(id) | Value::Function(id) => match &interpreter.heap.get(*id).kind {
+            ObjectKind::Arguments => "Arguments",
+            ObjectKind::Error => "Error",
+            ObjectKind::Date(_) => "Date",
+            ObjectKind::Primitive(Value::Number(_)) => "Number",
+            ObjectKind::Primitive(Value::String(_)) => "String",
+            ObjectKind::Primitive(Value::Boolean(_)) => "Boolean",
+            _ if interpreter.heap.get(*id).is_callable() => "Function",
+            _ => "Object",
+        },
+    };
+    // Other built-in objects are told apart by `Symbol.toStringTag`
+    let tag = match interpreter.get_member(this, &PropertyKey::Symbol(interpreter.symbols.to_string_tag.clone()))? {
+        Value::String(tag) => tag,
+        _ => builtin_tag.to_string().into(),
+    };
+    Ok(Value::String(format!("[object {}]", tag).into()))
+}
+
+fn to_locale_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let method = interpreter.get_member(this, &PropertyKey::from("toString"))?;
+    interpreter.call_function(&method, this.clone(), Vec::new())
+}
+
+fn value_of(_interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    object_id(this)?;
+    Ok(this.clone())
+}
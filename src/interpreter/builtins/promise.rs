@@ -0,0 +1,202 @@
+//! `Promise`, its prototype and combinators, and `AsyncFunction`.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use super::{arg, define_global, define_method, define_to_string_tag, incompatible_receiver, receiver_description};
+use crate::interpreter::iteration::Iteration;
+use crate::interpreter::object::{Attributes, NativeFunction, Property, PropertyKey};
+use crate::interpreter::promise::{Capability, Combinator, ElementKind, Handler, PromiseFunction};
+use crate::interpreter::{type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.promise_prototype;
+    let constructor_id = interpreter.intrinsics.promise_constructor;
+    let constructor = Value::Function(constructor_id);
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("length"), Property::data(Value::Number(1.0), Attributes::READ_ONLY));
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("name"), Property::data(Value::String("Promise".into()), Attributes::READ_ONLY));
+    super::link_constructor(interpreter, &constructor, prototype);
+
+    let methods: &[(&str, u32, NativeFunction)] = &[("catch", 1, catch), ("finally", 1, finally), ("then", 2, then)];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    define_to_string_tag(interpreter, prototype, "Promise");
+    let statics: &[(&str, u32, NativeFunction)] = &[
+        ("all", 1, |i, this, args| combine(i, this, args, Combination::All)),
+        ("allSettled", 1, |i, this, args| combine(i, this, args, Combination::AllSettled)),
+        ("any", 1, |i, this, args| combine(i, this, args, Combination::Any)),
+        ("race", 1, |i, this, args| combine(i, this, args, Combination::Race)),
+        ("reject", 1, reject),
+        ("resolve", 1, resolve),
+    ];
+    for &(name, length, function) in statics {
+        define_method(interpreter, constructor_id, name, length, function);
+    }
+    define_global(interpreter, "Promise", constructor);
+
+    install_async_function(interpreter);
+}
+
+/// `AsyncFunction` is not a global either; it is reached through the
+/// prototype of async functions, like `GeneratorFunction`.
+fn install_async_function(interpreter: &mut Interpreter) {
+    let function_prototype = interpreter.intrinsics.async_function_prototype;
+    let constructor = interpreter.create_native_function("AsyncFunction", 1, |i, _, args| {
+        super::function::compile_function(i, args, "async function")
+    });
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+    let function_prototype_value = Value::Object(function_prototype);
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("prototype"), Property::data(function_prototype_value, Attributes::FIXED));
+    interpreter.heap.define_property(function_prototype, PropertyKey::from("constructor"), Property::data(constructor, Attributes::READ_ONLY));
+    define_to_string_tag(interpreter, function_prototype, "AsyncFunction");
+}
+
+/// `new Promise(executor)` calls `executor` with the functions that
+/// resolve and reject the new promise.
+pub(in crate::interpreter) fn promise_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if interpreter.new_target.is_none() {
+        return Err(type_error("Promise constructor cannot be invoked without 'new'"));
+    }
+    let executor = arg(args, 0);
+    if !matches!(executor, Value::Function(_)) {
+        return Err(type_error(format!("Promise resolver {} is not a function", receiver_description(interpreter, &executor))));
+    }
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.promise_prototype)?;
+    let promise = interpreter.create_promise(prototype);
+    let (resolve, reject) = interpreter.create_resolving_functions(promise);
+    if let Err(message) = interpreter.call_function(&executor, Value::Undefined, vec![resolve, reject.clone()]) {
+        let reason = interpreter.catch_exception(&message);
+        interpreter.call_function(&reject, Value::Undefined, vec![reason])?;
+    }
+    Ok(Value::Object(promise))
+}
+
+fn then(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let Some(promise) = interpreter.promise_id(this) else {
+        return Err(incompatible_receiver(interpreter, "Promise.prototype.then", this));
+    };
+    let constructor = interpreter.promise_species_constructor(this)?;
+    let capability = interpreter.new_promise_capability(&constructor)?;
+    let result = capability.promise.clone();
+    interpreter.perform_then(promise, Handler::from_value(arg(args, 0)), Handler::from_value(arg(args, 1)), Some(capability));
+    Ok(result)
+}
+
+fn catch(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    interpreter.invoke(this, "then", vec![Value::Undefined, arg(args, 0)])
+}
+
+/// `promise.finally(callback)` calls `callback` however the promise
+/// settles, then settles like the promise unless the callback throws.
+fn finally(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    if !matches!(this, Value::Object(_) | Value::Function(_)) {
+        return Err(incompatible_receiver(interpreter, "Promise.prototype.finally", this));
+    }
+    let callback = arg(args, 0);
+    let (on_fulfilled, on_rejected) = match callback {
+        Value::Function(_) => {
+            let constructor = interpreter.promise_species_constructor(this)?;
+            let then_finally = PromiseFunction::ThenFinally { callback: callback.clone(), constructor: constructor.clone() };
+            let catch_finally = PromiseFunction::CatchFinally { callback, constructor };
+            (interpreter.create_promise_function(then_finally, 1), interpreter.create_promise_function(catch_finally, 1))
+        }
+        _ => (callback.clone(), callback),
+    };
+    interpreter.invoke(this, "then", vec![on_fulfilled, on_rejected])
+}
+
+fn require_object(interpreter: &Interpreter, this: &Value, method: &str) -> Result<(), String> {
+    match this {
+        Value::Object(_) | Value::Function(_) => Ok(()),
+        _ => Err(incompatible_receiver(interpreter, method, this)),
+    }
+}
+
+fn resolve(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    require_object(interpreter, this, "Promise.resolve")?;
+    interpreter.promise_resolve(this, arg(args, 0))
+}
+
+fn reject(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    require_object(interpreter, this, "Promise.reject")?;
+    let capability = interpreter.new_promise_capability(this)?;
+    interpreter.call_function(&capability.reject, Value::Undefined, vec![arg(args, 0)])?;
+    Ok(capability.promise)
+}
+
+/// The combinators, which differ in what settles their promise.
+#[derive(Clone, Copy, PartialEq)]
+enum Combination {
+    /// Fulfills with all values, or rejects with the first reason
+    All,
+    /// Fulfills with the outcomes of all inputs
+    AllSettled,
+    /// Fulfills with the first value, or rejects with all reasons
+    Any,
+    /// Settles like the first input to settle
+    Race,
+}
+
+/// `Promise.all`, `allSettled`, `any` and `race`. Errors along the way
+/// reject the promise returned rather than being thrown.
+fn combine(interpreter: &mut Interpreter, this: &Value, args: &[Value], combination: Combination) -> Result<Value, String> {
+    let capability = interpreter.new_promise_capability(this)?;
+    let result = interpreter
+        .get_member(this, &PropertyKey::from("resolve"))
+        .and_then(|resolve| match resolve {
+            Value::Function(_) => Ok(resolve),
+            other => Err(type_error(format!("{} is not a function", interpreter.to_display_string(&other)))),
+        })
+        .and_then(|resolve| {
+            let mut iteration = interpreter.iterate(&arg(args, 0))?;
+            let completion = combine_inputs(interpreter, this, &resolve, &mut iteration, &capability, combination);
+            interpreter.close_iteration(&mut iteration, completion)
+        });
+    if let Err(message) = result {
+        let reason = interpreter.catch_exception(&message);
+        interpreter.call_function(&capability.reject, Value::Undefined, vec![reason])?;
+    }
+    Ok(capability.promise)
+}
+
+/// Resolves each input with `resolve` and registers the handlers the
+/// combination needs on the promise that makes.
+fn combine_inputs(
+    interpreter: &mut Interpreter,
+    constructor: &Value,
+    resolve: &Value,
+    iteration: &mut Iteration,
+    capability: &Capability,
+    combination: Combination,
+) -> Result<(), String> {
+    let combinator = Rc::new(Combinator { values: RefCell::new(Vec::new()), remaining: Cell::new(1), capability: capability.clone() });
+    let mut index = 0;
+    while let Some(value) = interpreter.iteration_step(iteration)? {
+        let next = interpreter.call_function(resolve, constructor.clone(), vec![value])?;
+        if combination == Combination::Race {
+            interpreter.invoke(&next, "then", vec![capability.resolve.clone(), capability.reject.clone()])?;
+            continue;
+        }
+        combinator.values.borrow_mut().push(Value::Undefined);
+        combinator.remaining.set(combinator.remaining.get() + 1);
+        let called = Rc::new(Cell::new(false));
+        let mut element = |kind| {
+            let function = PromiseFunction::Element { combinator: Rc::clone(&combinator), index, kind, called: Rc::clone(&called) };
+            interpreter.create_promise_function(function, 1)
+        };
+        let handlers = match combination {
+            Combination::All => vec![element(ElementKind::Value), capability.reject.clone()],
+            Combination::AllSettled => vec![element(ElementKind::Fulfilled), element(ElementKind::Rejected)],
+            Combination::Any => vec![capability.resolve.clone(), element(ElementKind::Reason)],
+            Combination::Race => unreachable!(),
+        };
+        interpreter.invoke(&next, "then", handlers)?;
+        index += 1;
+    }
+    if combination != Combination::Race {
+        interpreter.finish_combinator_input(&combinator, combination == Combination::Any)?;
+    }
+    Ok(())
+}
+
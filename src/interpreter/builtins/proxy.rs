@@ -0,0 +1,53 @@
+//! `Proxy` and `Proxy.revocable`. The traps themselves are run by the
+//! internal methods in `interpreter/internal.rs`.
+
+use super::{arg, define_global, define_method};
+use crate::interpreter::object::{Attributes, Object, ObjectId, ObjectKind, Property, PropertyKey, ProxyData};
+use crate::interpreter::{type_error, Interpreter, JsString, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let constructor_id = interpreter.intrinsics.proxy_constructor;
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("length"), Property::data(Value::Number(2.0), Attributes::READ_ONLY));
+    interpreter.heap.define_property(constructor_id, PropertyKey::from("name"), Property::data(Value::String("Proxy".into()), Attributes::READ_ONLY));
+    define_method(interpreter, constructor_id, "revocable", 2, revocable);
+    define_global(interpreter, "Proxy", Value::Function(constructor_id));
+}
+
+/// `new Proxy(target, handler)`.
+pub(in crate::interpreter) fn proxy_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if interpreter.new_target.is_none() {
+        return Err(type_error("Constructor Proxy requires 'new'"));
+    }
+    let id = create_proxy(interpreter, &arg(args, 0), &arg(args, 1))?;
+    Ok(interpreter.object_value(id))
+}
+
+/// The `ProxyCreate` operation.
+fn create_proxy(interpreter: &mut Interpreter, target: &Value, handler: &Value) -> Result<ObjectId, String> {
+    let (Value::Object(target_id) | Value::Function(target_id), Value::Object(handler_id) | Value::Function(handler_id)) = (target, handler) else {
+        return Err(type_error("Cannot create proxy with a non-object as target or handler"));
+    };
+    let proxy = ProxyData {
+        target: Some(*target_id),
+        handler: Some(*handler_id),
+        callable: interpreter.heap.get(*target_id).is_callable(),
+        constructor: interpreter.is_constructor(target),
+    };
+    Ok(interpreter.heap.allocate(Object::new(ObjectKind::Proxy(proxy), None)))
+}
+
+/// `Proxy.revocable(target, handler)`: a proxy together with a function
+/// that revokes it.
+fn revocable(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let proxy_id = create_proxy(interpreter, &arg(args, 0), &arg(args, 1))?;
+    let proxy = interpreter.object_value(proxy_id);
+    let prototype = Some(interpreter.intrinsics.function_prototype);
+    let revoke = interpreter.heap.allocate(Object::new(ObjectKind::RevokeProxy(Some(proxy_id)), prototype));
+    interpreter.heap.define_property(revoke, PropertyKey::from("length"), Property::data(Value::Number(0.0), Attributes::READ_ONLY));
+    interpreter.heap.define_property(revoke, PropertyKey::from("name"), Property::data(Value::String(JsString::new()), Attributes::READ_ONLY));
+
+    let result = interpreter.create_object();
+    interpreter.heap.define_property(result, PropertyKey::from("proxy"), Property::data(proxy, Attributes::DEFAULT));
+    interpreter.heap.define_property(result, PropertyKey::from("revoke"), Property::data(Value::Function(revoke), Attributes::DEFAULT));
+    Ok(Value::Object(result))
+}
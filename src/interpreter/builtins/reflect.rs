@@ -0,0 +1,134 @@
+//! The `Reflect` namespace, one function per internal method of objects.
+
+use super::{arg, define_global, define_method, define_to_string_tag};
+use crate::interpreter::object::{NativeFunction, ObjectId, PropertyDescriptor};
+use crate::interpreter::{type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let reflect = interpreter.create_object();
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("apply", 3, apply),
+        ("construct", 2, construct),
+        ("defineProperty", 3, define_property),
+        ("deleteProperty", 2, delete_property),
+        ("get", 2, get),
+        ("getOwnPropertyDescriptor", 2, get_own_property_descriptor),
+        ("getPrototypeOf", 1, get_prototype_of),
+        ("has", 2, has),
+        ("isExtensible", 1, is_extensible),
+        ("ownKeys", 1, own_keys),
+        ("preventExtensions", 1, prevent_extensions),
+        ("set", 3, set),
+        ("setPrototypeOf", 2, set_prototype_of),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, reflect, name, length, function);
+    }
+    define_to_string_tag(interpreter, reflect, "Reflect");
+    define_global(interpreter, "Reflect", Value::Object(reflect));
+}
+
+/// The target object of a `Reflect` function, which must not be a
+/// primitive.
+fn target(args: &[Value], method: &str) -> Result<ObjectId, String> {
+    match arg(args, 0) {
+        Value::Object(id) | Value::Function(id) => Ok(id),
+        _ => Err(type_error(format!("Reflect.{} called on non-object", method))),
+    }
+}
+
+fn apply(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let arguments = interpreter.create_list_from_array_like(&arg(args, 2))?;
+    interpreter.call_function(&arg(args, 0), arg(args, 1), arguments)
+}
+
+fn construct(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let constructor = arg(args, 0);
+    let new_target = args.get(2).cloned().unwrap_or_else(|| constructor.clone());
+    for value in [&constructor, &new_target] {
+        if !interpreter.is_constructor(value) {
+            return Err(type_error(format!("{} is not a constructor", interpreter.to_display_string(value))));
+        }
+    }
+    let arguments = interpreter.create_list_from_array_like(&arg(args, 1))?;
+    interpreter.construct(&constructor, arguments, &new_target)
+}
+
+fn define_property(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "defineProperty")?;
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    let descriptor = interpreter.descriptor_from_object(&arg(args, 2))?;
+    Ok(Value::Boolean(interpreter.define_own_property(id, key, descriptor)?))
+}
+
+fn delete_property(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "deleteProperty")?;
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    Ok(Value::Boolean(interpreter.delete_property(id, &key)?))
+}
+
+fn get(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "get")?;
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    let receiver = args.get(2).cloned().unwrap_or_else(|| arg(args, 0));
+    interpreter.get_property(id, &key, &receiver)
+}
+
+fn get_own_property_descriptor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "getOwnPropertyDescriptor")?;
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    match interpreter.get_own_property(id, &key)? {
+        Some(property) => Ok(interpreter.descriptor_object(&PropertyDescriptor::of(&property))),
+        None => Ok(Value::Undefined),
+    }
+}
+
+fn get_prototype_of(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "getPrototypeOf")?;
+    let prototype = interpreter.get_prototype_of(id)?;
+    Ok(prototype.map_or(Value::Null, |prototype| interpreter.object_value(prototype)))
+}
+
+fn has(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "has")?;
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    Ok(Value::Boolean(interpreter.has_property(id, &key)?))
+}
+
+fn is_extensible(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "isExtensible")?;
+    Ok(Value::Boolean(interpreter.is_extensible(id)?))
+}
+
+fn own_keys(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "ownKeys")?;
+    let keys = interpreter.own_property_keys(id)?.iter().map(|key| key.to_value()).collect();
+    Ok(interpreter.create_array(keys))
+}
+
+fn prevent_extensions(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "preventExtensions")?;
+    Ok(Value::Boolean(interpreter.prevent_extensions(id)?))
+}
+
+fn set(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "set")?;
+    let key = interpreter.coerce_to_property_key(&arg(args, 1))?;
+    let receiver = args.get(3).cloned().unwrap_or_else(|| arg(args, 0));
+    Ok(Value::Boolean(interpreter.set_property(id, &key, arg(args, 2), &receiver)?))
+}
+
+fn set_prototype_of(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let id = target(args, "setPrototypeOf")?;
+    let prototype = match arg(args, 1) {
+        Value::Object(prototype) | Value::Function(prototype) => Some(prototype),
+        Value::Null => None,
+        other => {
+            return Err(type_error(format!(
+                "Object prototype may only be an Object or null: {}",
+                interpreter.to_display_string(&other)
+            )))
+        }
+    };
+    Ok(Value::Boolean(interpreter.set_prototype_of(id, prototype)?))
+}
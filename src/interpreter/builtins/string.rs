@@ -0,0 +1,629 @@
+//! `String`, its static methods and `String.prototype`.
+//!
+//! Strings are sequences of UTF-16 code units, which the methods that take
+//! or return positions index directly. Methods that work on characters,
+//! such as case mapping, leave lone surrogates as they are.
+
+use std::cmp::Ordering;
+
+use super::normalization::{self, Form};
+use super::{arg, define_alias, define_global, define_method, relative_index, to_integer};
+use crate::interpreter::object::{Attributes, NativeFunction, ObjectKind, Property, PropertyKey};
+use crate::interpreter::{number_to_string, range_error, to_uint32, type_error, Interpreter, JsString, Value};
+
+/// The longest string V8 creates, in code units.
+pub(super) const MAX_STRING_LENGTH: usize = (1 << 29) - 24;
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.string_prototype;
+    let constructor = interpreter.create_native_function("String", 1, string_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+    define_method(interpreter, constructor_id, "fromCharCode", 1, from_char_code);
+    define_method(interpreter, constructor_id, "fromCodePoint", 1, from_code_point);
+    define_method(interpreter, constructor_id, "raw", 1, raw);
+
+    let length = Property::data(Value::Number(0.0), Attributes::FIXED);
+    interpreter.heap.define_property(prototype, PropertyKey::from("length"), length);
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("at", 1, at),
+        ("charAt", 1, char_at),
+        ("charCodeAt", 1, char_code_at),
+        ("codePointAt", 1, code_point_at),
+        ("concat", 1, concat),
+        ("endsWith", 1, ends_with),
+        ("includes", 1, includes),
+        ("indexOf", 1, index_of),
+        ("isWellFormed", 0, is_well_formed),
+        ("lastIndexOf", 1, last_index_of),
+        ("localeCompare", 1, locale_compare),
+        ("normalize", 0, normalize),
+        ("padEnd", 1, pad_end),
+        ("padStart", 1, pad_start),
+        ("repeat", 1, repeat),
+        ("replace", 2, replace),
+        ("replaceAll", 2, replace_all),
+        ("slice", 2, slice),
+        ("split", 2, split),
+        ("startsWith", 1, starts_with),
+        ("substr", 2, substr),
+        ("substring", 2, substring),
+        ("toLocaleLowerCase", 0, to_lower_case),
+        ("toLocaleUpperCase", 0, to_upper_case),
+        ("toLowerCase", 0, to_lower_case),
+        ("toString", 0, to_string),
+        ("toUpperCase", 0, to_upper_case),
+        ("toWellFormed", 0, to_well_formed),
+        ("trim", 0, trim),
+        ("trimEnd", 0, trim_end),
+        ("trimStart", 0, trim_start),
+        ("valueOf", 0, value_of),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    // The legacy names are the same functions
+    for (alias, name) in [("trimLeft", "trimStart"), ("trimRight", "trimEnd")] {
+        define_alias(interpreter, prototype, PropertyKey::from(alias), name);
+    }
+    let iterator = interpreter.create_native_function("[Symbol.iterator]", 0, iterator);
+    let key = PropertyKey::Symbol(interpreter.symbols.iterator.clone());
+    interpreter.heap.define_property(prototype, key, Property::data(iterator, Attributes::HIDDEN));
+
+    define_global(interpreter, "String", constructor);
+}
+
+/// `String(value)` converts; `new String(value)` creates a wrapper object
+/// whose characters are read-only index properties.
+fn string_constructor(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = match args.first() {
+        // `String(symbol)` is the one way to turn a symbol into a string
+        Some(Value::Symbol(symbol)) if interpreter.new_target.is_none() => JsString::from(symbol.to_string()),
+        Some(value) => interpreter.coerce_to_string(value)?,
+        None => JsString::new(),
+    };
+    if interpreter.new_target.is_none() {
+        return Ok(Value::String(s));
+    }
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.string_prototype)?;
+    Ok(Value::Object(interpreter.create_primitive_wrapper(Value::String(s), prototype)))
+}
+
+/// `String.prototype[Symbol.iterator]` iterates over code points.
+fn iterator(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let s = match this {
+        Value::Null | Value::Undefined => return Err(type_error("String.prototype[Symbol.iterator] called on null or undefined")),
+        other => interpreter.coerce_to_string(other)?,
+    };
+    Ok(interpreter.create_string_iterator(s))
+}
+
+/// The string a generic `String.prototype` method works on: `this`
+/// converted to a string, which fails only for `null` and `undefined`.
+fn this_string(interpreter: &mut Interpreter, this: &Value, method: &str) -> Result<JsString, String> {
+    match this {
+        Value::Null | Value::Undefined => Err(type_error(format!("String.prototype.{} called on null or undefined", method))),
+        Value::String(s) => Ok(s.clone()),
+        other => interpreter.coerce_to_string(other),
+    }
+}
+
+/// The string a `String.prototype` method that needs an actual string
+/// was called on, unwrapping `String` objects.
+fn this_string_value(interpreter: &Interpreter, this: &Value, method: &str) -> Result<JsString, String> {
+    match this {
+        Value::String(s) => Ok(s.clone()),
+        Value::Object(id) => match &interpreter.heap.get(*id).kind {
+            ObjectKind::Primitive(Value::String(s)) => Ok(s.clone()),
+            _ => Err(type_error(format!("String.prototype.{} requires that 'this' be a String", method))),
+        },
+        _ => Err(type_error(format!("String.prototype.{} requires that 'this' be a String", method))),
+    }
+}
+
+fn from_units(units: &[u16]) -> Value {
+    Value::String(JsString::from(units))
+}
+
+/// Applies `f` to each character of `s`, keeping lone surrogates.
+fn map_chars<I: IntoIterator<Item = char>>(s: &JsString, f: impl Fn(char) -> I) -> JsString {
+    let mut result = Vec::with_capacity(s.len());
+    for c in char::decode_utf16(s.units().iter().copied()) {
+        match c {
+            Ok(c) => f(c).into_iter().for_each(|c| result.extend(c.encode_utf16(&mut [0; 2]).iter())),
+            Err(error) => result.push(error.unpaired_surrogate()),
+        }
+    }
+    JsString::from(result)
+}
+
+/// The first position at or after `from` where `needle` occurs.
+fn find_units(haystack: &[u16], needle: &[u16], from: usize) -> Option<usize> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    (from..=haystack.len() - needle.len()).find(|&start| haystack[start..start + needle.len()] == *needle)
+}
+
+/// A position argument converted with `ToIntegerOrInfinity` and clamped to
+/// `0..=length`, or `default` when it is `undefined`.
+fn clamped_position(interpreter: &mut Interpreter, value: &Value, length: usize, default: usize) -> Result<usize, String> {
+    if matches!(value, Value::Undefined) {
+        return Ok(default);
+    }
+    let n = to_integer(interpreter.coerce_to_number(value)?);
+    Ok(n.clamp(0.0, length as f64) as usize)
+}
+
+/// Whitespace as `trim` and `Number` conversion understand it: Unicode
+/// spaces, line terminators and the byte order mark.
+fn is_js_whitespace(c: char) -> bool {
+    c.is_whitespace() && c != '\u{85}' || c == '\u{FEFF}'
+}
+
+/// Whether a code unit is whitespace. All of it is in the Basic
+/// Multilingual Plane, so no whitespace is made of a surrogate pair.
+fn is_whitespace_unit(unit: &u16) -> bool {
+    char::from_u32(*unit as u32).is_some_and(is_js_whitespace)
+}
+
+fn at(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "at")?;
+    let units = s.units();
+    let n = to_integer(interpreter.coerce_to_number(&arg(args, 0))?);
+    let index = if n < 0.0 { units.len() as f64 + n } else { n };
+    if index < 0.0 || index >= units.len() as f64 {
+        return Ok(Value::Undefined);
+    }
+    Ok(from_units(&units[index as usize..index as usize + 1]))
+}
+
+/// The code unit index a `charAt`-style method reads, `None` when out of
+/// range.
+fn unit_index(interpreter: &mut Interpreter, value: &Value, length: usize) -> Result<Option<usize>, String> {
+    let n = to_integer(interpreter.coerce_to_number(value)?);
+    Ok((n >= 0.0 && n < length as f64).then_some(n as usize))
+}
+
+fn char_at(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "charAt")?;
+    let units = s.units();
+    Ok(match unit_index(interpreter, &arg(args, 0), units.len())? {
+        Some(index) => from_units(&units[index..index + 1]),
+        None => Value::String(JsString::new()),
+    })
+}
+
+fn char_code_at(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "charCodeAt")?;
+    let units = s.units();
+    Ok(Value::Number(match unit_index(interpreter, &arg(args, 0), units.len())? {
+        Some(index) => units[index] as f64,
+        None => f64::NAN,
+    }))
+}
+
+fn code_point_at(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "codePointAt")?;
+    let units = s.units();
+    let Some(index) = unit_index(interpreter, &arg(args, 0), units.len())? else {
+        return Ok(Value::Undefined);
+    };
+    let code_point = match char::decode_utf16(units[index..].iter().copied()).next() {
+        Some(Ok(c)) => c as u32,
+        _ => units[index] as u32,
+    };
+    Ok(Value::Number(code_point as f64))
+}
+
+fn concat(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let mut s = this_string(interpreter, this, "concat")?;
+    for value in args {
+        s.push_js_str(&interpreter.coerce_to_string(value)?);
+    }
+    Ok(Value::String(s))
+}
+
+fn starts_with(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "startsWith")?;
+    let units = s.units();
+    let search = interpreter.coerce_to_string(&arg(args, 0))?;
+    let start = clamped_position(interpreter, &arg(args, 1), units.len(), 0)?;
+    Ok(Value::Boolean(units[start..].starts_with(search.units())))
+}
+
+fn ends_with(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "endsWith")?;
+    let units = s.units();
+    let search = interpreter.coerce_to_string(&arg(args, 0))?;
+    let end = clamped_position(interpreter, &arg(args, 1), units.len(), units.len())?;
+    Ok(Value::Boolean(units[..end].ends_with(search.units())))
+}
+
+fn includes(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "includes")?;
+    let units = s.units();
+    let search = interpreter.coerce_to_string(&arg(args, 0))?;
+    let start = clamped_position(interpreter, &arg(args, 1), units.len(), 0)?;
+    Ok(Value::Boolean(find_units(units, search.units(), start).is_some()))
+}
+
+fn index_of(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "indexOf")?;
+    let units = s.units();
+    let search = interpreter.coerce_to_string(&arg(args, 0))?;
+    let start = clamped_position(interpreter, &arg(args, 1), units.len(), 0)?;
+    Ok(Value::Number(find_units(units, search.units(), start).map_or(-1.0, |index| index as f64)))
+}
+
+fn last_index_of(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "lastIndexOf")?;
+    let units = s.units();
+    let search = interpreter.coerce_to_string(&arg(args, 0))?;
+    let position = interpreter.coerce_to_number(&arg(args, 1))?;
+    let start = if position.is_nan() { units.len() } else { to_integer(position).clamp(0.0, units.len() as f64) as usize };
+    if search.len() > units.len() {
+        return Ok(Value::Number(-1.0));
+    }
+    let last_start = start.min(units.len() - search.len());
+    let found = (0..=last_start).rev().find(|&index| units[index..index + search.len()] == *search.units());
+    Ok(Value::Number(found.map_or(-1.0, |index| index as f64)))
+}
+
+fn is_well_formed(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(this_string(interpreter, this, "isWellFormed")?.is_well_formed()))
+}
+
+/// Replaces lone surrogates with U+FFFD, as converting to UTF-8 does.
+fn to_well_formed(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::String(JsString::from(this_string(interpreter, this, "toWellFormed")?.to_string())))
+}
+
+/// Compares strings the way a root-locale collator would: first by base
+/// letters, then by accents, then by case with lowercase first.
+fn locale_compare(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "localeCompare")?.to_string();
+    let that = interpreter.coerce_to_string(&arg(args, 0))?.to_string();
+    let levels = |s: &str| {
+        let decomposed = normalization::normalize(s, Form::Nfd);
+        let base: String = decomposed.chars().filter(|c| !is_combining_mark(*c)).flat_map(char::to_lowercase).collect();
+        let accents: String = decomposed.chars().filter(|c| is_combining_mark(*c)).collect();
+        let case: Vec<bool> = decomposed.chars().filter(|c| !is_combining_mark(*c)).map(char::is_uppercase).collect();
+        (base, accents, case)
+    };
+    let ordering = levels(&s).cmp(&levels(&that));
+    Ok(Value::Number(match ordering {
+        Ordering::Less => -1.0,
+        Ordering::Equal => 0.0,
+        Ordering::Greater => 1.0,
+    }))
+}
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c, '\u{300}'..='\u{36F}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' | '\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}')
+}
+
+fn normalize(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "normalize")?;
+    let form = match arg(args, 0) {
+        Value::Undefined => "NFC".to_string(),
+        form => interpreter.coerce_to_string(&form)?.to_string(),
+    };
+    let form = match form.as_str() {
+        "NFC" => Form::Nfc,
+        "NFD" => Form::Nfd,
+        "NFKC" => Form::Nfkc,
+        "NFKD" => Form::Nfkd,
+        _ => return Err(range_error("The normalization form should be one of NFC, NFD, NFKC, NFKD.")),
+    };
+    // Lone surrogates are left alone, splitting the string around them
+    let mut result = JsString::new();
+    let mut segment = String::new();
+    for c in char::decode_utf16(s.units().iter().copied()) {
+        match c {
+            Ok(c) => segment.push(c),
+            Err(error) => {
+                result.push_str(&normalization::normalize(&std::mem::take(&mut segment), form));
+                result.push_js_str(&JsString::from(vec![error.unpaired_surrogate()]));
+            }
+        }
+    }
+    result.push_str(&normalization::normalize(&segment, form));
+    Ok(Value::String(result))
+}
+
+/// The padding `padStart` and `padEnd` add, as UTF-16 code units.
+fn padding(interpreter: &mut Interpreter, s: &JsString, method: &str, args: &[Value]) -> Result<Vec<u16>, String> {
+    let length = s.len();
+    let max_length = to_integer(interpreter.coerce_to_number(&arg(args, 0))?);
+    let fill = match arg(args, 1) {
+        Value::Undefined => vec![' ' as u16],
+        fill => interpreter.coerce_to_string(&fill)?.units().to_vec(),
+    };
+    if max_length <= length as f64 || fill.is_empty() {
+        return Ok(Vec::new());
+    }
+    if max_length > MAX_STRING_LENGTH as f64 {
+        return Err(range_error(format!("Invalid string length (in String.prototype.{})", method)));
+    }
+    Ok(fill.iter().copied().cycle().take(max_length as usize - length).collect())
+}
+
+fn pad_start(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "padStart")?;
+    let mut result = JsString::from(padding(interpreter, &s, "padStart", args)?);
+    result.push_js_str(&s);
+    Ok(Value::String(result))
+}
+
+fn pad_end(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let mut s = this_string(interpreter, this, "padEnd")?;
+    let padding = padding(interpreter, &s, "padEnd", args)?;
+    s.push_js_str(&JsString::from(padding));
+    Ok(Value::String(s))
+}
+
+fn repeat(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "repeat")?;
+    let count = to_integer(interpreter.coerce_to_number(&arg(args, 0))?);
+    if count < 0.0 || count.is_infinite() {
+        return Err(range_error(format!("Invalid count value: {}", number_to_string(count))));
+    }
+    if s.is_empty() || count == 0.0 {
+        return Ok(Value::String(JsString::new()));
+    }
+    if s.len() as f64 * count > MAX_STRING_LENGTH as f64 {
+        return Err(range_error("Invalid string length"));
+    }
+    Ok(from_units(&s.units().repeat(count as usize)))
+}
+
+/// Expands the `$` patterns of a replacement string: `$$`, `$&` for the
+/// match, and `` $` `` / `$'` for the text before and after it.
+fn substitute(replacement: &JsString, subject: &[u16], position: usize, matched: &[u16]) -> Vec<u16> {
+    let replacement = replacement.units();
+    let mut result = Vec::with_capacity(replacement.len());
+    let mut index = 0;
+    while index < replacement.len() {
+        let unit = replacement[index];
+        let next = replacement.get(index + 1).copied();
+        if unit == '$' as u16 {
+            let expansion = match next.and_then(|next| char::from_u32(next as u32)) {
+                Some('$') => Some(&[unit][..]),
+                Some('&') => Some(matched),
+                Some('`') => Some(&subject[..position]),
+                Some('\'') => Some(&subject[(position + matched.len()).min(subject.len())..]),
+                _ => None,
+            };
+            if let Some(expansion) = expansion {
+                result.extend_from_slice(expansion);
+                index += 2;
+                continue;
+            }
+        }
+        result.push(unit);
+        index += 1;
+    }
+    result
+}
+
+/// Replaces the matches of a string pattern at `positions`, calling the
+/// replacement when it is a function.
+fn replace_at(
+    interpreter: &mut Interpreter,
+    subject: &JsString,
+    search: &[u16],
+    positions: Vec<usize>,
+    replacement: &Value,
+) -> Result<Value, String> {
+    let units = subject.units();
+    let replacement_text = match replacement {
+        Value::Function(_) => None,
+        other => Some(interpreter.coerce_to_string(other)?),
+    };
+    let mut result = Vec::with_capacity(units.len());
+    let mut end_of_last_match = 0;
+    for position in positions {
+        result.extend_from_slice(&units[end_of_last_match..position]);
+        let replaced = match &replacement_text {
+            Some(text) => substitute(text, units, position, search),
+            None => {
+                let arguments = vec![from_units(search), Value::Number(position as f64), Value::String(subject.clone())];
+                let value = interpreter.call_function(replacement, Value::Undefined, arguments)?;
+                interpreter.coerce_to_string(&value)?.units().to_vec()
+            }
+        };
+        result.extend(replaced);
+        end_of_last_match = position + search.len();
+    }
+    result.extend_from_slice(&units[end_of_last_match..]);
+    Ok(from_units(&result))
+}
+
+fn replace(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "replace")?;
+    let search = interpreter.coerce_to_string(&arg(args, 0))?;
+    let positions = find_units(s.units(), search.units(), 0).into_iter().collect();
+    replace_at(interpreter, &s, search.units(), positions, &arg(args, 1))
+}
+
+fn replace_all(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "replaceAll")?;
+    let search = interpreter.coerce_to_string(&arg(args, 0))?;
+    let (units, search) = (s.units(), search.units());
+    // An empty pattern matches between every code unit
+    let step = search.len().max(1);
+    let mut positions = Vec::new();
+    let mut from = 0;
+    while let Some(position) = (from <= units.len()).then(|| find_units(units, search, from)).flatten() {
+        positions.push(position);
+        from = position + step;
+    }
+    replace_at(interpreter, &s, search, positions, &arg(args, 1))
+}
+
+fn slice(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "slice")?;
+    let units = s.units();
+    let length = units.len() as u64;
+    let start = relative_index(interpreter, &arg(args, 0), length, 0)? as usize;
+    let end = relative_index(interpreter, &arg(args, 1), length, length)? as usize;
+    Ok(from_units(if start < end { &units[start..end] } else { &[] }))
+}
+
+fn substring(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "substring")?;
+    let units = s.units();
+    let start = clamped_position(interpreter, &arg(args, 0), units.len(), 0)?;
+    let end = clamped_position(interpreter, &arg(args, 1), units.len(), units.len())?;
+    Ok(from_units(&units[start.min(end)..start.max(end)]))
+}
+
+/// The legacy `substr(start, length)`.
+fn substr(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "substr")?;
+    let units = s.units();
+    let start = relative_index(interpreter, &arg(args, 0), units.len() as u64, 0)? as usize;
+    let length = match arg(args, 1) {
+        Value::Undefined => units.len() as f64,
+        length => to_integer(interpreter.coerce_to_number(&length)?),
+    };
+    let end = (start as f64 + length.max(0.0)).min(units.len() as f64) as usize;
+    Ok(from_units(&units[start..end.max(start)]))
+}
+
+fn split(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "split")?;
+    let limit = match arg(args, 1) {
+        Value::Undefined => u32::MAX,
+        limit => to_uint32(interpreter.coerce_to_number(&limit)?),
+    } as usize;
+    let separator = match arg(args, 0) {
+        Value::Undefined => None,
+        separator => Some(interpreter.coerce_to_string(&separator)?),
+    };
+    let units = s.units();
+    let parts: Vec<Value> = match separator {
+        _ if limit == 0 => Vec::new(),
+        None => vec![Value::String(s.clone())],
+        Some(separator) if separator.is_empty() => units.iter().take(limit).map(|unit| from_units(&[*unit])).collect(),
+        Some(_) if units.is_empty() => vec![Value::String(s.clone())],
+        Some(separator) => {
+            let mut parts = Vec::new();
+            let mut start = 0;
+            while let Some(position) = find_units(units, separator.units(), start) {
+                parts.push(from_units(&units[start..position]));
+                start = position + separator.len();
+            }
+            parts.push(from_units(&units[start..]));
+            parts.truncate(limit);
+            parts
+        }
+    };
+    Ok(interpreter.create_array(parts))
+}
+
+fn to_lower_case(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    // `str::to_lowercase` applies the Final_Sigma rule, which depends on
+    // the letters around each `Σ`. A lone surrogate is neither cased nor
+    // case-ignorable, so it ends what the rule looks at like the end of
+    // the string does.
+    let s = this_string(interpreter, this, "toLowerCase")?;
+    let mut result = Vec::with_capacity(s.len());
+    let mut run = String::new();
+    for c in char::decode_utf16(s.units().iter().copied()) {
+        match c {
+            Ok(c) => run.push(c),
+            Err(error) => {
+                result.extend(run.to_lowercase().encode_utf16());
+                run.clear();
+                result.push(error.unpaired_surrogate());
+            }
+        }
+    }
+    result.extend(run.to_lowercase().encode_utf16());
+    Ok(Value::String(JsString::from(result)))
+}
+
+fn to_upper_case(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::String(map_chars(&this_string(interpreter, this, "toUpperCase")?, char::to_uppercase)))
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::String(this_string_value(interpreter, this, "toString")?))
+}
+
+fn value_of(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::String(this_string_value(interpreter, this, "valueOf")?))
+}
+
+fn trim(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "trim")?;
+    let start = s.units().iter().position(|unit| !is_whitespace_unit(unit)).unwrap_or(s.len());
+    let end = s.units().iter().rposition(|unit| !is_whitespace_unit(unit)).map_or(start, |index| index + 1);
+    Ok(from_units(&s.units()[start..end]))
+}
+
+fn trim_start(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "trimStart")?;
+    let start = s.units().iter().position(|unit| !is_whitespace_unit(unit)).unwrap_or(s.len());
+    Ok(from_units(&s.units()[start..]))
+}
+
+fn trim_end(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let s = this_string(interpreter, this, "trimEnd")?;
+    let end = s.units().iter().rposition(|unit| !is_whitespace_unit(unit)).map_or(0, |index| index + 1);
+    Ok(from_units(&s.units()[..end]))
+}
+
+/// `String.fromCharCode(...codeUnits)`, where each number is taken modulo
+/// 2^16.
+fn from_char_code(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let mut units = Vec::with_capacity(args.len());
+    for value in args {
+        units.push(to_uint32(interpreter.coerce_to_number(value)?) as u16);
+    }
+    Ok(from_units(&units))
+}
+
+fn from_code_point(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let mut units = Vec::with_capacity(args.len());
+    for value in args {
+        let n = interpreter.coerce_to_number(value)?;
+        if n.fract() != 0.0 || !(0.0..=1114111.0).contains(&n) {
+            return Err(range_error(format!("Invalid code point {}", interpreter.coerce_to_string(value)?)));
+        }
+        // Surrogate code points are lone surrogates of their own
+        match char::from_u32(n as u32) {
+            Some(c) => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+            None => units.push(n as u16),
+        }
+    }
+    Ok(from_units(&units))
+}
+
+/// `String.raw(template, ...substitutions)` interleaves the `raw` strings
+/// of a template object with the substitutions.
+fn raw(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let template = arg(args, 0);
+    if matches!(template, Value::Null | Value::Undefined) {
+        return Err(type_error(format!("Cannot convert {} to object", template.to_js_string())));
+    }
+    let raw = interpreter.get_member(&template, &PropertyKey::from("raw"))?;
+    if matches!(raw, Value::Null | Value::Undefined) {
+        return Err(type_error(format!("Cannot convert {} to object", raw.to_js_string())));
+    }
+    let length = interpreter.get_member(&raw, &PropertyKey::from("length"))?;
+    let length = to_integer(interpreter.coerce_to_number(&length)?).max(0.0) as u32;
+    let mut s = JsString::new();
+    for index in 0..length {
+        let segment = interpreter.get_member(&raw, &PropertyKey::Index(index))?;
+        s.push_js_str(&interpreter.coerce_to_string(&segment)?);
+        if index + 1 < length {
+            if let Some(substitution) = args.get(index as usize + 1) {
+                s.push_js_str(&interpreter.coerce_to_string(substitution)?);
+            }
+        }
+    }
+    Ok(Value::String(s))
+}
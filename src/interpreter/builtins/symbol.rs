@@ -0,0 +1,99 @@
+//! `Symbol`, its prototype, the global symbol registry and the well-known
+//! symbols.
+
+use super::{arg, define_getter, define_global, define_method, define_to_string_tag};
+use crate::interpreter::object::{Attributes, NativeFunction, ObjectKind, Property, PropertyKey};
+use crate::interpreter::{type_error, Interpreter, JsString, Symbol, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.symbol_prototype;
+    let constructor = interpreter.create_native_function("Symbol", 0, symbol);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let Value::Function(id) = constructor else { unreachable!() };
+
+    let symbols = &interpreter.symbols;
+    let well_known = [
+        ("asyncIterator", symbols.async_iterator.clone()),
+        ("hasInstance", symbols.has_instance.clone()),
+        ("iterator", symbols.iterator.clone()),
+        ("species", symbols.species.clone()),
+        ("toPrimitive", symbols.to_primitive.clone()),
+        ("toStringTag", symbols.to_string_tag.clone()),
+    ];
+    for (name, symbol) in well_known {
+        interpreter.heap.define_property(id, PropertyKey::from(name), Property::data(Value::Symbol(symbol), Attributes::FIXED));
+    }
+    define_method(interpreter, id, "for", 1, symbol_for);
+    define_method(interpreter, id, "keyFor", 1, key_for);
+
+    let methods: &[(&str, u32, NativeFunction)] = &[("toString", 0, to_string), ("valueOf", 0, value_of)];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    define_getter(interpreter, prototype, "description", description);
+    let to_primitive = interpreter.create_native_function("[Symbol.toPrimitive]", 1, value_of);
+    let key = PropertyKey::Symbol(interpreter.symbols.to_primitive.clone());
+    interpreter.heap.define_property(prototype, key, Property::data(to_primitive, Attributes::READ_ONLY));
+    define_to_string_tag(interpreter, prototype, "Symbol");
+
+    define_global(interpreter, "Symbol", constructor);
+}
+
+/// `Symbol(description)` creates a new symbol; symbols are primitives, so
+/// `new Symbol()` is an error.
+fn symbol(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if interpreter.new_target.is_some() {
+        return Err(type_error("Symbol is not a constructor"));
+    }
+    let description = match arg(args, 0) {
+        Value::Undefined => None,
+        value => Some(interpreter.coerce_to_string(&value)?.to_string()),
+    };
+    Ok(Value::Symbol(Symbol::new(description)))
+}
+
+/// `Symbol.for(key)` returns the symbol registered for `key`, registering
+/// a new one the first time.
+fn symbol_for(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let key = interpreter.coerce_to_string(&arg(args, 0))?.to_string();
+    let symbol = interpreter.symbol_registry.entry(key.clone()).or_insert_with(|| Symbol::new(Some(key)));
+    Ok(Value::Symbol(symbol.clone()))
+}
+
+/// `Symbol.keyFor(symbol)` is the key `symbol` is registered under, or
+/// `undefined` if `Symbol.for` did not create it.
+fn key_for(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let Value::Symbol(symbol) = arg(args, 0) else {
+        return Err(type_error(format!("{} is not a symbol", interpreter.to_display_string(&arg(args, 0)))));
+    };
+    let registered = interpreter.symbol_registry.iter().find(|(_, registered)| **registered == symbol);
+    Ok(registered.map_or(Value::Undefined, |(key, _)| Value::String(key.into())))
+}
+
+/// The symbol a `Symbol.prototype` method was called on, unwrapping
+/// `Symbol` objects.
+fn this_symbol(interpreter: &Interpreter, this: &Value, method: &str) -> Result<Symbol, String> {
+    match this {
+        Value::Symbol(symbol) => Ok(symbol.clone()),
+        Value::Object(id) => match &interpreter.heap.get(*id).kind {
+            ObjectKind::Primitive(Value::Symbol(symbol)) => Ok(symbol.clone()),
+            _ => Err(type_error(format!("{} requires that 'this' be a Symbol", method))),
+        },
+        _ => Err(type_error(format!("{} requires that 'this' be a Symbol", method))),
+    }
+}
+
+fn to_string(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let symbol = this_symbol(interpreter, this, "Symbol.prototype.toString")?;
+    Ok(Value::String(JsString::from(symbol.to_string())))
+}
+
+/// `valueOf`, and `Symbol.prototype[Symbol.toPrimitive]`.
+fn value_of(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    this_symbol(interpreter, this, "Symbol.prototype.valueOf").map(Value::Symbol)
+}
+
+fn description(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let symbol = this_symbol(interpreter, this, "Symbol.prototype.description")?;
+    Ok(symbol.description().map_or(Value::Undefined, |description| Value::String(description.into())))
+}
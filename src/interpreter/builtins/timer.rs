@@ -0,0 +1,64 @@
+//! `setTimeout`, `setInterval`, their `clear` functions and
+//! `queueMicrotask`.
+
+use super::{arg, define_global};
+use crate::interpreter::object::NativeFunction;
+use crate::interpreter::{type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let functions: &[(&str, u32, NativeFunction)] = &[
+        ("clearInterval", 1, clear_timer),
+        ("clearTimeout", 1, clear_timer),
+        ("queueMicrotask", 1, queue_microtask),
+        ("setInterval", 2, |i, _, args| set_timer(i, args, true)),
+        ("setTimeout", 2, |i, _, args| set_timer(i, args, false)),
+    ];
+    for &(name, length, function) in functions {
+        let function = interpreter.create_native_function(name, length, function);
+        define_global(interpreter, name, function);
+    }
+}
+
+/// Checks that the `callback` argument is a function, with Node's message.
+fn require_callback(value: &Value) -> Result<(), String> {
+    let received = match value {
+        Value::Function(_) => return Ok(()),
+        Value::Undefined | Value::Null => value.to_js_string(),
+        Value::Object(_) => "an instance of Object".to_string(),
+        Value::String(s) => format!("type string ('{}')", s),
+        Value::Number(_) => format!("type number ({})", value.to_js_string()),
+        Value::BigInt(_) => format!("type bigint ({}n)", value.to_js_string()),
+        Value::Boolean(_) => format!("type boolean ({})", value.to_js_string()),
+        Value::Symbol(_) => format!("type symbol ({})", value.to_js_string()),
+    };
+    Err(type_error(format!("The \"callback\" argument must be of type function. Received {}", received)))
+}
+
+/// `setTimeout(callback, delay, ...args)` and `setInterval`, which return
+/// a number identifying the timer.
+fn set_timer(interpreter: &mut Interpreter, args: &[Value], repeat: bool) -> Result<Value, String> {
+    let callback = arg(args, 0);
+    require_callback(&callback)?;
+    let delay = interpreter.coerce_to_number(&arg(args, 1))?;
+    let rest = args.iter().skip(2).cloned().collect();
+    let id = interpreter.set_timer(callback, delay, rest, repeat);
+    Ok(Value::Number(id as f64))
+}
+
+/// `clearTimeout(id)` and `clearInterval(id)`, which cancel either kind of
+/// timer and ignore anything that is not a pending timer's id.
+fn clear_timer(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    if let Value::Number(id) = arg(args, 0) {
+        if id.fract() == 0.0 && (1.0..=u32::MAX as f64).contains(&id) {
+            interpreter.clear_timer(id as u32);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+fn queue_microtask(interpreter: &mut Interpreter, _this: &Value, args: &[Value]) -> Result<Value, String> {
+    let callback = arg(args, 0);
+    require_callback(&callback)?;
+    interpreter.queue_microtask(callback);
+    Ok(Value::Undefined)
+}
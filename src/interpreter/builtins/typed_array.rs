@@ -0,0 +1,500 @@
+//! `%TypedArray%`, the abstract constructor behind `Int8Array`,
+//! `Uint8Array` and the other typed arrays, and the prototype methods they
+//! share. Methods that only read elements run the generic ones of
+//! `Array.prototype` once the receiver is known to be a typed array.
+//!
+//! Methods that create a new typed array, such as `map` and `slice`, give
+//! it the same type as the receiver; `Symbol.species` is not consulted.
+
+use std::cmp::Ordering;
+
+use super::array;
+use super::{arg, define_getter, define_global, define_method, incompatible_receiver, relative_index, to_index, to_integer};
+use crate::interpreter::collection::IterationKind;
+use crate::interpreter::object::{Attributes, NativeFunction, Object, ObjectId, ObjectKind, Property, PropertyKey};
+use crate::interpreter::typed_array::{ElementType, TypedArrayData};
+use crate::interpreter::{number_to_string, range_error, type_error, Interpreter, Value};
+
+pub(super) fn install(interpreter: &mut Interpreter) {
+    let prototype = interpreter.intrinsics.typed_array_prototype;
+    let constructor = interpreter.create_native_function("TypedArray", 0, abstract_constructor);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+    define_method(interpreter, constructor_id, "from", 1, from);
+    define_method(interpreter, constructor_id, "of", 0, of);
+
+    let getters: &[(&str, NativeFunction)] = &[("buffer", buffer), ("byteLength", byte_length), ("byteOffset", byte_offset), ("length", length)];
+    for &(name, getter) in getters {
+        define_getter(interpreter, prototype, name, getter);
+    }
+    let tag_getter = interpreter.create_native_function("get [Symbol.toStringTag]", 0, to_string_tag);
+    let tag = PropertyKey::Symbol(interpreter.symbols.to_string_tag.clone());
+    interpreter.heap.define_property(prototype, tag, Property::accessor(None, true, tag_getter, Attributes::HIDDEN));
+
+    let methods: &[(&str, u32, NativeFunction)] = &[
+        ("at", 1, |i, this, args| generic(i, this, args, array::at)),
+        ("copyWithin", 2, copy_within),
+        ("entries", 0, |i, this, _| generic(i, this, &[], |i, this, _| array::iterator(i, this, "entries", IterationKind::Entries))),
+        ("every", 1, |i, this, args| generic(i, this, args, array::every)),
+        ("fill", 1, fill),
+        ("filter", 1, filter),
+        ("find", 1, |i, this, args| generic(i, this, args, array::find)),
+        ("findIndex", 1, |i, this, args| generic(i, this, args, array::find_index)),
+        ("findLast", 1, |i, this, args| generic(i, this, args, array::find_last)),
+        ("findLastIndex", 1, |i, this, args| generic(i, this, args, array::find_last_index)),
+        ("forEach", 1, |i, this, args| generic(i, this, args, array::for_each)),
+        ("includes", 1, |i, this, args| generic(i, this, args, array::includes)),
+        ("indexOf", 1, |i, this, args| generic(i, this, args, array::index_of)),
+        ("join", 1, |i, this, args| generic(i, this, args, array::join)),
+        ("keys", 0, |i, this, _| generic(i, this, &[], |i, this, _| array::iterator(i, this, "keys", IterationKind::Keys))),
+        ("lastIndexOf", 1, |i, this, args| generic(i, this, args, array::last_index_of)),
+        ("map", 1, map),
+        ("reduce", 1, |i, this, args| generic(i, this, args, array::reduce)),
+        ("reduceRight", 1, |i, this, args| generic(i, this, args, array::reduce_right)),
+        ("reverse", 0, reverse),
+        ("set", 1, set),
+        ("slice", 2, slice),
+        ("some", 1, |i, this, args| generic(i, this, args, array::some)),
+        ("sort", 1, sort),
+        ("subarray", 2, subarray),
+        ("toReversed", 0, to_reversed),
+        ("toSorted", 1, to_sorted),
+        ("values", 0, |i, this, args| generic(i, this, args, array::values)),
+        ("with", 2, with),
+    ];
+    for &(name, length, function) in methods {
+        define_method(interpreter, prototype, name, length, function);
+    }
+    let iterator = PropertyKey::Symbol(interpreter.symbols.iterator.clone());
+    super::define_alias(interpreter, prototype, iterator, "values");
+    // `toString` is the very function `Array.prototype.toString` is
+    let to_string = interpreter.heap.get(interpreter.intrinsics.array_prototype).properties[&PropertyKey::from("toString")].clone();
+    interpreter.heap.define_property(prototype, PropertyKey::from("toString"), to_string);
+
+    let constructors: [(ElementType, NativeFunction); 11] = [
+        (ElementType::Int8, |i, _, args| construct(i, args, ElementType::Int8)),
+        (ElementType::Uint8, |i, _, args| construct(i, args, ElementType::Uint8)),
+        (ElementType::Uint8Clamped, |i, _, args| construct(i, args, ElementType::Uint8Clamped)),
+        (ElementType::Int16, |i, _, args| construct(i, args, ElementType::Int16)),
+        (ElementType::Uint16, |i, _, args| construct(i, args, ElementType::Uint16)),
+        (ElementType::Int32, |i, _, args| construct(i, args, ElementType::Int32)),
+        (ElementType::Uint32, |i, _, args| construct(i, args, ElementType::Uint32)),
+        (ElementType::Float32, |i, _, args| construct(i, args, ElementType::Float32)),
+        (ElementType::Float64, |i, _, args| construct(i, args, ElementType::Float64)),
+        (ElementType::BigInt64, |i, _, args| construct(i, args, ElementType::BigInt64)),
+        (ElementType::BigUint64, |i, _, args| construct(i, args, ElementType::BigUint64)),
+    ];
+    for (element, function) in constructors {
+        install_constructor(interpreter, constructor_id, element, function);
+    }
+}
+
+/// Creates the constructor of one typed array type, which inherits from
+/// `%TypedArray%` as its prototype inherits from `%TypedArray%.prototype`.
+fn install_constructor(interpreter: &mut Interpreter, typed_array: ObjectId, element: ElementType, function: NativeFunction) {
+    let name = format!("{}Array", element.name());
+    let prototype = interpreter.intrinsics.typed_array_prototypes[element as usize];
+    let constructor = interpreter.create_native_function(&name, 3, function);
+    super::link_constructor(interpreter, &constructor, prototype);
+    let Value::Function(constructor_id) = constructor else { unreachable!() };
+    interpreter.heap.get_mut(constructor_id).prototype = Some(typed_array);
+    for target in [constructor_id, prototype] {
+        let size = Property::data(Value::Number(element.size() as f64), Attributes::FIXED);
+        interpreter.heap.define_property(target, PropertyKey::from("BYTES_PER_ELEMENT"), size);
+    }
+    define_global(interpreter, &name, constructor);
+}
+
+fn abstract_constructor(_interpreter: &mut Interpreter, _this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Err(type_error("Abstract class TypedArray not directly constructable"))
+}
+
+/// `new Uint8Array(...)` and the other constructors: from a length, a
+/// buffer to view, another typed array, or an iterable or array-like
+/// object.
+fn construct(interpreter: &mut Interpreter, args: &[Value], element: ElementType) -> Result<Value, String> {
+    let name = format!("{}Array", element.name());
+    if interpreter.new_target.is_none() {
+        return Err(type_error(format!("Constructor {} requires 'new'", name)));
+    }
+    let prototype = interpreter.new_target_prototype(interpreter.intrinsics.typed_array_prototypes[element as usize])?;
+    let first = arg(args, 0);
+    let (Value::Object(source) | Value::Function(source)) = first else {
+        let length = to_index(interpreter, &first, |n| range_error(format!("Invalid typed array length: {}", number_to_string(n))))?;
+        return Ok(Value::Object(interpreter.create_typed_array(element, length, prototype)?));
+    };
+
+    let values = match interpreter.heap.get(source).kind {
+        ObjectKind::ArrayBuffer(_) => return view_buffer(interpreter, args, source, element, prototype),
+        ObjectKind::TypedArray(view) => {
+            if view.element.is_bigint() != element.is_bigint() {
+                return Err(mixed_content());
+            }
+            interpreter.typed_array_values(source)
+        }
+        _ => match interpreter.iterator_method(&first)? {
+            Some(method) => {
+                let mut iteration = interpreter.iterate_with(&first, &method)?;
+                interpreter.collect_iteration(&mut iteration)?
+            }
+            None => interpreter.create_list_from_array_like(&first)?,
+        },
+    };
+    let id = interpreter.create_typed_array(element, values.len(), prototype)?;
+    for (index, value) in values.iter().enumerate() {
+        interpreter.write_element(id, Some(index), value)?;
+    }
+    Ok(Value::Object(id))
+}
+
+/// A typed array over part of an existing buffer, from the byte offset and
+/// length arguments.
+fn view_buffer(interpreter: &mut Interpreter, args: &[Value], buffer: ObjectId, element: ElementType, prototype: ObjectId) -> Result<Value, String> {
+    let name = format!("{}Array", element.name());
+    let size = element.size();
+    let outside = |offset: f64| range_error(format!("Start offset {} is outside the bounds of the buffer", number_to_string(offset)));
+    let byte_offset = to_index(interpreter, &arg(args, 1), outside)?;
+    if !byte_offset.is_multiple_of(size) {
+        return Err(range_error(format!("start offset of {} should be a multiple of {}", name, size)));
+    }
+    let invalid_length = |length: f64| range_error(format!("Invalid typed array length: {}", number_to_string(length)));
+    let buffer_length = interpreter.buffer_bytes(buffer).len();
+    let length = match arg(args, 2) {
+        Value::Undefined => {
+            if !buffer_length.is_multiple_of(size) {
+                return Err(range_error(format!("byte length of {} should be a multiple of {}", name, size)));
+            }
+            if byte_offset > buffer_length {
+                return Err(outside(byte_offset as f64));
+            }
+            (buffer_length - byte_offset) / size
+        }
+        length => {
+            let length = to_index(interpreter, &length, invalid_length)?;
+            if length.saturating_mul(size).saturating_add(byte_offset) > buffer_length {
+                return Err(invalid_length(length as f64));
+            }
+            length
+        }
+    };
+    let view = TypedArrayData { element, buffer, byte_offset, length };
+    Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::TypedArray(view), Some(prototype)))))
+}
+
+fn mixed_content() -> String {
+    type_error("Cannot mix BigInt and other types, use explicit conversions")
+}
+
+/// The typed array a method was called on.
+fn this_typed_array(interpreter: &Interpreter, this: &Value) -> Result<(ObjectId, TypedArrayData), String> {
+    if let Value::Object(id) = this {
+        if let ObjectKind::TypedArray(view) = interpreter.heap.get(*id).kind {
+            return Ok((*id, view));
+        }
+    }
+    Err(type_error("this is not a typed array."))
+}
+
+/// Runs a generic `Array.prototype` method on a typed array.
+fn generic(interpreter: &mut Interpreter, this: &Value, args: &[Value], method: NativeFunction) -> Result<Value, String> {
+    this_typed_array(interpreter, this)?;
+    method(interpreter, this, args)
+}
+
+/// A new typed array of the given type, as the copying methods return.
+fn create_like(interpreter: &mut Interpreter, element: ElementType, values: &[Value]) -> Result<Value, String> {
+    let prototype = interpreter.intrinsics.typed_array_prototypes[element as usize];
+    let id = interpreter.create_typed_array(element, values.len(), prototype)?;
+    for (index, value) in values.iter().enumerate() {
+        interpreter.store_element(id, index, value);
+    }
+    Ok(Value::Object(id))
+}
+
+/// The `TypedArrayCreateFromConstructor` operation behind `from` and `of`:
+/// constructs `constructor` with a length, which must give a typed array
+/// at least that long.
+fn create_from_constructor(interpreter: &mut Interpreter, constructor: &Value, length: usize) -> Result<Value, String> {
+    if !interpreter.is_constructor(constructor) {
+        return Err(type_error(format!("{} is not a constructor", interpreter.to_display_string(constructor))));
+    }
+    let result = interpreter.construct(constructor, vec![Value::Number(length as f64)], constructor)?;
+    let (_, view) = this_typed_array(interpreter, &result)?;
+    if view.length < length {
+        return Err(type_error("Derived TypedArray constructor created an array which was too small"));
+    }
+    Ok(result)
+}
+
+/// `Uint8Array.from(source, mapFn, thisArg)` and the same on the other
+/// constructors.
+fn from(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let map_fn = arg(args, 1);
+    if !matches!(map_fn, Value::Undefined) {
+        array::callback(interpreter, &map_fn)?;
+    }
+    let source = arg(args, 0);
+    let values = match (&source, interpreter.iterator_method(&source)?) {
+        (_, Some(method)) => {
+            let mut iteration = interpreter.iterate_with(&source, &method)?;
+            interpreter.collect_iteration(&mut iteration)?
+        }
+        (Value::Object(_) | Value::Function(_), None) => interpreter.create_list_from_array_like(&source)?,
+        (_, None) => Vec::new(),
+    };
+    let result = create_from_constructor(interpreter, this, values.len())?;
+    let this_arg = arg(args, 2);
+    for (index, value) in values.into_iter().enumerate() {
+        let value = match map_fn {
+            Value::Undefined => value,
+            _ => interpreter.call_function(&map_fn, this_arg.clone(), vec![value, Value::Number(index as f64)])?,
+        };
+        interpreter.set_member(&result, PropertyKey::from(index as u32), value)?;
+    }
+    Ok(result)
+}
+
+fn of(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let result = create_from_constructor(interpreter, this, args.len())?;
+    for (index, value) in args.iter().enumerate() {
+        interpreter.set_member(&result, PropertyKey::from(index as u32), value.clone())?;
+    }
+    Ok(result)
+}
+
+/// The receiver of a `%TypedArray%.prototype` getter.
+fn getter_receiver(interpreter: &Interpreter, this: &Value, name: &str) -> Result<TypedArrayData, String> {
+    this_typed_array(interpreter, this)
+        .map(|(_, view)| view)
+        .map_err(|_| incompatible_receiver(interpreter, &format!("get TypedArray.prototype.{}", name), this))
+}
+
+fn buffer(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Object(getter_receiver(interpreter, this, "buffer")?.buffer))
+}
+
+fn byte_length(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(getter_receiver(interpreter, this, "byteLength")?.byte_length() as f64))
+}
+
+fn byte_offset(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(getter_receiver(interpreter, this, "byteOffset")?.byte_offset as f64))
+}
+
+fn length(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(getter_receiver(interpreter, this, "length")?.length as f64))
+}
+
+/// `Symbol.toStringTag`, which is the type name for typed arrays and
+/// `undefined` for anything else.
+fn to_string_tag(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(match this_typed_array(interpreter, this) {
+        Ok((_, view)) => Value::String(format!("{}Array", view.element.name()).into()),
+        Err(_) => Value::Undefined,
+    })
+}
+
+fn copy_within(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (_, view) = this_typed_array(interpreter, this)?;
+    let length = view.length as u64;
+    let target = relative_index(interpreter, &arg(args, 0), length, 0)? as usize;
+    let start = relative_index(interpreter, &arg(args, 1), length, 0)? as usize;
+    let end = relative_index(interpreter, &arg(args, 2), length, length)? as usize;
+    let count = end.saturating_sub(start).min(view.length - target);
+    let size = view.element.size();
+    let base = view.byte_offset;
+    interpreter.buffer_bytes_mut(view.buffer).copy_within(base + start * size..base + (start + count) * size, base + target * size);
+    Ok(this.clone())
+}
+
+/// `fill(value, start, end)`, which converts `value` once.
+fn fill(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, view) = this_typed_array(interpreter, this)?;
+    let value = interpreter.coerce_to_element(view.element, &arg(args, 0))?;
+    let length = view.length as u64;
+    let start = relative_index(interpreter, &arg(args, 1), length, 0)?;
+    let end = relative_index(interpreter, &arg(args, 2), length, length)?;
+    for index in start..end {
+        interpreter.store_element(id, index as usize, &value);
+    }
+    Ok(this.clone())
+}
+
+/// Calls `callback(element, index, array)` for every element, collecting
+/// the results.
+fn call_for_each(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<(TypedArrayData, Vec<(Value, Value)>), String> {
+    let (id, view) = this_typed_array(interpreter, this)?;
+    let function = array::callback(interpreter, &arg(args, 0))?;
+    let this_arg = arg(args, 1);
+    let mut results = Vec::with_capacity(view.length);
+    for index in 0..view.length {
+        let element = interpreter.read_element(id, index);
+        let call_args = vec![element.clone(), Value::Number(index as f64), this.clone()];
+        let result = interpreter.call_function(&function, this_arg.clone(), call_args)?;
+        results.push((element, result));
+    }
+    Ok((view, results))
+}
+
+fn filter(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (view, results) = call_for_each(interpreter, this, args)?;
+    let kept: Vec<Value> = results.into_iter().filter(|(_, result)| result.is_truthy()).map(|(element, _)| element).collect();
+    create_like(interpreter, view.element, &kept)
+}
+
+fn map(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (view, results) = call_for_each(interpreter, this, args)?;
+    let mut mapped = Vec::with_capacity(results.len());
+    for (_, result) in results {
+        mapped.push(interpreter.coerce_to_element(view.element, &result)?);
+    }
+    create_like(interpreter, view.element, &mapped)
+}
+
+fn reverse(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let (id, _) = this_typed_array(interpreter, this)?;
+    let values = interpreter.typed_array_values(id);
+    for (index, value) in values.iter().rev().enumerate() {
+        interpreter.store_element(id, index, value);
+    }
+    Ok(this.clone())
+}
+
+/// `set(source, offset)`: copies a typed array or array-like into this
+/// one, starting at `offset`.
+fn set(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, view) = this_typed_array(interpreter, this)?;
+    let offset = to_integer(interpreter.coerce_to_number(&arg(args, 1))?);
+    if offset < 0.0 {
+        return Err(range_error("offset is out of bounds"));
+    }
+    let source = arg(args, 0);
+    let typed_source = match &source {
+        Value::Object(source_id) => match interpreter.heap.get(*source_id).kind {
+            ObjectKind::TypedArray(source_view) => Some((*source_id, source_view)),
+            _ => None,
+        },
+        Value::Null | Value::Undefined => return Err(type_error("Cannot convert undefined or null to object")),
+        _ => None,
+    };
+    if let Some((source_id, source_view)) = typed_source {
+        if source_view.element.is_bigint() != view.element.is_bigint() {
+            return Err(mixed_content());
+        }
+        if source_view.length as f64 + offset > view.length as f64 {
+            return Err(range_error("offset is out of bounds"));
+        }
+        // Reading everything first lets the source overlap this array
+        let values = interpreter.typed_array_values(source_id);
+        for (index, value) in values.iter().enumerate() {
+            interpreter.store_element(id, offset as usize + index, value);
+        }
+        return Ok(Value::Undefined);
+    }
+
+    let length = interpreter.get_member(&source, &PropertyKey::from("length"))?;
+    let length = to_integer(interpreter.coerce_to_number(&length)?).max(0.0);
+    if length + offset > view.length as f64 {
+        return Err(range_error("offset is out of bounds"));
+    }
+    for index in 0..length as u32 {
+        let value = interpreter.get_member(&source, &PropertyKey::from(index))?;
+        interpreter.write_element(id, Some(offset as usize + index as usize), &value)?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// `slice(start, end)`: a copy of the elements between two relative
+/// indices.
+fn slice(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, view) = this_typed_array(interpreter, this)?;
+    let length = view.length as u64;
+    let start = relative_index(interpreter, &arg(args, 0), length, 0)? as usize;
+    let end = relative_index(interpreter, &arg(args, 1), length, length)? as usize;
+    let values: Vec<Value> = (start..end.max(start)).map(|index| interpreter.read_element(id, index)).collect();
+    create_like(interpreter, view.element, &values)
+}
+
+/// `subarray(begin, end)`: a new view of part of the same buffer.
+fn subarray(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (_, view) = this_typed_array(interpreter, this)?;
+    let length = view.length as u64;
+    let begin = relative_index(interpreter, &arg(args, 0), length, 0)? as usize;
+    let end = relative_index(interpreter, &arg(args, 1), length, length)? as usize;
+    let subarray = TypedArrayData {
+        byte_offset: view.byte_offset + begin * view.element.size(),
+        length: end.saturating_sub(begin),
+        ..view
+    };
+    let prototype = Some(interpreter.intrinsics.typed_array_prototypes[view.element as usize]);
+    Ok(Value::Object(interpreter.heap.allocate(Object::new(ObjectKind::TypedArray(subarray), prototype))))
+}
+
+/// Sorts elements numerically, or with the user's comparator when it is
+/// not `undefined`.
+fn sort_elements(interpreter: &mut Interpreter, mut values: Vec<Value>, comparator: &Value) -> Result<Vec<Value>, String> {
+    if !matches!(comparator, Value::Undefined) {
+        return array::merge_sort(interpreter, values, comparator);
+    }
+    values.sort_by(numeric_order);
+    Ok(values)
+}
+
+/// The default order of elements: `-0` before `+0`, and `NaN` last.
+fn numeric_order(left: &Value, right: &Value) -> Ordering {
+    match (left, right) {
+        (Value::BigInt(left), Value::BigInt(right)) => left.cmp(right),
+        (Value::Number(left), Value::Number(right)) => match (left.is_nan(), right.is_nan()) {
+            (false, false) => left.total_cmp(right),
+            (left, right) => left.cmp(&right),
+        },
+        _ => Ordering::Equal,
+    }
+}
+
+fn comparator(args: &[Value]) -> Result<Value, String> {
+    match arg(args, 0) {
+        comparator @ (Value::Undefined | Value::Function(_)) => Ok(comparator),
+        _ => Err(type_error("The comparison function must be either a function or undefined")),
+    }
+}
+
+fn sort(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let comparator = comparator(args)?;
+    let (id, _) = this_typed_array(interpreter, this)?;
+    let values = interpreter.typed_array_values(id);
+    let sorted = sort_elements(interpreter, values, &comparator)?;
+    for (index, value) in sorted.iter().enumerate() {
+        interpreter.store_element(id, index, value);
+    }
+    Ok(this.clone())
+}
+
+fn to_reversed(interpreter: &mut Interpreter, this: &Value, _args: &[Value]) -> Result<Value, String> {
+    let (id, view) = this_typed_array(interpreter, this)?;
+    let mut values = interpreter.typed_array_values(id);
+    values.reverse();
+    create_like(interpreter, view.element, &values)
+}
+
+fn to_sorted(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let comparator = comparator(args)?;
+    let (id, view) = this_typed_array(interpreter, this)?;
+    let values = interpreter.typed_array_values(id);
+    let sorted = sort_elements(interpreter, values, &comparator)?;
+    create_like(interpreter, view.element, &sorted)
+}
+
+/// `with(index, value)`: a copy with one element replaced.
+fn with(interpreter: &mut Interpreter, this: &Value, args: &[Value]) -> Result<Value, String> {
+    let (id, view) = this_typed_array(interpreter, this)?;
+    let n = to_integer(interpreter.coerce_to_number(&arg(args, 0))?);
+    let index = if n < 0.0 { view.length as f64 + n } else { n };
+    let value = interpreter.coerce_to_element(view.element, &arg(args, 1))?;
+    if index < 0.0 || index >= view.length as f64 {
+        return Err(range_error("Invalid typed array index"));
+    }
+    let mut values = interpreter.typed_array_values(id);
+    values[index as usize] = value;
+    create_like(interpreter, view.element, &values)
+}
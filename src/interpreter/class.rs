@@ -0,0 +1,513 @@
+//! Classes and construction: evaluating class definitions, `new` and
+//! `super()`, and the `super.x` and `#private` member accesses methods use.
+
+use std::rc::Rc;
+
+use super::environment::{Binding, EnvRef, Environment, FunctionContext, ScopeKind};
+use super::object::{
+    Attributes, ClassField, ClassInfo, FieldKey, Object, ObjectId, ObjectKind, PrivateName, Property, PropertyDescriptor,
+    PropertyKey, PropertyValue,
+};
+use super::{reference_error, type_error, Interpreter, Value};
+use crate::parser::{ClassDefinition, ClassKey, ClassMemberKind, Expression, FunctionDefinition, FunctionKind, VariableKind};
+
+const THIS_BEFORE_SUPER: &str =
+    "Must call super constructor in derived class before accessing 'this' or returning from derived constructor";
+
+impl Interpreter {
+    /// The `this` of the current function, or `undefined` at the top level.
+    pub(super) fn this_value(&self) -> Result<Value, String> {
+        match Environment::function_context(&self.env) {
+            Some(FunctionContext { this: Some(this), .. }) => Ok(this),
+            Some(_) => Err(reference_error(THIS_BEFORE_SUPER)),
+            None => Ok(Value::Undefined),
+        }
+    }
+
+    /// Whether `value` can be used with `new`.
+    pub(super) fn is_constructor(&self, value: &Value) -> bool {
+        let Value::Function(id) = value else {
+            return false;
+        };
+        let object = self.heap.get(*id);
+        match &object.kind {
+            ObjectKind::Function(closure) => {
+                let definition = &closure.definition;
+                !definition.is_generator && !definition.is_async && !matches!(definition.kind, FunctionKind::Arrow | FunctionKind::Method)
+            }
+            // Only the built-in constructors have a `prototype`, apart from `Proxy`
+            ObjectKind::NativeFunction(_) => {
+                object.properties.contains_key(&PropertyKey::from("prototype")) || *id == self.intrinsics.proxy_constructor
+            }
+            ObjectKind::BoundFunction(bound) => self.is_constructor(&bound.target),
+            ObjectKind::Proxy(proxy) => proxy.constructor,
+            _ => false,
+        }
+    }
+
+    /// Creates an object with `constructor`, as `new` does. `new_target` is
+    /// the constructor `new` was applied to, which differs from
+    /// `constructor` when a subclass calls `super()`.
+    pub(super) fn construct(&mut self, constructor: &Value, args: Vec<Value>, new_target: &Value) -> Result<Value, String> {
+        let Value::Function(id) = *constructor else {
+            return Err(type_error(format!("{} is not a constructor", self.to_display_string(constructor))));
+        };
+        match &self.heap.get(id).kind {
+            ObjectKind::NativeFunction(native) => {
+                let native = *native;
+                self.call_native(native, Value::Undefined, args, Some(new_target.clone()))
+            }
+            ObjectKind::BoundFunction(bound) => {
+                let target = bound.target.clone();
+                let args = bound.args.iter().cloned().chain(args).collect();
+                let new_target = if new_target == constructor { target.clone() } else { new_target.clone() };
+                self.construct(&target, args, &new_target)
+            }
+            ObjectKind::Function(closure) => {
+                let kind = closure.definition.kind;
+                let home_object = closure.home_object;
+                if closure.class.as_ref().is_some_and(|class| class.is_abstract) && new_target == constructor {
+                    return Err(type_error("Cannot create an instance of an abstract class"));
+                }
+
+                // A derived constructor gets its `this` from `super()`
+                let this = if kind == FunctionKind::DerivedConstructor {
+                    None
+                } else {
+                    let prototype = self.prototype_of_constructor(new_target, self.intrinsics.object_prototype)?;
+                    Some(Value::Object(self.heap.allocate(Object::new(ObjectKind::Ordinary, Some(prototype)))))
+                };
+                let context = FunctionContext { this, callee: Some(id), home_object, new_target: new_target.clone() };
+                let (result, scope) = self.call_closure(id, context, args)?;
+                match result {
+                    Value::Object(_) | Value::Function(_) => return Ok(result),
+                    Value::Undefined => {}
+                    _ if kind == FunctionKind::DerivedConstructor => {
+                        return Err(type_error("Derived constructors may only return object or undefined"));
+                    }
+                    _ => {}
+                }
+                Environment::function_context(&scope)
+                    .and_then(|context| context.this)
+                    .ok_or_else(|| reference_error(THIS_BEFORE_SUPER))
+            }
+            ObjectKind::Proxy(proxy) if proxy.constructor => self.construct_proxy(id, args, new_target),
+            _ => Err(type_error("object is not a constructor")),
+        }
+    }
+
+    /// The `prototype` of `constructor` if that is an object, else `fallback`.
+    fn prototype_of_constructor(&mut self, constructor: &Value, fallback: ObjectId) -> Result<ObjectId, String> {
+        match self.get_member(constructor, &PropertyKey::from("prototype"))? {
+            Value::Object(id) | Value::Function(id) => Ok(id),
+            _ => Ok(fallback),
+        }
+    }
+
+    /// The prototype for an object made by a native constructor: taken from
+    /// `new.target`, so subclasses of built-ins get their own, or `fallback`
+    /// when the constructor was called without `new`.
+    pub(super) fn new_target_prototype(&mut self, fallback: ObjectId) -> Result<ObjectId, String> {
+        match self.new_target.clone() {
+            Some(new_target) => self.prototype_of_constructor(&new_target, fallback),
+            None => Ok(fallback),
+        }
+    }
+
+    /// `super(...)` in a derived constructor: constructs `this` with the
+    /// parent class, then sets up the fields of this class.
+    pub(super) fn eval_super_call(&mut self, arguments: &[Expression]) -> Result<Value, String> {
+        let scope = Environment::function_scope(&self.env).expect("parser only allows super() in constructors");
+        let context = Environment::function_context(&scope).expect("function scope without a context");
+        let callee = context.callee.expect("super() outside a constructor");
+
+        let parent = match self.heap.get(callee).prototype {
+            Some(parent) => self.object_value(parent),
+            None => Value::Null,
+        };
+        let args = self.eval_arguments(arguments)?;
+        if !self.is_constructor(&parent) {
+            let class = match self.get_member(&Value::Function(callee), &PropertyKey::from("name"))? {
+                Value::String(name) if !name.is_empty() => name,
+                _ => "anonymous class".to_string().into(),
+            };
+            // A class extending `null` inherits from `Function.prototype`,
+            // which V8 calls `null` here like any function without a name
+            let parent = match parent {
+                Value::Object(id) if id == self.intrinsics.function_prototype => "null".to_string(),
+                Value::Function(_) => match self.get_member(&parent, &PropertyKey::from("name"))? {
+                    Value::String(name) if !name.is_empty() => name.to_string(),
+                    _ => "null".to_string(),
+                },
+                other => self.to_display_string(&other),
+            };
+            return Err(type_error(format!("Super constructor {} of {} is not a constructor", parent, class)));
+        }
+        let this = self.construct(&parent, args, &context.new_target)?;
+
+        if let Some(function) = scope.borrow_mut().function_mut() {
+            if function.this.is_some() {
+                return Err(reference_error("Super constructor may only be called once"));
+            }
+            function.this = Some(this.clone());
+        }
+        self.initialize_instance(&this, callee)?;
+        Ok(this)
+    }
+
+    /// Adds the private methods, parameter properties and fields declared by
+    /// the class `constructor` to a new instance. Runs in the scope of the
+    /// constructor call.
+    pub(super) fn initialize_instance(&mut self, this: &Value, constructor: ObjectId) -> Result<(), String> {
+        let ObjectKind::Function(closure) = &self.heap.get(constructor).kind else {
+            return Ok(());
+        };
+        let definition = Rc::clone(&closure.definition);
+        let class = closure.class.clone().unwrap_or_default();
+        let (env, home_object) = (closure.env.clone(), closure.home_object);
+
+        for (name, property) in &class.private_methods {
+            self.add_private_element(this, name.clone(), property.clone())?;
+        }
+
+        // `constructor(public x: number)` copies the argument to `this.x`
+        // before any field initializer runs
+        if !definition.parameter_properties.is_empty() {
+            let scope = Environment::function_scope(&self.env).expect("constructor without a function scope");
+            for name in &definition.parameter_properties {
+                let value = scope.borrow().get_own(name).and_then(|binding| binding.value.clone()).unwrap_or(Value::Undefined);
+                self.set_member(this, PropertyKey::from(name.as_str()), value)?;
+            }
+        }
+
+        for field in &class.fields {
+            let value = match &field.initializer {
+                Some(initializer) => self.eval_field_initializer(initializer, this, home_object, &env, &field.key)?,
+                None => Value::Undefined,
+            };
+            self.define_field(this, &field.key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Evaluates a field initializer as if it were the body of a method
+    /// of the class, with `this` bound to the object being initialized.
+    fn eval_field_initializer(
+        &mut self,
+        initializer: &Expression,
+        this: &Value,
+        home_object: Option<ObjectId>,
+        env: &EnvRef,
+        key: &FieldKey,
+    ) -> Result<Value, String> {
+        let context = FunctionContext { this: Some(this.clone()), callee: None, home_object, new_target: Value::Undefined };
+        let scope = Environment::new_function(env, context);
+        self.in_scope(scope, |interpreter| interpreter.eval_named(initializer, &key.function_name()))
+    }
+
+    /// Creates a field on `target`. Public fields are defined rather than
+    /// assigned, so setters on the prototype are not called.
+    fn define_field(&mut self, target: &Value, key: &FieldKey, value: Value) -> Result<(), String> {
+        let (Value::Object(id) | Value::Function(id)) = *target else {
+            unreachable!("fields are only defined on objects")
+        };
+        match key {
+            FieldKey::Private(name) => self.add_private_element(target, name.clone(), Property::data(value, Attributes::HIDDEN)),
+            FieldKey::Public(key) => {
+                let descriptor = PropertyDescriptor {
+                    value: Some(value),
+                    writable: Some(true),
+                    enumerable: Some(true),
+                    configurable: Some(true),
+                    ..PropertyDescriptor::default()
+                };
+                self.define_property_or_throw(id, key.clone(), descriptor)
+            }
+        }
+    }
+
+    fn add_private_element(&mut self, target: &Value, name: PrivateName, property: Property) -> Result<(), String> {
+        let (Value::Object(id) | Value::Function(id)) = *target else {
+            unreachable!("private elements are only added to objects")
+        };
+        let elements = &mut self.heap.get_mut(id).private_elements;
+        if elements.contains_key(&name) {
+            return Err(type_error(format!("Cannot initialize {} twice on the same object", name)));
+        }
+        elements.insert(name, property);
+        Ok(())
+    }
+
+    /// Sets the object `super.x` is looked up from in a method.
+    pub(super) fn set_home_object(&mut self, function: &Value, home_object: ObjectId) {
+        if let Value::Function(id) = function {
+            if let ObjectKind::Function(closure) = &mut self.heap.get_mut(*id).kind {
+                closure.home_object = Some(home_object);
+            }
+        }
+    }
+
+    /// Evaluates a class declaration or expression to its constructor.
+    /// `name` names an anonymous class, as in `const A = class {}`.
+    pub(super) fn eval_class(&mut self, definition: &ClassDefinition, name: Option<&str>) -> Result<Value, String> {
+        // Methods see the class's own name even if the outer binding is
+        // reassigned, and the `#names` it declares
+        let scope = Environment::new_child(&self.env, ScopeKind::Block);
+        if let Some(name) = &definition.name {
+            scope.borrow_mut().insert(name.clone(), Binding { value: None, kind: VariableKind::Const });
+        }
+        self.in_scope(scope, |interpreter| interpreter.eval_class_body(definition, name))
+    }
+
+    fn eval_class_body(&mut self, definition: &ClassDefinition, name: Option<&str>) -> Result<Value, String> {
+        let (prototype_parent, constructor_parent) = match &definition.superclass {
+            None => (Some(self.intrinsics.object_prototype), self.intrinsics.function_prototype),
+            Some(superclass) => match self.eval_expression(superclass)? {
+                Value::Null => (None, self.intrinsics.function_prototype),
+                parent @ Value::Function(parent_id) if self.is_constructor(&parent) => {
+                    match self.get_member(&parent, &PropertyKey::from("prototype"))? {
+                        Value::Object(id) | Value::Function(id) => (Some(id), parent_id),
+                        Value::Null => (None, parent_id),
+                        other => {
+                            return Err(type_error(format!(
+                                "Class extends value does not have valid prototype property {}",
+                                other.to_js_string()
+                            )))
+                        }
+                    }
+                }
+                other => {
+                    let shown = match other {
+                        Value::Object(_) => "#<Object>".to_string(),
+                        other => other.to_js_string(),
+                    };
+                    return Err(type_error(format!("Class extends value {} is not a constructor or null", shown)));
+                }
+            },
+        };
+
+        let prototype = self.heap.allocate(Object::new(ObjectKind::Ordinary, prototype_parent));
+        let constructor = self.create_function(&definition.constructor, self.env.clone());
+        let Value::Function(constructor_id) = constructor else {
+            unreachable!("create_function returns a function")
+        };
+        self.heap.get_mut(constructor_id).prototype = Some(constructor_parent);
+        self.set_home_object(&constructor, prototype);
+        self.set_function_name(&constructor, definition.name.as_deref().or(name).unwrap_or_default());
+        let prototype_property = Property::data(Value::Object(prototype), Attributes::FIXED);
+        self.heap.define_property(constructor_id, PropertyKey::from("prototype"), prototype_property);
+        let constructor_property = Property::data(constructor.clone(), Attributes::HIDDEN);
+        self.heap.define_property(prototype, PropertyKey::from("constructor"), constructor_property);
+
+        // Each `#name` is bound to the constructor, which identifies this
+        // evaluation of the class
+        for member in &definition.members {
+            if let ClassKey::Private(private) = &member.key {
+                let binding = Binding { value: Some(constructor.clone()), kind: VariableKind::Const };
+                self.env.borrow_mut().insert(format!("#{}", private), binding);
+            }
+        }
+
+        let mut class = ClassInfo { is_abstract: definition.is_abstract, ..ClassInfo::default() };
+        let mut static_initializers = Vec::new();
+        for member in &definition.members {
+            if let ClassMemberKind::StaticBlock(block) = &member.kind {
+                static_initializers.push(StaticInitializer::Block(block));
+                continue;
+            }
+            let home_object = if member.is_static { constructor_id } else { prototype };
+            let key = match &member.key {
+                ClassKey::Public(name) => FieldKey::Public(self.eval_property_name(name)?),
+                ClassKey::Private(name) => FieldKey::Private(self.private_name(name)?),
+            };
+            let (function, accessor) = match &member.kind {
+                ClassMemberKind::Field(initializer) => {
+                    let field = ClassField { key, initializer: initializer.clone() };
+                    if member.is_static {
+                        static_initializers.push(StaticInitializer::Field(field));
+                    } else {
+                        class.fields.push(field);
+                    }
+                    continue;
+                }
+                ClassMemberKind::Method(function) => (function, None),
+                ClassMemberKind::Getter(function) => (function, Some(true)),
+                ClassMemberKind::Setter(function) => (function, Some(false)),
+                ClassMemberKind::StaticBlock(_) => unreachable!("static blocks are handled above"),
+            };
+
+            let function = self.create_function(function, self.env.clone());
+            self.set_home_object(&function, home_object);
+            let function_name = match accessor {
+                None => key.function_name(),
+                Some(true) => format!("get {}", key.function_name()),
+                Some(false) => format!("set {}", key.function_name()),
+            };
+            self.set_function_name(&function, &function_name);
+
+            match key {
+                FieldKey::Public(key) => {
+                    let property = match accessor {
+                        None => Property::data(function, Attributes::HIDDEN),
+                        Some(is_getter) => {
+                            let existing = self.heap.get(home_object).properties.get(&key);
+                            Property::accessor(existing, is_getter, function, Attributes::HIDDEN)
+                        }
+                    };
+                    self.heap.define_property(home_object, key, property);
+                }
+                // Private methods are added to the constructor right away if
+                // static, else to each instance as it is created
+                FieldKey::Private(name) => {
+                    let elements = if member.is_static {
+                        self.heap.get(constructor_id).private_elements.get(&name).cloned()
+                    } else {
+                        class.private_methods.iter().find(|(existing, _)| *existing == name).map(|(_, property)| property.clone())
+                    };
+                    let property = match accessor {
+                        None => Property::data(function, Attributes::FIXED),
+                        Some(is_getter) => Property::accessor(elements.as_ref(), is_getter, function, Attributes::FIXED),
+                    };
+                    if member.is_static {
+                        self.heap.get_mut(constructor_id).private_elements.insert(name, property);
+                    } else if let Some(entry) = class.private_methods.iter_mut().find(|(existing, _)| *existing == name) {
+                        entry.1 = property;
+                    } else {
+                        class.private_methods.push((name, property));
+                    }
+                }
+            }
+        }
+
+        if let ObjectKind::Function(closure) = &mut self.heap.get_mut(constructor_id).kind {
+            closure.class = Some(Rc::new(class));
+        }
+        if let Some(name) = &definition.name {
+            if let Some(binding) = self.env.borrow_mut().get_own_mut(name) {
+                binding.value = Some(constructor.clone());
+            }
+        }
+
+        for initializer in static_initializers {
+            match initializer {
+                StaticInitializer::Field(field) => {
+                    let value = match &field.initializer {
+                        Some(initializer) => {
+                            let env = self.env.clone();
+                            self.eval_field_initializer(initializer, &constructor, Some(constructor_id), &env, &field.key)?
+                        }
+                        None => Value::Undefined,
+                    };
+                    self.define_field(&constructor, &field.key, value)?;
+                }
+                StaticInitializer::Block(block) => {
+                    let function = self.create_function(block, self.env.clone());
+                    self.set_home_object(&function, constructor_id);
+                    self.call_function(&function, constructor.clone(), Vec::new())?;
+                }
+            }
+        }
+        Ok(constructor)
+    }
+
+    /// Resolves `#name` to the private name declared by the innermost
+    /// enclosing class that declares it.
+    pub(super) fn private_name(&self, name: &str) -> Result<PrivateName, String> {
+        match self.lookup(&format!("#{}", name))? {
+            Value::Function(class) => Ok(PrivateName { class, name: name.to_string() }),
+            _ => unreachable!("private names are bound to their class"),
+        }
+    }
+
+    fn private_element(&self, target: &Value, name: &PrivateName) -> Option<Property> {
+        match target {
+            Value::Object(id) | Value::Function(id) => self.heap.get(*id).private_elements.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Reads `target.#name`.
+    pub(super) fn get_private(&mut self, target: &Value, name: &PrivateName) -> Result<Value, String> {
+        let property = self.private_element(target, name).ok_or_else(|| {
+            type_error(format!("Cannot read private member {} from an object whose class did not declare it", name))
+        })?;
+        match property.value {
+            PropertyValue::Data(value) => Ok(value),
+            PropertyValue::Accessor { get: Some(getter), .. } => self.call_function(&getter, target.clone(), Vec::new()),
+            PropertyValue::Accessor { get: None, .. } => Err(type_error(format!("'{}' was defined without a getter", name))),
+        }
+    }
+
+    /// Writes `target.#name`.
+    pub(super) fn set_private(&mut self, target: &Value, name: &PrivateName, value: Value) -> Result<(), String> {
+        let property = self.private_element(target, name).ok_or_else(|| {
+            type_error(format!("Cannot write private member {} to an object whose class did not declare it", name))
+        })?;
+        match property.value {
+            PropertyValue::Data(_) if !property.attributes.writable => Err(type_error("Private method is not writable")),
+            PropertyValue::Data(_) => {
+                let (Value::Object(id) | Value::Function(id)) = *target else {
+                    unreachable!("only objects have private elements")
+                };
+                self.heap.get_mut(id).private_elements.insert(name.clone(), Property::data(value, property.attributes));
+                Ok(())
+            }
+            PropertyValue::Accessor { set: Some(setter), .. } => {
+                self.call_function(&setter, target.clone(), vec![value])?;
+                Ok(())
+            }
+            PropertyValue::Accessor { set: None, .. } => Err(type_error(format!("'{}' was defined without a setter", name))),
+        }
+    }
+
+    /// `#name in target`.
+    pub(super) fn has_private(&self, target: &Value, name: &PrivateName) -> Result<bool, String> {
+        match target {
+            Value::Object(id) | Value::Function(id) => Ok(self.heap.get(*id).private_elements.contains_key(name)),
+            other => Err(type_error(format!(
+                "Cannot use 'in' operator to search for '{}' in {}",
+                name,
+                other.to_js_string()
+            ))),
+        }
+    }
+
+    /// The object `super.x` starts its lookup from: the prototype of the
+    /// current method's home object.
+    fn super_base(&self) -> Option<ObjectId> {
+        let context = Environment::function_context(&self.env)?;
+        self.heap.get(context.home_object?).prototype
+    }
+
+    /// Reads `super.key`, calling getters with the current `this`.
+    pub(super) fn get_super(&mut self, key: &PropertyKey) -> Result<Value, String> {
+        let this = self.this_value()?;
+        let Some(base) = self.super_base() else {
+            return Err(type_error(format!("Cannot read properties of null (reading '{}')", key)));
+        };
+        self.get_property(base, key, &this)
+    }
+
+    /// Writes `super.key`: a setter found from the home object's prototype
+    /// is called, otherwise the property is set on `this`.
+    pub(super) fn set_super(&mut self, key: PropertyKey, value: Value) -> Result<(), String> {
+        let this = self.this_value()?;
+        let Some(base) = self.super_base() else {
+            return self.set_member(&this, key, value);
+        };
+        if !self.set_property(base, &key, value, &this)? {
+            let (Value::Object(id) | Value::Function(id)) = this else {
+                return Ok(());
+            };
+            return Err(self.set_failure(id, &key));
+        }
+        Ok(())
+    }
+}
+
+/// A static field or `static` block, run in source order once the class
+/// is defined.
+enum StaticInitializer<'a> {
+    Field(ClassField),
+    Block(&'a Rc<FunctionDefinition>),
+}
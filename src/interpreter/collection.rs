@@ -0,0 +1,219 @@
+//! The storage behind `Map` and `Set`, and the iterators that walk it.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use num_bigint::BigInt;
+
+use super::object::{Object, ObjectId, ObjectKind};
+use super::{Interpreter, Symbol, Value};
+
+/// A key compared by `SameValueZero`: `NaN` equals itself and `-0`
+/// equals `+0`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum TableKey {
+    Number(u64),
+    BigInt(BigInt),
+    String(String),
+    Boolean(bool),
+    Null,
+    Undefined,
+    Symbol(Symbol),
+    Object(ObjectId),
+}
+
+impl From<&Value> for TableKey {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Number(n) if n.is_nan() => TableKey::Number(f64::NAN.to_bits()),
+            Value::Number(n) if *n == 0.0 => TableKey::Number(0),
+            Value::Number(n) => TableKey::Number(n.to_bits()),
+            Value::BigInt(n) => TableKey::BigInt(n.clone()),
+            Value::String(s) => TableKey::String(s.to_string()),
+            Value::Boolean(b) => TableKey::Boolean(*b),
+            Value::Null => TableKey::Null,
+            Value::Undefined => TableKey::Undefined,
+            Value::Symbol(symbol) => TableKey::Symbol(symbol.clone()),
+            Value::Object(id) | Value::Function(id) => TableKey::Object(*id),
+        }
+    }
+}
+
+/// The entries of a `Map` or `Set` in insertion order. A deleted entry
+/// leaves a hole until the table is compacted, and compaction moves the
+/// cursors of live iterators with it, so iterators keep their place while
+/// the collection changes under them.
+#[derive(Debug, Default)]
+pub struct OrderedTable {
+    entries: Vec<Option<(Value, Value)>>,
+    index: HashMap<TableKey, usize>,
+    cursors: Vec<Weak<Cell<usize>>>,
+}
+
+impl OrderedTable {
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        let position = *self.index.get(&TableKey::from(key))?;
+        self.entries[position].as_ref().map(|(_, value)| value)
+    }
+
+    pub fn has(&self, key: &Value) -> bool {
+        self.index.contains_key(&TableKey::from(key))
+    }
+
+    /// Replaces the value of an existing key, or appends a new entry.
+    pub fn set(&mut self, key: Value, value: Value) {
+        let table_key = TableKey::from(&key);
+        if let Some(&position) = self.index.get(&table_key) {
+            if let Some(entry) = &mut self.entries[position] {
+                entry.1 = value;
+            }
+            return;
+        }
+        // Keys are stored with `-0` normalized, as `Map.prototype.set` does;
+        // adding `+0` turns `-0` into `+0` and leaves other numbers alone
+        let key = match key {
+            Value::Number(n) => Value::Number(n + 0.0),
+            key => key,
+        };
+        self.index.insert(table_key, self.entries.len());
+        self.entries.push(Some((key, value)));
+    }
+
+    pub fn delete(&mut self, key: &Value) -> bool {
+        let Some(position) = self.index.remove(&TableKey::from(key)) else {
+            return false;
+        };
+        self.entries[position] = None;
+        let holes = self.entries.len() - self.index.len();
+        if holes > 16 && holes > self.index.len() {
+            self.compact();
+        }
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.index.clear();
+        self.for_each_cursor(|cursor| cursor.set(0));
+    }
+
+    /// A new position at the start of the table, kept valid as it changes.
+    pub fn cursor(&mut self) -> Rc<Cell<usize>> {
+        let cursor = Rc::new(Cell::new(0));
+        self.cursors.retain(|cursor| cursor.strong_count() > 0);
+        self.cursors.push(Rc::downgrade(&cursor));
+        cursor
+    }
+
+    /// The entry at or after `cursor`, moving the cursor past it.
+    pub fn next_entry(&self, cursor: &Cell<usize>) -> Option<(Value, Value)> {
+        while let Some(entry) = self.entries.get(cursor.get()) {
+            cursor.set(cursor.get() + 1);
+            if let Some(entry) = entry {
+                return Some(entry.clone());
+            }
+        }
+        None
+    }
+
+    /// The live entries in order.
+    pub fn iter(&self) -> impl Iterator<Item = &(Value, Value)> {
+        self.entries_from(0)
+    }
+
+    /// The live entries at or after the position of a cursor.
+    pub fn entries_from(&self, position: usize) -> impl Iterator<Item = &(Value, Value)> {
+        self.entries.iter().skip(position).flatten()
+    }
+
+    /// Removes the holes, moving each cursor to the same live entry.
+    fn compact(&mut self) {
+        // The number of live entries before each position
+        let mut live_before = Vec::with_capacity(self.entries.len() + 1);
+        let mut live = 0;
+        for entry in &self.entries {
+            live_before.push(live);
+            live += entry.is_some() as usize;
+        }
+        live_before.push(live);
+        self.for_each_cursor(|cursor| cursor.set(live_before[cursor.get().min(live_before.len() - 1)]));
+        self.entries.retain(Option::is_some);
+        for (position, (key, _)) in self.entries.iter().flatten().enumerate() {
+            self.index.insert(TableKey::from(key), position);
+        }
+    }
+
+    fn for_each_cursor(&mut self, mut update: impl FnMut(&Cell<usize>)) {
+        self.cursors.retain(|cursor| match cursor.upgrade() {
+            Some(cursor) => {
+                update(&cursor);
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+/// What a collection iterator produces for each entry.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IterationKind {
+    Keys,
+    Values,
+    /// `[key, value]` arrays
+    Entries,
+}
+
+/// The state of a `Map` or `Set` iterator, such as `map.entries()`.
+#[derive(Debug)]
+pub struct CollectionIterator {
+    /// The `Map` or `Set`, or `None` once the iterator is exhausted
+    pub collection: Option<ObjectId>,
+    /// Whether the collection is a `Map` rather than a `Set`
+    pub of_map: bool,
+    pub cursor: Rc<Cell<usize>>,
+    pub kind: IterationKind,
+}
+
+impl Interpreter {
+    /// Creates an iterator over the `Map` or `Set` `collection`.
+    pub(super) fn create_collection_iterator(&mut self, collection: ObjectId, kind: IterationKind) -> ObjectId {
+        let (cursor, of_map) = match &mut self.heap.get_mut(collection).kind {
+            ObjectKind::Map(table) => (table.cursor(), true),
+            ObjectKind::Set(table) => (table.cursor(), false),
+            _ => unreachable!("collection iterators walk maps and sets"),
+        };
+        let prototype = if of_map { self.intrinsics.map_iterator_prototype } else { self.intrinsics.set_iterator_prototype };
+        let iterator = CollectionIterator { collection: Some(collection), of_map, cursor, kind };
+        self.heap.allocate(Object::new(ObjectKind::CollectionIterator(iterator), Some(prototype)))
+    }
+
+    /// Advances the collection iterator `id`, returning `None` when it is
+    /// done.
+    pub(super) fn collection_iterator_next(&mut self, id: ObjectId) -> Option<Value> {
+        let ObjectKind::CollectionIterator(iterator) = &self.heap.get(id).kind else {
+            unreachable!("not a collection iterator")
+        };
+        let kind = iterator.kind;
+        let cursor = Rc::clone(&iterator.cursor);
+        let entry = iterator.collection.and_then(|collection| match &self.heap.get(collection).kind {
+            ObjectKind::Map(table) | ObjectKind::Set(table) => table.next_entry(&cursor),
+            _ => None,
+        });
+        let Some((key, value)) = entry else {
+            if let ObjectKind::CollectionIterator(iterator) = &mut self.heap.get_mut(id).kind {
+                iterator.collection = None;
+            }
+            return None;
+        };
+        Some(match kind {
+            IterationKind::Keys => key,
+            IterationKind::Values => value,
+            IterationKind::Entries => self.create_array(vec![key, value]),
+        })
+    }
+}
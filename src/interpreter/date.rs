@@ -0,0 +1,531 @@
+//! Time values: the calendar arithmetic `Date` is built on, the local time
+//! zone, and the date formats `Date` parses and prints.
+//!
+//! Like the clock timers run on, the time zone is the embedder's to set:
+//! it is UTC until `set_time_zone` or `use_system_time_zone` is called, so
+//! that scripts print the same dates on every machine.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::Interpreter;
+
+pub(super) const MS_PER_DAY: f64 = 86400000.0;
+const MS_PER_HOUR: f64 = 3600000.0;
+const MS_PER_MINUTE: f64 = 60000.0;
+
+/// Time values are limited to 100,000,000 days either side of the epoch.
+const MAX_TIME: f64 = 8.64e15;
+
+pub(super) const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+pub(super) const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// The calendar fields of a finite time value. Months count from 0 and
+/// weekdays from Sunday, as in the `Date` getters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct Fields {
+    pub(super) year: f64,
+    pub(super) month: f64,
+    pub(super) date: f64,
+    pub(super) hours: f64,
+    pub(super) minutes: f64,
+    pub(super) seconds: f64,
+    pub(super) milliseconds: f64,
+    pub(super) weekday: f64,
+}
+
+impl Fields {
+    pub(super) fn of(t: f64) -> Self {
+        let day = (t / MS_PER_DAY).floor();
+        let time = t.rem_euclid(MS_PER_DAY) + 0.0;
+        let (year, month, date) = civil_from_days(day as i64);
+        Self {
+            year: year as f64,
+            month: (month - 1) as f64,
+            date: date as f64,
+            hours: (time / MS_PER_HOUR).floor(),
+            minutes: (time / MS_PER_MINUTE).floor() % 60.0,
+            seconds: (time / 1000.0).floor() % 60.0,
+            milliseconds: time % 1000.0,
+            weekday: (day + 4.0).rem_euclid(7.0),
+        }
+    }
+
+    /// The fields in the order the constructor and the setters take them.
+    pub(super) fn to_array(self) -> [f64; 7] {
+        [self.year, self.month, self.date, self.hours, self.minutes, self.seconds, self.milliseconds]
+    }
+}
+
+/// The `MakeDay` abstract operation: the day number of `date` in `month`
+/// of `year`, where the month may be out of range.
+pub(super) fn make_day(year: f64, month: f64, date: f64) -> f64 {
+    if !year.is_finite() || !month.is_finite() || !date.is_finite() {
+        return f64::NAN;
+    }
+    let (year, month, date) = (year.trunc(), month.trunc(), date.trunc());
+    let year = year + (month / 12.0).floor();
+    // Far enough out that the result is clipped anyway
+    if year.abs() > 400000.0 {
+        return f64::NAN;
+    }
+    let month = month.rem_euclid(12.0);
+    days_from_civil(year as i64, month as u32 + 1, 1) as f64 + date - 1.0
+}
+
+/// The `MakeTime` abstract operation.
+pub(super) fn make_time(hours: f64, minutes: f64, seconds: f64, milliseconds: f64) -> f64 {
+    if ![hours, minutes, seconds, milliseconds].iter().all(|n| n.is_finite()) {
+        return f64::NAN;
+    }
+    hours.trunc() * MS_PER_HOUR + minutes.trunc() * MS_PER_MINUTE + seconds.trunc() * 1000.0 + milliseconds.trunc()
+}
+
+/// The `MakeDate` abstract operation.
+pub(super) fn make_date(day: f64, time: f64) -> f64 {
+    day * MS_PER_DAY + time
+}
+
+/// Builds a time value from the fields the constructor and `Date.UTC`
+/// take, with the same defaults.
+pub(super) fn from_fields(fields: [f64; 7]) -> f64 {
+    let [year, month, date, hours, minutes, seconds, milliseconds] = fields;
+    make_date(make_day(year, month, date), make_time(hours, minutes, seconds, milliseconds))
+}
+
+/// The `TimeClip` abstract operation: NaN for times out of range.
+pub(super) fn time_clip(t: f64) -> f64 {
+    if !t.is_finite() || t.abs() > MAX_TIME {
+        return f64::NAN;
+    }
+    t.trunc() + 0.0
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar,
+/// after Howard Hinnant's algorithm.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// The inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    (days_from_civil(year + (month == 12) as i64, month % 12 + 1, 1) - days_from_civil(year, month, 1)) as u32
+}
+
+impl Interpreter {
+    /// `Date.now()`: the Unix time in milliseconds.
+    pub(super) fn current_time(&self) -> f64 {
+        let timers = &self.event_loop.timers;
+        timers.epoch + timers.clock.now() as f64
+    }
+
+    /// Sets the time `Date.now()` reports, in milliseconds since the Unix
+    /// epoch. It moves on from there with the clock timers run on.
+    pub fn set_current_time(&mut self, time: f64) {
+        let timers = &mut self.event_loop.timers;
+        timers.epoch = time - timers.clock.now() as f64;
+    }
+
+    /// Sets the time zone dates are shown in, from an IANA name such as
+    /// `Europe/Paris` or a POSIX `TZ` string such as `EST5EDT,M3.2.0,M11.1.0`.
+    /// IANA names are looked up in the host's zone files, usually under
+    /// `/usr/share/zoneinfo`, and are an error where those are missing.
+    pub fn set_time_zone(&mut self, name: &str) -> Result<(), String> {
+        self.time_zone = tz::TimeZone::from_posix_tz(name).map_err(|error| format!("Invalid time zone {}: {}", name, error))?;
+        Ok(())
+    }
+
+    /// Switches to the system's time zone, taken from `TZ` if it is set.
+    /// Falls back to UTC if the zone cannot be read.
+    pub fn use_system_time_zone(&mut self) {
+        let zone = match std::env::var("TZ") {
+            Ok(name) => tz::TimeZone::from_posix_tz(&name),
+            Err(_) => tz::TimeZone::local(),
+        };
+        self.time_zone = zone.unwrap_or_else(|_| tz::TimeZone::utc());
+    }
+
+    /// The local time type in effect at the time value `t`.
+    fn local_time_type(&self, t: f64) -> Option<&tz::LocalTimeType> {
+        self.time_zone.find_local_time_type((t / 1000.0).floor() as i64).ok()
+    }
+
+    /// How far local time is ahead of UTC at the time value `t`, in
+    /// milliseconds.
+    pub(super) fn time_zone_offset(&self, t: f64) -> f64 {
+        self.local_time_type(t).map_or(0.0, |local| local.ut_offset() as f64 * 1000.0)
+    }
+
+    /// The `LocalTime` abstract operation.
+    pub(super) fn local_time(&self, t: f64) -> f64 {
+        t + self.time_zone_offset(t)
+    }
+
+    /// The `UTC` abstract operation: the time value of a local time. A
+    /// local time repeated when the clocks go back is taken the first
+    /// time round; one skipped when they go forward is read with the
+    /// offset from before the change.
+    pub(super) fn utc_time(&self, local: f64) -> f64 {
+        if !local.is_finite() {
+            return f64::NAN;
+        }
+        let before = self.time_zone_offset(local - MS_PER_DAY);
+        let after = self.time_zone_offset(local + MS_PER_DAY);
+        let candidates = [local - before, local - after];
+        candidates
+            .into_iter()
+            .filter(|&t| self.local_time(t) == local)
+            .reduce(f64::min)
+            .unwrap_or(local - before)
+    }
+
+    /// The name of the local time zone at `t`, as `Date.prototype.toString`
+    /// shows it in parentheses.
+    fn time_zone_name(&self, t: f64) -> String {
+        match self.local_time_type(t).map(|local| local.time_zone_designation()) {
+            None | Some("UTC") | Some("") => "Coordinated Universal Time".to_string(),
+            Some(name) if name.starts_with(['+', '-']) => format!("GMT{}", format_offset(self.time_zone_offset(t), ":")),
+            Some(name) => name.to_string(),
+        }
+    }
+
+    /// `Date.prototype.toString`, `toDateString` and `toTimeString`.
+    pub(super) fn format_date(&self, t: f64, date: bool, time: bool) -> String {
+        if t.is_nan() {
+            return "Invalid Date".to_string();
+        }
+        let fields = Fields::of(self.local_time(t));
+        let mut parts = Vec::new();
+        if date {
+            parts.push(format!(
+                "{} {} {:02} {}",
+                WEEKDAYS[fields.weekday as usize],
+                MONTHS[fields.month as usize],
+                fields.date,
+                format_year(fields.year)
+            ));
+        }
+        if time {
+            parts.push(format!(
+                "{:02}:{:02}:{:02} GMT{} ({})",
+                fields.hours,
+                fields.minutes,
+                fields.seconds,
+                format_offset(self.time_zone_offset(t), ""),
+                self.time_zone_name(t)
+            ));
+        }
+        parts.join(" ")
+    }
+
+    /// `toLocaleString`, `toLocaleDateString` and `toLocaleTimeString` in
+    /// the `en-US` locale.
+    pub(super) fn format_locale_date(&self, t: f64, date: bool, time: bool) -> String {
+        if t.is_nan() {
+            return "Invalid Date".to_string();
+        }
+        let fields = Fields::of(self.local_time(t));
+        let mut parts = Vec::new();
+        if date {
+            parts.push(format!("{}/{}/{}", fields.month + 1.0, fields.date, fields.year));
+        }
+        if time {
+            let hours = if fields.hours % 12.0 == 0.0 { 12.0 } else { fields.hours % 12.0 };
+            let period = if fields.hours < 12.0 { "AM" } else { "PM" };
+            parts.push(format!("{}:{:02}:{:02} {}", hours, fields.minutes, fields.seconds, period));
+        }
+        parts.join(", ")
+    }
+
+    /// `Date.parse`: ISO 8601 strings as the specification defines them,
+    /// and otherwise the formats `toString` and `toUTCString` print and
+    /// RFC 2822 dates. NaN for anything else. Times without an offset are
+    /// local, except for ISO dates without a time.
+    pub(super) fn parse_date(&self, s: &str) -> f64 {
+        let s = s.trim();
+        let Some((fields, offset)) = parse_iso(s).or_else(|| parse_legacy(s)) else {
+            return f64::NAN;
+        };
+        let t = from_fields(fields);
+        match offset {
+            Some(offset) => time_clip(t - offset),
+            None => time_clip(self.utc_time(t)),
+        }
+    }
+}
+
+/// The current Unix time in milliseconds, for the real clock.
+pub(super) fn system_time() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |elapsed| elapsed.as_millis() as f64)
+}
+
+/// Years as `Date.prototype.toString` prints them: four digits at least,
+/// with a sign if negative.
+fn format_year(year: f64) -> String {
+    if year < 0.0 {
+        format!("-{:04}", -year)
+    } else {
+        format!("{:04}", year)
+    }
+}
+
+/// An offset in milliseconds as `+hhmm`, with `separator` between the
+/// hours and minutes.
+fn format_offset(offset: f64, separator: &str) -> String {
+    let sign = if offset < 0.0 { '-' } else { '+' };
+    let minutes = (offset.abs() / MS_PER_MINUTE).round();
+    format!("{}{:02}{}{:02}", sign, (minutes / 60.0).floor(), separator, minutes % 60.0)
+}
+
+/// `Date.prototype.toISOString` for a finite time value.
+pub(super) fn format_iso(t: f64) -> String {
+    let fields = Fields::of(t);
+    let year = if (0.0..=9999.0).contains(&fields.year) {
+        format!("{:04}", fields.year)
+    } else {
+        format!("{}{:06}", if fields.year < 0.0 { '-' } else { '+' }, fields.year.abs())
+    };
+    format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        fields.month + 1.0,
+        fields.date,
+        fields.hours,
+        fields.minutes,
+        fields.seconds,
+        fields.milliseconds
+    )
+}
+
+/// `Date.prototype.toUTCString` for a finite time value.
+pub(super) fn format_utc(t: f64) -> String {
+    let fields = Fields::of(t);
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[fields.weekday as usize],
+        fields.date,
+        MONTHS[fields.month as usize],
+        format_year(fields.year),
+        fields.hours,
+        fields.minutes,
+        fields.seconds
+    )
+}
+
+/// Reads the fixed-width number at the start of `s`, advancing past it.
+fn take_digits(s: &mut &str, count: usize) -> Option<f64> {
+    let digits = s.get(..count).filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))?;
+    *s = &s[count..];
+    digits.parse().ok()
+}
+
+/// Consumes `prefix` from the start of `s` if it is there.
+fn take(s: &mut &str, prefix: char) -> bool {
+    match s.strip_prefix(prefix) {
+        Some(rest) => {
+            *s = rest;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Parses the date time string format: `YYYY-MM-DDTHH:mm:ss.sssZ` and its
+/// shorter forms, with `±YYYYYY` for extended years. Returns the fields
+/// and the UTC offset in milliseconds, `None` for local time.
+fn parse_iso(s: &str) -> Option<([f64; 7], Option<f64>)> {
+    let mut rest = s;
+    let year = if rest.starts_with(['+', '-']) {
+        let negative = take(&mut rest, '-') || !take(&mut rest, '+');
+        let year = take_digits(&mut rest, 6)?;
+        if negative && year == 0.0 {
+            return None;
+        }
+        if negative { -year } else { year }
+    } else {
+        take_digits(&mut rest, 4)?
+    };
+    let mut fields = [year, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+    if take(&mut rest, '-') {
+        fields[1] = take_digits(&mut rest, 2)? - 1.0;
+        if take(&mut rest, '-') {
+            fields[2] = take_digits(&mut rest, 2)?;
+        }
+    }
+    if !(0.0..12.0).contains(&fields[1]) || fields[2] < 1.0 || fields[2] > days_in_month(year as i64, fields[1] as u32 + 1) as f64 {
+        return None;
+    }
+    if rest.is_empty() {
+        return Some((fields, Some(0.0)));
+    }
+
+    if !(take(&mut rest, 'T') || take(&mut rest, 't') || take(&mut rest, ' ')) {
+        return None;
+    }
+    fields[3] = take_digits(&mut rest, 2)?;
+    if !take(&mut rest, ':') {
+        return None;
+    }
+    fields[4] = take_digits(&mut rest, 2)?;
+    if take(&mut rest, ':') {
+        fields[5] = take_digits(&mut rest, 2)?;
+        if take(&mut rest, '.') || take(&mut rest, ',') {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits == 0 {
+                return None;
+            }
+            // Only milliseconds are kept
+            let fraction: String = rest[..digits].chars().chain("00".chars()).take(3).collect();
+            fields[6] = fraction.parse().ok()?;
+            rest = &rest[digits..];
+        }
+    }
+    let valid_time = fields[3] < 24.0 && fields[4] < 60.0 && fields[5] < 60.0;
+    let midnight = fields[3] == 24.0 && fields[4] == 0.0 && fields[5] == 0.0 && fields[6] == 0.0;
+    if !valid_time && !midnight {
+        return None;
+    }
+
+    let offset = if rest.is_empty() {
+        None
+    } else if take(&mut rest, 'Z') || take(&mut rest, 'z') {
+        Some(0.0)
+    } else {
+        let sign = if take(&mut rest, '+') { 1.0 } else if take(&mut rest, '-') { -1.0 } else { return None };
+        let hours = take_digits(&mut rest, 2)?;
+        take(&mut rest, ':');
+        let minutes = take_digits(&mut rest, 2)?;
+        if hours > 23.0 || minutes > 59.0 {
+            return None;
+        }
+        Some(sign * (hours * MS_PER_HOUR + minutes * MS_PER_MINUTE))
+    };
+    rest.is_empty().then_some((fields, offset))
+}
+
+/// The offsets of the North American zones RFC 2822 names, in hours.
+fn zone_offset(name: &str) -> Option<f64> {
+    Some(match name {
+        "UT" | "UTC" | "GMT" | "Z" => 0.0,
+        "EDT" => -4.0,
+        "EST" | "CDT" => -5.0,
+        "CST" | "MDT" => -6.0,
+        "MST" | "PDT" => -7.0,
+        "PST" => -8.0,
+        _ => return None,
+    })
+}
+
+/// Parses dates in the looser formats engines accept besides ISO 8601:
+/// RFC 2822 (`Tue, 1 Nov 2016 12:34:56 +0100`), what `toString` and
+/// `toUTCString` print, and US-style `11/1/2016` dates. Words in
+/// parentheses and weekday names are ignored.
+fn parse_legacy(s: &str) -> Option<([f64; 7], Option<f64>)> {
+    let mut text = String::new();
+    let mut depth = 0;
+    for c in s.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            _ if depth > 0 => {}
+            ',' => text.push(' '),
+            // A sign after a word or time starts a new token, as in `GMT+0100`
+            '+' | '-' if text.ends_with(|c: char| c.is_ascii_alphabetic()) => {
+                text.push(' ');
+                text.push(c);
+            }
+            _ => text.push(c),
+        }
+    }
+
+    let (mut year, mut month, mut day) = (None, None, None);
+    let mut time = [0.0; 4];
+    let mut offset = None;
+    let mut has_time = false;
+    let mut period = None;
+    for token in text.split_whitespace() {
+        let lower = token.to_ascii_lowercase();
+        if token.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            if let Some(index) = MONTHS.iter().position(|name| lower.starts_with(&name.to_ascii_lowercase())) {
+                month = Some(index as f64);
+            } else if lower == "am" || lower == "pm" {
+                period = Some(lower);
+            } else if !WEEKDAYS.iter().any(|name| lower.starts_with(&name.to_ascii_lowercase())) {
+                offset = Some(zone_offset(&token.to_ascii_uppercase())? * MS_PER_HOUR);
+            }
+        } else if token.contains(':') && !has_time {
+            has_time = true;
+            let mut parts = token.split(':');
+            for (i, part) in parts.by_ref().take(3).enumerate() {
+                let (whole, fraction) = part.split_once('.').unwrap_or((part, ""));
+                time[i] = whole.parse().ok()?;
+                if i == 2 && !fraction.is_empty() {
+                    time[3] = fraction.chars().chain("00".chars()).take(3).collect::<String>().parse().ok()?;
+                }
+            }
+            if parts.next().is_some() || time[0] > 24.0 || time[1] > 59.0 || time[2] > 59.0 {
+                return None;
+            }
+        } else if token.starts_with(['+', '-']) && (has_time || offset.is_some()) {
+            let sign = if token.starts_with('-') { -1.0 } else { 1.0 };
+            let digits: String = token[1..].chars().filter(|c| *c != ':').collect();
+            if !digits.bytes().all(|b| b.is_ascii_digit()) || !matches!(digits.len(), 2 | 4) {
+                return None;
+            }
+            let hours: f64 = digits[..2].parse().ok()?;
+            let minutes: f64 = digits.get(2..).filter(|m| !m.is_empty()).map_or(Some(0.0), |m| m.parse().ok())?;
+            offset = Some(sign * (hours * MS_PER_HOUR + minutes * MS_PER_MINUTE));
+        } else if token.contains('/') {
+            let parts: Vec<&str> = token.split('/').collect();
+            let [m, d, y] = parts[..] else { return None };
+            month = Some(m.parse::<f64>().ok()? - 1.0);
+            day = Some(d.parse().ok()?);
+            year = Some(y.parse().ok()?);
+        } else {
+            let n: f64 = token.parse().ok().filter(|_| token.bytes().all(|b| b.is_ascii_digit() || b == b'-'))?;
+            if day.is_none() && token.len() <= 2 && n <= 31.0 {
+                day = Some(n);
+            } else if year.is_none() {
+                // Two-digit years are 1950 to 2049
+                year = Some(match (token.len(), n) {
+                    (1 | 2, n) if n < 50.0 => n + 2000.0,
+                    (1 | 2, n) => n + 1900.0,
+                    _ => n,
+                });
+            } else {
+                return None;
+            }
+        }
+    }
+
+    let (year, month, day) = (year?, month?, day?);
+    if !(0.0..12.0).contains(&month) || !(1.0..=31.0).contains(&day) {
+        return None;
+    }
+    let mut hours = time[0];
+    match period.as_deref() {
+        Some(_) if hours > 12.0 => return None,
+        Some("am") if hours == 12.0 => hours = 0.0,
+        Some("pm") if hours < 12.0 => hours += 12.0,
+        _ => {}
+    }
+    Some(([year, month, day, hours, time[1], time[2], time[3]], offset))
+}
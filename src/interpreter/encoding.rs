@@ -0,0 +1,137 @@
+//! The character encodings `TextDecoder` understands, and the decoding
+//! state it keeps between streaming calls.
+
+use super::type_error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Encoding {
+    /// The encoding a label names, ignoring case and surrounding
+    /// whitespace, as the Encoding Standard's "get an encoding" does.
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim_matches(|c: char| c.is_ascii_whitespace()).to_ascii_lowercase().as_str() {
+            "unicode-1-1-utf-8" | "unicode11utf8" | "unicode20utf8" | "utf-8" | "utf8" | "x-unicode20utf8" => Some(Encoding::Utf8),
+            "csunicode" | "iso-10646-ucs-2" | "ucs-2" | "unicode" | "unicodefeff" | "utf-16" | "utf-16le" => Some(Encoding::Utf16Le),
+            "unicodefffe" | "utf-16be" => Some(Encoding::Utf16Be),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+        }
+    }
+}
+
+/// The state of a `TextDecoder`.
+#[derive(Debug)]
+pub struct TextDecoder {
+    pub encoding: Encoding,
+    /// Throw on malformed input instead of substituting U+FFFD
+    pub fatal: bool,
+    /// Keep a leading byte order mark in the output
+    pub ignore_bom: bool,
+    /// The start of a character split across streaming calls
+    pending: Vec<u8>,
+    /// Whether the stream has produced output, so that a byte order mark
+    /// is no longer at its start
+    started: bool,
+}
+
+impl TextDecoder {
+    pub fn new(encoding: Encoding, fatal: bool, ignore_bom: bool) -> Self {
+        Self { encoding, fatal, ignore_bom, pending: Vec::new(), started: false }
+    }
+
+    /// Decodes `input` after any bytes held back by the previous call.
+    /// When `stream` is set, an incomplete character at the end is held
+    /// back for the next call; otherwise the stream ends here.
+    pub fn decode(&mut self, input: &[u8], stream: bool) -> Result<String, String> {
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(input);
+        if stream {
+            let incomplete = self.incomplete_tail(&bytes);
+            self.pending = bytes.split_off(bytes.len() - incomplete);
+        }
+
+        let (mut output, malformed) = match self.encoding {
+            Encoding::Utf8 => match std::str::from_utf8(&bytes) {
+                Ok(text) => (text.to_string(), false),
+                Err(_) => (String::from_utf8_lossy(&bytes).into_owned(), true),
+            },
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| match self.encoding {
+                        Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+                        _ => u16::from_le_bytes([pair[0], pair[1]]),
+                    })
+                    .collect();
+                let odd_byte = bytes.len() % 2 == 1;
+                match String::from_utf16(&units) {
+                    Ok(text) if !odd_byte => (text, false),
+                    _ => {
+                        let mut text = String::from_utf16_lossy(&units);
+                        if odd_byte {
+                            text.push(char::REPLACEMENT_CHARACTER);
+                        }
+                        (text, true)
+                    }
+                }
+            }
+        };
+        if malformed && self.fatal {
+            self.reset();
+            return Err(type_error(format!("The encoded data was not valid for encoding {}", self.encoding.name())));
+        }
+
+        if !self.started && !output.is_empty() {
+            self.started = true;
+            if !self.ignore_bom && output.starts_with('\u{FEFF}') {
+                output.remove(0);
+            }
+        }
+        if !stream {
+            self.reset();
+        }
+        Ok(output)
+    }
+
+    fn reset(&mut self) {
+        self.pending.clear();
+        self.started = false;
+    }
+
+    /// How many bytes at the end of `bytes` start a character that the
+    /// next call may complete.
+    fn incomplete_tail(&self, bytes: &[u8]) -> usize {
+        match self.encoding {
+            Encoding::Utf8 => (1..=3.min(bytes.len()))
+                .find(|&length| {
+                    // An error without a length is input that ends too soon
+                    matches!(std::str::from_utf8(&bytes[bytes.len() - length..]), Err(error) if error.valid_up_to() == 0 && error.error_len().is_none())
+                })
+                .unwrap_or(0),
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let odd = bytes.len() % 2;
+                let last_unit = bytes.len().checked_sub(odd + 2).map(|start| match self.encoding {
+                    Encoding::Utf16Be => u16::from_be_bytes([bytes[start], bytes[start + 1]]),
+                    _ => u16::from_le_bytes([bytes[start], bytes[start + 1]]),
+                });
+                // A lead surrogate waits for the trail surrogate after it
+                match last_unit {
+                    Some(0xD800..=0xDBFF) => odd + 2,
+                    _ => odd,
+                }
+            }
+        }
+    }
+}
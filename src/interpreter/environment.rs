@@ -0,0 +1,161 @@
+//! Lexical environments: the chain of scopes variables are resolved through
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::{ObjectId, Value};
+use crate::parser::VariableKind;
+
+pub type EnvRef = Rc<RefCell<Environment>>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScopeKind {
+    Global,
+    /// A function body: the target of `var` declarations
+    Function,
+    /// A block, which only holds `let`, `const` and other lexical bindings
+    Block,
+}
+
+/// A variable slot. `value` is `None` while a `let`/`const` binding is in
+/// its temporal dead zone.
+#[derive(Clone, Debug)]
+pub struct Binding {
+    pub value: Option<Value>,
+    pub kind: VariableKind,
+}
+
+/// What the scope of a non-arrow function call knows about the call.
+#[derive(Clone, Debug)]
+pub struct FunctionContext {
+    /// `None` in a derived class constructor until `super()` returns
+    pub this: Option<Value>,
+    /// The function being run, whose prototype `super()` constructs
+    pub callee: Option<ObjectId>,
+    /// The object a method was defined on; `super.x` looks `x` up on its
+    /// prototype
+    pub home_object: Option<ObjectId>,
+    /// The constructor `new` was applied to, or `undefined` for a call
+    pub new_target: Value,
+}
+
+#[derive(Debug)]
+pub struct Environment {
+    bindings: HashMap<String, Binding>,
+    parent: Option<EnvRef>,
+    kind: ScopeKind,
+    /// Set on the scope of a non-arrow function call
+    function: Option<FunctionContext>,
+}
+
+impl Environment {
+    pub fn new_global() -> EnvRef {
+        Rc::new(RefCell::new(Self {
+            bindings: HashMap::new(),
+            parent: None,
+            kind: ScopeKind::Global,
+            function: None,
+        }))
+    }
+
+    pub fn new_child(parent: &EnvRef, kind: ScopeKind) -> EnvRef {
+        Rc::new(RefCell::new(Self {
+            bindings: HashMap::new(),
+            parent: Some(Rc::clone(parent)),
+            kind,
+            function: None,
+        }))
+    }
+
+    /// Creates the scope for a non-arrow function call.
+    pub fn new_function(parent: &EnvRef, context: FunctionContext) -> EnvRef {
+        let env = Self::new_child(parent, ScopeKind::Function);
+        env.borrow_mut().function = Some(context);
+        env
+    }
+
+    pub fn bindings(&self) -> &HashMap<String, Binding> {
+        &self.bindings
+    }
+
+    pub fn parent(&self) -> Option<&EnvRef> {
+        self.parent.as_ref()
+    }
+
+    /// The call this scope belongs to, if it is a function scope.
+    pub fn function(&self) -> Option<&FunctionContext> {
+        self.function.as_ref()
+    }
+
+    pub fn get_own(&self, name: &str) -> Option<&Binding> {
+        self.bindings.get(name)
+    }
+
+    pub fn get_own_mut(&mut self, name: &str) -> Option<&mut Binding> {
+        self.bindings.get_mut(name)
+    }
+
+    /// Creates or replaces a binding in this scope.
+    pub fn insert(&mut self, name: String, binding: Binding) {
+        self.bindings.insert(name, binding);
+    }
+
+    /// Creates a sibling of `env` holding copies of its bindings, used for
+    /// the per-iteration scopes of `for (let ...)` loops.
+    pub fn copy_scope(env: &EnvRef) -> EnvRef {
+        let env = env.borrow();
+        Rc::new(RefCell::new(Self {
+            bindings: env.bindings.clone(),
+            parent: env.parent.clone(),
+            kind: env.kind,
+            function: env.function.clone(),
+        }))
+    }
+
+    /// Walks outwards from `env` to the scope that declares `name`.
+    pub fn resolve(env: &EnvRef, name: &str) -> Option<EnvRef> {
+        let mut current = Rc::clone(env);
+        loop {
+            if current.borrow().bindings.contains_key(name) {
+                return Some(current);
+            }
+            let parent = current.borrow().parent.clone()?;
+            current = parent;
+        }
+    }
+
+    /// The nearest non-arrow function scope, whose `this` and `super` arrow
+    /// functions share. `None` at the top level.
+    pub fn function_scope(env: &EnvRef) -> Option<EnvRef> {
+        let mut current = Rc::clone(env);
+        loop {
+            if current.borrow().function.is_some() {
+                return Some(current);
+            }
+            let parent = current.borrow().parent.clone()?;
+            current = parent;
+        }
+    }
+
+    /// The context of the nearest non-arrow function scope.
+    pub fn function_context(env: &EnvRef) -> Option<FunctionContext> {
+        Self::function_scope(env).and_then(|scope| scope.borrow().function.clone())
+    }
+
+    pub fn function_mut(&mut self) -> Option<&mut FunctionContext> {
+        self.function.as_mut()
+    }
+
+    /// The nearest function or global scope, where `var` bindings live.
+    pub fn var_scope(env: &EnvRef) -> EnvRef {
+        let mut current = Rc::clone(env);
+        loop {
+            if current.borrow().kind != ScopeKind::Block {
+                return current;
+            }
+            let parent = current.borrow().parent.clone().expect("block scope without parent");
+            current = parent;
+        }
+    }
+}
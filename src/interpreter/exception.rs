@@ -0,0 +1,209 @@
+//! Exceptions: `throw`, `try` statements, error objects and the stack
+//! traces they carry.
+//!
+//! Evaluation reports a thrown exception as `Err(message)`, the text shown
+//! when nothing catches it. The thrown value itself is kept aside in
+//! `Interpreter::exception` so that `catch` receives exactly what was
+//! thrown; errors raised by the interpreter, which only exist as a message,
+//! become error objects the first time they need to be one.
+
+use super::environment::{Environment, ScopeKind};
+use super::object::{Attributes, Object, ObjectId, ObjectKind, Property, PropertyKey, PropertyValue};
+use super::{Completion, Interpreter, Value};
+use crate::lexer::Location;
+use crate::parser::{CatchClause, Statement, VariableKind};
+
+/// Frames listed in a `stack`, like V8's default `Error.stackTraceLimit`.
+const STACK_TRACE_LIMIT: usize = 10;
+
+/// The message of the `Err` a generator's `return` method unwinds the
+/// suspended body with. `finally` blocks run on the way out, but `catch`
+/// clauses do not.
+const GENERATOR_RETURN: &str = "generator return";
+
+/// A thrown value on its way to a `catch`, along with the message the
+/// `Err` carrying it holds.
+pub(super) struct Exception {
+    message: String,
+    pub(super) value: Value,
+    /// Whether this is a generator's `return` unwinding its body, whose
+    /// value is the generator's result, rather than anything thrown
+    returning: bool,
+}
+
+/// A function call in progress, as listed in stack traces. The bottom frame
+/// is the top-level script.
+#[derive(Default)]
+pub(super) struct StackFrame {
+    /// Empty for the top level and anonymous functions
+    pub function: String,
+    pub constructing: bool,
+    /// Where this frame is evaluating: the call it is making, or the last
+    /// call, property access, operator or variable read it reached
+    pub location: Option<Location>,
+}
+
+impl Interpreter {
+    /// Starts throwing `value`, returning the error to propagate.
+    pub(super) fn throw_value(&mut self, value: Value) -> String {
+        let message = match &value {
+            Value::Object(id) if matches!(self.heap.get(*id).kind, ObjectKind::Error) => self.error_summary(*id),
+            Value::Object(_) | Value::Function(_) => "Uncaught #<Object>".to_string(),
+            other => format!("Uncaught {}", other.to_js_string()),
+        };
+        self.exception = Some(Exception { message: message.clone(), value, returning: false });
+        message
+    }
+
+    /// Starts unwinding a generator body for its `return` method, which
+    /// completes the generator with `value`.
+    pub(super) fn start_generator_return(&mut self, value: Value) -> String {
+        self.exception = Some(Exception { message: GENERATOR_RETURN.to_string(), value, returning: true });
+        GENERATOR_RETURN.to_string()
+    }
+
+    /// Whether `message` is unwinding a generator body rather than
+    /// reporting an exception. An exception whose message merely reads the
+    /// same is still an exception.
+    pub(super) fn is_generator_return(&self, message: &str) -> bool {
+        self.exception.as_ref().is_some_and(|exception| exception.returning && exception.message == message)
+    }
+
+    /// Makes sure the exception `message` reports has a value, creating an
+    /// error object for errors raised by the interpreter itself. Called
+    /// while the frame that raised it is still on the stack.
+    pub(super) fn materialize_exception(&mut self, message: &str) {
+        if self.exception.as_ref().is_some_and(|exception| exception.message == message) {
+            return;
+        }
+        let (prototype, text) = match message.split_once(": ") {
+            Some(("Error", text)) => (self.intrinsics.error_prototype, text),
+            Some(("TypeError", text)) => (self.intrinsics.type_error_prototype, text),
+            Some(("RangeError", text)) => (self.intrinsics.range_error_prototype, text),
+            Some(("ReferenceError", text)) => (self.intrinsics.reference_error_prototype, text),
+            Some(("SyntaxError", text)) => (self.intrinsics.syntax_error_prototype, text),
+            _ => (self.intrinsics.error_prototype, message),
+        };
+        let id = self.create_error(prototype, Some(text.to_string()));
+        self.exception = Some(Exception { message: message.to_string(), value: Value::Object(id), returning: false });
+    }
+
+    /// Stops the exception `message` reports, returning the thrown value.
+    pub(super) fn catch_exception(&mut self, message: &str) -> Value {
+        self.materialize_exception(message);
+        self.exception.take().expect("exception was just materialized").value
+    }
+
+    /// Creates an error object inheriting from `prototype`, capturing the
+    /// current stack.
+    pub(super) fn create_error(&mut self, prototype: ObjectId, message: Option<String>) -> ObjectId {
+        let id = self.heap.allocate(Object::new(ObjectKind::Error, Some(prototype)));
+        if let Some(message) = message {
+            self.heap.define_property(id, PropertyKey::from("message"), Property::data(Value::String(message.into()), Attributes::HIDDEN));
+        }
+        let stack = self.stack_trace(&self.error_summary(id));
+        self.heap.define_property(id, PropertyKey::from("stack"), Property::data(Value::String(stack.into()), Attributes::HIDDEN));
+        id
+    }
+
+    /// `name: message` for an error, as `Error.prototype.toString` would
+    /// give it but without running any getters.
+    pub(super) fn error_summary(&self, id: ObjectId) -> String {
+        let field = |key: &str, default: &str| match self.heap.find_property(id, &PropertyKey::from(key)) {
+            Some(Property { value: PropertyValue::Data(Value::Undefined), .. }) | None => default.to_string(),
+            Some(Property { value: PropertyValue::Data(value), .. }) => value.to_js_string(),
+            Some(_) => String::new(),
+        };
+        let name = field("name", "Error");
+        let message = field("message", "");
+        match (name.is_empty(), message.is_empty()) {
+            (true, _) => message,
+            (false, true) => name,
+            (false, false) => format!("{}: {}", name, message),
+        }
+    }
+
+    /// `summary` followed by one line per active call, innermost first.
+    fn stack_trace(&self, summary: &str) -> String {
+        let mut trace = summary.to_string();
+        for frame in self.frames.iter().rev().take(STACK_TRACE_LIMIT) {
+            let place = match frame.location {
+                Some(location) => format!("<anonymous>:{}", location),
+                None => "<anonymous>".to_string(),
+            };
+            if frame.function.is_empty() {
+                trace.push_str(&format!("\n    at {}", place));
+            } else {
+                let new = if frame.constructing { "new " } else { "" };
+                trace.push_str(&format!("\n    at {}{} ({})", new, frame.function, place));
+            }
+        }
+        trace
+    }
+
+    /// Records that the current frame is evaluating the expression at
+    /// `location`, which is where an error raised now is reported.
+    pub(super) fn set_location(&mut self, location: Location) {
+        // Code the parser synthesized, like a default constructor's
+        // `super(...args)`, has no location
+        if location.line == 0 {
+            return;
+        }
+        if let Some(frame) = self.frames.last_mut() {
+            frame.location = Some(location);
+        }
+    }
+
+    pub(super) fn exec_try(
+        &mut self,
+        block: &[Statement],
+        handler: Option<&CatchClause>,
+        finalizer: Option<&[Statement]>,
+    ) -> Result<Completion, String> {
+        let scope = Environment::new_child(&self.env, ScopeKind::Block);
+        let mut result = self.in_scope(scope, |interpreter| interpreter.exec_block(block));
+
+        // A generator's `return` only runs `finally` blocks on its way out
+        let catchable = result.as_ref().is_err_and(|message| !self.is_generator_return(message));
+        if let (Err(message), Some(handler), true) = (&result, handler, catchable) {
+            let value = self.catch_exception(message);
+            let scope = Environment::new_child(&self.env, ScopeKind::Block);
+            result = self.in_scope(scope, |interpreter| {
+                if let Some(param) = &handler.param {
+                    interpreter.bind_pattern(param, value, Some(VariableKind::Let))?;
+                }
+                interpreter.exec_block(&handler.body)
+            });
+        }
+
+        self.exec_finalizer(result, finalizer)
+    }
+
+    /// Runs the `finally` block of a `try` statement that completed with
+    /// `result`.
+    fn exec_finalizer(&mut self, result: Result<Completion, String>, finalizer: Option<&[Statement]>) -> Result<Completion, String> {
+        let Some(finalizer) = finalizer else {
+            return result;
+        };
+        // The finally block may throw and catch exceptions of its own, so
+        // the one still propagating is set aside while it runs
+        if let Err(message) = &result {
+            self.materialize_exception(message);
+        }
+        let pending = self.exception.take();
+        if let Some(exception) = &pending {
+            self.hold(&exception.value);
+        }
+        if let Ok(Completion::Normal(value) | Completion::Return(value)) = &result {
+            self.hold(value);
+        }
+        let scope = Environment::new_child(&self.env, ScopeKind::Block);
+        match self.in_scope(scope, |interpreter| interpreter.exec_block(finalizer))? {
+            Completion::Normal(_) => {
+                self.exception = pending;
+                result
+            }
+            abrupt => Ok(abrupt),
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! A mark-and-sweep garbage collector for the heap.
+//!
+//! Collections happen at safepoints: between the top-level statements of a
+//! program and the jobs that run after it, and at the start of each
+//! function call and loop iteration. Everything live is reachable from the
+//! interpreter there. Rust code that keeps an object in a local across
+//! evaluation roots it with `hold` until the statement, loop iteration or
+//! call it belongs to finishes, and newly allocated objects start out
+//! held. The scopes of callers are kept on a stack of their own.
+//!
+//! Built-in functions are held to the same rule, with their receiver and
+//! arguments held for them. The values they read from properties,
+//! iterators and calls come back held, so what they keep in locals stays
+//! alive while callbacks run and collect garbage.
+//!
+//! A suspended generator is paused in the middle of a statement with Rust
+//! locals on its own stack. What they hold is saved with the generator when
+//! it suspends, and set aside while the code that resumed it waits. Async
+//! function calls and scripts suspend the same way, and a script's
+//! statements run on such a stack too.
+
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use super::environment::EnvRef;
+use super::iteration::HelperOperation;
+use super::object::{ObjectId, ObjectKind, Property, PropertyValue};
+use super::promise::PromiseFunction;
+use super::{Interpreter, Value};
+
+/// Allocations between automatic collections.
+const COLLECTION_THRESHOLD: usize = 100_000;
+
+/// The objects found reachable so far, and those whose references are
+/// still to be followed.
+#[derive(Default)]
+struct Marker {
+    marked: HashSet<ObjectId>,
+    pending: Vec<ObjectId>,
+    /// Scopes already visited, by address
+    environments: HashSet<usize>,
+    /// Promise combinators already visited, by address
+    combinators: HashSet<usize>,
+}
+
+impl Marker {
+    fn mark(&mut self, id: ObjectId) {
+        if self.marked.insert(id) {
+            self.pending.push(id);
+        }
+    }
+
+    fn mark_value(&mut self, value: &Value) {
+        if let Value::Object(id) | Value::Function(id) = value {
+            self.mark(*id);
+        }
+    }
+
+    fn mark_property(&mut self, property: &Property) {
+        match &property.value {
+            PropertyValue::Data(value) => self.mark_value(value),
+            PropertyValue::Accessor { get, set } => {
+                for function in get.iter().chain(set) {
+                    self.mark_value(function);
+                }
+            }
+        }
+    }
+
+    /// Marks everything a scope and the scopes around it hold.
+    fn mark_environment(&mut self, env: &EnvRef) {
+        let mut current = Some(Rc::clone(env));
+        while let Some(env) = current {
+            if !self.environments.insert(Rc::as_ptr(&env) as usize) {
+                return;
+            }
+            let scope = env.borrow();
+            for binding in scope.bindings().values() {
+                if let Some(value) = &binding.value {
+                    self.mark_value(value);
+                }
+            }
+            if let Some(context) = scope.function() {
+                if let Some(this) = &context.this {
+                    self.mark_value(this);
+                }
+                for id in context.callee.iter().chain(&context.home_object) {
+                    self.mark(*id);
+                }
+                self.mark_value(&context.new_target);
+            }
+            current = scope.parent().cloned();
+        }
+    }
+}
+
+impl Interpreter {
+    /// Frees every object the program can no longer reach, and drops the
+    /// `WeakMap` and `WeakSet` entries whose keys were freed.
+    ///
+    /// Values the host got back from earlier calls to `run` are only kept
+    /// alive if the program can still reach them.
+    pub fn collect_garbage(&mut self) {
+        self.collect_garbage_with(&[]);
+    }
+
+    /// The number of objects on the heap.
+    pub fn live_objects(&self) -> usize {
+        self.heap.live_ids().count()
+    }
+
+    /// Collects garbage once enough has been allocated since the last
+    /// collection, unless a built-in function is running. `roots` are
+    /// values the caller still holds.
+    pub(super) fn maybe_collect_garbage(&mut self, roots: &[&Value]) {
+        if self.heap.allocations >= COLLECTION_THRESHOLD {
+            self.collect_garbage_with(roots);
+        }
+    }
+
+    /// Keeps the object `value` refers to alive until the statement, loop
+    /// iteration or function call being evaluated finishes.
+    pub(super) fn hold(&mut self, value: &Value) {
+        if let Value::Object(id) | Value::Function(id) = value {
+            self.heap.temporaries.push(*id);
+        }
+    }
+
+    /// Moves a loop on to its next iteration, releasing what the last one
+    /// held: everything held since `mark`.
+    pub(super) fn end_iteration(&mut self, mark: usize) {
+        self.heap.temporaries.truncate(mark);
+        self.maybe_collect_garbage(&[]);
+    }
+
+    fn collect_garbage_with(&mut self, roots: &[&Value]) {
+        let mut marker = Marker::default();
+        for id in self.heap.live_ids().take(self.builtin_objects) {
+            marker.mark(id);
+        }
+        marker.mark_environment(&self.global);
+        marker.mark_environment(&self.env);
+        for env in self.scopes.iter().chain(self.resumers.iter().flat_map(|resumer| std::iter::once(&resumer.env).chain(&resumer.scopes))) {
+            marker.mark_environment(env);
+        }
+        let held = self.exception.iter().map(|exception| &exception.value).chain(&self.last_value);
+        for value in held.chain(roots.iter().copied()) {
+            marker.mark_value(value);
+        }
+        let resumers = self.resumers.iter().flat_map(|resumer| std::iter::once(&resumer.generator).chain(&resumer.temporaries));
+        for id in self.heap.temporaries.iter().chain(resumers).chain(self.template_objects.values().map(|(_, id)| id)) {
+            marker.mark(*id);
+        }
+        for value in self.event_loop.references() {
+            marker.mark_value(&value);
+        }
+        self.mark_reachable(&mut marker);
+
+        let garbage: Vec<ObjectId> = self.heap.live_ids().filter(|id| !marker.marked.contains(id)).collect();
+        for id in garbage {
+            self.heap.free(id);
+        }
+        let live: Vec<ObjectId> = self.heap.live_ids().collect();
+        for id in live {
+            match &mut self.heap.get_mut(id).kind {
+                ObjectKind::WeakMap(entries) => entries.retain(|key, _| marker.marked.contains(key)),
+                ObjectKind::WeakSet(keys) => keys.retain(|key| marker.marked.contains(key)),
+                _ => {}
+            }
+        }
+        self.heap.allocations = 0;
+    }
+
+    /// Follows references from the marked objects until nothing new is
+    /// found. A `WeakMap` value is only reachable through its map while
+    /// its key is reachable some other way.
+    fn mark_reachable(&self, marker: &mut Marker) {
+        loop {
+            while let Some(id) = marker.pending.pop() {
+                self.trace(id, marker);
+            }
+            let weak_maps: Vec<ObjectId> = marker
+                .marked
+                .iter()
+                .copied()
+                .filter(|id| matches!(self.heap.get(*id).kind, ObjectKind::WeakMap(_)))
+                .collect();
+            for id in weak_maps {
+                let ObjectKind::WeakMap(entries) = &self.heap.get(id).kind else { unreachable!() };
+                for (key, value) in entries {
+                    if marker.marked.contains(key) {
+                        marker.mark_value(value);
+                    }
+                }
+            }
+            if marker.pending.is_empty() {
+                return;
+            }
+        }
+    }
+
+    /// Marks what the object `id` refers to.
+    fn trace(&self, id: ObjectId, marker: &mut Marker) {
+        let object = self.heap.get(id);
+        if let Some(prototype) = object.prototype {
+            marker.mark(prototype);
+        }
+        for property in object.properties.values().chain(object.private_elements.values()) {
+            marker.mark_property(property);
+        }
+        match &object.kind {
+            ObjectKind::Function(closure) => {
+                marker.mark_environment(&closure.env);
+                if let Some(home_object) = closure.home_object {
+                    marker.mark(home_object);
+                }
+                if let Some(class) = &closure.class {
+                    for (_, property) in &class.private_methods {
+                        marker.mark_property(property);
+                    }
+                }
+            }
+            ObjectKind::BoundFunction(bound) => {
+                for value in [&bound.target, &bound.this].into_iter().chain(&bound.args) {
+                    marker.mark_value(value);
+                }
+            }
+            ObjectKind::Primitive(value) => marker.mark_value(value),
+            ObjectKind::Map(table) | ObjectKind::Set(table) => {
+                for (key, value) in table.iter() {
+                    marker.mark_value(key);
+                    marker.mark_value(value);
+                }
+            }
+            ObjectKind::CollectionIterator(iterator) => {
+                if let Some(collection) = iterator.collection {
+                    marker.mark(collection);
+                }
+            }
+            ObjectKind::ArrayIterator(iterator) => {
+                if let Some(target) = &iterator.target {
+                    marker.mark_value(target);
+                }
+            }
+            ObjectKind::Generator(generator) => {
+                let (scopes, objects) = generator.references();
+                for env in scopes {
+                    marker.mark_environment(env);
+                }
+                for id in objects {
+                    marker.mark(id);
+                }
+            }
+            ObjectKind::IteratorHelper(helper) => {
+                let operation = match &helper.operation {
+                    HelperOperation::Map(function) | HelperOperation::Filter(function) | HelperOperation::FlatMap(function) => Some(function),
+                    HelperOperation::Take(_) | HelperOperation::Drop(_) => None,
+                };
+                for iteration in std::iter::once(&helper.underlying).chain(&helper.inner) {
+                    marker.mark_value(&iteration.iterator);
+                    marker.mark_value(&iteration.next);
+                }
+                if let Some(function) = operation {
+                    marker.mark_value(function);
+                }
+            }
+            ObjectKind::WrappedIterator(iteration) => {
+                marker.mark_value(&iteration.iterator);
+                marker.mark_value(&iteration.next);
+            }
+            ObjectKind::Promise(state) => {
+                for value in state.references() {
+                    marker.mark_value(&value);
+                }
+            }
+            ObjectKind::TypedArray(view) => marker.mark(view.buffer),
+            ObjectKind::DataView(view) => marker.mark(view.buffer),
+            ObjectKind::Proxy(proxy) => {
+                for id in proxy.target.into_iter().chain(proxy.handler) {
+                    marker.mark(id);
+                }
+            }
+            ObjectKind::RevokeProxy(proxy) => {
+                if let Some(proxy) = proxy {
+                    marker.mark(*proxy);
+                }
+            }
+            ObjectKind::PromiseFunction(function) => {
+                for value in function.references() {
+                    marker.mark_value(&value);
+                }
+                if let PromiseFunction::Element { combinator, .. } = function {
+                    if marker.combinators.insert(Rc::as_ptr(combinator) as usize) {
+                        for value in combinator.values.borrow().iter() {
+                            marker.mark_value(value);
+                        }
+                    }
+                }
+            }
+            ObjectKind::Ordinary
+            | ObjectKind::StringIterator(_)
+            | ObjectKind::Array
+            | ObjectKind::NativeFunction(_)
+            | ObjectKind::Arguments
+            | ObjectKind::Error
+            | ObjectKind::Date(_)
+            | ObjectKind::ArrayBuffer(_)
+            | ObjectKind::TextEncoder
+            | ObjectKind::TextDecoder(_)
+            | ObjectKind::WeakMap(_)
+            | ObjectKind::WeakSet(_) => {}
+        }
+    }
+}
@@ -0,0 +1,645 @@
+//! Generators. The body of a generator runs on a coroutine with a stack of
+//! its own, so a `yield` anywhere in the body suspends the evaluation in
+//! place and returns to whoever called `next`, and resuming picks up in
+//! the same Rust frames.
+//!
+//! Async function calls and top-level scripts run on the same machinery,
+//! suspending at each `await` instead; see the `promise` module. Async
+//! generators do both, and queue the calls of `next`, `throw` and `return`
+//! that arrive while they are busy.
+//!
+//! The frames on a body's stack refer to an interpreter of the body's own,
+//! which shares the state of the one that created it, rather than to
+//! whichever interpreter resumes it. Suspending leaves them holding nothing
+//! else that the resumer goes on to use.
+//!
+//! While a generator is suspended, the frames on its stack still hold
+//! values in locals, which the garbage collector cannot see. What they
+//! hold with `hold`, and the scopes they will return to, are saved with
+//! the generator.
+
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use corosensei::stack::Stack;
+use corosensei::{Coroutine, CoroutineResult, Yielder};
+
+use super::environment::{EnvRef, Environment};
+use super::exception::StackFrame;
+use super::object::{Object, ObjectId, ObjectKind, PropertyKey};
+use super::promise::Handler;
+use super::stack::{Guard, PooledStack};
+use super::{range_error, syntax_error, type_error, Completion, Interpreter, Value, MAX_CALL_DEPTH, STACK_GROWTH, STACK_RED_ZONE};
+use crate::parser::{Expression, FunctionDefinition, Statement};
+
+/// What `next`, `throw` or `return` asks a generator to do.
+#[derive(Clone)]
+pub(super) enum ResumeAction {
+    Next(Value),
+    Throw(Value),
+    Return(Value),
+}
+
+/// How a generator handed control back.
+pub(super) enum GeneratorStep {
+    /// `yield value`
+    Yield(Value),
+    /// An iterator result of the iterator `yield*` delegates to, which is
+    /// passed on unchanged
+    Delegated(Value),
+    /// The body finished, returning a value
+    Return(Value),
+    /// `await`, waiting for the promise to settle
+    Await(ObjectId),
+}
+
+pub(super) type GeneratorYielder = Yielder<ResumeAction, GeneratorStep>;
+type GeneratorCoroutine = Coroutine<ResumeAction, GeneratorStep, Result<Value, String>, PooledStack>;
+
+/// The code a suspendable body runs.
+#[derive(Clone)]
+pub(super) enum Body {
+    Generator(Rc<FunctionDefinition>),
+    AsyncGenerator(Rc<FunctionDefinition>),
+    /// The body of an async function call, which settles `promise`
+    Async(Rc<FunctionDefinition>, ObjectId),
+    /// A top-level script, whose completion value fulfills `promise`
+    Script(Rc<[Statement]>, ObjectId),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GeneratorState {
+    SuspendedStart,
+    SuspendedYield,
+    Executing,
+    /// Suspended at an `await`, until the promise settles
+    Awaiting,
+    /// An async generator that has completed, awaiting the value a
+    /// `return` request returns
+    AwaitingReturn,
+    Completed,
+}
+
+/// A call of `next`, `throw` or `return` on an async generator, waiting
+/// for the generator to get to it, and the promise the call returned.
+pub(super) struct AsyncGeneratorRequest {
+    action: ResumeAction,
+    promise: ObjectId,
+}
+
+/// The state of a generator object, or of the suspendable body of an async
+/// function call or script, which scripts never see.
+pub struct Generator {
+    state: GeneratorState,
+    /// The generator function, and its name for stack traces
+    function: Option<ObjectId>,
+    name: String,
+    pub(super) body: Body,
+    /// The scope the body continues in, dropped once it completes
+    env: Option<EnvRef>,
+    /// The scopes the suspended body returns to as it leaves the ones it
+    /// is in
+    scopes: Vec<EnvRef>,
+    /// Objects held by the suspended body; see `Interpreter::hold`
+    temporaries: Vec<ObjectId>,
+    /// Created when the body first runs
+    coroutine: Option<GeneratorCoroutine>,
+    stack_limit: usize,
+    /// Whether the guard below the stack stays protected, rather than only
+    /// while the body runs
+    stack_guarded: bool,
+    /// The requests an async generator has yet to settle, oldest first
+    requests: VecDeque<AsyncGeneratorRequest>,
+}
+
+impl std::fmt::Debug for Generator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Generator").field("state", &self.state).field("function", &self.function).finish_non_exhaustive()
+    }
+}
+
+impl Generator {
+    /// The scopes and objects a generator keeps alive.
+    pub fn references(&self) -> (impl Iterator<Item = &EnvRef>, impl Iterator<Item = ObjectId> + '_) {
+        let promise = match &self.body {
+            Body::Generator(_) | Body::AsyncGenerator(_) => None,
+            Body::Async(_, promise) | Body::Script(_, promise) => Some(*promise),
+        };
+        let requests = self.requests.iter().flat_map(|request| {
+            let value = match &request.action {
+                ResumeAction::Next(value) | ResumeAction::Throw(value) | ResumeAction::Return(value) => value,
+            };
+            let value = match value {
+                Value::Object(id) | Value::Function(id) => Some(*id),
+                _ => None,
+            };
+            std::iter::once(request.promise).chain(value)
+        });
+        let objects = self.function.into_iter().chain(promise).chain(self.temporaries.iter().copied()).chain(requests);
+        (self.env.iter().chain(&self.scopes), objects)
+    }
+}
+
+/// The state of the code that resumed a generator, set aside while the
+/// body runs.
+pub(super) struct Resumer {
+    /// The generator being run
+    pub(super) generator: ObjectId,
+    pub(super) env: EnvRef,
+    pub(super) scopes: Vec<EnvRef>,
+    pub(super) temporaries: Vec<ObjectId>,
+}
+
+impl Interpreter {
+    /// Creates the generator object a call to a generator function returns,
+    /// with its parameters already bound in the current scope.
+    pub(super) fn create_generator(&mut self, definition: &Rc<FunctionDefinition>) -> Result<Value, String> {
+        let function = Environment::function_context(&self.env).and_then(|context| context.callee).expect("generator without callee");
+        let (body, default_prototype) = if definition.is_async {
+            (Body::AsyncGenerator(Rc::clone(definition)), self.intrinsics.async_generator_prototype)
+        } else {
+            (Body::Generator(Rc::clone(definition)), self.intrinsics.generator_prototype)
+        };
+        let prototype = match self.get_member(&Value::Function(function), &PropertyKey::from("prototype"))? {
+            Value::Object(prototype) | Value::Function(prototype) => prototype,
+            _ => default_prototype,
+        };
+        let name = self.function_name(function);
+        let id = self.create_body(body, Some(function), name, Some(prototype));
+        Ok(Value::Object(id))
+    }
+
+    /// Allocates the state of a body that has yet to start, to run in the
+    /// current scope.
+    pub(super) fn create_body(&mut self, body: Body, function: Option<ObjectId>, name: String, prototype: Option<ObjectId>) -> ObjectId {
+        let generator = Generator {
+            state: GeneratorState::SuspendedStart,
+            function,
+            name,
+            body,
+            env: Some(self.env.clone()),
+            scopes: Vec::new(),
+            temporaries: Vec::new(),
+            coroutine: None,
+            stack_limit: 0,
+            stack_guarded: false,
+            requests: VecDeque::new(),
+        };
+        self.heap.allocate(Object::new(ObjectKind::Generator(Box::new(generator)), prototype))
+    }
+
+    pub(super) fn generator_mut(&mut self, id: ObjectId) -> &mut Generator {
+        match &mut self.heap.get_mut(id).kind {
+            ObjectKind::Generator(generator) => generator,
+            _ => unreachable!("not a generator"),
+        }
+    }
+
+    /// Runs the generator `id` until it yields or completes.
+    pub(super) fn resume_generator(&mut self, id: ObjectId, action: ResumeAction) -> Result<GeneratorStep, String> {
+        let generator = self.generator_mut(id);
+        match (generator.state, &action) {
+            (GeneratorState::Executing, _) => return Err(type_error("Generator is already running")),
+            // A generator closed before it started never runs its body
+            (GeneratorState::SuspendedStart, ResumeAction::Throw(_) | ResumeAction::Return(_)) => {
+                generator.state = GeneratorState::Completed;
+                generator.env = None;
+            }
+            _ => {}
+        }
+        if self.generator_mut(id).state == GeneratorState::Completed {
+            return match action {
+                ResumeAction::Next(_) => Ok(GeneratorStep::Return(Value::Undefined)),
+                ResumeAction::Return(value) => Ok(GeneratorStep::Return(value)),
+                ResumeAction::Throw(value) => Err(self.throw_value(value)),
+            };
+        }
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(range_error("Maximum call stack size exceeded"));
+        }
+
+        let started = self.generator_mut(id).coroutine.is_some();
+        let mut coroutine = match self.generator_mut(id).coroutine.take() {
+            Some(coroutine) => coroutine,
+            None => self.start_generator(id)?,
+        };
+        let generator = self.generator_mut(id);
+        generator.state = GeneratorState::Executing;
+        let env = generator.env.take().expect("suspended generator without a scope");
+        let scopes = std::mem::take(&mut generator.scopes);
+        let temporaries = std::mem::take(&mut generator.temporaries);
+        let (stack_limit, stack_guarded) = (generator.stack_limit, generator.stack_guarded);
+        let name = generator.name.clone();
+        // A script is the bottom frame already, and an async call starts
+        // in the frame of the call
+        let new_frame = match generator.body {
+            Body::Generator(_) | Body::AsyncGenerator(_) => true,
+            Body::Async(..) => started,
+            Body::Script(..) => false,
+        };
+
+        let resumer = Resumer {
+            generator: id,
+            env: std::mem::replace(&mut self.env, env),
+            scopes: std::mem::replace(&mut self.scopes, scopes),
+            temporaries: std::mem::replace(&mut self.heap.temporaries, temporaries),
+        };
+        self.resumers.push(resumer);
+        let saved_yielder = self.yielder;
+        let saved_stack_limit = self.stack_limit.replace(stack_limit);
+        self.call_depth += 1;
+        if new_frame {
+            self.frames.push(StackFrame { function: name, constructing: false, location: None });
+        }
+        let guard = (!stack_guarded).then(|| Guard::protect(stack_limit));
+        let result = coroutine.resume(action);
+        drop(guard);
+        if new_frame {
+            self.frames.pop();
+        }
+        self.call_depth -= 1;
+        self.stack_limit = saved_stack_limit;
+        self.yielder = saved_yielder;
+        let resumer = self.resumers.pop().expect("resumers are restored in order");
+        let env = std::mem::replace(&mut self.env, resumer.env);
+        let scopes = std::mem::replace(&mut self.scopes, resumer.scopes);
+        let temporaries = std::mem::replace(&mut self.heap.temporaries, resumer.temporaries);
+
+        match result {
+            CoroutineResult::Yield(step) => {
+                if let GeneratorStep::Yield(value) | GeneratorStep::Delegated(value) = &step {
+                    self.hold(value);
+                }
+                let generator = self.generator_mut(id);
+                generator.state = match step {
+                    GeneratorStep::Await(_) => GeneratorState::Awaiting,
+                    _ => GeneratorState::SuspendedYield,
+                };
+                generator.env = Some(env);
+                generator.scopes = scopes;
+                generator.temporaries = temporaries;
+                generator.coroutine = Some(coroutine);
+                Ok(step)
+            }
+            CoroutineResult::Return(result) => {
+                if let Ok(value) = &result {
+                    self.hold(value);
+                }
+                self.generator_mut(id).state = GeneratorState::Completed;
+                result.map(GeneratorStep::Return)
+            }
+        }
+    }
+
+    /// Creates the coroutine the body of the generator `id` runs on.
+    fn start_generator(&mut self, id: ObjectId) -> Result<GeneratorCoroutine, String> {
+        let stack = self.generator_stacks.allocate().ok_or_else(|| range_error("Out of memory for generator stacks"))?;
+        let generator = self.generator_mut(id);
+        generator.stack_limit = stack.limit().get();
+        generator.stack_guarded = stack.is_guarded();
+        let body = generator.body.clone();
+        // The coroutine belongs to a generator in the heap, so it does not
+        // outlive the state
+        let mut interpreter = self.share();
+        Ok(Coroutine::with_stack(stack, move |yielder: &GeneratorYielder, _: ResumeAction| {
+            interpreter.yielder = yielder;
+            let completion = match &body {
+                Body::Generator(definition) | Body::AsyncGenerator(definition) | Body::Async(definition, _) => {
+                    interpreter.exec_block(&definition.body)
+                }
+                Body::Script(statements, _) => interpreter.exec_script(statements).map(Completion::Return),
+            };
+            match completion {
+                Ok(Completion::Return(value)) => Ok(value),
+                Ok(_) => Ok(Value::Undefined),
+                Err(message) if interpreter.is_generator_return(&message) => {
+                    Ok(interpreter.exception.take().map_or(Value::Undefined, |exception| exception.value))
+                }
+                Err(message) => {
+                    interpreter.materialize_exception(&message);
+                    Err(message)
+                }
+            }
+        }))
+    }
+
+    /// Suspends the generator running this code, returning what it is
+    /// resumed with.
+    pub(super) fn generator_suspend(&mut self, step: GeneratorStep) -> ResumeAction {
+        let yielder = self.yielder;
+        // SAFETY: `yielder` is set by the coroutine running this code, and
+        // outlives it
+        let action = unsafe { (*yielder).suspend(step) };
+        self.yielder = yielder;
+        action
+    }
+
+    /// `yield` and `yield*`.
+    pub(super) fn eval_yield(&mut self, argument: Option<&Expression>, delegate: bool) -> Result<Value, String> {
+        if self.yielder.is_null() {
+            return Err(syntax_error("Unexpected identifier 'yield'"));
+        }
+        let value = match argument {
+            Some(argument) => self.eval_expression(argument)?,
+            None => Value::Undefined,
+        };
+        let is_async = self.running_async_generator();
+        if delegate {
+            return if is_async { self.yield_delegate_async(&value) } else { self.yield_delegate(&value) };
+        }
+        let action = if is_async {
+            let value = self.await_value(value)?;
+            self.async_generator_suspend(value)?
+        } else {
+            self.generator_suspend(GeneratorStep::Yield(value))
+        };
+        match action {
+            ResumeAction::Next(value) => Ok(value),
+            ResumeAction::Throw(value) => Err(self.throw_value(value)),
+            ResumeAction::Return(value) => Err(self.start_generator_return(value)),
+        }
+    }
+
+    /// `yield* iterable`: passes each `next`, `throw` and `return` on to
+    /// the iterator of `iterable` and its results back out, until it is
+    /// done. Evaluates to the value it finishes with.
+    fn yield_delegate(&mut self, iterable: &Value) -> Result<Value, String> {
+        let mut iteration = self.iterate(iterable)?;
+        let iterator = iteration.iterator.clone();
+        let mut received = ResumeAction::Next(Value::Undefined);
+        loop {
+            let result = match received {
+                ResumeAction::Next(value) => self.call_function(&iteration.next, iterator.clone(), vec![value])?,
+                ResumeAction::Throw(value) => match self.get_method(&iterator, "throw")? {
+                    Some(throw) => self.call_function(&throw, iterator.clone(), vec![value])?,
+                    None => {
+                        // The iterator cannot take the exception, but is
+                        // given the chance to clean up
+                        self.close_iteration(&mut iteration, Ok(()))?;
+                        return Err(type_error("The iterator does not provide a 'throw' method."));
+                    }
+                },
+                ResumeAction::Return(value) => {
+                    let Some(method) = self.get_method(&iterator, "return")? else {
+                        return Err(self.start_generator_return(value));
+                    };
+                    let result = self.call_function(&method, iterator.clone(), vec![value])?;
+                    self.check_iterator_result(&result)?;
+                    if self.get_member(&result, &PropertyKey::from("done"))?.is_truthy() {
+                        let value = self.get_member(&result, &PropertyKey::from("value"))?;
+                        return Err(self.start_generator_return(value));
+                    }
+                    self.hold(&result);
+                    received = self.generator_suspend(GeneratorStep::Delegated(result));
+                    continue;
+                }
+            };
+            self.check_iterator_result(&result)?;
+            if self.get_member(&result, &PropertyKey::from("done"))?.is_truthy() {
+                return self.get_member(&result, &PropertyKey::from("value"));
+            }
+            self.hold(&result);
+            received = self.generator_suspend(GeneratorStep::Delegated(result));
+        }
+    }
+
+    /// `yield*` in an async generator: like `yield_delegate`, but with an
+    /// async iterator, whose results are awaited, or a sync one, whose
+    /// values are. The values are yielded rather than the results.
+    fn yield_delegate_async(&mut self, iterable: &Value) -> Result<Value, String> {
+        let (mut iteration, from_sync) = self.async_iterate(iterable)?;
+        let iterator = iteration.iterator.clone();
+        let mut received = ResumeAction::Next(Value::Undefined);
+        loop {
+            let returning = matches!(received, ResumeAction::Return(_));
+            let result = match received {
+                ResumeAction::Next(value) => self.call_function(&iteration.next, iterator.clone(), vec![value])?,
+                ResumeAction::Throw(value) => match self.get_method(&iterator, "throw")? {
+                    Some(throw) => self.call_function(&throw, iterator.clone(), vec![value])?,
+                    None => {
+                        let close = if from_sync { Self::close_iteration } else { Self::close_async_iteration };
+                        close(self, &mut iteration, Ok(()))?;
+                        return Err(type_error("The iterator does not provide a 'throw' method."));
+                    }
+                },
+                ResumeAction::Return(value) => match self.get_method(&iterator, "return")? {
+                    Some(method) => self.call_function(&method, iterator.clone(), vec![value])?,
+                    None => return Err(self.start_generator_return(value)),
+                },
+            };
+            let result = if from_sync { result } else { self.await_value(result)? };
+            self.check_iterator_result(&result)?;
+            let done = self.get_member(&result, &PropertyKey::from("done"))?.is_truthy();
+            let mut value = self.get_member(&result, &PropertyKey::from("value"))?;
+            if from_sync {
+                value = self.await_value(value)?;
+            }
+            match (done, returning) {
+                (true, true) => return Err(self.start_generator_return(value)),
+                (true, false) => return Ok(value),
+                (false, _) => received = self.async_generator_suspend(value)?,
+            }
+        }
+    }
+
+    /// Yields `value` from the async generator running this code. The
+    /// value of a `return` it is resumed with is awaited, and a rejection
+    /// thrown at the `yield`.
+    fn async_generator_suspend(&mut self, value: Value) -> Result<ResumeAction, String> {
+        match self.generator_suspend(GeneratorStep::Yield(value)) {
+            ResumeAction::Return(value) => self.await_value(value).map(ResumeAction::Return),
+            action => Ok(action),
+        }
+    }
+
+    /// Whether the body running is an async generator's.
+    fn running_async_generator(&self) -> bool {
+        let resumer = self.resumers.last().expect("yield outside a generator body");
+        matches!(&self.heap.get(resumer.generator).kind, ObjectKind::Generator(generator) if matches!(generator.body, Body::AsyncGenerator(_)))
+    }
+
+    /// Whether `id` is an async generator object, or with `is_async` unset,
+    /// a generator object.
+    pub(super) fn is_generator(&self, id: ObjectId, is_async: bool) -> bool {
+        match &self.heap.get(id).kind {
+            ObjectKind::Generator(generator) => match generator.body {
+                Body::Generator(_) => !is_async,
+                Body::AsyncGenerator(_) => is_async,
+                Body::Async(..) | Body::Script(..) => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// `next`, `throw` or `return` on the async generator `id`: queues the
+    /// request, and gets the generator going unless it is busy. Returns
+    /// the promise for the result.
+    pub(super) fn enqueue_async_generator(&mut self, id: ObjectId, action: ResumeAction) -> ObjectId {
+        let promise = self.create_promise(self.intrinsics.promise_prototype);
+        self.generator_mut(id).requests.push_back(AsyncGeneratorRequest { action, promise });
+        self.drain_async_generator(id);
+        promise
+    }
+
+    /// Continues the async generator `id` once the promise it awaits has
+    /// settled: the body, or a `return` request on a completed generator.
+    pub(super) fn resume_async_generator(&mut self, id: ObjectId, action: ResumeAction) {
+        if self.generator_mut(id).state == GeneratorState::AwaitingReturn {
+            self.generator_mut(id).state = GeneratorState::Completed;
+            match action {
+                ResumeAction::Next(value) => self.settle_async_generator_request(id, Ok(value), true),
+                ResumeAction::Throw(reason) => self.settle_async_generator_request(id, Err(reason), true),
+                ResumeAction::Return(_) => unreachable!("promises resume with a value or a reason"),
+            }
+        } else {
+            self.run_async_generator(id, action);
+        }
+        self.drain_async_generator(id);
+    }
+
+    /// Works through the requests queued on the async generator `id` for
+    /// as long as it is suspended: resumes the body with the oldest, or
+    /// settles it right away once the body has completed.
+    fn drain_async_generator(&mut self, id: ObjectId) {
+        loop {
+            let generator = self.generator_mut(id);
+            let Some(request) = generator.requests.front() else {
+                return;
+            };
+            let action = request.action.clone();
+            match (generator.state, &action) {
+                (GeneratorState::Executing | GeneratorState::Awaiting | GeneratorState::AwaitingReturn, _) => return,
+                // A generator closed before it started never runs its body
+                (GeneratorState::SuspendedStart, ResumeAction::Throw(_) | ResumeAction::Return(_)) => {
+                    generator.state = GeneratorState::Completed;
+                    generator.env = None;
+                }
+                _ => {}
+            }
+            if self.generator_mut(id).state != GeneratorState::Completed {
+                self.run_async_generator(id, action);
+                continue;
+            }
+            match action {
+                ResumeAction::Next(_) => self.settle_async_generator_request(id, Ok(Value::Undefined), true),
+                ResumeAction::Throw(reason) => self.settle_async_generator_request(id, Err(reason), true),
+                ResumeAction::Return(value) => {
+                    let constructor = Value::Function(self.intrinsics.promise_constructor);
+                    match self.promise_resolve(&constructor, value) {
+                        Ok(promise) => {
+                            let promise = self.promise_id(&promise).expect("Promise made something other than a promise");
+                            self.generator_mut(id).state = GeneratorState::AwaitingReturn;
+                            return self.perform_then(promise, Handler::Resume(id), Handler::Resume(id), None);
+                        }
+                        Err(message) => {
+                            let reason = self.catch_exception(&message);
+                            self.settle_async_generator_request(id, Err(reason), true);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs the body of the async generator `id` until it awaits, yields or
+    /// completes, settling the oldest request with what it yields or
+    /// completes with.
+    fn run_async_generator(&mut self, id: ObjectId, action: ResumeAction) {
+        match self.resume_generator(id, action) {
+            Ok(GeneratorStep::Await(promise)) => self.perform_then(promise, Handler::Resume(id), Handler::Resume(id), None),
+            Ok(GeneratorStep::Yield(value)) => self.settle_async_generator_request(id, Ok(value), false),
+            Ok(GeneratorStep::Return(value)) => self.settle_async_generator_request(id, Ok(value), true),
+            Ok(GeneratorStep::Delegated(_)) => unreachable!("async generators yield the values of delegated results"),
+            Err(message) => {
+                let reason = self.catch_exception(&message);
+                self.settle_async_generator_request(id, Err(reason), true);
+            }
+        }
+    }
+
+    /// Settles the promise of the oldest request on the async generator
+    /// `id` with an iterator result, or rejects it.
+    fn settle_async_generator_request(&mut self, id: ObjectId, result: Result<Value, Value>, done: bool) {
+        let request = self.generator_mut(id).requests.pop_front().expect("async generator settling without a request");
+        // Resolving looks up `then` on the result, which may run code
+        self.hold(&Value::Object(request.promise));
+        match result {
+            Ok(value) => {
+                let result = self.create_iterator_result(value, done);
+                self.hold(&result);
+                self.resolve_promise(request.promise, result);
+            }
+            Err(reason) => self.reject_promise(request.promise, reason),
+        }
+    }
+
+    fn check_iterator_result(&self, result: &Value) -> Result<(), String> {
+        match result {
+            Value::Object(_) | Value::Function(_) => Ok(()),
+            other => Err(type_error(format!("Iterator result {} is not an object", self.to_display_string(other)))),
+        }
+    }
+
+    /// Runs `f` with at least `STACK_RED_ZONE` of native stack left,
+    /// continuing on a new stack segment when it runs low.
+    #[inline]
+    pub(super) fn with_stack<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        // Every statement and expression comes through here, so the common
+        // case of enough stack left is kept to a comparison
+        match self.stack_limit {
+            Some(limit) if stack_pointer().saturating_sub(limit) >= STACK_RED_ZONE => f(self),
+            _ => self.with_stack_slow(f),
+        }
+    }
+
+    #[inline(never)]
+    fn with_stack_slow<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        match self.stack_limit {
+            // Out of room: continue on a new segment. `stacker` only knows
+            // the bounds of the thread's own stack and the segments it
+            // grows, not those of generator stacks.
+            Some(_) => {
+                let saved = self.stack_limit.take();
+                let result = stacker::grow(STACK_GROWTH, || f(self));
+                self.stack_limit = saved;
+                result
+            }
+            // On a stack `stacker` knows: note where it ends, so that the
+            // checks made meanwhile are a comparison
+            None => match stacker::remaining_stack() {
+                Some(remaining) => {
+                    self.stack_limit = Some(stack_pointer().saturating_sub(remaining));
+                    let result = self.with_stack(f);
+                    self.stack_limit = None;
+                    result
+                }
+                None => stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || f(self)),
+            },
+        }
+    }
+
+    /// Runs `f`, which grows the stack itself through `stacker`, on a
+    /// stack whose bounds `stacker` knows: inside a generator that means
+    /// moving to a new segment first.
+    pub(super) fn with_stacker<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let known = match self.stack_limit {
+            None => true,
+            // A limit noted on a stack `stacker` knows is the one it reports
+            Some(limit) => stacker::remaining_stack().is_some_and(|remaining| stack_pointer().saturating_sub(remaining).abs_diff(limit) < STACK_RED_ZONE),
+        };
+        if known {
+            return f(self);
+        }
+        let saved = self.stack_limit.take();
+        let result = stacker::grow(STACK_GROWTH, || f(self));
+        self.stack_limit = saved;
+        result
+    }
+}
+
+/// The approximate address of the top of the native stack.
+#[inline(always)]
+fn stack_pointer() -> usize {
+    let marker = 0u8;
+    std::hint::black_box(&marker) as *const u8 as usize
+}
@@ -0,0 +1,668 @@
+//! Node-style inspection of values, as `console.log` prints them.
+//!
+//! The layout follows Node's `util.inspect`: nested objects are shown to a
+//! limited depth, entries stay on one line while they fit in 80 columns,
+//! long arrays of short items are grouped into columns, and cycles are
+//! marked with `<ref *n>` / `[Circular *n]`. Inspection only reads the heap,
+//! so getters are shown as `[Getter]` rather than run.
+
+use super::collection::{CollectionIterator, IterationKind};
+use super::generator::Body;
+use super::object::{ObjectId, ObjectKind, Property, PropertyKey, PropertyValue};
+use super::promise::Settlement;
+use super::{number_to_string, Interpreter, Value};
+
+/// How much of a value `Interpreter::inspect` shows.
+#[derive(Clone, Copy)]
+pub(super) struct InspectOptions {
+    /// Levels of nested objects shown before they are abbreviated to
+    /// `[Object]`; `None` for no limit
+    pub depth: Option<usize>,
+    /// Array elements shown before `... n more items`
+    pub max_array_length: usize,
+    /// The line width entries are fitted into
+    pub break_length: usize,
+}
+
+impl Default for InspectOptions {
+    fn default() -> Self {
+        Self { depth: Some(2), max_array_length: 100, break_length: 80 }
+    }
+}
+
+/// Array entries are grouped into at most this many columns.
+const MAX_COLUMNS: usize = 15;
+
+impl Interpreter {
+    /// Formats `value` for display, quoting strings.
+    pub(super) fn inspect(&self, value: &Value, options: InspectOptions) -> String {
+        let mut inspector = Inspector { interpreter: self, options, seen: Vec::new(), circular: Vec::new(), indentation: 0, current_depth: 0 };
+        inspector.format_value(value, 0)
+    }
+}
+
+struct Inspector<'a> {
+    interpreter: &'a Interpreter,
+    options: InspectOptions,
+    /// The objects being formatted, from the outermost in
+    seen: Vec<ObjectId>,
+    /// Objects referred to cyclically, numbered by their position
+    circular: Vec<ObjectId>,
+    /// Columns the current nesting level is indented by
+    indentation: usize,
+    /// The nesting level of the object formatted most recently
+    current_depth: usize,
+}
+
+impl Inspector<'_> {
+    fn format_value(&mut self, value: &Value, recurse: usize) -> String {
+        match value {
+            Value::String(s) => self.format_string(&s.to_string()),
+            Value::Number(n) => format_number(*n),
+            Value::BigInt(n) => format!("{}n", n),
+            Value::Object(id) | Value::Function(id) => self.format_object(*id, recurse),
+            other => other.to_js_string(),
+        }
+    }
+
+    /// Quotes a string. Long multi-line strings are split after each line
+    /// break and joined with `+`.
+    fn format_string(&self, s: &str) -> String {
+        let width = s.chars().count();
+        if width > 16 && width > self.options.break_length.saturating_sub(self.indentation + 4) && s.contains('\n') {
+            let separator = format!(" +\n{}", " ".repeat(self.indentation + 2));
+            return s.split_inclusive('\n').map(quote).collect::<Vec<_>>().join(&separator);
+        }
+        quote(s)
+    }
+
+    fn format_object(&mut self, id: ObjectId, recurse: usize) -> String {
+        if self.seen.contains(&id) {
+            let index = match self.circular.iter().position(|&circular| circular == id) {
+                Some(index) => index,
+                None => {
+                    self.circular.push(id);
+                    self.circular.len() - 1
+                }
+            };
+            return format!("[Circular *{}]", index + 1);
+        }
+
+        let interpreter = self.interpreter;
+        let heap = &interpreter.heap;
+        let object = heap.get(id);
+        // Proxies show their target, without running any traps, as Node
+        // does by default
+        if let ObjectKind::Proxy(proxy) = &object.kind {
+            return match proxy.target {
+                Some(target) => self.format_object(target, recurse),
+                None => "<Revoked Proxy>".to_string(),
+            };
+        }
+        let constructor = self.constructor_name(id);
+        let keys: Vec<PropertyKey> = object
+            .own_property_keys()
+            .into_iter()
+            .filter(|key| object.is_enumerable(key) && !(object.is_array() && key.as_index().is_some()))
+            .filter(|key| !matches!(object.kind, ObjectKind::Arguments | ObjectKind::Primitive(Value::String(_))) || key.as_index().is_none())
+            .collect();
+
+        let (base, braces) = match &object.kind {
+            ObjectKind::Array | ObjectKind::Arguments => {
+                let length = object.array_length();
+                let prefix = match (&object.kind, constructor.as_deref()) {
+                    (ObjectKind::Arguments, _) => "[Arguments] ".to_string(),
+                    (_, Some("Array")) => String::new(),
+                    (_, Some(name)) => format!("{}({}) ", name, length),
+                    (_, None) => format!("[Array({}): null prototype] ", length),
+                };
+                if length == 0 && keys.is_empty() {
+                    return format!("{}[]", prefix);
+                }
+                (String::new(), (format!("{}[", prefix), "]"))
+            }
+            ObjectKind::Function(_)
+            | ObjectKind::NativeFunction(_)
+            | ObjectKind::BoundFunction(_)
+            | ObjectKind::PromiseFunction(_)
+            | ObjectKind::RevokeProxy(_) => {
+                let base = self.function_base(id);
+                if keys.is_empty() {
+                    return base;
+                }
+                (base, ("{".to_string(), "}"))
+            }
+            ObjectKind::Error => {
+                let base = match heap.get(id).properties.get(&PropertyKey::from("stack")) {
+                    Some(Property { value: PropertyValue::Data(Value::String(stack)), .. }) => stack.to_string(),
+                    _ => format!("[{}]", interpreter.error_summary(id)),
+                };
+                let base = base.replace('\n', &format!("\n{}", " ".repeat(self.indentation)));
+                if keys.is_empty() {
+                    return base;
+                }
+                (base, ("{".to_string(), "}"))
+            }
+            ObjectKind::Primitive(value) => {
+                let base = format!("[{}: {}]", primitive_type_name(value), self.format_value(value, recurse));
+                if keys.is_empty() {
+                    return base;
+                }
+                (base, ("{".to_string(), "}"))
+            }
+            ObjectKind::Map(table) | ObjectKind::Set(table) => {
+                let kind = if matches!(object.kind, ObjectKind::Map(_)) { "Map" } else { "Set" };
+                let prefix = match constructor.as_deref() {
+                    Some(name) if name == kind => format!("{}({}) ", kind, table.len()),
+                    Some(name) => format!("{}({}) [{}] ", name, table.len(), kind),
+                    None => format!("[{}({}): null prototype] ", kind, table.len()),
+                };
+                if table.len() == 0 && keys.is_empty() {
+                    return format!("{}{{}}", prefix);
+                }
+                (String::new(), (format!("{}{{", prefix), "}"))
+            }
+            ObjectKind::WeakMap(_) | ObjectKind::WeakSet(_) => {
+                let kind = if matches!(object.kind, ObjectKind::WeakMap(_)) { "WeakMap" } else { "WeakSet" };
+                let prefix = match constructor.as_deref() {
+                    Some(name) if name == kind => format!("{} ", kind),
+                    Some(name) => format!("{} [{}] ", name, kind),
+                    None => format!("[{}: null prototype] ", kind),
+                };
+                (String::new(), (format!("{}{{", prefix), "}"))
+            }
+            ObjectKind::CollectionIterator(iterator) => {
+                let kind = match (iterator.of_map, iterator.kind) {
+                    (true, IterationKind::Entries) => "[Map Entries]",
+                    (true, _) => "[Map Iterator]",
+                    (false, IterationKind::Entries) => "[Set Entries]",
+                    (false, _) => "[Set Iterator]",
+                };
+                (String::new(), (format!("{} {{", kind), "}"))
+            }
+            ObjectKind::ArrayIterator(_) | ObjectKind::StringIterator(_) | ObjectKind::Generator(_) | ObjectKind::IteratorHelper(_) => {
+                let tag = match &object.kind {
+                    ObjectKind::ArrayIterator(_) => "Array Iterator",
+                    ObjectKind::StringIterator(_) => "String Iterator",
+                    ObjectKind::Generator(generator) if matches!(generator.body, Body::AsyncGenerator(_)) => "AsyncGenerator",
+                    ObjectKind::Generator(_) => "Generator",
+                    _ => "Iterator Helper",
+                };
+                let prefix = match constructor.as_deref() {
+                    Some(name) => format!("{} [{}] ", name, tag),
+                    None => format!("[{}: null prototype] ", tag),
+                };
+                if keys.is_empty() {
+                    return format!("{}{{}}", prefix);
+                }
+                (String::new(), (format!("{}{{", prefix), "}"))
+            }
+            ObjectKind::Date(t) => {
+                let date = if t.is_nan() { "Invalid Date".to_string() } else { super::date::format_iso(*t) };
+                let base = match constructor.as_deref() {
+                    Some("Date") => date,
+                    Some(name) => format!("{} {}", name, date),
+                    None => format!("[Date: null prototype] {}", date),
+                };
+                if keys.is_empty() {
+                    return base;
+                }
+                (base, ("{".to_string(), "}"))
+            }
+            ObjectKind::TypedArray(view) => {
+                let name = format!("{}Array", view.element.name());
+                let prefix = match constructor.as_deref() {
+                    Some(constructor) if constructor == name => format!("{}({}) ", name, view.length),
+                    Some(constructor) => format!("{}({}) [{}] ", constructor, view.length, name),
+                    None => format!("[{}({}): null prototype] ", name, view.length),
+                };
+                if view.length == 0 && keys.is_empty() {
+                    return format!("{}[]", prefix);
+                }
+                (String::new(), (format!("{}[", prefix), "]"))
+            }
+            ObjectKind::ArrayBuffer(_) | ObjectKind::DataView(_) => {
+                let kind = match &object.kind {
+                    ObjectKind::ArrayBuffer(buffer) if buffer.shared => "SharedArrayBuffer",
+                    ObjectKind::ArrayBuffer(_) => "ArrayBuffer",
+                    _ => "DataView",
+                };
+                let prefix = match constructor.as_deref() {
+                    Some(name) if name == kind => format!("{} ", kind),
+                    Some(name) => format!("{} [{}] ", name, kind),
+                    None => format!("[{}: null prototype] ", kind),
+                };
+                (String::new(), (format!("{}{{", prefix), "}"))
+            }
+            ObjectKind::Promise(_) => {
+                let prefix = match constructor.as_deref() {
+                    Some("Promise") => "Promise ".to_string(),
+                    Some(name) => format!("{} [Promise] ", name),
+                    None => "[Promise: null prototype] ".to_string(),
+                };
+                (String::new(), (format!("{}{{", prefix), "}"))
+            }
+            ObjectKind::Ordinary | ObjectKind::WrappedIterator(_) | ObjectKind::TextEncoder | ObjectKind::TextDecoder(_) => {
+                let prefix = match (constructor.as_deref(), self.to_string_tag(id)) {
+                    (Some(name), Some(tag)) if name != tag => format!("{} [{}] ", name, tag),
+                    (Some("Object"), _) => String::new(),
+                    (Some(name), _) => format!("{} ", name),
+                    (None, Some(tag)) if tag != "Object" => format!("[Object: null prototype] [{}] ", tag),
+                    (None, _) => "[Object: null prototype] ".to_string(),
+                };
+                if keys.is_empty() {
+                    return format!("{}{{}}", prefix);
+                }
+                (String::new(), (format!("{}{{", prefix), "}"))
+            }
+            ObjectKind::Proxy(_) => unreachable!("proxies are formatted as their target"),
+        };
+
+        if self.options.depth.is_some_and(|depth| recurse > depth) {
+            return format!("[{}]", constructor.as_deref().unwrap_or("Object"));
+        }
+
+        self.seen.push(id);
+        self.current_depth = recurse;
+        self.indentation += 2;
+        let is_array = matches!(object.kind, ObjectKind::Array | ObjectKind::Arguments | ObjectKind::TypedArray(_));
+        let mut output = match &object.kind {
+            ObjectKind::Array | ObjectKind::Arguments => self.format_elements(id, recurse),
+            ObjectKind::TypedArray(view) => self.format_typed_array_elements(id, view.length),
+            ObjectKind::ArrayBuffer(buffer) => {
+                let shown = &buffer.bytes[..buffer.bytes.len().min(self.options.max_array_length)];
+                let mut contents = shown.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(" ");
+                let remaining = buffer.bytes.len() - shown.len();
+                if remaining > 0 {
+                    contents.push_str(&format!(" ... {} more byte{}", remaining, if remaining == 1 { "" } else { "s" }));
+                }
+                vec![format!("[Uint8Contents]: <{}>", contents), format!("byteLength: {}", buffer.bytes.len())]
+            }
+            ObjectKind::DataView(view) => vec![
+                format!("byteLength: {}", view.byte_length),
+                format!("byteOffset: {}", view.byte_offset),
+                format!("buffer: {}", self.format_object(view.buffer, recurse + 1)),
+            ],
+            ObjectKind::Map(_) | ObjectKind::Set(_) | ObjectKind::CollectionIterator(_) => self.format_collection_entries(id, recurse),
+            // Which entries are still alive depends on the garbage collector
+            ObjectKind::WeakMap(_) | ObjectKind::WeakSet(_) => vec!["<items unknown>".to_string()],
+            ObjectKind::Promise(state) => vec![match &state.settlement {
+                Settlement::Pending => "<pending>".to_string(),
+                Settlement::Fulfilled(value) => self.format_value(value, recurse + 1),
+                Settlement::Rejected(reason) => format!("<rejected> {}", self.format_value(reason, recurse + 1)),
+            }],
+            _ => Vec::new(),
+        };
+        let has_more_items = output.last().is_some_and(|entry| entry.starts_with("... "));
+        for key in &keys {
+            let entry = self.format_property(id, key, recurse);
+            output.push(entry);
+        }
+        self.indentation -= 2;
+        self.seen.pop();
+
+        let typed_array = matches!(object.kind, ObjectKind::TypedArray(_));
+        let all_numbers = is_array && (typed_array || (0..heap.get(id).array_length()).all(|index| {
+            matches!(heap.get(id).properties.get(&PropertyKey::Index(index)), Some(Property { value: PropertyValue::Data(Value::Number(_)), .. }))
+        }));
+        // Like Node, only the innermost three levels of nesting may share
+        // a line
+        let nested_levels = self.current_depth - recurse;
+        let array = is_array.then_some((all_numbers, has_more_items));
+        let mut result = self.reduce_to_single_string(output, &base, (&braces.0, braces.1), array, nested_levels < 3);
+        if let Some(index) = self.circular.iter().position(|&circular| circular == id) {
+            result = format!("<ref *{}> {}", index + 1, result);
+        }
+        result
+    }
+
+    /// The entries for the elements of an array, with runs of holes
+    /// collapsed into `<n empty items>`.
+    fn format_elements(&mut self, id: ObjectId, recurse: usize) -> Vec<String> {
+        let length = self.interpreter.heap.get(id).array_length() as usize;
+        let mut output = Vec::new();
+        let mut index = 0;
+        while index < length && output.len() < self.options.max_array_length {
+            let key = PropertyKey::Index(index as u32);
+            if self.interpreter.heap.get(id).properties.contains_key(&key) {
+                output.push(self.format_property_value(id, &key, recurse));
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while index < length && !self.interpreter.heap.get(id).properties.contains_key(&PropertyKey::Index(index as u32)) {
+                index += 1;
+            }
+            let holes = index - start;
+            output.push(format!("<{} empty item{}>", holes, if holes == 1 { "" } else { "s" }));
+        }
+        if index < length {
+            let remaining = length - index;
+            output.push(format!("... {} more item{}", remaining, if remaining == 1 { "" } else { "s" }));
+        }
+        output
+    }
+
+    /// The entries for the elements of a typed array, which are all
+    /// numbers or all BigInts.
+    fn format_typed_array_elements(&mut self, id: ObjectId, length: usize) -> Vec<String> {
+        let shown = length.min(self.options.max_array_length);
+        let mut output: Vec<String> = (0..shown).map(|index| self.format_value(&self.interpreter.read_element(id, index), 0)).collect();
+        if length > shown {
+            let remaining = length - shown;
+            output.push(format!("... {} more item{}", remaining, if remaining == 1 { "" } else { "s" }));
+        }
+        output
+    }
+
+    /// The entries of a `Map` or `Set` as `key => value` or `value`, or
+    /// those an iterator over one has yet to produce.
+    fn format_collection_entries(&mut self, id: ObjectId, recurse: usize) -> Vec<String> {
+        let heap = &self.interpreter.heap;
+        let (table, position, kind) = match &heap.get(id).kind {
+            ObjectKind::Map(table) => (table, 0, IterationKind::Entries),
+            ObjectKind::Set(table) => (table, 0, IterationKind::Values),
+            ObjectKind::CollectionIterator(CollectionIterator { collection: Some(collection), cursor, kind, .. }) => {
+                match &heap.get(*collection).kind {
+                    ObjectKind::Map(table) | ObjectKind::Set(table) => (table, cursor.get(), *kind),
+                    _ => return Vec::new(),
+                }
+            }
+            _ => return Vec::new(),
+        };
+        let is_map = matches!(heap.get(id).kind, ObjectKind::Map(_));
+        let entries: Vec<(Value, Value)> = table.entries_from(position).cloned().collect();
+        let mut output = Vec::new();
+        for (key, value) in entries.iter().take(self.options.max_array_length) {
+            output.push(match kind {
+                IterationKind::Keys => self.format_value(key, recurse + 1),
+                IterationKind::Values => self.format_value(value, recurse + 1),
+                IterationKind::Entries if is_map => {
+                    format!("{} => {}", self.format_value(key, recurse + 1), self.format_value(value, recurse + 1))
+                }
+                IterationKind::Entries => {
+                    let pair = vec![self.format_value(key, recurse + 1), self.format_value(value, recurse + 1)];
+                    self.reduce_to_single_string(pair, "", ("[", "]"), Some((false, false)), true)
+                }
+            });
+        }
+        if entries.len() > self.options.max_array_length {
+            let remaining = entries.len() - self.options.max_array_length;
+            output.push(format!("... {} more item{}", remaining, if remaining == 1 { "" } else { "s" }));
+        }
+        output
+    }
+
+    /// `key: value` for an own property.
+    fn format_property(&mut self, id: ObjectId, key: &PropertyKey, recurse: usize) -> String {
+        let name = match key {
+            PropertyKey::String(name) if is_identifier(name) => name.clone(),
+            PropertyKey::Symbol(symbol) => format!("[{}]", symbol),
+            other => quote(&other.to_string()),
+        };
+        format!("{}: {}", name, self.format_property_value(id, key, recurse))
+    }
+
+    fn format_property_value(&mut self, id: ObjectId, key: &PropertyKey, recurse: usize) -> String {
+        match self.interpreter.heap.get(id).properties.get(key).map(|property| property.value.clone()) {
+            Some(PropertyValue::Data(value)) => self.format_value(&value, recurse + 1),
+            Some(PropertyValue::Accessor { get: Some(_), set: Some(_) }) => "[Getter/Setter]".to_string(),
+            Some(PropertyValue::Accessor { get: Some(_), set: None }) => "[Getter]".to_string(),
+            Some(PropertyValue::Accessor { .. }) => "[Setter]".to_string(),
+            None => "undefined".to_string(),
+        }
+    }
+
+    /// `[Function: name]`, or `[class Name extends Base]` for classes.
+    fn function_base(&self, id: ObjectId) -> String {
+        let name = self.function_name(id);
+        match &self.interpreter.heap.get(id).kind {
+            ObjectKind::Function(closure) if closure.definition.is_class_constructor() => {
+                let name = if name.is_empty() { "(anonymous)".to_string() } else { name };
+                match self.interpreter.heap.get(id).prototype {
+                    Some(parent) if parent != self.interpreter.intrinsics.function_prototype => {
+                        format!("[class {} extends {}]", name, self.function_name(parent))
+                    }
+                    _ => format!("[class {}]", name),
+                }
+            }
+            ObjectKind::Function(closure) if closure.definition.is_generator && closure.definition.is_async => {
+                if name.is_empty() {
+                    "[AsyncGeneratorFunction (anonymous)]".to_string()
+                } else {
+                    format!("[AsyncGeneratorFunction: {}]", name)
+                }
+            }
+            ObjectKind::Function(closure) if closure.definition.is_generator => {
+                if name.is_empty() {
+                    "[GeneratorFunction (anonymous)]".to_string()
+                } else {
+                    format!("[GeneratorFunction: {}]", name)
+                }
+            }
+            ObjectKind::Function(closure) if closure.definition.is_async => {
+                if name.is_empty() {
+                    "[AsyncFunction (anonymous)]".to_string()
+                } else {
+                    format!("[AsyncFunction: {}]", name)
+                }
+            }
+            _ if name.is_empty() => "[Function (anonymous)]".to_string(),
+            _ => format!("[Function: {}]", name),
+        }
+    }
+
+    fn function_name(&self, id: ObjectId) -> String {
+        match self.interpreter.heap.get(id).properties.get(&PropertyKey::from("name")) {
+            Some(Property { value: PropertyValue::Data(Value::String(name)), .. }) => name.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// The `Symbol.toStringTag` of an object, unless it is an own
+    /// enumerable property, which is shown with the others. Getters are
+    /// not run.
+    fn to_string_tag(&self, id: ObjectId) -> Option<String> {
+        let heap = &self.interpreter.heap;
+        let key = PropertyKey::Symbol(self.interpreter.symbols.to_string_tag.clone());
+        if heap.get(id).is_enumerable(&key) {
+            return None;
+        }
+        match heap.find_property(id, &key) {
+            Some(Property { value: PropertyValue::Data(Value::String(tag)), .. }) if !tag.is_empty() => Some(tag.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The name of the nearest constructor on the prototype chain, or `None`
+    /// for objects without a prototype.
+    fn constructor_name(&self, id: ObjectId) -> Option<String> {
+        let heap = &self.interpreter.heap;
+        let mut current = Some(heap.get(id).prototype?);
+        while let Some(prototype) = current {
+            if let Some(Property { value: PropertyValue::Data(Value::Function(constructor)), .. }) =
+                heap.get(prototype).properties.get(&PropertyKey::from("constructor"))
+            {
+                let name = self.function_name(*constructor);
+                if !name.is_empty() {
+                    return Some(name);
+                }
+            }
+            current = heap.get(prototype).prototype;
+        }
+        Some("Object".to_string())
+    }
+
+    /// Joins the entries of an object onto one line when they fit, or one
+    /// entry (or group of array entries) per line otherwise. `array` holds
+    /// whether every element is a number and whether the last entry is a
+    /// `... more items` note.
+    fn reduce_to_single_string(
+        &self,
+        output: Vec<String>,
+        base: &str,
+        braces: (&str, &str),
+        array: Option<(bool, bool)>,
+        may_combine: bool,
+    ) -> String {
+        let entries = output.len();
+        let output = match array {
+            Some((all_numbers, has_more_items)) if entries > 6 => self.group_array_elements(output, all_numbers, has_more_items),
+            _ => output,
+        };
+        let base = if base.is_empty() { String::new() } else { format!("{} ", base) };
+        if may_combine && entries == output.len() {
+            let start = output.len() + self.indentation + braces.0.chars().count() + base.chars().count() + 10;
+            if self.is_below_break_length(&output, start, &base) {
+                let joined = output.join(", ");
+                if !joined.contains('\n') {
+                    return format!("{}{} {} {}", base, braces.0, joined, braces.1);
+                }
+            }
+        }
+        let indentation = format!("\n{}", " ".repeat(self.indentation));
+        format!(
+            "{}{}{}  {}{}{}",
+            base,
+            braces.0,
+            indentation,
+            output.join(&format!(",{}  ", indentation)),
+            indentation,
+            braces.1
+        )
+    }
+
+    fn is_below_break_length(&self, output: &[String], start: usize, base: &str) -> bool {
+        let mut total = output.len() + start;
+        if total + output.len() > self.options.break_length {
+            return false;
+        }
+        for entry in output {
+            total += entry.chars().count();
+            if total > self.options.break_length {
+                return false;
+            }
+        }
+        !base.contains('\n')
+    }
+
+    /// Lays out many short array entries in aligned columns, numbers
+    /// right-aligned and everything else left-aligned.
+    fn group_array_elements(&self, output: Vec<String>, all_numbers: bool, has_more_items: bool) -> Vec<String> {
+        const SEPARATOR_SPACE: usize = 2;
+        let output_length = if has_more_items { output.len() - 1 } else { output.len() };
+        let data_len: Vec<usize> = output[..output_length].iter().map(|entry| entry.chars().count()).collect();
+        let total_length: usize = data_len.iter().map(|len| len + SEPARATOR_SPACE).sum();
+        let max_length = data_len.iter().copied().max().unwrap_or(0);
+        let actual_max = max_length + SEPARATOR_SPACE;
+
+        if actual_max * 3 + self.indentation >= self.options.break_length
+            || (total_length as f64 / actual_max as f64 <= 5.0 && max_length > 6)
+        {
+            return output;
+        }
+
+        let average_bias = (actual_max as f64 - total_length as f64 / output.len() as f64).sqrt();
+        let biased_max = (actual_max as f64 - 3.0 - average_bias).max(1.0);
+        let columns = ((2.5 * biased_max * output_length as f64).sqrt() / biased_max)
+            .round()
+            .min(((self.options.break_length - self.indentation) / actual_max) as f64)
+            // Node limits this to four times its `compact` setting of 3
+            .min(12.0)
+            .min(MAX_COLUMNS as f64) as usize;
+        if columns <= 1 {
+            return output;
+        }
+
+        let max_line_length: Vec<usize> = (0..columns)
+            .map(|column| data_len.iter().skip(column).step_by(columns).copied().max().unwrap_or(0) + SEPARATOR_SPACE)
+            .collect();
+        let mut grouped = Vec::new();
+        for row_start in (0..output_length).step_by(columns) {
+            let row_end = (row_start + columns).min(output_length);
+            let mut line = String::new();
+            for index in row_start..row_end {
+                let is_last = index == row_end - 1;
+                let cell = if is_last { output[index].clone() } else { format!("{}, ", output[index]) };
+                let width = max_line_length[index - row_start] - if is_last { SEPARATOR_SPACE } else { 0 };
+                let padding = " ".repeat(width.saturating_sub(cell.chars().count()));
+                if all_numbers {
+                    line.push_str(&padding);
+                    line.push_str(&cell);
+                } else if is_last {
+                    line.push_str(&cell);
+                } else {
+                    line.push_str(&cell);
+                    line.push_str(&padding);
+                }
+            }
+            grouped.push(line);
+        }
+        if has_more_items {
+            grouped.push(output[output_length].clone());
+        }
+        grouped
+    }
+}
+
+/// The name of the wrapper type for a primitive, as in `[Number: 1]`.
+fn primitive_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Number(_) => "Number",
+        Value::String(_) => "String",
+        Value::Boolean(_) => "Boolean",
+        Value::Symbol(_) => "Symbol",
+        Value::BigInt(_) => "BigInt",
+        _ => "Object",
+    }
+}
+
+/// Quotes a string with single quotes, or double quotes or backticks when
+/// that avoids escaping.
+fn quote(s: &str) -> String {
+    let quote = if !s.contains('\'') {
+        '\''
+    } else if !s.contains('"') {
+        '"'
+    } else if !s.contains('`') && !s.contains("${") {
+        '`'
+    } else {
+        '\''
+    };
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push(quote);
+    for c in s.chars() {
+        match c {
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '\u{8}' => result.push_str("\\b"),
+            '\u{c}' => result.push_str("\\f"),
+            '\\' => result.push_str("\\\\"),
+            c if c == quote => {
+                result.push('\\');
+                result.push(c);
+            }
+            c if c < ' ' || c == '\u{7f}' => result.push_str(&format!("\\x{:02X}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push(quote);
+    result
+}
+
+/// Whether a property name can be shown without quotes.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Formats a number as `console.log` shows it, keeping the sign of `-0`.
+pub(super) fn format_number(n: f64) -> String {
+    if n == 0.0 && n.is_sign_negative() {
+        "-0".to_string()
+    } else {
+        number_to_string(n)
+    }
+}
@@ -0,0 +1,725 @@
+//! The internal methods every object has, such as `[[Get]]` and
+//! `[[DefineOwnProperty]]`. Ordinary objects implement them on the heap,
+//! and proxies by calling the traps of their handler, whose results are
+//! checked against the invariants of the proxy's target.
+
+use std::collections::HashSet;
+
+use super::object::{is_compatible_descriptor, Attributes, ObjectId, ObjectKind, Property, PropertyDescriptor, PropertyKey, PropertyValue};
+use super::value::same_value;
+use super::{range_error, type_error, Interpreter, Value};
+
+/// A trap a proxy's handler defines for an internal method.
+struct Trap {
+    target: ObjectId,
+    handler: ObjectId,
+    function: Value,
+}
+
+/// What a proxy does for an internal method: call its handler's trap, or
+/// forward the operation to its target when the handler has none.
+enum ProxyAction {
+    Trap(Trap),
+    Forward(ObjectId),
+}
+
+impl Interpreter {
+    /// How the proxy `id` handles the internal method `trap`, or `None` if
+    /// `id` is an ordinary object.
+    fn proxy_action(&mut self, id: ObjectId, trap: &str) -> Result<Option<ProxyAction>, String> {
+        let ObjectKind::Proxy(proxy) = &self.heap.get(id).kind else {
+            return Ok(None);
+        };
+        let (Some(target), Some(handler)) = (proxy.target, proxy.handler) else {
+            return Err(type_error(format!("Cannot perform '{}' on a proxy that has been revoked", trap)));
+        };
+        // The trap may revoke the proxy, leaving the target and handler
+        // unreachable while they are still in use
+        self.heap.temporaries.extend([target, handler]);
+        let handler_value = self.object_value(handler);
+        Ok(Some(match self.get_method(&handler_value, trap)? {
+            Some(function) => ProxyAction::Trap(Trap { target, handler, function }),
+            None => ProxyAction::Forward(target),
+        }))
+    }
+
+    /// Calls a trap with the proxy's target followed by `args`.
+    fn call_trap(&mut self, trap: &Trap, args: Vec<Value>) -> Result<Value, String> {
+        let handler = self.object_value(trap.handler);
+        let args = std::iter::once(self.object_value(trap.target)).chain(args).collect();
+        self.call_function(&trap.function, handler, args)
+    }
+
+    /// `[[GetPrototypeOf]]`
+    pub(super) fn get_prototype_of(&mut self, id: ObjectId) -> Result<Option<ObjectId>, String> {
+        let trap = match self.proxy_action(id, "getPrototypeOf")? {
+            None => return Ok(self.heap.get(id).prototype),
+            Some(ProxyAction::Forward(target)) => return self.get_prototype_of(target),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        let prototype = match self.call_trap(&trap, Vec::new())? {
+            Value::Object(prototype) | Value::Function(prototype) => Some(prototype),
+            Value::Null => None,
+            _ => return Err(type_error("'getPrototypeOf' on proxy: trap returned neither object nor null")),
+        };
+        if !self.is_extensible(trap.target)? && prototype != self.get_prototype_of(trap.target)? {
+            return Err(type_error(
+                "'getPrototypeOf' on proxy: proxy target is non-extensible but the trap did not return its actual prototype",
+            ));
+        }
+        Ok(prototype)
+    }
+
+    /// `[[SetPrototypeOf]]`: false if the object refuses the change, as a
+    /// non-extensible object or one that would end up in a cycle does.
+    pub(super) fn set_prototype_of(&mut self, id: ObjectId, prototype: Option<ObjectId>) -> Result<bool, String> {
+        let trap = match self.proxy_action(id, "setPrototypeOf")? {
+            None => return Ok(self.ordinary_set_prototype_of(id, prototype)),
+            Some(ProxyAction::Forward(target)) => return self.set_prototype_of(target, prototype),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        let prototype_value = prototype.map_or(Value::Null, |prototype| self.object_value(prototype));
+        if !self.call_trap(&trap, vec![prototype_value])?.is_truthy() {
+            return Ok(false);
+        }
+        if !self.is_extensible(trap.target)? && prototype != self.get_prototype_of(trap.target)? {
+            return Err(type_error(
+                "'setPrototypeOf' on proxy: trap returned truish for setting a new prototype on the non-extensible proxy target",
+            ));
+        }
+        Ok(true)
+    }
+
+    fn ordinary_set_prototype_of(&mut self, id: ObjectId, prototype: Option<ObjectId>) -> bool {
+        let object = self.heap.get(id);
+        if object.prototype == prototype {
+            return true;
+        }
+        if !object.extensible {
+            return false;
+        }
+        // Proxies end the search for cycles, since their prototype is
+        // only known by asking them
+        let mut current = prototype;
+        while let Some(ancestor) = current {
+            if ancestor == id {
+                return false;
+            }
+            let ancestor = self.heap.get(ancestor);
+            if matches!(ancestor.kind, ObjectKind::Proxy(_)) {
+                break;
+            }
+            current = ancestor.prototype;
+        }
+        self.heap.get_mut(id).prototype = prototype;
+        true
+    }
+
+    /// `[[IsExtensible]]`
+    pub(super) fn is_extensible(&mut self, id: ObjectId) -> Result<bool, String> {
+        let trap = match self.proxy_action(id, "isExtensible")? {
+            None => return Ok(self.heap.get(id).extensible),
+            Some(ProxyAction::Forward(target)) => return self.is_extensible(target),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        let extensible = self.call_trap(&trap, Vec::new())?.is_truthy();
+        let target_extensible = self.is_extensible(trap.target)?;
+        if extensible != target_extensible {
+            return Err(type_error(format!(
+                "'isExtensible' on proxy: trap result does not reflect extensibility of proxy target (which is '{}')",
+                target_extensible
+            )));
+        }
+        Ok(extensible)
+    }
+
+    /// `[[PreventExtensions]]`
+    pub(super) fn prevent_extensions(&mut self, id: ObjectId) -> Result<bool, String> {
+        let trap = match self.proxy_action(id, "preventExtensions")? {
+            None => {
+                self.heap.get_mut(id).extensible = false;
+                return Ok(true);
+            }
+            Some(ProxyAction::Forward(target)) => return self.prevent_extensions(target),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        let prevented = self.call_trap(&trap, Vec::new())?.is_truthy();
+        if prevented && self.is_extensible(trap.target)? {
+            return Err(type_error("'preventExtensions' on proxy: trap returned truish but the proxy target is extensible"));
+        }
+        Ok(prevented)
+    }
+
+    /// `[[GetOwnProperty]]`
+    pub(super) fn get_own_property(&mut self, id: ObjectId, key: &PropertyKey) -> Result<Option<Property>, String> {
+        let trap = match self.proxy_action(id, "getOwnPropertyDescriptor")? {
+            None => {
+                if let Some(index) = self.typed_array_element(id, key) {
+                    return Ok(index.map(|index| Property::data(self.read_element(id, index), Attributes::DEFAULT)));
+                }
+                return Ok(self.heap.get(id).properties.get(key).cloned());
+            }
+            Some(ProxyAction::Forward(target)) => return self.get_own_property(target, key),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        let result = self.call_trap(&trap, vec![key.to_value()])?;
+        let target_property = self.get_own_property(trap.target, key)?;
+        let fail = |problem: &str| type_error(format!("'getOwnPropertyDescriptor' on proxy: {}", problem.replace("{}", &key.to_string())));
+        if matches!(result, Value::Undefined) {
+            let Some(target_property) = target_property else {
+                return Ok(None);
+            };
+            if !target_property.attributes.configurable {
+                return Err(fail("trap returned undefined for property '{}' which is non-configurable in the proxy target"));
+            }
+            if !self.is_extensible(trap.target)? {
+                return Err(fail("trap returned undefined for property '{}' which exists in the non-extensible proxy target"));
+            }
+            return Ok(None);
+        }
+        if !matches!(result, Value::Object(_) | Value::Function(_)) {
+            return Err(fail("trap returned neither object nor undefined for property '{}'"));
+        }
+        let extensible = self.is_extensible(trap.target)?;
+        let descriptor = self.descriptor_from_object(&result)?;
+        if !is_compatible_descriptor(extensible, &descriptor, target_property.as_ref()) {
+            return Err(fail("trap returned descriptor for property '{}' that is incompatible with the existing property in the proxy target"));
+        }
+        let non_writable = descriptor.writable == Some(false);
+        let property = descriptor.complete();
+        if !property.attributes.configurable {
+            match &target_property {
+                Some(target) if !target.attributes.configurable => {
+                    if non_writable && target.attributes.writable {
+                        return Err(fail("trap reported non-configurable and non-writable for property '{}' which is writable in the proxy target"));
+                    }
+                }
+                _ => {
+                    return Err(fail(
+                        "trap reported non-configurability for property '{}' which is either non-existent or configurable in the proxy target",
+                    ))
+                }
+            }
+        }
+        Ok(Some(property))
+    }
+
+    /// `[[DefineOwnProperty]]`: false if the object refuses the definition.
+    pub(super) fn define_own_property(&mut self, id: ObjectId, key: PropertyKey, mut descriptor: PropertyDescriptor) -> Result<bool, String> {
+        let trap = match self.proxy_action(id, "defineProperty")? {
+            None => {
+                // Elements are always writable, enumerable and configurable
+                // data properties
+                if let Some(index) = self.typed_array_element(id, &key) {
+                    let fixed = descriptor.configurable == Some(false) || descriptor.enumerable == Some(false) || descriptor.writable == Some(false);
+                    let (Some(index), false, false) = (index, fixed, descriptor.is_accessor()) else {
+                        return Ok(false);
+                    };
+                    if let Some(value) = &descriptor.value {
+                        self.write_element(id, Some(index), value)?;
+                    }
+                    return Ok(true);
+                }
+                if self.heap.get(id).is_array() && key == PropertyKey::from("length") {
+                    if let Some(length) = &descriptor.value {
+                        let length = self.coerce_to_number(length)?;
+                        if length < 0.0 || length.fract() != 0.0 || length > u32::MAX as f64 {
+                            return Err(range_error("Invalid array length"));
+                        }
+                        descriptor.value = Some(Value::Number(length));
+                    }
+                }
+                return Ok(self.heap.define_own_property(id, key, descriptor));
+            }
+            Some(ProxyAction::Forward(target)) => return self.define_own_property(target, key, descriptor),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        let descriptor_object = self.descriptor_object(&descriptor);
+        if !self.call_trap(&trap, vec![key.to_value(), descriptor_object])?.is_truthy() {
+            return Ok(false);
+        }
+        let target_property = self.get_own_property(trap.target, &key)?;
+        let extensible = self.is_extensible(trap.target)?;
+        let fail = |problem: &str| type_error(format!("'defineProperty' on proxy: {}", problem.replace("{}", &key.to_string())));
+        let non_configurable = descriptor.configurable == Some(false);
+        let Some(target_property) = target_property else {
+            if !extensible {
+                return Err(fail("trap returned truish for adding property '{}' to the non-extensible proxy target"));
+            }
+            if non_configurable {
+                return Err(fail(
+                    "trap returned truish for defining non-configurable property '{}' which is either non-existent or configurable in the proxy target",
+                ));
+            }
+            return Ok(true);
+        };
+        if !is_compatible_descriptor(extensible, &descriptor, Some(&target_property)) {
+            return Err(fail("trap returned truish for adding property '{}' that is incompatible with the existing property in the proxy target"));
+        }
+        if non_configurable && target_property.attributes.configurable {
+            return Err(fail(
+                "trap returned truish for defining non-configurable property '{}' which is either non-existent or configurable in the proxy target",
+            ));
+        }
+        let writable_data = matches!(target_property.value, PropertyValue::Data(_)) && target_property.attributes.writable;
+        if writable_data && !target_property.attributes.configurable && descriptor.writable == Some(false) {
+            return Err(fail(
+                "trap returned truish for defining non-configurable property '{}' which cannot be non-writable, unless there exists a corresponding non-configurable, non-writable own property of the target object.",
+            ));
+        }
+        Ok(true)
+    }
+
+    /// `DefinePropertyOrThrow`
+    pub(super) fn define_property_or_throw(&mut self, id: ObjectId, key: PropertyKey, descriptor: PropertyDescriptor) -> Result<(), String> {
+        let element = self.typed_array_element(id, &key);
+        let exists = self.heap.get(id).properties.contains_key(&key) || element.is_some_and(|index| index.is_some());
+        if self.define_own_property(id, key.clone(), descriptor)? {
+            return Ok(());
+        }
+        Err(if matches!(self.heap.get(id).kind, ObjectKind::Proxy(_)) {
+            type_error(format!("'defineProperty' on proxy: trap returned falsish for property '{}'", key))
+        } else if element == Some(None) {
+            type_error("Invalid typed array index")
+        } else if exists {
+            type_error(format!("Cannot redefine property: {}", key))
+        } else {
+            type_error(format!("Cannot define property {}, object is not extensible", key))
+        })
+    }
+
+    /// `[[HasProperty]]`: whether `key` exists on the object or its
+    /// prototype chain.
+    pub(super) fn has_property(&mut self, id: ObjectId, key: &PropertyKey) -> Result<bool, String> {
+        let trap = match self.proxy_action(id, "has")? {
+            None => {
+                if let Some(index) = self.typed_array_element(id, key) {
+                    return Ok(index.is_some());
+                }
+                let object = self.heap.get(id);
+                if object.properties.contains_key(key) {
+                    return Ok(true);
+                }
+                return match object.prototype {
+                    Some(prototype) => self.has_property(prototype, key),
+                    None => Ok(false),
+                };
+            }
+            Some(ProxyAction::Forward(target)) => return self.has_property(target, key),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        let found = self.call_trap(&trap, vec![key.to_value()])?.is_truthy();
+        if !found {
+            if let Some(target_property) = self.get_own_property(trap.target, key)? {
+                if !target_property.attributes.configurable {
+                    return Err(type_error(format!(
+                        "'has' on proxy: trap returned falsish for property '{}' which exists in the proxy target as non-configurable",
+                        key
+                    )));
+                }
+                if !self.is_extensible(trap.target)? {
+                    return Err(type_error(format!(
+                        "'has' on proxy: trap returned falsish for property '{}' but the proxy target is not extensible",
+                        key
+                    )));
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// `[[Get]]`: reads `key` from the object or its prototypes, running
+    /// getters with `receiver` as `this`.
+    pub(super) fn get_property(&mut self, id: ObjectId, key: &PropertyKey, receiver: &Value) -> Result<Value, String> {
+        let value = self.find_property_value(id, key, receiver)?;
+        self.hold(&value);
+        Ok(value)
+    }
+
+    /// `get_property`, leaving the value unheld.
+    fn find_property_value(&mut self, id: ObjectId, key: &PropertyKey, receiver: &Value) -> Result<Value, String> {
+        let mut current = id;
+        let trap = loop {
+            let object = self.heap.get(current);
+            if matches!(object.kind, ObjectKind::Proxy(_)) {
+                match self.proxy_action(current, "get")? {
+                    Some(ProxyAction::Trap(trap)) => break trap,
+                    Some(ProxyAction::Forward(target)) => current = target,
+                    None => unreachable!("checked to be a proxy"),
+                }
+                continue;
+            }
+            // Numeric keys of typed arrays never reach the prototype chain
+            if let Some(index) = self.typed_array_element(current, key) {
+                return Ok(index.map_or(Value::Undefined, |index| self.read_element(current, index)));
+            }
+            match object.properties.get(key).map(|property| &property.value) {
+                Some(PropertyValue::Data(value)) => return Ok(value.clone()),
+                Some(PropertyValue::Accessor { get: Some(getter), .. }) => {
+                    let getter = getter.clone();
+                    return self.call_function(&getter, receiver.clone(), Vec::new());
+                }
+                Some(PropertyValue::Accessor { get: None, .. }) => return Ok(Value::Undefined),
+                None => match object.prototype {
+                    Some(prototype) => current = prototype,
+                    None => return Ok(Value::Undefined),
+                },
+            }
+        };
+        let value = self.call_trap(&trap, vec![key.to_value(), receiver.clone()])?;
+        if let Some(target_property) = self.get_own_property(trap.target, key)? {
+            if !target_property.attributes.configurable {
+                match &target_property.value {
+                    PropertyValue::Data(expected) if !target_property.attributes.writable && !same_value(expected, &value) => {
+                        return Err(type_error(format!(
+                            "'get' on proxy: property '{}' is a read-only and non-configurable data property on the proxy target but the proxy did not return its actual value (expected '{}' but got '{}')",
+                            key,
+                            self.to_display_string(expected),
+                            self.to_display_string(&value)
+                        )));
+                    }
+                    PropertyValue::Accessor { get: None, .. } if !matches!(value, Value::Undefined) => {
+                        return Err(type_error(format!(
+                            "'get' on proxy: property '{}' is a non-configurable accessor property on the proxy target and does not have a getter function, but the trap did not return 'undefined' (got '{}')",
+                            key,
+                            self.to_display_string(&value)
+                        )));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    /// `[[Set]]`: assigns `key`, calling a setter found on the object or
+    /// its prototypes with `receiver` as `this`, or else creating or
+    /// updating a data property on `receiver`. False if the assignment is
+    /// refused, as it is for read-only properties.
+    pub(super) fn set_property(&mut self, id: ObjectId, key: &PropertyKey, value: Value, receiver: &Value) -> Result<bool, String> {
+        let trap = match self.proxy_action(id, "set")? {
+            None => return self.ordinary_set(id, key, value, receiver),
+            Some(ProxyAction::Forward(target)) => return self.set_property(target, key, value, receiver),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        if !self.call_trap(&trap, vec![key.to_value(), value.clone(), receiver.clone()])?.is_truthy() {
+            return Ok(false);
+        }
+        if let Some(target_property) = self.get_own_property(trap.target, key)? {
+            if !target_property.attributes.configurable {
+                match &target_property.value {
+                    PropertyValue::Data(current) if !target_property.attributes.writable && !same_value(current, &value) => {
+                        return Err(type_error(format!(
+                            "'set' on proxy: trap returned truish for property '{}' which exists in the proxy target as a non-configurable and non-writable data property with a different value",
+                            key
+                        )));
+                    }
+                    PropertyValue::Accessor { set: None, .. } => {
+                        return Err(type_error(format!(
+                            "'set' on proxy: trap returned truish for property '{}' which exists in the proxy target as a non-configurable and non-writable accessor property without a setter",
+                            key
+                        )));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// The `OrdinarySet` operation.
+    fn ordinary_set(&mut self, id: ObjectId, key: &PropertyKey, value: Value, receiver: &Value) -> Result<bool, String> {
+        // A typed array stores its elements in place, and ignores numeric
+        // keys out of range
+        let element = self.typed_array_element(id, key);
+        if let Some(index) = element {
+            if matches!(receiver, Value::Object(receiver) if *receiver == id) {
+                self.write_element(id, index, &value)?;
+                return Ok(true);
+            }
+            if index.is_none() {
+                return Ok(true);
+            }
+        }
+        let object = self.heap.get(id);
+        let own = match object.properties.get(key) {
+            _ if element.is_some() => Some((PropertyValue::Data(Value::Undefined), true)),
+            Some(property) => Some((property.value.clone(), property.attributes.writable)),
+            None => match object.prototype {
+                Some(prototype) => return self.set_property(prototype, key, value, receiver),
+                None => None,
+            },
+        };
+        match own {
+            Some((PropertyValue::Accessor { set: Some(setter), .. }, _)) => {
+                self.call_function(&setter, receiver.clone(), vec![value])?;
+                return Ok(true);
+            }
+            Some((PropertyValue::Accessor { set: None, .. }, _)) | Some((PropertyValue::Data(_), false)) => return Ok(false),
+            Some((PropertyValue::Data(_), true)) | None => {}
+        }
+        let (Value::Object(receiver_id) | Value::Function(receiver_id)) = *receiver else {
+            return Ok(false);
+        };
+
+        // Ordinary receivers are written directly, except for the `length`
+        // of arrays, which is checked on the way
+        let receiver_object = self.heap.get(receiver_id);
+        let is_array_length = receiver_object.is_array() && *key == PropertyKey::from("length");
+        if !matches!(receiver_object.kind, ObjectKind::Proxy(_) | ObjectKind::TypedArray(_)) && !is_array_length {
+            match receiver_object.properties.get(key) {
+                Some(Property { value: PropertyValue::Data(_), attributes }) if attributes.writable => {}
+                Some(_) => return Ok(false),
+                None if !receiver_object.extensible => return Ok(false),
+                None => {}
+            }
+            self.heap.set_property(receiver_id, key.clone(), value);
+            return Ok(true);
+        }
+
+        let descriptor = match self.get_own_property(receiver_id, key)? {
+            Some(existing) if matches!(existing.value, PropertyValue::Accessor { .. }) || !existing.attributes.writable => return Ok(false),
+            Some(_) => PropertyDescriptor { value: Some(value), ..PropertyDescriptor::default() },
+            None => PropertyDescriptor {
+                value: Some(value),
+                writable: Some(true),
+                enumerable: Some(true),
+                configurable: Some(true),
+                ..PropertyDescriptor::default()
+            },
+        };
+        self.define_own_property(receiver_id, key.clone(), descriptor)
+    }
+
+    /// `[[Delete]]`: false if the property cannot be deleted.
+    pub(super) fn delete_property(&mut self, id: ObjectId, key: &PropertyKey) -> Result<bool, String> {
+        let trap = match self.proxy_action(id, "deleteProperty")? {
+            None => {
+                if let Some(index) = self.typed_array_element(id, key) {
+                    return Ok(index.is_none());
+                }
+                return Ok(self.heap.delete_property(id, key));
+            }
+            Some(ProxyAction::Forward(target)) => return self.delete_property(target, key),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        if !self.call_trap(&trap, vec![key.to_value()])?.is_truthy() {
+            return Ok(false);
+        }
+        if let Some(target_property) = self.get_own_property(trap.target, key)? {
+            if !target_property.attributes.configurable {
+                return Err(type_error(format!(
+                    "'deleteProperty' on proxy: trap returned truish for property '{}' which is non-configurable in the proxy target",
+                    key
+                )));
+            }
+            if !self.is_extensible(trap.target)? {
+                return Err(type_error(format!(
+                    "'deleteProperty' on proxy: trap returned truish for property '{}' but the proxy target is non-extensible",
+                    key
+                )));
+            }
+        }
+        Ok(true)
+    }
+
+    /// `[[OwnPropertyKeys]]`: indices, then strings, then symbols.
+    pub(super) fn own_property_keys(&mut self, id: ObjectId) -> Result<Vec<PropertyKey>, String> {
+        let trap = match self.proxy_action(id, "ownKeys")? {
+            None => {
+                let object = self.heap.get(id);
+                let mut keys = object.own_property_keys();
+                if let ObjectKind::TypedArray(data) = &object.kind {
+                    keys.splice(0..0, (0..data.length as u32).map(PropertyKey::Index));
+                }
+                return Ok(keys);
+            }
+            Some(ProxyAction::Forward(target)) => return self.own_property_keys(target),
+            Some(ProxyAction::Trap(trap)) => trap,
+        };
+        let result = self.call_trap(&trap, Vec::new())?;
+        let keys = self.property_key_list(&result)?;
+        let mut unchecked = HashSet::new();
+        for key in &keys {
+            if !unchecked.insert(key.clone()) {
+                return Err(type_error("'ownKeys' on proxy: trap returned duplicate entries"));
+            }
+        }
+
+        // The keys of non-configurable properties must be reported, and
+        // if the target is not extensible, exactly its own keys
+        let extensible = self.is_extensible(trap.target)?;
+        let mut configurable = Vec::new();
+        let mut non_configurable = Vec::new();
+        for key in self.own_property_keys(trap.target)? {
+            match self.get_own_property(trap.target, &key)? {
+                Some(property) if !property.attributes.configurable => non_configurable.push(key),
+                _ => configurable.push(key),
+            }
+        }
+        if extensible && non_configurable.is_empty() {
+            return Ok(keys);
+        }
+        let checked = if extensible { non_configurable } else { non_configurable.into_iter().chain(configurable).collect() };
+        for key in checked {
+            if !unchecked.remove(&key) {
+                return Err(type_error(format!("'ownKeys' on proxy: trap result did not include '{}'", key)));
+            }
+        }
+        if !extensible && !unchecked.is_empty() {
+            return Err(type_error("'ownKeys' on proxy: trap returned extra keys but proxy target is non-extensible"));
+        }
+        Ok(keys)
+    }
+
+    /// `CreateListFromArrayLike` for the result of an `ownKeys` trap,
+    /// whose elements must all be strings or symbols.
+    fn property_key_list(&mut self, value: &Value) -> Result<Vec<PropertyKey>, String> {
+        let mut keys = Vec::new();
+        for element in self.create_list_from_array_like(value)? {
+            match element {
+                Value::String(name) => keys.push(PropertyKey::from(name)),
+                Value::Symbol(symbol) => keys.push(PropertyKey::Symbol(symbol)),
+                other => return Err(type_error(format!("{} is not a valid property name", self.to_display_string(&other)))),
+            }
+        }
+        Ok(keys)
+    }
+
+    /// The `CreateListFromArrayLike` operation: the elements of any object
+    /// with a `length`.
+    pub(super) fn create_list_from_array_like(&mut self, value: &Value) -> Result<Vec<Value>, String> {
+        if !matches!(value, Value::Object(_) | Value::Function(_)) {
+            return Err(type_error("CreateListFromArrayLike called on non-object"));
+        }
+        let length = self.get_member(value, &PropertyKey::from("length"))?;
+        let length = self.coerce_to_number(&length)?;
+        let length = if length.is_nan() { 0 } else { length.clamp(0.0, u32::MAX as f64) as u32 };
+        let mut elements = Vec::with_capacity(length as usize);
+        for index in 0..length {
+            elements.push(self.get_member(value, &PropertyKey::Index(index))?);
+        }
+        Ok(elements)
+    }
+
+    /// `[[Call]]` on a proxy.
+    pub(super) fn call_proxy(&mut self, id: ObjectId, this: Value, args: Vec<Value>) -> Result<Value, String> {
+        match self.proxy_action(id, "apply")? {
+            Some(ProxyAction::Trap(trap)) => {
+                let args = self.create_array(args);
+                self.call_trap(&trap, vec![this, args])
+            }
+            Some(ProxyAction::Forward(target)) => self.call_function(&Value::Function(target), this, args),
+            None => unreachable!("call_proxy called on a non-proxy"),
+        }
+    }
+
+    /// `[[Construct]]` on a proxy.
+    pub(super) fn construct_proxy(&mut self, id: ObjectId, args: Vec<Value>, new_target: &Value) -> Result<Value, String> {
+        match self.proxy_action(id, "construct")? {
+            Some(ProxyAction::Trap(trap)) => {
+                let args = self.create_array(args);
+                match self.call_trap(&trap, vec![args, new_target.clone()])? {
+                    object @ (Value::Object(_) | Value::Function(_)) => Ok(object),
+                    other => Err(type_error(format!(
+                        "'construct' on proxy: trap returned non-object ('{}')",
+                        self.to_display_string(&other)
+                    ))),
+                }
+            }
+            Some(ProxyAction::Forward(target)) => self.construct(&Value::Function(target), args, new_target),
+            None => unreachable!("construct_proxy called on a non-proxy"),
+        }
+    }
+
+    /// The `revoke` function of `Proxy.revocable`, which cuts its proxy
+    /// off from the target and handler.
+    pub(super) fn revoke_proxy(&mut self, revoker: ObjectId) {
+        let ObjectKind::RevokeProxy(proxy) = &mut self.heap.get_mut(revoker).kind else {
+            unreachable!("revoke_proxy called on a non-revoker")
+        };
+        if let Some(proxy) = proxy.take() {
+            if let ObjectKind::Proxy(proxy) = &mut self.heap.get_mut(proxy).kind {
+                proxy.target = None;
+                proxy.handler = None;
+            }
+        }
+    }
+
+    /// The `IsArray` operation, which sees through proxies.
+    pub(super) fn is_array(&self, value: &Value) -> Result<bool, String> {
+        let (Value::Object(id) | Value::Function(id)) = value else {
+            return Ok(false);
+        };
+        match &self.heap.get(*id).kind {
+            ObjectKind::Array => Ok(true),
+            ObjectKind::Proxy(proxy) => match proxy.target {
+                Some(target) => self.is_array(&self.object_value(target)),
+                None => Err(type_error("Cannot perform 'IsArray' on a proxy that has been revoked")),
+            },
+            _ => Ok(false),
+        }
+    }
+
+    /// The `ToPropertyDescriptor` operation on a descriptor object, as
+    /// `Object.defineProperty` accepts.
+    pub(super) fn descriptor_from_object(&mut self, value: &Value) -> Result<PropertyDescriptor, String> {
+        let (Value::Object(id) | Value::Function(id)) = *value else {
+            return Err(type_error(format!(
+                "Property description must be an object: {}",
+                self.to_display_string(value)
+            )));
+        };
+        let field = |interpreter: &mut Interpreter, name: &str| -> Result<Option<Value>, String> {
+            let key = PropertyKey::from(name);
+            if !interpreter.has_property(id, &key)? {
+                return Ok(None);
+            }
+            interpreter.get_member(value, &key).map(Some)
+        };
+
+        let mut descriptor = PropertyDescriptor {
+            enumerable: field(self, "enumerable")?.map(|v| v.is_truthy()),
+            configurable: field(self, "configurable")?.map(|v| v.is_truthy()),
+            value: field(self, "value")?,
+            writable: field(self, "writable")?.map(|v| v.is_truthy()),
+            ..PropertyDescriptor::default()
+        };
+        for (name, slot) in [("get", &mut descriptor.get), ("set", &mut descriptor.set)] {
+            let accessor = field(self, name)?;
+            if let Some(function) = &accessor {
+                if !matches!(function, Value::Function(_) | Value::Undefined) {
+                    let kind = if name == "get" { "Getter" } else { "Setter" };
+                    return Err(type_error(format!("{} must be a function: {}", kind, self.to_display_string(function))));
+                }
+            }
+            *slot = accessor;
+        }
+        if descriptor.is_accessor() && descriptor.is_data() {
+            return Err(type_error(
+                "Invalid property descriptor. Cannot both specify accessors and a value or writable attribute",
+            ));
+        }
+        Ok(descriptor)
+    }
+
+    /// The `FromPropertyDescriptor` operation: the descriptor as an
+    /// object with only the fields it has.
+    pub(super) fn descriptor_object(&mut self, descriptor: &PropertyDescriptor) -> Value {
+        let id = self.create_object();
+        let fields = [
+            ("value", descriptor.value.clone()),
+            ("writable", descriptor.writable.map(Value::Boolean)),
+            ("get", descriptor.get.clone()),
+            ("set", descriptor.set.clone()),
+            ("enumerable", descriptor.enumerable.map(Value::Boolean)),
+            ("configurable", descriptor.configurable.map(Value::Boolean)),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                self.heap.define_property(id, PropertyKey::from(name), Property::data(value, Attributes::DEFAULT));
+            }
+        }
+        Value::Object(id)
+    }
+}
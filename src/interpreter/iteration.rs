@@ -0,0 +1,359 @@
+//! The iteration protocol behind `for...of`, `for await`, spread and
+//! destructuring, and the built-in iterators over arrays and strings.
+
+use super::builtins;
+use super::collection::IterationKind;
+use super::generator::{GeneratorStep, ResumeAction};
+use super::object::{Attributes, Object, ObjectId, ObjectKind, Property, PropertyKey};
+use super::{type_error, Interpreter, JsString, Value};
+
+/// An iterator being stepped through, with its `next` method read once at
+/// the start.
+#[derive(Clone, Debug)]
+pub struct Iteration {
+    pub iterator: Value,
+    pub next: Value,
+    /// Set once the iterator is exhausted, has thrown, or was closed
+    pub done: bool,
+    /// Whether `next` is the built-in method for the kind of iterator, so
+    /// that steps can skip creating result objects
+    native: bool,
+}
+
+/// The state of an iterator returned by `Iterator.prototype.map` and the
+/// other lazy helpers.
+#[derive(Debug)]
+pub struct IteratorHelper {
+    pub operation: HelperOperation,
+    pub underlying: Iteration,
+    /// How many values the underlying iterator has produced
+    pub counter: f64,
+    /// Set while `next` runs, so the helper cannot be re-entered
+    pub running: bool,
+    /// The iterator `flatMap` is currently flattening
+    pub inner: Option<Iteration>,
+}
+
+#[derive(Debug)]
+pub enum HelperOperation {
+    Map(Value),
+    Filter(Value),
+    /// The number of values still to be taken
+    Take(f64),
+    /// The number of values still to be skipped
+    Drop(f64),
+    FlatMap(Value),
+}
+
+/// The state of an iterator returned by `values`, `keys` or `entries` of
+/// an array or array-like object.
+#[derive(Debug)]
+pub struct ArrayIterator {
+    /// The object iterated, or `None` once the iterator is exhausted
+    pub target: Option<Value>,
+    pub index: u32,
+    pub kind: IterationKind,
+}
+
+/// The state of an iterator over the code points of a string.
+#[derive(Debug)]
+pub struct StringIterator {
+    pub string: JsString,
+    /// The index of the code unit that starts the next code point
+    pub position: usize,
+}
+
+impl Interpreter {
+    /// The `GetIterator` operation: starts iterating `value` through its
+    /// `Symbol.iterator` method.
+    pub(super) fn iterate(&mut self, value: &Value) -> Result<Iteration, String> {
+        let method = match value {
+            Value::Null | Value::Undefined => Value::Undefined,
+            _ => self.get_member(value, &PropertyKey::Symbol(self.symbols.iterator.clone()))?,
+        };
+        if !matches!(method, Value::Function(_)) {
+            return Err(type_error(format!("{} is not iterable", self.to_display_string(value))));
+        }
+        self.iterate_with(value, &method)
+    }
+
+    /// Starts iterating `value` with the iterator `method` returns.
+    pub(super) fn iterate_with(&mut self, value: &Value, method: &Value) -> Result<Iteration, String> {
+        let iterator = self.call_function(method, value.clone(), Vec::new())?;
+        if !matches!(iterator, Value::Object(_) | Value::Function(_)) {
+            return Err(type_error("Result of the Symbol.iterator method is not an object"));
+        }
+        self.iteration_of(iterator)
+    }
+
+    /// The `GetIteratorDirect` operation: an iteration over an object that
+    /// already is an iterator.
+    pub(super) fn iteration_of(&mut self, iterator: Value) -> Result<Iteration, String> {
+        let next = self.get_member(&iterator, &PropertyKey::from("next"))?;
+        self.hold(&iterator);
+        self.hold(&next);
+        let native = match (&iterator, &next) {
+            (Value::Object(iterator), Value::Function(next)) => match self.heap.get(*next).kind {
+                ObjectKind::NativeFunction(native) => builtins::is_native_next(native, &self.heap.get(*iterator).kind),
+                _ => false,
+            },
+            _ => false,
+        };
+        Ok(Iteration { iterator, next, done: false, native })
+    }
+
+    /// The `Symbol.iterator` method of `value`, or `None` if it has none.
+    pub(super) fn iterator_method(&mut self, value: &Value) -> Result<Option<Value>, String> {
+        self.get_method_by_key(value, &PropertyKey::Symbol(self.symbols.iterator.clone()))
+    }
+
+    /// The next value of an iteration, or `None` when it is finished. An
+    /// iterator that throws is finished too, and is not closed.
+    pub(super) fn iteration_step(&mut self, iteration: &mut Iteration) -> Result<Option<Value>, String> {
+        if iteration.done {
+            return Ok(None);
+        }
+        let step = if iteration.native {
+            self.step_natively(&iteration.iterator)
+        } else {
+            self.call_function(&iteration.next, iteration.iterator.clone(), Vec::new())
+                .and_then(|result| self.unpack_iterator_result(result))
+        };
+        match &step {
+            Ok(Some(value)) => self.hold(value),
+            _ => iteration.done = true,
+        }
+        step
+    }
+
+    /// Steps a built-in iterator whose `next` has not been replaced.
+    fn step_natively(&mut self, iterator: &Value) -> Result<Option<Value>, String> {
+        let Value::Object(id) = *iterator else {
+            unreachable!("built-in iterators are objects")
+        };
+        match &self.heap.get(id).kind {
+            ObjectKind::ArrayIterator(_) => self.array_iterator_next(id),
+            ObjectKind::StringIterator(_) => Ok(self.string_iterator_next(id)),
+            ObjectKind::CollectionIterator(_) => Ok(self.collection_iterator_next(id)),
+            ObjectKind::Generator(_) => match self.resume_generator(id, ResumeAction::Next(Value::Undefined))? {
+                GeneratorStep::Yield(value) => Ok(Some(value)),
+                GeneratorStep::Delegated(result) => self.unpack_iterator_result(result),
+                GeneratorStep::Return(_) => Ok(None),
+                GeneratorStep::Await(_) => unreachable!("await in a generator"),
+            },
+            _ => unreachable!("not a built-in iterator"),
+        }
+    }
+
+    /// Starts a `for await` loop over `value`, with its `Symbol.asyncIterator`
+    /// method or, failing that, its `Symbol.iterator` method. The second
+    /// value is whether the iterator is synchronous, so that the values it
+    /// produces are awaited rather than its results.
+    pub(super) fn async_iterate(&mut self, value: &Value) -> Result<(Iteration, bool), String> {
+        let not_iterable = |interpreter: &mut Self| type_error(format!("{} is not async iterable", interpreter.to_display_string(value)));
+        if matches!(value, Value::Null | Value::Undefined) {
+            return Err(not_iterable(self));
+        }
+        if let Some(method) = self.get_method_by_key(value, &PropertyKey::Symbol(self.symbols.async_iterator.clone()))? {
+            let iterator = self.call_function(&method, value.clone(), Vec::new())?;
+            if !matches!(iterator, Value::Object(_) | Value::Function(_)) {
+                return Err(type_error("Result of the Symbol.asyncIterator method is not an object"));
+            }
+            return Ok((self.iteration_of(iterator)?, false));
+        }
+        match self.iterator_method(value)? {
+            Some(method) => Ok((self.iterate_with(value, &method)?, true)),
+            None => Err(not_iterable(self)),
+        }
+    }
+
+    /// Advances a `for await` loop, awaiting the iterator's result, or the
+    /// value a synchronous iterator produces.
+    pub(super) fn async_iteration_step(&mut self, iteration: &mut Iteration, from_sync: bool) -> Result<Option<Value>, String> {
+        if from_sync {
+            return match self.iteration_step(iteration)? {
+                Some(value) => self.await_value(value).map(Some),
+                None => Ok(None),
+            };
+        }
+        if iteration.done {
+            return Ok(None);
+        }
+        let step = self
+            .call_function(&iteration.next, iteration.iterator.clone(), Vec::new())
+            .and_then(|result| self.await_value(result))
+            .and_then(|result| self.unpack_iterator_result(result));
+        if !matches!(step, Ok(Some(_))) {
+            iteration.done = true;
+        }
+        step
+    }
+
+    /// The value of an iterator result object, or `None` if it is done.
+    fn unpack_iterator_result(&mut self, result: Value) -> Result<Option<Value>, String> {
+        if !matches!(result, Value::Object(_) | Value::Function(_)) {
+            return Err(type_error(format!("Iterator result {} is not an object", self.to_display_string(&result))));
+        }
+        if self.get_member(&result, &PropertyKey::from("done"))?.is_truthy() {
+            return Ok(None);
+        }
+        self.get_member(&result, &PropertyKey::from("value")).map(Some)
+    }
+
+    /// The `IteratorClose` operation: calls the iterator's `return` method
+    /// when an iteration stops before the iterator is done, then carries
+    /// on with `completion`. An exception thrown by `return` replaces a
+    /// normal completion but never an exception already propagating.
+    pub(super) fn close_iteration<T>(&mut self, iteration: &mut Iteration, completion: Result<T, String>) -> Result<T, String> {
+        self.close_iteration_awaiting(iteration, completion, false)
+    }
+
+    /// `close_iteration` for a `for await` loop over an async iterator,
+    /// which awaits what `return` returns.
+    pub(super) fn close_async_iteration<T>(&mut self, iteration: &mut Iteration, completion: Result<T, String>) -> Result<T, String> {
+        self.close_iteration_awaiting(iteration, completion, true)
+    }
+
+    fn close_iteration_awaiting<T>(&mut self, iteration: &mut Iteration, completion: Result<T, String>, awaiting: bool) -> Result<T, String> {
+        if iteration.done {
+            return completion;
+        }
+        iteration.done = true;
+        let throwing = completion.as_ref().is_err_and(|message| !self.is_generator_return(message));
+        // The exception propagating is set aside while `return` runs
+        if let Err(message) = &completion {
+            self.materialize_exception(message);
+        }
+        let pending = self.exception.take();
+        let closed = match self.get_method(&iteration.iterator, "return") {
+            Ok(Some(method)) => self
+                .call_function(&method, iteration.iterator.clone(), Vec::new())
+                .and_then(|result| if awaiting { self.await_value(result) } else { Ok(result) })
+                .map(Some),
+            Ok(None) => Ok(None),
+            Err(message) => Err(message),
+        };
+        match closed {
+            Err(_) if throwing => {
+                self.exception = pending;
+                completion
+            }
+            Err(message) => Err(message),
+            Ok(Some(result)) if !throwing && !matches!(result, Value::Object(_) | Value::Function(_)) => {
+                Err(type_error(format!("Iterator result {} is not an object", self.to_display_string(&result))))
+            }
+            Ok(_) => {
+                self.exception = pending;
+                completion
+            }
+        }
+    }
+
+    /// Closes `iterator` before its `next` method has been read, as when
+    /// the arguments of an iterator helper are invalid.
+    pub(super) fn close_iterator<T>(&mut self, iterator: &Value, completion: Result<T, String>) -> Result<T, String> {
+        let mut iteration = Iteration { iterator: iterator.clone(), next: Value::Undefined, done: false, native: false };
+        self.close_iteration(&mut iteration, completion)
+    }
+
+    /// The `GetMethod` operation: the function at `name`, or `None` if the
+    /// property is `undefined` or `null`.
+    pub(super) fn get_method(&mut self, value: &Value, name: &str) -> Result<Option<Value>, String> {
+        self.get_method_by_key(value, &PropertyKey::from(name))
+    }
+
+    /// `get_method` for any property key.
+    pub(super) fn get_method_by_key(&mut self, value: &Value, key: &PropertyKey) -> Result<Option<Value>, String> {
+        match self.get_member(value, key)? {
+            Value::Undefined | Value::Null => Ok(None),
+            method @ Value::Function(_) => Ok(Some(method)),
+            other => Err(type_error(format!("{} is not a function", self.to_display_string(&other)))),
+        }
+    }
+
+    /// Collects the values produced by spreading `value`.
+    pub(super) fn collect_iterable(&mut self, value: &Value) -> Result<Vec<Value>, String> {
+        let mut iteration = self.iterate(value)?;
+        self.collect_iteration(&mut iteration)
+    }
+
+    /// Collects the values an iteration has yet to produce.
+    pub(super) fn collect_iteration(&mut self, iteration: &mut Iteration) -> Result<Vec<Value>, String> {
+        let mut values = Vec::new();
+        while let Some(value) = self.iteration_step(iteration)? {
+            self.hold(&value);
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// `{ value, done }`, the result of an iterator's `next`.
+    pub(super) fn create_iterator_result(&mut self, value: Value, done: bool) -> Value {
+        let result = self.create_object();
+        self.heap.define_property(result, PropertyKey::from("value"), Property::data(value, Attributes::DEFAULT));
+        self.heap.define_property(result, PropertyKey::from("done"), Property::data(Value::Boolean(done), Attributes::DEFAULT));
+        Value::Object(result)
+    }
+
+    /// Creates an iterator over the elements of `target`.
+    pub(super) fn create_array_iterator(&mut self, target: Value, kind: IterationKind) -> Value {
+        let iterator = ArrayIterator { target: Some(target), index: 0, kind };
+        let prototype = Some(self.intrinsics.array_iterator_prototype);
+        Value::Object(self.heap.allocate(Object::new(ObjectKind::ArrayIterator(iterator), prototype)))
+    }
+
+    /// Advances the array iterator `id`, returning `None` when it is done.
+    /// The length is read on every step, so elements added while iterating
+    /// are visited.
+    pub(super) fn array_iterator_next(&mut self, id: ObjectId) -> Result<Option<Value>, String> {
+        let ObjectKind::ArrayIterator(iterator) = &self.heap.get(id).kind else {
+            unreachable!("not an array iterator")
+        };
+        let (Some(target), index, kind) = (iterator.target.clone(), iterator.index, iterator.kind) else {
+            return Ok(None);
+        };
+        let length = match &target {
+            Value::Object(array) if self.heap.get(*array).is_array() => self.heap.get(*array).array_length(),
+            _ => {
+                let length = self.get_member(&target, &PropertyKey::from("length"))?;
+                self.coerce_to_number(&length)?.clamp(0.0, u32::MAX as f64) as u32
+            }
+        };
+        let ObjectKind::ArrayIterator(iterator) = &mut self.heap.get_mut(id).kind else { unreachable!() };
+        if index >= length {
+            iterator.target = None;
+            return Ok(None);
+        }
+        iterator.index = index + 1;
+        Ok(Some(match kind {
+            IterationKind::Keys => Value::Number(index as f64),
+            IterationKind::Values => self.get_member(&target, &PropertyKey::Index(index))?,
+            IterationKind::Entries => {
+                let value = self.get_member(&target, &PropertyKey::Index(index))?;
+                self.create_array(vec![Value::Number(index as f64), value])
+            }
+        }))
+    }
+
+    /// Creates an iterator over the code points of `string`.
+    pub(super) fn create_string_iterator(&mut self, string: JsString) -> Value {
+        let iterator = StringIterator { string, position: 0 };
+        let prototype = Some(self.intrinsics.string_iterator_prototype);
+        Value::Object(self.heap.allocate(Object::new(ObjectKind::StringIterator(iterator), prototype)))
+    }
+
+    /// Advances the string iterator `id`, returning `None` when it is done.
+    pub(super) fn string_iterator_next(&mut self, id: ObjectId) -> Option<Value> {
+        let ObjectKind::StringIterator(iterator) = &mut self.heap.get_mut(id).kind else {
+            unreachable!("not a string iterator")
+        };
+        let rest = &iterator.string.units()[iterator.position..];
+        // A lone surrogate is a code point of its own
+        let length = match char::decode_utf16(rest.iter().copied()).next()? {
+            Ok(c) => c.len_utf16(),
+            Err(_) => 1,
+        };
+        iterator.position += length;
+        Some(Value::String(JsString::from(&rest[..length])))
+    }
+}
@@ -0,0 +1,682 @@
+//! Heap-allocated objects. Values refer to objects by `ObjectId`, so copying
+//! a value shares the object rather than cloning it.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+
+use super::collection::{CollectionIterator, OrderedTable};
+use super::generator::Generator;
+use super::iteration::{ArrayIterator, Iteration, IteratorHelper, StringIterator};
+use super::promise::{PromiseFunction, PromiseState};
+use super::typed_array::{ArrayBufferData, DataViewData, TypedArrayData};
+use super::encoding::TextDecoder;
+use super::environment::EnvRef;
+use super::string::JsString;
+use super::value::{same_value, Symbol};
+use super::{Interpreter, Value};
+use crate::parser::{Expression, FunctionDefinition};
+
+/// A built-in function implemented in Rust, called with `this` and the
+/// argument list.
+pub type NativeFunction = fn(&mut Interpreter, &Value, &[Value]) -> Result<Value, String>;
+
+/// A handle to an object in the interpreter's heap. The generation tells
+/// the object apart from those that use its slot after it is collected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObjectId {
+    slot: u32,
+    generation: u32,
+}
+
+/// A property name. Canonical array indices are kept as integers so that
+/// element access does not round-trip through strings.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PropertyKey {
+    String(String),
+    Index(u32),
+    Symbol(Symbol),
+}
+
+impl PropertyKey {
+    pub fn as_index(&self) -> Option<u32> {
+        match self {
+            PropertyKey::Index(index) => Some(*index),
+            PropertyKey::String(_) | PropertyKey::Symbol(_) => None,
+        }
+    }
+}
+
+impl From<&str> for PropertyKey {
+    fn from(name: &str) -> Self {
+        // Only the canonical form of an integer below 2^32 - 1 is an index
+        match name.parse::<u32>() {
+            Ok(index) if index != u32::MAX && index.to_string() == name => PropertyKey::Index(index),
+            _ => PropertyKey::String(name.to_string()),
+        }
+    }
+}
+
+impl From<String> for PropertyKey {
+    fn from(name: String) -> Self {
+        PropertyKey::from(name.as_str())
+    }
+}
+
+/// Keys are Rust strings, so a lone surrogate in a key becomes U+FFFD.
+impl From<&JsString> for PropertyKey {
+    fn from(name: &JsString) -> Self {
+        PropertyKey::from(name.to_string())
+    }
+}
+
+impl From<JsString> for PropertyKey {
+    fn from(name: JsString) -> Self {
+        PropertyKey::from(&name)
+    }
+}
+
+impl From<u32> for PropertyKey {
+    fn from(index: u32) -> Self {
+        PropertyKey::Index(index)
+    }
+}
+
+impl PropertyKey {
+    /// The key as a string or symbol value, as proxy traps receive it.
+    pub fn to_value(&self) -> Value {
+        match self {
+            PropertyKey::Symbol(symbol) => Value::Symbol(symbol.clone()),
+            key => Value::String(JsString::from(key.to_string())),
+        }
+    }
+
+    /// The name a function defined under this key gets: a symbol's
+    /// description in brackets, or nothing if it has none.
+    pub fn function_name(&self) -> String {
+        match self {
+            PropertyKey::Symbol(symbol) => symbol.description().map(|description| format!("[{}]", description)).unwrap_or_default(),
+            key => key.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for PropertyKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PropertyKey::String(name) => write!(f, "{}", name),
+            PropertyKey::Index(index) => write!(f, "{}", index),
+            PropertyKey::Symbol(symbol) => write!(f, "{}", symbol),
+        }
+    }
+}
+
+/// A `#name` declared by one evaluation of a class body. Classes are
+/// told apart by their constructor, so two classes declaring `#x` (or one
+/// class evaluated twice) get distinct names.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PrivateName {
+    pub class: ObjectId,
+    /// The name without its `#`
+    pub name: String,
+}
+
+impl std::fmt::Display for PrivateName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.name)
+    }
+}
+
+/// The attributes of a property. `writable` only applies to data properties.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attributes {
+    pub writable: bool,
+    pub enumerable: bool,
+    pub configurable: bool,
+}
+
+impl Attributes {
+    /// Properties created by assignment and object literals
+    pub const DEFAULT: Self = Self { writable: true, enumerable: true, configurable: true };
+    /// Built-in methods and other properties skipped by enumeration
+    pub const HIDDEN: Self = Self { writable: true, enumerable: false, configurable: true };
+    /// The `name` and `length` of functions
+    pub const READ_ONLY: Self = Self { writable: false, enumerable: false, configurable: true };
+    /// Private methods and the `prototype` of classes
+    pub const FIXED: Self = Self { writable: false, enumerable: false, configurable: false };
+}
+
+#[derive(Clone, Debug)]
+pub enum PropertyValue {
+    Data(Value),
+    /// A getter / setter pair, either of which may be missing
+    Accessor { get: Option<Value>, set: Option<Value> },
+}
+
+#[derive(Clone, Debug)]
+pub struct Property {
+    pub value: PropertyValue,
+    pub attributes: Attributes,
+}
+
+impl Property {
+    pub fn data(value: Value, attributes: Attributes) -> Self {
+        Self { value: PropertyValue::Data(value), attributes }
+    }
+
+    /// An accessor with `function` as its getter or setter, keeping the
+    /// other half of `existing` if that is an accessor too.
+    pub fn accessor(existing: Option<&Property>, is_getter: bool, function: Value, attributes: Attributes) -> Self {
+        let (mut get, mut set) = match existing {
+            Some(Property { value: PropertyValue::Accessor { get, set }, .. }) => (get.clone(), set.clone()),
+            _ => (None, None),
+        };
+        if is_getter {
+            get = Some(function);
+        } else {
+            set = Some(function);
+        }
+        Self { value: PropertyValue::Accessor { get, set }, attributes }
+    }
+}
+
+/// A partial property definition, as passed to `Object.defineProperty`.
+/// Missing fields keep their current value, or default to `false` /
+/// `undefined` for a new property.
+#[derive(Clone, Debug, Default)]
+pub struct PropertyDescriptor {
+    pub value: Option<Value>,
+    pub writable: Option<bool>,
+    /// `Some(Value::Undefined)` explicitly removes the getter
+    pub get: Option<Value>,
+    pub set: Option<Value>,
+    pub enumerable: Option<bool>,
+    pub configurable: Option<bool>,
+}
+
+impl PropertyDescriptor {
+    pub fn is_accessor(&self) -> bool {
+        self.get.is_some() || self.set.is_some()
+    }
+
+    pub fn is_data(&self) -> bool {
+        self.value.is_some() || self.writable.is_some()
+    }
+
+    /// The property this descriptor creates when nothing exists under its
+    /// key yet, with missing fields `false` or `undefined`.
+    pub fn complete(self) -> Property {
+        let attributes = Attributes {
+            writable: self.writable.unwrap_or(false),
+            enumerable: self.enumerable.unwrap_or(false),
+            configurable: self.configurable.unwrap_or(false),
+        };
+        let value = if self.is_accessor() {
+            PropertyValue::Accessor { get: accessor_function(self.get), set: accessor_function(self.set) }
+        } else {
+            PropertyValue::Data(self.value.unwrap_or(Value::Undefined))
+        };
+        Property { value, attributes }
+    }
+
+    /// The complete descriptor of an existing property.
+    pub fn of(property: &Property) -> Self {
+        let attributes = property.attributes;
+        let (enumerable, configurable) = (Some(attributes.enumerable), Some(attributes.configurable));
+        match &property.value {
+            PropertyValue::Data(value) => Self { value: Some(value.clone()), writable: Some(attributes.writable), enumerable, configurable, ..Self::default() },
+            PropertyValue::Accessor { get, set } => Self {
+                get: Some(get.clone().unwrap_or(Value::Undefined)),
+                set: Some(set.clone().unwrap_or(Value::Undefined)),
+                enumerable,
+                configurable,
+                ..Self::default()
+            },
+        }
+    }
+}
+
+/// A function with its `this` and leading arguments fixed by `bind`.
+#[derive(Debug)]
+pub struct BoundFunction {
+    pub target: Value,
+    pub this: Value,
+    pub args: Vec<Value>,
+}
+
+/// The internal slots of a `Proxy`.
+#[derive(Debug)]
+pub struct ProxyData {
+    /// The target and handler, both cleared when the proxy is revoked
+    pub target: Option<ObjectId>,
+    pub handler: Option<ObjectId>,
+    /// Whether the target could be called and constructed, which the
+    /// proxy keeps after it is revoked
+    pub callable: bool,
+    pub constructor: bool,
+}
+
+/// A function created from source code, together with the scope it closes over.
+#[derive(Debug)]
+pub struct Closure {
+    pub definition: Rc<FunctionDefinition>,
+    pub env: EnvRef,
+    /// For methods, the object they were defined on, whose prototype
+    /// `super.x` looks `x` up on
+    pub home_object: Option<ObjectId>,
+    /// For class constructors, what each new instance is given
+    pub class: Option<Rc<ClassInfo>>,
+}
+
+/// A field declared in a class body.
+#[derive(Debug)]
+pub struct ClassField {
+    pub key: FieldKey,
+    pub initializer: Option<Rc<Expression>>,
+}
+
+#[derive(Clone, Debug)]
+pub enum FieldKey {
+    Public(PropertyKey),
+    Private(PrivateName),
+}
+
+impl FieldKey {
+    /// See `PropertyKey::function_name`.
+    pub fn function_name(&self) -> String {
+        match self {
+            FieldKey::Public(key) => key.function_name(),
+            FieldKey::Private(name) => name.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldKey::Public(key) => write!(f, "{}", key),
+            FieldKey::Private(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The per-instance parts of a class, added to each object it constructs
+/// before the constructor body runs (or when `super()` returns).
+#[derive(Debug, Default)]
+pub struct ClassInfo {
+    /// Private methods and accessors, installed before any field
+    pub private_methods: Vec<(PrivateName, Property)>,
+    pub fields: Vec<ClassField>,
+    pub is_abstract: bool,
+}
+
+#[derive(Debug)]
+pub enum ObjectKind {
+    Ordinary,
+    Array,
+    Function(Closure),
+    NativeFunction(NativeFunction),
+    BoundFunction(BoundFunction),
+    /// The `arguments` object of a non-arrow function call
+    Arguments,
+    /// An instance of `Error` or one of its subclasses
+    Error,
+    /// A wrapper object for a primitive, such as `new Number(1)`
+    Primitive(Value),
+    Map(OrderedTable),
+    /// A `Set`, whose entries hold each value as both key and value
+    Set(OrderedTable),
+    /// A `WeakMap`, whose entries are dropped when their key is collected
+    WeakMap(HashMap<ObjectId, Value>),
+    WeakSet(HashSet<ObjectId>),
+    /// An iterator returned by `keys`, `values` or `entries` of a `Map`
+    /// or `Set`
+    CollectionIterator(CollectionIterator),
+    /// An iterator returned by `keys`, `values` or `entries` of an array
+    ArrayIterator(ArrayIterator),
+    StringIterator(StringIterator),
+    Generator(Box<Generator>),
+    IteratorHelper(Box<IteratorHelper>),
+    /// An iterator returned by `Iterator.from` that does not already
+    /// inherit from `Iterator.prototype`
+    WrappedIterator(Iteration),
+    Promise(Box<PromiseState>),
+    /// A `Date`, holding its time value
+    Date(f64),
+    /// An `ArrayBuffer` or `SharedArrayBuffer`
+    ArrayBuffer(ArrayBufferData),
+    /// One of the typed arrays, such as `Uint8Array`, whose elements live
+    /// in its buffer rather than in its properties
+    TypedArray(TypedArrayData),
+    DataView(DataViewData),
+    TextEncoder,
+    /// A `TextDecoder`, with the bytes of an incomplete character kept
+    /// between streaming calls to `decode`
+    TextDecoder(Box<TextDecoder>),
+    /// An object whose internal methods are implemented by the traps of a
+    /// handler
+    Proxy(ProxyData),
+    /// The `revoke` function `Proxy.revocable` returns, holding its proxy
+    /// until it is called
+    RevokeProxy(Option<ObjectId>),
+    /// One of the functions promises create internally, such as the
+    /// `resolve` and `reject` functions passed to an executor
+    PromiseFunction(PromiseFunction),
+}
+
+#[derive(Debug)]
+pub struct Object {
+    pub kind: ObjectKind,
+    /// Own properties in creation order; see `own_keys` for the order
+    /// JavaScript exposes them in
+    pub properties: IndexMap<PropertyKey, Property>,
+    pub prototype: Option<ObjectId>,
+    /// Cleared by `Object.preventExtensions`, `seal` and `freeze`
+    pub extensible: bool,
+    /// `#name` fields, methods and accessors, which are invisible to
+    /// property lookups and unaffected by `extensible`
+    pub private_elements: HashMap<PrivateName, Property>,
+}
+
+impl Object {
+    pub fn new(kind: ObjectKind, prototype: Option<ObjectId>) -> Self {
+        Self {
+            kind,
+            properties: IndexMap::new(),
+            prototype,
+            extensible: true,
+            private_elements: HashMap::new(),
+        }
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self.kind, ObjectKind::Array)
+    }
+
+    pub fn is_callable(&self) -> bool {
+        match &self.kind {
+            ObjectKind::Function(_)
+            | ObjectKind::NativeFunction(_)
+            | ObjectKind::BoundFunction(_)
+            | ObjectKind::PromiseFunction(_)
+            | ObjectKind::RevokeProxy(_) => true,
+            ObjectKind::Proxy(proxy) => proxy.callable,
+            _ => false,
+        }
+    }
+
+    pub fn is_enumerable(&self, key: &PropertyKey) -> bool {
+        self.properties.get(key).is_some_and(|property| property.attributes.enumerable)
+    }
+
+    /// The array `length`, or zero for objects without one.
+    pub fn array_length(&self) -> u32 {
+        match self.properties.get(&PropertyKey::from("length")) {
+            Some(Property { value: PropertyValue::Data(Value::Number(n)), .. }) => *n as u32,
+            _ => 0,
+        }
+    }
+
+    /// The own property keys in the order JavaScript enumerates them:
+    /// array indices ascending, then strings in creation order. Symbol
+    /// keys are left out.
+    pub fn own_keys(&self) -> Vec<PropertyKey> {
+        let mut indices: Vec<u32> = self.properties.keys().filter_map(PropertyKey::as_index).collect();
+        indices.sort_unstable();
+        let strings = self.properties.keys().filter(|key| matches!(key, PropertyKey::String(_))).cloned();
+        indices.into_iter().map(PropertyKey::Index).chain(strings).collect()
+    }
+
+    /// `own_keys` followed by the symbol keys in creation order, as the
+    /// `OrdinaryOwnPropertyKeys` operation lists them.
+    pub fn own_property_keys(&self) -> Vec<PropertyKey> {
+        let symbols = self.properties.keys().filter(|key| matches!(key, PropertyKey::Symbol(_))).cloned();
+        let mut keys = self.own_keys();
+        keys.extend(symbols);
+        keys
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Heap {
+    slots: Vec<Slot>,
+    /// Slots freed by the garbage collector, for reuse
+    free: Vec<u32>,
+    /// Allocations since the last garbage collection
+    pub allocations: usize,
+    /// Objects Rust code holds in locals, which the garbage collector
+    /// treats as roots; see `Interpreter::hold`. New objects start out
+    /// held.
+    pub temporaries: Vec<ObjectId>,
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    /// Counts the objects that have used the slot
+    generation: u32,
+    /// `None` once the object is collected
+    object: Option<Object>,
+}
+
+impl Heap {
+    pub fn allocate(&mut self, object: Object) -> ObjectId {
+        self.allocations += 1;
+        let id = match self.free.pop() {
+            Some(slot) => {
+                let reused = &mut self.slots[slot as usize];
+                reused.generation += 1;
+                reused.object = Some(object);
+                ObjectId { slot, generation: reused.generation }
+            }
+            None => {
+                self.slots.push(Slot { generation: 0, object: Some(object) });
+                ObjectId { slot: (self.slots.len() - 1) as u32, generation: 0 }
+            }
+        };
+        self.temporaries.push(id);
+        id
+    }
+
+    pub fn get(&self, id: ObjectId) -> &Object {
+        self.try_get(id).expect("object used after being collected")
+    }
+
+    pub fn get_mut(&mut self, id: ObjectId) -> &mut Object {
+        let slot = &mut self.slots[id.slot as usize];
+        slot.object.as_mut().filter(|_| slot.generation == id.generation).expect("object used after being collected")
+    }
+
+    /// The object `id` refers to, or `None` if it has been collected.
+    pub fn try_get(&self, id: ObjectId) -> Option<&Object> {
+        let slot = &self.slots[id.slot as usize];
+        slot.object.as_ref().filter(|_| slot.generation == id.generation)
+    }
+
+    /// The objects that have not been freed.
+    pub fn live_ids(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.slots.iter().enumerate().filter(|(_, slot)| slot.object.is_some()).map(|(slot, Slot { generation, .. })| ObjectId {
+            slot: slot as u32,
+            generation: *generation,
+        })
+    }
+
+    /// Releases an unreachable object, making its slot available again.
+    pub fn free(&mut self, id: ObjectId) {
+        self.slots[id.slot as usize].object = None;
+        self.free.push(id.slot);
+    }
+
+    /// Looks `key` up on the object and then along its prototype chain.
+    pub fn find_property(&self, id: ObjectId, key: &PropertyKey) -> Option<&Property> {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let object = self.get(id);
+            if let Some(property) = object.properties.get(key) {
+                return Some(property);
+            }
+            current = object.prototype;
+        }
+        None
+    }
+
+    /// Whether `key` exists on the object or its prototype chain.
+    pub fn has_property(&self, id: ObjectId, key: &PropertyKey) -> bool {
+        self.find_property(id, key).is_some()
+    }
+
+    /// Creates or overwrites an own property, growing the `length` of an
+    /// array when an index at or past the end is defined.
+    pub fn define_property(&mut self, id: ObjectId, key: PropertyKey, property: Property) {
+        let object = self.get_mut(id);
+        if let (true, Some(index)) = (object.is_array(), key.as_index()) {
+            if index >= object.array_length() {
+                if let Some(length) = object.properties.get_mut(&PropertyKey::from("length")) {
+                    length.value = PropertyValue::Data(Value::Number(index as f64 + 1.0));
+                }
+            }
+        }
+        object.properties.insert(key, property);
+    }
+
+    /// Stores `value` in an own data property, keeping the attributes of an
+    /// existing one. Attribute checks are the caller's responsibility.
+    pub fn set_property(&mut self, id: ObjectId, key: PropertyKey, value: Value) {
+        let attributes = match self.get(id).properties.get(&key) {
+            Some(Property { value: PropertyValue::Data(_), attributes }) => *attributes,
+            _ => Attributes::DEFAULT,
+        };
+        self.define_property(id, key, Property::data(value, attributes));
+    }
+
+    /// The `ValidateAndApplyPropertyDescriptor` operation behind
+    /// `Object.defineProperty`. Returns false, changing nothing, if the
+    /// definition conflicts with a non-configurable property or the object
+    /// is not extensible.
+    pub fn define_own_property(&mut self, id: ObjectId, key: PropertyKey, descriptor: PropertyDescriptor) -> bool {
+        let object = self.get(id);
+        let current = object.properties.get(&key);
+        if !is_compatible_descriptor(object.extensible, &descriptor, current) {
+            return false;
+        }
+        let Some(current) = current.cloned() else {
+            self.define_property(id, key, descriptor.complete());
+            return true;
+        };
+
+        let mut attributes = current.attributes;
+        attributes.enumerable = descriptor.enumerable.unwrap_or(attributes.enumerable);
+        attributes.configurable = descriptor.configurable.unwrap_or(attributes.configurable);
+        let value = match current.value {
+            PropertyValue::Data(_) if descriptor.is_accessor() => {
+                attributes.writable = false;
+                PropertyValue::Accessor { get: accessor_function(descriptor.get), set: accessor_function(descriptor.set) }
+            }
+            PropertyValue::Accessor { .. } if descriptor.is_data() => {
+                attributes.writable = descriptor.writable.unwrap_or(false);
+                PropertyValue::Data(descriptor.value.unwrap_or(Value::Undefined))
+            }
+            PropertyValue::Data(value) => {
+                attributes.writable = descriptor.writable.unwrap_or(attributes.writable);
+                PropertyValue::Data(descriptor.value.unwrap_or(value))
+            }
+            PropertyValue::Accessor { get, set } => PropertyValue::Accessor {
+                get: descriptor.get.map_or(get, |get| accessor_function(Some(get))),
+                set: descriptor.set.map_or(set, |set| accessor_function(Some(set))),
+            },
+        };
+
+        if self.get(id).is_array() && key == PropertyKey::from("length") {
+            if let PropertyValue::Data(Value::Number(length)) = value {
+                self.set_array_length(id, length as u32);
+            }
+        }
+        self.get_mut(id).properties.insert(key, Property { value, attributes });
+        true
+    }
+
+    /// Changes an array's `length`, deleting the elements past the new end.
+    pub fn set_array_length(&mut self, id: ObjectId, length: u32) {
+        let object = self.get_mut(id);
+        let old_length = object.array_length();
+        if length < old_length {
+            object.properties.retain(|key, _| key.as_index().is_none_or(|index| index < length));
+        }
+        match object.properties.get_mut(&PropertyKey::from("length")) {
+            Some(property) => property.value = PropertyValue::Data(Value::Number(length as f64)),
+            None => {
+                object.properties.insert(PropertyKey::from("length"), array_length_property(length));
+            }
+        }
+    }
+
+    /// Removes an own property. Returns false, leaving it in place, if the
+    /// property is not configurable.
+    pub fn delete_property(&mut self, id: ObjectId, key: &PropertyKey) -> bool {
+        let properties = &mut self.get_mut(id).properties;
+        match properties.get(key) {
+            Some(property) if !property.attributes.configurable => false,
+            Some(_) => {
+                properties.shift_remove(key);
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Allocates an array holding `elements`, with `None` leaving a hole.
+    pub fn allocate_array(&mut self, elements: Vec<Option<Value>>, prototype: Option<ObjectId>) -> ObjectId {
+        let mut object = Object::new(ObjectKind::Array, prototype);
+        object.properties.insert(PropertyKey::from("length"), array_length_property(elements.len() as u32));
+        for (index, element) in elements.into_iter().enumerate() {
+            if let Some(element) = element {
+                object.properties.insert(PropertyKey::Index(index as u32), Property::data(element, Attributes::DEFAULT));
+            }
+        }
+        self.allocate(object)
+    }
+}
+
+/// The `IsCompatiblePropertyDescriptor` operation: whether `descriptor`
+/// may be applied to an object with the own property `current`, or
+/// without one if `None`.
+pub fn is_compatible_descriptor(extensible: bool, descriptor: &PropertyDescriptor, current: Option<&Property>) -> bool {
+    let Some(current) = current else {
+        return extensible;
+    };
+    if current.attributes.configurable {
+        return true;
+    }
+    if descriptor.configurable == Some(true) || descriptor.enumerable.is_some_and(|enumerable| enumerable != current.attributes.enumerable) {
+        return false;
+    }
+    match &current.value {
+        PropertyValue::Data(value) => {
+            !descriptor.is_accessor()
+                && (current.attributes.writable
+                    || (descriptor.writable != Some(true) && descriptor.value.as_ref().is_none_or(|new| same_value(new, value))))
+        }
+        PropertyValue::Accessor { get, set } => {
+            let unchanged = |new: &Option<Value>, old: &Option<Value>| {
+                new.as_ref().is_none_or(|new| match (accessor_function(Some(new.clone())), old) {
+                    (None, None) => true,
+                    (Some(new), Some(old)) => same_value(&new, old),
+                    _ => false,
+                })
+            };
+            !descriptor.is_data() && unchanged(&descriptor.get, get) && unchanged(&descriptor.set, set)
+        }
+    }
+}
+
+/// Normalizes a descriptor's `get` / `set`, where `undefined` means none.
+fn accessor_function(function: Option<Value>) -> Option<Value> {
+    function.filter(|function| !matches!(function, Value::Undefined))
+}
+
+/// An array's `length`, which is writable but neither enumerable nor
+/// configurable.
+fn array_length_property(length: u32) -> Property {
+    let attributes = Attributes { writable: true, enumerable: false, configurable: false };
+    Property::data(Value::Number(length as f64), attributes)
+}
@@ -0,0 +1,715 @@
+//! Promises, async functions and the job queue.
+//!
+//! Settling a promise queues a job for each reaction registered on it with
+//! `then`. Jobs run after the script that queued them has finished, oldest
+//! first, until none are left; jobs queued while they run join the end of
+//! the queue.
+//!
+//! An `await` suspends the body it is in, as `yield` suspends a
+//! generator's, and a reaction resumes it once the awaited promise has
+//! settled. Top-level scripts run as such a body too, so a script that
+//! awaits something finishes in a later job.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use super::generator::{Body, GeneratorStep, ResumeAction};
+use super::object::{Attributes, Object, ObjectId, ObjectKind, Property, PropertyKey};
+use super::timer::Timers;
+use super::{strict_equals, type_error, Interpreter, JsString, Value};
+use crate::parser::{Expression, FunctionDefinition, Statement};
+
+/// Whether and how a promise has settled.
+#[derive(Clone, Debug)]
+pub enum Settlement {
+    Pending,
+    Fulfilled(Value),
+    Rejected(Value),
+}
+
+/// The state of a promise object.
+#[derive(Debug)]
+pub struct PromiseState {
+    pub settlement: Settlement,
+    fulfill_reactions: Vec<Reaction>,
+    reject_reactions: Vec<Reaction>,
+    /// Whether a reaction was ever registered, which handles a rejection
+    handled: bool,
+}
+
+impl PromiseState {
+    fn new() -> Self {
+        Self { settlement: Settlement::Pending, fulfill_reactions: Vec::new(), reject_reactions: Vec::new(), handled: false }
+    }
+
+    /// The values a promise keeps alive.
+    pub fn references(&self) -> Vec<Value> {
+        let mut values = Vec::new();
+        if let Settlement::Fulfilled(value) | Settlement::Rejected(value) = &self.settlement {
+            values.push(value.clone());
+        }
+        for reaction in self.fulfill_reactions.iter().chain(&self.reject_reactions) {
+            reaction.add_references(&mut values);
+        }
+        values
+    }
+}
+
+/// A promise together with the functions that resolve and reject it.
+#[derive(Clone, Debug)]
+pub struct Capability {
+    pub promise: Value,
+    pub resolve: Value,
+    pub reject: Value,
+}
+
+impl Capability {
+    fn add_references(&self, values: &mut Vec<Value>) {
+        values.extend([self.promise.clone(), self.resolve.clone(), self.reject.clone()]);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ReactionKind {
+    Fulfill,
+    Reject,
+}
+
+/// What a reaction does with the value its promise settled with.
+#[derive(Clone, Debug)]
+pub enum Handler {
+    /// Passes it on unchanged, for a `then` without a function
+    Identity,
+    Callback(Value),
+    /// Resumes the async body `id` with it
+    Resume(ObjectId),
+}
+
+impl Handler {
+    /// A handler calling `value`, or passing values on if it is not a
+    /// function.
+    pub(super) fn from_value(value: Value) -> Self {
+        match value {
+            Value::Function(_) => Handler::Callback(value),
+            _ => Handler::Identity,
+        }
+    }
+}
+
+/// A handler registered on a pending promise, and the capability of the
+/// promise `then` returned for it, which the handler's result settles.
+#[derive(Clone, Debug)]
+pub struct Reaction {
+    kind: ReactionKind,
+    handler: Handler,
+    capability: Option<Capability>,
+}
+
+impl Reaction {
+    fn add_references(&self, values: &mut Vec<Value>) {
+        match &self.handler {
+            Handler::Identity => {}
+            Handler::Callback(function) => values.push(function.clone()),
+            Handler::Resume(body) => values.push(Value::Object(*body)),
+        }
+        if let Some(capability) = &self.capability {
+            capability.add_references(values);
+        }
+    }
+}
+
+/// A job waiting in the queue.
+enum Job {
+    /// Runs a reaction with the value its promise settled with
+    Reaction(Reaction, Value),
+    /// Calls the `then` method of a thenable `promise` was resolved with,
+    /// passing it functions that settle `promise`
+    ResolveThenable { promise: ObjectId, thenable: Value, then: Value },
+    /// Calls a function passed to `queueMicrotask`
+    Callback(Value),
+}
+
+impl Job {
+    fn add_references(&self, values: &mut Vec<Value>) {
+        match self {
+            Job::Reaction(reaction, argument) => {
+                reaction.add_references(values);
+                values.push(argument.clone());
+            }
+            Job::ResolveThenable { promise, thenable, then } => {
+                values.extend([Value::Object(*promise), thenable.clone(), then.clone()]);
+            }
+            Job::Callback(callback) => values.push(callback.clone()),
+        }
+    }
+}
+
+/// The script run most recently.
+struct Script {
+    body: ObjectId,
+    promise: ObjectId,
+    /// The error it threw, if it did
+    error: Option<String>,
+}
+
+/// The jobs waiting to run, and what is reported once they have.
+#[derive(Default)]
+pub(super) struct EventLoop {
+    jobs: VecDeque<Job>,
+    /// Promises rejected while nothing handled them, which are reported
+    /// unless a handler is added before the jobs run out
+    rejections: Vec<ObjectId>,
+    script: Option<Script>,
+    /// An exception a job threw that nothing could catch
+    uncaught: Option<String>,
+    pub(super) timers: Timers,
+}
+
+impl EventLoop {
+    /// The values the queued jobs and the last script keep alive.
+    pub(super) fn references(&self) -> Vec<Value> {
+        let mut values = Vec::new();
+        for job in &self.jobs {
+            job.add_references(&mut values);
+        }
+        values.extend(self.timers.references().cloned());
+        values.extend(self.rejections.iter().map(|id| Value::Object(*id)));
+        if let Some(script) = &self.script {
+            values.extend([Value::Object(script.body), Value::Object(script.promise)]);
+        }
+        values
+    }
+
+    /// Whether a job has thrown, or left a promise rejected without a
+    /// handler, since this was last reported.
+    pub(super) fn has_failed(&self) -> bool {
+        self.uncaught.is_some() || !self.rejections.is_empty()
+    }
+}
+
+/// The built-in functions promises hand out, which close over state of
+/// their own.
+#[derive(Clone, Debug)]
+pub enum PromiseFunction {
+    /// The `resolve` and `reject` functions of a promise, of which only
+    /// the first call has an effect
+    Resolve(ObjectId, Rc<Cell<bool>>),
+    Reject(ObjectId, Rc<Cell<bool>>),
+    /// The executor a promise constructor is called with to create a
+    /// capability, which records the `resolve` and `reject` it is given
+    CapabilityExecutor(Rc<RefCell<(Value, Value)>>),
+    /// The handlers `finally` registers, which call `callback` and then
+    /// pass the value their promise settled with on
+    ThenFinally { callback: Value, constructor: Value },
+    CatchFinally { callback: Value, constructor: Value },
+    ReturnValue(Value),
+    ThrowValue(Value),
+    /// Records the result of the input at `index` of `Promise.all`,
+    /// `allSettled` or `any`; `called` is shared by the functions for
+    /// the same input
+    Element { combinator: Rc<Combinator>, index: usize, kind: ElementKind, called: Rc<Cell<bool>> },
+}
+
+impl PromiseFunction {
+    /// The values a function keeps alive. The values collected by the
+    /// combinator of an element function are shared by all its element
+    /// functions, and left to the garbage collector to mark once.
+    pub fn references(&self) -> Vec<Value> {
+        match self {
+            PromiseFunction::Resolve(promise, _) | PromiseFunction::Reject(promise, _) => vec![Value::Object(*promise)],
+            PromiseFunction::CapabilityExecutor(slots) => {
+                let slots = slots.borrow();
+                vec![slots.0.clone(), slots.1.clone()]
+            }
+            PromiseFunction::ThenFinally { callback, constructor } | PromiseFunction::CatchFinally { callback, constructor } => {
+                vec![callback.clone(), constructor.clone()]
+            }
+            PromiseFunction::ReturnValue(value) | PromiseFunction::ThrowValue(value) => vec![value.clone()],
+            PromiseFunction::Element { combinator, .. } => {
+                let mut values = Vec::new();
+                combinator.capability.add_references(&mut values);
+                values
+            }
+        }
+    }
+}
+
+/// What an element function of a promise combinator records.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ElementKind {
+    /// The value, for `Promise.all`
+    Value,
+    /// `{ status: 'fulfilled', value }`, for `Promise.allSettled`
+    Fulfilled,
+    /// `{ status: 'rejected', reason }`, for `Promise.allSettled`
+    Rejected,
+    /// The rejection reason, for `Promise.any`
+    Reason,
+}
+
+/// The results `Promise.all`, `allSettled` or `any` collects.
+#[derive(Debug)]
+pub struct Combinator {
+    pub values: RefCell<Vec<Value>>,
+    /// Inputs yet to settle, plus one until all inputs have been seen
+    pub remaining: Cell<usize>,
+    pub capability: Capability,
+}
+
+impl Interpreter {
+    /// Creates a pending promise.
+    pub(super) fn create_promise(&mut self, prototype: ObjectId) -> ObjectId {
+        self.heap.allocate(Object::new(ObjectKind::Promise(Box::new(PromiseState::new())), Some(prototype)))
+    }
+
+    /// The promise state of `value`, if it is a promise.
+    pub(super) fn promise_id(&self, value: &Value) -> Option<ObjectId> {
+        match value {
+            Value::Object(id) if matches!(self.heap.get(*id).kind, ObjectKind::Promise(_)) => Some(*id),
+            _ => None,
+        }
+    }
+
+    fn promise_mut(&mut self, id: ObjectId) -> &mut PromiseState {
+        match &mut self.heap.get_mut(id).kind {
+            ObjectKind::Promise(state) => state,
+            _ => unreachable!("not a promise"),
+        }
+    }
+
+    /// Creates a function object for `function`, which is anonymous like
+    /// the functions the specification creates.
+    pub(super) fn create_promise_function(&mut self, function: PromiseFunction, length: u32) -> Value {
+        let prototype = Some(self.intrinsics.function_prototype);
+        let id = self.heap.allocate(Object::new(ObjectKind::PromiseFunction(function), prototype));
+        self.heap.define_property(id, PropertyKey::from("length"), Property::data(Value::Number(length as f64), Attributes::READ_ONLY));
+        self.heap.define_property(id, PropertyKey::from("name"), Property::data(Value::String(JsString::new()), Attributes::READ_ONLY));
+        Value::Function(id)
+    }
+
+    /// The `resolve` and `reject` functions for `promise`.
+    pub(super) fn create_resolving_functions(&mut self, promise: ObjectId) -> (Value, Value) {
+        let resolved = Rc::new(Cell::new(false));
+        let resolve = self.create_promise_function(PromiseFunction::Resolve(promise, Rc::clone(&resolved)), 1);
+        let reject = self.create_promise_function(PromiseFunction::Reject(promise, resolved), 1);
+        (resolve, reject)
+    }
+
+    pub(super) fn call_promise_function(&mut self, function: PromiseFunction, args: &[Value]) -> Result<Value, String> {
+        let argument = args.first().cloned().unwrap_or(Value::Undefined);
+        match function {
+            PromiseFunction::Resolve(promise, resolved) => {
+                if !resolved.replace(true) {
+                    self.resolve_promise(promise, argument);
+                }
+                Ok(Value::Undefined)
+            }
+            PromiseFunction::Reject(promise, resolved) => {
+                if !resolved.replace(true) {
+                    self.reject_promise(promise, argument);
+                }
+                Ok(Value::Undefined)
+            }
+            PromiseFunction::CapabilityExecutor(slots) => {
+                let mut slots = slots.borrow_mut();
+                if slots.0 != Value::Undefined || slots.1 != Value::Undefined {
+                    return Err(type_error("Promise executor has already been invoked with non-undefined arguments"));
+                }
+                *slots = (argument, args.get(1).cloned().unwrap_or(Value::Undefined));
+                Ok(Value::Undefined)
+            }
+            PromiseFunction::ThenFinally { callback, constructor } => {
+                self.call_finally_callback(&callback, &constructor, PromiseFunction::ReturnValue(argument))
+            }
+            PromiseFunction::CatchFinally { callback, constructor } => {
+                self.call_finally_callback(&callback, &constructor, PromiseFunction::ThrowValue(argument))
+            }
+            PromiseFunction::ReturnValue(value) => Ok(value),
+            PromiseFunction::ThrowValue(value) => Err(self.throw_value(value)),
+            PromiseFunction::Element { combinator, index, kind, called } => {
+                if called.replace(true) {
+                    return Ok(Value::Undefined);
+                }
+                let value = match kind {
+                    ElementKind::Value | ElementKind::Reason => argument,
+                    ElementKind::Fulfilled => self.create_settled_result("fulfilled", "value", argument),
+                    ElementKind::Rejected => self.create_settled_result("rejected", "reason", argument),
+                };
+                combinator.values.borrow_mut()[index] = value;
+                self.finish_combinator_input(&combinator, kind == ElementKind::Reason)?;
+                Ok(Value::Undefined)
+            }
+        }
+    }
+
+    /// Runs the callback of `finally`, then settles the promise `then`
+    /// returned like its own promise once the callback's result settles.
+    fn call_finally_callback(&mut self, callback: &Value, constructor: &Value, pass_on: PromiseFunction) -> Result<Value, String> {
+        let result = self.call_function(callback, Value::Undefined, Vec::new())?;
+        let promise = self.promise_resolve(constructor, result)?;
+        let handler = self.create_promise_function(pass_on, 0);
+        self.invoke(&promise, "then", vec![handler])
+    }
+
+    /// `{ status, [key]: value }`, an entry of the array `allSettled`
+    /// fulfills with.
+    fn create_settled_result(&mut self, status: &str, key: &str, value: Value) -> Value {
+        let result = self.create_object();
+        let status = Value::String(status.into());
+        self.heap.define_property(result, PropertyKey::from("status"), Property::data(status, Attributes::DEFAULT));
+        self.heap.define_property(result, PropertyKey::from(key), Property::data(value, Attributes::DEFAULT));
+        Value::Object(result)
+    }
+
+    /// Counts down the inputs of a combinator, settling its promise once
+    /// the last one is in: fulfilling it with the values collected, or for
+    /// `Promise.any`, rejecting it with all the reasons.
+    pub(super) fn finish_combinator_input(&mut self, combinator: &Combinator, rejects: bool) -> Result<(), String> {
+        let remaining = combinator.remaining.get() - 1;
+        combinator.remaining.set(remaining);
+        if remaining > 0 {
+            return Ok(());
+        }
+        let values = combinator.values.borrow().clone();
+        let values = self.create_array(values);
+        let capability = &combinator.capability;
+        if rejects {
+            let prototype = self.intrinsics.aggregate_error_prototype;
+            let error = self.create_error(prototype, Some("All promises were rejected".to_string()));
+            self.heap.define_property(error, PropertyKey::from("errors"), Property::data(values, Attributes::HIDDEN));
+            self.call_function(&capability.reject, Value::Undefined, vec![Value::Object(error)])?;
+        } else {
+            self.call_function(&capability.resolve, Value::Undefined, vec![values])?;
+        }
+        Ok(())
+    }
+
+    /// Calls the method `name` of `target`.
+    pub(super) fn invoke(&mut self, target: &Value, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        let method = self.get_member(target, &PropertyKey::from(name))?;
+        self.call_function(&method, target.clone(), args)
+    }
+
+    /// Resolves `promise` with `resolution`, adopting its state if it is
+    /// a thenable.
+    pub(super) fn resolve_promise(&mut self, promise: ObjectId, resolution: Value) {
+        if resolution == Value::Object(promise) {
+            let prototype = self.intrinsics.type_error_prototype;
+            let error = self.create_error(prototype, Some("Chaining cycle detected for promise #<Promise>".to_string()));
+            return self.reject_promise(promise, Value::Object(error));
+        }
+        if !matches!(resolution, Value::Object(_) | Value::Function(_)) {
+            return self.fulfill_promise(promise, resolution);
+        }
+        match self.get_member(&resolution, &PropertyKey::from("then")) {
+            Ok(then @ Value::Function(_)) => self.event_loop.jobs.push_back(Job::ResolveThenable { promise, thenable: resolution, then }),
+            Ok(_) => self.fulfill_promise(promise, resolution),
+            Err(message) => {
+                let reason = self.catch_exception(&message);
+                self.reject_promise(promise, reason);
+            }
+        }
+    }
+
+    pub(super) fn fulfill_promise(&mut self, promise: ObjectId, value: Value) {
+        self.settle_promise(promise, Settlement::Fulfilled(value));
+    }
+
+    pub(super) fn reject_promise(&mut self, promise: ObjectId, reason: Value) {
+        if !self.promise_mut(promise).handled {
+            self.event_loop.rejections.push(promise);
+        }
+        self.settle_promise(promise, Settlement::Rejected(reason));
+    }
+
+    /// Settles a pending promise, queueing the reactions registered on it.
+    fn settle_promise(&mut self, promise: ObjectId, settlement: Settlement) {
+        let state = self.promise_mut(promise);
+        debug_assert!(matches!(state.settlement, Settlement::Pending), "promise settled twice");
+        let fulfill_reactions = std::mem::take(&mut state.fulfill_reactions);
+        let reject_reactions = std::mem::take(&mut state.reject_reactions);
+        let (reactions, value) = match &settlement {
+            Settlement::Fulfilled(value) => (fulfill_reactions, value.clone()),
+            Settlement::Rejected(reason) => (reject_reactions, reason.clone()),
+            Settlement::Pending => unreachable!("settling with a pending state"),
+        };
+        state.settlement = settlement;
+        for reaction in reactions {
+            self.event_loop.jobs.push_back(Job::Reaction(reaction, value.clone()));
+        }
+    }
+
+    /// The `PerformPromiseThen` operation: registers handlers on
+    /// `promise`, whose results settle `capability`.
+    pub(super) fn perform_then(&mut self, promise: ObjectId, on_fulfilled: Handler, on_rejected: Handler, capability: Option<Capability>) {
+        let fulfill = Reaction { kind: ReactionKind::Fulfill, handler: on_fulfilled, capability: capability.clone() };
+        let reject = Reaction { kind: ReactionKind::Reject, handler: on_rejected, capability };
+        let state = self.promise_mut(promise);
+        let handled = std::mem::replace(&mut state.handled, true);
+        match state.settlement.clone() {
+            Settlement::Pending => {
+                state.fulfill_reactions.push(fulfill);
+                state.reject_reactions.push(reject);
+            }
+            Settlement::Fulfilled(value) => self.event_loop.jobs.push_back(Job::Reaction(fulfill, value)),
+            Settlement::Rejected(reason) => {
+                if !handled {
+                    self.event_loop.rejections.retain(|&id| id != promise);
+                }
+                self.event_loop.jobs.push_back(Job::Reaction(reject, reason));
+            }
+        }
+    }
+
+    /// The `NewPromiseCapability` operation: constructs a promise with
+    /// `constructor`, capturing the functions that settle it.
+    pub(super) fn new_promise_capability(&mut self, constructor: &Value) -> Result<Capability, String> {
+        if *constructor == Value::Function(self.intrinsics.promise_constructor) {
+            let promise = self.create_promise(self.intrinsics.promise_prototype);
+            let (resolve, reject) = self.create_resolving_functions(promise);
+            return Ok(Capability { promise: Value::Object(promise), resolve, reject });
+        }
+        if !self.is_constructor(constructor) {
+            return Err(type_error(format!("{} is not a constructor", self.to_display_string(constructor))));
+        }
+        let slots = Rc::new(RefCell::new((Value::Undefined, Value::Undefined)));
+        let executor = self.create_promise_function(PromiseFunction::CapabilityExecutor(Rc::clone(&slots)), 2);
+        let promise = self.construct(constructor, vec![executor], constructor)?;
+        let (resolve, reject) = slots.borrow().clone();
+        if !matches!((&resolve, &reject), (Value::Function(_), Value::Function(_))) {
+            return Err(type_error("Promise resolve or reject function is not callable"));
+        }
+        Ok(Capability { promise, resolve, reject })
+    }
+
+    /// The `PromiseResolve` operation: `value` itself if it is a promise
+    /// made by `constructor`, otherwise a new promise resolved with it.
+    pub(super) fn promise_resolve(&mut self, constructor: &Value, value: Value) -> Result<Value, String> {
+        if self.promise_id(&value).is_some() {
+            let value_constructor = self.get_member(&value, &PropertyKey::from("constructor"))?;
+            if strict_equals(&value_constructor, constructor) {
+                return Ok(value);
+            }
+        }
+        let capability = self.new_promise_capability(constructor)?;
+        self.call_function(&capability.resolve, Value::Undefined, vec![value])?;
+        Ok(capability.promise)
+    }
+
+    /// The constructor derived promises are made with: the `constructor`
+    /// of `promise` when that is `Promise` or a subclass of it, else the
+    /// `Promise` intrinsic.
+    pub(super) fn promise_species_constructor(&mut self, promise: &Value) -> Result<Value, String> {
+        let default = Value::Function(self.intrinsics.promise_constructor);
+        let constructor = self.get_member(promise, &PropertyKey::from("constructor"))?;
+        let Value::Function(mut current) = constructor else {
+            return match constructor {
+                Value::Undefined | Value::Object(_) => Ok(default),
+                _ => Err(type_error("The .constructor property is not an object")),
+            };
+        };
+        // Subclasses inherit `Promise[Symbol.species]`, which returns the
+        // constructor it is looked up on
+        loop {
+            if current == self.intrinsics.promise_constructor {
+                return Ok(constructor);
+            }
+            match self.heap.get(current).prototype {
+                Some(parent) => current = parent,
+                None => return Ok(default),
+            }
+        }
+    }
+
+    /// Calls an async function whose scope has been set up, returning the
+    /// promise for its result. The body runs until its first `await`.
+    pub(super) fn call_async(&mut self, definition: &Rc<FunctionDefinition>, args: Vec<Value>) -> Value {
+        let promise = self.create_promise(self.intrinsics.promise_prototype);
+        match self.bind_parameters(definition, args) {
+            Ok(()) => {
+                let name = self.frames.last().map(|frame| frame.function.clone()).unwrap_or_default();
+                let body = self.create_body(Body::Async(Rc::clone(definition), promise), None, name, None);
+                self.resume_async(body, ResumeAction::Next(Value::Undefined));
+            }
+            Err(message) => {
+                let reason = self.catch_exception(&message);
+                self.reject_promise(promise, reason);
+            }
+        }
+        Value::Object(promise)
+    }
+
+    /// `await value`: suspends the running body until `value` settles.
+    pub(super) fn eval_await(&mut self, argument: &Expression) -> Result<Value, String> {
+        let value = self.eval_expression(argument)?;
+        self.await_value(value)
+    }
+
+    /// The `Await` operation, returning what `value` fulfills with.
+    pub(super) fn await_value(&mut self, value: Value) -> Result<Value, String> {
+        let constructor = Value::Function(self.intrinsics.promise_constructor);
+        let promise = self.promise_resolve(&constructor, value)?;
+        let promise = self.promise_id(&promise).expect("Promise made something other than a promise");
+        match self.generator_suspend(GeneratorStep::Await(promise)) {
+            ResumeAction::Next(value) => Ok(value),
+            ResumeAction::Throw(reason) => Err(self.throw_value(reason)),
+            ResumeAction::Return(_) => unreachable!("async bodies are only resumed by their promises"),
+        }
+    }
+
+    /// Runs the async body `id` until it awaits or completes.
+    fn resume_async(&mut self, id: ObjectId, action: ResumeAction) {
+        if self.is_generator(id, true) {
+            return self.resume_async_generator(id, action);
+        }
+        let result = match self.resume_generator(id, action) {
+            Ok(GeneratorStep::Await(promise)) => {
+                return self.perform_then(promise, Handler::Resume(id), Handler::Resume(id), None);
+            }
+            Ok(GeneratorStep::Return(value)) => Ok(value),
+            Ok(GeneratorStep::Yield(_) | GeneratorStep::Delegated(_)) => unreachable!("yield in an async body"),
+            Err(message) => Err(message),
+        };
+        match self.generator_mut(id).body.clone() {
+            Body::Async(_, promise) => match result {
+                Ok(value) => self.resolve_promise(promise, value),
+                Err(message) => {
+                    let reason = self.catch_exception(&message);
+                    self.reject_promise(promise, reason);
+                }
+            },
+            Body::Script(_, promise) => match result {
+                Ok(value) => {
+                    self.last_value = Some(value.clone());
+                    self.fulfill_promise(promise, value);
+                }
+                Err(message) => {
+                    // The error is reported as the script's own, not as an
+                    // unhandled rejection
+                    let reason = self.catch_exception(&message);
+                    self.promise_mut(promise).handled = true;
+                    self.reject_promise(promise, reason);
+                    if let Some(script) = self.event_loop.script.as_mut().filter(|script| script.body == id) {
+                        script.error = Some(message);
+                    }
+                }
+            },
+            Body::Generator(_) | Body::AsyncGenerator(_) => unreachable!("generators are resumed by their methods"),
+        }
+    }
+
+    /// Runs a top-level script, followed by the jobs it queued. Returns its
+    /// completion value, or a promise for it if it is still waiting on an
+    /// `await`.
+    pub(super) fn run_script(&mut self, statements: Vec<Statement>) -> Result<Value, String> {
+        // Nothing is running before a script, so nothing is held
+        self.heap.temporaries.clear();
+        let promise = self.create_promise(self.intrinsics.promise_prototype);
+        let body = self.create_body(Body::Script(statements.into(), promise), None, String::new(), None);
+        self.event_loop.script = Some(Script { body, promise, error: None });
+        self.resume_async(body, ResumeAction::Next(Value::Undefined));
+        self.run_until_idle()
+    }
+
+    /// Runs queued jobs until there are none left, then reports how the
+    /// last script has done: its completion value, the error it threw, or
+    /// the promise for its completion while it is still waiting on an
+    /// `await`. A promise that was rejected without a handler, or an
+    /// exception a job threw, is reported as an error too.
+    pub fn run_until_idle(&mut self) -> Result<Value, String> {
+        self.run_jobs();
+        let Some(script) = &self.event_loop.script else {
+            return Ok(Value::Undefined);
+        };
+        let promise = script.promise;
+        if let Some(error) = script.error.clone().or_else(|| self.event_loop.uncaught.take()) {
+            self.event_loop.uncaught = None;
+            self.event_loop.rejections.clear();
+            return Err(error);
+        }
+        if !self.event_loop.rejections.is_empty() {
+            let rejected = std::mem::take(&mut self.event_loop.rejections)[0];
+            let Settlement::Rejected(reason) = self.promise_mut(rejected).settlement.clone() else {
+                unreachable!("unhandled rejection of an unrejected promise")
+            };
+            return Err(self.throw_value(reason));
+        }
+        match &self.promise_mut(promise).settlement {
+            Settlement::Fulfilled(value) => Ok(value.clone()),
+            _ => Ok(Value::Object(promise)),
+        }
+    }
+
+    /// Queues a job calling `callback`.
+    pub(super) fn queue_microtask(&mut self, callback: Value) {
+        self.event_loop.jobs.push_back(Job::Callback(callback));
+    }
+
+    /// Runs jobs until the queue is empty.
+    pub(super) fn run_jobs(&mut self) {
+        while let Some(job) = self.event_loop.jobs.pop_front() {
+            let mark = self.heap.temporaries.len();
+            let mut references = Vec::new();
+            job.add_references(&mut references);
+            for value in &references {
+                self.hold(value);
+            }
+            self.run_job(job);
+            // No job is running, so only the interpreter holds on to objects
+            self.heap.temporaries.truncate(mark);
+            self.maybe_collect_garbage(&[]);
+        }
+    }
+
+    fn run_job(&mut self, job: Job) {
+        let (reaction, argument) = match job {
+            Job::Reaction(reaction, argument) => (reaction, argument),
+            Job::ResolveThenable { promise, thenable, then } => {
+                let (resolve, reject) = self.create_resolving_functions(promise);
+                if let Err(message) = self.call_function(&then, thenable, vec![resolve, reject.clone()]) {
+                    let reason = self.catch_exception(&message);
+                    let result = self.call_function(&reject, Value::Undefined, vec![reason]);
+                    self.report_job_error(result);
+                }
+                return;
+            }
+            Job::Callback(callback) => {
+                let result = self.call_function(&callback, Value::Undefined, Vec::new());
+                return self.report_job_error(result);
+            }
+        };
+        let outcome = match reaction.handler {
+            Handler::Identity if reaction.kind == ReactionKind::Fulfill => Ok(argument),
+            Handler::Identity => Err(argument),
+            Handler::Callback(function) => match self.call_function(&function, Value::Undefined, vec![argument]) {
+                Ok(value) => Ok(value),
+                Err(message) => Err(self.catch_exception(&message)),
+            },
+            Handler::Resume(body) => {
+                let action = match reaction.kind {
+                    ReactionKind::Fulfill => ResumeAction::Next(argument),
+                    ReactionKind::Reject => ResumeAction::Throw(argument),
+                };
+                return self.resume_async(body, action);
+            }
+        };
+        let Some(capability) = reaction.capability else {
+            return;
+        };
+        let result = match outcome {
+            Ok(value) => self.call_function(&capability.resolve, Value::Undefined, vec![value]),
+            Err(reason) => self.call_function(&capability.reject, Value::Undefined, vec![reason]),
+        };
+        self.report_job_error(result);
+    }
+
+    /// Keeps the first exception a job threw, to report once the jobs are
+    /// done.
+    pub(super) fn report_job_error(&mut self, result: Result<Value, String>) {
+        if let Err(message) = result {
+            self.exception = None;
+            self.event_loop.uncaught.get_or_insert(message);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! The stacks that generator bodies, async calls and scripts run on.
+//!
+//! Every suspended body keeps a stack of its own, and programs keep
+//! hundreds of thousands of them suspended at once. Stacks are therefore
+//! carved out of large allocations rather than each mapped separately,
+//! which would soon use up the memory mappings a process may have. Only
+//! the pages a body touches are ever committed.
+//!
+//! Every call, statement and expression checks that `STACK_RED_ZONE` is
+//! left and moves to a segment grown onto the heap when it is not, as on
+//! the thread's own stack (see `Interpreter::with_stack`), so bodies keep
+//! clear of the end of their stack. Should one run past it anyway, it hits
+//! the guard below the stack rather than the stack of another body.
+//! Protecting a guard splits the mapping of its allocation in two, so only
+//! the first `MAX_GUARDS` stacks keep theirs protected for good; those
+//! handed out beyond that are only protected while their body runs, which
+//! is when it can overflow.
+
+use std::alloc::{alloc, dealloc, Layout};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use corosensei::stack::{Stack, StackPointer};
+
+use super::STACK_RED_ZONE;
+
+/// The size of each stack including its guard, which leaves the red zone
+/// free with nearly as much again to spare.
+const STACK_SIZE: usize = 2 * STACK_RED_ZONE;
+
+/// The size of the guard at the bottom of each stack, a whole number of
+/// pages on any platform.
+const GUARD_SIZE: usize = 64 * 1024;
+
+/// How many stacks are allocated together.
+const STACKS_PER_CHUNK: usize = 64;
+
+/// How many stacks keep their guard protected while they are free or
+/// suspended, each using up two memory mappings.
+const MAX_GUARDS: usize = 8192;
+
+/// The stacks of an interpreter, shared by the stacks handed out so they
+/// can return themselves.
+#[derive(Clone, Default)]
+pub(super) struct StackPool(Rc<RefCell<Chunks>>);
+
+#[derive(Default)]
+struct Chunks {
+    /// The stacks of each allocation, by its address
+    chunks: HashMap<usize, Chunk>,
+    /// Allocations that had free stacks when last seen, most recent last.
+    /// Entries for those since used up or freed are skipped.
+    available: Vec<usize>,
+    /// How many stacks have their guard protected for good
+    guards: usize,
+}
+
+#[derive(Default)]
+struct Chunk {
+    free: Vec<usize>,
+    /// A bit for each stack whose guard is protected for good
+    guarded: u64,
+}
+
+/// A stack handed out by a `StackPool`, which returns to it when dropped.
+pub(super) struct PooledStack {
+    pool: StackPool,
+    chunk: usize,
+    slot: usize,
+    guarded: bool,
+}
+
+fn chunk_layout() -> Layout {
+    Layout::from_size_align(STACK_SIZE * STACKS_PER_CHUNK, GUARD_SIZE).expect("valid stack layout")
+}
+
+fn guard_address(chunk: usize, slot: usize) -> usize {
+    chunk + slot * STACK_SIZE
+}
+
+impl StackPool {
+    /// Hands out a free stack, or `None` when no memory is left for one.
+    pub(super) fn allocate(&self) -> Option<PooledStack> {
+        let mut chunks = self.0.borrow_mut();
+        let chunks = &mut *chunks;
+        let (chunk, slot) = loop {
+            let Some(&chunk) = chunks.available.last() else {
+                // SAFETY: the layout has a non-zero size
+                let memory = unsafe { alloc(chunk_layout()) };
+                if memory.is_null() {
+                    return None;
+                }
+                let chunk = memory as usize;
+                chunks.chunks.insert(chunk, Chunk { free: (1..STACKS_PER_CHUNK).rev().collect(), guarded: 0 });
+                chunks.available.push(chunk);
+                break (chunk, 0);
+            };
+            let free = chunks.chunks.get_mut(&chunk).map(|entry| (entry.free.pop(), entry.free.is_empty()));
+            if let Some((Some(slot), used_up)) = free {
+                if used_up {
+                    chunks.available.pop();
+                }
+                break (chunk, slot);
+            }
+            chunks.available.pop();
+        };
+
+        let entry = chunks.chunks.get_mut(&chunk).expect("chunk just found");
+        let mut guarded = entry.guarded & (1 << slot) != 0;
+        if !guarded && chunks.guards < MAX_GUARDS && set_guard(guard_address(chunk, slot), false) {
+            entry.guarded |= 1 << slot;
+            chunks.guards += 1;
+            guarded = true;
+        }
+        Some(PooledStack { pool: self.clone(), chunk, slot, guarded })
+    }
+
+    /// Takes back a stack, freeing its allocation once all its stacks are
+    /// free, unless it is the last one left. Guards stay protected until
+    /// then, ready for the next stack handed out.
+    fn release(&self, chunk: usize, slot: usize) {
+        let mut chunks = self.0.borrow_mut();
+        let entry = chunks.chunks.get_mut(&chunk).expect("stack from this pool");
+        entry.free.push(slot);
+        let count = entry.free.len();
+        if count == STACKS_PER_CHUNK && chunks.chunks.len() > 1 {
+            let entry = chunks.chunks.remove(&chunk).expect("chunk just found");
+            for slot in (0..STACKS_PER_CHUNK).filter(|slot| entry.guarded & (1 << slot) != 0) {
+                set_guard(guard_address(chunk, slot), true);
+            }
+            chunks.guards -= entry.guarded.count_ones() as usize;
+            // SAFETY: the chunk was allocated with this layout, none of its
+            // stacks are in use, and all of it is accessible again
+            unsafe { dealloc(chunk as *mut u8, chunk_layout()) };
+        } else if count == 1 {
+            chunks.available.push(chunk);
+        }
+    }
+}
+
+impl PooledStack {
+    /// Whether the guard is protected for good, rather than needing a
+    /// `Guard` while the stack is run on.
+    pub(super) fn is_guarded(&self) -> bool {
+        self.guarded
+    }
+}
+
+impl Drop for PooledStack {
+    fn drop(&mut self) {
+        self.pool.release(self.chunk, self.slot);
+    }
+}
+
+// SAFETY: the stack is memory of its own, aligned to a page, which stays
+// allocated while the stack exists. Below it is its guard, protected
+// whenever the stack is run on, as described above.
+unsafe impl Stack for PooledStack {
+    fn base(&self) -> StackPointer {
+        StackPointer::new(self.chunk + (self.slot + 1) * STACK_SIZE).expect("stack in allocated memory")
+    }
+
+    fn limit(&self) -> StackPointer {
+        StackPointer::new(guard_address(self.chunk, self.slot) + GUARD_SIZE).expect("stack in allocated memory")
+    }
+
+    #[cfg(windows)]
+    fn teb_fields(&self) -> corosensei::stack::StackTebFields {
+        corosensei::stack::StackTebFields {
+            StackBase: self.base().get(),
+            StackLimit: self.limit().get(),
+            DeallocationStack: self.limit().get(),
+            GuaranteedStackBytes: 0,
+        }
+    }
+
+    #[cfg(windows)]
+    fn update_teb_fields(&mut self, _stack_limit: usize, _guaranteed_stack_bytes: usize) {}
+}
+
+/// The guard of a stack that is not guarded for good, protected while the
+/// stack whose limit is `limit` is run on.
+pub(super) struct Guard(usize);
+
+impl Guard {
+    pub(super) fn protect(limit: usize) -> Self {
+        // A guard the kernel refuses is done without: the red zone checks
+        // still keep the stack from overflowing
+        set_guard(limit - GUARD_SIZE, false);
+        Guard(limit - GUARD_SIZE)
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        set_guard(self.0, true);
+    }
+}
+
+/// Makes the guard at `address` accessible or not, returning whether that
+/// worked.
+#[cfg(unix)]
+fn set_guard(address: usize, accessible: bool) -> bool {
+    let protection = if accessible { libc::PROT_READ | libc::PROT_WRITE } else { libc::PROT_NONE };
+    // SAFETY: the guard is whole pages of a stack allocation, which
+    // nothing but an overflowing stack touches
+    unsafe { libc::mprotect(address as *mut libc::c_void, GUARD_SIZE, protection) == 0 }
+}
+
+#[cfg(not(unix))]
+fn set_guard(_address: usize, _accessible: bool) -> bool {
+    false
+}
@@ -0,0 +1,68 @@
+//! The state of an interpreter, which `Interpreter` keeps on the heap so
+//! that generator bodies can run on interpreters of their own that share
+//! it.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::builtins;
+use super::environment::EnvRef;
+use super::exception::{Exception, StackFrame};
+use super::generator::{GeneratorYielder, Resumer};
+use super::object::{Heap, ObjectId};
+use super::promise::EventLoop;
+use super::stack::StackPool;
+use super::{Intrinsics, Symbol, Value, WellKnownSymbols};
+use crate::parser::TemplateLiteral;
+
+/// Everything an interpreter works with, reached through `Interpreter`,
+/// which dereferences to it.
+pub struct State {
+    /// The scope of the built-in globals, which encloses `global`
+    pub(super) builtin_scope: EnvRef,
+    /// The top-level scope of scripts and host variables
+    pub(super) global: EnvRef,
+    pub(super) env: EnvRef,
+    pub(super) heap: Heap,
+    pub(super) intrinsics: Intrinsics,
+    pub(super) call_depth: usize,
+    /// Arrays currently being joined, so cyclic arrays print as empty
+    pub(super) joining: Vec<ObjectId>,
+    /// The `new.target` of the native function being run, `None` when it
+    /// was called rather than constructed
+    pub(super) new_target: Option<Value>,
+    /// The exception currently propagating, if it has a value yet
+    pub(super) exception: Option<Exception>,
+    /// The active calls, for stack traces
+    pub(super) frames: Vec<StackFrame>,
+    /// Where `console` writes, and its counters and timers
+    pub(super) console: builtins::Console,
+    /// The generator behind `Math.random`
+    pub(super) random: builtins::Random,
+    /// The local time zone of `Date`
+    pub(super) time_zone: tz::TimeZone,
+    /// How many objects the built-ins occupy at the start of the heap,
+    /// which the garbage collector never frees
+    pub(super) builtin_objects: usize,
+    pub(super) last_value: Option<Value>,
+    pub(super) symbols: WellKnownSymbols,
+    /// The symbols `Symbol.for` has handed out, by key
+    pub(super) symbol_registry: HashMap<String, Symbol>,
+    /// The template object of each tagged template evaluated so far, which
+    /// every evaluation of it receives. The template is kept so its
+    /// address is not reused.
+    pub(super) template_objects: HashMap<*const TemplateLiteral, (Rc<TemplateLiteral>, ObjectId)>,
+    /// The scopes `in_scope` has replaced, restored as it returns
+    pub(super) scopes: Vec<EnvRef>,
+    /// What the code that resumed each running generator holds, innermost
+    /// last
+    pub(super) resumers: Vec<Resumer>,
+    /// The yielder of the generator whose body is running, or null
+    pub(super) yielder: *const GeneratorYielder,
+    /// The lowest address of the stack being run on, `None` until it is
+    /// known on the thread's own stack or a segment `stacker` grew
+    pub(super) stack_limit: Option<usize>,
+    /// The stacks generator bodies run on
+    pub(super) generator_stacks: StackPool,
+    pub(super) event_loop: EventLoop,
+}
@@ -0,0 +1,241 @@
+//! JavaScript strings, which are sequences of UTF-16 code units rather than
+//! of characters: slicing can split a surrogate pair and escapes can create
+//! lone surrogates, and both must survive being joined back together.
+
+use std::cell::{OnceCell, RefCell};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// Strings shorter than this are copied when concatenated rather than
+/// joined into a rope.
+const MIN_ROPE_LENGTH: usize = 64;
+
+/// A string as JavaScript sees it. Converting to a Rust `String`, through
+/// `Display`, replaces lone surrogates with U+FFFD.
+///
+/// Clones share the code units, so reading a string out of a variable or
+/// property copies nothing. Concatenating long strings joins them into a
+/// rope, which is copied into a single buffer the first time its code
+/// units are read, so that building a string piece by piece takes linear
+/// time.
+#[derive(Clone)]
+pub struct JsString(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    /// Code units shared by clones, copied when one of them is changed
+    Flat(Rc<Vec<u16>>),
+    Rope(Rc<Rope>),
+}
+
+/// Two strings joined together.
+struct Rope {
+    len: usize,
+    /// The two halves, until the rope is flattened
+    halves: RefCell<Option<(JsString, JsString)>>,
+    flat: OnceCell<Rc<Vec<u16>>>,
+}
+
+impl Rope {
+    /// Copies the halves into one buffer, releasing them. Ropes nest as
+    /// deeply as the concatenations that built them, so this walks them
+    /// with a stack of its own.
+    fn flatten(&self) -> Rc<Vec<u16>> {
+        let mut units = Vec::with_capacity(self.len);
+        let mut pending: Vec<JsString> = self.halves.borrow_mut().take().map(|(left, right)| vec![right, left]).unwrap_or_default();
+        while let Some(s) = pending.pop() {
+            match &s.0 {
+                Repr::Flat(flat) => units.extend_from_slice(flat),
+                Repr::Rope(rope) => match rope.flat.get() {
+                    Some(flat) => units.extend_from_slice(flat),
+                    None => {
+                        let halves = rope.halves.borrow().clone().expect("an unflattened rope has halves");
+                        pending.extend([halves.1, halves.0]);
+                    }
+                },
+            }
+        }
+        Rc::new(units)
+    }
+}
+
+impl Drop for Rope {
+    /// Drops nested ropes one at a time rather than recursively.
+    fn drop(&mut self) {
+        let mut pending: Vec<JsString> = self.halves.get_mut().take().map(|(left, right)| vec![left, right]).unwrap_or_default();
+        while let Some(s) = pending.pop() {
+            if let Repr::Rope(rope) = s.0 {
+                if let Ok(mut rope) = Rc::try_unwrap(rope) {
+                    pending.extend(rope.halves.get_mut().take().into_iter().flat_map(|(left, right)| [left, right]));
+                }
+            }
+        }
+    }
+}
+
+impl Default for JsString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsString {
+    pub fn new() -> Self {
+        Self(Repr::Flat(Rc::default()))
+    }
+
+    /// `left` followed by `right`.
+    pub fn concat(left: &JsString, right: &JsString) -> Self {
+        if left.is_empty() {
+            return right.clone();
+        }
+        if right.is_empty() {
+            return left.clone();
+        }
+        let len = left.len() + right.len();
+        if len < MIN_ROPE_LENGTH {
+            let mut s = left.clone();
+            s.push_js_str(right);
+            return s;
+        }
+        let halves = RefCell::new(Some((left.clone(), right.clone())));
+        Self(Repr::Rope(Rc::new(Rope { len, halves, flat: OnceCell::new() })))
+    }
+
+    pub fn units(&self) -> &[u16] {
+        match &self.0 {
+            Repr::Flat(units) => units,
+            Repr::Rope(rope) => rope.flat.get_or_init(|| rope.flatten()),
+        }
+    }
+
+    /// The code units to change, unshared from any clones.
+    fn units_mut(&mut self) -> &mut Vec<u16> {
+        if let Repr::Rope(rope) = &self.0 {
+            let units = Rc::clone(rope.flat.get_or_init(|| rope.flatten()));
+            self.0 = Repr::Flat(units);
+        }
+        match &mut self.0 {
+            Repr::Flat(units) => Rc::make_mut(units),
+            Repr::Rope(_) => unreachable!("flattened above"),
+        }
+    }
+
+    /// The length in code units, as `length` reports it.
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Repr::Flat(units) => units.len(),
+            Repr::Rope(rope) => rope.len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push_str(&mut self, s: &str) {
+        self.units_mut().extend(s.encode_utf16());
+    }
+
+    pub fn push_js_str(&mut self, s: &JsString) {
+        self.units_mut().extend_from_slice(s.units());
+    }
+
+    /// Shortens the string to its first `len` code units.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.units_mut().truncate(len);
+        }
+    }
+
+    /// Whether the string has no lone surrogates, so that converting it to
+    /// a Rust `String` loses nothing.
+    pub fn is_well_formed(&self) -> bool {
+        char::decode_utf16(self.units().iter().copied()).all(|c| c.is_ok())
+    }
+}
+
+impl PartialEq for JsString {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.units() == other.units()
+    }
+}
+
+impl Eq for JsString {}
+
+impl Hash for JsString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.units().hash(state);
+    }
+}
+
+impl PartialOrd for JsString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JsString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.units().cmp(other.units())
+    }
+}
+
+impl From<Vec<u16>> for JsString {
+    fn from(units: Vec<u16>) -> Self {
+        Self(Repr::Flat(Rc::new(units)))
+    }
+}
+
+impl From<&[u16]> for JsString {
+    fn from(units: &[u16]) -> Self {
+        Self::from(units.to_vec())
+    }
+}
+
+impl From<&str> for JsString {
+    fn from(s: &str) -> Self {
+        Self::from(s.encode_utf16().collect::<Vec<u16>>())
+    }
+}
+
+impl From<String> for JsString {
+    fn from(s: String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<&String> for JsString {
+    fn from(s: &String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl PartialEq<str> for JsString {
+    fn eq(&self, other: &str) -> bool {
+        self.units().iter().copied().eq(other.encode_utf16())
+    }
+}
+
+impl PartialEq<&str> for JsString {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl fmt::Display for JsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in char::decode_utf16(self.units().iter().copied()) {
+            fmt::Write::write_char(f, c.unwrap_or(char::REPLACEMENT_CHARACTER))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for JsString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string(), f)
+    }
+}
@@ -0,0 +1,175 @@
+//! Timers, and the clock they run on.
+//!
+//! Timer callbacks are macrotasks: each runs on its own once it is due,
+//! followed by the jobs it queued. By default time is virtual and only
+//! passes when the embedder calls `advance_time`, so scripts using timers
+//! run the same way every time; the `tsi` binary switches to real time.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use super::{Interpreter, Value};
+
+/// The longest delay a timer accepts, in milliseconds; longer delays, like
+/// shorter ones than 1, are taken to be 1, as in Node.
+const MAX_DELAY: f64 = 2147483647.0;
+
+/// Where the current time comes from.
+pub(super) enum Clock {
+    /// Milliseconds that have been let pass with `advance_time`
+    Virtual(u64),
+    /// Milliseconds since the given instant
+    Real(Instant),
+}
+
+impl Clock {
+    /// The current time in milliseconds.
+    pub(super) fn now(&self) -> u64 {
+        match self {
+            Clock::Virtual(now) => *now,
+            Clock::Real(start) => start.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// A callback waiting for its time to come.
+struct Timer {
+    /// The id that cancels it, which an interval keeps from run to run
+    id: u32,
+    callback: Value,
+    args: Vec<Value>,
+    /// The period of an interval, which is scheduled again each time it runs
+    interval: Option<u64>,
+}
+
+/// The pending timers, ordered by when they are due and then by the order
+/// they were scheduled in. An interval is scheduled anew each time it runs,
+/// so it comes after the timers created before that with the same due time.
+pub(super) struct Timers {
+    pub(super) clock: Clock,
+    /// The Unix time in milliseconds at which the clock read zero, which
+    /// `Date.now()` counts from
+    pub(super) epoch: f64,
+    queue: BTreeMap<(u64, u64), Timer>,
+    next_id: u32,
+    next_sequence: u64,
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Self { clock: Clock::Virtual(0), epoch: 0.0, queue: BTreeMap::new(), next_id: 1, next_sequence: 0 }
+    }
+}
+
+impl Timers {
+    /// The values the pending timers keep alive.
+    pub(super) fn references(&self) -> impl Iterator<Item = &Value> {
+        self.queue.values().flat_map(|timer| std::iter::once(&timer.callback).chain(&timer.args))
+    }
+
+    /// Queues `timer` to run at `due`, after those already due then.
+    fn schedule(&mut self, due: u64, timer: Timer) {
+        self.queue.insert((due, self.next_sequence), timer);
+        self.next_sequence += 1;
+    }
+
+    /// When the next timer is due, if there is one.
+    fn next_due(&self) -> Option<u64> {
+        self.queue.keys().next().map(|&(due, _)| due)
+    }
+}
+
+impl Interpreter {
+    /// Schedules `callback` to be called with `args` after `delay`
+    /// milliseconds, and every `delay` milliseconds after that if
+    /// `repeat` is set. Returns the id that cancels it.
+    pub(super) fn set_timer(&mut self, callback: Value, delay: f64, args: Vec<Value>, repeat: bool) -> u32 {
+        let delay = if (1.0..=MAX_DELAY).contains(&delay) { delay as u64 } else { 1 };
+        let timers = &mut self.event_loop.timers;
+        let id = timers.next_id;
+        timers.next_id += 1;
+        let due = timers.clock.now() + delay;
+        timers.schedule(due, Timer { id, callback, args, interval: repeat.then_some(delay) });
+        id
+    }
+
+    /// Cancels the timer `id`, if it is still pending.
+    pub(super) fn clear_timer(&mut self, id: u32) {
+        self.event_loop.timers.queue.retain(|_, timer| timer.id != id);
+    }
+
+    /// Switches to the real clock, so that timers wait for the time they
+    /// were set for to actually pass and `Date.now()` is the system time.
+    pub fn use_real_time(&mut self) {
+        let timers = &mut self.event_loop.timers;
+        timers.clock = Clock::Real(Instant::now());
+        timers.epoch = super::date::system_time();
+    }
+
+    /// Lets `ms` milliseconds pass, running the timers that become due in
+    /// that time, then reports how the last script has done like
+    /// `run_until_idle`. Stops early at a timer whose callback throws or
+    /// leaves a promise rejected without a handler. With the real clock,
+    /// this sleeps.
+    pub fn advance_time(&mut self, ms: u64) -> Result<Value, String> {
+        let deadline = self.event_loop.timers.clock.now() + ms;
+        if self.run_timers_until(deadline) {
+            self.wait_until(deadline);
+        }
+        self.run_until_idle()
+    }
+
+    /// Runs timers until none are left, letting time pass as needed, then
+    /// reports how the last script has done like `run_until_idle`. Never
+    /// returns while an interval is still set.
+    pub fn run_event_loop(&mut self) -> Result<Value, String> {
+        self.run_jobs();
+        while let Some(due) = self.event_loop.timers.next_due() {
+            if !self.run_timers_until(due) {
+                break;
+            }
+        }
+        self.run_until_idle()
+    }
+
+    /// Runs the timers due by `deadline` in order, each followed by the
+    /// jobs it queued. Returns false if one of them failed.
+    fn run_timers_until(&mut self, deadline: u64) -> bool {
+        loop {
+            if self.event_loop.has_failed() {
+                return false;
+            }
+            let Some(&(due, sequence)) = self.event_loop.timers.queue.keys().next().filter(|&&(due, _)| due <= deadline) else {
+                return true;
+            };
+            self.wait_until(due);
+            let timers = &mut self.event_loop.timers;
+            let timer = timers.queue.remove(&(due, sequence)).expect("timer is queued");
+            if let Some(interval) = timer.interval {
+                // Scheduled before the callback runs, so that it can clear it
+                let next = Timer { id: timer.id, callback: timer.callback.clone(), args: timer.args.clone(), interval: timer.interval };
+                timers.schedule(due + interval, next);
+            }
+            let mark = self.heap.temporaries.len();
+            let result = self.call_function(&timer.callback, Value::Undefined, timer.args);
+            self.report_job_error(result);
+            self.heap.temporaries.truncate(mark);
+            self.maybe_collect_garbage(&[]);
+            self.run_jobs();
+        }
+    }
+
+    /// Moves the clock on to `time`, sleeping if it is the real clock.
+    fn wait_until(&mut self, time: u64) {
+        match &mut self.event_loop.timers.clock {
+            Clock::Virtual(now) => *now = (*now).max(time),
+            Clock::Real(start) => {
+                let elapsed = start.elapsed();
+                let target = Duration::from_millis(time);
+                if target > elapsed {
+                    std::thread::sleep(target - elapsed);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Binary data: the bytes of `ArrayBuffer`s, the element types typed
+//! arrays and `DataView` read them as, and the element access that makes
+//! typed arrays integer-indexed exotic objects.
+//!
+//! Embedders can hand bytes to scripts without copying them:
+//! `create_array_buffer` takes ownership of a `Vec<u8>`, and
+//! `array_buffer_bytes` borrows the bytes back out of a buffer or view.
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use super::bigint::{as_int_n, as_uint_n};
+use super::object::{Object, ObjectId, ObjectKind, PropertyKey};
+use super::{number_to_string, range_error, string_to_number, to_int32, to_uint32, Interpreter, Value};
+
+/// The element types of typed arrays, which `DataView` also reads and
+/// writes, apart from `Uint8Clamped`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElementType {
+    Int8,
+    Uint8,
+    Uint8Clamped,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
+    BigInt64,
+    BigUint64,
+}
+
+impl ElementType {
+    /// The name of the type, as in `Int8Array` and `DataView.prototype.getInt8`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ElementType::Int8 => "Int8",
+            ElementType::Uint8 => "Uint8",
+            ElementType::Uint8Clamped => "Uint8Clamped",
+            ElementType::Int16 => "Int16",
+            ElementType::Uint16 => "Uint16",
+            ElementType::Int32 => "Int32",
+            ElementType::Uint32 => "Uint32",
+            ElementType::Float32 => "Float32",
+            ElementType::Float64 => "Float64",
+            ElementType::BigInt64 => "BigInt64",
+            ElementType::BigUint64 => "BigUint64",
+        }
+    }
+
+    /// The size of an element in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            ElementType::Int8 | ElementType::Uint8 | ElementType::Uint8Clamped => 1,
+            ElementType::Int16 | ElementType::Uint16 => 2,
+            ElementType::Int32 | ElementType::Uint32 | ElementType::Float32 => 4,
+            ElementType::Float64 | ElementType::BigInt64 | ElementType::BigUint64 => 8,
+        }
+    }
+
+    /// Whether elements are BigInts rather than numbers.
+    pub fn is_bigint(self) -> bool {
+        matches!(self, ElementType::BigInt64 | ElementType::BigUint64)
+    }
+
+    /// Reads an element from exactly `size` bytes.
+    pub fn decode(self, bytes: &[u8], little_endian: bool) -> Value {
+        let mut raw = [0u8; 8];
+        raw[..bytes.len()].copy_from_slice(bytes);
+        if !little_endian {
+            raw[..bytes.len()].reverse();
+        }
+        let [a, b, c, d, ..] = raw;
+        match self {
+            ElementType::Int8 => Value::Number(a as i8 as f64),
+            ElementType::Uint8 | ElementType::Uint8Clamped => Value::Number(a as f64),
+            ElementType::Int16 => Value::Number(i16::from_le_bytes([a, b]) as f64),
+            ElementType::Uint16 => Value::Number(u16::from_le_bytes([a, b]) as f64),
+            ElementType::Int32 => Value::Number(i32::from_le_bytes([a, b, c, d]) as f64),
+            ElementType::Uint32 => Value::Number(u32::from_le_bytes([a, b, c, d]) as f64),
+            ElementType::Float32 => Value::Number(f32::from_le_bytes([a, b, c, d]) as f64),
+            ElementType::Float64 => Value::Number(f64::from_le_bytes(raw)),
+            ElementType::BigInt64 => Value::BigInt(BigInt::from(i64::from_le_bytes(raw))),
+            ElementType::BigUint64 => Value::BigInt(BigInt::from(u64::from_le_bytes(raw))),
+        }
+    }
+
+    /// Writes an element into exactly `size` bytes. `value` must already
+    /// be converted, as `Interpreter::coerce_to_element` does.
+    pub fn encode(self, value: &Value, bytes: &mut [u8], little_endian: bool) {
+        let n = match value {
+            Value::Number(n) => *n,
+            _ => 0.0,
+        };
+        let raw: [u8; 8] = match (self, value) {
+            (ElementType::BigInt64, Value::BigInt(b)) => as_int_n(64, b).ok().and_then(|b| b.to_i64()).unwrap_or(0).to_le_bytes(),
+            (ElementType::BigUint64, Value::BigInt(b)) => as_uint_n(64, b).ok().and_then(|b| b.to_u64()).unwrap_or(0).to_le_bytes(),
+            (ElementType::BigInt64 | ElementType::BigUint64, _) => [0; 8],
+            (ElementType::Int8 | ElementType::Int16 | ElementType::Int32, _) => (to_int32(n) as i64).to_le_bytes(),
+            (ElementType::Uint8 | ElementType::Uint16 | ElementType::Uint32, _) => (to_uint32(n) as u64).to_le_bytes(),
+            (ElementType::Uint8Clamped, _) => (clamp_to_uint8(n) as u64).to_le_bytes(),
+            (ElementType::Float32, _) => {
+                let mut raw = [0; 8];
+                raw[..4].copy_from_slice(&(n as f32).to_le_bytes());
+                raw
+            }
+            (ElementType::Float64, _) => n.to_le_bytes(),
+        };
+        // The integer types keep only their low bytes, wrapping as
+        // `ToInt8` and the other conversions do
+        bytes.copy_from_slice(&raw[..bytes.len()]);
+        if !little_endian {
+            bytes.reverse();
+        }
+    }
+}
+
+/// The `ToUint8Clamp` conversion: rounds half to even and saturates.
+fn clamp_to_uint8(n: f64) -> u8 {
+    if n.is_nan() || n <= 0.0 {
+        return 0;
+    }
+    if n >= 255.0 {
+        return 255;
+    }
+    let floor = n.floor();
+    let rounded = match n - floor {
+        half if half > 0.5 => floor + 1.0,
+        half if half < 0.5 => floor,
+        _ if floor % 2.0 == 0.0 => floor,
+        _ => floor + 1.0,
+    };
+    rounded as u8
+}
+
+/// Typed arrays store elements in the byte order of the machine, as in
+/// other engines.
+const NATIVE_LITTLE_ENDIAN: bool = cfg!(target_endian = "little");
+
+/// The bytes of an `ArrayBuffer` or `SharedArrayBuffer`.
+#[derive(Debug)]
+pub struct ArrayBufferData {
+    pub bytes: Vec<u8>,
+    pub shared: bool,
+}
+
+/// The internal slots of a typed array: which part of which buffer it
+/// views. Buffers cannot be resized or detached, so neither can change.
+#[derive(Clone, Copy, Debug)]
+pub struct TypedArrayData {
+    pub element: ElementType,
+    pub buffer: ObjectId,
+    pub byte_offset: usize,
+    pub length: usize,
+}
+
+impl TypedArrayData {
+    pub fn byte_length(&self) -> usize {
+        self.length * self.element.size()
+    }
+}
+
+/// The internal slots of a `DataView`.
+#[derive(Clone, Copy, Debug)]
+pub struct DataViewData {
+    pub buffer: ObjectId,
+    pub byte_offset: usize,
+    pub byte_length: usize,
+}
+
+/// The largest buffer scripts may allocate, so that a mistaken length
+/// fails quickly instead of exhausting memory.
+const MAX_BYTE_LENGTH: usize = 1 << 32;
+
+impl Interpreter {
+    /// Wraps `bytes` in a new `ArrayBuffer`, taking ownership of them
+    /// rather than copying. Like other values the host holds, the buffer
+    /// must be made reachable, for example with `set_variable`, before
+    /// more code runs.
+    pub fn create_array_buffer(&mut self, bytes: Vec<u8>) -> Value {
+        let data = ArrayBufferData { bytes, shared: false };
+        let prototype = Some(self.intrinsics.array_buffer_prototype);
+        Value::Object(self.heap.allocate(Object::new(ObjectKind::ArrayBuffer(data), prototype)))
+    }
+
+    /// The bytes an `ArrayBuffer` or `SharedArrayBuffer` holds, or the
+    /// part of its buffer a typed array or `DataView` views. `None` for
+    /// any other value, or once the object has been garbage collected.
+    pub fn array_buffer_bytes(&self, value: &Value) -> Option<&[u8]> {
+        let (buffer, range) = self.viewed_range(value)?;
+        let ObjectKind::ArrayBuffer(data) = &self.heap.get(buffer).kind else { unreachable!("views refer to buffers") };
+        Some(&data.bytes[range])
+    }
+
+    /// Like `array_buffer_bytes`, but allows the bytes to be changed in
+    /// place.
+    pub fn array_buffer_bytes_mut(&mut self, value: &Value) -> Option<&mut [u8]> {
+        let (buffer, range) = self.viewed_range(value)?;
+        Some(&mut self.buffer_bytes_mut(buffer)[range])
+    }
+
+    /// The buffer behind a buffer or view, and the range of bytes seen.
+    fn viewed_range(&self, value: &Value) -> Option<(ObjectId, std::ops::Range<usize>)> {
+        let Value::Object(id) = value else { return None };
+        match &self.heap.try_get(*id)?.kind {
+            ObjectKind::ArrayBuffer(data) => Some((*id, 0..data.bytes.len())),
+            ObjectKind::TypedArray(view) => Some((view.buffer, view.byte_offset..view.byte_offset + view.byte_length())),
+            ObjectKind::DataView(view) => Some((view.buffer, view.byte_offset..view.byte_offset + view.byte_length)),
+            _ => None,
+        }
+    }
+
+    /// Allocates a zeroed buffer, failing with a `RangeError` rather than
+    /// aborting when the memory is not available.
+    pub(super) fn allocate_array_buffer(&mut self, length: usize, shared: bool, prototype: ObjectId) -> Result<ObjectId, String> {
+        let mut bytes = Vec::new();
+        if length > MAX_BYTE_LENGTH || bytes.try_reserve_exact(length).is_err() {
+            return Err(range_error("Array buffer allocation failed"));
+        }
+        bytes.resize(length, 0);
+        let data = ArrayBufferData { bytes, shared };
+        Ok(self.heap.allocate(Object::new(ObjectKind::ArrayBuffer(data), Some(prototype))))
+    }
+
+    /// Creates a typed array of `length` zeroed elements over a new buffer.
+    pub(super) fn create_typed_array(&mut self, element: ElementType, length: usize, prototype: ObjectId) -> Result<ObjectId, String> {
+        let byte_length = length.checked_mul(element.size()).ok_or_else(|| range_error("Array buffer allocation failed"))?;
+        let buffer = self.allocate_array_buffer(byte_length, false, self.intrinsics.array_buffer_prototype)?;
+        let data = TypedArrayData { element, buffer, byte_offset: 0, length };
+        Ok(self.heap.allocate(Object::new(ObjectKind::TypedArray(data), Some(prototype))))
+    }
+
+    pub(super) fn buffer_bytes(&self, buffer: ObjectId) -> &[u8] {
+        match &self.heap.get(buffer).kind {
+            ObjectKind::ArrayBuffer(data) => &data.bytes,
+            _ => unreachable!("not an array buffer"),
+        }
+    }
+
+    pub(super) fn buffer_bytes_mut(&mut self, buffer: ObjectId) -> &mut [u8] {
+        match &mut self.heap.get_mut(buffer).kind {
+            ObjectKind::ArrayBuffer(data) => &mut data.bytes,
+            _ => unreachable!("not an array buffer"),
+        }
+    }
+
+    /// What `key` names on the typed array `id`: `None` if `id` is not a
+    /// typed array or `key` is not numeric, `Some(Some(index))` for an
+    /// element, and `Some(None)` for a numeric key out of range, which
+    /// reads as `undefined` and ignores writes.
+    pub(super) fn typed_array_element(&self, id: ObjectId, key: &PropertyKey) -> Option<Option<usize>> {
+        let ObjectKind::TypedArray(data) = &self.heap.get(id).kind else {
+            return None;
+        };
+        // The `CanonicalNumericIndexString` operation
+        let n = match key {
+            PropertyKey::Index(index) => *index as f64,
+            PropertyKey::String(name) if name == "-0" => return Some(None),
+            PropertyKey::String(name) => {
+                let n = string_to_number(name);
+                if number_to_string(n) != *name {
+                    return None;
+                }
+                n
+            }
+            PropertyKey::Symbol(_) => return None,
+        };
+        if n.fract() != 0.0 || n < 0.0 || n >= data.length as f64 {
+            return Some(None);
+        }
+        Some(Some(n as usize))
+    }
+
+    /// The element at `index` of the typed array `id`, which must be in
+    /// range.
+    pub(super) fn read_element(&self, id: ObjectId, index: usize) -> Value {
+        let ObjectKind::TypedArray(data) = self.heap.get(id).kind else { unreachable!("not a typed array") };
+        let size = data.element.size();
+        let start = data.byte_offset + index * size;
+        data.element.decode(&self.buffer_bytes(data.buffer)[start..start + size], NATIVE_LITTLE_ENDIAN)
+    }
+
+    /// Converts `value` to the element type, then stores it at `index` if
+    /// that is in range. The conversion runs either way, as it may have
+    /// side effects.
+    pub(super) fn write_element(&mut self, id: ObjectId, index: Option<usize>, value: &Value) -> Result<(), String> {
+        let ObjectKind::TypedArray(data) = self.heap.get(id).kind else { unreachable!("not a typed array") };
+        let value = self.coerce_to_element(data.element, value)?;
+        if let Some(index) = index {
+            self.store_element(id, index, &value);
+        }
+        Ok(())
+    }
+
+    /// Stores an already converted value at `index`, which must be in
+    /// range.
+    pub(super) fn store_element(&mut self, id: ObjectId, index: usize, value: &Value) {
+        let ObjectKind::TypedArray(data) = self.heap.get(id).kind else { unreachable!("not a typed array") };
+        let size = data.element.size();
+        let start = data.byte_offset + index * size;
+        let bytes = &mut self.buffer_bytes_mut(data.buffer)[start..start + size];
+        data.element.encode(value, bytes, NATIVE_LITTLE_ENDIAN);
+    }
+
+    /// `ToBigInt` for the BigInt types and `ToNumber` for the others.
+    pub(super) fn coerce_to_element(&mut self, element: ElementType, value: &Value) -> Result<Value, String> {
+        if element.is_bigint() {
+            self.coerce_to_bigint(value).map(Value::BigInt)
+        } else {
+            self.coerce_to_number(value).map(Value::Number)
+        }
+    }
+
+    /// All the elements of the typed array `id`.
+    pub(super) fn typed_array_values(&self, id: ObjectId) -> Vec<Value> {
+        let ObjectKind::TypedArray(data) = self.heap.get(id).kind else { unreachable!("not a typed array") };
+        (0..data.length).map(|index| self.read_element(id, index)).collect()
+    }
+}
@@ -0,0 +1,286 @@
+//! Values and the conversions between primitive types
+
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+
+use super::bigint::{compare_with_number, string_to_bigint};
+use super::object::ObjectId;
+use super::string::JsString;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Number(f64),
+    BigInt(BigInt),
+    String(JsString),
+    Boolean(bool),
+    Null,
+    Undefined,
+    Symbol(Symbol),
+    Object(ObjectId),
+    /// A callable object, closing over the scope it was defined in
+    Function(ObjectId),
+}
+
+impl Value {
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Number(n) => *n != 0.0 && !n.is_nan(),
+            Value::BigInt(n) => !n.is_zero(),
+            Value::String(s) => !s.is_empty(),
+            Value::Boolean(b) => *b,
+            Value::Null | Value::Undefined => false,
+            Value::Symbol(_) | Value::Object(_) | Value::Function(_) => true,
+        }
+    }
+
+    /// The result of the `typeof` operator.
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::BigInt(_) => "bigint",
+            Value::String(_) => "string",
+            Value::Boolean(_) => "boolean",
+            Value::Undefined => "undefined",
+            Value::Symbol(_) => "symbol",
+            Value::Null | Value::Object(_) => "object",
+            Value::Function(_) => "function",
+        }
+    }
+
+    /// The `ToNumber` abstract operation for primitives. Objects must be
+    /// converted with `Interpreter::coerce_to_primitive` first. BigInts,
+    /// which `ToNumber` rejects, convert like `Number(bigint)` does.
+    pub fn to_number(&self) -> f64 {
+        match self {
+            Value::Number(n) => *n,
+            Value::BigInt(n) => n.to_f64().unwrap_or(f64::NAN),
+            Value::String(s) => string_to_number(&s.to_string()),
+            Value::Boolean(b) => if *b { 1.0 } else { 0.0 },
+            Value::Null => 0.0,
+            Value::Undefined | Value::Symbol(_) | Value::Object(_) | Value::Function(_) => f64::NAN,
+        }
+    }
+
+    /// The `ToString` abstract operation for primitives. Objects must be
+    /// converted with `Interpreter::coerce_to_primitive` first.
+    pub fn to_utf16(&self) -> JsString {
+        match self {
+            Value::String(s) => s.clone(),
+            other => JsString::from(other.to_js_string()),
+        }
+    }
+
+    /// `to_utf16` as a Rust string, in which lone surrogates become U+FFFD.
+    pub fn to_js_string(&self) -> String {
+        match self {
+            Value::Number(n) => number_to_string(*n),
+            Value::BigInt(n) => n.to_string(),
+            Value::String(s) => s.to_string(),
+            Value::Boolean(b) => b.to_string(),
+            Value::Null => "null".to_string(),
+            Value::Undefined => "undefined".to_string(),
+            Value::Symbol(symbol) => symbol.to_string(),
+            Value::Object(_) => "[object Object]".to_string(),
+            Value::Function(_) => "function".to_string(),
+        }
+    }
+}
+
+/// A symbol. Every symbol created is distinct from all others, however it
+/// is described; copies of one share its identity.
+#[derive(Clone, Debug)]
+pub struct Symbol(Rc<Option<String>>);
+
+impl Symbol {
+    pub fn new(description: Option<String>) -> Self {
+        Self(Rc::new(description))
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl std::hash::Hash for Symbol {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Symbol({})", self.description().unwrap_or_default())
+    }
+}
+
+/// The `IsStrictlyEqual` abstract operation.
+pub fn strict_equals(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l == r,
+        (Value::BigInt(l), Value::BigInt(r)) => l == r,
+        (Value::String(l), Value::String(r)) => l == r,
+        (Value::Boolean(l), Value::Boolean(r)) => l == r,
+        (Value::Null, Value::Null) | (Value::Undefined, Value::Undefined) => true,
+        (Value::Symbol(l), Value::Symbol(r)) => l == r,
+        (Value::Object(l), Value::Object(r)) | (Value::Function(l), Value::Function(r)) => l == r,
+        _ => false,
+    }
+}
+
+/// The `SameValue` comparison used by `Object.is`: `NaN` equals itself and
+/// `+0` differs from `-0`.
+pub fn same_value(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => (l.is_nan() && r.is_nan()) || (l == r && l.is_sign_negative() == r.is_sign_negative()),
+        _ => strict_equals(left, right),
+    }
+}
+
+/// The `SameValueZero` comparison: strict equality, except that `NaN`
+/// equals itself.
+pub fn same_value_zero(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) if l.is_nan() && r.is_nan() => true,
+        _ => strict_equals(left, right),
+    }
+}
+
+/// The `IsLooselyEqual` abstract operation for primitives.
+pub fn loose_equals(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Null | Value::Undefined, Value::Null | Value::Undefined) => true,
+        (Value::Null | Value::Undefined, _) | (_, Value::Null | Value::Undefined) => false,
+        (Value::Boolean(b), other) | (other, Value::Boolean(b)) => loose_equals(&Value::Number(if *b { 1.0 } else { 0.0 }), other),
+        (Value::BigInt(b), Value::Number(n)) | (Value::Number(n), Value::BigInt(b)) => compare_with_number(b, *n) == Some(Ordering::Equal),
+        (Value::BigInt(b), Value::String(s)) | (Value::String(s), Value::BigInt(b)) => string_to_bigint(&s.to_string()).as_ref() == Some(b),
+        (Value::Number(_), Value::String(_))
+        | (Value::String(_), Value::Number(_)) => left.to_number() == right.to_number(),
+        _ => strict_equals(left, right),
+    }
+}
+
+/// The `IsLessThan` abstract operation: `None` when either side is NaN.
+pub(crate) fn less_than(left: &Value, right: &Value) -> Option<bool> {
+    match (left, right) {
+        (Value::String(l), Value::String(r)) => Some(l < r),
+        (Value::BigInt(l), Value::BigInt(r)) => Some(l < r),
+        (Value::BigInt(l), Value::String(r)) => string_to_bigint(&r.to_string()).map(|r| *l < r),
+        (Value::String(l), Value::BigInt(r)) => string_to_bigint(&l.to_string()).map(|l| l < *r),
+        (Value::BigInt(l), r) => compare_with_number(l, r.to_number()).map(|order| order == Ordering::Less),
+        (l, Value::BigInt(r)) => compare_with_number(r, l.to_number()).map(|order| order == Ordering::Greater),
+        _ => {
+            let (l, r) = (left.to_number(), right.to_number());
+            if l.is_nan() || r.is_nan() {
+                None
+            } else {
+                Some(l < r)
+            }
+        }
+    }
+}
+
+/// `Number::exponentiate`, which differs from `powf` for a NaN exponent and
+/// for `(±1) ** ±Infinity`.
+pub(crate) fn pow(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        f64::NAN
+    } else {
+        base.powf(exponent)
+    }
+}
+
+/// The `ToUint32` abstract operation.
+pub fn to_uint32(n: f64) -> u32 {
+    if !n.is_finite() {
+        return 0;
+    }
+    n.trunc().rem_euclid(4294967296.0) as u32
+}
+
+/// The `ToInt32` abstract operation.
+pub fn to_int32(n: f64) -> i32 {
+    to_uint32(n) as i32
+}
+
+/// `StringToNumber`: whitespace is trimmed, the empty string is zero and
+/// anything that is not a complete numeric literal is NaN.
+pub fn string_to_number(s: &str) -> f64 {
+    let s = s.trim();
+    if s.is_empty() {
+        return 0.0;
+    }
+    let radix = match s.get(..2) {
+        Some("0x") | Some("0X") => Some(16),
+        Some("0o") | Some("0O") => Some(8),
+        Some("0b") | Some("0B") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        let digits = &s[2..];
+        if digits.is_empty() {
+            return f64::NAN;
+        }
+        return digits.chars().try_fold(0.0, |acc, c| {
+            c.to_digit(radix).map(|d| acc * radix as f64 + d as f64)
+        }).unwrap_or(f64::NAN);
+    }
+    match s {
+        "Infinity" | "+Infinity" => f64::INFINITY,
+        "-Infinity" => f64::NEG_INFINITY,
+        _ if s.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) => {
+            s.parse::<f64>().unwrap_or(f64::NAN)
+        }
+        _ => f64::NAN,
+    }
+}
+
+/// `Number::toString` with radix 10: the shortest round-tripping digits,
+/// switching to exponential notation outside `1e-7 <= |n| < 1e21`.
+pub fn number_to_string(n: f64) -> String {
+    if n.is_nan() {
+        return "NaN".to_string();
+    }
+    if n == 0.0 {
+        return "0".to_string();
+    }
+    if n.is_infinite() {
+        return if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if n < 0.0 {
+        return format!("-{}", number_to_string(-n));
+    }
+
+    // `{:e}` yields the shortest round-trip digits as `d.ddde±x`
+    let formatted = format!("{:e}", n);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n_exp = exponent.parse::<i32>().unwrap() + 1;
+
+    if k <= n_exp && n_exp <= 21 {
+        format!("{}{}", digits, "0".repeat((n_exp - k) as usize))
+    } else if 0 < n_exp && n_exp <= 21 {
+        format!("{}.{}", &digits[..n_exp as usize], &digits[n_exp as usize..])
+    } else if -6 < n_exp && n_exp <= 0 {
+        format!("0.{}{}", "0".repeat((-n_exp) as usize), digits)
+    } else {
+        let sign = if n_exp - 1 < 0 { '-' } else { '+' };
+        let exp = (n_exp - 1).abs();
+        if k == 1 {
+            format!("{}e{}{}", digits, sign, exp)
+        } else {
+            format!("{}.{}e{}{}", &digits[..1], &digits[1..], sign, exp)
+        }
+    }
+}
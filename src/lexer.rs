@@ -1,19 +1,62 @@
 //! Lexical analysis for TypeScript code
 
+use num_bigint::BigInt;
+
+use crate::interpreter::JsString;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     // Keywords
     Let,
     Const,
+    Var,
     Function,
     Return,
     If,
     Else,
+    While,
+    Do,
+    For,
+    Switch,
+    Case,
+    Default,
+    Break,
+    Continue,
+    This,
+    True,
+    False,
+    Null,
+    Delete,
+    Typeof,
+    Void,
+    In,
+    Instanceof,
+    Class,
+    Extends,
+    Super,
+    New,
+    Throw,
+    Try,
+    Catch,
+    Finally,
     
     // Literals
     Number(f64),
-    String(String),
+    /// A BigInt literal such as `10n`
+    BigInt(BigInt),
+    String(JsString),
     Identifier(String),
+    /// `#name`, a private class member, stored without the `#`
+    PrivateName(String),
+    /// A piece of a template literal: the text from a `` ` `` or the `}`
+    /// closing a substitution up to the next `${` or the closing
+    /// `` ` ``, which makes it the `tail`. `cooked` has escapes applied,
+    /// or is the error a bad escape causes outside tagged templates.
+    Template {
+        cooked: Result<JsString, String>,
+        raw: String,
+        tail: bool,
+    },
     
     // Symbols
     Equal,
@@ -21,6 +64,50 @@ pub enum Token {
     Minus,
     Star,
     Slash,
+    Percent,
+    StarStar,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
+    Bang,
+    ShiftLeft,
+    ShiftRight,
+    UnsignedShiftRight,
+    PlusPlus,
+    MinusMinus,
+    EqualEqual,
+    EqualEqualEqual,
+    BangEqual,
+    BangEqualEqual,
+    LessEqual,
+    GreaterEqual,
+    AmpersandAmpersand,
+    PipePipe,
+    QuestionQuestion,
+    Question,
+    /// `?.`, which starts a link of an optional chain
+    QuestionDot,
+    Arrow,
+
+    // Compound assignment
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PercentEqual,
+    StarStarEqual,
+    AmpersandEqual,
+    PipeEqual,
+    CaretEqual,
+    ShiftLeftEqual,
+    ShiftRightEqual,
+    UnsignedShiftRightEqual,
+    // Logical assignment, which only assigns when the operator would not
+    // short-circuit
+    AmpersandAmpersandEqual,
+    PipePipeEqual,
+    QuestionQuestionEqual,
     
     // Delimiters
     LParen,
@@ -30,27 +117,133 @@ pub enum Token {
     LAngle,
     RAngle,
     Semicolon,
+    Colon,
     Comma,
+    Dot,
+    Ellipsis,
+    LBracket,
+    RBracket,
     
+    /// A malformed token, such as a string with a bad escape, holding the
+    /// message of the SyntaxError it causes
+    Invalid(String),
     EOF,
 }
 
+/// A 1-based line and column in the source text.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A saved lexer position, for parsers that need to backtrack.
+#[derive(Clone, Copy, Debug)]
+pub struct LexerState {
+    position: usize,
+    token_start: usize,
+    newline_before: bool,
+}
+
 pub struct Lexer {
     input: Vec<char>,
     position: usize,
+    /// Where the last token returned by `next_token` starts
+    token_start: usize,
+    /// The offset of the first character of each line
+    line_starts: Vec<usize>,
+    newline_before: bool,
 }
 
 impl Lexer {
     pub fn new(input: &str) -> Self {
+        let input: Vec<char> = input.chars().collect();
+        let line_starts = std::iter::once(0)
+            .chain(input.iter().enumerate().filter(|(_, c)| **c == '\n').map(|(i, _)| i + 1))
+            .collect();
         Self {
-            input: input.chars().collect(),
+            input,
             position: 0,
+            token_start: 0,
+            line_starts,
+            newline_before: false,
         }
     }
 
+    /// Where the last token returned by `next_token` starts.
+    pub fn location(&self) -> Location {
+        let line = self.line_starts.partition_point(|&start| start <= self.token_start);
+        Location { line, column: self.token_start - self.line_starts[line - 1] + 1 }
+    }
+
+    /// The offset of the first character of the last token returned by
+    /// `next_token`.
+    pub fn token_start(&self) -> usize {
+        self.token_start
+    }
+
+    /// The offset just past the last token returned by `next_token`.
+    pub fn token_end(&self) -> usize {
+        self.position
+    }
+
+    /// The source text between two offsets, as used for
+    /// `Function.prototype.toString`.
+    pub fn source_text(&self, start: usize, end: usize) -> String {
+        self.input[start..end].iter().collect()
+    }
+
+    /// Whether a line terminator appeared between the previous token and the
+    /// last token returned by `next_token`. Used for automatic semicolon
+    /// insertion and restricted productions such as postfix `++`.
+    pub fn had_newline_before(&self) -> bool {
+        self.newline_before
+    }
+
+    /// Returns the token after the current one without consuming it.
+    pub fn peek_token(&mut self) -> Token {
+        let state = self.save();
+        let token = self.next_token();
+        self.restore(state);
+        token
+    }
+
+    /// Returns the token after the current one, if it is on the same line,
+    /// without consuming it.
+    pub fn peek_token_on_same_line(&mut self) -> Option<Token> {
+        let state = self.save();
+        let token = self.next_token();
+        let newline_before = self.newline_before;
+        self.restore(state);
+        (!newline_before).then_some(token)
+    }
+
+    pub fn save(&self) -> LexerState {
+        LexerState {
+            position: self.position,
+            token_start: self.token_start,
+            newline_before: self.newline_before,
+        }
+    }
+
+    pub fn restore(&mut self, state: LexerState) {
+        self.position = state.position;
+        self.token_start = state.token_start;
+        self.newline_before = state.newline_before;
+    }
+
     pub fn next_token(&mut self) -> Token {
+        let start = self.position;
         self.skip_whitespace();
         self.skip_comments();
+        self.newline_before = self.input[start..self.position].contains(&'\n');
+        self.token_start = self.position;
         
         if self.position >= self.input.len() {
             return Token::EOF;
@@ -58,27 +251,41 @@ impl Lexer {
 
         let ch = self.input[self.position];
         
+        // A leading dot followed by a digit starts a number such as `.5`
+        if ch == '.' && self.input.get(self.position + 1).is_some_and(|c| c.is_ascii_digit()) {
+            return self.read_number();
+        }
+
+        // Handle operators, longest match first
+        if let Some(token) = self.read_operator() {
+            return token;
+        }
+
         // Handle single-character tokens
         let token = match ch {
-            '"' => return self.read_string(),
-            '=' => Token::Equal,
-            '+' => Token::Plus,
-            '-' => Token::Minus,
-            '*' => Token::Star,
-            '/' => Token::Slash,
+            '"' | '\'' => return self.read_string(),
+            '`' => {
+                self.position += 1; // consume '`'
+                return self.read_template();
+            }
+            '#' if self.input.get(self.position + 1).is_some_and(|c| c.is_alphabetic() || *c == '_') => {
+                self.position += 1; // consume '#'
+                return Token::PrivateName(self.read_word());
+            }
             '(' => Token::LParen,
             ')' => Token::RParen,
             '{' => Token::LBrace,
             '}' => Token::RBrace,
-            '<' => Token::LAngle,
-            '>' => Token::RAngle,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
             ';' => Token::Semicolon,
+            ':' => Token::Colon,
             ',' => Token::Comma,
             _ => {
                 // Handle identifiers, keywords, and numbers
                 if ch.is_alphabetic() || ch == '_' {
                     return self.read_identifier();
-                } else if ch.is_numeric() {
+                } else if ch.is_ascii_digit() {
                     return self.read_number();
                 } else {
                     Token::EOF
@@ -90,47 +297,218 @@ impl Lexer {
         token
     }
 
-    fn read_identifier(&mut self) -> Token {
+    fn read_operator(&mut self) -> Option<Token> {
+        // Sorted so that longer operators are tried before their prefixes
+        const OPERATORS: &[(&str, Token)] = &[
+            (">>>=", Token::UnsignedShiftRightEqual),
+            ("...", Token::Ellipsis),
+            ("===", Token::EqualEqualEqual),
+            ("!==", Token::BangEqualEqual),
+            (">>>", Token::UnsignedShiftRight),
+            ("**=", Token::StarStarEqual),
+            ("&&=", Token::AmpersandAmpersandEqual),
+            ("||=", Token::PipePipeEqual),
+            ("??=", Token::QuestionQuestionEqual),
+            ("<<=", Token::ShiftLeftEqual),
+            (">>=", Token::ShiftRightEqual),
+            ("=>", Token::Arrow),
+            ("==", Token::EqualEqual),
+            ("!=", Token::BangEqual),
+            ("<=", Token::LessEqual),
+            (">=", Token::GreaterEqual),
+            ("&&", Token::AmpersandAmpersand),
+            ("||", Token::PipePipe),
+            ("??", Token::QuestionQuestion),
+            ("**", Token::StarStar),
+            ("<<", Token::ShiftLeft),
+            (">>", Token::ShiftRight),
+            ("++", Token::PlusPlus),
+            ("--", Token::MinusMinus),
+            ("+=", Token::PlusEqual),
+            ("-=", Token::MinusEqual),
+            ("*=", Token::StarEqual),
+            ("/=", Token::SlashEqual),
+            ("%=", Token::PercentEqual),
+            ("&=", Token::AmpersandEqual),
+            ("|=", Token::PipeEqual),
+            ("^=", Token::CaretEqual),
+            ("=", Token::Equal),
+            ("+", Token::Plus),
+            ("-", Token::Minus),
+            ("*", Token::Star),
+            ("/", Token::Slash),
+            ("%", Token::Percent),
+            ("&", Token::Ampersand),
+            ("|", Token::Pipe),
+            ("^", Token::Caret),
+            ("~", Token::Tilde),
+            ("!", Token::Bang),
+            ("<", Token::LAngle),
+            (">", Token::RAngle),
+            ("?", Token::Question),
+            (".", Token::Dot),
+        ];
+
+        // `a?.5:b` is a conditional, not an optional chain
+        let after = self.input.get(self.position + 2).copied();
+        if self.starts_with("?.") && !after.is_some_and(|c| c.is_ascii_digit()) {
+            self.position += 2;
+            return Some(Token::QuestionDot);
+        }
+        for (text, token) in OPERATORS {
+            if self.starts_with(text) {
+                self.position += text.len();
+                return Some(token.clone());
+            }
+        }
+        None
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(offset, expected)| self.input.get(self.position + offset) == Some(&expected))
+    }
+
+    /// Reads the characters of an identifier or keyword.
+    fn read_word(&mut self) -> String {
         let start = self.position;
         while self.position < self.input.len() && 
               (self.input[self.position].is_alphanumeric() || self.input[self.position] == '_') {
             self.position += 1;
         }
-        
-        let identifier: String = self.input[start..self.position].iter().collect();
+        self.input[start..self.position].iter().collect()
+    }
+
+    fn read_identifier(&mut self) -> Token {
+        let identifier = self.read_word();
         
         // Check for keywords
         match identifier.as_str() {
             "let" => Token::Let,
             "const" => Token::Const,
+            "var" => Token::Var,
             "function" => Token::Function,
             "return" => Token::Return,
             "if" => Token::If,
             "else" => Token::Else,
+            "while" => Token::While,
+            "do" => Token::Do,
+            "for" => Token::For,
+            "switch" => Token::Switch,
+            "case" => Token::Case,
+            "default" => Token::Default,
+            "break" => Token::Break,
+            "continue" => Token::Continue,
+            "this" => Token::This,
+            "true" => Token::True,
+            "false" => Token::False,
+            "null" => Token::Null,
+            "delete" => Token::Delete,
+            "typeof" => Token::Typeof,
+            "void" => Token::Void,
+            "in" => Token::In,
+            "instanceof" => Token::Instanceof,
+            "class" => Token::Class,
+            "extends" => Token::Extends,
+            "super" => Token::Super,
+            "new" => Token::New,
+            "throw" => Token::Throw,
+            "try" => Token::Try,
+            "catch" => Token::Catch,
+            "finally" => Token::Finally,
             _ => Token::Identifier(identifier),
         }
     }
 
     fn read_number(&mut self) -> Token {
-        let start = self.position;
-        let mut seen_dot = false;
-        
-        while self.position < self.input.len() {
-            let ch = self.input[self.position];
-            if ch.is_numeric() {
-                self.position += 1;
-            } else if ch == '.' && !seen_dot {
-                seen_dot = true;
-                self.position += 1;
-            } else {
-                break;
+        let token = self.read_numeric_literal();
+        // A literal cannot run straight into an identifier or another
+        // number, as in `3in` or `0b12`
+        let followed_by_word = self.input.get(self.position).is_some_and(|&c| c.is_alphanumeric() || c == '_' || c == '$');
+        if followed_by_word && !matches!(token, Token::Invalid(_)) {
+            return Token::Invalid("Invalid or unexpected token".to_string());
+        }
+        token
+    }
+
+    fn read_numeric_literal(&mut self) -> Token {
+        let radix = match (self.input[self.position], self.input.get(self.position + 1)) {
+            ('0', Some('x' | 'X')) => 16,
+            ('0', Some('o' | 'O')) => 8,
+            ('0', Some('b' | 'B')) => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.position += 2;
+            let digits = self.read_digits(|c| c.is_digit(radix));
+            if digits.is_empty() {
+                return Token::Invalid("Invalid or unexpected token".to_string());
+            }
+            if self.eat_bigint_suffix() {
+                return Token::BigInt(BigInt::parse_bytes(digits.as_bytes(), radix).unwrap_or_default());
             }
+            let value = digits.chars().fold(0.0, |acc, c| acc * radix as f64 + c.to_digit(radix).unwrap() as f64);
+            return Token::Number(value);
+        }
+
+        // Scripts are strict, which rules out legacy octal literals like
+        // `012` and leading zeros like `09`
+        if self.input[self.position] == '0' {
+            match self.input.get(self.position + 1) {
+                Some('0'..='7') => return Token::Invalid("Octal literals are not allowed in strict mode.".to_string()),
+                Some('8' | '9') => return Token::Invalid("Decimals with leading zeros are not allowed in strict mode.".to_string()),
+                _ => {}
+            }
+        }
+        let mut number_str = self.read_digits(|c| c.is_ascii_digit());
+        if self.eat_bigint_suffix() {
+            return Token::BigInt(number_str.parse().expect("decimal digits"));
+        }
+        if self.input.get(self.position) == Some(&'.') {
+            self.position += 1;
+            number_str.push('.');
+            number_str.push_str(&self.read_digits(|c| c.is_ascii_digit()));
+        }
+        if matches!(self.input.get(self.position), Some('e' | 'E')) {
+            let sign = matches!(self.input.get(self.position + 1), Some('+' | '-')) as usize;
+            // An exponent needs at least one digit
+            if !self.input.get(self.position + 1 + sign).is_some_and(|c| c.is_ascii_digit()) {
+                return Token::Invalid("Invalid or unexpected token".to_string());
+            }
+            number_str.push('e');
+            number_str.extend(&self.input[self.position + 1..self.position + 1 + sign]);
+            self.position += 1 + sign;
+            number_str.push_str(&self.read_digits(|c| c.is_ascii_digit()));
         }
-        
-        let number_str: String = self.input[start..self.position].iter().collect();
         Token::Number(number_str.parse::<f64>().unwrap())
     }
 
+    /// Consumes the `n` that makes an integer literal a BigInt, if it is
+    /// there.
+    fn eat_bigint_suffix(&mut self) -> bool {
+        let found = self.input.get(self.position) == Some(&'n');
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// Reads a run of digits, dropping the `_` separators allowed between
+    /// them.
+    fn read_digits(&mut self, is_digit: impl Fn(char) -> bool) -> String {
+        let mut digits = String::new();
+        while let Some(&ch) = self.input.get(self.position) {
+            if is_digit(ch) {
+                digits.push(ch);
+            } else if ch != '_' || digits.is_empty() || !self.input.get(self.position + 1).is_some_and(|&c| is_digit(c)) {
+                break;
+            }
+            self.position += 1;
+        }
+        digits
+    }
+
     fn skip_whitespace(&mut self) {
         while self.position < self.input.len() && self.input[self.position].is_whitespace() {
             self.position += 1;
@@ -157,16 +535,154 @@ impl Lexer {
     }
 
     fn read_string(&mut self) -> Token {
+        let quote = self.input[self.position];
         self.position += 1; // Skip opening quote
-        let start = self.position;
-        
-        while self.position < self.input.len() && self.input[self.position] != '"' {
+
+        // Collected as UTF-16 so that escaped surrogate pairs combine
+        let mut units: Vec<u16> = Vec::new();
+        loop {
+            let ch = match self.input.get(self.position) {
+                Some(&ch) if ch != '\n' && ch != '\r' => ch,
+                _ => return Token::Invalid("Invalid or unexpected token".to_string()),
+            };
             self.position += 1;
+            if ch == quote {
+                break;
+            }
+            if ch != '\\' {
+                units.extend(ch.encode_utf16(&mut [0; 2]).iter());
+                continue;
+            }
+            if let Err(message) = self.read_escape(&mut units, "strict mode") {
+                return Token::Invalid(message);
+            }
+        }
+        Token::String(JsString::from(units))
+    }
+
+    /// Continues a template literal after the `}` closing a substitution,
+    /// which the parser has just received as `Token::RBrace`.
+    pub fn read_template_continuation(&mut self) -> Token {
+        self.read_template()
+    }
+
+    /// Reads template text up to and including the next `${` or the
+    /// closing `` ` ``. Line terminators are normalized to `\n`.
+    fn read_template(&mut self) -> Token {
+        let mut units: Vec<u16> = Vec::new();
+        let mut raw = String::new();
+        let mut error = None;
+        let tail = loop {
+            let Some(&ch) = self.input.get(self.position) else {
+                return Token::Invalid("Unterminated template literal".to_string());
+            };
+            self.position += 1;
+            match ch {
+                '`' => break true,
+                '$' if self.input.get(self.position) == Some(&'{') => {
+                    self.position += 1;
+                    break false;
+                }
+                '\\' => {
+                    let start = self.position - 1;
+                    if let Err(message) = self.read_escape(&mut units, "template strings") {
+                        error.get_or_insert(message);
+                    }
+                    let escape: String = self.input[start..self.position].iter().collect();
+                    raw.push_str(&escape.replace("\r\n", "\n").replace('\r', "\n"));
+                }
+                '\r' => {
+                    if self.input.get(self.position) == Some(&'\n') {
+                        self.position += 1;
+                    }
+                    units.push('\n' as u16);
+                    raw.push('\n');
+                }
+                ch => {
+                    units.extend(ch.encode_utf16(&mut [0; 2]).iter());
+                    raw.push(ch);
+                }
+            }
+        };
+        let cooked = match error {
+            Some(message) => Err(message),
+            None => Ok(JsString::from(units)),
+        };
+        Token::Template { cooked, raw, tail }
+    }
+
+    /// Reads the escape sequence after a `\\` into `units`. Legacy octal
+    /// escapes are rejected, naming `context` in the message.
+    fn read_escape(&mut self, units: &mut Vec<u16>, context: &str) -> Result<(), String> {
+        let Some(&escaped) = self.input.get(self.position) else {
+            return Err("Invalid or unexpected token".to_string());
+        };
+        self.position += 1;
+        let unit = match escaped {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'v' => '\u{b}',
+            '0' if !self.input.get(self.position).is_some_and(|c| c.is_ascii_digit()) => '\0',
+            '8' | '9' => return Err(format!("\\8 and \\9 are not allowed in {}.", context)),
+            '0'..='7' => return Err(format!("Octal escape sequences are not allowed in {}.", context)),
+            'x' => match self.read_hex_digits(2) {
+                Some(code) => char::from_u32(code).expect("two hex digits are a valid char"),
+                None => return Err("Invalid hexadecimal escape sequence".to_string()),
+            },
+            'u' => {
+                let code = self.read_unicode_escape()?;
+                match char::from_u32(code) {
+                    Some(c) => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+                    // A lone surrogate, which may pair with the next escape
+                    None => units.push(code as u16),
+                }
+                return Ok(());
+            }
+            // Line continuations
+            '\r' => {
+                if self.input.get(self.position) == Some(&'\n') {
+                    self.position += 1;
+                }
+                return Ok(());
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => return Ok(()),
+            other => other,
+        };
+        units.extend(unit.encode_utf16(&mut [0; 2]).iter());
+        Ok(())
+    }
+
+    /// Reads exactly `count` hex digits, leaving the position unchanged if
+    /// there are fewer.
+    fn read_hex_digits(&mut self, count: usize) -> Option<u32> {
+        let digits = self.input.get(self.position..self.position + count)?;
+        let code = digits.iter().try_fold(0, |acc, c| Some(acc * 16 + c.to_digit(16)?))?;
+        self.position += count;
+        Some(code)
+    }
+
+    /// The code of a `\uXXXX` or `\u{X...}` escape, after the `u`.
+    fn read_unicode_escape(&mut self) -> Result<u32, String> {
+        let invalid = || "Invalid Unicode escape sequence".to_string();
+        if self.input.get(self.position) != Some(&'{') {
+            return self.read_hex_digits(4).ok_or_else(invalid);
+        }
+        let digits = self.input[self.position + 1..].iter().take_while(|c| c.is_ascii_hexdigit()).count();
+        let end = self.position + 1 + digits;
+        if digits == 0 || self.input.get(end) != Some(&'}') {
+            return Err(invalid());
+        }
+        let code = self.input[self.position + 1..end].iter().try_fold(0u32, |acc, c| acc.checked_mul(16)?.checked_add(c.to_digit(16)?));
+        match code {
+            Some(code) if code <= 0x10FFFF => {
+                self.position = end + 1;
+                Ok(code)
+            }
+            _ => Err("Undefined Unicode code-point".to_string()),
         }
-        
-        let string = self.input[start..self.position].iter().collect();
-        self.position += 1; // Skip closing quote
-        Token::String(string)
     }
 }
 
@@ -194,4 +710,16 @@ mod tests {
         let mut lexer = Lexer::new("");
         assert_eq!(lexer.next_token(), Token::EOF);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_token_location() {
+        let mut lexer = Lexer::new("let x;\n  foo(1);");
+        lexer.next_token();
+        assert_eq!(lexer.location(), Location { line: 1, column: 1 });
+        lexer.next_token();
+        assert_eq!(lexer.location(), Location { line: 1, column: 5 });
+        lexer.next_token();
+        lexer.next_token();
+        assert_eq!(lexer.location(), Location { line: 2, column: 3 });
+    }
+}
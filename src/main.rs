@@ -1,4 +1,5 @@
 use clap::Parser;
+use ts_interpreter_rs::interpreter::Interpreter;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -15,10 +16,25 @@ fn main() {
     let cli = Cli::parse();
     
     if let Some(file) = cli.file {
-        println!("Executing file: {}", file);
-        // TODO: Implement file execution
+        if let Err(message) = execute_file(&file) {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
     } else {
         println!("Starting REPL...");
         // TODO: Implement REPL
     }
-}
\ No newline at end of file
+}
+
+/// Runs the script in `path` in the system's time zone, waiting in real
+/// time for the timers it sets until none are left.
+fn execute_file(path: &str) -> Result<(), String> {
+    let source = std::fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}", path, error))?;
+    let program = ts_interpreter_rs::parser::Parser::new(&source).parse_program().map_err(|message| format!("SyntaxError: {}", message))?;
+    let mut interpreter = Interpreter::new();
+    interpreter.use_real_time();
+    interpreter.use_system_time_zone();
+    interpreter.run(program)?;
+    interpreter.run_event_loop()?;
+    Ok(())
+}
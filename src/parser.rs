@@ -1,164 +1,2866 @@
 //! Parser for TypeScript code
 
-use crate::lexer::{Lexer, Token};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use num_bigint::BigInt;
+
+use crate::interpreter::{number_to_string, JsString, STACK_GROWTH, STACK_RED_ZONE};
+use crate::lexer::{Lexer, LexerState, Location, Token};
 
 #[derive(Debug, PartialEq)]
 pub enum Expression {
     Number(f64),
-    String(String),
-    Identifier(String),
+    BigInt(BigInt),
+    String(JsString),
+    Boolean(bool),
+    Null,
+    /// A variable read; the location, like those of the other expressions
+    /// that can throw, is reported in stack traces
+    Identifier {
+        name: String,
+        location: Location,
+    },
+    This,
+    Function(Rc<FunctionDefinition>),
+    /// `[a, , ...rest]`, where `None` is a hole
+    Array(Vec<Option<Expression>>),
+    /// `...value` inside an array literal or argument list
+    Spread(Box<Expression>),
+    Object(Vec<ObjectProperty>),
+    Class(Rc<ClassDefinition>),
+    /// `super`, only valid as a call's callee or a member access's object
+    Super,
+    /// `#name in object`, testing for a private member
+    PrivateName(String),
+    Call {
+        callee: Box<Expression>,
+        arguments: Vec<Expression>,
+        /// Where the callee starts, reported in stack traces
+        location: Location,
+    },
+    /// `new callee(arguments)`
+    New {
+        callee: Box<Expression>,
+        arguments: Vec<Expression>,
+        location: Location,
+    },
+    /// `new.target`
+    NewTarget,
+    /// `object.property`
+    Member {
+        object: Box<Expression>,
+        property: String,
+        /// Where the property name starts
+        location: Location,
+    },
+    /// `object[index]`
+    Index {
+        object: Box<Expression>,
+        index: Box<Expression>,
+        /// Where the index starts
+        location: Location,
+    },
+    /// `object.#name`
+    PrivateMember {
+        object: Box<Expression>,
+        name: String,
+    },
+    /// A chain of member accesses and calls with at least one `?.`, such
+    /// as `a?.b.c()`, which is `undefined` if any `?.` short-circuits
+    OptionalChain(Box<Expression>),
+    /// The object or function before a `?.` inside an `OptionalChain`
+    Optional(Box<Expression>),
     Binary {
         left: Box<Expression>,
         operator: Token,
         right: Box<Expression>,
+        /// Where the operator is
+        location: Location,
+    },
+    Unary {
+        operator: Token,
+        operand: Box<Expression>,
+        location: Location,
+    },
+    /// `++` / `--` in prefix or postfix position
+    Update {
+        operator: Token,
+        prefix: bool,
+        target: Box<Expression>,
+    },
+    /// `&&`, `||` and `??`, which short-circuit
+    Logical {
+        left: Box<Expression>,
+        operator: Token,
+        right: Box<Expression>,
+    },
+    Conditional {
+        test: Box<Expression>,
+        consequent: Box<Expression>,
+        alternate: Box<Expression>,
+    },
+    /// Comma-separated expressions, evaluating to the last one
+    Sequence(Vec<Expression>),
+    /// `=` and the compound assignment operators (`+=`, `**=`, ...)
+    Assignment {
+        operator: Token,
+        target: Box<Expression>,
+        value: Box<Expression>,
+    },
+    /// `[a, b] = value` and `({ a } = value)`
+    DestructuringAssignment {
+        target: Pattern,
+        value: Box<Expression>,
+    },
+    /// `yield value`, or `yield* iterable` when `delegate` is set
+    Yield {
+        argument: Option<Box<Expression>>,
+        delegate: bool,
+    },
+    /// `await value`, in an async function or at the top level
+    Await(Box<Expression>),
+    /// `` `text ${value} text` ``
+    Template(TemplateLiteral),
+    /// `` tag`text ${value}` ``, which calls `tag` with the template's
+    /// text and the substitutions. The template is shared so each
+    /// evaluation passes the same template object.
+    TaggedTemplate {
+        tag: Box<Expression>,
+        template: Rc<TemplateLiteral>,
+        location: Location,
+    },
+}
+
+/// The pieces of text of a template literal and the substitutions
+/// between them, of which there is one fewer.
+#[derive(Debug, PartialEq)]
+pub struct TemplateLiteral {
+    /// The text with escapes applied, or `None` for text with an invalid
+    /// escape, which only tagged templates allow
+    pub cooked: Vec<Option<JsString>>,
+    /// The text as written
+    pub raw: Vec<String>,
+    pub expressions: Vec<Expression>,
+}
+
+/// The target of a declaration, parameter or assignment, which may
+/// destructure the value it is given.
+#[derive(Debug, PartialEq)]
+pub enum Pattern {
+    Identifier(String),
+    /// `[a, , b = 1, ...rest]`, where `None` is a hole
+    Array {
+        elements: Vec<Option<PatternElement>>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// `{ a, b: c = 1, ...rest }`
+    Object {
+        properties: Vec<PatternProperty>,
+        rest: Option<Box<Pattern>>,
     },
+    /// A member expression, which only assignment patterns may contain,
+    /// as in `[obj.a, obj.b] = pair`
+    Expression(Box<Expression>),
+}
+
+/// An element of an array pattern, used when the value is `undefined`.
+#[derive(Debug, PartialEq)]
+pub struct PatternElement {
+    pub target: Pattern,
+    pub default: Option<Expression>,
+}
+
+/// `key: target = default` in an object pattern. The shorthand `{ a = 1 }`
+/// has an identifier target named after the key.
+#[derive(Debug, PartialEq)]
+pub struct PatternProperty {
+    pub key: PropertyName,
+    pub target: Pattern,
+    pub default: Option<Expression>,
+}
+
+impl Pattern {
+    /// The names a binding pattern declares, in source order.
+    pub fn bound_names(&self) -> Vec<&String> {
+        let mut names = Vec::new();
+        self.collect_bound_names(&mut names);
+        names
+    }
+
+    fn collect_bound_names<'a>(&'a self, names: &mut Vec<&'a String>) {
+        match self {
+            Pattern::Identifier(name) => names.push(name),
+            Pattern::Array { elements, rest } => {
+                for element in elements.iter().flatten() {
+                    element.target.collect_bound_names(names);
+                }
+                if let Some(rest) = rest {
+                    rest.collect_bound_names(names);
+                }
+            }
+            Pattern::Object { properties, rest } => {
+                for property in properties {
+                    property.target.collect_bound_names(names);
+                }
+                if let Some(rest) = rest {
+                    rest.collect_bound_names(names);
+                }
+            }
+            Pattern::Expression(_) => {}
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VariableKind {
+    Var,
+    Let,
+    Const,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct VariableDeclarator {
+    pub target: Pattern,
+    pub init: Option<Expression>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Parameter {
+    pub target: Pattern,
+    pub default: Option<Expression>,
+    /// `...name`, collecting the remaining arguments into an array
+    pub rest: bool,
+}
+
+/// What sort of function a definition creates, which decides how it
+/// binds `this`, whether it can be used with `new` and where `super` is
+/// allowed.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FunctionKind {
+    Normal,
+    /// Takes `this` and `arguments` from its defining scope
+    Arrow,
+    /// An object literal or class method or accessor, which may use
+    /// `super.x` but is not a constructor
+    Method,
+    /// A class constructor, which can only be called with `new`
+    BaseConstructor,
+    /// The constructor of a class with an `extends` clause, whose `this`
+    /// is created by calling `super()`
+    DerivedConstructor,
+}
+
+/// A function's parameters and body, shared between the AST and every
+/// function value created from it.
+#[derive(Debug, PartialEq)]
+pub struct FunctionDefinition {
+    pub name: Option<String>,
+    pub params: Vec<Parameter>,
+    pub body: Vec<Statement>,
+    /// Whether the body refers to `arguments`, so calls can skip creating
+    /// the object otherwise
+    pub uses_arguments: bool,
+    pub kind: FunctionKind,
+    /// `function*` and `*method()`, whose calls return a generator
+    pub is_generator: bool,
+    /// `async function` and friends, whose calls return a promise
+    pub is_async: bool,
+    /// Constructor parameters declared `public`, `private`, `protected` or
+    /// `readonly`, which are copied onto each new instance
+    pub parameter_properties: Vec<String>,
+    /// The function's source, for `Function.prototype.toString`; a class
+    /// constructor holds the whole class
+    pub source_text: String,
+}
+
+impl FunctionDefinition {
+    pub fn is_arrow(&self) -> bool {
+        self.kind == FunctionKind::Arrow
+    }
+
+    pub fn is_class_constructor(&self) -> bool {
+        matches!(self.kind, FunctionKind::BaseConstructor | FunctionKind::DerivedConstructor)
+    }
+}
+
+/// A property name in an object literal.
+#[derive(Debug, PartialEq)]
+pub enum PropertyName {
+    /// An identifier, string or number key, already in its string form
+    Literal(String),
+    /// `[expression]`
+    Computed(Box<Expression>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ObjectProperty {
+    /// `key: value`, shorthand `key` and methods `key() {}`
+    Property { key: PropertyName, value: Expression },
+    /// `get key() {}`
+    Getter { key: PropertyName, function: Rc<FunctionDefinition> },
+    /// `set key(value) {}`
+    Setter { key: PropertyName, function: Rc<FunctionDefinition> },
+    /// `...source`
+    Spread(Expression),
+}
+
+/// A class declaration or expression.
+#[derive(Debug, PartialEq)]
+pub struct ClassDefinition {
+    pub name: Option<String>,
+    /// The `extends` expression
+    pub superclass: Option<Expression>,
+    /// The explicit constructor, or a synthesized default one
+    pub constructor: Rc<FunctionDefinition>,
+    /// Methods, accessors and fields in source order. TypeScript-only
+    /// members such as overload signatures are dropped by the parser.
+    pub members: Vec<ClassMember>,
+    /// `abstract class`, which cannot be instantiated directly
+    pub is_abstract: bool,
+}
+
+/// The name of a class member.
+#[derive(Debug, PartialEq)]
+pub enum ClassKey {
+    Public(PropertyName),
+    /// `#name`, stored without the `#`
+    Private(String),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ClassMemberKind {
+    Method(Rc<FunctionDefinition>),
+    Getter(Rc<FunctionDefinition>),
+    Setter(Rc<FunctionDefinition>),
+    /// A field and its initializer, evaluated once per instance (or once
+    /// for the class if static) with `this` bound to the target
+    Field(Option<Rc<Expression>>),
+    /// `static { ... }`, run like a static method once the class is
+    /// defined, in order with the static fields. Its member has no name of
+    /// its own; the key is always `static`.
+    StaticBlock(Rc<FunctionDefinition>),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ClassMember {
+    pub key: ClassKey,
+    pub is_static: bool,
+    pub kind: ClassMemberKind,
+}
+
+/// What a `for...in` or `for...of` loop assigns each value to.
+#[derive(Debug, PartialEq)]
+pub enum ForBinding {
+    /// `for (const key in ...)`, a fresh binding per iteration for `let` and `const`
+    Declaration { kind: VariableKind, target: Pattern },
+    /// `for (key in ...)`, assigning to existing targets
+    Assignment(Pattern),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SwitchCase {
+    /// `None` for the `default` clause
+    pub test: Option<Expression>,
+    pub consequent: Vec<Statement>,
+}
+
+/// The `catch (param) { body }` clause of a `try` statement.
+#[derive(Debug, PartialEq)]
+pub struct CatchClause {
+    /// `None` for `catch { ... }` without a binding
+    pub param: Option<Pattern>,
+    pub body: Vec<Statement>,
 }
 
 #[derive(Debug, PartialEq)]
 pub enum Statement {
-    Let {
-        name: String,
-        value: Expression,
+    VariableDeclaration {
+        kind: VariableKind,
+        declarations: Vec<VariableDeclarator>,
     },
     Expression(Expression),
+    Block(Vec<Statement>),
+    If {
+        condition: Expression,
+        consequent: Box<Statement>,
+        alternate: Option<Box<Statement>>,
+    },
+    While {
+        condition: Expression,
+        body: Box<Statement>,
+    },
+    DoWhile {
+        body: Box<Statement>,
+        condition: Expression,
+    },
+    For {
+        /// A variable declaration or expression statement
+        init: Option<Box<Statement>>,
+        test: Option<Expression>,
+        update: Option<Expression>,
+        body: Box<Statement>,
+    },
+    /// `for (left in object)`
+    ForIn {
+        left: ForBinding,
+        object: Expression,
+        body: Box<Statement>,
+    },
+    /// `for (left of iterable)`, or `for await (left of iterable)`
+    ForOf {
+        left: ForBinding,
+        iterable: Expression,
+        body: Box<Statement>,
+        is_await: bool,
+    },
+    Switch {
+        discriminant: Expression,
+        cases: Vec<SwitchCase>,
+    },
+    FunctionDeclaration(Rc<FunctionDefinition>),
+    ClassDeclaration(Rc<ClassDefinition>),
+    Return(Option<Expression>),
+    Throw(Expression),
+    /// `try` with a `catch` clause, a `finally` block or both
+    Try {
+        block: Vec<Statement>,
+        handler: Option<CatchClause>,
+        finalizer: Option<Vec<Statement>>,
+    },
+    Break(Option<String>),
+    Continue(Option<String>),
+    Labeled {
+        label: String,
+        body: Box<Statement>,
+    },
+    Empty,
+}
+
+/// The statement label in scope while parsing, used to validate `break`
+/// and `continue` targets.
+struct Label {
+    name: String,
+    is_loop: bool,
+}
+
+/// How often a private name has been declared in a class body. Only a
+/// getter and setter pair may share a name.
+#[derive(Clone, Copy, PartialEq)]
+enum PrivateDeclaration {
+    Getter,
+    Setter,
+    Complete,
+}
+
+/// The private names declared by a class body being parsed, and those it
+/// uses, which may instead belong to an enclosing class.
+#[derive(Default)]
+struct PrivateScope {
+    declared: HashMap<String, PrivateDeclaration>,
+    used: Vec<String>,
+}
+
+/// Parser state saved before speculatively parsing an ambiguous construct.
+struct Snapshot {
+    lexer: LexerState,
+    current_token: Token,
+    location: Location,
+    newline_before: bool,
+    previous_end: usize,
+    uses_arguments: bool,
+    cover_initializers: usize,
 }
 
+/// The error for a `for await` loop that is not a `for...of` loop.
+const FOR_AWAIT_WITHOUT_OF: &str = "for await loops must iterate with 'of'";
+
 pub struct Parser {
     lexer: Lexer,
     current_token: Token,
+    /// Where `current_token` starts
+    location: Location,
+    newline_before: bool,
+    /// Where the token before `current_token` ends
+    previous_end: usize,
+    labels: Vec<Label>,
+    loop_depth: usize,
+    breakable_depth: usize,
+    in_function: bool,
+    /// Set in the body of a generator, where `yield` is an operator
+    in_generator: bool,
+    /// Set in the body of an async function and at the top level, where
+    /// `await` is an operator
+    in_async: bool,
+    uses_arguments: bool,
+    /// Set while parsing a `for` initializer, where `in` starts a
+    /// `for...in` loop instead of being an operator
+    no_in: bool,
+    /// Whether `super.x` and `super()` may appear in the current function
+    allow_super_property: bool,
+    allow_super_call: bool,
+    private_scopes: Vec<PrivateScope>,
+    /// Shorthand properties with an initializer, as in `{ a = 1 }`, which
+    /// are only valid if their object literal turns out to be a pattern
+    cover_initializers: usize,
+    /// Set just before parsing an element of an array or object literal,
+    /// whose shorthand initializers an enclosing assignment may still
+    /// turn into a pattern
+    in_literal_element: bool,
 }
 
 impl Parser {
     pub fn new(input: &str) -> Self {
         let mut lexer = Lexer::new(input);
         let current_token = lexer.next_token();
+        let newline_before = lexer.had_newline_before();
+        let location = lexer.location();
         Self {
             lexer,
             current_token,
+            location,
+            newline_before,
+            previous_end: 0,
+            labels: Vec::new(),
+            loop_depth: 0,
+            breakable_depth: 0,
+            in_function: false,
+            in_generator: false,
+            in_async: true,
+            uses_arguments: false,
+            no_in: false,
+            allow_super_property: false,
+            allow_super_call: false,
+            private_scopes: Vec::new(),
+            cover_initializers: 0,
+            in_literal_element: false,
         }
     }
 
     fn next_token(&mut self) -> Token {
+        self.previous_end = self.lexer.token_end();
         let token = self.lexer.next_token();
+        self.newline_before = self.lexer.had_newline_before();
+        self.location = self.lexer.location();
         std::mem::replace(&mut self.current_token, token)
     }
 
-    pub fn parse_statement(&mut self) -> Result<Statement, String> {
-        println!("Parsing statement, current token: {:?}", self.current_token);
+    /// Runs `parse` with the `in` operator allowed again, as it is inside
+    /// any brackets nested in a `for` initializer.
+    fn allowing_in<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        let no_in = std::mem::replace(&mut self.no_in, false);
+        let result = parse(self);
+        self.no_in = no_in;
+        result
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            lexer: self.lexer.save(),
+            current_token: self.current_token.clone(),
+            location: self.location,
+            newline_before: self.newline_before,
+            previous_end: self.previous_end,
+            uses_arguments: self.uses_arguments,
+            cover_initializers: self.cover_initializers,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.lexer.restore(snapshot.lexer);
+        self.current_token = snapshot.current_token;
+        self.location = snapshot.location;
+        self.newline_before = snapshot.newline_before;
+        self.previous_end = snapshot.previous_end;
+        self.uses_arguments = snapshot.uses_arguments;
+        self.cover_initializers = snapshot.cover_initializers;
+    }
+
+    /// The source text from `start` through the last consumed token.
+    fn source_since(&self, start: usize) -> String {
+        self.lexer.source_text(start, self.previous_end)
+    }
+
+    fn expect(&mut self, expected: Token, context: &str) -> Result<(), String> {
+        if self.current_token != expected {
+            return Err(format!(
+                "Expected {:?} {}, got {:?}",
+                expected, context, self.current_token
+            ));
+        }
+        self.next_token();
+        Ok(())
+    }
+
+    /// Consumes a statement terminator, applying automatic semicolon insertion
+    /// before `}`, at the end of input and after a line break.
+    fn consume_semicolon(&mut self) -> Result<(), String> {
         match self.current_token {
-            Token::Let => self.parse_let_statement(),
-            _ => {
-                println!("Attempting to parse as expression statement");
-                Ok(Statement::Expression(self.parse_expression()?))
+            Token::Semicolon => {
+                self.next_token();
+                Ok(())
             }
+            Token::RBrace | Token::EOF => Ok(()),
+            _ if self.newline_before => Ok(()),
+            _ => Err(format!("Expected ';', got {:?}", self.current_token)),
         }
     }
 
-    fn parse_let_statement(&mut self) -> Result<Statement, String> {
-        self.next_token(); // consume 'let'
+    /// Parses statements until the end of input.
+    pub fn parse_program(&mut self) -> Result<Vec<Statement>, String> {
+        let mut statements = Vec::new();
+        while !self.is_eof() {
+            statements.push(self.parse_statement()?);
+        }
+        Ok(statements)
+    }
 
-        let name = match &self.current_token {
-            Token::Identifier(name) => name.clone(),
-            _ => return Err("Expected identifier after 'let'".to_string()),
+    pub fn parse_statement(&mut self) -> Result<Statement, String> {
+        with_stack(|| {
+            match self.current_token {
+                Token::Let => self.parse_variable_declaration(VariableKind::Let),
+                Token::Const => self.parse_variable_declaration(VariableKind::Const),
+                Token::Var => self.parse_variable_declaration(VariableKind::Var),
+                Token::LBrace => Ok(Statement::Block(self.parse_block()?)),
+                Token::If => self.parse_if_statement(),
+                Token::While => self.parse_while_statement(),
+                Token::Do => self.parse_do_while_statement(),
+                Token::For => self.parse_for_statement(),
+                Token::Switch => self.parse_switch_statement(),
+                Token::Break | Token::Continue => self.parse_jump_statement(),
+                Token::Identifier(ref word) if word == "async" && self.lexer.peek_token() == Token::Function => {
+                    self.parse_function_declaration(true)
+                }
+                Token::Function => self.parse_function_declaration(false),
+                Token::Class => self.parse_class_declaration(false),
+                Token::Identifier(ref word) if word == "abstract" && self.lexer.peek_token() == Token::Class => {
+                    self.next_token(); // consume 'abstract'
+                    self.parse_class_declaration(true)
+                }
+                Token::Identifier(ref word) if word == "interface" && matches!(self.lexer.peek_token(), Token::Identifier(_)) => {
+                    self.skip_interface_declaration()
+                }
+                // `type` is only a keyword when a name follows on the same line
+                Token::Identifier(ref word) if word == "type" && matches!(self.lexer.peek_token_on_same_line(), Some(Token::Identifier(_))) => {
+                    self.skip_type_alias()
+                }
+                Token::Return => self.parse_return_statement(),
+                Token::Throw => self.parse_throw_statement(),
+                Token::Try => self.parse_try_statement(),
+                Token::Semicolon => {
+                    self.next_token();
+                    Ok(Statement::Empty)
+                }
+                Token::Identifier(_) if self.lexer.peek_token() == Token::Colon => self.parse_labeled_statement(),
+                _ => {
+                    let expr = self.parse_expression()?;
+                    self.consume_semicolon()?;
+                    Ok(Statement::Expression(expr))
+                }
+            }
+        })
+    }
+
+    /// Parses `{ statements }`, returning the statements.
+    fn parse_block(&mut self) -> Result<Vec<Statement>, String> {
+        self.expect(Token::LBrace, "to open block")?;
+        let mut body = Vec::new();
+        while self.current_token != Token::RBrace {
+            if self.is_eof() {
+                return Err("Expected '}' to close block".to_string());
+            }
+            body.push(self.parse_statement()?);
+        }
+        self.next_token(); // consume '}'
+        Ok(body)
+    }
+
+    fn parse_if_statement(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'if'
+        let condition = self.parse_parenthesized()?;
+        let consequent = Box::new(self.parse_statement()?);
+        let alternate = if self.current_token == Token::Else {
+            self.next_token(); // consume 'else'
+            Some(Box::new(self.parse_statement()?))
+        } else {
+            None
         };
+        Ok(Statement::If { condition, consequent, alternate })
+    }
 
-        self.next_token(); // consume identifier
+    fn parse_while_statement(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'while'
+        let condition = self.parse_parenthesized()?;
+        let body = Box::new(self.parse_loop_body()?);
+        Ok(Statement::While { condition, body })
+    }
 
-        if self.current_token != Token::Equal {
-            return Err("Expected '=' after identifier in let statement".to_string());
+    fn parse_do_while_statement(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'do'
+        let body = Box::new(self.parse_loop_body()?);
+        self.expect(Token::While, "after do-while body")?;
+        let condition = self.parse_parenthesized()?;
+        // A semicolon is always inserted after `do ... while (...)`
+        if self.current_token == Token::Semicolon {
+            self.next_token();
         }
+        Ok(Statement::DoWhile { body, condition })
+    }
 
-        self.next_token(); // consume '='
+    fn parse_for_statement(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'for'
+        let is_await = self.is_contextual("await");
+        if is_await {
+            if !self.in_async {
+                return Err("for await is only valid in async functions and the top level bodies of modules".to_string());
+            }
+            self.next_token(); // consume 'await'
+        }
+        self.expect(Token::LParen, "after 'for'")?;
 
-        let value = self.parse_expression()?;
+        let kind = match self.current_token {
+            Token::Let => Some(VariableKind::Let),
+            Token::Const => Some(VariableKind::Const),
+            Token::Var => Some(VariableKind::Var),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            // `for (const [key, value] of entries)` declares a single binding
+            let snapshot = self.snapshot();
+            self.next_token(); // consume 'let' / 'const' / 'var'
+            if let Ok(target) = self.parse_binding_target() {
+                if self.current_token == Token::Colon {
+                    self.next_token(); // consume ':'
+                    self.skip_type()?;
+                }
+                if self.current_token == Token::In || self.is_contextual("of") {
+                    return self.parse_for_in_of_rest(ForBinding::Declaration { kind, target }, is_await);
+                }
+            }
+            self.restore(snapshot);
+        }
 
-        if self.current_token == Token::Semicolon {
-            self.next_token(); // consume semicolon
+        self.no_in = true;
+        let cover_initializers = self.cover_initializers;
+        let init = match kind {
+            _ if self.current_token == Token::Semicolon => Ok(None),
+            Some(kind) => self.parse_variable_declarations(kind).map(Some),
+            None => {
+                self.in_literal_element = true;
+                self.parse_expression().map(|expr| Some(Statement::Expression(expr)))
+            }
+        };
+        self.no_in = false;
+        let init = init?;
+
+        if let Some(Statement::Expression(target)) = init {
+            if self.current_token == Token::In || self.is_contextual("of") {
+                let target = match target {
+                    Expression::Array(_) | Expression::Object(_) => expression_to_pattern(target)?,
+                    target if is_simple_assignment_target(&target) => simple_pattern(target),
+                    _ => return Err("Invalid left-hand side in for-in loop".to_string()),
+                };
+                self.cover_initializers = cover_initializers;
+                return self.parse_for_in_of_rest(ForBinding::Assignment(target), is_await);
+            }
+            if self.cover_initializers > cover_initializers {
+                return Err("Invalid shorthand property initializer".to_string());
+            }
+            if is_await {
+                return Err(FOR_AWAIT_WITHOUT_OF.to_string());
+            }
+            return self.parse_for_rest(Some(Statement::Expression(target)));
+        }
+        if is_await {
+            return Err(FOR_AWAIT_WITHOUT_OF.to_string());
         }
+        self.parse_for_rest(init)
+    }
 
-        Ok(Statement::Let { name, value })
+    /// Whether the current token is the identifier `word`, such as a
+    /// contextual keyword like `of`.
+    fn is_contextual(&self, word: &str) -> bool {
+        matches!(&self.current_token, Token::Identifier(name) if name == word)
     }
 
-    pub fn parse_expression(&mut self) -> Result<Expression, String> {
-        self.parse_binary_expression(0) // Start with lowest precedence
+    /// Parses the rest of a `for (init; test; update)` loop after `init`.
+    fn parse_for_rest(&mut self, init: Option<Statement>) -> Result<Statement, String> {
+        self.expect(Token::Semicolon, "after for loop initializer")?;
+
+        let test = match self.current_token {
+            Token::Semicolon => None,
+            _ => Some(self.parse_expression()?),
+        };
+        self.expect(Token::Semicolon, "after for loop condition")?;
+
+        let update = match self.current_token {
+            Token::RParen => None,
+            _ => Some(self.parse_expression()?),
+        };
+        self.expect(Token::RParen, "to close for loop header")?;
+
+        let body = Box::new(self.parse_loop_body()?);
+        Ok(Statement::For { init: init.map(Box::new), test, update, body })
     }
 
-    fn get_operator_precedence(token: &Token) -> u8 {
-        match token {
-            Token::Plus | Token::Minus => 1,
-            Token::Star | Token::Slash => 2,
-            _ => 0,
+    /// Parses the rest of a `for (left in object)` or `for (left of
+    /// iterable)` loop, starting at `in` / `of`.
+    fn parse_for_in_of_rest(&mut self, left: ForBinding, is_await: bool) -> Result<Statement, String> {
+        let is_for_of = self.next_token() != Token::In;
+        if is_await && !is_for_of {
+            return Err(FOR_AWAIT_WITHOUT_OF.to_string());
         }
+        let right = if is_for_of { self.parse_assignment()? } else { self.parse_expression()? };
+        self.expect(Token::RParen, "to close for loop header")?;
+        let body = Box::new(self.parse_loop_body()?);
+        Ok(if is_for_of {
+            Statement::ForOf { left, iterable: right, body, is_await }
+        } else {
+            Statement::ForIn { left, object: right, body }
+        })
     }
 
-    fn parse_binary_expression(&mut self, precedence: u8) -> Result<Expression, String> {
-        let mut left = self.parse_atom()?;
-        println!("Parsed left side: {:?}", left);
-
-        while self.is_operator(&self.current_token) {
-            let op_precedence = Self::get_operator_precedence(&self.current_token);
-            println!("Current operator: {:?}, precedence: {}, min precedence: {}", 
-                    self.current_token, op_precedence, precedence);
-            
-            if op_precedence <= precedence {
-                break;
-            }
+    fn parse_loop_body(&mut self) -> Result<Statement, String> {
+        self.loop_depth += 1;
+        self.breakable_depth += 1;
+        let body = self.parse_statement();
+        self.loop_depth -= 1;
+        self.breakable_depth -= 1;
+        body
+    }
 
-            let operator = self.current_token.clone();
-            self.next_token(); // consume operator
+    fn parse_switch_statement(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'switch'
+        let discriminant = self.parse_parenthesized()?;
+        self.expect(Token::LBrace, "to open switch body")?;
 
-            let right = self.parse_binary_expression(op_precedence)?;
-            println!("Parsed right side: {:?}", right);
-            
-            left = Expression::Binary {
-                left: Box::new(left),
-                operator,
-                right: Box::new(right),
-            };
-        }
+        self.breakable_depth += 1;
+        let cases = self.parse_switch_cases();
+        self.breakable_depth -= 1;
 
-        Ok(left)
+        Ok(Statement::Switch { discriminant, cases: cases? })
     }
 
-    fn parse_atom(&mut self) -> Result<Expression, String> {
-        match &self.current_token {
-            Token::LParen => {
-                self.next_token(); // consume '('
-                let expr = self.parse_expression()?;
-                
-                if self.current_token != Token::RParen {
-                    return Err("Expected ')'".to_string());
+    fn parse_switch_cases(&mut self) -> Result<Vec<SwitchCase>, String> {
+        let mut cases = Vec::new();
+        let mut seen_default = false;
+        while self.current_token != Token::RBrace {
+            let test = match self.current_token {
+                Token::Case => {
+                    self.next_token(); // consume 'case'
+                    Some(self.parse_expression()?)
                 }
-                self.next_token(); // consume ')'
-                Ok(expr)
+                Token::Default => {
+                    if seen_default {
+                        return Err("More than one default clause in switch statement".to_string());
+                    }
+                    seen_default = true;
+                    self.next_token(); // consume 'default'
+                    None
+                }
+                _ => return Err(format!("Expected 'case' or 'default', got {:?}", self.current_token)),
+            };
+            self.expect(Token::Colon, "after switch case")?;
+
+            let mut consequent = Vec::new();
+            while !matches!(self.current_token, Token::Case | Token::Default | Token::RBrace) {
+                if self.is_eof() {
+                    return Err("Expected '}' to close switch body".to_string());
+                }
+                consequent.push(self.parse_statement()?);
             }
-            _ => self.parse_primary()
+            cases.push(SwitchCase { test, consequent });
         }
+        self.next_token(); // consume '}'
+        Ok(cases)
     }
 
-    fn parse_primary(&mut self) -> Result<Expression, String> {
-        println!("Parsing primary expression, current token: {:?}", self.current_token);
-        let expr = match &self.current_token {
-            Token::Number(n) => Expression::Number(*n),
-            Token::String(s) => Expression::String(s.clone()),
-            Token::Identifier(name) => Expression::Identifier(name.clone()),
-            token => {
-                println!("Unexpected token in primary expression: {:?}", token);
-                return Err(format!("Expected expression, got {:?}", token));
+    fn parse_jump_statement(&mut self) -> Result<Statement, String> {
+        let is_break = self.next_token() == Token::Break;
+
+        // The label must be on the same line as the keyword
+        let label = match &self.current_token {
+            Token::Identifier(name) if !self.newline_before => {
+                let name = name.clone();
+                self.next_token();
+                Some(name)
             }
+            _ => None,
         };
 
-        self.next_token(); // consume the token
-        Ok(expr)
+        match &label {
+            Some(name) => match self.labels.iter().rev().find(|label| &label.name == name) {
+                None => return Err(format!("Undefined label '{}'", name)),
+                Some(label) if !is_break && !label.is_loop => {
+                    return Err(format!("Illegal continue statement: '{}' does not denote an iteration statement", name));
+                }
+                Some(_) => {}
+            },
+            None if is_break && self.breakable_depth == 0 => {
+                return Err("Illegal break statement".to_string());
+            }
+            None if !is_break && self.loop_depth == 0 => {
+                return Err("Illegal continue statement: no surrounding iteration statement".to_string());
+            }
+            None => {}
+        }
+
+        self.consume_semicolon()?;
+        Ok(if is_break { Statement::Break(label) } else { Statement::Continue(label) })
     }
 
-    fn is_operator(&self, token: &Token) -> bool {
-        matches!(token, 
-            Token::Plus | Token::Minus | Token::Star | Token::Slash
-        )
+    fn parse_labeled_statement(&mut self) -> Result<Statement, String> {
+        let label = match self.next_token() {
+            Token::Identifier(name) => name,
+            _ => unreachable!("labeled statement starts with an identifier"),
+        };
+        self.next_token(); // consume ':'
+
+        if self.labels.iter().any(|existing| existing.name == label) {
+            return Err(format!("Label '{}' has already been declared", label));
+        }
+        let is_loop = matches!(self.current_token, Token::While | Token::Do | Token::For);
+        self.labels.push(Label { name: label.clone(), is_loop });
+        let body = self.parse_statement();
+        self.labels.pop();
+
+        Ok(Statement::Labeled { label, body: Box::new(body?) })
     }
 
-    pub fn is_eof(&self) -> bool {
-        self.current_token == Token::EOF
+    fn parse_return_statement(&mut self) -> Result<Statement, String> {
+        if !self.in_function {
+            return Err("Illegal return statement".to_string());
+        }
+        self.next_token(); // consume 'return'
+
+        // A line break after `return` ends the statement
+        let value = match self.current_token {
+            Token::Semicolon | Token::RBrace | Token::EOF => None,
+            _ if self.newline_before => None,
+            _ => Some(self.parse_expression()?),
+        };
+        self.consume_semicolon()?;
+        // An async generator awaits the value it returns, where a rejection
+        // can still be caught
+        let value = match value {
+            Some(value) if self.in_generator && self.in_async => Some(Expression::Await(Box::new(value))),
+            value => value,
+        };
+        Ok(Statement::Return(value))
+    }
+
+    fn parse_throw_statement(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'throw'
+        if self.newline_before {
+            return Err("Illegal newline after throw".to_string());
+        }
+        let value = self.parse_expression()?;
+        self.consume_semicolon()?;
+        Ok(Statement::Throw(value))
+    }
+
+    /// Parses `try { } catch (param) { } finally { }`, where either the
+    /// `catch` or the `finally` clause may be left out.
+    fn parse_try_statement(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'try'
+        let block = self.parse_block()?;
+
+        let handler = if self.current_token == Token::Catch {
+            self.next_token(); // consume 'catch'
+            let param = if self.current_token == Token::LParen {
+                self.next_token(); // consume '('
+                let param = self.parse_binding_target()?;
+                if self.current_token == Token::Colon {
+                    // Only `any` and `unknown` are allowed, which changes nothing
+                    self.next_token(); // consume ':'
+                    self.skip_type()?;
+                }
+                self.expect(Token::RParen, "to close catch parameter")?;
+                Some(param)
+            } else {
+                None
+            };
+            let body = self.parse_block()?;
+            Some(CatchClause { param, body })
+        } else {
+            None
+        };
+
+        let finalizer = if self.current_token == Token::Finally {
+            self.next_token(); // consume 'finally'
+            Some(self.parse_block()?)
+        } else {
+            None
+        };
+
+        if handler.is_none() && finalizer.is_none() {
+            return Err("Missing catch or finally after try".to_string());
+        }
+        Ok(Statement::Try { block, handler, finalizer })
+    }
+
+    /// Parses `function name<T>(params): ReturnType { body }`, or
+    /// `function*` for a generator; the name is optional so this serves
+    /// declarations and expressions alike. With `is_async`, the current
+    /// token is the `async` before it.
+    fn parse_function(&mut self, is_async: bool) -> Result<Rc<FunctionDefinition>, String> {
+        let function = self.parse_function_or_signature(is_async, false)?;
+        Ok(function.expect("only signatures may omit the body"))
+    }
+
+    /// Parses a function declaration, skipping it when it is an overload
+    /// signature, which has no body.
+    fn parse_function_declaration(&mut self, is_async: bool) -> Result<Statement, String> {
+        let Some(function) = self.parse_function_or_signature(is_async, true)? else {
+            return Ok(Statement::Empty);
+        };
+        if function.name.is_none() {
+            return Err("Function statements require a function name".to_string());
+        }
+        Ok(Statement::FunctionDeclaration(function))
+    }
+
+    /// Parses `function name(...) { ... }`, or with `allow_signature` a
+    /// signature without the body, which returns `None`.
+    fn parse_function_or_signature(&mut self, is_async: bool, allow_signature: bool) -> Result<Option<Rc<FunctionDefinition>>, String> {
+        let start = self.lexer.token_start();
+        if is_async {
+            self.next_token(); // consume 'async'
+        }
+        self.expect(Token::Function, "to start function")?;
+        let is_generator = self.current_token == Token::Star;
+        if is_generator {
+            self.next_token(); // consume '*'
+        }
+        let name = match &self.current_token {
+            Token::Identifier(name) => {
+                let name = name.clone();
+                self.next_token();
+                Some(name)
+            }
+            _ => None,
+        };
+        self.parse_function_parts(start, name, FunctionKind::Normal, is_generator, is_async, allow_signature)
+    }
+
+    /// Parses the parameters and body of a function or method, starting at
+    /// its optional type parameters. Its source text runs from `start`.
+    fn parse_function_rest(
+        &mut self,
+        start: usize,
+        name: Option<String>,
+        kind: FunctionKind,
+        is_generator: bool,
+        is_async: bool,
+    ) -> Result<Rc<FunctionDefinition>, String> {
+        let function = self.parse_function_parts(start, name, kind, is_generator, is_async, false)?;
+        Ok(function.expect("only signatures may omit the body"))
     }
-}
\ No newline at end of file
+
+    /// Parses a function after its name. With `allow_signature`, as in
+    /// class bodies, a missing body makes this an overload or abstract
+    /// signature, which is skipped and returns `None`.
+    fn parse_function_parts(
+        &mut self,
+        start: usize,
+        name: Option<String>,
+        kind: FunctionKind,
+        is_generator: bool,
+        is_async: bool,
+        allow_signature: bool,
+    ) -> Result<Option<Rc<FunctionDefinition>>, String> {
+        if self.current_token == Token::LAngle {
+            self.skip_type_parameters()?;
+        }
+        let uses_arguments = std::mem::replace(&mut self.uses_arguments, false);
+        let in_generator = std::mem::replace(&mut self.in_generator, false);
+        let in_async = std::mem::replace(&mut self.in_async, false);
+        let mut parameter_properties = Vec::new();
+        let is_constructor = matches!(kind, FunctionKind::BaseConstructor | FunctionKind::DerivedConstructor);
+        let params = self.in_function_kind(kind, |parser| {
+            parser.parse_parameters(is_constructor.then_some(&mut parameter_properties))
+        });
+        self.in_generator = in_generator;
+        self.in_async = in_async;
+        let params = params?;
+        if self.current_token == Token::Colon {
+            self.next_token(); // consume ':'
+            self.skip_type()?;
+        }
+        if allow_signature && self.current_token != Token::LBrace {
+            self.consume_semicolon()?;
+            self.uses_arguments = uses_arguments;
+            return Ok(None);
+        }
+        let in_generator = std::mem::replace(&mut self.in_generator, is_generator);
+        let in_async = std::mem::replace(&mut self.in_async, is_async);
+        let body = self.in_function_kind(kind, Self::parse_function_body);
+        self.in_generator = in_generator;
+        self.in_async = in_async;
+        let body = body?;
+        let uses_arguments = std::mem::replace(&mut self.uses_arguments, uses_arguments);
+        let source_text = self.source_since(start);
+        Ok(Some(Rc::new(FunctionDefinition { name, params, body, uses_arguments, kind, is_generator, is_async, parameter_properties, source_text })))
+    }
+
+    /// Runs `parse` with the uses of `super` that a function of `kind`
+    /// allows.
+    fn in_function_kind<T>(&mut self, kind: FunctionKind, parse: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        let saved = (self.allow_super_property, self.allow_super_call);
+        self.allow_super_property = kind != FunctionKind::Normal;
+        self.allow_super_call = kind == FunctionKind::DerivedConstructor;
+        let result = parse(self);
+        (self.allow_super_property, self.allow_super_call) = saved;
+        result
+    }
+
+    /// Parses a parameter list. Constructors pass `properties` to collect
+    /// the parameters marked with an accessibility or `readonly` modifier.
+    fn parse_parameters(&mut self, mut properties: Option<&mut Vec<String>>) -> Result<Vec<Parameter>, String> {
+        self.expect(Token::LParen, "to open parameter list")?;
+        let mut params = Vec::new();
+        while self.current_token != Token::RParen {
+            let is_property = self.skip_parameter_modifiers();
+            let rest = self.current_token == Token::Ellipsis;
+            if rest {
+                self.next_token(); // consume '...'
+            }
+
+            let target = self.parse_binding_target()?;
+            if is_property {
+                let (Some(properties), Pattern::Identifier(name)) = (properties.as_mut(), &target) else {
+                    return Err("A parameter property is only allowed in a constructor implementation.".to_string());
+                };
+                properties.push(name.clone());
+            }
+
+            // Optional marker and type annotation only matter to the type checker
+            if self.current_token == Token::Question {
+                self.next_token();
+            }
+            if self.current_token == Token::Colon {
+                self.next_token();
+                self.skip_type()?;
+            }
+
+            let default = if self.current_token == Token::Equal {
+                self.next_token(); // consume '='
+                Some(self.parse_assignment()?)
+            } else {
+                None
+            };
+
+            params.push(Parameter { target, default, rest });
+
+            if rest && self.current_token != Token::RParen {
+                return Err("A rest parameter must be last in a parameter list".to_string());
+            }
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token(); // consume ','
+        }
+        self.expect(Token::RParen, "to close parameter list")?;
+        Ok(params)
+    }
+
+    /// Parses the name or destructuring pattern a declaration or parameter
+    /// binds.
+    fn parse_binding_target(&mut self) -> Result<Pattern, String> {
+        match &self.current_token {
+            Token::Identifier(name) => {
+                let name = name.clone();
+                self.next_token(); // consume identifier
+                Ok(Pattern::Identifier(name))
+            }
+            Token::LBracket => self.allowing_in(Self::parse_array_pattern),
+            Token::LBrace => self.allowing_in(Self::parse_object_pattern),
+            token => Err(format!("Expected identifier or pattern, got {:?}", token)),
+        }
+    }
+
+    fn parse_array_pattern(&mut self) -> Result<Pattern, String> {
+        self.expect(Token::LBracket, "to open array pattern")?;
+        let mut elements = Vec::new();
+        let mut rest = None;
+        while self.current_token != Token::RBracket {
+            if self.current_token == Token::Comma {
+                self.next_token(); // a hole
+                elements.push(None);
+                continue;
+            }
+            if self.current_token == Token::Ellipsis {
+                self.next_token(); // consume '...'
+                rest = Some(Box::new(self.parse_binding_target()?));
+                if self.current_token != Token::RBracket {
+                    return Err("Rest element must be last element".to_string());
+                }
+                break;
+            }
+            let target = self.parse_binding_target()?;
+            let default = self.parse_pattern_default()?;
+            elements.push(Some(PatternElement { target, default }));
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token(); // consume ','
+        }
+        self.expect(Token::RBracket, "to close array pattern")?;
+        Ok(Pattern::Array { elements, rest })
+    }
+
+    fn parse_object_pattern(&mut self) -> Result<Pattern, String> {
+        self.expect(Token::LBrace, "to open object pattern")?;
+        let mut properties = Vec::new();
+        let mut rest = None;
+        while self.current_token != Token::RBrace {
+            if self.current_token == Token::Ellipsis {
+                self.next_token(); // consume '...'
+                let Token::Identifier(name) = self.next_token() else {
+                    return Err("`...` must be followed by an identifier in declaration contexts".to_string());
+                };
+                rest = Some(Box::new(Pattern::Identifier(name)));
+                if self.current_token != Token::RBrace {
+                    return Err("Rest element must be last element".to_string());
+                }
+                break;
+            }
+            let shorthand = match &self.current_token {
+                Token::Identifier(name) => Some(name.clone()),
+                _ => None,
+            };
+            let key = self.parse_object_key()?;
+            let target = match shorthand {
+                Some(name) if self.current_token != Token::Colon => Pattern::Identifier(name),
+                _ => {
+                    self.expect(Token::Colon, "after property name in object pattern")?;
+                    self.parse_binding_target()?
+                }
+            };
+            let default = self.parse_pattern_default()?;
+            properties.push(PatternProperty { key, target, default });
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token(); // consume ','
+        }
+        self.expect(Token::RBrace, "to close object pattern")?;
+        Ok(Pattern::Object { properties, rest })
+    }
+
+    /// Parses the `= default` after a pattern element, if present.
+    fn parse_pattern_default(&mut self) -> Result<Option<Expression>, String> {
+        if self.current_token != Token::Equal {
+            return Ok(None);
+        }
+        self.next_token(); // consume '='
+        Ok(Some(self.parse_assignment()?))
+    }
+
+    /// Skips the TypeScript modifiers before a constructor parameter,
+    /// returning whether there were any.
+    fn skip_parameter_modifiers(&mut self) -> bool {
+        let mut found = false;
+        while let Token::Identifier(word) = &self.current_token {
+            let is_modifier = matches!(word.as_str(), "public" | "private" | "protected" | "readonly" | "override");
+            if !is_modifier || !matches!(self.lexer.peek_token(), Token::Identifier(_)) {
+                break;
+            }
+            self.next_token();
+            found = true;
+        }
+        found
+    }
+
+    /// Parses a function body with fresh label and loop context, since
+    /// `break`, `continue` and labels cannot cross a function boundary.
+    fn parse_function_body(&mut self) -> Result<Vec<Statement>, String> {
+        let labels = std::mem::take(&mut self.labels);
+        let loop_depth = std::mem::replace(&mut self.loop_depth, 0);
+        let breakable_depth = std::mem::replace(&mut self.breakable_depth, 0);
+        let in_function = std::mem::replace(&mut self.in_function, true);
+        let no_in = std::mem::replace(&mut self.no_in, false);
+
+        let body = self.parse_block();
+
+        self.labels = labels;
+        self.loop_depth = loop_depth;
+        self.breakable_depth = breakable_depth;
+        self.in_function = in_function;
+        self.no_in = no_in;
+        body
+    }
+
+    fn parse_class_declaration(&mut self, is_abstract: bool) -> Result<Statement, String> {
+        let class = self.parse_class(is_abstract)?;
+        if class.name.is_none() {
+            return Err("Class statements require a class name".to_string());
+        }
+        Ok(Statement::ClassDeclaration(class))
+    }
+
+    /// Parses `class Name<T> extends Base<T> implements I { members }`; the
+    /// name is optional so this serves declarations and expressions alike.
+    fn parse_class(&mut self, is_abstract: bool) -> Result<Rc<ClassDefinition>, String> {
+        let start = self.lexer.token_start();
+        self.expect(Token::Class, "to start class")?;
+        let name = match &self.current_token {
+            Token::Identifier(name) if name != "implements" => {
+                let name = name.clone();
+                self.next_token();
+                Some(name)
+            }
+            _ => None,
+        };
+        if self.current_token == Token::LAngle {
+            self.skip_type_parameters()?;
+        }
+
+        let superclass = if self.current_token == Token::Extends {
+            self.next_token(); // consume 'extends'
+            let superclass = self.parse_call_member()?;
+            if self.current_token == Token::LAngle {
+                self.skip_type_arguments()?;
+            }
+            Some(superclass)
+        } else {
+            None
+        };
+        if self.current_token == Token::Identifier("implements".to_string()) {
+            self.next_token();
+            loop {
+                self.skip_type()?;
+                if self.current_token != Token::Comma {
+                    break;
+                }
+                self.next_token();
+            }
+        }
+
+        self.expect(Token::LBrace, "to open class body")?;
+        self.private_scopes.push(PrivateScope::default());
+        let body = self.parse_class_body(&name, superclass.is_some());
+        let scope = self.private_scopes.pop().expect("class private scope");
+        let (constructor, members) = body?;
+
+        // Names not declared here must belong to an enclosing class
+        for used in scope.used {
+            if !scope.declared.contains_key(&used) {
+                self.use_private_name(&used)?;
+            }
+        }
+
+        // The class itself is what its constructor prints as
+        let mut constructor = constructor.unwrap_or_else(|| default_constructor(name.clone(), superclass.is_some()));
+        Rc::get_mut(&mut constructor).expect("constructor is not shared yet").source_text = self.source_since(start);
+        Ok(Rc::new(ClassDefinition { name, superclass, constructor, members, is_abstract }))
+    }
+
+    /// Parses class members up to and including the closing `}`.
+    fn parse_class_body(
+        &mut self,
+        class_name: &Option<String>,
+        derived: bool,
+    ) -> Result<(Option<Rc<FunctionDefinition>>, Vec<ClassMember>), String> {
+        let mut constructor = None;
+        let mut members = Vec::new();
+        while self.current_token != Token::RBrace {
+            if self.is_eof() {
+                return Err("Expected '}' to close class body".to_string());
+            }
+            if self.current_token == Token::Semicolon {
+                self.next_token();
+                continue;
+            }
+            if self.current_token == Token::Identifier("static".to_string()) && self.lexer.peek_token() == Token::LBrace {
+                let start = self.lexer.token_start();
+                self.next_token(); // consume 'static'
+                let block = self.parse_static_block(start)?;
+                let key = ClassKey::Public(PropertyName::Literal("static".to_string()));
+                members.push(ClassMember { key, is_static: true, kind: ClassMemberKind::StaticBlock(block) });
+                continue;
+            }
+
+            // Modifiers other than `static` only matter to the type checker,
+            // but abstract and `declare` members have no runtime effect
+            let mut is_static = false;
+            let mut type_only = false;
+            while let Token::Identifier(word) = &self.current_token {
+                let word = word.clone();
+                let is_modifier = matches!(
+                    word.as_str(),
+                    "public" | "private" | "protected" | "readonly" | "abstract" | "override" | "declare" | "static"
+                );
+                if !is_modifier || is_member_name_end(&self.lexer.peek_token()) {
+                    break;
+                }
+                self.next_token();
+                match word.as_str() {
+                    "static" => is_static = true,
+                    "abstract" | "declare" => type_only = true,
+                    _ => {}
+                }
+            }
+
+            let start = self.lexer.token_start();
+            let mut accessor = None;
+            if let Token::Identifier(word) = &self.current_token {
+                if (word == "get" || word == "set") && !is_member_name_end(&self.lexer.peek_token()) {
+                    accessor = Some(word == "get");
+                    self.next_token();
+                }
+            }
+            let mut is_async = false;
+            if let Token::Identifier(word) = &self.current_token {
+                if accessor.is_none() && word == "async" && !is_member_name_end(&self.lexer.peek_token()) {
+                    is_async = true;
+                    self.next_token();
+                }
+            }
+            let is_generator = accessor.is_none() && self.current_token == Token::Star;
+            if is_generator {
+                self.next_token(); // consume '*'
+            }
+
+            let key = match &self.current_token {
+                Token::PrivateName(name) => {
+                    if name == "constructor" {
+                        return Err("Classes may not have a private field named '#constructor'".to_string());
+                    }
+                    let name = name.clone();
+                    self.next_token();
+                    ClassKey::Private(name)
+                }
+                _ => ClassKey::Public(self.parse_object_key()?),
+            };
+            let literal_name = match &key {
+                ClassKey::Public(PropertyName::Literal(name)) => Some(name.as_str()),
+                _ => None,
+            };
+            if is_static && literal_name == Some("prototype") {
+                return Err("Classes may not have a static property named 'prototype'".to_string());
+            }
+            let is_constructor = !is_static && literal_name == Some("constructor");
+
+            // Optional and definite assignment markers
+            if matches!(self.current_token, Token::Question | Token::Bang) {
+                self.next_token();
+            }
+
+            if is_generator && !matches!(self.current_token, Token::LParen | Token::LAngle) {
+                return Err("Expected '(' after generator method name".to_string());
+            }
+            if is_constructor && is_generator {
+                return Err("Class constructor may not be a generator".to_string());
+            }
+            if is_constructor && is_async {
+                return Err("Class constructor may not be an async method".to_string());
+            }
+            if accessor.is_some() || matches!(self.current_token, Token::LParen | Token::LAngle) {
+                let kind = match (is_constructor, derived) {
+                    (true, true) => FunctionKind::DerivedConstructor,
+                    (true, false) => FunctionKind::BaseConstructor,
+                    (false, _) => FunctionKind::Method,
+                };
+                if is_constructor && accessor.is_some() {
+                    return Err("Class constructor may not be an accessor".to_string());
+                }
+                let name = if is_constructor { class_name.clone() } else { None };
+                let Some(function) = self.parse_function_parts(start, name, kind, is_generator, is_async, true)? else {
+                    continue;
+                };
+                if type_only {
+                    return Err("Method cannot have an implementation because it is marked abstract.".to_string());
+                }
+                if is_constructor {
+                    if constructor.is_some() {
+                        return Err("A class may only have one constructor".to_string());
+                    }
+                    constructor = Some(function);
+                    continue;
+                }
+                if let Some(is_getter) = accessor {
+                    check_accessor(is_getter, &function)?;
+                }
+                if let ClassKey::Private(name) = &key {
+                    self.declare_private_name(name, accessor)?;
+                }
+                let kind = match accessor {
+                    Some(true) => ClassMemberKind::Getter(function),
+                    Some(false) => ClassMemberKind::Setter(function),
+                    None => ClassMemberKind::Method(function),
+                };
+                members.push(ClassMember { key, is_static, kind });
+                continue;
+            }
+
+            if literal_name == Some("constructor") {
+                return Err("Classes may not have a field named 'constructor'".to_string());
+            }
+            if self.current_token == Token::Colon {
+                self.next_token();
+                self.skip_type()?;
+            }
+            let initializer = if self.current_token == Token::Equal {
+                self.next_token(); // consume '='
+                Some(Rc::new(self.parse_field_initializer()?))
+            } else {
+                None
+            };
+            self.consume_semicolon()?;
+            if type_only {
+                continue;
+            }
+            if let ClassKey::Private(name) = &key {
+                self.declare_private_name(name, None)?;
+            }
+            members.push(ClassMember { key, is_static, kind: ClassMemberKind::Field(initializer) });
+        }
+        self.next_token(); // consume '}'
+        Ok((constructor, members))
+    }
+
+    /// Parses a field initializer, which runs like a method body with
+    /// `this` bound to the instance.
+    fn parse_field_initializer(&mut self) -> Result<Expression, String> {
+        let uses_arguments = std::mem::replace(&mut self.uses_arguments, false);
+        let in_generator = std::mem::replace(&mut self.in_generator, false);
+        let in_async = std::mem::replace(&mut self.in_async, false);
+        let initializer = self.in_function_kind(FunctionKind::Method, |parser| parser.allowing_in(Self::parse_assignment));
+        self.in_generator = in_generator;
+        self.in_async = in_async;
+        let initializer = initializer?;
+        if std::mem::replace(&mut self.uses_arguments, uses_arguments) {
+            return Err("'arguments' is not allowed in class field initializer".to_string());
+        }
+        Ok(initializer)
+    }
+
+    /// Parses the block of `static { ... }` as the body of a static method
+    /// without parameters, in which `arguments` is not allowed.
+    fn parse_static_block(&mut self, start: usize) -> Result<Rc<FunctionDefinition>, String> {
+        let uses_arguments = std::mem::replace(&mut self.uses_arguments, false);
+        let in_generator = std::mem::replace(&mut self.in_generator, false);
+        let in_async = std::mem::replace(&mut self.in_async, false);
+        let body = self.in_function_kind(FunctionKind::Method, Self::parse_function_body);
+        self.in_generator = in_generator;
+        self.in_async = in_async;
+        let body = body?;
+        if std::mem::replace(&mut self.uses_arguments, uses_arguments) {
+            return Err("'arguments' is not allowed in class static initialization block".to_string());
+        }
+        Ok(Rc::new(FunctionDefinition {
+            name: None,
+            params: Vec::new(),
+            body,
+            uses_arguments: false,
+            kind: FunctionKind::Method,
+            is_generator: false,
+            is_async: false,
+            parameter_properties: Vec::new(),
+            source_text: self.source_since(start),
+        }))
+    }
+
+    /// Records a private name declared by the innermost class body.
+    fn declare_private_name(&mut self, name: &str, accessor: Option<bool>) -> Result<(), String> {
+        let scope = self.private_scopes.last_mut().expect("private names are declared in a class body");
+        let declaration = match accessor {
+            Some(true) => PrivateDeclaration::Getter,
+            Some(false) => PrivateDeclaration::Setter,
+            None => PrivateDeclaration::Complete,
+        };
+        let merged = match (scope.declared.get(name), declaration) {
+            (None, declaration) => declaration,
+            (Some(PrivateDeclaration::Getter), PrivateDeclaration::Setter)
+            | (Some(PrivateDeclaration::Setter), PrivateDeclaration::Getter) => PrivateDeclaration::Complete,
+            _ => return Err(format!("Identifier '#{}' has already been declared", name)),
+        };
+        scope.declared.insert(name.to_string(), merged);
+        Ok(())
+    }
+
+    /// Records a use of `#name`, which must be declared by an enclosing
+    /// class body. Checked once the body has been parsed, since members
+    /// may refer to names declared after them.
+    fn use_private_name(&mut self, name: &str) -> Result<(), String> {
+        match self.private_scopes.last_mut() {
+            Some(scope) => {
+                scope.used.push(name.to_string());
+                Ok(())
+            }
+            None => Err(format!("Private field '#{}' must be declared in an enclosing class", name)),
+        }
+    }
+
+    /// Skips `interface Name<T> extends Base { ... }`, which only
+    /// describes types.
+    fn skip_interface_declaration(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'interface'
+        self.next_token(); // consume name
+        if self.current_token == Token::LAngle {
+            self.skip_type_parameters()?;
+        }
+        if self.current_token == Token::Extends {
+            self.next_token();
+            loop {
+                self.skip_type()?;
+                if self.current_token != Token::Comma {
+                    break;
+                }
+                self.next_token();
+            }
+        }
+        if self.current_token != Token::LBrace {
+            return Err(format!("Expected '{{' to open interface body, got {:?}", self.current_token));
+        }
+        self.skip_primary_type()?;
+        Ok(Statement::Empty)
+    }
+
+    /// Skips `type Name<T> = ...;`, which only names a type.
+    fn skip_type_alias(&mut self) -> Result<Statement, String> {
+        self.next_token(); // consume 'type'
+        self.next_token(); // consume name
+        if self.current_token == Token::LAngle {
+            self.skip_type_parameters()?;
+        }
+        self.expect(Token::Equal, "in type alias")?;
+        self.skip_type()?;
+        self.consume_semicolon()?;
+        Ok(Statement::Empty)
+    }
+
+    fn parse_parenthesized(&mut self) -> Result<Expression, String> {
+        self.expect(Token::LParen, "before condition")?;
+        let expr = self.parse_expression()?;
+        self.expect(Token::RParen, "after condition")?;
+        Ok(expr)
+    }
+
+    fn parse_variable_declaration(&mut self, kind: VariableKind) -> Result<Statement, String> {
+        let declaration = self.parse_variable_declarations(kind)?;
+        self.consume_semicolon()?;
+        Ok(declaration)
+    }
+
+    /// Parses `let a = 1, b` without the terminating semicolon.
+    fn parse_variable_declarations(&mut self, kind: VariableKind) -> Result<Statement, String> {
+        self.next_token(); // consume 'let' / 'const' / 'var'
+
+        let mut declarations = Vec::new();
+        loop {
+            let target = self.parse_binding_target()?;
+            // `let x!: number` asserts that `x` is assigned before use
+            if self.current_token == Token::Bang && matches!(target, Pattern::Identifier(_)) && self.lexer.peek_token() == Token::Colon {
+                self.next_token(); // consume '!'
+            }
+            if self.current_token == Token::Colon {
+                self.next_token(); // consume ':'
+                self.skip_type()?;
+            }
+
+            let init = if self.current_token == Token::Equal {
+                self.next_token(); // consume '='
+                Some(self.parse_assignment()?)
+            } else {
+                match &target {
+                    Pattern::Identifier(name) if kind == VariableKind::Const => {
+                        return Err(format!("Missing initializer in const declaration '{}'", name));
+                    }
+                    Pattern::Identifier(_) => None,
+                    _ => return Err("Missing initializer in destructuring declaration".to_string()),
+                }
+            };
+
+            declarations.push(VariableDeclarator { target, init });
+
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token(); // consume ','
+        }
+
+        Ok(Statement::VariableDeclaration { kind, declarations })
+    }
+
+    pub fn parse_expression(&mut self) -> Result<Expression, String> {
+        let first = self.parse_assignment()?;
+        if self.current_token != Token::Comma {
+            return Ok(first);
+        }
+
+        let mut expressions = vec![first];
+        while self.current_token == Token::Comma {
+            self.next_token(); // consume ','
+            expressions.push(self.parse_assignment()?);
+        }
+        Ok(Expression::Sequence(expressions))
+    }
+
+    fn is_assignment_operator(token: &Token) -> bool {
+        matches!(token,
+            Token::Equal | Token::PlusEqual | Token::MinusEqual | Token::StarEqual |
+            Token::SlashEqual | Token::PercentEqual | Token::StarStarEqual |
+            Token::AmpersandEqual | Token::PipeEqual | Token::CaretEqual |
+            Token::ShiftLeftEqual | Token::ShiftRightEqual | Token::UnsignedShiftRightEqual |
+            Token::AmpersandAmpersandEqual | Token::PipePipeEqual | Token::QuestionQuestionEqual
+        )
+    }
+
+    fn parse_assignment(&mut self) -> Result<Expression, String> {
+        with_stack(|| {
+            let in_literal_element = std::mem::replace(&mut self.in_literal_element, false);
+            if self.in_generator && self.current_token == Token::Identifier("yield".to_string()) {
+                return self.parse_yield();
+            }
+            if let Some(arrow) = self.try_parse_arrow_function()? {
+                return Ok(arrow);
+            }
+
+            let cover_initializers = self.cover_initializers;
+            let target = self.parse_conditional()?;
+
+            // An array or object literal followed by `=` is reinterpreted as a pattern
+            if self.current_token == Token::Equal && matches!(target, Expression::Array(_) | Expression::Object(_)) {
+                let target = expression_to_pattern(target)?;
+                self.cover_initializers = cover_initializers;
+                self.next_token(); // consume '='
+                let value = self.parse_assignment()?;
+                return Ok(Expression::DestructuringAssignment { target, value: Box::new(value) });
+            }
+            // Unless an enclosing literal may still become a pattern, `{ a = 1 }`
+            // can no longer be one
+            let may_become_pattern = in_literal_element && matches!(target, Expression::Array(_) | Expression::Object(_));
+            if self.cover_initializers > cover_initializers && !may_become_pattern {
+                return Err("Invalid shorthand property initializer".to_string());
+            }
+
+            if !Self::is_assignment_operator(&self.current_token) {
+                return Ok(target);
+            }
+
+            if !is_simple_assignment_target(&target) {
+                return Err("Invalid left-hand side in assignment".to_string());
+            }
+
+            let operator = self.next_token(); // consume operator
+            let value = self.parse_assignment()?; // right-associative
+
+            Ok(Expression::Assignment {
+                operator,
+                target: Box::new(target),
+                value: Box::new(value),
+            })
+        })
+    }
+
+    /// Parses `yield`, `yield value` or `yield* iterable`. The operand is
+    /// optional, and left out when the expression ends after `yield`.
+    fn parse_yield(&mut self) -> Result<Expression, String> {
+        self.next_token(); // consume 'yield'
+        let delegate = self.current_token == Token::Star && !self.newline_before;
+        if delegate {
+            self.next_token(); // consume '*'
+        }
+        let ends_expression = self.newline_before
+            || self.is_eof()
+            || matches!(
+                self.current_token,
+                Token::RParen | Token::RBracket | Token::RBrace | Token::Comma | Token::Semicolon | Token::Colon
+            );
+        let argument = if delegate || !ends_expression {
+            Some(Box::new(self.parse_assignment()?))
+        } else {
+            None
+        };
+        Ok(Expression::Yield { argument, delegate })
+    }
+
+    fn parse_conditional(&mut self) -> Result<Expression, String> {
+        let test = self.parse_binary_expression(0)?;
+        if self.current_token != Token::Question {
+            return Ok(test);
+        }
+
+        self.next_token(); // consume '?'
+        let consequent = self.parse_assignment()?;
+        self.expect(Token::Colon, "in conditional expression")?;
+        let alternate = self.parse_assignment()?;
+
+        Ok(Expression::Conditional {
+            test: Box::new(test),
+            consequent: Box::new(consequent),
+            alternate: Box::new(alternate),
+        })
+    }
+
+    fn get_operator_precedence(token: &Token) -> u8 {
+        match token {
+            Token::PipePipe | Token::QuestionQuestion => 1,
+            Token::AmpersandAmpersand => 2,
+            Token::Pipe => 3,
+            Token::Caret => 4,
+            Token::Ampersand => 5,
+            Token::EqualEqual | Token::BangEqual | Token::EqualEqualEqual | Token::BangEqualEqual => 6,
+            Token::LAngle | Token::RAngle | Token::LessEqual | Token::GreaterEqual | Token::In | Token::Instanceof => 7,
+            Token::ShiftLeft | Token::ShiftRight | Token::UnsignedShiftRight => 8,
+            Token::Plus | Token::Minus => 9,
+            Token::Star | Token::Slash | Token::Percent => 10,
+            Token::StarStar => 11,
+            _ => 0,
+        }
+    }
+
+    fn parse_binary_expression(&mut self, precedence: u8) -> Result<Expression, String> {
+        with_stack(|| {
+            let mut left = self.parse_unary()?;
+            // `??` cannot be mixed with `&&` or `||` without parentheses
+            let mut logical_operator = None;
+
+            loop {
+                // `value as T` and `value satisfies T` bind like relational
+                // operators and evaluate to `value`
+                if self.is_type_assertion() && Self::get_operator_precedence(&Token::LAngle) > precedence {
+                    self.next_token();
+                    if self.current_token == Token::Const {
+                        self.next_token();
+                    } else {
+                        self.skip_type()?;
+                    }
+                    continue;
+                }
+                if !self.is_operator(&self.current_token) {
+                    break;
+                }
+                if self.no_in && self.current_token == Token::In {
+                    break;
+                }
+                let op_precedence = Self::get_operator_precedence(&self.current_token);
+
+                if op_precedence <= precedence {
+                    break;
+                }
+
+                let operator = self.current_token.clone();
+                let location = self.location;
+                if matches!(operator, Token::AmpersandAmpersand | Token::PipePipe | Token::QuestionQuestion) {
+                    let coalesces = operator == Token::QuestionQuestion;
+                    if logical_operator.is_some_and(|previous: bool| previous != coalesces) {
+                        let text = match operator {
+                            Token::AmpersandAmpersand => "&&",
+                            Token::PipePipe => "||",
+                            _ => "??",
+                        };
+                        return Err(format!("Unexpected token '{}'", text));
+                    }
+                    logical_operator = Some(coalesces);
+                }
+                self.next_token(); // consume operator
+
+                // `**` is right-associative, everything else groups to the left.
+                // The operand of `??` stops before `&&` so that mixing is caught.
+                let right = match operator {
+                    Token::StarStar => self.parse_binary_expression(op_precedence - 1)?,
+                    Token::QuestionQuestion => self.parse_binary_expression(Self::get_operator_precedence(&Token::AmpersandAmpersand))?,
+                    _ => self.parse_binary_expression(op_precedence)?,
+                };
+
+                left = if matches!(operator, Token::AmpersandAmpersand | Token::PipePipe | Token::QuestionQuestion) {
+                    Expression::Logical {
+                        left: Box::new(left),
+                        operator,
+                        right: Box::new(right),
+                    }
+                } else {
+                    Expression::Binary {
+                        left: Box::new(left),
+                        operator,
+                        right: Box::new(right),
+                        location,
+                    }
+                };
+            }
+
+            Ok(left)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        with_stack(|| {
+            match self.current_token {
+                Token::Minus | Token::Plus | Token::Bang | Token::Tilde | Token::Delete | Token::Typeof | Token::Void => {
+                    let location = self.location;
+                    let operator = self.next_token();
+                    let operand = self.parse_unary()?;
+                    if operator == Token::Delete && matches!(operand, Expression::Identifier { .. }) {
+                        return Err("Delete of an unqualified identifier in strict mode.".to_string());
+                    }
+                    if operator == Token::Delete && matches!(operand, Expression::PrivateMember { .. }) {
+                        return Err("Private fields can not be deleted".to_string());
+                    }
+                    Ok(Expression::Unary {
+                        operator,
+                        operand: Box::new(operand),
+                        location,
+                    })
+                }
+                Token::Identifier(ref word) if word == "await" && self.in_async => {
+                    self.next_token(); // consume 'await'
+                    let argument = self.parse_unary()?;
+                    Ok(Expression::Await(Box::new(argument)))
+                }
+                Token::PlusPlus | Token::MinusMinus => {
+                    let operator = self.next_token();
+                    let target = self.parse_unary()?;
+                    if !is_simple_assignment_target(&target) {
+                        return Err("Invalid left-hand side expression in prefix operation".to_string());
+                    }
+                    Ok(Expression::Update {
+                        operator,
+                        prefix: true,
+                        target: Box::new(target),
+                    })
+                }
+                _ => self.parse_postfix(),
+            }
+        })
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expression, String> {
+        let expr = self.parse_call_member()?;
+
+        // A line break before `++` / `--` ends the expression instead
+        if matches!(self.current_token, Token::PlusPlus | Token::MinusMinus) && !self.newline_before {
+            if !is_simple_assignment_target(&expr) {
+                return Err("Invalid left-hand side expression in postfix operation".to_string());
+            }
+            let operator = self.next_token();
+            return Ok(Expression::Update {
+                operator,
+                prefix: false,
+                target: Box::new(expr),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses an arrow function if one starts at the current token. A
+    /// parenthesized parameter list looks like a parenthesized expression
+    /// until the `=>` is reached, so that case is parsed speculatively and
+    /// rewound on failure.
+    fn try_parse_arrow_function(&mut self) -> Result<Option<Expression>, String> {
+        // `async` is only a modifier if an arrow function follows it, and
+        // a plain identifier otherwise, as in `async(x)`
+        let is_async = self.current_token == Token::Identifier("async".to_string())
+            && matches!(self.lexer.peek_token(), Token::Identifier(_) | Token::LParen | Token::LAngle);
+        let snapshot = self.snapshot();
+        let start = self.lexer.token_start();
+        if is_async {
+            self.next_token(); // consume 'async'
+        }
+        let params = match &self.current_token {
+            _ if is_async && self.newline_before => None,
+            Token::Identifier(name) if self.lexer.peek_token() == Token::Arrow => {
+                let name = name.clone();
+                self.next_token(); // consume parameter
+                Some(vec![Parameter { target: Pattern::Identifier(name), default: None, rest: false }])
+            }
+            Token::LParen | Token::LAngle => self.parse_arrow_head().ok(),
+            _ => None,
+        };
+        let Some(params) = params else {
+            self.restore(snapshot);
+            return Ok(None);
+        };
+        if self.newline_before {
+            return Err("Line terminator not permitted before arrow".to_string());
+        }
+        self.expect(Token::Arrow, "in arrow function")?;
+
+        let in_generator = std::mem::replace(&mut self.in_generator, false);
+        let in_async = std::mem::replace(&mut self.in_async, is_async);
+        let body = if self.current_token == Token::LBrace {
+            self.parse_function_body()
+        } else {
+            self.parse_assignment().map(|value| vec![Statement::Return(Some(value))])
+        };
+        self.in_generator = in_generator;
+        self.in_async = in_async;
+        let body = body?;
+
+        Ok(Some(Expression::Function(Rc::new(FunctionDefinition {
+            name: None,
+            params,
+            body,
+            uses_arguments: false,
+            kind: FunctionKind::Arrow,
+            is_generator: false,
+            is_async,
+            parameter_properties: Vec::new(),
+            source_text: self.source_since(start),
+        }))))
+    }
+
+    /// Parses `<T>(params): ReturnType` up to the `=>`, failing if the
+    /// tokens cannot be an arrow function head.
+    fn parse_arrow_head(&mut self) -> Result<Vec<Parameter>, String> {
+        if self.current_token == Token::LAngle {
+            self.skip_type_parameters()?;
+        }
+        let params = self.parse_parameters(None)?;
+        if self.current_token == Token::Colon {
+            self.next_token(); // consume ':'
+            self.skip_type()?;
+        }
+        if self.current_token != Token::Arrow {
+            return Err("Expected '=>'".to_string());
+        }
+        Ok(params)
+    }
+
+    /// Parses an atom followed by any chain of calls and member accesses,
+    /// wrapping it in an `OptionalChain` if any link starts with `?.`.
+    fn parse_call_member(&mut self) -> Result<Expression, String> {
+        let location = self.location;
+        let mut expr = match self.current_token {
+            Token::New => self.parse_new()?,
+            _ => self.parse_atom()?,
+        };
+        let mut optional = false;
+        loop {
+            match self.current_token {
+                // Type arguments, as in `obj.m<number>(1)`
+                Token::LAngle => {
+                    if !self.skip_call_type_arguments() {
+                        break;
+                    }
+                }
+                Token::LParen => {
+                    let arguments = self.allowing_in(Self::parse_arguments)?;
+                    expr = Expression::Call { callee: Box::new(expr), arguments, location };
+                }
+                Token::Template { .. } => {
+                    if optional {
+                        return Err("Invalid tagged template on optional chain".to_string());
+                    }
+                    let template = Rc::new(self.parse_template(true)?);
+                    expr = Expression::TaggedTemplate { tag: Box::new(expr), template, location };
+                }
+                Token::Dot | Token::LBracket => expr = self.parse_member_access(expr)?,
+                // A non-null assertion, as in `value!.name`, has no effect
+                Token::Bang if !self.newline_before => {
+                    self.next_token();
+                }
+                Token::QuestionDot => {
+                    optional = true;
+                    let base = Expression::Optional(Box::new(expr));
+                    expr = match self.lexer.peek_token() {
+                        Token::LParen => {
+                            self.next_token(); // consume '?.'
+                            let arguments = self.allowing_in(Self::parse_arguments)?;
+                            Expression::Call { callee: Box::new(base), arguments, location }
+                        }
+                        Token::LBracket => {
+                            self.next_token(); // consume '?.'
+                            self.parse_member_access(base)?
+                        }
+                        // `?.` takes the place of the `.` before a name
+                        _ => self.parse_member_access(base)?,
+                    };
+                }
+                _ => break,
+            }
+        }
+        Ok(if optional { Expression::OptionalChain(Box::new(expr)) } else { expr })
+    }
+
+    /// Skips the type arguments of a call. `<` is otherwise a comparison,
+    /// so this only succeeds if they parse as type arguments followed by
+    /// the argument list or a template, and leaves the position unchanged
+    /// if not.
+    fn skip_call_type_arguments(&mut self) -> bool {
+        let snapshot = self.snapshot();
+        if self.skip_type_arguments().is_ok() && matches!(self.current_token, Token::LParen | Token::Template { .. }) {
+            return true;
+        }
+        self.restore(snapshot);
+        false
+    }
+
+    /// Parses `new callee(arguments)`. The callee extends only through
+    /// member accesses, so `new a.B()` constructs `a.B` while `new f()()`
+    /// calls the constructed object. The argument list is optional.
+    fn parse_new(&mut self) -> Result<Expression, String> {
+        let location = self.location;
+        self.next_token(); // consume 'new'
+        if self.current_token == Token::Dot {
+            self.next_token();
+            if self.current_token != Token::Identifier("target".to_string()) || !self.in_function {
+                return Err("new.target expression is not allowed here".to_string());
+            }
+            self.next_token();
+            return Ok(Expression::NewTarget);
+        }
+        let mut callee = match self.current_token {
+            Token::New => self.parse_new()?,
+            _ => self.parse_atom()?,
+        };
+        while matches!(self.current_token, Token::Dot | Token::LBracket) {
+            callee = self.parse_member_access(callee)?;
+        }
+        if self.current_token == Token::QuestionDot {
+            return Err("Invalid optional chain from new expression".to_string());
+        }
+        if self.current_token == Token::LAngle {
+            // Type arguments, as in `new Box<number>(1)`
+            self.skip_type_arguments()?;
+        }
+        let arguments = match self.current_token {
+            Token::LParen => self.allowing_in(Self::parse_arguments)?,
+            _ => Vec::new(),
+        };
+        Ok(Expression::New { callee: Box::new(callee), arguments, location })
+    }
+
+    /// Parses `.name`, `.#name` or `[index]` after `object`. The `.` may
+    /// also be the `?.` of an optional chain.
+    fn parse_member_access(&mut self, object: Expression) -> Result<Expression, String> {
+        let object = Box::new(object);
+        if self.next_token() == Token::LBracket {
+            let location = self.location;
+            let index = self.allowing_in(Self::parse_expression)?;
+            self.expect(Token::RBracket, "to close computed member access")?;
+            return Ok(Expression::Index { object, index: Box::new(index), location });
+        }
+        if let Token::PrivateName(name) = &self.current_token {
+            if matches!(*object, Expression::Super) {
+                return Err("Unexpected private field".to_string());
+            }
+            let name = name.clone();
+            self.next_token();
+            self.use_private_name(&name)?;
+            return Ok(Expression::PrivateMember { object, name });
+        }
+        let location = self.location;
+        let property = self.parse_property_name()?;
+        Ok(Expression::Member { object, property, location })
+    }
+
+    fn parse_arguments(&mut self) -> Result<Vec<Expression>, String> {
+        self.expect(Token::LParen, "to open argument list")?;
+        let mut arguments = Vec::new();
+        while self.current_token != Token::RParen {
+            arguments.push(self.parse_element()?);
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token(); // consume ','
+        }
+        self.expect(Token::RParen, "to close argument list")?;
+        Ok(arguments)
+    }
+
+    /// Parses an argument or array element, which may be spread.
+    fn parse_element(&mut self) -> Result<Expression, String> {
+        if self.current_token == Token::Ellipsis {
+            self.next_token(); // consume '...'
+            return Ok(Expression::Spread(Box::new(self.parse_assignment()?)));
+        }
+        self.parse_assignment()
+    }
+
+    fn parse_array_literal(&mut self) -> Result<Expression, String> {
+        self.expect(Token::LBracket, "to open array literal")?;
+        let mut elements = Vec::new();
+        while self.current_token != Token::RBracket {
+            if self.current_token == Token::Comma {
+                self.next_token(); // a hole
+                elements.push(None);
+                continue;
+            }
+            self.in_literal_element = true;
+            elements.push(Some(self.parse_element()?));
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token(); // consume ','
+        }
+        self.expect(Token::RBracket, "to close array literal")?;
+        Ok(Expression::Array(elements))
+    }
+
+    fn parse_object_literal(&mut self) -> Result<Expression, String> {
+        self.expect(Token::LBrace, "to open object literal")?;
+        let mut properties = Vec::new();
+        while self.current_token != Token::RBrace {
+            properties.push(self.parse_object_property()?);
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token(); // consume ','
+        }
+        self.expect(Token::RBrace, "to close object literal")?;
+        Ok(Expression::Object(properties))
+    }
+
+    fn parse_object_property(&mut self) -> Result<ObjectProperty, String> {
+        if self.current_token == Token::Ellipsis {
+            self.next_token(); // consume '...'
+            return Ok(ObjectProperty::Spread(self.parse_assignment()?));
+        }
+
+        let start = self.lexer.token_start();
+        // `get` and `set` start an accessor only when a property name follows
+        if let Token::Identifier(word) = &self.current_token {
+            if word == "get" || word == "set" {
+                let is_getter = word == "get";
+                let next = self.lexer.peek_token();
+                if !matches!(next, Token::Colon | Token::LParen | Token::Comma | Token::RBrace | Token::LAngle) {
+                    self.next_token(); // consume 'get' / 'set'
+                    let key = self.parse_object_key()?;
+                    let function = self.parse_function_rest(start, None, FunctionKind::Method, false, false)?;
+                    check_accessor(is_getter, &function)?;
+                    return Ok(if is_getter {
+                        ObjectProperty::Getter { key, function }
+                    } else {
+                        ObjectProperty::Setter { key, function }
+                    });
+                }
+            }
+        }
+
+        // `*key() {}`, a generator method
+        if self.current_token == Token::Star {
+            self.next_token(); // consume '*'
+            let key = self.parse_object_key()?;
+            let function = self.parse_function_rest(start, None, FunctionKind::Method, true, false)?;
+            return Ok(ObjectProperty::Property { key, value: Expression::Function(function) });
+        }
+
+        // `async key() {}` and `async *key() {}`, unless `async` is the
+        // property's own name
+        if self.current_token == Token::Identifier("async".to_string()) {
+            let next = self.lexer.peek_token();
+            if !matches!(next, Token::Colon | Token::LParen | Token::Comma | Token::RBrace | Token::LAngle | Token::Equal) {
+                self.next_token(); // consume 'async'
+                let is_generator = self.current_token == Token::Star;
+                if is_generator {
+                    self.next_token(); // consume '*'
+                }
+                let key = self.parse_object_key()?;
+                let function = self.parse_function_rest(start, None, FunctionKind::Method, is_generator, true)?;
+                return Ok(ObjectProperty::Property { key, value: Expression::Function(function) });
+            }
+        }
+
+        let shorthand = match &self.current_token {
+            Token::Identifier(name) => Some((name.clone(), self.location)),
+            _ => None,
+        };
+        let key = self.parse_object_key()?;
+        match self.current_token {
+            Token::Colon => {
+                self.next_token(); // consume ':'
+                self.in_literal_element = true;
+                let value = self.parse_assignment()?;
+                Ok(ObjectProperty::Property { key, value })
+            }
+            Token::LParen | Token::LAngle => {
+                let function = self.parse_function_rest(start, None, FunctionKind::Method, false, false)?;
+                Ok(ObjectProperty::Property { key, value: Expression::Function(function) })
+            }
+            _ => match shorthand {
+                Some((name, location)) => {
+                    if name == "arguments" {
+                        self.uses_arguments = true;
+                    }
+                    let target = Expression::Identifier { name, location };
+                    if self.current_token != Token::Equal {
+                        return Ok(ObjectProperty::Property { key, value: target });
+                    }
+                    // `{ a = 1 }`, only valid as a pattern; see `expression_to_pattern`
+                    self.next_token(); // consume '='
+                    self.cover_initializers += 1;
+                    let default = self.parse_assignment()?;
+                    let value = Expression::Assignment { operator: Token::Equal, target: Box::new(target), value: Box::new(default) };
+                    Ok(ObjectProperty::Property { key, value })
+                }
+                None => Err(format!("Expected ':' after property name, got {:?}", self.current_token)),
+            },
+        }
+    }
+
+    fn parse_object_key(&mut self) -> Result<PropertyName, String> {
+        let key = match &self.current_token {
+            Token::String(s) => PropertyName::Literal(s.to_string()),
+            Token::Number(n) => PropertyName::Literal(number_to_string(*n)),
+            Token::BigInt(n) => PropertyName::Literal(n.to_string()),
+            Token::LBracket => {
+                self.next_token(); // consume '['
+                let key = self.parse_assignment()?;
+                self.expect(Token::RBracket, "after computed property name")?;
+                return Ok(PropertyName::Computed(Box::new(key)));
+            }
+            Token::Invalid(message) => return Err(message.clone()),
+            _ => return Ok(PropertyName::Literal(self.parse_property_name()?)),
+        };
+        self.next_token();
+        Ok(key)
+    }
+
+    /// Property names after `.` may be any identifier, including reserved words.
+    fn parse_property_name(&mut self) -> Result<String, String> {
+        let name = match &self.current_token {
+            Token::Identifier(name) => name.clone(),
+            token => match keyword_text(token) {
+                Some(keyword) => keyword.to_string(),
+                None => return Err(format!("Expected property name, got {:?}", token)),
+            },
+        };
+        self.next_token();
+        Ok(name)
+    }
+
+    fn parse_atom(&mut self) -> Result<Expression, String> {
+        match &self.current_token {
+            Token::LParen => {
+                self.next_token(); // consume '('
+                let expr = self.allowing_in(Self::parse_expression)?;
+
+                if self.current_token != Token::RParen {
+                    return Err("Expected ')'".to_string());
+                }
+                self.next_token(); // consume ')'
+                Ok(expr)
+            }
+            Token::LBracket => self.allowing_in(Self::parse_array_literal),
+            Token::LBrace => self.allowing_in(Self::parse_object_literal),
+            _ => self.parse_primary()
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        let expr = match &self.current_token {
+            Token::Number(n) => Expression::Number(*n),
+            Token::BigInt(n) => Expression::BigInt(n.clone()),
+            Token::String(s) => Expression::String(s.clone()),
+            Token::True => Expression::Boolean(true),
+            Token::False => Expression::Boolean(false),
+            Token::Null => Expression::Null,
+            Token::This => Expression::This,
+            Token::Function => return Ok(Expression::Function(self.parse_function(false)?)),
+            Token::Identifier(name) if name == "async" && self.lexer.peek_token() == Token::Function => {
+                return Ok(Expression::Function(self.parse_function(true)?));
+            }
+            Token::Class => return Ok(Expression::Class(self.parse_class(false)?)),
+            Token::Super => {
+                let allowed = match self.lexer.peek_token() {
+                    Token::LParen => self.allow_super_call,
+                    Token::Dot | Token::LBracket => self.allow_super_property,
+                    _ => false,
+                };
+                if !allowed {
+                    return Err("'super' keyword unexpected here".to_string());
+                }
+                Expression::Super
+            }
+            Token::PrivateName(name) => {
+                if self.lexer.peek_token() != Token::In {
+                    return Err(format!("Unexpected private name #{}", name));
+                }
+                let name = name.clone();
+                self.use_private_name(&name)?;
+                Expression::PrivateName(name)
+            }
+            Token::Identifier(name) => {
+                if name == "arguments" {
+                    self.uses_arguments = true;
+                }
+                if name == "yield" && self.in_generator {
+                    return Err("Unexpected identifier 'yield'".to_string());
+                }
+                let operand_follows = matches!(
+                    self.lexer.peek_token(),
+                    Token::Identifier(_) | Token::Number(_) | Token::BigInt(_) | Token::String(_) | Token::This | Token::New
+                );
+                if name == "await" && operand_follows {
+                    return Err("await is only valid in async functions and the top level bodies of modules".to_string());
+                }
+                Expression::Identifier { name: name.clone(), location: self.location }
+            }
+            Token::Template { .. } => return Ok(Expression::Template(self.parse_template(false)?)),
+            Token::Invalid(message) => return Err(message.clone()),
+            token => {
+                return Err(format!("Expected expression, got {:?}", token));
+            }
+        };
+
+        self.next_token(); // consume the token
+        Ok(expr)
+    }
+
+    /// Parses a template literal from its first piece of text. Invalid
+    /// escapes are only allowed if it is `tagged`.
+    fn parse_template(&mut self, tagged: bool) -> Result<TemplateLiteral, String> {
+        let mut template = TemplateLiteral { cooked: Vec::new(), raw: Vec::new(), expressions: Vec::new() };
+        loop {
+            let (cooked, raw, tail) = match self.next_token() {
+                Token::Template { cooked, raw, tail } => (cooked, raw, tail),
+                Token::Invalid(message) => return Err(message),
+                token => return Err(format!("Expected template literal, got {:?}", token)),
+            };
+            template.cooked.push(match cooked {
+                Ok(text) => Some(text),
+                Err(_) if tagged => None,
+                Err(message) => return Err(message),
+            });
+            template.raw.push(raw);
+            if tail {
+                return Ok(template);
+            }
+            template.expressions.push(self.allowing_in(Self::parse_expression)?);
+            if self.current_token != Token::RBrace {
+                return Err(format!("Expected '}}' after template substitution, got {:?}", self.current_token));
+            }
+            self.current_token = self.lexer.read_template_continuation();
+        }
+    }
+
+    /// Skips over a type annotation. Types are parsed for syntax only and
+    /// have no effect on evaluation.
+    fn skip_type(&mut self) -> Result<(), String> {
+        with_stack(|| {
+            // Assertion signatures, as in `asserts x` or `asserts x is string`
+            if self.current_token == Token::Identifier("asserts".to_string())
+                && matches!(self.lexer.peek_token(), Token::Identifier(_) | Token::This)
+            {
+                self.next_token();
+            }
+            // Leading `|` / `&` are allowed in multi-line unions
+            if matches!(self.current_token, Token::Pipe | Token::Ampersand) {
+                self.next_token();
+            }
+            self.skip_primary_type()?;
+            while matches!(self.current_token, Token::Pipe | Token::Ampersand) {
+                self.next_token();
+                self.skip_primary_type()?;
+            }
+            // Type predicates, as in `(x: unknown): x is string`
+            if self.current_token == Token::Identifier("is".to_string()) && !self.newline_before {
+                self.next_token();
+                self.skip_type()?;
+            }
+            Ok(())
+        })
+    }
+
+    fn skip_primary_type(&mut self) -> Result<(), String> {
+        match &self.current_token {
+            // Type operators, as in `keyof typeof x`, `readonly T[]` and
+            // `abstract new () => T`
+            Token::Identifier(word)
+                if matches!(word.as_str(), "keyof" | "readonly" | "unique" | "infer" | "abstract")
+                    && starts_type(&self.lexer.peek_token()) =>
+            {
+                self.next_token();
+                return self.skip_primary_type();
+            }
+            Token::Identifier(_) => {
+                self.next_token();
+                if self.current_token == Token::LAngle {
+                    self.skip_type_arguments()?;
+                }
+            }
+            Token::Number(_) | Token::BigInt(_) | Token::String(_) | Token::Void | Token::Null | Token::This | Token::True | Token::False => {
+                self.next_token();
+            }
+            Token::LAngle => {
+                // A generic function type, as in `<T>(x: T) => T`
+                self.skip_type_parameters()?;
+                if self.current_token != Token::LParen {
+                    return Err(format!("Expected '(' in function type, got {:?}", self.current_token));
+                }
+                return self.skip_primary_type();
+            }
+            Token::New => {
+                // A constructor type, as in `new () => T`
+                self.next_token();
+                if !matches!(self.current_token, Token::LAngle | Token::LParen) {
+                    return Err(format!("Expected '(' in constructor type, got {:?}", self.current_token));
+                }
+                return self.skip_primary_type();
+            }
+            Token::Template { .. } => {
+                // Template literal types, as in `` `id-${number}` ``
+                loop {
+                    match self.next_token() {
+                        Token::Template { tail: true, .. } => break,
+                        Token::Invalid(message) => return Err(message),
+                        _ => {}
+                    }
+                    self.skip_type()?;
+                    if self.current_token != Token::RBrace {
+                        return Err(format!("Expected '}}' in template literal type, got {:?}", self.current_token));
+                    }
+                    self.current_token = self.lexer.read_template_continuation();
+                }
+            }
+            Token::Typeof => {
+                // A type query, as in `typeof value`
+                self.next_token();
+                self.skip_primary_type()?;
+            }
+            Token::Minus => {
+                // Negative numeric literal type
+                self.next_token();
+                self.skip_primary_type()?;
+            }
+            Token::LParen => {
+                // Either a parenthesized type or a function type's parameters
+                let mut depth = 0;
+                loop {
+                    match self.current_token {
+                        Token::LParen => depth += 1,
+                        Token::RParen => depth -= 1,
+                        Token::EOF => return Err("Unterminated parenthesized type".to_string()),
+                        _ => {}
+                    }
+                    self.next_token();
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                if self.current_token == Token::Arrow {
+                    self.next_token(); // consume '=>'
+                    self.skip_type()?;
+                }
+            }
+            Token::LBracket => {
+                // Tuple type, whose members may be named, optional or rest
+                // elements, as in `[name: string, age?: number, ...T[]]`
+                self.next_token();
+                while self.current_token != Token::RBracket {
+                    if self.current_token == Token::Ellipsis {
+                        self.next_token();
+                    }
+                    self.skip_tuple_member_name();
+                    self.skip_type()?;
+                    if self.current_token == Token::Question {
+                        self.next_token();
+                    }
+                    if self.current_token != Token::Comma {
+                        break;
+                    }
+                    self.next_token();
+                }
+                self.expect(Token::RBracket, "to close tuple type")?;
+            }
+            Token::LBrace => {
+                // Object type literal: skip to the matching brace
+                let mut depth = 0;
+                loop {
+                    match self.current_token {
+                        Token::LBrace => depth += 1,
+                        Token::RBrace => depth -= 1,
+                        Token::EOF => return Err("Unterminated object type".to_string()),
+                        _ => {}
+                    }
+                    self.next_token();
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            token => return Err(format!("Expected type, got {:?}", token)),
+        }
+
+        // Array type suffixes and indexed access: `T[]`, `T[][]`, `T["key"]`
+        while self.current_token == Token::LBracket && !self.newline_before {
+            self.next_token();
+            if self.current_token != Token::RBracket {
+                self.skip_type()?;
+            }
+            self.expect(Token::RBracket, "in array type")?;
+        }
+        Ok(())
+    }
+
+    /// Skips the `name:` or `name?:` labelling a tuple member, if present.
+    fn skip_tuple_member_name(&mut self) {
+        if !matches!(self.current_token, Token::Identifier(_)) {
+            return;
+        }
+        let snapshot = self.snapshot();
+        self.next_token();
+        if self.current_token == Token::Question {
+            self.next_token();
+        }
+        if self.current_token == Token::Colon {
+            self.next_token();
+        } else {
+            self.restore(snapshot);
+        }
+    }
+
+    /// Skips `<T, U extends V = W>` after a function or class name.
+    fn skip_type_parameters(&mut self) -> Result<(), String> {
+        self.next_token(); // consume '<'
+        loop {
+            match &self.current_token {
+                Token::Identifier(_) => self.next_token(),
+                token => return Err(format!("Expected type parameter name, got {:?}", token)),
+            };
+            if self.current_token == Token::Extends {
+                self.next_token();
+                self.skip_type()?;
+            }
+            if self.current_token == Token::Equal {
+                self.next_token();
+                self.skip_type()?;
+            }
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token();
+            // A trailing comma, as in `<T,>(x: T) => x`
+            if self.current_token == Token::RAngle {
+                break;
+            }
+        }
+        self.expect_closing_angle()
+    }
+
+    fn skip_type_arguments(&mut self) -> Result<(), String> {
+        self.next_token(); // consume '<'
+        loop {
+            self.skip_type()?;
+            if self.current_token != Token::Comma {
+                break;
+            }
+            self.next_token();
+        }
+        self.expect_closing_angle()
+    }
+
+    /// Consumes a single `>` closing a type argument list. The lexer greedily
+    /// produces `>>`, `>>>`, `>=` and `>>=` tokens, so nested generics such
+    /// as `Array<Array<number>>` need those split back apart.
+    fn expect_closing_angle(&mut self) -> Result<(), String> {
+        match self.current_token {
+            Token::RAngle => {
+                self.next_token();
+            }
+            Token::ShiftRight => self.current_token = Token::RAngle,
+            Token::UnsignedShiftRight => self.current_token = Token::ShiftRight,
+            // As in `let m: Map<K, Set<V>>= new Map()`
+            Token::GreaterEqual => self.current_token = Token::Equal,
+            Token::ShiftRightEqual => self.current_token = Token::GreaterEqual,
+            Token::UnsignedShiftRightEqual => self.current_token = Token::ShiftRightEqual,
+            _ => return Err(format!("Expected '>' to close type arguments, got {:?}", self.current_token)),
+        }
+        Ok(())
+    }
+
+    fn is_type_assertion(&self) -> bool {
+        matches!(&self.current_token, Token::Identifier(word) if word == "as" || word == "satisfies") && !self.newline_before
+    }
+
+    fn is_operator(&self, token: &Token) -> bool {
+        Self::get_operator_precedence(token) > 0
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.current_token == Token::EOF
+    }
+}
+
+/// Reinterprets an array or object literal on the left of `=` as the
+/// pattern it is written like, as in `[a, b] = [b, a]`.
+/// Runs `f`, a step of the recursive descent, with at least
+/// `STACK_RED_ZONE` of native stack left, so that nesting is only limited by
+/// memory, as when the code runs.
+fn with_stack<T>(f: impl FnOnce() -> T) -> T {
+    stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, f)
+}
+
+fn expression_to_pattern(expr: Expression) -> Result<Pattern, String> {
+    const INVALID: &str = "Invalid destructuring assignment target";
+    match expr {
+        Expression::Array(elements) => {
+            let mut pattern_elements = Vec::new();
+            let mut rest = None;
+            let count = elements.len();
+            for (index, element) in elements.into_iter().enumerate() {
+                match element {
+                    None => pattern_elements.push(None),
+                    Some(Expression::Spread(target)) => {
+                        if index + 1 != count {
+                            return Err("Rest element must be last element".to_string());
+                        }
+                        rest = Some(Box::new(expression_to_pattern(*target)?));
+                    }
+                    Some(element) => {
+                        let (target, default) = pattern_with_default(element)?;
+                        pattern_elements.push(Some(PatternElement { target, default }));
+                    }
+                }
+            }
+            Ok(Pattern::Array { elements: pattern_elements, rest })
+        }
+        Expression::Object(properties) => {
+            let mut pattern_properties = Vec::new();
+            let mut rest = None;
+            let count = properties.len();
+            for (index, property) in properties.into_iter().enumerate() {
+                match property {
+                    ObjectProperty::Property { value: Expression::Function(_), .. } => return Err(INVALID.to_string()),
+                    ObjectProperty::Property { key, value } => {
+                        let (target, default) = pattern_with_default(value)?;
+                        pattern_properties.push(PatternProperty { key, target, default });
+                    }
+                    ObjectProperty::Spread(target) if index + 1 == count && is_simple_assignment_target(&target) => {
+                        rest = Some(Box::new(simple_pattern(target)));
+                    }
+                    ObjectProperty::Spread(_) => return Err("Rest element must be last element".to_string()),
+                    ObjectProperty::Getter { .. } | ObjectProperty::Setter { .. } => return Err(INVALID.to_string()),
+                }
+            }
+            Ok(Pattern::Object { properties: pattern_properties, rest })
+        }
+        target if is_simple_assignment_target(&target) => Ok(simple_pattern(target)),
+        _ => Err(INVALID.to_string()),
+    }
+}
+
+/// Splits an element of an assignment pattern into its target and
+/// default, as in `[a = 1] = []`.
+fn pattern_with_default(element: Expression) -> Result<(Pattern, Option<Expression>), String> {
+    match element {
+        Expression::Assignment { operator: Token::Equal, target, value } => Ok((expression_to_pattern(*target)?, Some(*value))),
+        Expression::DestructuringAssignment { target, value } => Ok((target, Some(*value))),
+        element => Ok((expression_to_pattern(element)?, None)),
+    }
+}
+
+/// The pattern for a simple assignment target: a name or member expression.
+fn simple_pattern(target: Expression) -> Pattern {
+    match target {
+        Expression::Identifier { name, .. } => Pattern::Identifier(name),
+        target => Pattern::Expression(Box::new(target)),
+    }
+}
+
+/// Whether `expr` can be assigned to by `=`, compound assignment or `++`.
+fn is_simple_assignment_target(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Identifier { .. } | Expression::Member { .. } | Expression::Index { .. } | Expression::PrivateMember { .. }
+    )
+}
+
+/// Whether `token`, following a word at the start of a class member, makes
+/// that word the member's name rather than a modifier or `get` / `set`.
+fn is_member_name_end(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::LParen | Token::Equal | Token::Semicolon | Token::Colon | Token::Question | Token::Bang | Token::RBrace | Token::LAngle
+    )
+}
+
+/// Whether `token`, following a type operator such as `keyof`, starts the
+/// type it applies to rather than ending a type named like the operator.
+fn starts_type(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Identifier(_)
+            | Token::Typeof
+            | Token::New
+            | Token::LBracket
+            | Token::LParen
+            | Token::LBrace
+            | Token::LAngle
+            | Token::String(_)
+            | Token::Number(_)
+            | Token::Template { .. }
+    )
+}
+
+/// The constructor of a class without one: `constructor() {}`, or
+/// `constructor(...args) { super(...args); }` in a derived class.
+fn default_constructor(name: Option<String>, derived: bool) -> Rc<FunctionDefinition> {
+    let (kind, params, body) = if derived {
+        let args = Parameter { target: Pattern::Identifier("args".to_string()), default: None, rest: true };
+        let call = Expression::Call {
+            callee: Box::new(Expression::Super),
+            arguments: vec![Expression::Spread(Box::new(Expression::Identifier { name: "args".to_string(), location: Location::default() }))],
+            location: Location::default(),
+        };
+        (FunctionKind::DerivedConstructor, vec![args], vec![Statement::Expression(call)])
+    } else {
+        (FunctionKind::BaseConstructor, Vec::new(), Vec::new())
+    };
+    Rc::new(FunctionDefinition {
+        name,
+        params,
+        body,
+        uses_arguments: false,
+        kind,
+        is_generator: false,
+        is_async: false,
+        parameter_properties: Vec::new(),
+        source_text: String::new(),
+    })
+}
+
+/// Checks the parameter count of a getter or setter.
+fn check_accessor(is_getter: bool, function: &FunctionDefinition) -> Result<(), String> {
+    if is_getter && !function.params.is_empty() {
+        return Err("Getter must not have any formal parameters.".to_string());
+    }
+    if !is_getter && (function.params.len() != 1 || function.params[0].rest) {
+        return Err("Setter must have exactly one formal parameter.".to_string());
+    }
+    Ok(())
+}
+
+/// The source text of a keyword token, for contexts such as property names
+/// where reserved words are allowed.
+fn keyword_text(token: &Token) -> Option<&'static str> {
+    Some(match token {
+        Token::Let => "let",
+        Token::Const => "const",
+        Token::Var => "var",
+        Token::Function => "function",
+        Token::Return => "return",
+        Token::If => "if",
+        Token::Else => "else",
+        Token::While => "while",
+        Token::Do => "do",
+        Token::For => "for",
+        Token::Switch => "switch",
+        Token::Case => "case",
+        Token::Default => "default",
+        Token::Break => "break",
+        Token::Continue => "continue",
+        Token::This => "this",
+        Token::True => "true",
+        Token::False => "false",
+        Token::Null => "null",
+        Token::Delete => "delete",
+        Token::Typeof => "typeof",
+        Token::Void => "void",
+        Token::In => "in",
+        Token::Instanceof => "instanceof",
+        Token::Class => "class",
+        Token::Extends => "extends",
+        Token::Super => "super",
+        Token::New => "new",
+        Token::Throw => "throw",
+        Token::Try => "try",
+        Token::Catch => "catch",
+        Token::Finally => "finally",
+        _ => return None,
+    })
+}
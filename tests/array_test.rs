@@ -0,0 +1,126 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_array_literals() {
+    let cases = vec![
+        ("[1, 2, 3].length", Value::Number(3.0)),
+        ("[1, , 3].length", Value::Number(3.0)),
+        ("[1, 2, ].length", Value::Number(2.0)),
+        ("const a = [1, 2]; [0, ...a, ...\"ab\"].join()", string("0,1,2,a,b")),
+        ("const a = [10, 20, 30]; a[1] + a[a.length - 1]", Value::Number(50.0)),
+        ("const a = []; a[3] = 1; a.length", Value::Number(4.0)),
+        ("const a = [1, 2, 3]; a.length = 1; a.join()", string("1")),
+        ("const a = [1, 2]; a[0] += 5; a[1]++; a.join()", string("6,3")),
+        ("[1, [2, 3]] + \"\"", string("1,2,3")),
+        ("const a = [1, 2]; a == \"1,2\"", Value::Boolean(true)),
+        ("function sum(a, b, c) { return a + b + c; } sum(...[1, 2, 3])", Value::Number(6.0)),
+        ("const a = [1]; a.push(a); a.join()", string("1,")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_array_methods() {
+    let cases = vec![
+        ("[1, 2, 3].map((x) => x * 2).join()", "2,4,6"),
+        ("[1, 2, 3, 4].filter((x) => x % 2 === 0).join()", "2,4"),
+        ("[1, 2, 3].reduce((acc, x) => acc + x, 10) + \"\"", "16"),
+        ("[\"a\", \"b\", \"c\"].reduceRight((acc, x) => acc + x)", "cba"),
+        ("[3, 1, 10, 2].sort().join()", "1,10,2,3"),
+        ("[3, 1, 10, 2].sort((a, b) => a - b).join()", "1,2,3,10"),
+        ("const a = [1, 2, 3, 4, 5]; const r = a.splice(1, 2, \"x\"); a.join() + \"|\" + r.join()", "1,x,4,5|2,3"),
+        ("[1, 2, 3, 4, 5].slice(-2).join()", "4,5"),
+        ("[1, [2, [3, [4]]]].flat(10).join()", "1,2,3,4"),
+        ("[1, 2].flatMap((x) => [x, x * 10]).join()", "1,10,2,20"),
+        ("const a = [1, 2]; a.unshift(0); a.push(3, 4); a.shift(); a.pop(); a.join()", "1,2,3"),
+        ("[1, 2, 3].reverse().join(\"-\")", "3-2-1"),
+        ("[1, 2, 3].indexOf(2) + \",\" + [1, 2, 3].lastIndexOf(4)", "1,-1"),
+        ("const nan = 0 / 0; [nan].includes(nan) + \",\" + [nan].indexOf(nan)", "true,-1"),
+        ("[5, 12, 8].find((x) => x > 6) + \",\" + [5, 12, 8].findLastIndex((x) => x > 6)", "12,2"),
+        ("[1, 2, 3].every((x) => x > 0) + \",\" + [1, 2, 3].some((x) => x > 2)", "true,true"),
+        ("const a = [1, 2, 3]; a.at(-1) + \"|\" + a.with(0, 9).join() + \"|\" + a.join()", "3|9,2,3|1,2,3"),
+        ("const a = [3, 1, 2]; a.toSorted().join() + \"|\" + a.toReversed().join() + \"|\" + a.join()", "1,2,3|2,1,3|3,1,2"),
+        ("[1, 2, 3, 4].toSpliced(1, 2).join()", "1,4"),
+        ("Array(3).fill(0).join()", "0,0,0"),
+        ("[1, 2, 3, 4, 5].copyWithin(0, 3).join()", "4,5,3,4,5"),
+        ("Array.of(7, 8).concat([9], 10).join()", "7,8,9,10"),
+        ("Array.from(\"abc\", (c, i) => c + i).join()", "a0,b1,c2"),
+        ("Array.from({ length: 2, 1: \"b\" }, (x, i) => i + String(x)).join()", "0undefined,1b"),
+        ("let log = \"\"; const it = { [Symbol.iterator]() { let n = 0; return { next() { log += \"n\"; return { value: n++, done: n > 3 }; } }; } }; Array.from(it, (x) => { log += \"m\"; return x; }); log", "nmnmnmn"),
+        ("let closed = 0; const it = { [Symbol.iterator]() { return { next: () => ({ value: 1, done: false }), return() { closed++; return {}; } }; } }; try { Array.from(it, () => { throw 1; }); } catch {} String(closed)", "1"),
+        ("Array.prototype.join.call({ length: -5 })", ""),
+        ("Array.isArray([]) + \",\" + Array.isArray(\"[]\")", "true,false"),
+        ("let out = \"\"; [1, , 3].forEach((x, i) => { out = out + i; }); out", "02"),
+        ("let nothing; [null, 1, nothing].join(\"-\")", "-1-"),
+        ("const a: any = []; a.length = 4294967295; let name; try { a.push(1); } catch (e) { name = e.name; } name + \",\" + a[4294967295]", "RangeError,1"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(string(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_sort_is_stable() {
+    let source = "
+        const people = [[\"b\", 1], [\"a\", 2], [\"c\", 1], [\"d\", 2]];
+        people.sort((x, y) => x[1] - y[1]).map((p) => p[0]).join()
+    ";
+    assert_eq!(run(source), Ok(string("b,c,a,d")));
+}
+
+#[test]
+fn test_array_errors() {
+    let cases = vec![
+        ("Array(-1)", "RangeError: Invalid array length"),
+        ("const a = []; a.length = 1.5", "RangeError: Invalid array length"),
+        ("[].reduce((a, b) => a + b)", "TypeError: Reduce of empty array with no initial value"),
+        ("[1].map(5)", "TypeError: 5 is not a function"),
+        ("let nothing; nothing[0] = 1", "TypeError: Cannot set properties of undefined (setting '0')"),
+        ("[1, 2].with(5, 0)", "RangeError: Invalid index"),
+        ("Array.from({ length: 2 ** 32 })", "RangeError: Invalid array length"),
+        ("Array.prototype.indexOf.call({ length: Infinity }, 1)", "RangeError: Invalid array length"),
+        ("Array.from([1], 1)", "TypeError: 1 is not a function"),
+        ("const a: any = []; a.length = 4294967295; a.push(1)", "RangeError: Invalid array length"),
+        ("const a: any = []; a.length = 4294967295; a.unshift(1)", "RangeError: Invalid array length"),
+        ("const a: any = []; a.length = 4294967294; a.splice(0, 0, 1, 2)", "RangeError: Invalid array length"),
+        ("const a: any = []; a.length = 4294967295; a.concat([1])", "RangeError: Invalid array length"),
+        ("new Array(2 ** 32 - 1).join('ab')", "RangeError: Invalid string length"),
+        ("const a: any = [1]; a.constructor = 1; a.map(x => x)", "TypeError: object.constructor[Symbol.species] is not a constructor"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_subclass_results() {
+    let cases = vec![
+        ("class E extends Array {} E.from([1]) instanceof E", Value::Boolean(true)),
+        ("class E extends Array {} const e = E.from({ length: 2, 0: 'a' }); e instanceof E && e.length", Value::Number(2.0)),
+        ("class E extends Array {} const e = E.of(1, 2); e instanceof E && e.join()", string("1,2")),
+        ("Array.from.call(Object, [1, 2]).length", Value::Number(2.0)),
+        ("Array.of.call(undefined, 7) instanceof Array", Value::Boolean(true)),
+        ("class E extends Array {} new E().map(x => x) instanceof E", Value::Boolean(true)),
+        (
+            r#"class E extends Array {} const e = E.of(1, [2], 3);
+            [e.map(x => x), e.filter(x => x), e.slice(1), e.splice(0, 1), e.concat([4]), e.flat(), e.flatMap(x => x)]
+                .map(r => r instanceof E && r.length).join()"#,
+            string("3,3,2,1,3,2,2"),
+        ),
+        ("class E extends Array { static get [Symbol.species]() { return Array; } } new E(1, 2).map(x => x) instanceof E", Value::Boolean(false)),
+        ("const a: any = [1, 2]; a.constructor = { [Symbol.species]: null }; a.map(x => x * 2).join()", string("2,4")),
+        ("[Array[Symbol.species] === Array, [1].map(x => x).constructor === Array].join()", string("true,true")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
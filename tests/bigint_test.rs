@@ -0,0 +1,167 @@
+mod common;
+
+use common::run;
+use ts_interpreter_rs::interpreter::Value;
+
+/// Runs `source`, converting its result to a string, so that BigInt
+/// results compare easily.
+fn run_bigint(source: &str) -> Result<String, String> {
+    run(source).map(|value| value.to_js_string())
+}
+
+#[test]
+fn test_bigint_literals() {
+    let cases = vec![
+        ("10n", "10"),
+        ("0x1fn", "31"),
+        ("0o17n", "15"),
+        ("0b101n", "5"),
+        ("1_000_000n", "1000000"),
+        ("123456789012345678901234567890n", "123456789012345678901234567890"),
+        ("-5n", "-5"),
+        ("({ 1n: 'a' })[1]", "a"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_bigint(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_bigint_arithmetic() {
+    let cases = vec![
+        ("2n ** 100n", "1267650600228229401496703205376"),
+        ("9007199254740993n + 1n", "9007199254740994"),
+        ("10n - 15n", "-5"),
+        ("123456789n * 987654321n", "121932631112635269"),
+        ("7n / 2n", "3"),
+        ("-7n / 2n", "-3"),
+        ("7n % 3n", "1"),
+        ("-7n % 3n", "-1"),
+        ("(-2n) ** 3n", "-8"),
+        ("0n ** 0n", "1"),
+        ("-(3n)", "-3"),
+        ("~5n", "-6"),
+        ("let x = 1n; x++; ++x; x", "3"),
+        ("let x = 1n; x += 2n; x *= 3n; x", "9"),
+        ("let x = 5n; [x--, x].join()", "5,4"),
+        ("6n & 3n", "2"),
+        ("6n | 3n", "7"),
+        ("6n ^ 3n", "5"),
+        ("-6n & 0xffn", "250"),
+        ("1n << 70n", "1180591620717411303424"),
+        ("-9n >> 1n", "-5"),
+        ("8n >> -2n", "32"),
+        ("1n >> 1000n", "0"),
+        ("-1n >> 1000n", "-1"),
+        ("1n + 'a'", "1a"),
+        ("'x' + 2n", "x2"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_bigint(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_bigint_comparison() {
+    let cases = vec![
+        ("1n === 1n", true),
+        ("1n === 1", false),
+        ("1n == 1", true),
+        ("1n == 1.5", false),
+        ("2n == '2'", true),
+        ("2n == '2.0'", false),
+        ("1n == true", true),
+        ("0n == ''", true),
+        ("1n < 2", true),
+        ("2n > 1.5", true),
+        ("2n < 2.5", true),
+        ("9007199254740993n > 9007199254740992", true),
+        ("1n < NaN || 1n >= NaN", false),
+        ("1n < Infinity && -1n > -Infinity", true),
+        ("'10' > 9n", true),
+        ("1n < 'x' || 1n >= 'x'", false),
+        ("Object.is(0n, -0n)", true),
+        ("new Set([1n, 1n, 1]).size === 2", true),
+        ("new Map([[10n ** 20n, 'big']]).get(100000000000000000000n) === 'big'", true),
+        ("!0n && !!1n", true),
+        ("[3n, 1n, 2n].sort().join() === '1,2,3'", true),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::Boolean(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_bigint_builtins() {
+    let cases = vec![
+        ("BigInt(42)", "42"),
+        ("BigInt(-0)", "0"),
+        ("BigInt(1e21)", "1000000000000000000000"),
+        ("BigInt('  123  ')", "123"),
+        ("BigInt('-9007199254740993')", "-9007199254740993"),
+        ("BigInt('0x10')", "16"),
+        ("BigInt('')", "0"),
+        ("BigInt(true)", "1"),
+        ("BigInt({ valueOf() { return 7; } })", "7"),
+        ("BigInt.asIntN(8, 255n)", "-1"),
+        ("BigInt.asIntN(8, 127n)", "127"),
+        ("BigInt.asIntN(64, 2n ** 63n)", "-9223372036854775808"),
+        ("BigInt.asIntN(0, 5n)", "0"),
+        ("BigInt.asUintN(8, -1n)", "255"),
+        ("BigInt.asUintN(64, -1n)", "18446744073709551615"),
+        ("BigInt.asUintN(8, 257n)", "1"),
+        ("(255n).toString(16)", "ff"),
+        ("(-255n).toString(2)", "-11111111"),
+        ("(10n ** 30n).toString(36)", "2oy99wnkl1c76diocq9s"),
+        ("(1234567n).toLocaleString()", "1,234,567"),
+        ("(5n).valueOf() === 5n", "true"),
+        ("Number(2n ** 64n)", "18446744073709552000"),
+        ("Number(-7n)", "-7"),
+        ("parseInt(15n)", "15"),
+        ("Object.prototype.toString.call(1n)", "[object BigInt]"),
+        ("[BigInt.length, BigInt.name, BigInt.asIntN.length].join()", "1,BigInt,2"),
+        ("Object.getPrototypeOf(1n) === BigInt.prototype", "true"),
+        ("JSON.stringify({ a: 1n }, (k, v) => v === 1n ? v.toString() : v)", "{\"a\":\"1\"}"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_bigint(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_bigint_errors() {
+    let cases = vec![
+        ("1n + 1", "TypeError: Cannot mix BigInt and other types, use explicit conversions"),
+        ("1 * 2n", "TypeError: Cannot mix BigInt and other types, use explicit conversions"),
+        ("let x = 1n; x += 1", "TypeError: Cannot mix BigInt and other types, use explicit conversions"),
+        ("1n >>> 0n", "TypeError: BigInts have no unsigned right shift, use >> instead"),
+        ("+1n", "TypeError: Cannot convert a BigInt value to a number"),
+        ("Math.max(1n)", "TypeError: Cannot convert a BigInt value to a number"),
+        ("1n / 0n", "RangeError: Division by zero"),
+        ("1n % 0n", "RangeError: Division by zero"),
+        ("2n ** -1n", "RangeError: Exponent must be non-negative"),
+        ("2n ** 10000000000n", "RangeError: Maximum BigInt size exceeded"),
+        ("1n << 10000000000n", "RangeError: Maximum BigInt size exceeded"),
+        ("new BigInt(1)", "TypeError: BigInt is not a constructor"),
+        ("BigInt(1.5)", "RangeError: The number 1.5 cannot be converted to a BigInt because it is not an integer"),
+        ("BigInt(NaN)", "RangeError: The number NaN cannot be converted to a BigInt because it is not an integer"),
+        ("BigInt('1.5')", "SyntaxError: Cannot convert 1.5 to a BigInt"),
+        ("BigInt('-0x10')", "SyntaxError: Cannot convert -0x10 to a BigInt"),
+        ("BigInt()", "TypeError: Cannot convert undefined to a BigInt"),
+        ("BigInt(null)", "TypeError: Cannot convert null to a BigInt"),
+        ("BigInt.asIntN(8, 5)", "TypeError: Cannot convert 5 to a BigInt"),
+        ("BigInt.asUintN(-1, 5n)", "RangeError: Invalid value: not (convertible to) a safe integer"),
+        ("(1n).toString(1)", "RangeError: toString() radix must be between 2 and 36"),
+        ("BigInt.prototype.toString.call(1)", "TypeError: BigInt.prototype.toString requires that 'this' be a BigInt"),
+        ("JSON.stringify({ a: 1n })", "TypeError: Do not know how to serialize a BigInt"),
+        ("1.5n", "Invalid or unexpected token"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
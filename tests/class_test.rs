@@ -0,0 +1,151 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_class_basics() {
+    let cases = vec![
+        ("class Point { constructor(x, y) { this.x = x; this.y = y; } sum() { return this.x + this.y; } } new Point(1, 2).sum()", Value::Number(3.0)),
+        ("class A {} const a = new A(); a instanceof A && Object.getPrototypeOf(a) === A.prototype", Value::Boolean(true)),
+        ("class A { m() {} } Object.keys(A.prototype).length + A.prototype.propertyIsEnumerable(\"m\")", Value::Number(0.0)),
+        ("class A {} A.prototype.constructor === A && Object.getPrototypeOf(A) === Function.prototype", Value::Boolean(true)),
+        ("const A = class {}; A.name", string("A")),
+        ("const A = class B { who() { return B.name; } }; new A().who()", string("B")),
+        ("class A { constructor(a, b) {} } A.length", Value::Number(2.0)),
+        ("class A { get v() { return 1; } set v(x) { this.w = x; } } const a = new A(); a.v = 5; a.v + a.w", Value::Number(6.0)),
+        ("class A { static create() { return new this(); } } A.create() instanceof A", Value::Boolean(true)),
+        ("class A { [\"comp\" + \"uted\"]() { return 1; } } new A().computed()", Value::Number(1.0)),
+        ("class A { constructor() { return { custom: true }; } } new A().custom", Value::Boolean(true)),
+        ("function F(x) { this.x = x; } new F(4).x", Value::Number(4.0)),
+        ("function F() {} new F instanceof F", Value::Boolean(true)),
+        ("new Array(3).length + new Array(1, 2).length", Value::Number(5.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_inheritance() {
+    let cases = vec![
+        (
+            "class Animal { constructor(name) { this.name = name; } speak() { return this.name + \" makes a sound\"; } }
+             class Dog extends Animal { speak() { return super.speak() + \" (woof)\"; } }
+             new Dog(\"Rex\").speak()",
+            string("Rex makes a sound (woof)"),
+        ),
+        (
+            "class A { constructor(x) { this.x = x; } }
+             class B extends A { constructor(x, y) { super(x); this.y = y; } }
+             const b = new B(1, 2); b.x + b.y",
+            Value::Number(3.0),
+        ),
+        ("class A {} class B extends A {} const b = new B(); b instanceof A && b instanceof B", Value::Boolean(true)),
+        ("class A { static make() { return \"A\"; } } class B extends A {} B.make()", string("A")),
+        ("class A { static who() { return \"A\"; } } class B extends A { static who() { return super.who() + \"B\"; } } B.who()", string("AB")),
+        ("class A { get v() { return 1; } } class B extends A { get v() { return super.v + 1; } } new B().v", Value::Number(2.0)),
+        ("class A { constructor() { this.kind = new.target === B; } } class B extends A {} new B().kind", Value::Boolean(true)),
+        ("class L extends Array {} const l = new L(); l.push(1, 2); l.length + (l instanceof L)", Value::Number(3.0)),
+        ("class O extends Object { constructor() { super(); this.a = 1; } } new O() instanceof O", Value::Boolean(true)),
+        ("const base = { hi() { return \"hi\"; } }; const o = { __proto__: base, hi() { return super.hi() + \"!\"; } }; o.hi()", string("hi!")),
+        ("class A { constructor() { this.v = 1; } } class B extends A { constructor() { const f = () => super(); f(); } } new B().v", Value::Number(1.0)),
+        ("class A {} class B extends A { constructor() { return { other: 1 }; } } new B().other", Value::Number(1.0)),
+        ("function F() { this.f = 1; } class C extends F {} new C().f", Value::Number(1.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_fields_and_private_members() {
+    let cases = vec![
+        ("class A { x = 1; y = this.x + 1; } const a = new A(); a.x + a.y", Value::Number(3.0)),
+        ("class A { x; } \"x\" in new A()", Value::Boolean(true)),
+        ("class A { static count = 2; static double = A.count * 2; } A.double", Value::Number(4.0)),
+        ("class A { #secret = 42; reveal() { return this.#secret; } } new A().reveal()", Value::Number(42.0)),
+        ("class C { #n = 0; inc() { this.#n++; this.#n += 2; return this.#n; } } new C().inc()", Value::Number(3.0)),
+        ("class A { #m() { return 5; } call() { return this.#m(); } } new A().call()", Value::Number(5.0)),
+        ("class A { #v = 1; get #double() { return this.#v * 2; } read() { return this.#double; } } new A().read()", Value::Number(2.0)),
+        ("class A { static #count = 0; static next() { return ++A.#count; } } A.next(); A.next()", Value::Number(2.0)),
+        ("class A { #x; static has(o) { return #x in o; } } A.has(new A()) + \",\" + A.has({})", string("true,false")),
+        ("class A { #x = 1; } Object.keys(new A()).length", Value::Number(0.0)),
+        ("class A { f = () => this; } const a = new A(); a.f() === a", Value::Boolean(true)),
+        ("class A { handler = function () {}; } new A().handler.name", string("handler")),
+        ("class A { constructor() { this.a = 1; } } class B extends A { b = this.a + 1; } new B().b", Value::Number(2.0)),
+        ("class A { set x(v) { this.seen = true; } } class B extends A { x = 1; } new B().seen", Value::Undefined),
+        ("class A { static a = 1; static { this.b = this.a + 1; } static c = A.b + 1; } [A.a, A.b, A.c].join()", string("1,2,3")),
+        ("class A { static #n = 2; static { var v = A.#n * 2; this.v = v; } } A.v + typeof v", string("4undefined")),
+        ("class A { static m() { return 1; } } class B extends A { static { this.r = super.m() + 1; } } B.r", Value::Number(2.0)),
+        ("class A { static static() { return 1; } static = 2; } A.static() + new A().static", Value::Number(3.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_typescript_class_syntax() {
+    let cases = vec![
+        ("class P { constructor(public name: string, private age: number) {} } const p = new P(\"a\", 3); p.name + p.age", string("a3")),
+        ("class P { constructor(readonly id: number) {} } Object.keys(new P(1)).join()", string("id")),
+        ("class A { constructor(public x: number) {} } class B extends A { constructor(x: number, public y: number) { super(x); } } const b = new B(1, 2); b.x + b.y", Value::Number(3.0)),
+        ("class A { private x: number = 1; protected y?: number; public z!: string; } new A().x", Value::Number(1.0)),
+        ("class A { static readonly max: number = 10; } A.max", Value::Number(10.0)),
+        ("abstract class Shape { abstract area(): number; describe() { return \"area \" + this.area(); } } class Sq extends Shape { area() { return 4; } } new Sq().describe()", string("area 4")),
+        ("class Box<T> implements Iterable<T> { constructor(public value: T) {} get(): T { return this.value; } } new Box<number>(5).get()", Value::Number(5.0)),
+        ("interface Named { name: string; } class N implements Named { name = \"n\"; } new N().name", string("n")),
+        ("class A { m(): void; m(x?: number): void {} } new A().m.length", Value::Number(1.0)),
+        ("class A { declare d: number; } \"d\" in new A()", Value::Boolean(false)),
+        ("class A { m<T>(x: T): T { return x; } } const a = new A(); a.m<number>(1) + a?.m<number>(2)", Value::Number(3.0)),
+        ("function pair<A, B>(a: A, b: B) { return [a, b]; } pair<string, Array<number>>(\"a\", [1]).length", Value::Number(2.0)),
+        ("const a = 1, b = 2, c = 3; (a < b) === (c > b)", Value::Boolean(true)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_class_errors() {
+    let cases = vec![
+        ("class A {} A()", "TypeError: Class constructor A cannot be invoked without 'new'"),
+        ("new A(); class A {}", "ReferenceError: Cannot access 'A' before initialization"),
+        ("const f = () => {}; new f()", "TypeError: f is not a constructor"),
+        ("const o = { m() {} }; new o.m()", "TypeError: o.m is not a constructor"),
+        ("class A extends 1 {}", "TypeError: Class extends value 1 is not a constructor or null"),
+        ("class B extends A {} class A {}", "ReferenceError: Cannot access 'A' before initialization"),
+        (
+            "class A {} class B extends A { constructor() { this.x = 1; } } new B()",
+            "ReferenceError: Must call super constructor in derived class before accessing 'this' or returning from derived constructor",
+        ),
+        (
+            "class A {} class B extends A { constructor() {} } new B()",
+            "ReferenceError: Must call super constructor in derived class before accessing 'this' or returning from derived constructor",
+        ),
+        ("class A {} class B extends A { constructor() { super(); super(); } } new B()", "ReferenceError: Super constructor may only be called once"),
+        ("class A {} class B extends A { constructor() { super(); return 1; } } new B()", "TypeError: Derived constructors may only return object or undefined"),
+        ("abstract class S {} new S()", "TypeError: Cannot create an instance of an abstract class"),
+        ("class A { #x = 1; static read(o) { return o.#x; } } A.read({})", "TypeError: Cannot read private member #x from an object whose class did not declare it"),
+        ("class A { #x = 1; static write(o) { o.#x = 2; } } A.write({})", "TypeError: Cannot write private member #x to an object whose class did not declare it"),
+        ("class A { #m() {} constructor() { this.#m = 1; } } new A()", "TypeError: Private method is not writable"),
+        ("class A { get #g() { return 1; } constructor() { this.#g = 1; } } new A()", "TypeError: '#g' was defined without a setter"),
+        ("class A { #x; static has(o) { return #x in o; } } A.has(1)", "TypeError: Cannot use 'in' operator to search for '#x' in 1"),
+        ("class A { constructor() { Object.preventExtensions(this); } } class B extends A { y = 1; } new B()", "TypeError: Cannot define property y, object is not extensible"),
+        ("class C { constructor(o) { return o; } } class D extends C { #x = 1; } const o = {}; new D(o); new D(o)", "TypeError: Cannot initialize #x twice on the same object"),
+        ("class A { m() {} } class B extends A { m() { delete super.m; } } new B().m()", "ReferenceError: Unsupported reference to 'super'"),
+        ("class D extends null { constructor() { super(); } } new D()", "TypeError: Super constructor null of D is not a constructor"),
+        ("const E = class extends null {}; new E()", "TypeError: Super constructor null of E is not a constructor"),
+        ("class A {} class B extends A {} Object.setPrototypeOf(B, () => {}); new B()", "TypeError: Super constructor null of B is not a constructor"),
+        ("class A { static { throw new Error(\"in block\"); } }", "Error: in block"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::{Interpreter, Value};
+use ts_interpreter_rs::parser::Parser;
+
+#[test]
+fn test_map() {
+    let cases = vec![
+        ("const m = new Map(); m.set('a', 1).set('b', 2); m.get('b')", Value::Number(2.0)),
+        ("new Map([[1, 'x'], [1, 'y']]).get(1)", string("y")),
+        ("new Map([[1, 'x']]).get('1')", Value::Undefined),
+        ("new Map([[NaN, 'n']]).get(NaN)", string("n")),
+        ("new Map([[-0, 'z']]).get(0)", string("z")),
+        ("1 / [...new Map([[-0, 'z']]).keys()][0]", Value::Number(f64::INFINITY)),
+        ("const k = {}; new Map([[k, 1]]).has({})", Value::Boolean(false)),
+        ("const k = {}; new Map([[k, 1]]).has(k)", Value::Boolean(true)),
+        ("const m = new Map([[1, 1], [2, 2]]); [m.delete(1), m.delete(1), m.size].join()", string("true,false,1")),
+        ("const m = new Map([[1, 1]]); [m.clear(), m.size].join()", string(",0")),
+        ("[...new Map([['b', 1], ['a', 2]])].join(';')", string("b,1;a,2")),
+        ("const m = new Map([['b', 1], ['a', 2]]); m.set('b', 3); [...m.values()].join()", string("3,2")),
+        ("const m = new Map([['b', 1]]); m.delete('b'); m.set('b', 2); m.set('a', 3); [...m.keys()].join()", string("b,a")),
+        ("const out = []; new Map([[1, 'a']]).forEach(function (v, k, m) { out.push(v, k, m.size, this.x); }, { x: 'y' }); out.join()", string("a,1,1,y")),
+        ("class M extends Map { set(k, v) { return super.set(k, v * 2); } } new M([[1, 2]]).get(1)", Value::Number(4.0)),
+        ("Object.getPrototypeOf(new Map().entries()) === Object.getPrototypeOf(new Map().keys())", Value::Boolean(true)),
+        ("Map.prototype.entries === Map.prototype.entries && [Map.length, Map.prototype.set.length].join()", string("0,2")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_set() {
+    let cases = vec![
+        ("new Set([1, 2, 2, '2', NaN, NaN]).size", Value::Number(4.0)),
+        ("const s = new Set([-0]); [s.has(0), 1 / [...s][0]].join()", string("true,Infinity")),
+        ("[...new Set('hello')].join('')", string("helo")),
+        ("const s = new Set([3, 1]); s.add(2).add(1); [...s].join()", string("3,1,2")),
+        ("[...new Set([1, 2]).entries()].join(';')", string("1,1;2,2")),
+        ("Set.prototype.keys === Set.prototype.values", Value::Boolean(true)),
+        ("const s = new Set([1]); [s.delete(1), s.has(1), s.size].join()", string("true,false,0")),
+        ("const out = []; new Set(['a']).forEach((v, k, s) => out.push(v, k, s.size)); out.join()", string("a,a,1")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_live_iteration() {
+    let cases = vec![
+        // Entries added during iteration are visited, deleted ones are not
+        ("const s = new Set([1, 2, 3]); const out = []; for (const v of s) { out.push(v); if (v < 3) s.add(v + 3); if (v === 1) s.delete(2); } out.join()", string("1,3,4")),
+        ("const m = new Map([[1, 1]]); const out = []; m.forEach((v, k) => { out.push(k); if (k < 4) m.set(k + 1, 0); }); out.join()", string("1,2,3,4")),
+        ("const s = new Set([1, 2]); const it = s.values(); it.next(); s.clear(); s.add(3); it.next().value", Value::Number(3.0)),
+        ("const s = new Set([1]); const it = s.values(); it.next(); s.add(2); it.next().value", Value::Number(2.0)),
+        ("const s = new Set([1]); const it = s.values(); it.next(); it.next(); s.add(2); it.next().done", Value::Boolean(true)),
+        // Deleting enough entries to compact the table keeps iterators in place
+        ("const s = new Set(); for (let i = 0; i < 100; i++) s.add(i); const it = s.values(); for (let i = 0; i < 50; i++) it.next(); for (let i = 0; i < 60; i++) s.delete(i); it.next().value", Value::Number(60.0)),
+        ("const s = new Set(); for (let i = 0; i < 100; i++) s.add(i); const it = s.values(); for (let i = 0; i < 70; i++) it.next(); for (let i = 0; i < 60; i++) s.delete(i); [...it].length", Value::Number(30.0)),
+        ("const it = new Map([['a', 1]]).entries(); const r = it.next(); [r.value.join(), r.done, it.next().done].join()", string("a,1,false,true")),
+        ("[...new Map([['a', 1], ['b', 2]]).keys(), ...new Set([3])].join()", string("a,b,3")),
+        ("const [[k, v]] = new Map([['a', 1]]); k + v", string("a1")),
+        ("Array.from(new Set([1, 2])).length", Value::Number(2.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_weak_collections() {
+    let cases = vec![
+        ("const k = {}; const w = new WeakMap([[k, 1]]); [w.get(k), w.has(k), w.has({})].join()", string("1,true,false")),
+        ("const w = new WeakMap(); [w.get(1), w.has(1), w.delete(1)].join()", string(",false,false")),
+        ("const k = () => 1; const w = new WeakMap(); w.set(k, 2).get(k)", Value::Number(2.0)),
+        ("const k = {}; const w = new WeakMap([[k, 1]]); [w.delete(k), w.has(k)].join()", string("true,false")),
+        ("const k = {}; const w = new WeakSet([k]); [w.has(k), w.has(1), w.delete(k), w.has(k)].join()", string("true,false,true,false")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_collection_errors() {
+    let cases = vec![
+        ("Map()", "TypeError: Constructor Map requires 'new'"),
+        ("WeakSet()", "TypeError: Constructor WeakSet requires 'new'"),
+        ("Map.prototype.get.call({}, 1)", "TypeError: Method Map.prototype.get called on incompatible receiver #<Object>"),
+        ("Map.prototype.get.call(new Set(), 1)", "TypeError: Method Map.prototype.get called on incompatible receiver #<Set>"),
+        ("Set.prototype.add.call('abc', 1)", "TypeError: Method Set.prototype.add called on incompatible receiver abc"),
+        ("Object.getOwnPropertyDescriptor(Map.prototype, 'size').get.call([])", "TypeError: Method get Map.prototype.size called on incompatible receiver [object Array]"),
+        ("new Map().keys().next.call(new Set().values())", "TypeError: Method Map Iterator.prototype.next called on incompatible receiver #<Object>"),
+        ("new Map([1])", "TypeError: Iterator value 1 is not an entry object"),
+        ("new Map(5)", "TypeError: 5 is not iterable"),
+        ("new WeakMap().set(1, 1)", "TypeError: Invalid value used as weak map key"),
+        ("new WeakMap([['a', 1]])", "TypeError: Invalid value used as weak map key"),
+        ("new WeakSet().add(1)", "TypeError: Invalid value used in weak set"),
+        ("new Map().forEach(1)", "TypeError: 1 is not a function"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+/// Runs `source` in `interpreter`, then collects garbage and returns the
+/// number of objects left.
+fn live_after(interpreter: &mut Interpreter, source: &str) -> usize {
+    interpreter.run(Parser::new(source).parse_program().unwrap()).unwrap();
+    interpreter.collect_garbage();
+    interpreter.live_objects()
+}
+
+#[test]
+fn test_weak_entries_are_collected() {
+    let mut interpreter = Interpreter::new();
+    let before = live_after(&mut interpreter, "var weak = new WeakMap(); var strong = new Map(); var key = {};");
+    // The key stays reachable through `key`, and the value through the key
+    let with_entry = live_after(&mut interpreter, "weak.set(key, { data: [1] });");
+    assert_eq!(with_entry, before + 2);
+    // The value refers back to its key, which must not keep either alive
+    let unreachable = live_after(&mut interpreter, "var other = {}; weak.set(other, { other }); other = null; key = null;");
+    assert_eq!(unreachable, before - 1);
+
+    // A `Map` holds its keys strongly
+    let strong = live_after(&mut interpreter, "key = {}; strong.set(key, 1); weak.set(key, 2); key = null;");
+    assert_eq!(strong, unreachable + 1);
+    let result = interpreter.run(Parser::new("[...strong.keys()].map(k => weak.get(k)).join()").parse_program().unwrap());
+    assert_eq!(result, Ok(string("2")));
+}
+
+#[test]
+fn test_garbage_is_collected_between_statements() {
+    let mut interpreter = Interpreter::new();
+    let source = "var kept = new WeakSet(); for (let i = 0; i < 150000; i++) kept.add({}); var done = true;";
+    interpreter.run(Parser::new(source).parse_program().unwrap()).unwrap();
+    assert!(interpreter.live_objects() < 100_000, "{} live objects", interpreter.live_objects());
+}
+
+/// Collections also happen at function calls and loop iterations, so a
+/// long-running function does not keep its garbage until it returns.
+#[test]
+fn test_garbage_is_collected_inside_functions() {
+    let mut interpreter = Interpreter::new();
+    // The script is left waiting in the statement that calls `main`, before
+    // the collection that follows each statement
+    let source = "
+        function main() { const w = new WeakMap(); for (let i = 0; i < 200000; i++) w.set({}, [i]); return w; }
+        [main(), await new Promise(() => {})];
+    ";
+    let pending = interpreter.run(Parser::new(source).parse_program().unwrap());
+    assert!(matches!(pending, Ok(Value::Object(_))), "{:?}", pending);
+    assert!(interpreter.live_objects() < 150_000, "{} live objects", interpreter.live_objects());
+}
+
+/// Callbacks of built-in functions collect garbage too.
+#[test]
+fn test_garbage_is_collected_inside_callbacks() {
+    let mut interpreter = Interpreter::new();
+    let source = "
+        function main() { const w = new WeakMap(); Array.from({ length: 200000 }, (_, i) => i).forEach(i => { w.set({}, [i]); }); return w; }
+        [main(), await new Promise(() => {})];
+    ";
+    let pending = interpreter.run(Parser::new(source).parse_program().unwrap());
+    assert!(matches!(pending, Ok(Value::Object(_))), "{:?}", pending);
+    assert!(interpreter.live_objects() < 150_000, "{} live objects", interpreter.live_objects());
+}
+
+/// Objects that are only held by the Rust code evaluating an expression
+/// survive the collections that calls made in the middle of it trigger.
+#[test]
+fn test_objects_in_use_survive_collections() {
+    let source = "
+        function churn() { for (let i = 0; i < 100001; i++) [i]; return 'k'; }
+        const sum = ({ valueOf() { churn(); return 1; } }) + ({ valueOf() { return 2; } });
+        function f(a = churn(), b) { return b.x; }
+        const o = { m() { return 3; } };
+        const gen = (function* () { const a = [{ x: 4 }, yield, { y: 5 }]; churn(); return a[0].x + a[2].y; })();
+        gen.next();
+        const sorted = [{ v: 3 }, { v: 2 }, { v: 1 }];
+        sorted.sort((a, b) => { sorted.length = 0; churn(); return a.v - b.v; });
+        const total = [{ x: 1 }, { x: 2 }].reduce((acc, o) => { churn(); return { x: acc.x + o.x }; });
+        const { proxy, revoke } = Proxy.revocable({ x: 7 }, { get(target, key) { revoke(); churn(); return target[key]; } });
+        [sum, f(undefined, { x: 6 }), o.m(delete o.m, churn()), gen.next().value, sorted.map(o => o.v).join(''), total.x, proxy.x].join()
+    ";
+    assert_eq!(run(source), Ok(string("3,6,3,9,123,3,7")));
+}
+
+/// A value the host kept after its object was collected is reported as
+/// gone, even once the object's slot is reused.
+#[test]
+fn test_host_values_of_collected_objects() {
+    let mut interpreter = Interpreter::new();
+    let buffer = interpreter.create_array_buffer(vec![1, 2]);
+    live_after(&mut interpreter, "var unrelated = 1;");
+    live_after(&mut interpreter, "var buffers = [new ArrayBuffer(1), new ArrayBuffer(2)];");
+    assert_eq!(interpreter.array_buffer_bytes(&buffer), None);
+    assert!(interpreter.set_variable("buffer", buffer).is_err());
+}
@@ -0,0 +1,24 @@
+// Each test crate includes this module and uses only some of its helpers
+#![allow(dead_code)]
+
+use ts_interpreter_rs::interpreter::{Interpreter, Value};
+use ts_interpreter_rs::parser::Parser;
+
+/// Parses and runs `source` as a program in a fresh interpreter, returning
+/// the value of the last statement.
+pub fn run(source: &str) -> Result<Value, String> {
+    let mut interpreter = Interpreter::new();
+    let mut parser = Parser::new(source);
+    interpreter.run(parser.parse_program()?)
+}
+
+/// Parses and runs `source` in `interpreter`, which keeps the globals and
+/// pending jobs of the programs run in it before.
+pub fn run_in(interpreter: &mut Interpreter, source: &str) -> Result<Value, String> {
+    interpreter.run(Parser::new(source).parse_program()?)
+}
+
+/// `s` as a JavaScript string value.
+pub fn string(s: &str) -> Value {
+    Value::String(s.into())
+}
@@ -0,0 +1,246 @@
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
+
+use ts_interpreter_rs::interpreter::Interpreter;
+use ts_interpreter_rs::parser::Parser;
+
+/// A sink that keeps everything written to it.
+#[derive(Clone, Default)]
+struct Capture(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Capture {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+/// Runs `source`, returning what it wrote to stdout and stderr.
+fn run_capturing(source: &str) -> (String, String) {
+    let (stdout, stderr) = (Capture::default(), Capture::default());
+    let mut interpreter = Interpreter::new();
+    interpreter.set_stdout(Box::new(stdout.clone()));
+    interpreter.set_stderr(Box::new(stderr.clone()));
+    let program = Parser::new(source).parse_program().unwrap();
+    interpreter.run(program).unwrap();
+    (stdout.text(), stderr.text())
+}
+
+fn stdout_of(source: &str) -> String {
+    run_capturing(source).0
+}
+
+#[test]
+fn test_log_values() {
+    let cases = vec![
+        ("console.log(\"hello\", 1, true, null, [][0])", "hello 1 true null undefined\n"),
+        ("console.log()", "\n"),
+        ("console.log(-0, 0.1 + 0.2, 0 / 0)", "-0 0.30000000000000004 NaN\n"),
+        ("console.log([\"a\", 1])", "[ 'a', 1 ]\n"),
+        ("console.log({ a: 1, \"b-c\": \"it's\" })", "{ a: 1, 'b-c': \"it's\" }\n"),
+        ("console.log({ a: { b: { c: { d: 1 } } } })", "{ a: { b: { c: [Object] } } }\n"),
+        ("console.log([[[[1]]]])", "[ [ [ [Array] ] ] ]\n"),
+        ("console.log({}, [])", "{} []\n"),
+        ("console.log([1, , 3, , , 6])", "[ 1, <1 empty item>, 3, <2 empty items>, 6 ]\n"),
+        ("const a = [1]; a.extra = true; console.log(a)", "[ 1, extra: true ]\n"),
+        ("console.log(function f() {}, () => {}, class A {})", "[Function: f] [Function (anonymous)] [class A]\n"),
+        ("class A {} class B extends A {} console.log(B)", "[class B extends A]\n"),
+        ("console.log({ get a() { return 1; }, set b(v) {}, get c() { return 1; }, set c(v) {} })", "{ a: [Getter], b: [Setter], c: [Getter/Setter] }\n"),
+        ("console.log(Object.create(null))", "[Object: null prototype] {}\n"),
+        ("console.log(new Number(-0), [1.5e-7])", "[Number: -0] [ 1.5e-7 ]\n"),
+        ("console.log(new String(\"ab\"))", "[String: 'ab']\n"),
+        ("console.log(1n, [-2n]); console.log(\"%d %s\", 3n, 4n)", "1n [ -2n ]\n3n 4n\n"),
+        ("class D extends Date {} console.log(new Date(0), [new Date(NaN)], new D(1))", "1970-01-01T00:00:00.000Z [ Invalid Date ] D 1970-01-01T00:00:00.001Z\n"),
+        ("const s = Symbol(\"s\"); console.log(s, { [s]: 1, a: [Symbol()] })", "Symbol(s) { a: [ Symbol() ], [Symbol(s)]: 1 }\n"),
+        ("function* g() {} console.log(g.prototype, { [Symbol.toStringTag]: \"Own\" })", "Object [Generator] {} { [Symbol(Symbol.toStringTag)]: 'Own' }\n"),
+        ("console.log(Object.create(null, { [Symbol.toStringTag]: { value: \"T\" } }))", "[Object: null prototype] [T] {}\n"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(stdout_of(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_log_collections() {
+    let cases = vec![
+        ("console.log(new Map([[\"a\", 1], [{ x: 1 }, [1]]]))", "Map(2) { 'a' => 1, { x: 1 } => [ 1 ] }\n"),
+        ("console.log(new Map(), new Set())", "Map(0) {} Set(0) {}\n"),
+        ("console.log(new Set([1, \"a\"]))", "Set(2) { 1, 'a' }\n"),
+        ("console.log(new WeakMap(), new WeakSet())", "WeakMap { <items unknown> } WeakSet { <items unknown> }\n"),
+        ("const m = new Map([[\"a\", 1], [{ x: 1 }, 2]]); console.log(m.entries(), m.keys())", "[Map Entries] { [ 'a', 1 ], [ { x: 1 }, 2 ] } [Map Iterator] { 'a', { x: 1 } }\n"),
+        ("const it = new Set([1]).entries(); console.log(it); it.next(); console.log(it)", "[Set Entries] { [ 1, 1 ] }\n[Set Entries] {  }\n"),
+        ("class M extends Map {} console.log(new M([[1, 2]]))", "M(1) [Map] { 1 => 2 }\n"),
+        ("const s = new Set(); s.add(s); console.log(s)", "<ref *1> Set(1) { [Circular *1] }\n"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(stdout_of(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_class_instances_and_cycles() {
+    let cases = vec![
+        ("class Point { constructor() { this.x = 1; this.y = 2; } } console.log(new Point())", "Point { x: 1, y: 2 }\n"),
+        ("class Empty {} console.log(new Empty())", "Empty {}\n"),
+        ("const o = { name: \"o\" }; o.self = o; console.log(o)", "<ref *1> { name: 'o', self: [Circular *1] }\n"),
+        ("const a = [1]; a.push(a); console.log(a)", "<ref *1> [ 1, [Circular *1] ]\n"),
+        ("const o = {}; const p = { o }; o.p = p; console.log([o])", "[ <ref *1> { p: { o: [Circular *1] } } ]\n"),
+        ("const shared = { n: 1 }; console.log([shared, shared])", "[ { n: 1 }, { n: 1 } ]\n"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(stdout_of(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_line_breaking() {
+    let cases = vec![
+        (
+            "console.log({ first: \"aaaaaaaaaaaaaaa\", second: \"bbbbbbbbbbbbbbb\", third: \"ccccccccccccccc\", fourth: 1 })",
+            "{\n  first: 'aaaaaaaaaaaaaaa',\n  second: 'bbbbbbbbbbbbbbb',\n  third: 'ccccccccccccccc',\n  fourth: 1\n}\n",
+        ),
+        (
+            "const a = []; for (let i = 0; i < 30; i++) a.push(i); console.log(a)",
+            "[\n   0,  1,  2,  3,  4,  5,  6,  7,  8,\n   9, 10, 11, 12, 13, 14, 15, 16, 17,\n  18, 19, 20, 21, 22, 23, 24, 25, 26,\n  27, 28, 29\n]\n",
+        ),
+        (
+            "const a = []; for (let i = 0; i < 101; i++) a.push(0); console.log(a.length, [a].length)",
+            "101 1\n",
+        ),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(stdout_of(input), expected, "{}", input);
+    }
+
+    let ones = "  1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,\n".repeat(8);
+    let expected = format!("[\n{}  1, 1, 1, 1,\n  ... 20 more items\n]\n", ones);
+    assert_eq!(stdout_of("console.log(new Array(120).fill(1))"), expected);
+}
+
+#[test]
+fn test_format_specifiers() {
+    let cases = vec![
+        ("console.log(\"%s is %d years\", \"Ann\", 42.5)", "Ann is 42.5 years\n"),
+        ("console.log(\"%i|%f\", 42.9, \"1.5\")", "42|1.5\n"),
+        ("console.log(\"%d\", {})", "NaN\n"),
+        ("console.log(\"%j\", { a: [1, \"x\"], f() {} })", "{\"a\":[1,\"x\"]}\n"),
+        ("const o = {}; o.o = o; console.log(\"%j\", o)", "[Circular]\n"),
+        ("console.log(\"%o\", { a: { b: { c: { d: { e: 1 } } } } })", "{\n  a: {\n    b: { c: { d: { e: 1 } } }\n  }\n}\n"),
+        ("console.log(\"%O\", { a: { b: { c: { d: 1 } } } })", "{ a: { b: { c: [Object] } } }\n"),
+        ("console.log(\"%s\", { a: { b: 1 } })", "{ a: [Object] }\n"),
+        ("console.log(\"%s\", { toString() { return \"custom\"; } })", "custom\n"),
+        ("console.log(\"100%% %c done\", \"color: red\")", "100%  done\n"),
+        ("console.log(\"%s and %s\", \"one\")", "one and %s\n"),
+        ("console.log(\"%s\", \"a\", \"b\", 3)", "a b 3\n"),
+        ("console.log(\"%x\", 1)", "%x 1\n"),
+        ("console.log(1, \"%s\", 2)", "1 %s 2\n"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(stdout_of(input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_streams_and_groups() {
+    let (stdout, stderr) = run_capturing(
+        "console.info(\"info\"); console.debug(\"debug\"); console.error(\"error\"); console.warn(\"warn\");",
+    );
+    assert_eq!(stdout, "info\ndebug\n");
+    assert_eq!(stderr, "error\nwarn\n");
+
+    let (stdout, stderr) = run_capturing(
+        "console.group(\"outer\"); console.log(\"a\"); console.group(); console.log({ x: [1] }); console.error({ first: \"aaaaaaaaaaaaaaaaaaaa\", second: \"bbbbbbbbbbbbbbbbbbbb\", third: \"cccccccccccccccccccc\" }); \
+         console.groupEnd(); console.groupEnd(); console.groupEnd(); console.log(\"d\");",
+    );
+    assert_eq!(stdout, "outer\n  a\n    { x: [ 1 ] }\nd\n");
+    assert_eq!(stderr, "    {\n      first: 'aaaaaaaaaaaaaaaaaaaa',\n      second: 'bbbbbbbbbbbbbbbbbbbb',\n      third: 'cccccccccccccccccccc'\n    }\n");
+}
+
+#[test]
+fn test_dir_count_and_assert() {
+    let cases = vec![
+        ("console.dir(\"text\")", "'text'\n"),
+        ("console.dir({ a: { b: { c: 1 } } }, { depth: 0 })", "{ a: [Object] }\n"),
+        ("console.dir({ a: { b: { c: { d: 1 } } } }, { depth: null })", "{\n  a: { b: { c: { d: 1 } } }\n}\n"),
+        ("console.count(); console.count(); console.count(\"x\"); console.countReset(); console.count()", "default: 1\ndefault: 2\nx: 1\ndefault: 1\n"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(stdout_of(input), expected, "{}", input);
+    }
+
+    let (stdout, stderr) = run_capturing("console.assert(true, \"no\"); console.assert(false); console.assert(0, \"bad %s\", \"thing\"); console.assert(null, { a: 1 });");
+    assert_eq!(stdout, "");
+    assert_eq!(stderr, "Assertion failed\nAssertion failed: bad thing\nAssertion failed: [object Object]\n");
+}
+
+#[test]
+fn test_timers() {
+    let (stdout, stderr) = run_capturing("console.time(\"t\"); console.time(\"t\"); console.timeLog(\"t\", \"mid\"); console.timeEnd(\"t\"); console.timeEnd(\"t\");");
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", stdout);
+    assert!(lines[0].starts_with("t: ") && lines[0].ends_with("ms mid"), "{}", lines[0]);
+    assert!(lines[1].starts_with("t: ") && lines[1].ends_with("ms"), "{}", lines[1]);
+    assert_eq!(stderr, "Warning: Label 't' already exists for console.time()\nWarning: No such label 't' for console.timeEnd()\n");
+}
+
+#[test]
+fn test_table() {
+    let cases = vec![
+        (
+            "console.table([{ a: 1, b: \"x\" }, { a: 2, c: true }])",
+            "┌─────────┬───┬─────┬──────┐\n\
+             │ (index) │ a │ b   │ c    │\n\
+             ├─────────┼───┼─────┼──────┤\n\
+             │ 0       │ 1 │ 'x' │      │\n\
+             │ 1       │ 2 │     │ true │\n\
+             └─────────┴───┴─────┴──────┘\n",
+        ),
+        (
+            "console.table([1, \"two\"])",
+            "┌─────────┬────────┐\n\
+             │ (index) │ Values │\n\
+             ├─────────┼────────┤\n\
+             │ 0       │ 1      │\n\
+             │ 1       │ 'two'  │\n\
+             └─────────┴────────┘\n",
+        ),
+        (
+            "console.table({ r: { a: 1, b: 2 } }, [\"b\"])",
+            "┌─────────┬───┐\n\
+             │ (index) │ b │\n\
+             ├─────────┼───┤\n\
+             │ r       │ 2 │\n\
+             └─────────┴───┘\n",
+        ),
+        (
+            "console.table([{ n: [1, 2, 3, 4], o: { x: 1, y: 2, z: 3 } }])",
+            "┌─────────┬──────────────────────────────┬──────────┐\n\
+             │ (index) │ n                            │ o        │\n\
+             ├─────────┼──────────────────────────────┼──────────┤\n\
+             │ 0       │ [ 1, 2, 3, ... 1 more item ] │ [Object] │\n\
+             └─────────┴──────────────────────────────┴──────────┘\n",
+        ),
+        ("console.table(5)", "5\n"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(stdout_of(input), expected, "{}", input);
+    }
+}
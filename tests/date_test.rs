@@ -0,0 +1,216 @@
+mod common;
+
+use common::run;
+use ts_interpreter_rs::interpreter::Interpreter;
+use ts_interpreter_rs::parser::Parser;
+
+/// Runs `source` in an interpreter whose local time zone is `zone`,
+/// converting its result to a string.
+fn run_in_zone(zone: &str, source: &str) -> Result<String, String> {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_time_zone(zone)?;
+    interpreter.run(Parser::new(source).parse_program()?).map(|value| value.to_js_string())
+}
+
+fn eval(interpreter: &mut Interpreter, source: &str) -> String {
+    interpreter.run(Parser::new(source).parse_program().unwrap()).unwrap().to_js_string()
+}
+
+fn run_date(source: &str) -> Result<String, String> {
+    run(source).map(|value| value.to_js_string())
+}
+
+#[test]
+fn test_date_construction() {
+    let cases = vec![
+        ("Date.now()", "0"),
+        ("new Date().getTime()", "0"),
+        ("new Date(86400000).toISOString()", "1970-01-02T00:00:00.000Z"),
+        ("new Date(-1).toISOString()", "1969-12-31T23:59:59.999Z"),
+        ("new Date(1.9).getTime()", "1"),
+        ("new Date(true).getTime()", "1"),
+        ("new Date(null).getTime()", "0"),
+        ("new Date(new Date(42)).getTime()", "42"),
+        ("new Date({ valueOf() { return 7; } }).getTime()", "7"),
+        ("new Date('1970-01-02').getTime()", "86400000"),
+        ("new Date(2020, 0, 15, 10, 30, 15, 250).toISOString()", "2020-01-15T10:30:15.250Z"),
+        ("new Date(2020, 1, 30).toISOString()", "2020-03-01T00:00:00.000Z"),
+        ("new Date(2020, -1).toISOString()", "2019-12-01T00:00:00.000Z"),
+        ("new Date(99, 0).getFullYear()", "1999"),
+        ("new Date(8.64e15).toISOString()", "+275760-09-13T00:00:00.000Z"),
+        ("new Date(-62167219200001).toISOString()", "-000001-12-31T23:59:59.999Z"),
+        ("new Date(8.64e15 + 1).getTime()", "NaN"),
+        ("new Date(NaN).getTime()", "NaN"),
+        ("new Date(2020, NaN).getTime()", "NaN"),
+        ("Date.UTC(2020, 0, 15, 10, 30)", "1579084200000"),
+        ("Date.UTC(2020)", "1577836800000"),
+        ("Date.UTC(99, 11, 31)", "946598400000"),
+        ("Date.UTC()", "NaN"),
+        ("Date.length", "7"),
+        ("Date()", "Thu Jan 01 1970 00:00:00 GMT+0000 (Coordinated Universal Time)"),
+        ("Object.prototype.toString.call(new Date(0))", "[object Date]"),
+        ("class D extends Date { year() { return this.getUTCFullYear(); } } new D(0).year()", "1970"),
+        ("class D extends Date {} new D(0) instanceof Date", "true"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_date(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_date_parse() {
+    let cases = vec![
+        ("2020-01-15", "1579046400000"),
+        ("2020-01", "1577836800000"),
+        ("2020", "1577836800000"),
+        ("+002020-01-15", "1579046400000"),
+        ("2020-01-15T10:30:00Z", "1579084200000"),
+        ("2020-01-15T10:30Z", "1579084200000"),
+        ("2020-01-15T10:30:00.5+01:00", "1579080600500"),
+        ("2020-01-15T10:30:00", "1579084200000"),
+        ("2020-01-15T24:00:00Z", "1579132800000"),
+        ("Wed, 15 Jan 2020 10:30:00 GMT", "1579084200000"),
+        ("15 Jan 2020 10:30:00 -0500", "1579102200000"),
+        ("15 Jan 20 10:30 EST", "1579102200000"),
+        ("Wed Jan 15 2020 10:30:00 GMT+0100 (Central European Standard Time)", "1579080600000"),
+        ("Jan 15, 2020", "1579046400000"),
+        ("January 15, 2020 10:30 PM", "1579127400000"),
+        ("1/15/2020", "1579046400000"),
+        ("-000000-01-01", "NaN"),
+        ("2020-13-01", "NaN"),
+        ("2020-02-30", "NaN"),
+        ("2020-01-15T25:00Z", "NaN"),
+        ("nonsense", "NaN"),
+        ("", "NaN"),
+    ];
+
+    for (input, expected) in cases {
+        let source = format!("Date.parse('{}')", input);
+        assert_eq!(run_date(&source), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_date_formats() {
+    let cases = vec![
+        ("new Date(0).toString()", "Thu Jan 01 1970 00:00:00 GMT+0000 (Coordinated Universal Time)"),
+        ("new Date(0).toDateString()", "Thu Jan 01 1970"),
+        ("new Date(0).toTimeString()", "00:00:00 GMT+0000 (Coordinated Universal Time)"),
+        ("new Date(0).toUTCString()", "Thu, 01 Jan 1970 00:00:00 GMT"),
+        ("new Date(0).toGMTString === Date.prototype.toUTCString", "true"),
+        ("new Date(0).toISOString()", "1970-01-01T00:00:00.000Z"),
+        ("new Date(0).toLocaleString()", "1/1/1970, 12:00:00 AM"),
+        ("new Date(0).toLocaleDateString()", "1/1/1970"),
+        ("new Date(Date.UTC(2020, 11, 24, 13, 5, 9)).toLocaleTimeString()", "1:05:09 PM"),
+        ("new Date(NaN).toString()", "Invalid Date"),
+        ("new Date(NaN).toUTCString()", "Invalid Date"),
+        ("new Date(NaN).toLocaleString()", "Invalid Date"),
+        ("JSON.stringify({ d: new Date(0) })", r#"{"d":"1970-01-01T00:00:00.000Z"}"#),
+        ("JSON.stringify(new Date(NaN))", "null"),
+        ("Date.prototype.toJSON.call({ toISOString() { return 'iso'; } })", "iso"),
+        ("new Date(0) + 1", "Thu Jan 01 1970 00:00:00 GMT+0000 (Coordinated Universal Time)1"),
+        ("new Date(5) - new Date(2)", "3"),
+        ("+new Date(7)", "7"),
+        ("new Date(0) < new Date(1)", "true"),
+        ("new Date(0)[Symbol.toPrimitive]('number')", "0"),
+        ("new Date(0)[Symbol.toPrimitive]('default')", "Thu Jan 01 1970 00:00:00 GMT+0000 (Coordinated Universal Time)"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_date(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_date_setters() {
+    let cases = vec![
+        ("const d = new Date(0); d.setFullYear(2020); d.toISOString()", "2020-01-01T00:00:00.000Z"),
+        ("const d = new Date(0); d.setFullYear(2020, 1, 29)", "1582934400000"),
+        ("const d = new Date(0); d.setMonth(13); d.toISOString()", "1971-02-01T00:00:00.000Z"),
+        ("const d = new Date(0); d.setDate(0); d.toISOString()", "1969-12-31T00:00:00.000Z"),
+        ("const d = new Date(0); d.setHours(25, 61, 61, 1001); d.toISOString()", "1970-01-02T02:02:02.001Z"),
+        ("const d = new Date(0); d.setMinutes(90); d.getHours()", "1"),
+        ("const d = new Date(0); d.setSeconds(30, 500); d.toISOString()", "1970-01-01T00:00:30.500Z"),
+        ("const d = new Date(0); d.setMilliseconds(-1); d.toISOString()", "1969-12-31T23:59:59.999Z"),
+        ("const d = new Date(0); d.setUTCDate(15); d.getUTCDate()", "15"),
+        ("const d = new Date(0); d.setTime(1e3)", "1000"),
+        ("const d = new Date(0); d.setTime(); d.getTime()", "NaN"),
+        ("const d = new Date(0); d.setHours(); d.getTime()", "NaN"),
+        ("const d = new Date(NaN); d.setDate(1)", "NaN"),
+        ("const d = new Date(NaN); d.setFullYear(2000); d.getTime()", "946684800000"),
+        ("const d = new Date(0); d.setFullYear(3e5)", "NaN"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_date(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_date_time_zones() {
+    let cases = vec![
+        ("new Date(2020, 0, 15, 10, 30).getTime()", "1579102200000"),
+        ("new Date(2020, 0, 15, 10, 30).toString()", "Wed Jan 15 2020 10:30:00 GMT-0500 (EST)"),
+        ("new Date(2020, 6, 1).toString()", "Wed Jul 01 2020 00:00:00 GMT-0400 (EDT)"),
+        ("new Date(2020, 0, 15).getTimezoneOffset()", "300"),
+        ("new Date(2020, 6, 1).getTimezoneOffset()", "240"),
+        ("new Date(Date.UTC(2020, 0, 15, 3)).getDate()", "14"),
+        ("new Date(Date.UTC(2020, 0, 15, 3)).getUTCDate()", "15"),
+        ("new Date(Date.UTC(2020, 0, 15, 3)).getDay()", "2"),
+        ("new Date(Date.UTC(2020, 0, 15, 3)).getUTCDay()", "3"),
+        ("new Date(Date.UTC(2020, 0, 15, 3)).getHours()", "22"),
+        ("new Date(Date.UTC(2020, 6, 1, 16)).toLocaleString()", "7/1/2020, 12:00:00 PM"),
+        ("Date.parse('2020-07-01T12:00:00')", "1593619200000"),
+        ("Date.parse('2020-07-01')", "1593561600000"),
+        // Skipped when the clocks go forward, and read with the old offset
+        ("new Date(2020, 2, 8, 2, 30).getHours()", "3"),
+        ("new Date(2020, 2, 8, 2, 30).toISOString()", "2020-03-08T07:30:00.000Z"),
+        // Repeated when the clocks go back, and taken the first time
+        ("new Date(2020, 10, 1, 1, 30).toISOString()", "2020-11-01T05:30:00.000Z"),
+        ("const d = new Date(2020, 0, 15); d.setMonth(6); d.getHours()", "0"),
+        ("const d = new Date(2020, 6, 15); d.setUTCHours(0); d.getDate()", "14"),
+    ];
+
+    // POSIX strings only, as IANA names depend on the host's zone files
+    for zone in ["EST5EDT,M3.2.0,M11.1.0", "EST+5EDT,M3.2.0/2,M11.1.0/2"] {
+        for (input, expected) in &cases {
+            assert_eq!(run_in_zone(zone, input), Ok(expected.to_string()), "{} in {}", input, zone);
+        }
+    }
+
+    let offset = "new Date(0).toString()";
+    assert_eq!(run_in_zone("<+0530>-5:30", offset), Ok("Thu Jan 01 1970 05:30:00 GMT+0530 (GMT+05:30)".to_string()));
+    assert!(Interpreter::new().set_time_zone("Nowhere/Else").is_err());
+}
+
+#[test]
+fn test_injected_clock() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_current_time(1e12);
+    assert_eq!(eval(&mut interpreter, "Date.now()"), "1000000000000");
+    eval(&mut interpreter, "var seen; setTimeout(() => seen = new Date().getTime(), 100);");
+    interpreter.advance_time(250).unwrap();
+    assert_eq!(eval(&mut interpreter, "seen"), "1000000000100");
+    assert_eq!(eval(&mut interpreter, "Date.now()"), "1000000000250");
+    assert_eq!(eval(&mut interpreter, "new Date().toISOString()"), "2001-09-09T01:46:40.250Z");
+}
+
+#[test]
+fn test_date_errors() {
+    let cases = vec![
+        ("Date.prototype.getTime.call({})", "TypeError: this is not a Date object."),
+        ("Date.prototype.setDate.call(1, 1)", "TypeError: this is not a Date object."),
+        ("Date.prototype.toString.call(Object.create(Date.prototype))", "TypeError: this is not a Date object."),
+        ("new Date(NaN).toISOString()", "RangeError: Invalid time value"),
+        ("new Date(0)[Symbol.toPrimitive]('x')", "TypeError: Invalid hint: x"),
+        ("new Date(0)[Symbol.toPrimitive]()", "TypeError: Invalid hint: undefined"),
+        ("Date.prototype[Symbol.toPrimitive].call(1, 'number')", "TypeError: Date.prototype [ @@toPrimitive ] called on non-object"),
+        ("new Date(Symbol())", "TypeError: Cannot convert a Symbol value to a number"),
+        ("new Date(1n)", "TypeError: Cannot convert a BigInt value to a number"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_declaration_patterns() {
+    let cases = vec![
+        ("const [a, b] = [1, 2]; a + b", Value::Number(3.0)),
+        ("const [, second, , fourth] = [1, 2, 3, 4]; second + fourth", Value::Number(6.0)),
+        ("const [first, ...others] = [1, 2, 3]; first + others.length", Value::Number(3.0)),
+        ("const [x = 10, y = 20] = [1]; x + y", Value::Number(21.0)),
+        ("const [x = 10] = [null]; x", Value::Null),
+        ("const { a, b } = { a: 1, b: 2 }; a + b", Value::Number(3.0)),
+        ("const { a: renamed } = { a: 5 }; renamed", Value::Number(5.0)),
+        ("const { missing = 7 } = {}; missing", Value::Number(7.0)),
+        ("const { a, ...rest } = { a: 1, b: 2, c: 3 }; Object.keys(rest).join()", string("b,c")),
+        ("const { [\"dyn\" + 1]: value } = { dyn1: 9 }; value", Value::Number(9.0)),
+        ("const { length } = \"hello\"; length", Value::Number(5.0)),
+        ("const [c1, c2] = \"hi\"; c2 + c1", string("ih")),
+        ("const { p: { q: [deep] } } = { p: { q: [42] } }; deep", Value::Number(42.0)),
+        ("const [{ n }, [m]] = [{ n: 1 }, [2]]; n + m", Value::Number(3.0)),
+        ("var [v1, v2] = [1, 2]; v1 + v2", Value::Number(3.0)),
+        ("function f() { return v; var { v } = { v: 1 }; } f()", Value::Undefined),
+        ("const { f = () => {} } = {}; f.name", string("f")),
+        ("let calls = 0; const { a = ++calls } = { a: 1 }; calls", Value::Number(0.0)),
+        ("const { a, b = a * 2 } = { a: 3 }; b", Value::Number(6.0)),
+        ("const { x }: { x: number } = { x: 4 }; x", Value::Number(4.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_assignment_patterns() {
+    let cases = vec![
+        ("let a = 1, b = 2; [a, b] = [b, a]; a * 10 + b", Value::Number(21.0)),
+        ("let a, b; ({ a, b } = { a: 3, b: 4 }); a + b", Value::Number(7.0)),
+        ("let a; ({ a = 5 } = {}); a", Value::Number(5.0)),
+        ("const o = {}; [o.x, o[\"y\"]] = [1, 2]; o.x + o.y", Value::Number(3.0)),
+        ("let a, rest; [a, ...rest] = [1, 2, 3]; rest.join()", string("2,3")),
+        ("let x, y; [x, [y]] = [1, [2]]; x + y", Value::Number(3.0)),
+        ("let a, r; ({ a, ...r } = { a: 1, b: 2 }); r.b", Value::Number(2.0)),
+        ("let a; const result = [a] = [1, 2]; result.length", Value::Number(2.0)),
+        ("let a, b; [a = 1, b = a + 1] = []; b", Value::Number(2.0)),
+        ("let n; ({ k: n = 3 } = { j: 1 }); n", Value::Number(3.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_parameter_patterns() {
+    let cases = vec![
+        ("function f({ a, b }) { return a + b; } f({ a: 1, b: 2 })", Value::Number(3.0)),
+        ("function f([x, y] = [1, 2]) { return x * y; } f() + f([3, 4])", Value::Number(14.0)),
+        ("const f = ({ name = \"anon\" }: { name?: string }) => name; f({})", string("anon")),
+        ("const f = ([a, b]) => a + b; f([1, 2])", Value::Number(3.0)),
+        ("function f(a, { b } = { b: a }) { return b; } f(5)", Value::Number(5.0)),
+        ("function f(...[a, b]) { return a + b; } f(1, 2)", Value::Number(3.0)),
+        ("function f({ a }, ...rest) { return a + rest.length; } f({ a: 1 }, 2, 3)", Value::Number(3.0)),
+        ("function f({ a }, b = 1) {} f.length", Value::Number(1.0)),
+        ("class P { constructor({ x, y }) { this.sum = x + y; } } new P({ x: 1, y: 2 }).sum", Value::Number(3.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_for_of_and_for_in_heads() {
+    let cases = vec![
+        ("let s = 0; for (const n of [1, 2, 3]) s += n; s", Value::Number(6.0)),
+        ("let s = \"\"; for (const [k, v] of [[\"a\", 1], [\"b\", 2]]) s += k + v; s", string("a1b2")),
+        ("let s = 0; for (const { n } of [{ n: 1 }, { n: 2 }]) s += n; s", Value::Number(3.0)),
+        ("let s = \"\"; for (const c of \"abc\") s = c + s; s", string("cba")),
+        ("let a, b, s = 0; for ([a, b] of [[1, 2], [3, 4]]) s += a * b; s", Value::Number(14.0)),
+        ("const fns = []; for (const [x] of [[1], [2]]) fns.push(() => x); fns[0]() + fns[1]()", Value::Number(3.0)),
+        ("let last; for (var [v] of [[1], [2]]) last = v; v + last", Value::Number(4.0)),
+        ("let s = \"\"; for (const [first] in { ab: 1, cd: 2 }) s += first; s", string("ac")),
+        ("let s = 0; outer: for (const a of [1, 2]) { for (const b of [1, 2]) { if (b === 2) continue outer; s += a * b; } } s", Value::Number(3.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_spread() {
+    let cases = vec![
+        ("function f(a, b, c) { return a + b + c; } f(...[1, 2], 3)", Value::Number(6.0)),
+        ("[0, ...[1, 2], ...\"ab\"].join()", string("0,1,2,a,b")),
+        ("const o = { a: 1, ...{ b: 2, a: 3 } }; o.a + o.b", Value::Number(5.0)),
+        ("const copy = { ...[\"x\"] }; copy[0]", string("x")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_destructuring_errors() {
+    let cases = vec![
+        ("const { a } = null;", "TypeError: Cannot destructure property 'a' of 'null' as it is null."),
+        ("const {} = [][0];", "TypeError: Cannot destructure 'undefined' as it is undefined."),
+        ("function f({ a }) {} f()", "TypeError: Cannot destructure property 'a' of 'undefined' as it is undefined."),
+        ("const [a] = 1;", "TypeError: 1 is not iterable"),
+        ("const [a, a2] = [1, 2]; const { a } = { a: 1 };", "SyntaxError: Identifier 'a' has already been declared"),
+        ("const [c] = [1]; [c] = [2];", "TypeError: Assignment to constant variable."),
+        ("for (const x of 5) {}", "TypeError: 5 is not iterable"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_throw_and_catch() {
+    let cases = vec![
+        ("try { throw 1; } catch (e) { e + 1 }", Value::Number(2.0)),
+        ("const o = {}; let caught; try { throw o; } catch (e) { caught = e; } caught === o", Value::Boolean(true)),
+        ("try { throw new Error(\"boom\"); } catch (e) { e.message }", string("boom")),
+        ("try { null.x; } catch ({ name, message }) { name + \": \" + message }", string("TypeError: Cannot read properties of null (reading 'x')")),
+        ("try { missing; } catch (e) { e instanceof ReferenceError }", Value::Boolean(true)),
+        ("try { new Array(-1); } catch (e) { e.constructor === RangeError }", Value::Boolean(true)),
+        ("try { new Function(\"(\"); } catch (e) { e instanceof SyntaxError }", Value::Boolean(true)),
+        ("let ok = false; try { [1].map(() => { throw 2; }); } catch { ok = true; } ok", Value::Boolean(true)),
+        ("function f() { throw new TypeError(\"inner\"); } function g() { f(); } try { g(); } catch (e) { e.message }", string("inner")),
+        ("let e = \"outer\"; try { throw 1; } catch (e) { e = 2; } e", string("outer")),
+        ("var v = 1; try { var v = 2; } catch {} v", Value::Number(2.0)),
+        ("try { try { throw 1; } catch (e) { throw e + 1; } } catch (e) { e }", Value::Number(2.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_finally() {
+    let cases = vec![
+        ("const log = []; try { log.push(1); } finally { log.push(2); } log.join()", string("1,2")),
+        ("const log = []; try { try { throw new Error(\"a\"); } finally { log.push(1); } } catch (e) { log.push(e.message); } log.join()", string("1,a")),
+        ("function f() { try { return 1; } finally { return 2; } } f()", Value::Number(2.0)),
+        ("function f() { try { throw 1; } finally { return 2; } } f()", Value::Number(2.0)),
+        ("const log = []; function f() { try { return \"r\"; } finally { log.push(\"f\"); } } f() + log.join()", string("rf")),
+        ("const s = []; for (const x of [1, 2, 3]) { try { if (x === 2) continue; s.push(x); } finally { s.push(0); } } s.join()", string("1,0,0,3,0")),
+        ("try { try { throw 1; } finally { try { throw 2; } catch {} } } catch (e) { e }", Value::Number(1.0)),
+        ("try { try { throw 1; } finally { throw 2; } } catch (e) { e }", Value::Number(2.0)),
+        ("let n = 0; while (true) { try { break; } finally { n++; } } n", Value::Number(1.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_error_objects() {
+    let cases = vec![
+        ("new Error(\"m\").toString()", string("Error: m")),
+        ("new TypeError().toString()", string("TypeError")),
+        ("TypeError(\"no new\") instanceof TypeError", Value::Boolean(true)),
+        ("new RangeError(\"r\") instanceof Error && Object.getPrototypeOf(RangeError) === Error", Value::Boolean(true)),
+        ("const e = new Error(\"m\"); Object.keys(e).length + \",\" + e.hasOwnProperty(\"message\")", string("0,true")),
+        ("new Error().hasOwnProperty(\"message\") + \",\" + Error.prototype.message", string("false,")),
+        ("new Error(\"m\", { cause: 42 }).cause", Value::Number(42.0)),
+        ("new Error(\"m\", {}).hasOwnProperty(\"cause\")", Value::Boolean(false)),
+        ("Object.prototype.toString.call(new SyntaxError())", string("[object Error]")),
+        ("Error.prototype.toString.call({ name: \"Custom\", message: \"msg\" })", string("Custom: msg")),
+        ("Error.prototype.toString.call({ name: \"\", message: \"only message\" })", string("only message")),
+        ("ReferenceError.prototype.name + \",\" + ReferenceError.name + \",\" + Error.length", string("ReferenceError,ReferenceError,1")),
+        (
+            "class ValidationError extends Error { constructor(message) { super(message); this.name = \"ValidationError\"; } }
+             const e = new ValidationError(\"bad\"); (e instanceof ValidationError) + \",\" + (e instanceof Error) + \",\" + e",
+            string("true,true,ValidationError: bad"),
+        ),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_stack_traces() {
+    let cases = vec![
+        (
+            "function inner() { return new Error(\"boom\"); }\nfunction outer() {\n  return inner();\n}\nouter().stack",
+            "Error: boom\n    at inner (<anonymous>:1:27)\n    at outer (<anonymous>:3:10)\n    at <anonymous>:5:1",
+        ),
+        (
+            "class A { constructor() { this.error = new TypeError(\"t\"); } }\nnew A().error.stack",
+            "TypeError: t\n    at new A (<anonymous>:1:40)\n    at <anonymous>:2:1",
+        ),
+        (
+            "const f = () => {\n  null.x;\n};\nlet stack;\ntry { f(); } catch (e) { stack = e.stack; }\nstack",
+            "TypeError: Cannot read properties of null (reading 'x')\n    at f (<anonymous>:2:8)\n    at <anonymous>:5:7",
+        ),
+        // Errors raised after an earlier call point at what raised them
+        (
+            "function noop() {}\nfunction inner() {\n  noop();\n  const o = null;\n  return o.field;\n}\nlet stack;\ntry { inner(); } catch (e) { stack = e.stack; }\nstack",
+            "TypeError: Cannot read properties of null (reading 'field')\n    at inner (<anonymous>:5:12)\n    at <anonymous>:8:7",
+        ),
+        (
+            "let stack;\ntry { null.x; } catch {}\ntry {\n  undefined.y = 1;\n} catch (e) { stack = e.stack; }\nstack",
+            "TypeError: Cannot set properties of undefined (setting 'y')\n    at <anonymous>:4:13",
+        ),
+        (
+            "function f() {\n  Math.max(1);\n  return missing + 1;\n}\nlet stack;\ntry { f(); } catch (e) { stack = e.stack; }\nstack",
+            "ReferenceError: missing is not defined\n    at f (<anonymous>:3:10)\n    at <anonymous>:6:7",
+        ),
+        (
+            "function f(a) {\n  String(a);\n  return a + 1n;\n}\nlet stack;\ntry { f(1); } catch (e) { stack = e.stack; }\nstack",
+            "TypeError: Cannot mix BigInt and other types, use explicit conversions\n    at f (<anonymous>:3:12)\n    at <anonymous>:6:7",
+        ),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(string(expected)), "{}", input);
+    }
+
+    // Only the innermost frames are kept
+    let expected = format!("RangeError: Maximum call stack size exceeded{}", "\n    at r (<anonymous>:1:16)".repeat(10));
+    let input = "function r() { r(); }\nlet stack;\ntry { r(); } catch (e) { stack = e.stack; }\nstack";
+    assert_eq!(run(input), Ok(string(&expected)));
+}
+
+#[test]
+fn test_uncaught_errors() {
+    let cases = vec![
+        ("throw new RangeError(\"r\");", "RangeError: r"),
+        ("throw new Error();", "Error"),
+        ("class E extends Error {} throw new E(\"e\");", "Error: e"),
+        ("throw 5;", "Uncaught 5"),
+        ("throw \"text\";", "Uncaught text"),
+        ("throw {};", "Uncaught #<Object>"),
+        ("function f() { throw new TypeError(\"deep\"); } [1].forEach(f);", "TypeError: deep"),
+        ("try { throw 1; } catch (e) { let e = 2; }", "SyntaxError: Identifier 'e' has already been declared"),
+        ("Error.prototype.toString.call(1)", "TypeError: Method Error.prototype.toString called on incompatible receiver 1"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use common::run;
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_function_declarations_and_calls() {
+    let cases = vec![
+        ("function add(a: number, b: number): number { return a + b; } add(2, 3)", 5.0),
+        ("let r = twice(4); function twice(x) { return x * 2; } r", 8.0),
+        ("const square = function (x) { return x * x; }; square(7)", 49.0),
+        ("function fact(n) { return n <= 1 ? 1 : n * fact(n - 1); } fact(10)", 3628800.0),
+        ("const f = function g(n) { return n ? g(n - 1) + 1 : 0; }; f(3)", 3.0),
+        ("let nothing; function noReturn() {} noReturn() === nothing ? 1 : 0", 1.0),
+        ("function id(x: string): string; function id(x: number): number; function id(x: any) { return x; } id(9)", 9.0),
+        ("type Pair<T> = [T, T]; let p!: Pair<number>; p = [1, 2]; p[0] + p[1]", 3.0),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::Number(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_parameters() {
+    let cases = vec![
+        ("function f(a, b = a + 1) { return a + b; } f(1)", 3.0),
+        ("let nothing; function f(a, b = 10) { return b; } f(1, nothing)", 10.0),
+        ("function f(a, ...rest) { return rest.length; } f(1, 2, 3, 4)", 3.0),
+        ("function f(...rest) { return rest[1]; } f(1, 2, 3)", 2.0),
+        ("let nothing; function f(a, b) { return b; } f(1) === nothing ? 1 : 0", 1.0),
+        ("function f(a, b = 2, c) {} f.length", 1.0),
+        ("function f() { return arguments.length + arguments[1]; } f(1, 10, 100)", 13.0),
+        // Closures in parameters see the parameters, not the body's `var`s
+        ("function f(a, g = () => a) { var a = 5; return a * 10 + g(); } f(1)", 51.0),
+        ("function f(a, b = 2) { var a; return a + b; } f(1)", 3.0),
+        ("function f({ a }, g = () => a) { var a = 4; a += 1; return a * 10 + g(); } f({ a: 2 })", 52.0),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::Number(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_closures() {
+    let source = "
+        function counter() {
+            let count = 0;
+            return function () { count += 1; return count; };
+        }
+        const a = counter();
+        const b = counter();
+        a(); a(); b();
+        a() * 10 + b()
+    ";
+    assert_eq!(run(source), Ok(Value::Number(32.0)));
+
+    // Each loop iteration has its own `i`
+    let source = "
+        let getter;
+        for (let i = 0; i < 3; i++) {
+            if (i == 1) { getter = function () { return i; }; }
+        }
+        getter()
+    ";
+    assert_eq!(run(source), Ok(Value::Number(1.0)));
+}
+
+#[test]
+fn test_call_errors() {
+    assert_eq!(
+        run("let x = 1; x();"),
+        Err("TypeError: x is not a function".to_string())
+    );
+    assert_eq!(
+        run("function f() { return f(); } f();"),
+        Err("RangeError: Maximum call stack size exceeded".to_string())
+    );
+
+    let cases = vec![
+        ("(1)()", "TypeError: 1 is not a function"),
+        ("new (1)()", "TypeError: 1 is not a constructor"),
+        ("\"x\".foo()", "TypeError: \"x\".foo is not a function"),
+        ("({ a: {} }).a.b()", "TypeError: (intermediate value).a.b is not a function"),
+        ("function f() {} f()()", "TypeError: f(...) is not a function"),
+        ("[1, 2]()", "TypeError: array is not a function"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_function_source_text() {
+    let cases = vec![
+        ("function add(a, b) { return a + b; } add.toString()", "function add(a, b) { return a + b; }"),
+        ("(async function f() { await 1; }).toString()", "async function f() { await 1; }"),
+        ("((x, y) => x + y).toString()", "(x, y) => x + y"),
+        ("(async x => x).toString()", "async x => x"),
+        ("Object.getOwnPropertyDescriptor({ get a() { return 1; } }, 'a').get.toString()", "get a() { return 1; }"),
+        ("({ *g() {} }).g.toString()", "*g() {}"),
+        ("class A { static m(x) { return x; } } A.m.toString()", "m(x) { return x; }"),
+        ("class A extends Object { } A.toString()", "class A extends Object { }"),
+        ("new Function('a', 'return a').toString()", "function anonymous(a\n) {\nreturn a\n}"),
+        ("Math.max.toString()", "function max() { [native code] }"),
+        ("(function () {}).bind(null).toString()", "function bound () { [native code] }"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input).map(|value| value.to_js_string()), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_arrow_functions() {
+    let cases = vec![
+        ("const inc = x => x + 1; inc(1)", 2.0),
+        ("const add = (a, b) => a + b; add(2, 3)", 5.0),
+        ("const typed = (a: number, b: number = 2): number => a * b; typed(4)", 8.0),
+        ("const block = (n) => { let doubled = n * 2; return doubled + 1; }; block(5)", 11.0),
+        ("const none = () => 42; none()", 42.0),
+        ("const rest = (...items) => items.length; rest(1, 2, 3)", 3.0),
+        ("const curried = a => b => c => a + b + c; curried(1)(2)(3)", 6.0),
+        ("const generic = <T>(value: T): T => value; generic(7)", 7.0),
+        ("const id = <T,>(x: T): T => x; id(8)", 8.0),
+        ("(1 + 2) * (3)", 9.0),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::Number(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_arrow_functions_capture_lexical_bindings() {
+    // `arguments` inside an arrow is the enclosing function's
+    let source = "
+        function outer() {
+            const inner = () => arguments.length;
+            return inner(1, 2, 3, 4);
+        }
+        outer(1, 2)
+    ";
+    assert_eq!(run(source), Ok(Value::Number(2.0)));
+
+    let source = "
+        function outer() {
+            const arrow = () => this;
+            return arrow();
+        }
+        let nothing;
+        outer() === nothing
+    ";
+    assert_eq!(run(source), Ok(Value::Boolean(true)));
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::{Interpreter, Value};
+use ts_interpreter_rs::parser::Parser;
+
+#[test]
+fn test_generators() {
+    let cases = vec![
+        ("function* g() { yield 1; yield 2; } [...g()].join()", string("1,2")),
+        ("function* g() { const x = yield 1; yield x * 2; } const it = g(); it.next(); it.next(5).value", Value::Number(10.0)),
+        ("function* g() { yield 1; return 3; } const it = g(); [it.next().done, it.next().value, it.next().value].join()", string("false,3,")),
+        ("function* g(a, b = 2) { yield a + b; } g(1).next().value", Value::Number(3.0)),
+        ("function* g() { yield arguments.length; } g(1, 2, 3).next().value", Value::Number(3.0)),
+        ("function* fib() { let [a, b] = [0, 1]; while (true) { yield a; [a, b] = [b, a + b]; } } const out = []; for (const n of fib()) { if (n > 20) break; out.push(n); } out.join()", string("0,1,1,2,3,5,8,13")),
+        ("const o = { *items() { yield this.x; } , x: 7 }; o.items().next().value", Value::Number(7.0)),
+        ("class C { constructor() { this.v = [1, 2]; } *[Symbol.iterator]() { yield* this.v; } } [...new C()].join()", string("1,2")),
+        ("const g = function* () { yield [{ a: 1 }, yield 2]; }; const it = g(); it.next(); it.next(3).value[1]", Value::Number(3.0)),
+        // The body only starts running on the first `next`
+        ("let started = false; function* g() { started = true; } const it = g(); const before = started; it.next(); [before, started].join()", string("false,true")),
+        ("function* g() { yield 1; } const it = g(); it[Symbol.iterator]() === it", Value::Boolean(true)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_delegation() {
+    let cases = vec![
+        ("function* inner() { yield 1; return 'r'; } function* outer() { const r = yield* inner(); yield r; } [...outer()].join()", string("1,r")),
+        ("function* g() { yield* 'ab'; yield* [1, 2]; } [...g()].join()", string("a,b,1,2")),
+        ("function* inner() { const x = yield 1; yield x; } function* outer() { yield* inner(); } const it = outer(); it.next(); it.next('sent').value", string("sent")),
+        ("function* inner() { try { yield 1; } catch (e) { yield 'caught ' + e; } } function* outer() { yield* inner(); } const it = outer(); it.next(); it.throw('x').value", string("caught x")),
+        ("let closed = false; function* inner() { try { yield 1; } finally { closed = true; } } function* outer() { yield* inner(); } const it = outer(); it.next(); [it.return(5).value, closed].join()", string("5,true")),
+        ("function* deep(n) { if (n > 0) { yield* deep(n - 1); } else { yield 'bottom'; } } deep(500).next().value", string("bottom")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_return_and_throw() {
+    let cases = vec![
+        ("function* g() { yield 1; yield 2; } const it = g(); it.next(); [it.return(9).value, it.next().done].join()", string("9,true")),
+        ("function* g() { try { yield 1; } finally { yield 'cleanup'; } } const it = g(); it.next(); const r = it.return(9); [r.value, r.done, it.next().value].join()", string("cleanup,false,9")),
+        ("function* g() { try { yield 1; } catch (e) { yield 'caught ' + e; } } const it = g(); it.next(); it.throw('x').value", string("caught x")),
+        ("function* g() { try { yield 1; } catch (e) { } } const it = g(); it.return(2).value", Value::Number(2.0)),
+        // A generator that has not started completes without running its body
+        ("let ran = false; function* g() { ran = true; } const it = g(); try { it.throw(new Error('e')); } catch (e) {} [ran, it.next().done].join()", string("false,true")),
+        ("function* g() { try { yield 1; } finally { return 'override'; } } const it = g(); it.next(); it.return(5).value", string("override")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_generator_objects() {
+    let cases = vec![
+        ("function* g() {} Object.getPrototypeOf(g()) === g.prototype", Value::Boolean(true)),
+        ("function* g() {} Object.getPrototypeOf(g.prototype) === Object.getPrototypeOf(function* () {}.prototype)", Value::Boolean(true)),
+        ("function* g() {} Object.prototype.toString.call(g())", string("[object Generator]")),
+        ("function* g() {} g.prototype.hasOwnProperty('constructor')", Value::Boolean(false)),
+        ("Object.getOwnPropertyNames(Object.getPrototypeOf(function* () {}.prototype)).join()", string("constructor,next,return,throw")),
+        ("const GeneratorFunction = Object.getPrototypeOf(function* () {}).constructor; [GeneratorFunction.name, [...new GeneratorFunction('a', 'yield a; yield a * 2')(3)].join()].join()", string("GeneratorFunction,3,6")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_generator_errors() {
+    let cases = vec![
+        ("function* g() { it.next(); } const it = g(); it.next()", "TypeError: Generator is already running"),
+        ("function* g() {} new g()", "TypeError: g is not a constructor"),
+        ("function* g() {} g().next.call({})", "TypeError: Method [Generator].prototype.next called on incompatible receiver #<Object>"),
+        ("function* g() { yield 1; } const it = g(); it.next(); it.throw(new TypeError('boom'))", "TypeError: boom"),
+        ("function* g() { yield* { [Symbol.iterator]() { return { next() { return { done: false }; } }; } }; } const it = g(); it.next(); it.throw(1)", "TypeError: The iterator does not provide a 'throw' method."),
+        ("function* g() { yield* g(); } g().next()", "RangeError: Maximum call stack size exceeded"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+/// Values a suspended generator holds only in the middle of evaluating an
+/// expression survive garbage collection.
+#[test]
+fn test_suspended_generators_survive_collection() {
+    let mut interpreter = Interpreter::new();
+    let source = "function* g() { const a = [{ x: 1 }, yield 1, { y: 5 }.y + (yield 2)]; return a[0].x + a[1] + a[2]; } var it = g(); it.next();";
+    interpreter.run(Parser::new(source).parse_program().unwrap()).unwrap();
+    interpreter.collect_garbage();
+    interpreter.run(Parser::new("it.next(10);").parse_program().unwrap()).unwrap();
+    interpreter.collect_garbage();
+    let result = interpreter.run(Parser::new("it.next(100).value").parse_program().unwrap());
+    assert_eq!(result, Ok(Value::Number(116.0)));
+}
+
+/// A suspended generator resumes after the interpreter is moved.
+#[test]
+fn test_generators_resume_after_the_interpreter_moves() {
+    let mut interpreter = Interpreter::new();
+    let source = "function* g() { let total = 0; while (true) total += yield total; } var it = g(); it.next();";
+    interpreter.run(Parser::new(source).parse_program().unwrap()).unwrap();
+    let mut moved = vec![interpreter];
+    let result = moved[0].run(Parser::new("it.next(2); it.next(3).value").parse_program().unwrap());
+    assert_eq!(result, Ok(Value::Number(5.0)));
+
+    let mut interpreter = *Box::new(moved.pop().unwrap());
+    let result = interpreter.run(Parser::new("it.next(4).value").parse_program().unwrap());
+    assert_eq!(result, Ok(Value::Number(9.0)));
+}
+
+/// Tens of thousands of generators can be suspended at once.
+#[test]
+fn test_many_suspended_generators() {
+    let source = "
+        function* count(i) { yield i; yield i + 1; }
+        const generators = Array.from({ length: 41000 }, (_, i) => count(i));
+        let total = 0;
+        for (const it of generators) total += it.next().value;
+        for (const it of generators) total += it.next().value;
+        total
+    ";
+    assert_eq!(run(source), Ok(Value::Number(1681000000.0)));
+}
+
+/// Expressions nested more deeply than a generator's stack holds move on
+/// to a grown stack, rather than running over the stack of another body.
+#[test]
+fn test_deep_expressions() {
+    let terms = vec!["k"; 3000].join(" + ");
+    let source = format!("const k = 1; {}", terms);
+    assert_eq!(run(&source), Ok(Value::Number(3000.0)));
+
+    let source = format!(
+        "function* g(k) {{ yield 1; return {}; }}
+        const a = g(1), b = g(2);
+        a.next(); b.next();
+        [a.next().value, b.next().value].join()",
+        terms
+    );
+    assert_eq!(run(&source), Ok(string("3000,6000")));
+}
+
+/// An exception whose message reads like a generator's `return` is still
+/// caught.
+#[test]
+fn test_errors_named_like_generator_returns_are_caught() {
+    let source = "let caught = false; try { const e = new Error(); e.name = 'generator return'; throw e; } catch { caught = true; } caught";
+    assert_eq!(run(source), Ok(Value::Boolean(true)));
+    let source = "function* g() { try { yield 1; } finally { throw 'cleanup'; } } const it = g(); it.next(); let caught; try { it.return(5); } catch (e) { caught = e; } caught";
+    assert_eq!(run(source), Ok(string("cleanup")));
+}
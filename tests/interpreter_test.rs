@@ -1,3 +1,6 @@
+mod common;
+
+use common::run;
 use ts_interpreter_rs::interpreter::*;
 use ts_interpreter_rs::parser::*;
 use std::fs;
@@ -48,7 +51,7 @@ fn test_string_operations() {
     
     assert_eq!(
         interpreter.get_last_value(),
-        Some(&Value::String("Hello, World".to_string()))
+        Some(&Value::String("Hello, World".into()))
     );
 }
 
@@ -78,13 +81,276 @@ fn test_evaluate_input_file() {
     assert!(interpreter.get_last_value().is_some(), 
         "Should have evaluated at least one expression");
 
+    // The class example at the end of the file ran
+    assert_eq!(
+        interpreter.get_variables().get("description"),
+        Some(&Value::String("Ann is 20 years old".into()))
+    );
+
     // Verify all variables in the context are valid
     for (_name, value) in interpreter.get_variables() {
         match value {
             Value::Number(n) => assert!(!n.is_nan(), "Number values should be valid"),
             Value::String(s) => assert!(!s.is_empty(), "String values should not be empty"),
             Value::Boolean(_) => {},
-            Value::Null => {},
+            Value::Null | Value::Undefined => {},
+            Value::BigInt(_) | Value::Symbol(_) | Value::Object(_) | Value::Function(_) => {},
         }
     }
 }
+
+
+#[test]
+fn test_declarations() {
+    let cases = vec![
+        ("const a = 1, b = a + 1; b", Value::Number(2.0)),
+        ("let x; x", Value::Undefined),
+        ("var v = 1; var v; v", Value::Number(1.0)),
+        ("let total: number = 40; total + 2", Value::Number(42.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_assignment_operators() {
+    let cases = vec![
+        ("let x = 1; x = x + 1; x", Value::Number(2.0)),
+        ("let a; let b; a = b = 3; a + b", Value::Number(6.0)),
+        ("let x = 10; x -= 4; x *= 2; x /= 3; x", Value::Number(4.0)),
+        ("let x = 2; x **= 3; x %= 5; x", Value::Number(3.0)),
+        ("let x = 5; x <<= 2; x |= 1; x >>= 1; x", Value::Number(10.0)),
+        ("let s = \"a\"; s += 1; s", Value::String("a1".into())),
+        ("let i = 0; i++", Value::Number(0.0)),
+        ("let i = 0; ++i", Value::Number(1.0)),
+        ("let i = 5; i--; --i; i", Value::Number(3.0)),
+        ("let a = 0; let b = 1; a ||= 5; b ||= 6; a + b", Value::Number(6.0)),
+        ("let a = 1; let b = 0; a &&= 5; b &&= 6; a + b", Value::Number(5.0)),
+        ("let a = null; let b = 0; a ??= 5; b ??= 6; a + b", Value::Number(5.0)),
+        ("let n = 0; let a = 1; let b = null; a ||= n++; b &&= n++; a ??= n++; n", Value::Number(0.0)),
+        ("const c = 1; c ||= 2; c", Value::Number(1.0)),
+        ("const o = { x: 0 }; o.x ||= 7; o[\"y\"] ??= 8; o.x + o.y", Value::Number(15.0)),
+        ("let f; f ||= function () {}; f.name", Value::String("f".into())),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_const_and_tdz_errors() {
+    let cases = vec![
+        ("const x = 1; x = 2;", "TypeError: Assignment to constant variable."),
+        ("const x = 1; x++;", "TypeError: Assignment to constant variable."),
+        ("let x = x + 1;", "ReferenceError: Cannot access 'x' before initialization"),
+        ("y = 1;", "ReferenceError: y is not defined"),
+        ("let x = 1; let x = 2;", "SyntaxError: Identifier 'x' has already been declared"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_block_scopes() {
+    let cases = vec![
+        ("let x = 1; { let x = 2; x = 3; } x", Value::Number(1.0)),
+        ("let x = 1; { x = 2; } x", Value::Number(2.0)),
+        ("const x = 1; { const x = 2; { let y = x; y } }", Value::Number(2.0)),
+        ("{ var v = 5; } v", Value::Number(5.0)),
+        ("let r = v; var v = 1; r", Value::Undefined),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+
+    let errors = vec![
+        ("{ let inner = 1; } inner", "ReferenceError: inner is not defined"),
+        ("x; let x = 1;", "ReferenceError: Cannot access 'x' before initialization"),
+        ("let x = 1; { x; let x = 2; }", "ReferenceError: Cannot access 'x' before initialization"),
+        ("let x = 1; var x = 2;", "SyntaxError: Identifier 'x' has already been declared"),
+    ];
+
+    for (input, expected) in errors {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_get_variables_reports_global_scope() {
+    let mut interpreter = Interpreter::new();
+    let mut parser = Parser::new("let outer = 1; { let inner = 2; var hoisted = 3; }");
+    interpreter.run(parser.parse_program().unwrap()).unwrap();
+
+    let variables = interpreter.get_variables();
+    assert_eq!(variables.get("outer"), Some(&Value::Number(1.0)));
+    assert_eq!(variables.get("hoisted"), Some(&Value::Number(3.0)));
+    assert_eq!(variables.get("inner"), None);
+}
+
+#[test]
+fn test_comparison_and_logical_operators() {
+    let cases = vec![
+        ("1 < 2 && 2 <= 2", Value::Boolean(true)),
+        ("\"10\" == 10", Value::Boolean(true)),
+        ("\"10\" === 10", Value::Boolean(false)),
+        ("null == 0", Value::Boolean(false)),
+        ("\"b\" > \"a\"", Value::Boolean(true)),
+        ("0 || \"fallback\"", Value::String("fallback".into())),
+        ("1 > 2 ? \"yes\" : \"no\"", Value::String("no".into())),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_undefined_typeof_and_void() {
+    let cases = vec![
+        ("undefined", Value::Undefined),
+        ("({}).missing", Value::Undefined),
+        ("(function () {})()", Value::Undefined),
+        ("let x; x", Value::Undefined),
+        ("void 0", Value::Undefined),
+        ("let n = 0; void n++; n", Value::Number(1.0)),
+        ("typeof notDeclared", Value::String("undefined".into())),
+        ("typeof null + typeof undefined", Value::String("objectundefined".into())),
+        ("typeof 1n + typeof Symbol() + typeof class {}", Value::String("bigintsymbolfunction".into())),
+        ("null == undefined && null !== undefined", Value::Boolean(true)),
+        ("undefined == 0 || undefined == false || null == false", Value::Boolean(false)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+    assert_eq!(run("typeof x; let x = 1;"), Err("ReferenceError: Cannot access 'x' before initialization".to_string()));
+}
+
+#[test]
+fn test_optional_chaining_and_nullish_coalescing() {
+    let cases = vec![
+        ("const o = { a: { b: () => 42 } }; o?.a.b()", Value::Number(42.0)),
+        ("const o = {}; o.a?.b.c.d", Value::Undefined),
+        ("const o = { a: null }; o.a?.[0]", Value::Undefined),
+        ("let n = 0; const o = {}; o.a?.[n++]; o.a?.f(n++); n", Value::Number(0.0)),
+        ("const o = { m() { return this; } }; o.m?.() === o", Value::Boolean(true)),
+        ("const o = {}; o.missing?.()", Value::Undefined),
+        ("const o = { a: null }; delete o.a?.b", Value::Boolean(true)),
+        ("class P { #x = 1; static get(p) { return p?.#x; } } P.get(new P())", Value::Number(1.0)),
+        ("null ?? \"default\"", Value::String("default".into())),
+        ("0 ?? 1", Value::Number(0.0)),
+        ("\"\" ?? \"x\"", Value::String("".into())),
+        ("(null || undefined) ?? 2", Value::Number(2.0)),
+        ("true ? .5 : 1", Value::Number(0.5)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+    assert_eq!(run("const o = { list: [] }; o.list?.foo()"), Err("TypeError: o.list?.foo is not a function".to_string()));
+    assert_eq!(run("const o = {}; (o.a?.b).c"), Err("TypeError: Cannot read properties of undefined (reading 'c')".to_string()));
+}
+
+#[test]
+fn test_template_literals() {
+    let cases = vec![
+        ("const name = \"Ann\"; `hi ${name}!`", "hi Ann!"),
+        ("`${1 + 1}${\"\"}${[1, 2]}`", "21,2"),
+        ("`a${`b${\"c\"}`}d`", "abcd"),
+        ("`${ { a: 1 }.a }}`", "1}"),
+        ("`line\\u0041\\x42\\``", "lineAB`"),
+        ("`one\ntwo`.length", "7"),
+        ("`${{ toString() { return \"s\"; }, valueOf() { return 1; } }}`", "s"),
+        ("let t: `id-${number}` = `id-${7}`; t", "id-7"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input).map(|value| value.to_js_string()), Ok(expected.to_string()), "{}", input);
+    }
+
+    assert_eq!(run("`${Symbol()}`"), Err("TypeError: Cannot convert a Symbol value to a string".to_string()));
+    assert_eq!(run("`\\x4`"), Err("Invalid hexadecimal escape sequence".to_string()));
+    assert_eq!(run("`\\01`"), Err("Octal escape sequences are not allowed in template strings.".to_string()));
+    assert_eq!(run("`open ${1}"), Err("Unterminated template literal".to_string()));
+}
+
+#[test]
+fn test_tagged_templates() {
+    let cases = vec![
+        ("function tag(s, ...v) { return s.join(\"|\") + \":\" + v.join(); } tag`a${1}b${2}`", "a|b|:1,2"),
+        ("((s) => s.raw[0] + s[0])`\\t`", "\\t\t"),
+        ("((s) => s[0] === undefined && s.raw[0])`\\unicode`", "\\unicode"),
+        ("((s) => Object.isFrozen(s) && Object.isFrozen(s.raw) && !Object.keys(s).includes(\"raw\"))`x`", "true"),
+        ("const seen = []; for (let i = 0; i < 2; i++) seen.push(((s) => s)`a`); seen[0] === seen[1]", "true"),
+        ("((s) => s)`a` === ((s) => s)`a`", "false"),
+        ("const o = { t() { return this === o; } }; o.t`x`", "true"),
+        ("const id = <T>(s: T) => s; id<TemplateStringsArray>`a`.length", "1"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input).map(|value| value.to_js_string()), Ok(expected.to_string()), "{}", input);
+    }
+
+    assert_eq!(run("const n = 1; n`x`"), Err("TypeError: n is not a function".to_string()));
+    assert_eq!(run("const o = {}; o?.f`x`"), Err("Invalid tagged template on optional chain".to_string()));
+}
+
+#[test]
+fn test_loops() {
+    let cases = vec![
+        ("let n = 0; while (n < 5) n++; n", 5.0),
+        ("let n = 10; do { n++; } while (n < 5); n", 11.0),
+        ("let sum = 0; for (let i = 1; i <= 10; i++) { sum += i; } sum", 55.0),
+        ("let sum = 0; for (let i = 0; i < 10; i++) { if (i % 2) continue; if (i > 6) break; sum += i; } sum", 12.0),
+        (
+            "let count = 0;
+             outer: for (let i = 0; i < 5; i++) {
+                 for (let j = 0; j < 5; j++) {
+                     if (j == 3) continue outer;
+                     if (i == 3) break outer;
+                     count++;
+                 }
+             }
+             count",
+            9.0,
+        ),
+        ("let n = 0; block: { n = 1; break block; n = 2; } n", 1.0),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::Number(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_switch() {
+    let source = |value: &str| format!(
+        "let out = \"\";
+         switch ({}) {{
+             case 1: out += \"one\";
+             case 2: out += \"two\"; break;
+             default: out += \"other\";
+             case 3: out += \"three\";
+         }}
+         out",
+        value
+    );
+
+    let cases = vec![
+        ("1", "onetwo"),
+        ("2", "two"),
+        ("3", "three"),
+        ("4", "otherthree"),
+        ("\"1\"", "otherthree"),
+    ];
+
+    for (value, expected) in cases {
+        assert_eq!(run(&source(value)), Ok(Value::String(expected.into())), "{}", value);
+    }
+}
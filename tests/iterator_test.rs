@@ -0,0 +1,92 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_iteration_protocol() {
+    let cases = vec![
+        ("const it = { [Symbol.iterator]() { let i = 0; return { next: () => ({ value: i++, done: i > 3 }) }; } }; [...it].join()", string("0,1,2")),
+        ("const out = []; for (const c of 'a😀') out.push(c); out.length", Value::Number(2.0)),
+        ("const [a, , b] = 'xyz'; a + b", string("xz")),
+        ("const it = [1, 2].values(); [it.next().value, it.next().value, it.next().done].join()", string("1,2,true")),
+        ("[...['a', 'b'].entries()].join(';')", string("0,a;1,b")),
+        ("[...Array(2).keys()].join()", string("0,1")),
+        ("Array.prototype[Symbol.iterator] === Array.prototype.values", Value::Boolean(true)),
+        ("Map.prototype[Symbol.iterator] === Map.prototype.entries", Value::Boolean(true)),
+        ("function f() { return [...arguments]; } f(1, 2).join()", string("1,2")),
+        ("Array.from({ length: 2, 0: 'a', 1: 'b' }).join()", string("a,b")),
+        ("Array.from({ *[Symbol.iterator]() { yield 1; yield 2; } }, x => x * 3).join()", string("3,6")),
+        // Arrays are read as they are iterated
+        ("const a = [1]; const out = []; for (const x of a) { out.push(x); if (a.length < 3) a.push(x + 1); } out.join()", string("1,2,3")),
+        ("const it = [][Symbol.iterator](); Object.getPrototypeOf(Object.getPrototypeOf(it)) === Iterator.prototype", Value::Boolean(true)),
+        ("Object.prototype.toString.call([].values())", string("[object Array Iterator]")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_iterator_closing() {
+    let counter = "let closed = 0; const iterable = { [Symbol.iterator]() { let i = 0; return { next: () => ({ value: i++, done: false }), return() { closed++; return {}; } }; } };";
+    let cases = vec![
+        ("for (const x of iterable) { if (x > 2) break; } closed", 1.0),
+        ("try { for (const x of iterable) { throw 1; } } catch (e) {} closed", 1.0),
+        ("(() => { for (const x of iterable) { return x; } })(); closed", 1.0),
+        ("const [a, b] = iterable; closed", 1.0),
+        ("const [...all] = [1, 2]; closed", 0.0),
+        ("function* g() { for (const x of iterable) yield x; } const it = g(); it.next(); it.return(); closed", 1.0),
+        ("outer: for (const a of [1]) { for (const x of iterable) { continue outer; } } closed", 1.0),
+    ];
+
+    for (input, expected) in cases {
+        let source = format!("{} {}", counter, input);
+        assert_eq!(run(&source), Ok(Value::Number(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_iterator_helpers() {
+    let cases = vec![
+        ("[1, 2, 3].values().map((x, i) => x * 10 + i).toArray().join()", string("10,21,32")),
+        ("[1, 2, 3, 4].values().filter(x => x % 2).toArray().join()", string("1,3")),
+        ("function* n() { let i = 0; while (true) yield i++; } n().drop(2).take(3).toArray().join()", string("2,3,4")),
+        ("['ab', 'c'].values().flatMap(s => s.split('')).toArray().join()", string("a,b,c")),
+        ("[1, 2, 3].values().reduce((a, b) => a + b)", Value::Number(6.0)),
+        ("[1, 2, 3].values().reduce((a, b) => a + b, 10)", Value::Number(16.0)),
+        ("[1, 2, 3].values().some(x => x > 2)", Value::Boolean(true)),
+        ("[1, 2, 3].values().every(x => x > 2)", Value::Boolean(false)),
+        ("[1, 2, 3].values().find(x => x > 1)", Value::Number(2.0)),
+        ("const out = []; [1, 2].values().forEach((x, i) => out.push(x + i)); out.join()", string("1,3")),
+        ("Iterator.from({ i: 0, next() { return { value: this.i++, done: this.i > 3 }; } }).toArray().join()", string("0,1,2")),
+        ("const it = [1].values(); Iterator.from(it) === it", Value::Boolean(true)),
+        ("class Count extends Iterator { n = 0; next() { return { value: this.n++, done: this.n > 3 }; } } new Count().map(x => x * 2).toArray().join()", string("0,2,4")),
+        ("let closed = false; const it = { next: () => ({ value: 1, done: false }), return() { closed = true; return {}; }, __proto__: Iterator.prototype }; it.take(1).toArray(); closed", Value::Boolean(true)),
+        ("Iterator.prototype.constructor === Iterator", Value::Boolean(true)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_iteration_errors() {
+    let cases = vec![
+        ("for (const x of 1) {}", "TypeError: 1 is not iterable"),
+        ("[...{ [Symbol.iterator]() { return 1; } }]", "TypeError: Result of the Symbol.iterator method is not an object"),
+        ("[...{ [Symbol.iterator]() { return { next() { return 1; } }; } }]", "TypeError: Iterator result 1 is not an object"),
+        ("[].values().next.call({})", "TypeError: Method Array Iterator.prototype.next called on incompatible receiver #<Object>"),
+        ("Iterator.prototype.map.call(1, x => x)", "TypeError: Iterator.prototype.map called on non-object"),
+        ("[].values().take(-1)", "RangeError: -1 must be positive"),
+        ("[].values().reduce((a, b) => a)", "TypeError: Reduce of a done iterator with no initial value"),
+        ("new Iterator()", "TypeError: Abstract class Iterator not directly constructable"),
+        ("new Symbol()", "TypeError: Symbol is not a constructor"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
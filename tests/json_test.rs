@@ -0,0 +1,175 @@
+mod common;
+
+use common::run;
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_stringify() {
+    let cases = vec![
+        (r#"JSON.stringify({ a: 1, b: [1, "x", null, [][0], () => 1], c: [][0] })"#, r#"{"a":1,"b":[1,"x",null,null,null]}"#),
+        (r#"JSON.stringify([1e21, -0, 0 / 0, 1 / 0, 0.1 + 0.2, 1e-7])"#, "[1e+21,0,null,null,0.30000000000000004,1e-7]"),
+        (r#"JSON.stringify("q\"\n\u0001é")"#, r#""q\"\n\u0001é""#),
+        (r#"JSON.stringify(["\uD83D", "\uDE00\uD83D", "😀"])"#, r#"["\ud83d","\ude00\ud83d","😀"]"#),
+        (r#"JSON.stringify({ b: 1, a: 2, 1: 3 })"#, r#"{"1":3,"b":1,"a":2}"#),
+        (r#"JSON.stringify([new Number(3), new String("s")])"#, r#"[3,"s"]"#),
+        (r#"JSON.stringify({ toJSON(key) { return "key:" + key; } })"#, r#""key:""#),
+        (r#"JSON.stringify({ d: { toJSON(key) { return key; } } })"#, r#"{"d":"d"}"#),
+        (r#"JSON.stringify({ get a() { return 1; } })"#, r#"{"a":1}"#),
+        (r#"JSON.stringify(Object.defineProperty({}, "hidden", { value: 1 }))"#, "{}"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+
+    for input in ["JSON.stringify([][0])", "JSON.stringify(() => 1)"] {
+        assert_eq!(run(input), Ok(Value::Undefined), "{}", input);
+    }
+}
+
+#[test]
+fn test_stringify_replacer_and_indentation() {
+    let cases = vec![
+        (r#"JSON.stringify({ a: 1, b: { c: 2 } }, (k, v) => v > 0 ? v * 10 : v)"#, r#"{"a":10,"b":{"c":20}}"#),
+        (r#"JSON.stringify({ a: 1, b: 2 }, (k, v) => k === "a" ? [][0] : v)"#, r#"{"b":2}"#),
+        (r#"JSON.stringify({ a: 1, b: 2, c: { a: 3, z: 4 } }, ["a", "c", 1, "a"])"#, r#"{"a":1,"c":{"a":3}}"#),
+        (r#"JSON.stringify([{ a: 1, b: 2 }], ["b"])"#, r#"[{"b":2}]"#),
+        (r#"JSON.stringify({ a: [1, { b: 2 }], c: "x" }, null, "--")"#, "{\n--\"a\": [\n----1,\n----{\n------\"b\": 2\n----}\n--],\n--\"c\": \"x\"\n}"),
+        (r#"JSON.stringify({ a: [], b: {} }, null, 2)"#, "{\n  \"a\": [],\n  \"b\": {}\n}"),
+        (r#"JSON.stringify([1], null, 20)"#, "[\n          1\n]"),
+        (r#"JSON.stringify([1], null, "abcdefghijkl")"#, "[\nabcdefghij1\n]"),
+        (r#"JSON.stringify([1], null, new Number(1))"#, "[\n 1\n]"),
+        (r#"JSON.stringify([1], null, 0)"#, "[1]"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+}
+
+#[test]
+fn test_stringify_cycles() {
+    let cases = vec![
+        (
+            "const o = {}; o.self = o; JSON.stringify(o)",
+            "TypeError: Converting circular structure to JSON\n    --> starting at object with constructor 'Object'\n    --- property 'self' closes the circle",
+        ),
+        (
+            "class A { constructor() { this.list = [{ x: this }]; } } JSON.stringify(new A())",
+            "TypeError: Converting circular structure to JSON\n    --> starting at object with constructor 'A'\n    |     property 'list' -> object with constructor 'Array'\n    |     index 0 -> object with constructor 'Object'\n    --- property 'x' closes the circle",
+        ),
+        (
+            "const a = []; a.push(a); JSON.stringify(a)",
+            "TypeError: Converting circular structure to JSON\n    --> starting at object with constructor 'Array'\n    --- index 0 closes the circle",
+        ),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+
+    // The same object twice is not a cycle
+    let input = "const shared = { n: 1 }; JSON.stringify([shared, shared])";
+    assert_eq!(run(input), Ok(Value::String(r#"[{"n":1},{"n":1}]"#.into())));
+}
+
+#[test]
+fn test_parse() {
+    let cases = vec![
+        (r#"JSON.parse('{"a":[1,{"b":2}],"c":"x"}').a[1].b"#, Value::Number(2.0)),
+        (r#"JSON.parse(' 12.5e1 ')"#, Value::Number(125.0)),
+        (r#"JSON.parse('1e400')"#, Value::Number(f64::INFINITY)),
+        (r#"JSON.parse('0.1')"#, Value::Number(0.1)),
+        (r#"JSON.parse('"\\u00e9\\n"')"#, Value::String("é\n".into())),
+        (r#"JSON.parse('null')"#, Value::Null),
+        (r#"JSON.parse('[true, false]')[1]"#, Value::Boolean(false)),
+        (r#"Object.keys(JSON.parse('{"b":1,"a":2,"1":3}')).join()"#, Value::String("1,b,a".into())),
+        (r#"JSON.parse('{"a":1,"a":2}').a"#, Value::Number(2.0)),
+        (r#"Object.keys(JSON.parse('{"__proto__":1}')).join()"#, Value::String("__proto__".into())),
+        (r#"JSON.stringify(JSON.parse('{"a":[1,{"b":2}],"c":3}', (k, v) => v > 0 ? (v === 3 ? [][0] : v + 1) : v))"#, Value::String(r#"{"a":[2,{"b":3}]}"#.into())),
+        (r#"const keys = []; JSON.parse('{"a":{"b":1},"c":[2]}', (k, v) => { keys.push(k); return v; }); keys.join()"#, Value::String("b,a,0,c,".into())),
+        (r#"JSON.parse('{"a":1}', function (k, v) { return k === "" ? this[""].a : v; })"#, Value::Number(1.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_parse_errors() {
+    let cases = vec![
+        ("", "Unexpected end of JSON input"),
+        ("x", "Unexpected token 'x', \"x\" is not valid JSON"),
+        ("[1,]", "Unexpected token ']', \"[1,]\" is not valid JSON"),
+        ("{", "Expected property name or '}' in JSON at position 1"),
+        ("{\"a\":1,}", "Expected double-quoted property name in JSON at position 7"),
+        ("{'a':1}", "Expected property name or '}' in JSON at position 1"),
+        ("{\"a\" 1}", "Expected ':' after property name in JSON at position 5"),
+        ("[1 2]", "Expected ',' or ']' after array element in JSON at position 3"),
+        ("{\"a\":1", "Expected ',' or '}' after property value in JSON at position 6"),
+        ("\"abc", "Unterminated string in JSON at position 4"),
+        ("1 2", "Unexpected non-whitespace character after JSON at position 2"),
+        ("01", "Unexpected number in JSON at position 1"),
+        ("\"\\x\"", "Bad escaped character in JSON at position 2"),
+        ("\"\\u12x4\"", "Bad Unicode escape in JSON at position 5"),
+        ("\"a\tb\"", "Bad control character in string literal in JSON at position 2"),
+        ("-", "No number after minus sign in JSON at position 1"),
+        ("1.", "Unterminated fractional number in JSON at position 2"),
+        ("1e+", "Exponent part is missing a number in JSON at position 3"),
+        ("[1", "Expected ',' or ']' after array element in JSON at position 2"),
+        ("[", "Unexpected end of JSON input"),
+        ("tru", "Unexpected end of JSON input"),
+    ];
+
+    for (text, expected) in cases {
+        let input = format!("JSON.parse({})", quote(text));
+        assert_eq!(run(&input), Err(format!("SyntaxError: {}", expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_deep_nesting() {
+    let cases = vec![
+        (r#"JSON.parse("[".repeat(200) + "]".repeat(200)).length"#, Value::Number(1.0)),
+        (
+            r#"let value = JSON.parse("[".repeat(20000) + "]".repeat(20000)); let depth = 0;
+            while (value.length) { value = value[0]; depth++; } depth"#,
+            Value::Number(19999.0),
+        ),
+        (
+            r#"let value = JSON.parse('{"a":'.repeat(20000) + "1" + "}".repeat(20000), (k, v) => v);
+            let depth = 0; while (typeof value === "object") { value = value.a; depth++; } depth"#,
+            Value::Number(20000.0),
+        ),
+        (r#"let a = []; for (let i = 0; i < 20000; i++) a = [a]; JSON.stringify(a).length"#, Value::Number(40002.0)),
+        (r#"let o = {}; for (let i = 0; i < 20000; i++) o = { o }; JSON.stringify(o).length"#, Value::Number(120002.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_parse_lone_surrogates() {
+    let cases = vec![
+        (r#"JSON.parse('"\\ud800"').charCodeAt(0)"#, Value::Number(55296.0)),
+        (r#"JSON.parse(JSON.stringify("a\uD800b")) === "a\uD800b""#, Value::Boolean(true)),
+        (r#"JSON.parse(JSON.stringify(["\uDE00\uD83D", "😀"])).join() === "\uDE00\uD83D,😀""#, Value::Boolean(true)),
+        (r#"JSON.parse('"\\ud83d\\ude00"')"#, Value::String("😀".into())),
+        (r#"JSON.parse('"\uD800"').charCodeAt(0)"#, Value::Number(55296.0)),
+        (r#"JSON.parse('["\\ufdd0\uFDD0", "\\ud800\uFDD0"]').join() === "\uFDD0\uFDD0,\uD800\uFDD0""#, Value::Boolean(true)),
+        (r#"try { JSON.parse('["\\ud800" 1]') } catch (e) { e.message }"#, Value::String("Expected ',' or ']' after array element in JSON at position 10".into())),
+        (r#"try { JSON.parse('["\uD800\\x"]') } catch (e) { e.message }"#, Value::String("Bad escaped character in JSON at position 4".into())),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+/// `text` as a JavaScript string literal.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -1,4 +1,5 @@
 use ts_interpreter_rs::lexer::*;
+use ts_interpreter_rs::interpreter::JsString;
 use pretty_assertions::assert_eq;
 
 #[test]
@@ -46,6 +47,7 @@ fn test_complex_expression() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_float_numbers() {
     let input = "let pi = 3.14159;";
     let mut lexer = Lexer::new(input);
@@ -58,6 +60,113 @@ fn test_float_numbers() {
     assert_eq!(lexer.next_token(), Token::EOF);
 }
 
+#[test]
+fn test_number_literals() {
+    let cases = vec![
+        ("0x1F", 31.0),
+        ("0o17", 15.0),
+        ("0b101", 5.0),
+        ("1_000_000", 1_000_000.0),
+        ("1e3", 1000.0),
+        ("2.5E-3", 0.0025),
+        (".5", 0.5),
+        ("5.", 5.0),
+    ];
+
+    for (input, expected) in cases {
+        let mut lexer = Lexer::new(input);
+        assert_eq!(lexer.next_token(), Token::Number(expected), "{}", input);
+        assert_eq!(lexer.next_token(), Token::EOF, "{}", input);
+    }
+}
+
+#[test]
+fn test_bigint_literals() {
+    let cases = vec![("10n", 10), ("0xffn", 255), ("0b11n", 3), ("1_000n", 1000)];
+
+    for (input, expected) in cases {
+        let mut lexer = Lexer::new(input);
+        assert_eq!(lexer.next_token(), Token::BigInt(num_bigint::BigInt::from(expected)), "{}", input);
+        assert_eq!(lexer.next_token(), Token::EOF, "{}", input);
+    }
+
+    let mut lexer = Lexer::new("1.5n");
+    assert_eq!(lexer.next_token(), Token::Invalid("Invalid or unexpected token".to_string()));
+}
+
+#[test]
+fn test_invalid_number_literals() {
+    let cases = vec![
+        ("0x", "Invalid or unexpected token"),
+        ("0b", "Invalid or unexpected token"),
+        ("0o", "Invalid or unexpected token"),
+        ("0b12", "Invalid or unexpected token"),
+        ("1e", "Invalid or unexpected token"),
+        ("1e+", "Invalid or unexpected token"),
+        ("3in", "Invalid or unexpected token"),
+        ("012", "Octal literals are not allowed in strict mode."),
+        ("09", "Decimals with leading zeros are not allowed in strict mode."),
+    ];
+
+    for (input, expected) in cases {
+        let mut lexer = Lexer::new(input);
+        assert_eq!(lexer.next_token(), Token::Invalid(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_string_literals() {
+    let cases = vec![
+        (r#""plain""#, "plain"),
+        (r#"'single "quoted"'"#, "single \"quoted\""),
+        (r#""tab\there\n""#, "tab\there\n"),
+        (r#"'it\'s'"#, "it's"),
+        (r#""\x41B\u{1F600}""#, "AB\u{1F600}"),
+        (r#""😀""#, "\u{1F600}"),
+        ("\"line \\\ncontinued\"", "line continued"),
+        (r#""\q\0""#, "q\0"),
+        ("\"\\😀\"", "\u{1F600}"),
+        ("\"a\\\r\nb\"", "ab"),
+    ];
+
+    for (input, expected) in cases {
+        let mut lexer = Lexer::new(input);
+        assert_eq!(lexer.next_token(), Token::String(expected.into()), "{}", input);
+        assert_eq!(lexer.next_token(), Token::EOF, "{}", input);
+    }
+}
+
+#[test]
+fn test_lone_surrogate_escapes() {
+    let mut lexer = Lexer::new(r#""\uD83D" "\uD83D\uDE00""#);
+    assert_eq!(lexer.next_token(), Token::String(JsString::from(vec![0xD83D])));
+    assert_eq!(lexer.next_token(), Token::String("\u{1F600}".into()));
+}
+
+#[test]
+fn test_invalid_string_literals() {
+    let cases = vec![
+        (r#""\x4""#, "Invalid hexadecimal escape sequence"),
+        (r#""\xZZ""#, "Invalid hexadecimal escape sequence"),
+        (r#""\uZZZZ""#, "Invalid Unicode escape sequence"),
+        (r#""\u12""#, "Invalid Unicode escape sequence"),
+        (r#""\u{}""#, "Invalid Unicode escape sequence"),
+        (r#""\u{12""#, "Invalid Unicode escape sequence"),
+        (r#""\u{110000}""#, "Undefined Unicode code-point"),
+        (r#""\u{FFFFFFFFF}""#, "Undefined Unicode code-point"),
+        (r#""\012""#, "Octal escape sequences are not allowed in strict mode."),
+        (r#""\8""#, "\\8 and \\9 are not allowed in strict mode."),
+        (r#""unterminated"#, "Invalid or unexpected token"),
+        ("'line\nbreak'", "Invalid or unexpected token"),
+        (r#""ends in \"#, "Invalid or unexpected token"),
+    ];
+
+    for (input, expected) in cases {
+        let mut lexer = Lexer::new(input);
+        assert_eq!(lexer.next_token(), Token::Invalid(expected.to_string()), "{}", input);
+    }
+}
+
 #[test]
 fn test_skip_comments() {
     let input = "// this is a comment\nlet x = 42;";
@@ -69,4 +178,18 @@ fn test_skip_comments() {
     assert_eq!(lexer.next_token(), Token::Number(42.0));
     assert_eq!(lexer.next_token(), Token::Semicolon);
     assert_eq!(lexer.next_token(), Token::EOF);
-}
\ No newline at end of file
+}
+#[test]
+fn test_template_literals() {
+    let mut lexer = Lexer::new("`a\\x41${x}\\q\r\n`");
+    assert_eq!(lexer.next_token(), Token::Template { cooked: Ok("aA".into()), raw: "a\\x41".to_string(), tail: false });
+    assert_eq!(lexer.next_token(), Token::Identifier("x".to_string()));
+    assert_eq!(lexer.next_token(), Token::RBrace);
+    assert_eq!(lexer.read_template_continuation(), Token::Template { cooked: Ok("q\n".into()), raw: "\\q\n".to_string(), tail: true });
+    assert_eq!(lexer.next_token(), Token::EOF);
+
+    let mut lexer = Lexer::new("`\\u{}`");
+    let cooked = Err("Invalid Unicode escape sequence".to_string());
+    assert_eq!(lexer.next_token(), Token::Template { cooked, raw: "\\u{}".to_string(), tail: true });
+    assert_eq!(Lexer::new("`open").next_token(), Token::Invalid("Unterminated template literal".to_string()));
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use common::run;
+use ts_interpreter_rs::interpreter::{Interpreter, Value};
+use ts_interpreter_rs::parser::Parser;
+
+/// Runs `expression` and returns its result converted to a string, so
+/// that results like `NaN` and `-0` can be compared exactly.
+fn display(expression: &str) -> Result<Value, String> {
+    run(&format!("\"\" + ({})", expression))
+}
+
+#[test]
+fn test_number_formatting() {
+    let cases = vec![
+        ("(1.005).toFixed(2)", "1.00"),
+        ("(1.45).toFixed(1)", "1.4"),
+        ("(2.5).toFixed(0)", "3"),
+        ("(-1.5).toFixed(0)", "-2"),
+        ("(0.000001).toFixed(7)", "0.0000010"),
+        ("(1e21).toFixed(2)", "1e+21"),
+        ("(123.456).toFixed(10)", "123.4560000000"),
+        ("(-0.0000001).toFixed(2)", "-0.00"),
+        ("(0).toFixed(2)", "0.00"),
+        ("(123.456).toPrecision(4)", "123.5"),
+        ("(0.00001234).toPrecision(2)", "0.000012"),
+        ("(123456).toPrecision(2)", "1.2e+5"),
+        ("(0.000001).toPrecision(1)", "0.000001"),
+        ("(0.0000001).toPrecision(1)", "1e-7"),
+        ("(99.99).toPrecision(3)", "100"),
+        ("(1).toPrecision(3)", "1.00"),
+        ("(255).toPrecision()", "255"),
+        ("(123456).toExponential(2)", "1.23e+5"),
+        ("(0.00015).toExponential()", "1.5e-4"),
+        ("(1e21).toExponential()", "1e+21"),
+        ("(-5).toExponential(0)", "-5e+0"),
+        ("(0).toExponential(2)", "0.00e+0"),
+        ("(1234567.891).toLocaleString()", "1,234,567.891"),
+        ("(-1234.5).toLocaleString()", "-1,234.5"),
+        ("(0.0001).toLocaleString()", "0"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(display(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+}
+
+#[test]
+fn test_radix_strings() {
+    let cases = vec![
+        ("(255).toString(16)", "ff"),
+        ("(255).toString(2)", "11111111"),
+        ("(-255).toString(36)", "-73"),
+        ("(0.5).toString(2)", "0.1"),
+        ("(0.1).toString(2)", "0.0001100110011001100110011001100110011001100110011001101"),
+        ("(0.1).toString(3)", "0.0022002200220022002200220022002201"),
+        ("(3.14159).toString(16)", "3.243f3e0370cdc"),
+        ("(1e21).toString(7)", "5135235413265003022600000"),
+        ("(2 ** 60).toString(2)", "1000000000000000000000000000000000000000000000000000000000000"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(display(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+}
+
+#[test]
+fn test_number_literals_and_printing() {
+    let cases = vec![
+        ("1e21 + 1", "1e+21"),
+        ("1.5e-7", "1.5e-7"),
+        ("0x1F + 0o17 + 0b101", "51"),
+        ("1_000_000", "1000000"),
+        ("0.1 + 0.2", "0.30000000000000004"),
+        ("123e-20", "1.23e-18"),
+        ("2 ** 53", "9007199254740992"),
+        ("1 / 3", "0.3333333333333333"),
+        ("100 / 3", "33.333333333333336"),
+        ("5e-324", "5e-324"),
+        ("1.7976931348623157e308", "1.7976931348623157e+308"),
+        ("-1e-7", "-1e-7"),
+        ("123456789012345680000", "123456789012345680000"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(display(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+}
+
+#[test]
+fn test_parsing_functions() {
+    let cases = vec![
+        ("parseInt(\"  -42px\")", "-42"),
+        ("parseInt(\"0x1A\")", "26"),
+        ("parseInt(\"1010\", 2)", "10"),
+        ("parseInt(\"z\", 36)", "35"),
+        ("parseInt(\"9\", 8)", "NaN"),
+        ("parseInt(\"\")", "NaN"),
+        ("parseInt(\"12\", 1)", "NaN"),
+        ("parseInt(\"0x10\", 16)", "16"),
+        ("parseInt(\"0x10\", 10)", "0"),
+        ("parseInt(15.99)", "15"),
+        ("parseInt(\"123456789012345678901234567890\")", "1.2345678901234568e+29"),
+        ("parseFloat(\"3.14abc\")", "3.14"),
+        ("parseFloat(\"  -.5e3x\")", "-500"),
+        ("parseFloat(\"Infinityx\")", "Infinity"),
+        ("parseFloat(\"e5\")", "NaN"),
+        ("parseFloat(\"1e\")", "1"),
+        ("parseFloat(\".\")", "NaN"),
+        ("1 / parseFloat(\"-0\")", "-Infinity"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(display(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+}
+
+#[test]
+fn test_number_statics() {
+    let cases = vec![
+        ("isNaN(\"abc\")", "true"),
+        ("isNaN(\"12\")", "false"),
+        ("isFinite(\"12\")", "true"),
+        ("Number.isNaN(\"abc\")", "false"),
+        ("Number.isFinite(\"12\")", "false"),
+        ("Number.isInteger(5.0)", "true"),
+        ("Number.isInteger(5.5)", "false"),
+        ("Number.isSafeInteger(2 ** 53)", "false"),
+        ("Number.isSafeInteger(2 ** 53 - 1)", "true"),
+        ("Number.EPSILON", "2.220446049250313e-16"),
+        ("Number.MAX_SAFE_INTEGER", "9007199254740991"),
+        ("Number.MIN_VALUE", "5e-324"),
+        ("Number.MAX_VALUE", "1.7976931348623157e+308"),
+        ("Number(\"  12  \")", "12"),
+        ("Number(\"0b11\")", "3"),
+        ("Number(\"\")", "0"),
+        ("Number(\"12px\")", "NaN"),
+        ("Number()", "0"),
+        ("Number.parseInt === parseInt", "true"),
+        ("new Number(5) + 1", "6"),
+        ("(5).constructor === Number", "true"),
+        ("Object.prototype.toString.call(new Number(1))", "[object Number]"),
+        ("new (class Big extends Number {})(7).toFixed(1)", "7.0"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(display(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+}
+
+#[test]
+fn test_math() {
+    let cases = vec![
+        ("Math.round(2.5)", "3"),
+        ("Math.round(-2.5)", "-2"),
+        ("1 / Math.round(-0.4)", "-Infinity"),
+        ("Math.round(0.49999999999999994)", "0"),
+        ("Math.max()", "-Infinity"),
+        ("Math.min()", "Infinity"),
+        ("Math.max(1, 3, 2)", "3"),
+        ("Math.max(1, NaN)", "NaN"),
+        ("1 / (Math.min(0, -0))", "-Infinity"),
+        ("Math.sign(-3)", "-1"),
+        ("Math.trunc(-4.7)", "-4"),
+        ("Math.cbrt(27)", "3"),
+        ("Math.hypot(3, 4)", "5"),
+        ("Math.hypot(NaN, Infinity)", "Infinity"),
+        ("Math.clz32(1)", "31"),
+        ("Math.imul(0xffffffff, 5)", "-5"),
+        ("Math.fround(5.5)", "5.5"),
+        ("Math.fround(5.05)", "5.050000190734863"),
+        ("Math.log2(8)", "3"),
+        ("Math.log10(1000)", "3"),
+        ("Math.pow(2, 10)", "1024"),
+        ("Math.pow(1, Infinity)", "NaN"),
+        ("Math.PI", "3.141592653589793"),
+        ("Math.E", "2.718281828459045"),
+        ("Math.SQRT1_2", "0.7071067811865476"),
+        ("Math.atan2(1, 1)", "0.7853981633974483"),
+        ("Math.expm1(0)", "0"),
+        ("Math.sin(Math.PI)", "1.2246467991473532e-16"),
+        ("Math.abs(-7.25)", "7.25"),
+        ("1 / Math.ceil(-0.5)", "-Infinity"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(display(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+}
+
+#[test]
+fn test_seeded_random() {
+    let source = "const values = []; for (let i = 0; i < 5; i++) values.push(Math.random()); values.join()";
+    let run_seeded = |seed| {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_random_seed(seed);
+        interpreter.run(Parser::new(source).parse_program().unwrap())
+    };
+    assert_eq!(run_seeded(42), run_seeded(42));
+    assert_ne!(run_seeded(42), run_seeded(43));
+
+    let cases = vec![
+        "let ok = true; for (let i = 0; i < 1000; i++) { const r = Math.random(); ok = ok && r >= 0 && r < 1; } ok",
+    ];
+    for input in cases {
+        assert_eq!(run(input), Ok(Value::Boolean(true)), "{}", input);
+    }
+}
+
+#[test]
+fn test_number_errors() {
+    let cases = vec![
+        ("(1).toFixed(101)", "RangeError: toFixed() digits argument must be between 0 and 100"),
+        ("(1).toPrecision(0)", "RangeError: toPrecision() argument must be between 1 and 100"),
+        ("(1).toExponential(-1)", "RangeError: toExponential() argument must be between 0 and 100"),
+        ("(1).toString(37)", "RangeError: toString() radix must be between 2 and 36"),
+        ("Number.prototype.toFixed.call(\"1\")", "TypeError: Number.prototype.toFixed requires that 'this' be a Number"),
+        ("Number.prototype.valueOf.call({})", "TypeError: Number.prototype.valueOf requires that 'this' be a Number"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
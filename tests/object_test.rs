@@ -0,0 +1,114 @@
+mod common;
+
+use common::run;
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_object_literals() {
+    let cases = vec![
+        ("const o = { a: 1, \"b\": 2, 3: 4 }; o.a + o[\"b\"] + o[3]", Value::Number(7.0)),
+        ("const a = 5; const o = { a }; o.a", Value::Number(5.0)),
+        ("const k = \"x\"; const o = { [k + 1]: 2 }; o.x1", Value::Number(2.0)),
+        ("const o = { n: 2, double(x) { return x * this.n; } }; o.double(21)", Value::Number(42.0)),
+        ("const o = { if: 1, default: 2 }; o.if + o.default", Value::Number(3.0)),
+        ("const o = { f: function () {}, g: () => {} }; o.f.name + o.g.name", Value::String("fg".into())),
+        ("const o = { get: 1, set(v) { return v; } }; o.get + o.set(1)", Value::Number(2.0)),
+        ("({ a: 1 }).b === ({}).c", Value::Boolean(true)),
+        ("const f = () => ({ x: 1 }); f().x", Value::Number(1.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_reference_semantics() {
+    let source = "
+        const a = { count: 0 };
+        const b = a;
+        function bump(o) { o.count += 1; }
+        bump(a);
+        b.count++;
+        a.count
+    ";
+    assert_eq!(run(source), Ok(Value::Number(2.0)));
+
+    let source = "
+        const inner = { v: 1 };
+        const outer = { inner, list: [inner] };
+        outer.list[0].v = 7;
+        inner.v + outer.inner.v
+    ";
+    assert_eq!(run(source), Ok(Value::Number(14.0)));
+}
+
+#[test]
+fn test_accessors_and_spread() {
+    let source = "
+        const temp = {
+            celsius: 20,
+            get fahrenheit() { return this.celsius * 9 / 5 + 32; },
+            set fahrenheit(f) { this.celsius = (f - 32) * 5 / 9; },
+        };
+        temp.fahrenheit = 212;
+        temp.celsius + temp.fahrenheit
+    ";
+    assert_eq!(run(source), Ok(Value::Number(312.0)));
+
+    let source = "
+        let reads = 0;
+        const base = { a: 1, get b() { reads++; return 2; } };
+        const copy = { ...base, a: 10, c: 3 };
+        base.a = 100;
+        copy.a + copy.b + copy.c + reads
+    ";
+    assert_eq!(run(source), Ok(Value::Number(16.0)));
+
+    let source = "const o = { ...\"hi\", ...null, ...[7] }; o[0] + o[1]";
+    assert_eq!(run(source), Ok(Value::String("7i".into())));
+}
+
+#[test]
+fn test_delete_and_attributes() {
+    let cases = vec![
+        ("const o = { a: 1 }; delete o.a; o.a === o.b", Ok(Value::Boolean(true))),
+        ("const o = { a: 1 }; delete o[\"a\"]", Ok(Value::Boolean(true))),
+        ("const a = [1, 2, 3]; delete a[1]; a.length", Ok(Value::Number(3.0))),
+        ("const a = [1, 2, 3]; delete a[1]; a.join()", Ok(Value::String("1,,3".into()))),
+        (
+            "const a = []; delete a.length",
+            Err("TypeError: Cannot delete property 'length' of object '[object Array]'"),
+        ),
+        ("function f() {} f.name = \"g\"", Err("TypeError: Cannot assign to read only property 'name' of function")),
+        (
+            "const o = { get x() { return 1; } }; o.x = 2",
+            Err("TypeError: Cannot set property x of object '#<Object>' which has only a getter"),
+        ),
+        ("let nothing; nothing.a = 1", Err("TypeError: Cannot set properties of undefined (setting 'a')")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), expected.map_err(String::from), "{}", input);
+    }
+}
+
+#[test]
+fn test_object_constructor() {
+    let cases = vec![
+        ("Object(\"ab\").length", Value::Number(2.0)),
+        ("Object(\"ab\")[1]", Value::String("b".into())),
+        ("Object(1) instanceof Number", Value::Boolean(true)),
+        ("Object(\"ab\") instanceof String && typeof Object(\"ab\") === \"object\"", Value::Boolean(true)),
+        ("Object(2) * 3", Value::Number(6.0)),
+        ("Object(1n) instanceof BigInt", Value::Boolean(true)),
+        ("Object.prototype.toString.call(Object(true))", Value::String("[object Boolean]".into())),
+        ("const o = { a: 1 }; Object(o) === o && new Object(o) === o", Value::Boolean(true)),
+        ("const f = () => 1; Object(f) === f", Value::Boolean(true)),
+        ("Object.keys(Object(null)).length + Object.keys(new Object()).length", Value::Number(0.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
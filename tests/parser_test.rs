@@ -23,7 +23,7 @@ fn test_expression_evaluation() {
 fn test_statement_evaluation() {
     let cases = vec![
         ("let x = 42;", Value::Number(42.0)),
-        ("let message = \"Hello\";", Value::String("Hello".to_string())),
+        ("let message = \"Hello\";", Value::String("Hello".into())),
     ];
 
     for (input, expected) in cases {
@@ -37,3 +37,162 @@ fn test_statement_evaluation() {
         }
     }
 }
+
+#[test]
+fn test_variable_declaration_parsing() {
+    let mut parser = Parser::new("let a = 1, b;");
+    assert_eq!(
+        parser.parse_statement().unwrap(),
+        Statement::VariableDeclaration {
+            kind: VariableKind::Let,
+            declarations: vec![
+                VariableDeclarator { target: Pattern::Identifier("a".to_string()), init: Some(Expression::Number(1.0)) },
+                VariableDeclarator { target: Pattern::Identifier("b".to_string()), init: None },
+            ],
+        }
+    );
+
+    assert!(Parser::new("const c;").parse_statement().is_err());
+    assert!(Parser::new("1 = 2;").parse_statement().is_err());
+}
+
+#[test]
+fn test_jump_statement_validation() {
+    assert!(Parser::new("while (true) { break; }").parse_program().is_ok());
+    assert!(Parser::new("break;").parse_program().is_err());
+    assert!(Parser::new("switch (1) { case 1: continue; }").parse_program().is_err());
+    assert!(Parser::new("a: { continue a; }").parse_program().is_err());
+    assert!(Parser::new("for (;;) { break missing; }").parse_program().is_err());
+}
+
+#[test]
+fn test_object_literal_validation() {
+    assert!(Parser::new("({ a, b: 1, [c]: 2, d() {}, get e() { return 1; }, ...f });").parse_program().is_ok());
+    assert!(Parser::new("({ 1 });").parse_program().is_err());
+    assert!(Parser::new("({ get e(x) {} });").parse_program().is_err());
+    assert!(Parser::new("({ set e() {} });").parse_program().is_err());
+    assert!(Parser::new("let x = 1; delete x;").parse_program().is_err());
+}
+
+#[test]
+fn test_class_validation() {
+    assert!(Parser::new("class A extends B { #x = 1; static s; get g() { return super.g; } constructor() { super(); } }").parse_program().is_ok());
+    assert!(Parser::new("class A { constructor() {} constructor() {} }").parse_program().is_err());
+    assert!(Parser::new("class A { get constructor() {} }").parse_program().is_err());
+    assert!(Parser::new("class A { static prototype() {} }").parse_program().is_err());
+    assert!(Parser::new("class A { constructor() { super(); } }").parse_program().is_err());
+    assert!(Parser::new("function f() { super.x; }").parse_program().is_err());
+    assert!(Parser::new("class A { m() { return this.#missing; } }").parse_program().is_err());
+    assert!(Parser::new("class A { #x; m() { delete this.#x; } }").parse_program().is_err());
+    assert!(Parser::new("class A { #x; #x; }").parse_program().is_err());
+    assert!(Parser::new("class A { abstract m() {} }").parse_program().is_err());
+    assert!(Parser::new("function f(public x) {}").parse_program().is_err());
+    assert!(Parser::new("class A { static { const x = 1; } static {} }").parse_program().is_ok());
+    assert!(Parser::new("class A { static { arguments; } }").parse_program().is_err());
+}
+
+#[test]
+fn test_pattern_validation() {
+    assert!(Parser::new("const [a, , ...b] = c; ({ d, e: [f] = [], ...g } = h); for (const [k, v] of m) {}").parse_program().is_ok());
+    assert!(Parser::new("const [a];").parse_program().is_err());
+    assert!(Parser::new("let { b };").parse_program().is_err());
+    assert!(Parser::new("({ a = 1 });").parse_program().is_err());
+    assert!(Parser::new("f({ a = 1 });").parse_program().is_err());
+    assert!(Parser::new("[...rest, last] = [];").parse_program().is_err());
+    assert!(Parser::new("let [...r, x] = [];").parse_program().is_err());
+    assert!(Parser::new("({ m() {} } = {});").parse_program().is_err());
+}
+
+#[test]
+fn test_exception_statements() {
+    assert!(Parser::new("try { f(); } catch (e: unknown) { g(e); } finally { h(); } try {} catch {} try {} finally {}").parse_program().is_ok());
+    assert!(Parser::new("try { f(); } catch ({ message }) {}").parse_program().is_ok());
+    assert!(Parser::new("try {}").parse_program().is_err());
+    assert!(Parser::new("try f(); catch (e) {}").parse_program().is_err());
+    assert!(Parser::new("throw\nerror;").parse_program().is_err());
+    assert!(Parser::new("throw;").parse_program().is_err());
+}
+
+#[test]
+fn test_optional_chain_and_coalesce_validation() {
+    assert!(Parser::new("a?.b; a?.[0]; a?.(); a?.b.c(); a ?? b ?? c; (a || b) ?? c; a ?? (b && c);").parse_program().is_ok());
+    assert!(Parser::new("let t: typeof a = a; function f(): void {} let n: string | null = null;").parse_program().is_ok());
+    assert!(Parser::new("a || b ?? c;").parse_program().is_err());
+    assert!(Parser::new("a ?? b && c;").parse_program().is_err());
+    assert!(Parser::new("a?.b = 1;").parse_program().is_err());
+    assert!(Parser::new("new a?.b();").parse_program().is_err());
+}
+
+#[test]
+fn test_generator_validation() {
+    assert!(Parser::new("function* g(a) { const x = yield a; yield; yield* [x]; } const o = { *m() {} }; class C { *[k]() {} static *s() {} }").parse_program().is_ok());
+    assert!(Parser::new("const h = function* () { (yield 1) + (yield); };").parse_program().is_ok());
+    assert!(Parser::new("function* g() { function f() { yield 1; } }").parse_program().is_err());
+    assert!(Parser::new("function* g() { const f = () => yield 1; }").parse_program().is_err());
+    assert!(Parser::new("function* g() { yield\n* 1; }").parse_program().is_err());
+}
+
+#[test]
+fn test_invalid_tokens_are_syntax_errors() {
+    assert_eq!(Parser::new(r#"let s = "\x4";"#).parse_program(), Err("Invalid hexadecimal escape sequence".to_string()));
+    assert_eq!(Parser::new(r#"({ "\u{}": 1 });"#).parse_program(), Err("Invalid Unicode escape sequence".to_string()));
+    assert!(Parser::new("let s = 'open;").parse_program().is_err());
+    assert_eq!(Parser::new("let n = 0x;").parse_program(), Err("Invalid or unexpected token".to_string()));
+    assert_eq!(Parser::new("let n = 012;").parse_program(), Err("Octal literals are not allowed in strict mode.".to_string()));
+}
+
+#[test]
+fn test_type_annotations() {
+    let programs = [
+        "const isString = (x: unknown): x is string => typeof x === 'string';",
+        "function check(x: unknown): asserts x is number {} function ok(x: unknown): asserts x {}",
+        "class A { is(): this is A { return true; } }",
+        "const sum = (xs: readonly number[]) => xs.length; let t: readonly [number, string];",
+        "const X = { a: 1 }; let k: keyof typeof X = 'a'; let v: (typeof X)[keyof typeof X] = 1;",
+        "let id: <T>(x: T) => T = (x) => x;",
+        "const id = <T,>(x: T): T => x; const pair = <A, B extends object = {},>(a: A, b: B): [A, B] => [a, b];",
+        "const f = async <T,>(x: T) => x; function g<T,>(x: T) {} class C<T,> {}",
+        "let p: [name: string, age?: number, ...rest: boolean[]] = ['a']; let q: [string?, ...number[]];",
+        "let make: new () => object = Object; let f: abstract new <T>(x: T) => T;",
+        "const m: Map<string, Set<number>>= new Map(); let a: Array<number>= [];",
+        "let b: boolean | true | false = true; let s: unique symbol;",
+        "const n = (1 as any) + (2 as number); const c = [1] as const; const o = {} satisfies object;",
+        "let o: { a: number } | null = { a: 1 }; o!.a; (o as { a: number }).a; o as unknown as string;",
+        "type Pair<T> = [T, T]; type Id = number; type Tree<T = unknown> = { value: T; children: Tree<T>[] } | null;",
+        "function f(a: string): string; function f<T>(a: T): T; function f(a: any): any { return a; }",
+        "async function g(): Promise<void>\nasync function g() {}",
+        "let x!: number; let y!: string, z = 1;",
+    ];
+    for program in programs {
+        assert!(Parser::new(program).parse_program().is_ok(), "{}", program);
+    }
+
+    assert!(Parser::new("let x: keyof;").parse_program().is_ok());
+    assert!(Parser::new("let x: new X;").parse_program().is_err());
+    assert!(Parser::new("let x = a\nas\nb;").parse_program().is_ok());
+
+    // `type` is an identifier unless a name follows on the same line
+    let statements = Parser::new("let type = 1, X; type\nX = 2;").parse_program().unwrap();
+    assert_eq!(statements.len(), 3);
+    assert!(Parser::new("function f();").parse_program().is_ok());
+    assert!(Parser::new("(function f(): void);").parse_program().is_err());
+}
+
+#[test]
+fn test_deep_nesting() {
+    let chain: String = (1..2000).map(|i| format!(" else if (x === {i}) {{ x = -{i}; }}")).collect();
+    let programs = [
+        format!("let x = 1999; if (x === 0) {{ x = 0; }}{chain} x"),
+        format!("{}1{}", "(".repeat(500), ")".repeat(500)),
+        format!("{}1", "2 ** ".repeat(500)),
+        format!("{}1", "- ".repeat(2000)),
+        format!("let t: {}number{} = []; t.length", "Array<".repeat(300), ">".repeat(300)),
+        format!("new Function('return {}1{}')()", "(".repeat(500), ")".repeat(500)),
+    ];
+    let expected = [Value::Number(-1999.0), Value::Number(1.0), Value::Number(f64::INFINITY), Value::Number(1.0), Value::Number(0.0), Value::Number(1.0)];
+
+    for (program, expected) in programs.iter().zip(expected) {
+        let statements = Parser::new(program).parse_program().unwrap();
+        assert_eq!(Interpreter::new().run(statements), Ok(expected));
+    }
+}
@@ -0,0 +1,258 @@
+mod common;
+
+use common::{run, run_in, string};
+use ts_interpreter_rs::interpreter::{Interpreter, Value};
+
+#[test]
+fn test_promise() {
+    let cases = vec![
+        ("await Promise.resolve(1).then(x => x + 1)", Value::Number(2.0)),
+        ("await new Promise(resolve => resolve(3))", Value::Number(3.0)),
+        ("await new Promise((resolve, reject) => { resolve(1); reject(2); resolve(3); })", Value::Number(1.0)),
+        ("await new Promise(() => { throw 4; }).catch(x => x)", Value::Number(4.0)),
+        ("await Promise.reject(5).then(x => 0, x => x + 1)", Value::Number(6.0)),
+        ("await Promise.resolve(1).then(2, 3).then(x => x)", Value::Number(1.0)),
+        ("await Promise.resolve(Promise.resolve(7))", Value::Number(7.0)),
+        ("const p = Promise.resolve(); Promise.resolve(p) === p", Value::Boolean(true)),
+        ("await Promise.resolve({ then(resolve) { resolve(8); } })", Value::Number(8.0)),
+        ("await Promise.resolve(1).finally(() => 2)", Value::Number(1.0)),
+        ("await Promise.reject(1).finally(() => 2).catch(x => 'caught ' + x)", string("caught 1")),
+        ("await Promise.resolve(1).finally(() => { throw 3; }).catch(x => x)", Value::Number(3.0)),
+        ("let done = 0; await Promise.resolve(1).finally(() => new Promise(r => r(done = 1))); done", Value::Number(1.0)),
+        ("class P extends Promise {} const p = P.resolve(1).then(x => x); [p instanceof P, P.all([]) instanceof P].join()", string("true,true")),
+        ("[Promise.length, Promise.name, Promise.prototype.then.length, Promise.all.length].join()", string("1,Promise,2,1")),
+        ("Object.prototype.toString.call(Promise.resolve())", string("[object Promise]")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_promise_combinators() {
+    let cases = vec![
+        ("(await Promise.all([1, Promise.resolve(2), new Promise(r => r(3))])).join()", string("1,2,3")),
+        ("(await Promise.all([])).length", Value::Number(0.0)),
+        ("await Promise.all([1, Promise.reject(2), Promise.reject(3)]).catch(x => x)", Value::Number(2.0)),
+        ("await Promise.all(5).catch(e => e.message)", string("5 is not iterable")),
+        ("JSON.stringify(await Promise.allSettled([1, Promise.reject(2)]))", string(r#"[{"status":"fulfilled","value":1},{"status":"rejected","reason":2}]"#)),
+        ("await Promise.any([Promise.reject(1), 2, 3])", Value::Number(2.0)),
+        ("const e = await Promise.any([Promise.reject(1), Promise.reject(2)]).catch(e => e); [e.name, e.message, e.errors.join()].join()", string("AggregateError,All promises were rejected,1,2")),
+        ("(await Promise.any([]).catch(e => e)) instanceof AggregateError", Value::Boolean(true)),
+        ("await Promise.race([new Promise(() => {}), 7, 8])", Value::Number(7.0)),
+        ("await Promise.race([Promise.reject(1), 2]).catch(x => 'r' + x)", string("r1")),
+        ("(await Promise.all(new Set([1, 2]).values())).join()", string("1,2")),
+        ("const e = new AggregateError(new Set([1, 2]), 'x'); [e.message, e.errors.join(), e instanceof Error, AggregateError.length].join()", string("x,1,2,true,2")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_job_order() {
+    let cases = vec![
+        ("const log = []; Promise.resolve().then(() => log.push('job')); log.push('script'); await null; log.join()", string("script,job")),
+        (
+            "const log = []; Promise.resolve().then(() => log.push(1)).then(() => log.push(3)); Promise.resolve().then(() => log.push(2)).then(() => log.push(4)); await null; await null; await null; log.join()",
+            string("1,2,3,4"),
+        ),
+        ("const log = []; const t = { then(r) { log.push('then'); r(); } }; Promise.resolve(t); log.push('script'); await null; log.join()", string("script,then")),
+        ("const log = []; async function a() { log.push(1); await b(); log.push(4); } async function b() { log.push(2); } a(); log.push(3); await null; log.join()", string("1,2,3,4")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_async_functions() {
+    let cases = vec![
+        ("async function f() { return 1; } await f()", Value::Number(1.0)),
+        ("async function f() { return 1; } f() instanceof Promise", Value::Boolean(true)),
+        ("async function f(x) { const y = await x; return y * 2; } await f(Promise.resolve(4))", Value::Number(8.0)),
+        ("async function f() { try { await Promise.reject(1); } catch (e) { return 'caught ' + e; } } await f()", string("caught 1")),
+        ("async function f() { throw new Error('x'); } await f().catch(e => e.message)", string("x")),
+        ("let x = 0; async function f() { x = 1; await 0; x = 2; } f(); x", Value::Number(1.0)),
+        ("const f = async x => x + 1; const g = async (a, b) => a + b; [await f(1), await g(1, 2)].join()", string("2,3")),
+        ("const o = { v: 4, async m() { return this.v; } }; await o.m()", Value::Number(4.0)),
+        ("class C { async m(x) { return x * 2; } static async s() { return 's'; } } [await new C().m(2), await C.s()].join()", string("4,s")),
+        ("(await Promise.all([1, 2].map(async x => { await null; return x * 10; }))).join()", string("10,20")),
+        ("async function f() {} Object.getPrototypeOf(f).constructor.name", string("AsyncFunction")),
+        ("async function f() {} 'prototype' in f", Value::Boolean(false)),
+        ("const AsyncFunction = Object.getPrototypeOf(async () => {}).constructor; await new AsyncFunction('a', 'return await a')(3)", Value::Number(3.0)),
+        ("const async = 5; async", Value::Number(5.0)),
+        ("function async() { return 2; } async()", Value::Number(2.0)),
+        ("let total = 0; for (const p of [1, 2, 3]) total += await p; total", Value::Number(6.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_for_await() {
+    let counter = "const counter = { [Symbol.asyncIterator]() { let i = 0; return { next: async () => ({ value: ++i, done: i > 3 }), return: async () => { closed = true; return {}; } }; } }; let closed = false;";
+    let cases = vec![
+        (format!("{} const seen = []; for await (const n of counter) seen.push(n); seen.join()", counter), string("1,2,3")),
+        (format!("{} async function f() {{ let total = 0; for await (const n of counter) total += n; return total; }} await f()", counter), Value::Number(6.0)),
+        (format!("{} for await (const n of counter) if (n === 2) break; closed", counter), Value::Boolean(true)),
+        (format!("{} try {{ for await (const n of counter) throw 1; }} catch {{}} closed", counter), Value::Boolean(true)),
+        // Synchronous iterables have their values awaited
+        ("const seen = []; for await (const x of [Promise.resolve('a'), 'b']) seen.push(x); seen.join()".to_string(), string("a,b")),
+        ("let caught; try { for await (const x of [Promise.reject('no')]) {} } catch (e) { caught = e; } caught".to_string(), string("no")),
+        ("let x; for await (x of new Set([1])) {} x".to_string(), Value::Number(1.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(&input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_async_generators() {
+    let counter = "async function* count(n) { for (let i = 1; i <= n; i++) yield await Promise.resolve(i); return 'done'; }";
+    let cases = vec![
+        (format!("{} const seen = []; for await (const n of count(3)) seen.push(n); seen.join()", counter), string("1,2,3")),
+        (format!("{} const it = count(1); JSON.stringify([await it.next(), await it.next(), await it.next()])", counter), string(r#"[{"value":1,"done":false},{"value":"done","done":true},{"done":true}]"#)),
+        // Requests made while the generator is busy wait their turn
+        (format!("{} const it = count(2); JSON.stringify(await Promise.all([it.next(), it.next(), it.next()]))", counter), string(r#"[{"value":1,"done":false},{"value":2,"done":false},{"value":"done","done":true}]"#)),
+        (format!("{} const it = count(3); [it[Symbol.asyncIterator]() === it, it.next() instanceof Promise].join()", counter), string("true,true")),
+        // `yield` and `return` await their operands
+        ("async function* g() { yield Promise.resolve(1); return Promise.resolve(2); } const it = g(); [(await it.next()).value, (await it.next()).value].join()".to_string(), string("1,2")),
+        ("async function* g() { try { return Promise.reject(3); } catch (e) { yield 'caught ' + e; } } (await g().next()).value".to_string(), string("caught 3")),
+        ("async function* g() { const x = yield 1; yield x * 2; } const it = g(); await it.next(); (await it.next(5)).value".to_string(), Value::Number(10.0)),
+        // `yield*` delegates to async iterables, and awaits the values of sync ones
+        (format!("{} async function* g() {{ const r = yield* count(2); yield r; yield* [Promise.resolve('a'), 'b']; }} const seen = []; for await (const x of g()) seen.push(x); seen.join()", counter), string("1,2,done,a,b")),
+        // `return` and `throw` run `finally` blocks, and leave the generator completed
+        ("let log = []; async function* g() { try { yield 1; yield 2; } finally { log.push('closed'); } } for await (const x of g()) { log.push(x); break; } log.join()".to_string(), string("1,closed")),
+        ("async function* g() { try { yield 1; } catch (e) { yield 'caught ' + e; } } const it = g(); await it.next(); (await it.throw('oops')).value".to_string(), string("caught oops")),
+        ("async function* g() { yield 1; } const it = g(); JSON.stringify([await it.return(Promise.resolve(4)), await it.next()])".to_string(), string(r#"[{"value":4,"done":true},{"done":true}]"#)),
+        ("async function* g() { yield 1; } await g().throw(new Error('never started')).catch(e => e.message)".to_string(), string("never started")),
+        ("async function* g() { try { yield 1; } finally { await null; yield 'cleanup'; } } const it = g(); await it.next(); (await it.return(2)).value".to_string(), string("cleanup")),
+        // Methods, and the intrinsics behind them
+        ("const o = { async *m() { yield 'o'; } }; class C { async *m() { yield 'c'; } static async *s() { yield 's'; } } [(await o.m().next()).value, (await new C().m().next()).value, (await C.s().next()).value].join()".to_string(), string("o,c,s")),
+        ("async function* g() {} const AsyncGeneratorFunction = Object.getPrototypeOf(g).constructor; [AsyncGeneratorFunction.name, Object.prototype.toString.call(g()), Object.getPrototypeOf(g.prototype) === AsyncGeneratorFunction.prototype.prototype].join()".to_string(), string("AsyncGeneratorFunction,[object AsyncGenerator],true")),
+        ("const g = new (Object.getPrototypeOf(async function* () {}).constructor)('a', 'yield a; yield a + 1;'); const seen = []; for await (const x of g(1)) seen.push(x); seen.join()".to_string(), string("1,2")),
+        ("await (async function* () {}).prototype.next.call({}).catch(e => e.message)".to_string(), string("Method [AsyncGenerator].prototype.next called on incompatible receiver #<Object>")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(&input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_promise_errors() {
+    let cases = vec![
+        ("Promise()", "TypeError: Promise constructor cannot be invoked without 'new'"),
+        ("new Promise(1)", "TypeError: Promise resolver 1 is not a function"),
+        ("Promise.prototype.then.call(1)", "TypeError: Method Promise.prototype.then called on incompatible receiver 1"),
+        ("Promise.resolve.call(1)", "TypeError: Method Promise.resolve called on incompatible receiver 1"),
+        ("Promise.all.call(1)", "TypeError: 1 is not a constructor"),
+        ("const p = Promise.resolve(); const q = p.then(() => q); await q", "TypeError: Chaining cycle detected for promise #<Promise>"),
+        ("async function f() {} new f()", "TypeError: f is not a constructor"),
+        ("async function f() { throw new RangeError('r'); } await f()", "RangeError: r"),
+        // Rejections nothing handles are reported once the jobs have run
+        ("Promise.reject(new Error('boom'))", "Error: boom"),
+        ("Promise.reject(5); 1", "Uncaught 5"),
+        ("Promise.resolve().then(() => { throw new TypeError('in job'); }); 1", "TypeError: in job"),
+        ("function f() { await 1; }", "await is only valid in async functions and the top level bodies of modules"),
+        ("async function* g() { yield 1; } for (const x of g()) {}", "TypeError: object is not iterable"),
+        ("async function* g() { yield 1; } const it = g(); it.next = function* () {}.prototype.next; [...{ [Symbol.iterator]: () => it }]", "TypeError: Method [Generator].prototype.next called on incompatible receiver #<Object>"),
+        ("async function* g() { throw new RangeError('in body'); } await g().next()", "RangeError: in body"),
+        ("async function* g() {} new g()", "TypeError: g is not a constructor"),
+        ("for await (const x of 1) {}", "TypeError: 1 is not async iterable"),
+        ("for await (const x of { [Symbol.asyncIterator]: () => 1 }) {}", "TypeError: Result of the Symbol.asyncIterator method is not an object"),
+        ("for await (const x of { [Symbol.asyncIterator]: () => ({ next: () => 1 }) }) {}", "TypeError: Iterator result 1 is not an object"),
+        ("function f() { for await (const x of []) {} }", "for await is only valid in async functions and the top level bodies of modules"),
+        ("for await (const x in []) {}", "for await loops must iterate with 'of'"),
+        ("class C { async constructor() {} }", "Class constructor may not be an async method"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_handled_rejections_are_not_reported() {
+    let cases = vec![
+        ("const p = Promise.reject(5); p.catch(() => {}); 1", Value::Number(1.0)),
+        ("const p = Promise.reject(5); Promise.resolve().then(() => p.catch(() => {})); 1", Value::Number(1.0)),
+        ("async function f() { throw 1; } f().catch(() => {}); 2", Value::Number(2.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+/// A script waiting on a promise that nothing settles yet returns the
+/// promise for its completion; it carries on once another script settles
+/// what it waits for, even after a collection.
+#[test]
+fn test_top_level_await_across_runs() {
+    let mut interpreter = Interpreter::new();
+    let pending = run_in(&mut interpreter, "var resolve; var out = await new Promise(r => resolve = r) + [1].length; out");
+    let Ok(Value::Object(_)) = pending else {
+        panic!("expected a pending promise, got {:?}", pending)
+    };
+    assert_eq!(run_in(&mut interpreter, "String(out)"), Ok(string("undefined")));
+    interpreter.collect_garbage();
+    run_in(&mut interpreter, "resolve(5)").unwrap();
+    assert_eq!(run_in(&mut interpreter, "out"), Ok(Value::Number(6.0)));
+}
+
+#[test]
+fn test_run_until_idle_reports_the_last_script() {
+    let mut interpreter = Interpreter::new();
+    assert_eq!(interpreter.run_until_idle(), Ok(Value::Undefined));
+    assert_eq!(run_in(&mut interpreter, "await Promise.resolve(3)"), Ok(Value::Number(3.0)));
+    assert_eq!(interpreter.run_until_idle(), Ok(Value::Number(3.0)));
+}
+
+#[test]
+fn test_awaiting_bodies_survive_collection() {
+    let mut interpreter = Interpreter::new();
+    let source = "
+        async function work(i) { const o = { arr: [i, i + 1] }; await null; await new Promise(r => r()); return o.arr[1]; }
+        let total = 0;
+        for (let i = 0; i < 3000; i++) total += await work(i);
+        const results = await Promise.all(Array.from({ length: 3000 }, (_, i) => work(i)));
+        total + results.reduce((a, b) => a + b)
+    ";
+    assert_eq!(run_in(&mut interpreter, source), Ok(Value::Number(9003000.0)));
+}
+
+#[test]
+fn test_suspended_async_generators_survive_collection() {
+    let mut interpreter = Interpreter::new();
+    let source = "
+        async function* pairs(i) { const o = { arr: [i, i + 1] }; await null; yield o; yield { arr: o.arr.slice() }; }
+        let total = 0;
+        for (let i = 0; i < 2000; i++) {
+            const it = pairs(i);
+            const [a, b] = await Promise.all([it.next(), it.next(), it.next({ sent: [i] })]);
+            total += a.value.arr[1] + b.value.arr[0];
+        }
+        total
+    ";
+    assert_eq!(run_in(&mut interpreter, source), Ok(Value::Number(4000000.0)));
+}
+
+/// Tens of thousands of async calls can wait at once, each keeping its
+/// body suspended.
+#[test]
+fn test_many_pending_async_calls() {
+    let source = "
+        async function wait(i) { await null; return i; }
+        const results = await Promise.all(Array.from({ length: 41000 }, (_, i) => wait(i)));
+        results.length + ',' + results[40999]
+    ";
+    assert_eq!(run(source), Ok(string("41000,40999")));
+}
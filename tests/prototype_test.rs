@@ -0,0 +1,167 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_prototype_chain() {
+    let cases = vec![
+        ("const base = { greet() { return \"hi \" + this.name; } }; const o = Object.create(base); o.name = \"ann\"; o.greet()", string("hi ann")),
+        ("const p = { x: 1 }; const o = Object.create(p); p.x = 2; o.x", Value::Number(2.0)),
+        ("const o = Object.create(null); Object.getPrototypeOf(o) === null", Value::Boolean(true)),
+        ("Object.getPrototypeOf([]) === Array.prototype", Value::Boolean(true)),
+        ("Object.getPrototypeOf(Array.prototype) === Object.prototype", Value::Boolean(true)),
+        ("const a = {}; const b = { y: 2 }; Object.setPrototypeOf(a, b); a.y", Value::Number(2.0)),
+        ("const o = { __proto__: { z: 3 } }; o.z", Value::Number(3.0)),
+        ("function Point() {} const p = Object.create(Point.prototype); p instanceof Point", Value::Boolean(true)),
+        ("function A() {} ({}) instanceof A", Value::Boolean(false)),
+        ("[] instanceof Array && [] instanceof Object", Value::Boolean(true)),
+        ("\"a\" in { a: 1 } && 0 in [5] && !(\"b\" in { a: 1 })", Value::Boolean(true)),
+        ("\"toString\" in {}", Value::Boolean(true)),
+        ("({ a: 1 }).hasOwnProperty(\"a\") && !({}).hasOwnProperty(\"toString\")", Value::Boolean(true)),
+        ("Object.prototype.toString.call([]) + Object.prototype.toString.call(null)", string("[object Array][object Null]")),
+        ("({}) + \"\"", string("[object Object]")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+/// Booleans reach `Object.prototype` through `Boolean.prototype`, and
+/// their wrappers convert back to the boolean.
+#[test]
+fn test_boolean_prototype() {
+    let cases = vec![
+        ("true.toString() + ',' + false.valueOf()", string("true,false")),
+        ("const flag = true; [typeof flag.hasOwnProperty, typeof flag.valueOf, flag.constructor === Boolean].join()", string("function,function,true")),
+        ("`${Object(false)}` + ',' + (Object(true) instanceof Boolean)", string("false,true")),
+        ("[Boolean(0), Boolean('x'), typeof new Boolean(false), new Boolean(false) ? 1 : 2].join()", string("false,true,object,1")),
+        ("Object.getPrototypeOf(true) === Boolean.prototype && Object.getPrototypeOf(Boolean.prototype) === Object.prototype", Value::Boolean(true)),
+        ("JSON.stringify([new Boolean(true), Boolean.prototype.valueOf()])", string("[true,false]")),
+        ("class B extends Boolean {} new B(1).valueOf()", Value::Boolean(true)),
+        ("try { Boolean.prototype.toString.call(1); } catch (e) { e.message }", string("Boolean.prototype.toString requires that 'this' be a Boolean")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_object_statics() {
+    let cases = vec![
+        ("Object.keys({ b: 1, a: 2, 2: 0, 1: 0 }).join()", "1,2,b,a"),
+        ("Object.values({ a: 1, b: 2 }).join()", "1,2"),
+        ("Object.entries({ a: 1, b: 2 }).join(\";\")", "a,1;b,2"),
+        ("Object.keys(\"ab\").join()", "0,1"),
+        ("const o = Object.assign({ a: 1 }, { b: 2 }, null, { a: 3 }); Object.entries(o).join()", "a,3,b,2"),
+        ("Object.keys(Object.fromEntries([[\"x\", 1], [\"y\", 2]])).join()", "x,y"),
+        ("Object.getOwnPropertyNames([1]).join()", "0,length"),
+        ("const o = { a: 1 }; delete o.a; o.a = 2; o.b = 3; Object.keys(o).join()", "a,b"),
+        ("Object.is(0 / 0, 0 / 0) + \",\" + Object.is(0, -0)", "true,false"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(string(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_property_descriptors() {
+    let source = "
+        const o = {};
+        Object.defineProperty(o, \"hidden\", { value: 1 });
+        Object.defineProperty(o, \"shown\", { value: 2, enumerable: true, writable: true });
+        const d = Object.getOwnPropertyDescriptor(o, \"hidden\");
+        Object.keys(o).join() + \"|\" + d.value + d.writable + d.enumerable + d.configurable
+    ";
+    assert_eq!(run(source), Ok(string("shown|1falsefalsefalse")));
+
+    let source = "
+        let store = 0;
+        const o = {};
+        Object.defineProperty(o, \"x\", { get() { return store * 2; }, set(v) { store = v; }, enumerable: true });
+        o.x = 5;
+        o.x + Object.keys(o).length
+    ";
+    assert_eq!(run(source), Ok(Value::Number(11.0)));
+
+    let cases = vec![
+        (
+            "const o = {}; Object.defineProperty(o, \"x\", { value: 1 }); o.x = 2",
+            "TypeError: Cannot assign to read only property 'x' of object '#<Object>'",
+        ),
+        (
+            "const o = {}; Object.defineProperty(o, \"x\", { value: 1 }); Object.defineProperty(o, \"x\", { value: 2 })",
+            "TypeError: Cannot redefine property: x",
+        ),
+        (
+            "Object.defineProperty({}, \"x\", { get() {}, value: 1 })",
+            "TypeError: Invalid property descriptor. Cannot both specify accessors and a value or writable attribute",
+        ),
+        ("Object.defineProperty({}, \"x\", { get: 1 })", "TypeError: Getter must be a function: 1"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_freeze_and_seal() {
+    let cases = vec![
+        ("const o = Object.freeze({ a: 1 }); o.a = 2", "TypeError: Cannot assign to read only property 'a' of object '#<Object>'"),
+        ("const o = Object.freeze({ a: 1 }); o.b = 2", "TypeError: Cannot add property b, object is not extensible"),
+        ("const o = Object.seal({ a: 1 }); delete o.a", "TypeError: Cannot delete property 'a' of object '#<Object>'"),
+        ("const a = Object.freeze([1]); a.push(2)", "TypeError: Cannot add property 1, object is not extensible"),
+        ("const a = {}; const b = Object.create(a); Object.setPrototypeOf(a, b)", "TypeError: Cyclic __proto__ value"),
+        ("1 in 2", "TypeError: Cannot use 'in' operator to search for '1' in 2"),
+        ("({}) instanceof {}", "TypeError: Right-hand side of 'instanceof' is not callable"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+
+    let source = "
+        const o = Object.seal({ a: 1 });
+        o.a = 2;
+        o.a + \",\" + Object.isSealed(o) + \",\" + Object.isFrozen(o) + \",\" + Object.isFrozen(Object.freeze(o))
+    ";
+    assert_eq!(run(source), Ok(string("2,true,false,true")));
+}
+
+#[test]
+fn test_for_in() {
+    let cases = vec![
+        ("let s = \"\"; for (const k in { b: 1, a: 2, 1: 3 }) { s = s + k; } s", "1ba"),
+        ("const p = { inherited: 1 }; const o = Object.create(p); o.own = 2; let s = \"\"; for (let k in o) s = s + k + \",\"; s", "own,inherited,"),
+        ("const o = { a: 1, b: 2, c: 3 }; let s = \"\"; for (const k in o) { delete o.b; s = s + k; } s", "ac"),
+        ("let s = \"\"; for (var i in [7, 8]) s = s + i; s + i", "011"),
+        ("const o = {}; Object.defineProperty(o, \"h\", { value: 1 }); o.v = 2; let s = \"\"; for (const k in o) s = s + k; s", "v"),
+        ("let k; let s = \"\"; for (k in \"ab\") s = s + k; s", "01"),
+        ("const fns = []; for (const k in { x: 1, y: 2 }) fns.push(() => k); fns[0]() + fns[1]()", "xy"),
+        ("let s = \"\"; outer: for (const a in [1, 2]) { for (const b in [1, 2]) { if (b === \"1\") continue outer; s = s + a + b; } } s", "0010"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(string(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_function_prototype_methods() {
+    let cases = vec![
+        ("function f(a, b) { return this.x + a + b; } f.call({ x: 1 }, 2, 3)", Value::Number(6.0)),
+        ("function f(a, b) { return this.x + a + b; } f.apply({ x: 1 }, [2, 3])", Value::Number(6.0)),
+        ("function f(a, b) { return this.x + a + b; } const g = f.bind({ x: 1 }, 2); g(3) + g.length", Value::Number(7.0)),
+        ("function f() {} f.bind(null).name", string("bound f")),
+        ("function F() {} const B = F.bind(null); ({ __proto__: F.prototype }) instanceof B", Value::Boolean(true)),
+        ("const add = Function(\"a\", \"b\", \"return a + b\"); add(2, 3)", Value::Number(5.0)),
+        ("Object.keys(function () {}).length", Value::Number(0.0)),
+        ("function F() {} F.prototype.constructor === F", Value::Boolean(true)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
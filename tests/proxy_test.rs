@@ -0,0 +1,150 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+/// A handler whose traps record their names in `log` and then forward
+/// to `Reflect`.
+const LOGGING_HANDLER: &str = r#"
+    const log = [];
+    const handler = {};
+    for (const trap of Reflect.ownKeys(Reflect)) {
+        if (trap === Symbol.toStringTag) continue;
+        handler[trap] = (...args) => {
+            log.push(trap);
+            return Reflect[trap](...args);
+        };
+    }
+"#;
+
+#[test]
+fn test_traps() {
+    let cases = vec![
+        ("const p = new Proxy({ a: 1 }, { get(t, k) { return k === 'a' ? t.a + 1 : 'missing'; } }); [p.a, p.b].join()", string("2,missing")),
+        ("const t = {}; const p = new Proxy(t, { set(t, k, v) { t[k] = v * 2; return true; } }); p.x = 4; t.x", Value::Number(8.0)),
+        ("const p = new Proxy({}, { has(t, k) { return k.startsWith('x'); } }); ['xa' in p, 'y' in p].join()", string("true,false")),
+        ("const t = { a: 1, b: 2 }; const p = new Proxy(t, { deleteProperty(t, k) { return k !== 'a' && delete t[k]; } }); delete p.b; Object.keys(t).join()", string("a")),
+        ("const p = new Proxy({}, { ownKeys() { return ['b', 'a']; }, getOwnPropertyDescriptor() { return { value: 0, enumerable: true, configurable: true }; } }); Object.keys(p).join()", string("b,a")),
+        ("const p = new Proxy({}, { getPrototypeOf() { return Array.prototype; } }); [p instanceof Array, Array.prototype.isPrototypeOf(p)].join()", string("true,true")),
+        ("const f = new Proxy((a, b) => a + b, { apply(t, self, args) { return t(...args) * 10; } }); f(1, 2)", Value::Number(30.0)),
+        ("class A { constructor(x) { this.x = x; } } const C = new Proxy(A, { construct(t, args) { return new t(args[0] + 1); } }); new C(1).x", Value::Number(2.0)),
+        ("const p = new Proxy({}, {}); p.a = 1; [p.a, 'a' in p, Object.keys(p).join()].join()", string("1,true,a")),
+        ("const p = new Proxy({ x: 1, y: 2 }, {}); const keys = []; for (const k in p) keys.push(k); keys.join()", string("x,y")),
+        ("const o = Object.create(new Proxy({}, { get(t, k, r) { return r === o ? 'receiver' : 'other'; } })); o.anything", string("receiver")),
+        ("class B { hi() { return 'hi ' + this.n; } } class D extends new Proxy(B, {}) { constructor() { super(); this.n = 'd'; } } new D().hi()", string("hi d")),
+        ("const a = new Proxy([1, 2, 3], {}); [Array.isArray(a), a.length, a.map(x => x * 2).join()].join()", string("true,3,2,4,6")),
+        ("Object.prototype.toString.call(new Proxy([], {}))", string("[object Array]")),
+        ("JSON.stringify(new Proxy({ a: [1, new Proxy([2], {})] }, {}))", string(r#"{"a":[1,[2]]}"#)),
+        ("const a = new Proxy([1, 2, 3], {}); a.length = 1; a.join()", string("1")),
+        ("Object.isFrozen(Object.freeze(new Proxy({ a: 1 }, {})))", Value::Boolean(true)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_trap_order() {
+    let cases = vec![
+        ("const p = new Proxy({ a: 1 }, handler); p.a; p.b = 2; 'a' in p; delete p.a; log.join()", "get,set,getOwnPropertyDescriptor,defineProperty,has,deleteProperty"),
+        ("const p = new Proxy({ a: 1 }, handler); Object.keys(p); log.join()", "ownKeys,getOwnPropertyDescriptor"),
+        ("const p = new Proxy({}, handler); Object.setPrototypeOf(p, null); Object.getPrototypeOf(p); log.join()", "setPrototypeOf,getPrototypeOf"),
+        ("const p = new Proxy({}, handler); Object.preventExtensions(p); Object.isExtensible(p); log.join()", "preventExtensions,isExtensible"),
+        ("const p = new Proxy({ a: 1 }, handler); Object.freeze(p); log.join()", "preventExtensions,ownKeys,getOwnPropertyDescriptor,defineProperty"),
+        ("const p = new Proxy(function () {}, handler); p(); new p(); log.join()", "apply,construct,get"),
+    ];
+
+    for (input, expected) in cases {
+        let source = format!("{}{}", LOGGING_HANDLER, input);
+        assert_eq!(run(&source), Ok(string(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_invariants() {
+    let cases = vec![
+        (
+            "new Proxy(Object.freeze({ k: 1 }), { get() { return 2; } }).k",
+            "TypeError: 'get' on proxy: property 'k' is a read-only and non-configurable data property on the proxy target but the proxy did not return its actual value (expected '1' but got '2')",
+        ),
+        (
+            "new Proxy(Object.freeze({ k: 1 }), { set() { return true; } }).k = 2",
+            "TypeError: 'set' on proxy: trap returned truish for property 'k' which exists in the proxy target as a non-configurable and non-writable data property with a different value",
+        ),
+        ("new Proxy({}, { set() { return false; } }).x = 1", "TypeError: 'set' on proxy: trap returned falsish for property 'x'"),
+        (
+            "'k' in new Proxy(Object.seal({ k: 1 }), { has() { return false; } })",
+            "TypeError: 'has' on proxy: trap returned falsish for property 'k' which exists in the proxy target as non-configurable",
+        ),
+        (
+            "delete new Proxy(Object.seal({ k: 1 }), { deleteProperty() { return true; } }).k",
+            "TypeError: 'deleteProperty' on proxy: trap returned truish for property 'k' which is non-configurable in the proxy target",
+        ),
+        ("delete new Proxy({}, { deleteProperty() { return false; } }).k", "TypeError: 'deleteProperty' on proxy: trap returned falsish for property 'k'"),
+        (
+            "Object.defineProperty(new Proxy(Object.preventExtensions({}), { defineProperty() { return true; } }), 'j', { value: 1 })",
+            "TypeError: 'defineProperty' on proxy: trap returned truish for adding property 'j' to the non-extensible proxy target",
+        ),
+        (
+            "Object.defineProperty(new Proxy({}, { defineProperty() { return false; } }), 'j', { value: 1 })",
+            "TypeError: 'defineProperty' on proxy: trap returned falsish for property 'j'",
+        ),
+        (
+            "Object.getOwnPropertyDescriptor(new Proxy(Object.seal({ k: 1 }), { getOwnPropertyDescriptor() {} }), 'k')",
+            "TypeError: 'getOwnPropertyDescriptor' on proxy: trap returned undefined for property 'k' which is non-configurable in the proxy target",
+        ),
+        (
+            "Object.getOwnPropertyDescriptor(new Proxy({}, { getOwnPropertyDescriptor() { return { value: 1, configurable: false }; } }), 'k')",
+            "TypeError: 'getOwnPropertyDescriptor' on proxy: trap reported non-configurability for property 'k' which is either non-existent or configurable in the proxy target",
+        ),
+        ("Object.getPrototypeOf(new Proxy({}, { getPrototypeOf() { return 1; } }))", "TypeError: 'getPrototypeOf' on proxy: trap returned neither object nor null"),
+        (
+            "Object.getPrototypeOf(new Proxy(Object.preventExtensions({}), { getPrototypeOf() { return Array.prototype; } }))",
+            "TypeError: 'getPrototypeOf' on proxy: proxy target is non-extensible but the trap did not return its actual prototype",
+        ),
+        ("Object.setPrototypeOf(new Proxy({}, { setPrototypeOf() { return false; } }), null)", "TypeError: 'setPrototypeOf' on proxy: trap returned falsish"),
+        (
+            "Object.isExtensible(new Proxy({}, { isExtensible() { return false; } }))",
+            "TypeError: 'isExtensible' on proxy: trap result does not reflect extensibility of proxy target (which is 'true')",
+        ),
+        (
+            "Object.preventExtensions(new Proxy({}, { preventExtensions() { return true; } }))",
+            "TypeError: 'preventExtensions' on proxy: trap returned truish but the proxy target is extensible",
+        ),
+        ("Object.keys(new Proxy({}, { ownKeys() { return ['a', 'a']; } }))", "TypeError: 'ownKeys' on proxy: trap returned duplicate entries"),
+        ("Object.keys(new Proxy(Object.freeze({ k: 1 }), { ownKeys() { return []; } }))", "TypeError: 'ownKeys' on proxy: trap result did not include 'k'"),
+        (
+            "Object.keys(new Proxy(Object.preventExtensions({ k: 1 }), { ownKeys() { return ['k', 'z']; } }))",
+            "TypeError: 'ownKeys' on proxy: trap returned extra keys but proxy target is non-extensible",
+        ),
+        ("Object.keys(new Proxy({}, { ownKeys() { return [1]; } }))", "TypeError: 1 is not a valid property name"),
+        ("new (new Proxy(function () {}, { construct() { return 1; } }))()", "TypeError: 'construct' on proxy: trap returned non-object ('1')"),
+        ("new Proxy({}, { get: 1 }).x", "TypeError: 1 is not a function"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_creation_and_revocation() {
+    let cases = vec![
+        ("Proxy({}, {})", Err("TypeError: Constructor Proxy requires 'new'")),
+        ("new Proxy(1, {})", Err("TypeError: Cannot create proxy with a non-object as target or handler")),
+        ("new Proxy({}, null)", Err("TypeError: Cannot create proxy with a non-object as target or handler")),
+        ("const { proxy, revoke } = Proxy.revocable({}, {}); proxy.a = 1; revoke(); proxy.a", Err("TypeError: Cannot perform 'get' on a proxy that has been revoked")),
+        ("const { proxy, revoke } = Proxy.revocable({}, {}); revoke(); revoke(); 'a' in proxy", Err("TypeError: Cannot perform 'has' on a proxy that has been revoked")),
+        ("const { proxy, revoke } = Proxy.revocable([], {}); revoke(); Array.isArray(proxy)", Err("TypeError: Cannot perform 'IsArray' on a proxy that has been revoked")),
+        ("const { proxy, revoke } = Proxy.revocable({ a: 1 }, {}); const a = proxy.a; revoke(); a", Ok(Value::Number(1.0))),
+        ("new Proxy({}, {})()", Err("TypeError: object is not a function")),
+        ("new Proxy(() => 1, {})()", Ok(Value::Number(1.0))),
+        ("'prototype' in Proxy", Ok(Value::Boolean(false))),
+        ("[Proxy.length, Proxy.name, Proxy.revocable.length].join()", Ok(string("2,Proxy,2"))),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), expected.map_err(str::to_string), "{}", input);
+    }
+}
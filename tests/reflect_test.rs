@@ -0,0 +1,52 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_reflect() {
+    let cases = vec![
+        ("Reflect.apply(Math.max, null, [1, 3, 2])", Value::Number(3.0)),
+        ("Reflect.apply(function () { return this.x; }, { x: 4 }, [])", Value::Number(4.0)),
+        ("class A { constructor(x) { this.x = x; } } Reflect.construct(A, [7]).x", Value::Number(7.0)),
+        ("class A {} class B {} Object.getPrototypeOf(Reflect.construct(A, [], B)) === B.prototype", Value::Boolean(true)),
+        ("const o = {}; [Reflect.defineProperty(o, 'a', { value: 1 }), o.a].join()", string("true,1")),
+        ("Reflect.defineProperty(Object.freeze({}), 'a', { value: 1 })", Value::Boolean(false)),
+        ("const o = { a: 1 }; [Reflect.deleteProperty(o, 'a'), 'a' in o].join()", string("true,false")),
+        ("Reflect.deleteProperty(Object.freeze({ a: 1 }), 'a')", Value::Boolean(false)),
+        ("Reflect.get({ a: 1 }, 'a')", Value::Number(1.0)),
+        ("Reflect.get({ get a() { return this.b; } }, 'a', { b: 2 })", Value::Number(2.0)),
+        ("Reflect.getOwnPropertyDescriptor({ a: 1 }, 'a').writable", Value::Boolean(true)),
+        ("Reflect.getOwnPropertyDescriptor({}, 'a') === Reflect.getOwnPropertyDescriptor({}, 'b')", Value::Boolean(true)),
+        ("Reflect.getPrototypeOf([]) === Array.prototype", Value::Boolean(true)),
+        ("[Reflect.has({ a: 1 }, 'a'), Reflect.has({}, 'toString'), Reflect.has({}, 'b')].join()", string("true,true,false")),
+        ("const o = {}; [Reflect.isExtensible(o), Reflect.preventExtensions(o), Reflect.isExtensible(o)].join()", string("true,true,false")),
+        ("const s = Symbol('s'); const keys = Reflect.ownKeys({ b: 1, 1: 2, [s]: 3, a: 4 }); [keys.length, keys[3] === s, keys.slice(0, 3).join()].join()", string("4,true,1,b,a")),
+        ("const o = {}; [Reflect.set(o, 'a', 1), o.a].join()", string("true,1")),
+        ("Reflect.set(Object.freeze({ a: 1 }), 'a', 2)", Value::Boolean(false)),
+        ("const receiver = {}; Reflect.set({ set a(v) { this.v = v; } }, 'a', 3, receiver); receiver.v", Value::Number(3.0)),
+        ("const o = {}; [Reflect.setPrototypeOf(o, null), Reflect.getPrototypeOf(o)].join()", string("true,")),
+        ("const a = {}; const b = Object.create(a); Reflect.setPrototypeOf(a, b)", Value::Boolean(false)),
+        ("Object.prototype.toString.call(Reflect)", string("[object Reflect]")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_reflect_errors() {
+    let cases = vec![
+        ("Reflect.get(1, 'a')", "TypeError: Reflect.get called on non-object"),
+        ("Reflect.ownKeys('abc')", "TypeError: Reflect.ownKeys called on non-object"),
+        ("Reflect.apply(Math.max, null)", "TypeError: CreateListFromArrayLike called on non-object"),
+        ("Reflect.construct(Math.max, [])", "TypeError: function is not a constructor"),
+        ("Reflect.setPrototypeOf({}, 1)", "TypeError: Object prototype may only be an Object or null: 1"),
+        ("Reflect.defineProperty({}, 'a', 1)", "TypeError: Property description must be an object: 1"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
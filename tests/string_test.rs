@@ -0,0 +1,183 @@
+mod common;
+
+use common::run;
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_character_access() {
+    let cases = vec![
+        ("'abc'.at(-1)", Value::String("c".into())),
+        ("'abc'.at(3)", Value::Undefined),
+        ("'abc'.charAt(1)", Value::String("b".into())),
+        ("'abc'.charAt(5)", Value::String("".into())),
+        ("'abc'.charCodeAt(0)", Value::Number(97.0)),
+        ("'😀'.length", Value::Number(2.0)),
+        ("'😀'.codePointAt(0)", Value::Number(128512.0)),
+        ("'😀'.codePointAt(1)", Value::Number(56832.0)),
+        ("'😀'.charCodeAt(0)", Value::Number(55357.0)),
+        ("[...'a😀b'].length", Value::Number(3.0)),
+        ("'abc'[1]", Value::String("b".into())),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_lone_surrogates() {
+    let cases = vec![
+        ("'😀'.slice(0, 1) + '😀'.slice(1) === '😀'", Value::Boolean(true)),
+        ("'😀'[0].charCodeAt(0)", Value::Number(55357.0)),
+        ("'\\uD83D'.codePointAt(0)", Value::Number(55357.0)),
+        ("'\\uD83D' === '\\uDE00'", Value::Boolean(false)),
+        ("`\\uD83D${'\\uDE00'}` === '😀'", Value::Boolean(true)),
+        ("['\\uD83D', '\\uDE00'].join('') === '😀'", Value::Boolean(true)),
+        ("String.fromCharCode(0xD83D).concat(String.fromCodePoint(0xDE00)) === '😀'", Value::Boolean(true)),
+        ("[...'a\\uD83Db'].length", Value::Number(3.0)),
+        ("'\\uD83D'.isWellFormed() + ',' + '😀'.isWellFormed()", Value::String("false,true".into())),
+        ("'\\uD83Da'.toWellFormed().charCodeAt(0)", Value::Number(65533.0)),
+        ("'a\\uD83D'.toUpperCase().charCodeAt(1)", Value::Number(55357.0)),
+        ("' \\uDE00 '.trim().length", Value::Number(1.0)),
+        ("'\\uDE00' < '\\uFFFD'", Value::Boolean(true)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_searching_and_slicing() {
+    let cases = vec![
+        ("'Hello'.indexOf('l')", Value::Number(2.0)),
+        ("'Hello'.indexOf('l', 3)", Value::Number(3.0)),
+        ("'Hello'.indexOf('')", Value::Number(0.0)),
+        ("'Hello'.lastIndexOf('l')", Value::Number(3.0)),
+        ("'Hello'.lastIndexOf('l', 2)", Value::Number(2.0)),
+        ("'abc'.includes('bc', 2)", Value::Boolean(false)),
+        ("'abc'.startsWith('b', 1)", Value::Boolean(true)),
+        ("'abc'.endsWith('a', 1)", Value::Boolean(true)),
+        ("'Hello'.slice(-3, -1)", Value::String("ll".into())),
+        ("'Hello'.slice(3, 1)", Value::String("".into())),
+        ("'Hello'.substring(3, 1)", Value::String("el".into())),
+        ("'Hello'.substring(-5, 2)", Value::String("He".into())),
+        ("'Hello'.substr(-3, 2)", Value::String("ll".into())),
+        ("'a-b-c'.split('-').join('|')", Value::String("a|b|c".into())),
+        ("'abc'.split('').length", Value::Number(3.0)),
+        ("'a,b,c'.split(',', 2).join('|')", Value::String("a|b".into())),
+        ("''.split(',').length", Value::Number(1.0)),
+        ("''.split('').length", Value::Number(0.0)),
+        ("'x'.split()[0]", Value::String("x".into())),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_transforming() {
+    let cases = vec![
+        ("'aaa'.replace('a', 'b')", "baa"),
+        ("'aaa'.replaceAll('a', '$&$&')", "aaaaaa"),
+        ("'abc'.replace('b', '[$`|$\\'|$$]')", "a[a|c|$]c"),
+        ("'xy'.replaceAll('', '_')", "_x_y_"),
+        ("'abc'.replace('b', (m, p, s) => m.toUpperCase() + p + s)", "aB1abcc"),
+        ("'abc'.replace('z', 'y')", "abc"),
+        ("'5'.padStart(3, '0')", "005"),
+        ("'ab'.padEnd(5, 'xy')", "abxyx"),
+        ("'ab'.padStart(5, '')", "ab"),
+        ("'\\t hi \\n\\u00a0'.trim()", "hi"),
+        ("'  hi  '.trimStart() + '|'", "hi  |"),
+        ("'  hi  '.trimEnd() + '|'", "  hi|"),
+        ("'ab'.repeat(3)", "ababab"),
+        ("'Straße'.toUpperCase()", "STRASSE"),
+        ("'ÀB'.toLowerCase()", "àb"),
+        ("'ΣΑΣ'.toLowerCase()", "σας"),
+        ("'ΟΔΥΣΣΕΥΣ. Σ ΑΣ'.toLowerCase()", "οδυσσευς. σ ας"),
+        ("'ΑΣ\\u0301Β'.toLowerCase()", "ασ\u{301}β"),
+        ("'a'.concat(1, null)", "a1null"),
+        ("'café'.normalize('NFD').length + ''", "5"),
+        ("'cafe\\u0301'.normalize() === 'café' ? 'yes' : 'no'", "yes"),
+        ("'ﬁ'.normalize('NFKC')", "fi"),
+        ("'한'.normalize('NFD').normalize('NFC')", "한"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::String(expected.into())), "{}", input);
+    }
+}
+
+#[test]
+fn test_locale_compare() {
+    let cases = vec![
+        ("'a'.localeCompare('b')", -1.0),
+        ("'b'.localeCompare('a')", 1.0),
+        ("'a'.localeCompare('a')", 0.0),
+        ("'a'.localeCompare('A')", -1.0),
+        ("'résumé'.localeCompare('resume')", 1.0),
+        ("'résumé'.localeCompare('resumes')", -1.0),
+        ("'Z'.localeCompare('a')", 1.0),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(Value::Number(expected)), "{}", input);
+    }
+}
+
+#[test]
+fn test_string_constructor() {
+    let cases = vec![
+        ("String(123)", Value::String("123".into())),
+        ("String()", Value::String("".into())),
+        ("String([1, 2])", Value::String("1,2".into())),
+        ("new String('ab').length", Value::Number(2.0)),
+        ("new String('ab')[1]", Value::String("b".into())),
+        ("Object.keys(new String('ab')).length", Value::Number(2.0)),
+        ("new String('ab') + 'c'", Value::String("abc".into())),
+        ("Object.prototype.toString.call(new String(''))", Value::String("[object String]".into())),
+        ("String.fromCharCode(72, 105, 65601)", Value::String("HiA".into())),
+        ("String.fromCodePoint(128512)", Value::String("😀".into())),
+        ("String.raw({ raw: ['a', 'b', 'c'] }, 1, 2, 3)", Value::String("a1b2c".into())),
+        ("String.raw`a\\n${1 + 1}\\u{41}`", Value::String("a\\n2\\u{41}".into())),
+        ("String.prototype.trimLeft === String.prototype.trimStart", Value::Boolean(true)),
+        ("String.prototype.toUpperCase.call(true)", Value::String("TRUE".into())),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+/// Appending to a string in a loop takes linear time, and the pieces of
+/// long strings join up as if copied.
+#[test]
+fn test_building_long_strings() {
+    let cases = vec![
+        ("let s = ''; for (let i = 0; i < 200000; i++) s += 'x'; s.length + ',' + s.slice(-2)", Value::String("200000,xx".into())),
+        ("let s = ''; for (let i = 0; i < 100; i++) s = i % 10 + s; s.slice(0, 12)", Value::String("987654321098".into())),
+        ("const a = 'x'.repeat(100) + '\\uD83D'; (a + '\\uDE00').slice(100) === '😀'", Value::Boolean(true)),
+        ("const a = 'y'.repeat(70); const b = a + a; (b + b).length + ',' + (b + 'z' === a + a + 'z')", Value::String("280,true".into())),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_string_errors() {
+    let cases = vec![
+        ("'a'.repeat(-1)", "RangeError: Invalid count value: -1"),
+        ("'a'.repeat(1 / 0)", "RangeError: Invalid count value: Infinity"),
+        ("'a'.normalize('NFX')", "RangeError: The normalization form should be one of NFC, NFD, NFKC, NFKD."),
+        ("String.fromCodePoint(1.5)", "RangeError: Invalid code point 1.5"),
+        ("String.prototype.trim.call(null)", "TypeError: String.prototype.trim called on null or undefined"),
+        ("String.prototype.toString.call(1)", "TypeError: String.prototype.toString requires that 'this' be a String"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
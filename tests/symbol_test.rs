@@ -0,0 +1,125 @@
+mod common;
+
+use common::{run, string};
+use ts_interpreter_rs::interpreter::Value;
+
+#[test]
+fn test_symbols() {
+    let cases = vec![
+        ("Symbol('a') === Symbol('a')", Value::Boolean(false)),
+        ("const s = Symbol('a'); s === s", Value::Boolean(true)),
+        ("[Symbol('a').description, String(Symbol().description), Symbol('').description.length].join()", string("a,undefined,0")),
+        ("[Symbol('a').toString(), String(Symbol()), String(Symbol.iterator)].join()", string("Symbol(a),Symbol(),Symbol(Symbol.iterator)")),
+        ("const s = Symbol('x'); s.valueOf() === s", Value::Boolean(true)),
+        ("Object.getPrototypeOf(Symbol()) === Symbol.prototype", Value::Boolean(true)),
+        ("Symbol.prototype.constructor === Symbol", Value::Boolean(true)),
+        ("!!Symbol('x')", Value::Boolean(true)),
+        ("Symbol.for('k') === Symbol.for('k')", Value::Boolean(true)),
+        ("Symbol.for('k') === Symbol('k')", Value::Boolean(false)),
+        ("[Symbol.keyFor(Symbol.for('k')), String(Symbol.keyFor(Symbol('k'))), String(Symbol.keyFor(Symbol.iterator))].join()", string("k,undefined,undefined")),
+        ("Symbol.for(1).description", string("1")),
+        ("[Symbol.asyncIterator, Symbol.hasInstance, Symbol.species, Symbol.toPrimitive, Symbol.toStringTag].map(String).join()", string("Symbol(Symbol.asyncIterator),Symbol(Symbol.hasInstance),Symbol(Symbol.species),Symbol(Symbol.toPrimitive),Symbol(Symbol.toStringTag)")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_symbol_keys() {
+    let cases = vec![
+        ("const s = Symbol('s'); const o = { [s]: 1, a: 2 }; o[s]", Value::Number(1.0)),
+        ("const s = Symbol('s'); const o = { [s]: 1, a: 2 }; Object.keys(o).join()", string("a")),
+        ("const s = Symbol('s'); const o = { [s]: 1, a: 2 }; JSON.stringify(o)", string(r#"{"a":2}"#)),
+        ("const s = Symbol('s'); const o = { [s]: 1 }; let n = 0; for (const k in o) n++; n", Value::Number(0.0)),
+        ("const s = Symbol('s'); const o = { a: 1, [s]: 2 }; Object.getOwnPropertySymbols(o)[0] === s", Value::Boolean(true)),
+        ("Object.getOwnPropertySymbols({ a: 1 }).length", Value::Number(0.0)),
+        ("const s = Symbol(); const o = { [s]: 3 }; ({ ...o })[s] + Object.assign({}, o)[s]", Value::Number(6.0)),
+        ("const s = Symbol(); const o = {}; o[s] = 1; s in o && o.hasOwnProperty(s)", Value::Boolean(true)),
+        ("const s = Symbol(); const o = { [s]: 1 }; delete o[s]; s in o", Value::Boolean(false)),
+        ("const s = Symbol(); const o = {}; Object.defineProperty(o, s, { get: () => 4 }); o[s]", Value::Number(4.0)),
+        ("const s = Symbol('m'); const o = { [s]() {} }; o[s].name", string("[m]")),
+        ("class C { static [Symbol.hasInstance](x) { return x === 1; } } 1 instanceof C", Value::Boolean(true)),
+        ("const s = Symbol('k'); const o = { [s]: 1, b: 2 }; Object.getOwnPropertyDescriptors(o)[s].value", Value::Number(1.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_to_primitive() {
+    let cases = vec![
+        ("const o = { [Symbol.toPrimitive](hint) { return hint; } }; [String(o), o + '', +{ [Symbol.toPrimitive]: () => 5 }].join()", string("string,default,5")),
+        ("const hints = []; const o = { [Symbol.toPrimitive](hint) { hints.push(hint); return 1; } }; o * 2; String(o); o == 1; hints.join()", string("number,string,default")),
+        ("const o = { [Symbol.toPrimitive]: null, valueOf() { return 7; } }; o + 1", Value::Number(8.0)),
+        ("const o = { [Symbol.toPrimitive]() { return 2; }, valueOf() { return 7; } }; o + 1", Value::Number(3.0)),
+        ("Symbol.prototype[Symbol.toPrimitive].call(Symbol.iterator) === Symbol.iterator", Value::Boolean(true)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_to_string_tag() {
+    let cases = vec![
+        ("Object.prototype.toString.call({ [Symbol.toStringTag]: 'Custom' })", string("[object Custom]")),
+        ("class C { get [Symbol.toStringTag]() { return 'Tagged'; } } String(new C())", string("[object Tagged]")),
+        ("Object.prototype.toString.call({ [Symbol.toStringTag]: 1 })", string("[object Object]")),
+        ("Object.prototype.toString.call([])", string("[object Array]")),
+        ("Object.prototype.toString.call(new Map())", string("[object Map]")),
+        ("Object.prototype.toString.call(Symbol())", string("[object Symbol]")),
+        ("Object.prototype.toString.call(Math) + JSON[Symbol.toStringTag]", string("[object Math]JSON")),
+        ("function* g() {} Object.prototype.toString.call(g())", string("[object Generator]")),
+        ("Object.prototype.toString.call([].values())", string("[object Array Iterator]")),
+        ("Object.prototype.toString.call(async () => {})", string("[object AsyncFunction]")),
+        ("Object.prototype.toString.call(null) + Object.prototype.toString.call()", string("[object Null][object Undefined]")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+/// `Object(symbol)` wraps a symbol, and the `Symbol.prototype` methods
+/// unwrap it again.
+#[test]
+fn test_symbol_objects() {
+    let cases = vec![
+        ("const s = Symbol('d'); const w = Object(s); [typeof w, w == s, w === s, w.description].join()", string("object,true,false,d")),
+        ("const s = Symbol('d'); const w = Object(s); w.valueOf() === s && w[Symbol.toPrimitive]('default') === s", Value::Boolean(true)),
+        ("const w = Object(Symbol.iterator); [w.toString(), w instanceof Symbol, Object.prototype.toString.call(w)].join()", string("Symbol(Symbol.iterator),true,[object Symbol]")),
+        ("const s = Symbol(); Object(s) == Object(s)", Value::Boolean(false)),
+        ("const s = Symbol(); ({ [Object(s)]: 1 })[s]", Value::Number(1.0)),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_symbol_errors() {
+    let cases = vec![
+        ("new Symbol()", "TypeError: Symbol is not a constructor"),
+        ("Symbol() + ''", "TypeError: Cannot convert a Symbol value to a string"),
+        ("[Symbol()].join()", "TypeError: Cannot convert a Symbol value to a string"),
+        ("+Symbol()", "TypeError: Cannot convert a Symbol value to a number"),
+        ("Symbol.keyFor('k')", "TypeError: \"k\" is not a symbol"),
+        ("Symbol.prototype.toString.call(1)", "TypeError: Symbol.prototype.toString requires that 'this' be a Symbol"),
+        ("Object.getOwnPropertyDescriptor(Symbol.prototype, 'description').get.call(Object(1))", "TypeError: Symbol.prototype.description requires that 'this' be a Symbol"),
+        ("Object(Symbol()) + ''", "TypeError: Cannot convert a Symbol value to a string"),
+        ("({ [Symbol.toPrimitive]() { return {}; } }) + 1", "TypeError: Cannot convert object to primitive value"),
+        ("({ [Symbol.toPrimitive]: 1 }) + 1", "TypeError: 1 is not a function"),
+        ("1 instanceof 1", "TypeError: Right-hand side of 'instanceof' is not an object"),
+        ("1 instanceof {}", "TypeError: Right-hand side of 'instanceof' is not callable"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
@@ -17,46 +17,52 @@ let mixed = 1 + 2 * 3 / 4 - 5;
 let z = x + y;
 let greeting = "Hello, " + name;
 
-// TODO: Function declaration and calls
-// function add(a: number, b: number): number {
-//     return a + b;
-// }
-// let result = add(x, y);
-
-// TODO: Control flow
-// if (x > 10) {
-//     let message = "x is large";
-// } else {
-//     let message = "x is small";
-// }
-
-// TODO: Loops
-// while (x > 0) {
-//     x = x - 1;
-// }
-
-// TODO: Arrays and objects
-// let numbers = [1, 2, 3, 4, 5];
-// let person = {
-//     name: "John",
-//     age: 30
-// };
-
-// TODO: Type annotations
-// let typed: string = "typed string";
-// let numbers: number[] = [1, 2, 3];
-// let tuple: [string, number] = ["hello", 42];
-
-// TODO: Interfaces and classes
-// interface Person {
-//     name: string;
-//     age: number;
-// }
-
-// class Student implements Person {
-//     constructor(public name: string, public age: number) {}
-//     
-//     getDescription(): string {
-//         return `${this.name} is ${this.age} years old`;
-//     }
-// }
+// Function declaration and calls
+function add(a: number, b: number): number {
+    return a + b;
+}
+let result = add(x, y);
+
+// Control flow
+if (x > 10) {
+    let message = "x is large";
+} else {
+    let message = "x is small";
+}
+
+// Loops
+while (x > 0) {
+    x = x - 1;
+}
+
+// Arrays
+let numbers = [1, 2, 3, 4, 5];
+let doubled = numbers.map((n) => n * 2);
+
+// Objects
+let person = {
+    name: "John",
+    age: 30
+};
+person.age = person.age + 1;
+
+// Type annotations
+let typed: string = "typed string";
+let scores: number[] = [1, 2, 3];
+let tuple: [string, number] = ["hello", 42];
+
+// Interfaces and classes
+interface Person {
+    name: string;
+    age: number;
+}
+
+class Student implements Person {
+    constructor(public name: string, public age: number) {}
+
+    getDescription(): string {
+        return `${this.name} is ${this.age} years old`;
+    }
+}
+let student = new Student("Ann", 20);
+let description = student.getDescription();
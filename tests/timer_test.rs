@@ -0,0 +1,166 @@
+mod common;
+
+use common::{run, run_in, string};
+use ts_interpreter_rs::interpreter::{Interpreter, Value};
+
+#[test]
+fn test_timers_wait_for_time_to_pass() {
+    let mut interpreter = Interpreter::new();
+    let source = "
+        var log = [];
+        setTimeout(() => log.push('timeout'), 0);
+        queueMicrotask(() => log.push('microtask'));
+        Promise.resolve().then(() => log.push('promise'));
+        log.push('script');
+        log.join()
+    ";
+    assert_eq!(run_in(&mut interpreter, source), Ok(string("script")));
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("script,microtask,promise")));
+    interpreter.advance_time(1).unwrap();
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("script,microtask,promise,timeout")));
+}
+
+#[test]
+fn test_advance_time() {
+    let mut interpreter = Interpreter::new();
+    let source = "
+        var log = [];
+        setTimeout(() => log.push(20), 20);
+        setTimeout((a, b) => log.push(a + b), 10, 'a', 'b');
+        setTimeout(() => log.push('same'), 10);
+        setTimeout(() => { log.push(15); setTimeout(() => log.push(16), 1); Promise.resolve().then(() => log.push('then')); }, 15);
+        const cancelled = setTimeout(() => log.push('cancelled'), 5);
+        clearTimeout(cancelled);
+    ";
+    run_in(&mut interpreter, source).unwrap();
+    interpreter.advance_time(9).unwrap();
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("")));
+    interpreter.advance_time(7).unwrap();
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("ab,same,15,then,16")));
+    interpreter.advance_time(100).unwrap();
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("ab,same,15,then,16,20")));
+}
+
+#[test]
+fn test_intervals() {
+    let mut interpreter = Interpreter::new();
+    run_in(&mut interpreter, "var ticks = 0; var id = setInterval(() => ticks++, 30);").unwrap();
+    interpreter.advance_time(100).unwrap();
+    assert_eq!(run_in(&mut interpreter, "ticks"), Ok(Value::Number(3.0)));
+    run_in(&mut interpreter, "clearInterval(id)").unwrap();
+    interpreter.advance_time(100).unwrap();
+    assert_eq!(run_in(&mut interpreter, "ticks"), Ok(Value::Number(3.0)));
+
+    // An interval can clear itself, and `run_event_loop` returns once no
+    // timers are left
+    let source = "var n = 0; var id = setInterval(() => { if (++n === 5) clearTimeout(id); }, 1000);";
+    run_in(&mut interpreter, source).unwrap();
+    interpreter.run_event_loop().unwrap();
+    assert_eq!(run_in(&mut interpreter, "n"), Ok(Value::Number(5.0)));
+}
+
+#[test]
+fn test_rescheduled_intervals_run_after_earlier_timers() {
+    // The interval's second run is due at 10 like the timeout, but was
+    // scheduled after it
+    let mut interpreter = Interpreter::new();
+    let source = "
+        var log = [];
+        const id = setInterval(() => log.push('i' + log.length), 5);
+        setTimeout(() => { log.push('t10'); clearInterval(id); }, 10);
+    ";
+    run_in(&mut interpreter, source).unwrap();
+    interpreter.advance_time(20).unwrap();
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("i0,t10")));
+
+    let source = "
+        var log = [];
+        let runs = 0;
+        const interval = setInterval(() => { log.push('i' + runs); if (++runs === 3) clearInterval(interval); }, 5);
+        setTimeout(() => log.push('t10'), 10);
+        setTimeout(() => log.push('t15'), 15);
+    ";
+    run_in(&mut interpreter, source).unwrap();
+    interpreter.advance_time(20).unwrap();
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("i0,t10,i1,t15,i2")));
+}
+
+#[test]
+fn test_awaiting_a_timer() {
+    let mut interpreter = Interpreter::new();
+    let source = "const sleep = ms => new Promise(resolve => setTimeout(resolve, ms)); await sleep(50); 'slept'";
+    let pending = run_in(&mut interpreter, source);
+    assert!(matches!(pending, Ok(Value::Object(_))), "{:?}", pending);
+    assert!(matches!(interpreter.advance_time(49), Ok(Value::Object(_))));
+    assert_eq!(interpreter.advance_time(1), Ok(string("slept")));
+
+    let mut interpreter = Interpreter::new();
+    let source = "let total = 0; for (let i = 1; i <= 3; i++) total += await new Promise(r => setTimeout(r, 1000, i)); total";
+    run_in(&mut interpreter, source).unwrap();
+    assert_eq!(interpreter.run_event_loop(), Ok(Value::Number(6.0)));
+}
+
+#[test]
+fn test_timer_errors() {
+    let mut interpreter = Interpreter::new();
+    run_in(&mut interpreter, "var log = []; setTimeout(() => { throw new Error('late'); }, 10); setTimeout(() => log.push('next'), 20);").unwrap();
+    assert_eq!(interpreter.advance_time(30), Err("Error: late".to_string()));
+    // Time stops at the timer that threw
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("")));
+    interpreter.advance_time(10).unwrap();
+    assert_eq!(run_in(&mut interpreter, "log.join()"), Ok(string("next")));
+
+    let mut interpreter = Interpreter::new();
+    run_in(&mut interpreter, "setTimeout(() => Promise.reject(3), 10)").unwrap();
+    assert_eq!(interpreter.advance_time(10), Err("Uncaught 3".to_string()));
+
+    let cases = vec![
+        ("setTimeout()", "TypeError: The \"callback\" argument must be of type function. Received undefined"),
+        ("setInterval('code', 10)", "TypeError: The \"callback\" argument must be of type function. Received type string ('code')"),
+        ("queueMicrotask(1)", "TypeError: The \"callback\" argument must be of type function. Received type number (1)"),
+        ("queueMicrotask(() => { throw new RangeError('in microtask'); }); 1", "RangeError: in microtask"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_timer_ids() {
+    let cases = vec![
+        ("const a = setTimeout(() => {}); const b = setInterval(() => {}, 5); [a, b, b > a].join()", string("1,2,true")),
+        ("[clearTimeout(), clearTimeout('x'), clearInterval(99)].join()", string(",,")),
+        ("[setTimeout.length, setInterval.length, queueMicrotask.length].join()", string("2,2,1")),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Ok(expected), "{}", input);
+    }
+}
+
+/// Scripts and async calls waiting on timers finish after the interpreter
+/// is moved between `run` and `advance_time`.
+#[test]
+fn test_awaiting_across_interpreter_moves() {
+    let mut interpreter = Interpreter::new();
+    let source = "
+        const sleep = ms => new Promise(resolve => setTimeout(resolve, ms));
+        var log = [];
+        async function tick(n) { await sleep(n); log.push(n); return n; }
+        const total = (await tick(10)) + (await tick(20));
+        log.push(total);
+        log.join()
+    ";
+    assert!(matches!(run_in(&mut interpreter, source), Ok(Value::Object(_))));
+    let mut moved = Some(interpreter);
+    let mut interpreter = moved.take().unwrap();
+    assert!(matches!(interpreter.advance_time(15), Ok(Value::Object(_))));
+    let mut moved = [interpreter];
+    assert_eq!(moved[0].advance_time(15), Ok(string("10,20,30")));
+
+    let mut interpreter = Interpreter::new();
+    run_in(&mut interpreter, "await new Promise(resolve => setTimeout(resolve, 10)); 'done'").unwrap();
+    let mut boxed = Box::new(interpreter);
+    assert_eq!(boxed.advance_time(10), Ok(string("done")));
+}
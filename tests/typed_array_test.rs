@@ -0,0 +1,112 @@
+mod common;
+
+use common::run;
+use ts_interpreter_rs::interpreter::Interpreter;
+use ts_interpreter_rs::parser::Parser;
+
+fn run_string(source: &str) -> Result<String, String> {
+    run(source).map(|value| value.to_js_string())
+}
+
+#[test]
+fn test_typed_arrays() {
+    let cases = vec![
+        ("new Uint8Array([1, 2, 300]).join()", "1,2,44"),
+        ("new Int8Array([200, -129]).join()", "-56,127"),
+        ("new Uint8ClampedArray([300, -5, 1.5, 2.5]).join()", "255,0,2,2"),
+        ("new Float32Array([0.1])[0]", "0.10000000149011612"),
+        ("new BigUint64Array([-1n])[0]", "18446744073709551615"),
+        ("const a = new Float64Array(2); a[5] = 1; a[5] + ':' + Object.keys(a)", "undefined:0,1"),
+        ("new Int16Array(new ArrayBuffer(8), 2, 2).byteLength", "4"),
+        ("const b = new ArrayBuffer(4); new Uint16Array(b)[1] = 0xFFFF; new Uint8Array(b).join()", "0,0,255,255"),
+        ("const s = new Uint8Array([1, 2, 3, 4]); s.subarray(1, 3)[0] = 9; s.join()", "1,9,3,4"),
+        ("const s = new Uint8Array(4); s.set([7, 8], 2); s.join()", "0,0,7,8"),
+        ("new Float64Array([NaN, 1, 0, -0, -5]).sort().join()", "-5,0,0,1,NaN"),
+        ("new Int32Array([1, 2, 3]).map(x => x * 2).filter(x => x > 2).join()", "4,6"),
+        ("[...new Uint8Array([7, 8]).entries()].join(';')", "0,7;1,8"),
+        ("Uint8Array.from('123', Number).reduce((sum, x) => sum + x)", "6"),
+        ("Int16Array.of(3, 1, 2).toSorted().toReversed().join()", "3,2,1"),
+        ("new Uint8Array([1, 2]).with(-1, 5).join()", "1,5"),
+        ("Object.prototype.toString.call(new BigInt64Array(1))", "[object BigInt64Array]"),
+        ("Float64Array.BYTES_PER_ELEMENT + Int16Array.prototype.BYTES_PER_ELEMENT", "10"),
+        ("Object.getPrototypeOf(Int8Array) === Object.getPrototypeOf(Uint32Array)", "true"),
+        ("class Bytes extends Uint8Array { sum() { return this.reduce((a, b) => a + b); } } new Bytes([1, 2]).sum()", "3"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_string(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_array_buffers_and_data_views() {
+    let cases = vec![
+        ("new ArrayBuffer(10).slice(2, -2).byteLength", "6"),
+        ("new SharedArrayBuffer(3).byteLength", "3"),
+        ("ArrayBuffer.isView(new DataView(new ArrayBuffer(1))) && !ArrayBuffer.isView([])", "true"),
+        ("const v = new DataView(new ArrayBuffer(4)); v.setUint16(0, 0x1234); new Uint8Array(v.buffer).join()", "18,52,0,0"),
+        ("const v = new DataView(new ArrayBuffer(4)); v.setUint16(0, 0x1234, true); new Uint8Array(v.buffer).join()", "52,18,0,0"),
+        ("const v = new DataView(new ArrayBuffer(4)); v.setInt32(0, -2); v.getInt32(0) + ',' + v.getUint8(3)", "-2,254"),
+        ("const v = new DataView(new ArrayBuffer(8)); v.setFloat64(0, Math.PI, true); v.getFloat64(0, true)", "3.141592653589793"),
+        ("const v = new DataView(new ArrayBuffer(8)); v.setBigUint64(0, 2n ** 64n - 1n); v.getBigInt64(0)", "-1"),
+        ("const v = new DataView(new ArrayBuffer(8), 2, 4); v.byteOffset + v.byteLength", "6"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_string(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_text_encoding() {
+    let cases = vec![
+        ("new TextEncoder().encode('h€').join()", "104,226,130,172"),
+        ("new TextDecoder().decode(new TextEncoder().encode('héllo €'))", "héllo €"),
+        ("new TextDecoder().decode(new Uint8Array([0xEF, 0xBB, 0xBF, 0x61]))", "a"),
+        ("new TextDecoder().decode(new Uint8Array([0x61, 0xFF])).length", "2"),
+        ("new TextDecoder('UTF-16LE').decode(new Uint8Array([104, 0, 105, 0]).buffer)", "hi"),
+        ("new TextDecoder('unicodefffe').encoding", "utf-16be"),
+        ("const d = new TextDecoder(); const b = new TextEncoder().encode('€'); d.decode(b.subarray(0, 2), { stream: true }) + '|' + d.decode(b.subarray(2))", "|€"),
+        ("const r = new TextEncoder().encodeInto('a€b', new Uint8Array(4)); r.read + ',' + r.written", "2,4"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run_string(input), Ok(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_binary_errors() {
+    let cases = vec![
+        ("Uint8Array(2)", "TypeError: Constructor Uint8Array requires 'new'"),
+        ("new Uint8Array(-1)", "RangeError: Invalid typed array length: -1"),
+        ("new Int16Array(new ArrayBuffer(3))", "RangeError: byte length of Int16Array should be a multiple of 2"),
+        ("new Int16Array(new ArrayBuffer(4), 1)", "RangeError: start offset of Int16Array should be a multiple of 2"),
+        ("new Int32Array(new ArrayBuffer(4), 8)", "RangeError: Start offset 8 is outside the bounds of the buffer"),
+        ("new BigInt64Array([1])", "TypeError: Cannot convert 1 to a BigInt"),
+        ("new Uint8Array(1).set(new BigInt64Array(1))", "TypeError: Cannot mix BigInt and other types, use explicit conversions"),
+        ("new Uint8Array(1).set([1, 2])", "RangeError: offset is out of bounds"),
+        ("new ArrayBuffer(1e12)", "RangeError: Array buffer allocation failed"),
+        ("new DataView({})", "TypeError: First argument to DataView constructor must be an ArrayBuffer"),
+        ("new DataView(new ArrayBuffer(4)).getInt32(1)", "RangeError: Offset is outside the bounds of the DataView"),
+        ("new TextDecoder('latin2')", "RangeError: The \"latin2\" encoding is not supported"),
+        ("new TextDecoder('utf-8', { fatal: true }).decode(new Uint8Array([255]))", "TypeError: The encoded data was not valid for encoding utf-8"),
+        ("TextEncoder()", "TypeError: Class constructor TextEncoder cannot be invoked without 'new'"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(run(input), Err(expected.to_string()), "{}", input);
+    }
+}
+
+#[test]
+fn test_host_array_buffer() {
+    let mut interpreter = Interpreter::new();
+    let buffer = interpreter.create_array_buffer(vec![1, 2, 3, 4]);
+    interpreter.set_variable("input", buffer.clone()).unwrap();
+
+    let source = "const bytes = new Uint8Array(input); bytes[0] = 10; bytes.reduce((a, b) => a + b)";
+    let sum = interpreter.run(Parser::new(source).parse_program().unwrap()).unwrap();
+    assert_eq!(sum.to_js_string(), "19");
+    assert_eq!(interpreter.array_buffer_bytes(&buffer), Some(&[10, 2, 3, 4][..]));
+}